@@ -36,3 +36,6 @@ pub const MIX_HASH_SEED: &str = "randomMixHashSeed";
 
 /// Seed value for the generator of state root hashes.
 pub const STATE_ROOT_HASH_SEED: &str = "seed";
+
+/// Seed value for the generator of parent beacon block roots.
+pub const PARENT_BEACON_BLOCK_ROOT_SEED: &str = "randomParentBeaconBlockRootSeed";
@@ -11,7 +11,7 @@ mod reward;
 
 use std::sync::OnceLock;
 
-use alloy_rlp::{BufMut, Decodable, RlpDecodable, RlpEncodable};
+use alloy_rlp::{Buf, BufMut, Decodable, RlpDecodable, RlpEncodable};
 use revm_primitives::{calc_excess_blob_gas, keccak256};
 
 use self::difficulty::calculate_ethash_canonical_difficulty;
@@ -89,6 +89,74 @@ impl PartialEq for Block {
     }
 }
 
+impl Decodable for Block {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let rlp_header = alloy_rlp::Header::decode(buf)?;
+        if !rlp_header.list {
+            return Err(alloy_rlp::Error::UnexpectedString);
+        }
+
+        let started_len = buf.len();
+        if started_len < rlp_header.payload_length {
+            return Err(alloy_rlp::Error::InputTooShort);
+        }
+
+        let header = Header::decode(buf)?;
+
+        let transactions_header = alloy_rlp::Header::decode(buf)?;
+        if !transactions_header.list {
+            return Err(alloy_rlp::Error::UnexpectedString);
+        }
+
+        if buf.len() < transactions_header.payload_length {
+            return Err(alloy_rlp::Error::InputTooShort);
+        }
+
+        let mut transactions_payload = &buf[..transactions_header.payload_length];
+        buf.advance(transactions_header.payload_length);
+
+        let mut transactions = Vec::new();
+        while !transactions_payload.is_empty() {
+            // Typed transactions are wrapped in an RLP string inside blocks
+            let is_string = transactions_payload[0] < 0xc0;
+            let transaction = if is_string {
+                let mut payload =
+                    alloy_rlp::Header::decode_bytes(&mut transactions_payload, false)?;
+                SignedTransaction::decode(&mut payload)?
+            } else {
+                SignedTransaction::decode(&mut transactions_payload)?
+            };
+
+            transactions.push(transaction);
+        }
+
+        let ommers = Vec::<Header>::decode(buf)?;
+
+        let consumed = started_len - buf.len();
+        let withdrawals = if consumed < rlp_header.payload_length {
+            Some(Vec::<Withdrawal>::decode(buf)?)
+        } else {
+            None
+        };
+
+        let consumed = started_len - buf.len();
+        if consumed != rlp_header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: rlp_header.payload_length,
+                got: consumed,
+            });
+        }
+
+        Ok(Self {
+            header,
+            transactions,
+            ommers,
+            withdrawals,
+            hash: OnceLock::new(),
+        })
+    }
+}
+
 /// ethereum block header
 #[derive(Clone, Debug, Default, PartialEq, Eq, RlpDecodable, RlpEncodable)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -482,6 +550,125 @@ mod tests {
         }
     }
 
+    #[test]
+    fn block_rlp_decode() {
+        use alloy_rlp::Encodable;
+
+        use crate::{
+            signature::Signature,
+            transaction::{Eip1559SignedTransaction, LegacySignedTransaction, TransactionKind},
+        };
+
+        let transactions = vec![
+            SignedTransaction::PreEip155Legacy(LegacySignedTransaction {
+                nonce: 0,
+                gas_price: U256::from(1),
+                gas_limit: 2,
+                kind: TransactionKind::Call(Address::default()),
+                value: U256::from(3),
+                input: Bytes::from(vec![1, 2]),
+                signature: Signature {
+                    r: U256::default(),
+                    s: U256::default(),
+                    v: 1,
+                },
+                hash: OnceLock::new(),
+            }),
+            SignedTransaction::Eip1559(Eip1559SignedTransaction {
+                chain_id: 1,
+                nonce: 1,
+                max_priority_fee_per_gas: U256::from(1),
+                max_fee_per_gas: U256::from(2),
+                gas_limit: 3,
+                kind: TransactionKind::Create,
+                value: U256::from(4),
+                input: Bytes::from(vec![1, 2]),
+                access_list: vec![].into(),
+                odd_y_parity: true,
+                r: U256::default(),
+                s: U256::default(),
+                hash: OnceLock::new(),
+            }),
+        ];
+
+        let withdrawals = vec![Withdrawal {
+            index: 0,
+            validator_index: 1,
+            address: Address::random(),
+            amount: U256::from(2),
+        }];
+
+        let block = Block::new(
+            PartialHeader::default(),
+            transactions,
+            Vec::new(),
+            Some(withdrawals),
+        );
+
+        // Canonical block encoding, which wraps typed transactions in an RLP string
+        let mut transactions = Vec::new();
+        for transaction in &block.transactions {
+            let encoded = alloy_rlp::encode(transaction);
+            if matches!(transaction, SignedTransaction::PreEip155Legacy(_)) {
+                transactions.extend(encoded);
+            } else {
+                encoded.as_slice().encode(&mut transactions);
+            }
+        }
+
+        let mut payload = alloy_rlp::encode(&block.header);
+        alloy_rlp::Header {
+            list: true,
+            payload_length: transactions.len(),
+        }
+        .encode(&mut payload);
+        payload.extend(transactions);
+        block.ommers.encode(&mut payload);
+        block.withdrawals.as_ref().unwrap().encode(&mut payload);
+
+        let mut encoded = Vec::new();
+        alloy_rlp::Header {
+            list: true,
+            payload_length: payload.len(),
+        }
+        .encode(&mut encoded);
+        encoded.extend(payload);
+
+        let decoded = Block::decode(&mut encoded.as_slice()).unwrap();
+        assert_eq!(block, decoded);
+        assert_eq!(block.hash(), decoded.hash());
+    }
+
+    #[test]
+    fn block_rlp_decode_truncated_transactions() {
+        use alloy_rlp::Encodable;
+
+        let mut payload = alloy_rlp::encode(&Header::new(
+            PartialHeader::default(),
+            B256::ZERO,
+            B256::ZERO,
+        ));
+        // The transactions list claims more bytes than remain in the block
+        alloy_rlp::Header {
+            list: true,
+            payload_length: 100,
+        }
+        .encode(&mut payload);
+
+        let mut encoded = Vec::new();
+        alloy_rlp::Header {
+            list: true,
+            payload_length: payload.len(),
+        }
+        .encode(&mut encoded);
+        encoded.extend(payload);
+
+        assert_eq!(
+            Block::decode(&mut encoded.as_slice()),
+            Err(alloy_rlp::Error::InputTooShort)
+        );
+    }
+
     #[test]
     fn header_rlp_roundtrip() {
         let mut header = Header {
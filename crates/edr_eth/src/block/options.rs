@@ -1,4 +1,4 @@
-use crate::{Address, Bytes, B256, B64, U256};
+use crate::{withdrawal::Withdrawal, Address, Bytes, B256, B64, U256};
 
 /// Data of a block header
 #[derive(Debug, Default)]
@@ -27,6 +27,9 @@ pub struct BlockOptions {
     pub base_fee: Option<U256>,
     /// The block's withdrawals root
     pub withdrawals_root: Option<B256>,
+    /// The block's withdrawals. Post-Shanghai blocks without withdrawals are
+    /// built with an empty list, unless a withdrawals root is provided.
+    pub withdrawals: Option<Vec<Withdrawal>>,
     /// The hash tree root of the parent beacon block for the given execution
     /// block (EIP-4788).
     pub parent_beacon_block_root: Option<B256>,
@@ -0,0 +1,291 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    spec::{ForkCondition, HardforkActivations},
+    Address, Bytes, SpecId, B256, U256,
+};
+
+/// A geth-style genesis file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Genesis {
+    /// The chain configuration, including the hardfork schedule
+    pub config: GenesisConfig,
+    /// The nonce of the genesis block
+    #[serde(default, with = "crate::serde::u64")]
+    pub nonce: u64,
+    /// The timestamp of the genesis block
+    #[serde(default, with = "crate::serde::u64")]
+    pub timestamp: u64,
+    /// The extra data of the genesis block
+    #[serde(default)]
+    pub extra_data: Bytes,
+    /// The gas limit of the genesis block. Zero if not provided.
+    #[serde(default, with = "crate::serde::u64")]
+    pub gas_limit: u64,
+    /// The difficulty of the genesis block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<U256>,
+    /// The mix hash of the genesis block
+    #[serde(default)]
+    pub mix_hash: B256,
+    /// The beneficiary of the genesis block
+    #[serde(default)]
+    pub coinbase: Address,
+    /// The accounts of the genesis state
+    #[serde(default)]
+    pub alloc: BTreeMap<Address, GenesisAccount>,
+    /// The base fee of the genesis block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_fee_per_gas: Option<U256>,
+    /// The excess blob gas of the genesis block
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde::optional_u64"
+    )]
+    pub excess_blob_gas: Option<u64>,
+    /// The blob gas used by the genesis block
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde::optional_u64"
+    )]
+    pub blob_gas_used: Option<u64>,
+}
+
+/// An account in the genesis state.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct GenesisAccount {
+    /// The code of the account
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// The storage of the account
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<B256, B256>,
+    /// The balance of the account
+    pub balance: U256,
+    /// The nonce of the account
+    #[serde(default, with = "crate::serde::u64")]
+    pub nonce: u64,
+}
+
+/// The chain configuration of a genesis file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenesisConfig {
+    /// The chain ID
+    pub chain_id: u64,
+    /// The Homestead activation block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homestead_block: Option<u64>,
+    /// The DAO fork activation block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dao_fork_block: Option<u64>,
+    /// The Tangerine Whistle activation block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eip150_block: Option<u64>,
+    /// The EIP-155 activation block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eip155_block: Option<u64>,
+    /// The Spurious Dragon activation block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eip158_block: Option<u64>,
+    /// The Byzantium activation block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byzantium_block: Option<u64>,
+    /// The Constantinople activation block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constantinople_block: Option<u64>,
+    /// The Petersburg activation block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub petersburg_block: Option<u64>,
+    /// The Istanbul activation block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub istanbul_block: Option<u64>,
+    /// The Muir Glacier activation block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub muir_glacier_block: Option<u64>,
+    /// The Berlin activation block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub berlin_block: Option<u64>,
+    /// The London activation block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub london_block: Option<u64>,
+    /// The Arrow Glacier activation block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arrow_glacier_block: Option<u64>,
+    /// The Gray Glacier activation block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gray_glacier_block: Option<u64>,
+    /// The block at which the network splits after the merge
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge_netsplit_block: Option<u64>,
+    /// The Shanghai activation timestamp
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shanghai_time: Option<u64>,
+    /// The Cancun activation timestamp
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancun_time: Option<u64>,
+    /// The total difficulty at which the merge happens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub terminal_total_difficulty: Option<U256>,
+    /// Whether the merge has already happened
+    #[serde(default)]
+    pub terminal_total_difficulty_passed: bool,
+}
+
+impl GenesisConfig {
+    /// Constructs a configuration for the provided chain, in which all
+    /// hardforks up to and including the provided one are activated at
+    /// genesis.
+    pub fn with_spec_id(chain_id: u64, spec_id: SpecId) -> Self {
+        let at_genesis = |hardfork: SpecId| (spec_id >= hardfork).then_some(0);
+
+        Self {
+            chain_id,
+            homestead_block: at_genesis(SpecId::HOMESTEAD),
+            dao_fork_block: None,
+            eip150_block: at_genesis(SpecId::TANGERINE),
+            eip155_block: at_genesis(SpecId::SPURIOUS_DRAGON),
+            eip158_block: at_genesis(SpecId::SPURIOUS_DRAGON),
+            byzantium_block: at_genesis(SpecId::BYZANTIUM),
+            constantinople_block: at_genesis(SpecId::CONSTANTINOPLE),
+            petersburg_block: at_genesis(SpecId::PETERSBURG),
+            istanbul_block: at_genesis(SpecId::ISTANBUL),
+            muir_glacier_block: at_genesis(SpecId::MUIR_GLACIER),
+            berlin_block: at_genesis(SpecId::BERLIN),
+            london_block: at_genesis(SpecId::LONDON),
+            arrow_glacier_block: at_genesis(SpecId::ARROW_GLACIER),
+            gray_glacier_block: at_genesis(SpecId::GRAY_GLACIER),
+            merge_netsplit_block: at_genesis(SpecId::MERGE),
+            shanghai_time: at_genesis(SpecId::SHANGHAI),
+            cancun_time: at_genesis(SpecId::CANCUN),
+            terminal_total_difficulty: (spec_id >= SpecId::MERGE).then_some(U256::ZERO),
+            terminal_total_difficulty_passed: spec_id >= SpecId::MERGE,
+        }
+    }
+
+    /// Returns the hardfork schedule of the configuration.
+    ///
+    /// Without a `mergeNetsplitBlock`, the merge is only included if the
+    /// terminal total difficulty is zero, as it can't be determined in advance
+    /// otherwise.
+    pub fn hardfork_activations(&self) -> HardforkActivations {
+        let merge_block = self
+            .merge_netsplit_block
+            .or_else(|| (self.terminal_total_difficulty == Some(U256::ZERO)).then_some(0));
+
+        let hardforks = [
+            (Some(0), SpecId::FRONTIER),
+            (self.homestead_block, SpecId::HOMESTEAD),
+            (self.dao_fork_block, SpecId::DAO_FORK),
+            (self.eip150_block, SpecId::TANGERINE),
+            (self.eip158_block, SpecId::SPURIOUS_DRAGON),
+            (self.byzantium_block, SpecId::BYZANTIUM),
+            (self.constantinople_block, SpecId::CONSTANTINOPLE),
+            (self.petersburg_block, SpecId::PETERSBURG),
+            (self.istanbul_block, SpecId::ISTANBUL),
+            (self.muir_glacier_block, SpecId::MUIR_GLACIER),
+            (self.berlin_block, SpecId::BERLIN),
+            (self.london_block, SpecId::LONDON),
+            (self.arrow_glacier_block, SpecId::ARROW_GLACIER),
+            (self.gray_glacier_block, SpecId::GRAY_GLACIER),
+            (merge_block, SpecId::MERGE),
+        ]
+        .into_iter()
+        .filter_map(|(block_number, spec_id)| {
+            block_number.map(|block_number| (ForkCondition::Block(block_number), spec_id))
+        })
+        .chain(
+            [
+                (self.shanghai_time, SpecId::SHANGHAI),
+                (self.cancun_time, SpecId::CANCUN),
+            ]
+            .into_iter()
+            .filter_map(|(timestamp, spec_id)| {
+                timestamp.map(|timestamp| (ForkCondition::Timestamp(timestamp), spec_id))
+            }),
+        )
+        .collect();
+
+        HardforkActivations::new(hardforks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_geth_genesis() -> anyhow::Result<()> {
+        let json = r#"{
+            "config": {
+                "chainId": 1337,
+                "homesteadBlock": 0,
+                "eip150Block": 0,
+                "eip155Block": 0,
+                "eip158Block": 0,
+                "byzantiumBlock": 0,
+                "constantinopleBlock": 0,
+                "petersburgBlock": 0,
+                "istanbulBlock": 0,
+                "berlinBlock": 0,
+                "londonBlock": 0,
+                "mergeNetsplitBlock": 0,
+                "shanghaiTime": 0,
+                "cancunTime": 1000,
+                "terminalTotalDifficulty": 0,
+                "terminalTotalDifficultyPassed": true
+            },
+            "nonce": "0x0",
+            "timestamp": "0x10",
+            "extraData": "0x1234",
+            "gasLimit": "0x1c9c380",
+            "difficulty": "0x0",
+            "baseFeePerGas": "0x3b9aca00",
+            "alloc": {
+                "0x0000000000000000000000000000000000000042": {
+                    "code": "0x6001",
+                    "storage": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000001": "0x0000000000000000000000000000000000000000000000000000000000000002"
+                    },
+                    "balance": "0x1",
+                    "nonce": "0x1"
+                }
+            }
+        }"#;
+
+        let genesis: Genesis = serde_json::from_str(json)?;
+        assert_eq!(genesis.config.chain_id, 1337);
+        assert_eq!(genesis.timestamp, 0x10);
+        assert_eq!(genesis.gas_limit, 30_000_000);
+        assert_eq!(genesis.base_fee_per_gas, Some(U256::from(1_000_000_000)));
+
+        let account = &genesis.alloc[&Address::with_last_byte(0x42)];
+        assert_eq!(account.nonce, 1);
+        assert_eq!(account.storage.len(), 1);
+
+        let activations = genesis.config.hardfork_activations();
+        assert_eq!(
+            activations.hardfork_at_block(0, genesis.timestamp),
+            Some(SpecId::SHANGHAI)
+        );
+        assert_eq!(
+            activations.hardfork_at_block(10, 1000),
+            Some(SpecId::CANCUN)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_spec_id_round_trip() {
+        let config = GenesisConfig::with_spec_id(1, SpecId::SHANGHAI);
+
+        assert_eq!(
+            config.hardfork_activations().hardfork_at_block(0, 0),
+            Some(SpecId::SHANGHAI)
+        );
+    }
+}
@@ -11,6 +11,9 @@ pub mod access_list;
 pub mod account;
 /// Ethereum block types
 pub mod block;
+/// Geth-style genesis files
+#[cfg(feature = "serde")]
+pub mod genesis;
 /// Ethereum log types
 pub mod log;
 /// Ethereum receipt types
@@ -39,7 +42,7 @@ pub mod utils;
 pub mod withdrawal;
 
 pub use alloy_primitives::{
-    hex_literal, Address, Bloom, BloomInput, Bytes, B256, B512, B64, U256, U64,
+    hex_literal, Address, Bloom, BloomInput, Bytes, FixedBytes, B256, B512, B64, U256, U64,
 };
 pub use revm_primitives::{AccountInfo, HashMap, SpecId};
 
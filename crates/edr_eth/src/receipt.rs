@@ -14,10 +14,13 @@ use revm_primitives::SpecId;
 #[cfg(feature = "serde")]
 use serde::{ser::SerializeStruct, Serialize, Serializer};
 
-pub use self::{block::BlockReceipt, transaction::TransactionReceipt};
+pub use self::{
+    block::BlockReceipt,
+    transaction::{L1Fee, TransactionReceipt},
+};
 #[cfg(feature = "serde")]
 use crate::U64;
-use crate::{Bloom, B256};
+use crate::{transaction::DEPOSIT_TRANSACTION_TYPE, Bloom, B256};
 
 /// Typed receipt that's generated after execution of a transaction.
 #[derive(Clone, Debug)]
@@ -73,6 +76,24 @@ impl<LogT: serde::Serialize> Serialize for TypedReceipt<LogT> {
             | TypedReceiptData::Eip4844 { status } => {
                 state.serialize_field("status", &format!("0x{status}"))?;
             }
+            TypedReceiptData::Deposit {
+                status,
+                deposit_nonce,
+                deposit_receipt_version,
+            } => {
+                state.serialize_field("status", &format!("0x{status}"))?;
+
+                if let Some(deposit_nonce) = deposit_nonce {
+                    state.serialize_field("depositNonce", &U64::from(*deposit_nonce))?;
+                }
+
+                if let Some(deposit_receipt_version) = deposit_receipt_version {
+                    state.serialize_field(
+                        "depositReceiptVersion",
+                        &U64::from(*deposit_receipt_version),
+                    )?;
+                }
+            }
         }
 
         if self.spec_id >= SpecId::BERLIN {
@@ -87,11 +108,28 @@ impl<LogT: serde::Serialize> Serialize for TypedReceipt<LogT> {
 /// Data of a typed receipt.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum TypedReceiptData {
-    PreEip658Legacy { state_root: B256 },
-    PostEip658Legacy { status: u8 },
-    Eip2930 { status: u8 },
-    Eip1559 { status: u8 },
-    Eip4844 { status: u8 },
+    PreEip658Legacy {
+        state_root: B256,
+    },
+    PostEip658Legacy {
+        status: u8,
+    },
+    Eip2930 {
+        status: u8,
+    },
+    Eip1559 {
+        status: u8,
+    },
+    Eip4844 {
+        status: u8,
+    },
+    /// OP-stack deposit receipt. The deposit nonce is included since Regolith
+    /// and the receipt version since Canyon.
+    Deposit {
+        status: u8,
+        deposit_nonce: Option<u64>,
+        deposit_receipt_version: Option<u64>,
+    },
 }
 
 impl<LogT> TypedReceipt<LogT> {
@@ -102,7 +140,8 @@ impl<LogT> TypedReceipt<LogT> {
             TypedReceiptData::PostEip658Legacy { status }
             | TypedReceiptData::Eip2930 { status }
             | TypedReceiptData::Eip1559 { status }
-            | TypedReceiptData::Eip4844 { status } => Some(*status),
+            | TypedReceiptData::Eip4844 { status }
+            | TypedReceiptData::Deposit { status, .. } => Some(*status),
         }
     }
 
@@ -122,6 +161,7 @@ impl<LogT> TypedReceipt<LogT> {
             TypedReceiptData::Eip2930 { .. } => 1u64,
             TypedReceiptData::Eip1559 { .. } => 2u64,
             TypedReceiptData::Eip4844 { .. } => 3u64,
+            TypedReceiptData::Deposit { .. } => u64::from(DEPOSIT_TRANSACTION_TYPE),
         }
     }
 }
@@ -137,6 +177,14 @@ where
             | TypedReceiptData::Eip2930 { .. }
             | TypedReceiptData::Eip1559 { .. }
             | TypedReceiptData::Eip4844 { .. } => 1,
+            TypedReceiptData::Deposit {
+                deposit_nonce,
+                deposit_receipt_version,
+                ..
+            } => {
+                1 + deposit_nonce.map_or(0, |nonce| nonce.length())
+                    + deposit_receipt_version.map_or(0, |version| version.length())
+            }
         };
 
         data_length
@@ -168,6 +216,10 @@ where
             CumulativeGasUsed,
             LogsBloom,
             Logs,
+            DepositNonce,
+            DepositReceiptVersion,
+            #[serde(other)]
+            Other,
         }
 
         struct TypedReceiptVisitor<LogT> {
@@ -200,6 +252,8 @@ where
                 let mut cumulative_gas_used: Option<U64> = None;
                 let mut logs_bloom = None;
                 let mut logs = None;
+                let mut deposit_nonce: Option<U64> = None;
+                let mut deposit_receipt_version: Option<U64> = None;
 
                 while let Some(key) = map.next_key()? {
                     match key {
@@ -239,6 +293,23 @@ where
                             }
                             logs = Some(map.next_value()?);
                         }
+                        Field::DepositNonce => {
+                            if deposit_nonce.is_some() {
+                                return Err(Error::duplicate_field("depositNonce"));
+                            }
+                            deposit_nonce = Some(map.next_value()?);
+                        }
+                        Field::DepositReceiptVersion => {
+                            if deposit_receipt_version.is_some() {
+                                return Err(Error::duplicate_field("depositReceiptVersion"));
+                            }
+                            deposit_receipt_version = Some(map.next_value()?);
+                        }
+                        // Receipts are flattened into larger types, so other fields are
+                        // ignored.
+                        Field::Other => {
+                            map.next_value::<serde::de::IgnoredAny>()?;
+                        }
                     }
                 }
 
@@ -261,6 +332,13 @@ where
                             "0x0" => TypedReceiptData::PostEip658Legacy { status },
                             "0x1" => TypedReceiptData::Eip2930 { status },
                             "0x2" => TypedReceiptData::Eip1559 { status },
+                            "0x3" => TypedReceiptData::Eip4844 { status },
+                            "0x7e" => TypedReceiptData::Deposit {
+                                status,
+                                deposit_nonce: deposit_nonce.map(|nonce| nonce.to()),
+                                deposit_receipt_version: deposit_receipt_version
+                                    .map(|version| version.to()),
+                            },
                             _ => return Err(Error::custom("unknown transaction type")),
                         }
                     } else {
@@ -342,10 +420,18 @@ where
                 Some(2) => TypedReceiptData::Eip1559 {
                     status: normalize_status(u8::decode(buf)?),
                 },
+                Some(3) => TypedReceiptData::Eip4844 {
+                    status: normalize_status(u8::decode(buf)?),
+                },
+                Some(DEPOSIT_TRANSACTION_TYPE) => TypedReceiptData::Deposit {
+                    status: normalize_status(u8::decode(buf)?),
+                    deposit_nonce: None,
+                    deposit_receipt_version: None,
+                },
                 _ => return Err(alloy_rlp::Error::Custom("Unknown receipt type")),
             };
 
-            let receipt = TypedReceipt {
+            let mut receipt = TypedReceipt {
                 cumulative_gas_used: u64::decode(buf)?,
                 logs_bloom: Bloom::decode(buf)?,
                 logs: Vec::<LogT>::decode(buf)?,
@@ -353,6 +439,22 @@ where
                 spec_id: SpecId::LATEST,
             };
 
+            // The optional deposit fields trail the logs
+            if let TypedReceiptData::Deposit {
+                deposit_nonce,
+                deposit_receipt_version,
+                ..
+            } = &mut receipt.data
+            {
+                if started_len - buf.len() < payload_length {
+                    *deposit_nonce = Some(u64::decode(buf)?);
+                }
+
+                if started_len - buf.len() < payload_length {
+                    *deposit_receipt_version = Some(u64::decode(buf)?);
+                }
+            }
+
             let consumed = started_len - buf.len();
             if consumed != payload_length {
                 return Err(alloy_rlp::Error::ListLengthMismatch {
@@ -379,6 +481,7 @@ where
                 0x01 => Some(1u8),
                 0x02 => Some(2u8),
                 0x03 => Some(3u8),
+                DEPOSIT_TRANSACTION_TYPE => Some(DEPOSIT_TRANSACTION_TYPE),
                 _ => return Err(alloy_rlp::Error::Custom("unknown receipt type")),
             }
         };
@@ -398,6 +501,7 @@ where
             TypedReceiptData::Eip2930 { .. } => Some(1u8),
             TypedReceiptData::Eip1559 { .. } => Some(2u8),
             TypedReceiptData::Eip4844 { .. } => Some(3u8),
+            TypedReceiptData::Deposit { .. } => Some(DEPOSIT_TRANSACTION_TYPE),
         };
 
         if let Some(id) = id {
@@ -417,7 +521,8 @@ where
             TypedReceiptData::PostEip658Legacy { status }
             | TypedReceiptData::Eip2930 { status }
             | TypedReceiptData::Eip1559 { status }
-            | TypedReceiptData::Eip4844 { status } => {
+            | TypedReceiptData::Eip4844 { status }
+            | TypedReceiptData::Deposit { status, .. } => {
                 if *status == 0 {
                     out.put_u8(alloy_rlp::EMPTY_STRING_CODE);
                 } else {
@@ -429,6 +534,21 @@ where
         self.cumulative_gas_used.encode(out);
         self.logs_bloom.encode(out);
         self.logs.encode(out);
+
+        if let TypedReceiptData::Deposit {
+            deposit_nonce,
+            deposit_receipt_version,
+            ..
+        } = &self.data
+        {
+            if let Some(deposit_nonce) = deposit_nonce {
+                deposit_nonce.encode(out);
+            }
+
+            if let Some(deposit_receipt_version) = deposit_receipt_version {
+                deposit_receipt_version.encode(out);
+            }
+        }
     }
 
     fn length(&self) -> usize {
@@ -438,7 +558,8 @@ where
             | TypedReceiptData::PostEip658Legacy { .. } => 0,
             TypedReceiptData::Eip2930 { .. }
             | TypedReceiptData::Eip1559 { .. }
-            | TypedReceiptData::Eip4844 { .. } => 1,
+            | TypedReceiptData::Eip4844 { .. }
+            | TypedReceiptData::Deposit { .. } => 1,
         };
 
         let payload_length = self.rlp_payload_length();
@@ -515,6 +636,17 @@ mod tests {
         post_eip658 => TypedReceiptData::PostEip658Legacy { status: 1 },
         eip2930 => TypedReceiptData::Eip2930 { status: 1 },
         eip1559 => TypedReceiptData::Eip1559 { status: 0 },
+        eip4844 => TypedReceiptData::Eip4844 { status: 1 },
+        deposit => TypedReceiptData::Deposit {
+            status: 1,
+            deposit_nonce: Some(7),
+            deposit_receipt_version: Some(1),
+        },
+        deposit_pre_regolith => TypedReceiptData::Deposit {
+            status: 1,
+            deposit_nonce: None,
+            deposit_receipt_version: None,
+        },
     }
 
     #[cfg(feature = "test-remote")]
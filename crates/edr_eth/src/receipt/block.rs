@@ -67,6 +67,7 @@ mod test {
                 contract_address: None,
                 gas_used: 1,
                 effective_gas_price: Some(U256::from(1)),
+                l1_fee: None,
             },
             block_hash: B256::default(),
             block_number: 1,
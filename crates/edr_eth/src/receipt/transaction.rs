@@ -34,6 +34,51 @@ pub struct TransactionReceipt<L> {
     /// the hardfork is at least London.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub effective_gas_price: Option<U256>,
+    /// The L1 data fee charged to the sender. Only present for non-deposit
+    /// transactions on OP-stack chains.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, flatten, skip_serializing_if = "Option::is_none")
+    )]
+    pub l1_fee: Option<L1Fee>,
+}
+
+/// The L1 data fee of a transaction on an OP-stack chain, based on the values
+/// of the `L1Block` predeploy at the time of execution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct L1Fee {
+    /// The L1 base fee
+    pub l1_gas_price: U256,
+    /// The amount of L1 gas used to post the transaction's data
+    pub l1_gas_used: U256,
+    /// The fee charged for posting the transaction's data to L1
+    pub l1_fee: U256,
+    /// The decimal fee scalar. Only present before Ecotone.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub l1_fee_scalar: Option<String>,
+    /// The base fee scalar. Only present since Ecotone.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub l1_base_fee_scalar: Option<U256>,
+    /// The L1 blob base fee. Only present since Ecotone.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub l1_blob_base_fee: Option<U256>,
+    /// The blob base fee scalar. Only present since Ecotone.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub l1_blob_base_fee_scalar: Option<U256>,
 }
 
 impl<L> TransactionReceipt<L> {
@@ -112,6 +157,7 @@ mod test {
             contract_address: Some(Address::default()),
             gas_used: 100,
             effective_gas_price: Some(U256::from(100)),
+            l1_fee: None,
         };
 
         let serialized = serde_json::to_string(&receipt).unwrap();
@@ -119,4 +165,71 @@ mod test {
 
         assert_eq!(receipt, deserialized);
     }
+
+    #[test]
+    fn test_transaction_receipt_serde_l1_fee() {
+        let receipt = TransactionReceipt {
+            inner: TypedReceipt {
+                cumulative_gas_used: 100,
+                logs_bloom: Bloom::default(),
+                logs: vec![],
+                data: TypedReceiptData::Eip1559 { status: 1 },
+                spec_id: SpecId::LATEST,
+            },
+            transaction_hash: B256::default(),
+            transaction_index: 5,
+            from: Address::default(),
+            to: Some(Address::default()),
+            contract_address: None,
+            gas_used: 100,
+            effective_gas_price: Some(U256::from(100)),
+            l1_fee: Some(L1Fee {
+                l1_gas_price: U256::from(30_000_000_000u64),
+                l1_gas_used: U256::from(1600),
+                l1_fee: U256::from(1_000_000),
+                l1_fee_scalar: None,
+                l1_base_fee_scalar: Some(U256::from(1368)),
+                l1_blob_base_fee: Some(U256::from(1)),
+                l1_blob_base_fee_scalar: Some(U256::from(810_949)),
+            }),
+        };
+
+        let serialized = serde_json::to_value(&receipt).unwrap();
+        assert_eq!(serialized["l1GasUsed"], "0x640");
+
+        let deserialized: TransactionReceipt<()> = serde_json::from_value(serialized).unwrap();
+        assert_eq!(receipt, deserialized);
+    }
+
+    #[test]
+    fn test_deposit_transaction_receipt_serde() {
+        let receipt = TransactionReceipt {
+            inner: TypedReceipt {
+                cumulative_gas_used: 100,
+                logs_bloom: Bloom::default(),
+                logs: vec![],
+                data: TypedReceiptData::Deposit {
+                    status: 1,
+                    deposit_nonce: Some(3),
+                    deposit_receipt_version: Some(1),
+                },
+                spec_id: SpecId::LATEST,
+            },
+            transaction_hash: B256::default(),
+            transaction_index: 0,
+            from: Address::default(),
+            to: Some(Address::default()),
+            contract_address: None,
+            gas_used: 100,
+            effective_gas_price: Some(U256::ZERO),
+            l1_fee: None,
+        };
+
+        let serialized = serde_json::to_value(&receipt).unwrap();
+        assert_eq!(serialized["type"], "0x7e");
+        assert_eq!(serialized["depositNonce"], "0x3");
+
+        let deserialized: TransactionReceipt<()> = serde_json::from_value(serialized).unwrap();
+        assert_eq!(receipt, deserialized);
+    }
 }
@@ -208,7 +208,10 @@ impl<'a> TryFrom<&'a RequestMethod> for CacheableRequestMethod<'a> {
             // Explicit to make sure if a new method is added, it is not forgotten here.
             // Chain id is not cacheable since a remote might change its chain id e.g. if it's a
             // forked node running on localhost.
-            RequestMethod::BlockNumber(_) | RequestMethod::ChainId(_) => {
+            // Proofs are rarely requested and can be large, so they aren't cached.
+            RequestMethod::BlockNumber(_)
+            | RequestMethod::ChainId(_)
+            | RequestMethod::GetProof(_, _, _) => {
                 Err(MethodNotCacheableError::RequestMethod(value.clone()))
             }
         }
@@ -38,7 +38,7 @@ use crate::{
             CacheKeyForUncheckedBlockNumber, ReadCacheKey, ResolvedSymbolicTag, WriteCacheKey,
         },
         chain_id::chain_id_from_url,
-        eth::FeeHistoryResult,
+        eth::{AccountProof, FeeHistoryResult},
         jsonrpc::Id,
     },
     reward_percentile::RewardPercentile,
@@ -982,6 +982,18 @@ impl RpcClient {
             .collect()
     }
 
+    /// Calls `eth_getProof`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
+    pub async fn get_proof(
+        &self,
+        address: Address,
+        storage_keys: Vec<U256>,
+        block: BlockSpec,
+    ) -> Result<AccountProof, RpcClientError> {
+        self.call(RequestMethod::GetProof(address, storage_keys, block))
+            .await
+    }
+
     /// Calls `eth_getStorageAt`.
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
     pub async fn get_storage_at(
@@ -95,6 +95,15 @@ pub struct Transaction {
     /// data blobs.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub blob_versioned_hashes: Option<Vec<B256>>,
+    /// The source hash of an OP-stack deposit transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_hash: Option<B256>,
+    /// The ETH value minted on L2 by an OP-stack deposit transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mint: Option<U256>,
+    /// Whether an OP-stack deposit transaction is a system transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_system_tx: Option<bool>,
 }
 
 impl Transaction {
@@ -232,3 +241,36 @@ impl FeeHistoryResult {
         }
     }
 }
+
+/// The Merkle proof of an account and some of its storage slots, as returned
+/// by `eth_getProof` (EIP-1186).
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountProof {
+    /// The address of the account
+    pub address: Address,
+    /// The RLP-encoded trie nodes from the state root to the account
+    pub account_proof: Vec<Bytes>,
+    /// The balance of the account
+    pub balance: U256,
+    /// The hash of the account's code
+    pub code_hash: B256,
+    /// The nonce of the account
+    #[serde(with = "crate::serde::u64")]
+    pub nonce: u64,
+    /// The root of the account's storage trie
+    pub storage_hash: B256,
+    /// The proofs of the requested storage slots
+    pub storage_proof: Vec<StorageProof>,
+}
+
+/// The Merkle proof of a storage slot, as returned by `eth_getProof`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct StorageProof {
+    /// The index of the storage slot
+    pub key: U256,
+    /// The value of the storage slot
+    pub value: U256,
+    /// The RLP-encoded trie nodes from the storage root to the storage slot
+    pub proof: Vec<Bytes>,
+}
@@ -28,3 +28,49 @@ pub struct AccountOverrideOptions {
 
 /// Type representing a full set of overrides for account information.
 pub type StateOverrideOptions = HashMap<Address, AccountOverrideOptions>;
+
+/// Options for overriding the block environment of a call.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockOverrides {
+    /// Block number override.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde::optional_u64"
+    )]
+    pub number: Option<u64>,
+    /// Block difficulty override.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<U256>,
+    /// Block timestamp override.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde::optional_u64"
+    )]
+    pub time: Option<u64>,
+    /// Block gas limit override.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde::optional_u64"
+    )]
+    pub gas_limit: Option<u64>,
+    /// Block beneficiary override.
+    #[serde(default, skip_serializing_if = "Option::is_none", alias = "coinbase")]
+    pub fee_recipient: Option<Address>,
+    /// Block prevrandao override.
+    #[serde(default, skip_serializing_if = "Option::is_none", alias = "random")]
+    pub prev_randao: Option<B256>,
+    /// Block base fee override.
+    #[serde(default, skip_serializing_if = "Option::is_none", alias = "baseFee")]
+    pub base_fee_per_gas: Option<U256>,
+    /// Block blob base fee override.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde::optional_u64"
+    )]
+    pub blob_base_fee: Option<u64>,
+}
@@ -66,6 +66,14 @@ pub enum RequestMethod {
     /// eth_getLogs
     #[serde(rename = "eth_getLogs", with = "crate::serde::sequence")]
     GetLogs(LogFilterOptions),
+    /// eth_getProof
+    #[serde(rename = "eth_getProof")]
+    GetProof(
+        Address,
+        /// storage keys
+        Vec<U256>,
+        BlockSpec,
+    ),
     /// eth_getStorageAt
     #[serde(rename = "eth_getStorageAt")]
     GetStorageAt(
@@ -110,6 +118,7 @@ impl RequestMethod {
             Self::GetBlockByHash(_, _) => "eth_getBlockByHash",
             Self::GetCode(_, _) => "eth_getCode",
             Self::GetLogs(_) => "eth_getLogs",
+            Self::GetProof(_, _, _) => "eth_getProof",
             Self::GetStorageAt(_, _, _) => "eth_getStorageAt",
             Self::GetTransactionByHash(_) => "eth_getTransactionByHash",
             Self::GetTransactionCount(_, _) => "eth_getTransactionCount",
@@ -2,23 +2,44 @@ use std::sync::OnceLock;
 
 use crate::{HashMap, SpecId};
 
+/// The condition under which a hardfork is activated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForkCondition {
+    /// Activated at the block with the provided number
+    Block(u64),
+    /// Activated at the first block with a timestamp greater than or equal to
+    /// the provided one
+    Timestamp(u64),
+}
+
+impl ForkCondition {
+    /// Whether the condition is met by the block with the provided number and
+    /// timestamp.
+    pub fn is_met(&self, block_number: u64, timestamp: u64) -> bool {
+        match self {
+            ForkCondition::Block(activation) => block_number >= *activation,
+            ForkCondition::Timestamp(activation) => timestamp >= *activation,
+        }
+    }
+}
+
 /// A struct that stores the hardforks for a chain.
 #[derive(Clone, Debug)]
 pub struct HardforkActivations {
-    /// (Start block number -> SpecId) mapping
-    hardforks: Vec<(u64, SpecId)>,
+    /// (Activation condition -> SpecId) mapping, in order of activation
+    hardforks: Vec<(ForkCondition, SpecId)>,
 }
 
 impl HardforkActivations {
     /// Constructs a new instance with the provided hardforks.
-    pub fn new(hardforks: Vec<(u64, SpecId)>) -> Self {
+    pub fn new(hardforks: Vec<(ForkCondition, SpecId)>) -> Self {
         Self { hardforks }
     }
 
     /// Creates a new instance for a new chain with the provided [`SpecId`].
     pub fn with_spec_id(spec_id: SpecId) -> Self {
         Self {
-            hardforks: vec![(0, spec_id)],
+            hardforks: vec![(ForkCondition::Block(0), spec_id)],
         }
     }
 
@@ -27,58 +48,97 @@ impl HardforkActivations {
         self.hardforks.is_empty()
     }
 
-    /// Returns the hardfork's `SpecId` corresponding to the provided block
-    /// number.
-    pub fn hardfork_at_block_number(&self, block_number: u64) -> Option<SpecId> {
+    /// Returns the hardfork's `SpecId` corresponding to the block with the
+    /// provided number and timestamp.
+    pub fn hardfork_at_block(&self, block_number: u64, timestamp: u64) -> Option<SpecId> {
         self.hardforks
             .iter()
             .rev()
-            .find(|(hardfork_number, _)| block_number >= *hardfork_number)
+            .find(|(condition, _)| condition.is_met(block_number, timestamp))
             .map(|entry| entry.1)
     }
 
-    /// Retrieves the block number at which the provided hardfork was activated.
-    pub fn hardfork_activation(&self, spec_id: SpecId) -> Option<u64> {
+    /// Returns the hardfork that is activated last, if any.
+    pub fn latest_hardfork(&self) -> Option<SpecId> {
+        self.hardforks.last().map(|(_, spec_id)| *spec_id)
+    }
+
+    /// Retrieves the condition under which the provided hardfork was
+    /// activated.
+    pub fn hardfork_activation(&self, spec_id: SpecId) -> Option<ForkCondition> {
         self.hardforks
             .iter()
             .find(|(_, id)| *id == spec_id)
-            .map(|(block, _)| *block)
+            .map(|(condition, _)| *condition)
     }
 }
 
-impl From<&[(u64, SpecId)]> for HardforkActivations {
-    fn from(hardforks: &[(u64, SpecId)]) -> Self {
+impl From<&[(ForkCondition, SpecId)]> for HardforkActivations {
+    fn from(hardforks: &[(ForkCondition, SpecId)]) -> Self {
         Self {
             hardforks: hardforks.to_vec(),
         }
     }
 }
 
+/// A hardfork of an OP-stack chain. Each is applied on top of the Ethereum
+/// hardfork it's based on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OptimismHardfork {
+    /// The initial OP-stack release
+    Bedrock,
+    /// Fixes the gas accounting of deposit transactions
+    Regolith,
+    /// Based on Shanghai; adds the deposit receipt version
+    Canyon,
+    /// Introduces span batches
+    Delta,
+    /// Based on Cancun; adds blob-based L1 data fees
+    Ecotone,
+}
+
+impl OptimismHardfork {
+    /// Returns the OP-stack hardfork that a local chain with the provided
+    /// [`SpecId`] is assumed to run.
+    pub fn from_spec_id(spec_id: SpecId) -> Self {
+        if spec_id >= SpecId::CANCUN {
+            Self::Ecotone
+        } else if spec_id >= SpecId::SHANGHAI {
+            Self::Canyon
+        } else {
+            Self::Regolith
+        }
+    }
+}
+
 struct ChainConfig {
     /// Chain name
     pub name: String,
     /// Hardfork activations for the chain
     pub hardfork_activations: HardforkActivations,
+    /// OP-stack hardfork activations, if the chain is an OP-stack chain
+    pub optimism_hardforks: Option<&'static [(ForkCondition, OptimismHardfork)]>,
 }
 
-const MAINNET_HARDFORKS: &[(u64, SpecId)] = &[
-    (0, SpecId::FRONTIER),
-    (200_000, SpecId::FRONTIER_THAWING),
-    (1_150_000, SpecId::HOMESTEAD),
-    (1_920_000, SpecId::DAO_FORK),
-    (2_463_000, SpecId::TANGERINE),
-    (2_675_000, SpecId::SPURIOUS_DRAGON),
-    (4_370_000, SpecId::BYZANTIUM),
-    (7_280_000, SpecId::CONSTANTINOPLE),
-    (7_280_000, SpecId::PETERSBURG),
-    (9_069_000, SpecId::ISTANBUL),
-    (9_200_000, SpecId::MUIR_GLACIER),
-    (12_244_000, SpecId::BERLIN),
-    (12_965_000, SpecId::LONDON),
-    (13_773_000, SpecId::ARROW_GLACIER),
-    (15_050_000, SpecId::GRAY_GLACIER),
-    (15_537_394, SpecId::MERGE),
-    (17_034_870, SpecId::SHANGHAI),
+const MAINNET_HARDFORKS: &[(ForkCondition, SpecId)] = &[
+    (ForkCondition::Block(0), SpecId::FRONTIER),
+    (ForkCondition::Block(200_000), SpecId::FRONTIER_THAWING),
+    (ForkCondition::Block(1_150_000), SpecId::HOMESTEAD),
+    (ForkCondition::Block(1_920_000), SpecId::DAO_FORK),
+    (ForkCondition::Block(2_463_000), SpecId::TANGERINE),
+    (ForkCondition::Block(2_675_000), SpecId::SPURIOUS_DRAGON),
+    (ForkCondition::Block(4_370_000), SpecId::BYZANTIUM),
+    (ForkCondition::Block(7_280_000), SpecId::CONSTANTINOPLE),
+    (ForkCondition::Block(7_280_000), SpecId::PETERSBURG),
+    (ForkCondition::Block(9_069_000), SpecId::ISTANBUL),
+    (ForkCondition::Block(9_200_000), SpecId::MUIR_GLACIER),
+    (ForkCondition::Block(12_244_000), SpecId::BERLIN),
+    (ForkCondition::Block(12_965_000), SpecId::LONDON),
+    (ForkCondition::Block(13_773_000), SpecId::ARROW_GLACIER),
+    (ForkCondition::Block(15_050_000), SpecId::GRAY_GLACIER),
+    (ForkCondition::Block(15_537_394), SpecId::MERGE),
+    (ForkCondition::Timestamp(1_681_338_455), SpecId::SHANGHAI),
+    (ForkCondition::Timestamp(1_710_338_135), SpecId::CANCUN),
 ];
 
 fn mainnet_config() -> &'static ChainConfig {
@@ -90,18 +150,19 @@ fn mainnet_config() -> &'static ChainConfig {
         ChainConfig {
             name: "mainnet".to_string(),
             hardfork_activations,
+            optimism_hardforks: None,
         }
     })
 }
 
-const ROPSTEN_HARDFORKS: &[(u64, SpecId)] = &[
-    (1_700_000, SpecId::BYZANTIUM),
-    (4_230_000, SpecId::CONSTANTINOPLE),
-    (4_939_394, SpecId::PETERSBURG),
-    (6_485_846, SpecId::ISTANBUL),
-    (7_117_117, SpecId::MUIR_GLACIER),
-    (9_812_189, SpecId::BERLIN),
-    (10_499_401, SpecId::LONDON),
+const ROPSTEN_HARDFORKS: &[(ForkCondition, SpecId)] = &[
+    (ForkCondition::Block(1_700_000), SpecId::BYZANTIUM),
+    (ForkCondition::Block(4_230_000), SpecId::CONSTANTINOPLE),
+    (ForkCondition::Block(4_939_394), SpecId::PETERSBURG),
+    (ForkCondition::Block(6_485_846), SpecId::ISTANBUL),
+    (ForkCondition::Block(7_117_117), SpecId::MUIR_GLACIER),
+    (ForkCondition::Block(9_812_189), SpecId::BERLIN),
+    (ForkCondition::Block(10_499_401), SpecId::LONDON),
 ];
 
 fn ropsten_config() -> &'static ChainConfig {
@@ -113,17 +174,18 @@ fn ropsten_config() -> &'static ChainConfig {
         ChainConfig {
             name: "ropsten".to_string(),
             hardfork_activations,
+            optimism_hardforks: None,
         }
     })
 }
 
-const RINKEBY_HARDFORKS: &[(u64, SpecId)] = &[
-    (1_035_301, SpecId::BYZANTIUM),
-    (3_660_663, SpecId::CONSTANTINOPLE),
-    (4_321_234, SpecId::PETERSBURG),
-    (5_435_345, SpecId::ISTANBUL),
-    (8_290_928, SpecId::BERLIN),
-    (8_897_988, SpecId::LONDON),
+const RINKEBY_HARDFORKS: &[(ForkCondition, SpecId)] = &[
+    (ForkCondition::Block(1_035_301), SpecId::BYZANTIUM),
+    (ForkCondition::Block(3_660_663), SpecId::CONSTANTINOPLE),
+    (ForkCondition::Block(4_321_234), SpecId::PETERSBURG),
+    (ForkCondition::Block(5_435_345), SpecId::ISTANBUL),
+    (ForkCondition::Block(8_290_928), SpecId::BERLIN),
+    (ForkCondition::Block(8_897_988), SpecId::LONDON),
 ];
 
 fn rinkeby_config() -> &'static ChainConfig {
@@ -135,18 +197,19 @@ fn rinkeby_config() -> &'static ChainConfig {
         ChainConfig {
             name: "rinkeby".to_string(),
             hardfork_activations,
+            optimism_hardforks: None,
         }
     })
 }
 
-const GOERLI_HARDFORKS: &[(u64, SpecId)] = &[
-    (0, SpecId::PETERSBURG),
-    (1_561_651, SpecId::ISTANBUL),
-    (4_460_644, SpecId::BERLIN),
-    (5_062_605, SpecId::LONDON),
-    (7_382_818, SpecId::MERGE),
-    (8_656_123, SpecId::SHANGHAI),
-    (10_388_176, SpecId::CANCUN),
+const GOERLI_HARDFORKS: &[(ForkCondition, SpecId)] = &[
+    (ForkCondition::Block(0), SpecId::PETERSBURG),
+    (ForkCondition::Block(1_561_651), SpecId::ISTANBUL),
+    (ForkCondition::Block(4_460_644), SpecId::BERLIN),
+    (ForkCondition::Block(5_062_605), SpecId::LONDON),
+    (ForkCondition::Block(7_382_818), SpecId::MERGE),
+    (ForkCondition::Timestamp(1_678_832_736), SpecId::SHANGHAI),
+    (ForkCondition::Timestamp(1_705_473_120), SpecId::CANCUN),
 ];
 
 fn goerli_config() -> &'static ChainConfig {
@@ -158,17 +221,18 @@ fn goerli_config() -> &'static ChainConfig {
         ChainConfig {
             name: "goerli".to_string(),
             hardfork_activations,
+            optimism_hardforks: None,
         }
     })
 }
 
-const KOVAN_HARDFORKS: &[(u64, SpecId)] = &[
-    (5_067_000, SpecId::BYZANTIUM),
-    (9_200_000, SpecId::CONSTANTINOPLE),
-    (10_255_201, SpecId::PETERSBURG),
-    (14_111_141, SpecId::ISTANBUL),
-    (24_770_900, SpecId::BERLIN),
-    (26_741_100, SpecId::LONDON),
+const KOVAN_HARDFORKS: &[(ForkCondition, SpecId)] = &[
+    (ForkCondition::Block(5_067_000), SpecId::BYZANTIUM),
+    (ForkCondition::Block(9_200_000), SpecId::CONSTANTINOPLE),
+    (ForkCondition::Block(10_255_201), SpecId::PETERSBURG),
+    (ForkCondition::Block(14_111_141), SpecId::ISTANBUL),
+    (ForkCondition::Block(24_770_900), SpecId::BERLIN),
+    (ForkCondition::Block(26_741_100), SpecId::LONDON),
 ];
 
 fn kovan_config() -> &'static ChainConfig {
@@ -180,14 +244,15 @@ fn kovan_config() -> &'static ChainConfig {
         ChainConfig {
             name: "kovan".to_string(),
             hardfork_activations,
+            optimism_hardforks: None,
         }
     })
 }
 
-const HOLESKY_HARDFORKS: &[(u64, SpecId)] = &[
-    (0, SpecId::MERGE),
-    (6_698, SpecId::SHANGHAI),
-    (894_733, SpecId::CANCUN),
+const HOLESKY_HARDFORKS: &[(ForkCondition, SpecId)] = &[
+    (ForkCondition::Block(0), SpecId::MERGE),
+    (ForkCondition::Timestamp(1_696_000_704), SpecId::SHANGHAI),
+    (ForkCondition::Timestamp(1_707_305_664), SpecId::CANCUN),
 ];
 
 fn holesky_config() -> &'static ChainConfig {
@@ -199,15 +264,16 @@ fn holesky_config() -> &'static ChainConfig {
         ChainConfig {
             name: "holesky".to_string(),
             hardfork_activations,
+            optimism_hardforks: None,
         }
     })
 }
 
-const SEPOLIA_HARDFORKS: &[(u64, SpecId)] = &[
-    (0, SpecId::LONDON),
-    (1_450_409, SpecId::MERGE),
-    (2_990_908, SpecId::SHANGHAI),
-    (5_187_023, SpecId::CANCUN),
+const SEPOLIA_HARDFORKS: &[(ForkCondition, SpecId)] = &[
+    (ForkCondition::Block(0), SpecId::LONDON),
+    (ForkCondition::Block(1_450_409), SpecId::MERGE),
+    (ForkCondition::Timestamp(1_677_557_088), SpecId::SHANGHAI),
+    (ForkCondition::Timestamp(1_706_655_072), SpecId::CANCUN),
 ];
 
 fn sepolia_config() -> &'static ChainConfig {
@@ -219,6 +285,133 @@ fn sepolia_config() -> &'static ChainConfig {
         ChainConfig {
             name: "sepolia".to_string(),
             hardfork_activations,
+            optimism_hardforks: None,
+        }
+    })
+}
+
+const OP_MAINNET_HARDFORKS: &[(ForkCondition, SpecId)] = &[
+    (ForkCondition::Block(105_235_063), SpecId::MERGE),
+    (ForkCondition::Timestamp(1_704_992_401), SpecId::SHANGHAI),
+    (ForkCondition::Timestamp(1_710_374_401), SpecId::CANCUN),
+];
+
+const OP_MAINNET_OPTIMISM_HARDFORKS: &[(ForkCondition, OptimismHardfork)] = &[
+    (
+        ForkCondition::Block(105_235_063),
+        OptimismHardfork::Regolith,
+    ),
+    (
+        ForkCondition::Timestamp(1_704_992_401),
+        OptimismHardfork::Canyon,
+    ),
+    (
+        ForkCondition::Timestamp(1_708_560_000),
+        OptimismHardfork::Delta,
+    ),
+    (
+        ForkCondition::Timestamp(1_710_374_401),
+        OptimismHardfork::Ecotone,
+    ),
+];
+
+fn op_mainnet_config() -> &'static ChainConfig {
+    static CONFIG: OnceLock<ChainConfig> = OnceLock::new();
+
+    CONFIG.get_or_init(|| {
+        let hardfork_activations = OP_MAINNET_HARDFORKS.into();
+
+        ChainConfig {
+            name: "optimism".to_string(),
+            hardfork_activations,
+            optimism_hardforks: Some(OP_MAINNET_OPTIMISM_HARDFORKS),
+        }
+    })
+}
+
+const OP_SEPOLIA_HARDFORKS: &[(ForkCondition, SpecId)] = &[
+    (ForkCondition::Block(0), SpecId::MERGE),
+    (ForkCondition::Timestamp(1_699_981_200), SpecId::SHANGHAI),
+    (ForkCondition::Timestamp(1_708_534_800), SpecId::CANCUN),
+];
+
+const OP_SEPOLIA_OPTIMISM_HARDFORKS: &[(ForkCondition, OptimismHardfork)] = &[
+    (ForkCondition::Block(0), OptimismHardfork::Regolith),
+    (
+        ForkCondition::Timestamp(1_699_981_200),
+        OptimismHardfork::Canyon,
+    ),
+    (
+        ForkCondition::Timestamp(1_703_203_200),
+        OptimismHardfork::Delta,
+    ),
+    (
+        ForkCondition::Timestamp(1_708_534_800),
+        OptimismHardfork::Ecotone,
+    ),
+];
+
+fn op_sepolia_config() -> &'static ChainConfig {
+    static CONFIG: OnceLock<ChainConfig> = OnceLock::new();
+
+    CONFIG.get_or_init(|| {
+        let hardfork_activations = OP_SEPOLIA_HARDFORKS.into();
+
+        ChainConfig {
+            name: "optimism-sepolia".to_string(),
+            hardfork_activations,
+            optimism_hardforks: Some(OP_SEPOLIA_OPTIMISM_HARDFORKS),
+        }
+    })
+}
+
+const BASE_MAINNET_HARDFORKS: &[(ForkCondition, SpecId)] = &[
+    (ForkCondition::Block(0), SpecId::MERGE),
+    (ForkCondition::Timestamp(1_704_992_401), SpecId::SHANGHAI),
+    (ForkCondition::Timestamp(1_710_374_401), SpecId::CANCUN),
+];
+
+const BASE_MAINNET_OPTIMISM_HARDFORKS: &[(ForkCondition, OptimismHardfork)] = &[
+    (ForkCondition::Block(0), OptimismHardfork::Regolith),
+    (
+        ForkCondition::Timestamp(1_704_992_401),
+        OptimismHardfork::Canyon,
+    ),
+    (
+        ForkCondition::Timestamp(1_708_560_000),
+        OptimismHardfork::Delta,
+    ),
+    (
+        ForkCondition::Timestamp(1_710_374_401),
+        OptimismHardfork::Ecotone,
+    ),
+];
+
+fn base_mainnet_config() -> &'static ChainConfig {
+    static CONFIG: OnceLock<ChainConfig> = OnceLock::new();
+
+    CONFIG.get_or_init(|| {
+        let hardfork_activations = BASE_MAINNET_HARDFORKS.into();
+
+        ChainConfig {
+            name: "base".to_string(),
+            hardfork_activations,
+            optimism_hardforks: Some(BASE_MAINNET_OPTIMISM_HARDFORKS),
+        }
+    })
+}
+
+fn base_sepolia_config() -> &'static ChainConfig {
+    static CONFIG: OnceLock<ChainConfig> = OnceLock::new();
+
+    CONFIG.get_or_init(|| {
+        // Base Sepolia follows the same schedule as OP Sepolia
+        let hardfork_activations = OP_SEPOLIA_HARDFORKS.into();
+
+        ChainConfig {
+            name: "base-sepolia".to_string(),
+            hardfork_activations,
+            optimism_hardforks: Some(OP_SEPOLIA_OPTIMISM_HARDFORKS),
         }
     })
 }
@@ -235,6 +428,10 @@ fn chain_configs() -> &'static HashMap<u64, &'static ChainConfig> {
         hardforks.insert(42, kovan_config());
         hardforks.insert(17_000, holesky_config());
         hardforks.insert(11_155_111, sepolia_config());
+        hardforks.insert(10, op_mainnet_config());
+        hardforks.insert(8453, base_mainnet_config());
+        hardforks.insert(84_532, base_sepolia_config());
+        hardforks.insert(11_155_420, op_sepolia_config());
 
         hardforks
     })
@@ -254,3 +451,77 @@ pub fn chain_hardfork_activations(chain_id: u64) -> Option<&'static HardforkActi
         .get(&chain_id)
         .map(|config| &config.hardfork_activations)
 }
+
+/// Whether the provided chain ID belongs to a supported OP-stack chain.
+pub fn is_optimism_chain(chain_id: u64) -> bool {
+    chain_configs()
+        .get(&chain_id)
+        .is_some_and(|config| config.optimism_hardforks.is_some())
+}
+
+/// Returns the OP-stack hardfork of the block with the provided number and
+/// timestamp, if the chain ID belongs to a supported OP-stack chain.
+pub fn optimism_hardfork_at_block(
+    chain_id: u64,
+    block_number: u64,
+    timestamp: u64,
+) -> Option<OptimismHardfork> {
+    chain_configs()
+        .get(&chain_id)
+        .and_then(|config| config.optimism_hardforks)?
+        .iter()
+        .rev()
+        .find(|(condition, _)| condition.is_met(block_number, timestamp))
+        .map(|(_, hardfork)| *hardfork)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hardfork_at_block_with_timestamp_activations() {
+        let activations = chain_hardfork_activations(1).expect("mainnet is supported");
+
+        // Shanghai and Cancun are activated by timestamp, not by block number
+        assert_eq!(
+            activations.hardfork_at_block(17_034_869, 1_681_338_443),
+            Some(SpecId::MERGE)
+        );
+        assert_eq!(
+            activations.hardfork_at_block(17_034_870, 1_681_338_455),
+            Some(SpecId::SHANGHAI)
+        );
+        assert_eq!(
+            activations.hardfork_at_block(19_426_587, 1_710_338_135),
+            Some(SpecId::CANCUN)
+        );
+    }
+
+    #[test]
+    fn optimism_hardfork_at_block_with_timestamp_activations() {
+        assert!(is_optimism_chain(10));
+        assert!(!is_optimism_chain(1));
+
+        // Blocks before Bedrock can't be executed
+        assert_eq!(optimism_hardfork_at_block(10, 105_235_062, 0), None);
+        assert_eq!(
+            optimism_hardfork_at_block(10, 105_235_063, 1_686_068_903),
+            Some(OptimismHardfork::Regolith)
+        );
+        assert_eq!(
+            optimism_hardfork_at_block(10, 114_696_812, 1_704_992_401),
+            Some(OptimismHardfork::Canyon)
+        );
+        assert_eq!(
+            optimism_hardfork_at_block(8453, 11_000_000, 1_710_374_401),
+            Some(OptimismHardfork::Ecotone)
+        );
+
+        let activations = chain_hardfork_activations(10).expect("OP mainnet is supported");
+        assert_eq!(
+            activations.hardfork_at_block(114_696_812, 1_704_992_401),
+            Some(SpecId::SHANGHAI)
+        );
+    }
+}
@@ -5,10 +5,11 @@
 //! transaction related data
 
 mod kind;
+mod pooled;
 mod request;
 mod signed;
 
-pub use self::{kind::TransactionKind, request::*, signed::*};
+pub use self::{kind::TransactionKind, pooled::*, request::*, signed::*};
 use crate::{access_list::AccessListItem, Address, Bytes, U256};
 
 /// Represents _all_ transaction requests received from RPC
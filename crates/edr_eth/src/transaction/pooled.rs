@@ -0,0 +1,399 @@
+use alloy_rlp::{Buf, BufMut, Decodable, Encodable, RlpDecodable, RlpEncodable};
+use c_kzg::{Blob, Bytes48, KzgProof};
+use revm_primitives::kzg::EnvKzgSettings;
+use sha2::{Digest, Sha256};
+
+use super::{Eip4844SignedTransaction, SignedTransaction};
+use crate::{utils::enveloped, Bytes, FixedBytes, B256};
+
+/// The version byte of KZG versioned hashes.
+const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// The blobs of an EIP-4844 transaction, along with their KZG commitments and
+/// proofs.
+#[derive(Clone, Debug, PartialEq, Eq, RlpDecodable, RlpEncodable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlobSidecar {
+    // The order of these fields determines de-/encoding order.
+    /// The blobs of the transaction
+    pub blobs: Vec<Bytes>,
+    /// The KZG commitments of the blobs
+    pub commitments: Vec<FixedBytes<48>>,
+    /// The KZG proofs of the blobs
+    pub proofs: Vec<FixedBytes<48>>,
+}
+
+impl BlobSidecar {
+    /// Computes the versioned hash of a KZG commitment.
+    pub fn versioned_hash(commitment: &FixedBytes<48>) -> B256 {
+        let mut hash: [u8; 32] = Sha256::digest(commitment).into();
+        hash[0] = VERSIONED_HASH_VERSION_KZG;
+
+        B256::from(hash)
+    }
+
+    /// Verifies that the sidecar matches the provided versioned hashes and
+    /// that its KZG proofs are valid, using the bundled trusted setup.
+    pub fn verify(&self, versioned_hashes: &[B256]) -> Result<(), BlobVerificationError> {
+        if self.blobs.len() != versioned_hashes.len()
+            || self.commitments.len() != versioned_hashes.len()
+            || self.proofs.len() != versioned_hashes.len()
+        {
+            return Err(BlobVerificationError::MismatchedLengths {
+                blob_hashes: versioned_hashes.len(),
+                blobs: self.blobs.len(),
+                commitments: self.commitments.len(),
+                proofs: self.proofs.len(),
+            });
+        }
+
+        for (index, (commitment, expected)) in
+            self.commitments.iter().zip(versioned_hashes).enumerate()
+        {
+            let actual = Self::versioned_hash(commitment);
+            if actual != *expected {
+                return Err(BlobVerificationError::VersionedHashMismatch {
+                    index,
+                    expected: *expected,
+                    actual,
+                });
+            }
+        }
+
+        let blobs = self
+            .blobs
+            .iter()
+            .map(|blob| Blob::from_bytes(blob))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(kzg_error)?;
+
+        let commitments = self
+            .commitments
+            .iter()
+            .map(|commitment| Bytes48::from_bytes(commitment.as_slice()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(kzg_error)?;
+
+        let proofs = self
+            .proofs
+            .iter()
+            .map(|proof| Bytes48::from_bytes(proof.as_slice()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(kzg_error)?;
+
+        let is_valid = KzgProof::verify_blob_kzg_proof_batch(
+            &blobs,
+            &commitments,
+            &proofs,
+            EnvKzgSettings::Default.get(),
+        )
+        .map_err(kzg_error)?;
+
+        if is_valid {
+            Ok(())
+        } else {
+            Err(BlobVerificationError::InvalidProof)
+        }
+    }
+}
+
+fn kzg_error(error: c_kzg::Error) -> BlobVerificationError {
+    BlobVerificationError::Kzg(format!("{error:?}"))
+}
+
+/// An error that occurs when verifying a [`BlobSidecar`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+pub enum BlobVerificationError {
+    /// The number of blobs, commitments and proofs doesn't match the number of
+    /// blob hashes of the transaction.
+    #[cfg_attr(
+        feature = "std",
+        error("Expected {blob_hashes} blobs, commitments and proofs, but got {blobs} blobs, {commitments} commitments and {proofs} proofs")
+    )]
+    MismatchedLengths {
+        /// The number of blob hashes of the transaction
+        blob_hashes: usize,
+        /// The number of blobs
+        blobs: usize,
+        /// The number of commitments
+        commitments: usize,
+        /// The number of proofs
+        proofs: usize,
+    },
+    /// A commitment doesn't match the corresponding blob hash of the
+    /// transaction.
+    #[cfg_attr(
+        feature = "std",
+        error("Blob hash {index} is {expected}, but its commitment hashes to {actual}")
+    )]
+    VersionedHashMismatch {
+        /// The index of the blob
+        index: usize,
+        /// The blob hash of the transaction
+        expected: B256,
+        /// The versioned hash of the commitment
+        actual: B256,
+    },
+    /// The KZG proofs are invalid.
+    #[cfg_attr(feature = "std", error("Invalid KZG proof"))]
+    InvalidProof,
+    /// The blobs, commitments or proofs are malformed.
+    #[cfg_attr(feature = "std", error("Malformed blob data: {0}"))]
+    Kzg(String),
+}
+
+/// An EIP-4844 transaction in its network form, which includes the blob
+/// sidecar.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Eip4844PooledTransaction {
+    /// The signed transaction
+    pub transaction: Eip4844SignedTransaction,
+    /// The blobs, commitments and proofs of the transaction
+    pub sidecar: BlobSidecar,
+}
+
+impl Eip4844PooledTransaction {
+    /// Verifies the sidecar against the blob hashes of the transaction.
+    pub fn verify(&self) -> Result<(), BlobVerificationError> {
+        self.sidecar.verify(&self.transaction.blob_hashes)
+    }
+
+    fn payload_length(&self) -> usize {
+        self.transaction.length()
+            + self.sidecar.blobs.length()
+            + self.sidecar.commitments.length()
+            + self.sidecar.proofs.length()
+    }
+}
+
+impl Decodable for Eip4844PooledTransaction {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = alloy_rlp::Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString);
+        }
+
+        let started_len = buf.len();
+
+        let transaction = Eip4844SignedTransaction::decode(buf)?;
+        let sidecar = BlobSidecar {
+            blobs: Decodable::decode(buf)?,
+            commitments: Decodable::decode(buf)?,
+            proofs: Decodable::decode(buf)?,
+        };
+
+        let consumed = started_len - buf.len();
+        if consumed != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            });
+        }
+
+        Ok(Self {
+            transaction,
+            sidecar,
+        })
+    }
+}
+
+impl Encodable for Eip4844PooledTransaction {
+    fn encode(&self, out: &mut dyn BufMut) {
+        alloy_rlp::Header {
+            list: true,
+            payload_length: self.payload_length(),
+        }
+        .encode(out);
+
+        self.transaction.encode(out);
+        self.sidecar.blobs.encode(out);
+        self.sidecar.commitments.encode(out);
+        self.sidecar.proofs.encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+/// A transaction as it is received from the network, e.g. through
+/// `eth_sendRawTransaction`. EIP-4844 transactions can be in their network
+/// form, including their blob sidecar.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PooledTransaction {
+    /// A transaction in its canonical form
+    Signed(SignedTransaction),
+    /// An EIP-4844 transaction with its blob sidecar
+    Eip4844(Eip4844PooledTransaction),
+}
+
+impl PooledTransaction {
+    /// Splits the pooled transaction into the signed transaction and its blob
+    /// sidecar, if it has one.
+    pub fn into_parts(self) -> (SignedTransaction, Option<BlobSidecar>) {
+        match self {
+            PooledTransaction::Signed(transaction) => (transaction, None),
+            PooledTransaction::Eip4844(Eip4844PooledTransaction {
+                transaction,
+                sidecar,
+            }) => (SignedTransaction::Eip4844(transaction), Some(sidecar)),
+        }
+    }
+}
+
+impl Decodable for PooledTransaction {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        if buf.first() == Some(&0x03) {
+            // The network form wraps the canonical payload in an outer list,
+            // so the first item of the list is itself a list.
+            let mut payload = &buf[1..];
+            let header = alloy_rlp::Header::decode(&mut payload)?;
+            if header.list && payload.first().is_some_and(|byte| *byte >= 0xc0) {
+                buf.advance(1);
+
+                return Ok(PooledTransaction::Eip4844(
+                    Eip4844PooledTransaction::decode(buf)?,
+                ));
+            }
+        }
+
+        SignedTransaction::decode(buf).map(PooledTransaction::Signed)
+    }
+}
+
+impl Encodable for PooledTransaction {
+    fn encode(&self, out: &mut dyn BufMut) {
+        match self {
+            PooledTransaction::Signed(transaction) => transaction.encode(out),
+            PooledTransaction::Eip4844(transaction) => enveloped(3, transaction, out),
+        }
+    }
+
+    fn length(&self) -> usize {
+        match self {
+            PooledTransaction::Signed(transaction) => transaction.length(),
+            PooledTransaction::Eip4844(transaction) => transaction.length() + 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::OnceLock;
+
+    use c_kzg::{KzgCommitment, BYTES_PER_BLOB};
+
+    use super::*;
+    use crate::{Address, U256};
+
+    fn sidecar() -> BlobSidecar {
+        let settings = EnvKzgSettings::Default.get();
+
+        // Every field element has to be smaller than the BLS modulus, so the
+        // most significant byte of each 32-byte element is left empty.
+        let mut bytes = vec![0u8; BYTES_PER_BLOB];
+        for (index, element) in bytes.chunks_mut(32).enumerate() {
+            element[31] = index as u8;
+        }
+
+        let blob = Blob::from_bytes(&bytes).expect("valid blob");
+        let commitment =
+            KzgCommitment::blob_to_kzg_commitment(&blob, settings).expect("valid commitment");
+        let commitment = commitment.to_bytes();
+        let proof =
+            KzgProof::compute_blob_kzg_proof(&blob, &commitment, settings).expect("valid proof");
+
+        BlobSidecar {
+            blobs: vec![Bytes::from(bytes)],
+            commitments: vec![FixedBytes::from_slice(commitment.as_slice())],
+            proofs: vec![FixedBytes::from_slice(proof.to_bytes().as_slice())],
+        }
+    }
+
+    fn transaction(blob_hashes: Vec<B256>) -> Eip4844SignedTransaction {
+        Eip4844SignedTransaction {
+            chain_id: 1337,
+            nonce: 0,
+            max_priority_fee_per_gas: U256::from(1),
+            max_fee_per_gas: U256::from(1_000_000_000),
+            gas_limit: 21_000,
+            to: Address::random(),
+            value: U256::ZERO,
+            input: Bytes::new(),
+            access_list: vec![].into(),
+            max_fee_per_blob_gas: U256::from(1),
+            blob_hashes,
+            odd_y_parity: true,
+            r: U256::from(1),
+            s: U256::from(2),
+            hash: OnceLock::new(),
+        }
+    }
+
+    #[test]
+    fn verify_sidecar() {
+        let sidecar = sidecar();
+        let versioned_hash = BlobSidecar::versioned_hash(&sidecar.commitments[0]);
+        assert_eq!(versioned_hash[0], VERSIONED_HASH_VERSION_KZG);
+
+        assert_eq!(sidecar.verify(&[versioned_hash]), Ok(()));
+
+        assert!(matches!(
+            sidecar.verify(&[]),
+            Err(BlobVerificationError::MismatchedLengths { .. })
+        ));
+
+        assert!(matches!(
+            sidecar.verify(&[B256::ZERO]),
+            Err(BlobVerificationError::VersionedHashMismatch { index: 0, .. })
+        ));
+
+        let mut tampered = sidecar.clone();
+        let mut blob = tampered.blobs[0].to_vec();
+        blob[31] ^= 1;
+        tampered.blobs[0] = Bytes::from(blob);
+
+        assert_eq!(
+            tampered.verify(&[versioned_hash]),
+            Err(BlobVerificationError::InvalidProof)
+        );
+    }
+
+    #[test]
+    fn decode_network_form() -> anyhow::Result<()> {
+        let sidecar = sidecar();
+        let versioned_hash = BlobSidecar::versioned_hash(&sidecar.commitments[0]);
+
+        let pooled = PooledTransaction::Eip4844(Eip4844PooledTransaction {
+            transaction: transaction(vec![versioned_hash]),
+            sidecar: sidecar.clone(),
+        });
+
+        let encoded = alloy_rlp::encode(&pooled);
+        assert_eq!(encoded.len(), pooled.length());
+        assert_eq!(encoded[0], 0x03);
+
+        let decoded = PooledTransaction::decode(&mut encoded.as_slice())?;
+        assert_eq!(decoded, pooled);
+
+        let (transaction, decoded_sidecar) = decoded.into_parts();
+        assert_eq!(transaction.blob_hashes(), Some(vec![versioned_hash]));
+        assert_eq!(decoded_sidecar, Some(sidecar));
+
+        Ok(())
+    }
+
+    #[test]
+    fn decode_canonical_form() -> anyhow::Result<()> {
+        let transaction = SignedTransaction::Eip4844(transaction(vec![B256::ZERO]));
+
+        let encoded = alloy_rlp::encode(&transaction);
+        let decoded = PooledTransaction::decode(&mut encoded.as_slice())?;
+
+        assert_eq!(decoded, PooledTransaction::Signed(transaction));
+
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+mod deposit;
 mod eip155;
 mod eip1559;
 mod eip2930;
@@ -7,8 +8,11 @@ mod legacy;
 use alloy_rlp::{Buf, BufMut, Decodable};
 
 pub use self::{
-    eip155::Eip155SignedTransaction, eip1559::Eip1559SignedTransaction,
-    eip2930::Eip2930SignedTransaction, eip4844::Eip4844SignedTransaction,
+    deposit::{DepositTransaction, DEPOSIT_TRANSACTION_TYPE},
+    eip155::Eip155SignedTransaction,
+    eip1559::Eip1559SignedTransaction,
+    eip2930::Eip2930SignedTransaction,
+    eip4844::Eip4844SignedTransaction,
     legacy::LegacySignedTransaction,
 };
 use super::kind::TransactionKind;
@@ -34,6 +38,8 @@ pub enum SignedTransaction {
     Eip1559(Eip1559SignedTransaction),
     /// EIP-4844 transaction
     Eip4844(Eip4844SignedTransaction),
+    /// OP-stack deposit transaction
+    Deposit(DepositTransaction),
 }
 
 impl SignedTransaction {
@@ -45,6 +51,8 @@ impl SignedTransaction {
             SignedTransaction::Eip2930(tx) => tx.gas_price,
             SignedTransaction::Eip1559(tx) => tx.max_fee_per_gas,
             SignedTransaction::Eip4844(tx) => tx.max_fee_per_gas,
+            // Deposit transactions don't pay for L2 gas
+            SignedTransaction::Deposit(_) => U256::ZERO,
         }
     }
 
@@ -56,6 +64,7 @@ impl SignedTransaction {
             SignedTransaction::Eip2930(tx) => tx.gas_limit,
             SignedTransaction::Eip1559(tx) => tx.gas_limit,
             SignedTransaction::Eip4844(tx) => tx.gas_limit,
+            SignedTransaction::Deposit(tx) => tx.gas_limit,
         }
     }
 
@@ -67,6 +76,7 @@ impl SignedTransaction {
             SignedTransaction::Eip2930(tx) => tx.value,
             SignedTransaction::Eip1559(tx) => tx.value,
             SignedTransaction::Eip4844(tx) => tx.value,
+            SignedTransaction::Deposit(tx) => tx.value,
         }
     }
 
@@ -78,13 +88,16 @@ impl SignedTransaction {
             SignedTransaction::Eip2930(tx) => &tx.input,
             SignedTransaction::Eip1559(tx) => &tx.input,
             SignedTransaction::Eip4844(tx) => &tx.input,
+            SignedTransaction::Deposit(tx) => &tx.input,
         }
     }
 
     /// Returns the access list of the transaction, if any.
     pub fn access_list(&self) -> Option<&AccessList> {
         match self {
-            SignedTransaction::PreEip155Legacy(_) | SignedTransaction::PostEip155Legacy(_) => None,
+            SignedTransaction::PreEip155Legacy(_)
+            | SignedTransaction::PostEip155Legacy(_)
+            | SignedTransaction::Deposit(_) => None,
             SignedTransaction::Eip2930(tx) => Some(&tx.access_list),
             SignedTransaction::Eip1559(tx) => Some(&tx.access_list),
             SignedTransaction::Eip4844(tx) => Some(&tx.access_list),
@@ -101,7 +114,8 @@ impl SignedTransaction {
         match self {
             SignedTransaction::PreEip155Legacy(_)
             | SignedTransaction::PostEip155Legacy(_)
-            | SignedTransaction::Eip2930(_) => None,
+            | SignedTransaction::Eip2930(_)
+            | SignedTransaction::Deposit(_) => None,
             SignedTransaction::Eip1559(tx) => Some(tx.max_fee_per_gas),
             SignedTransaction::Eip4844(tx) => Some(tx.max_fee_per_gas),
         }
@@ -112,7 +126,8 @@ impl SignedTransaction {
         match self {
             SignedTransaction::PreEip155Legacy(_)
             | SignedTransaction::PostEip155Legacy(_)
-            | SignedTransaction::Eip2930(_) => None,
+            | SignedTransaction::Eip2930(_)
+            | SignedTransaction::Deposit(_) => None,
             SignedTransaction::Eip1559(tx) => Some(tx.max_priority_fee_per_gas),
             SignedTransaction::Eip4844(tx) => Some(tx.max_priority_fee_per_gas),
         }
@@ -124,7 +139,8 @@ impl SignedTransaction {
             SignedTransaction::PreEip155Legacy(_)
             | SignedTransaction::PostEip155Legacy(_)
             | SignedTransaction::Eip2930(_)
-            | SignedTransaction::Eip1559(_) => None,
+            | SignedTransaction::Eip1559(_)
+            | SignedTransaction::Deposit(_) => None,
             SignedTransaction::Eip4844(tx) => Some(tx.max_fee_per_blob_gas),
         }
     }
@@ -135,7 +151,8 @@ impl SignedTransaction {
             SignedTransaction::PreEip155Legacy(_)
             | SignedTransaction::PostEip155Legacy(_)
             | SignedTransaction::Eip2930(_)
-            | SignedTransaction::Eip1559(_) => None,
+            | SignedTransaction::Eip1559(_)
+            | SignedTransaction::Deposit(_) => None,
             SignedTransaction::Eip4844(tx) => Some(tx.blob_hashes.clone()),
         }
     }
@@ -145,7 +162,8 @@ impl SignedTransaction {
         self.max_cost().saturating_add(self.value())
     }
 
-    /// Returns the nonce of the transaction.
+    /// Returns the nonce of the transaction. Deposit transactions don't have a
+    /// nonce, so zero is returned for them.
     pub fn nonce(&self) -> u64 {
         match self {
             SignedTransaction::PreEip155Legacy(t) => t.nonce,
@@ -153,13 +171,14 @@ impl SignedTransaction {
             SignedTransaction::Eip2930(t) => t.nonce,
             SignedTransaction::Eip1559(t) => t.nonce,
             SignedTransaction::Eip4844(t) => t.nonce,
+            SignedTransaction::Deposit(_) => 0,
         }
     }
 
     /// Returns the chain id of the transaction.
     pub fn chain_id(&self) -> Option<u64> {
         match self {
-            SignedTransaction::PreEip155Legacy(_) => None,
+            SignedTransaction::PreEip155Legacy(_) | SignedTransaction::Deposit(_) => None,
             SignedTransaction::PostEip155Legacy(t) => Some(t.chain_id()),
             SignedTransaction::Eip2930(t) => Some(t.chain_id),
             SignedTransaction::Eip1559(t) => Some(t.chain_id),
@@ -189,6 +208,19 @@ impl SignedTransaction {
         matches!(self, SignedTransaction::Eip1559(_))
     }
 
+    /// Returns the deposit transaction, if this is one.
+    pub fn as_deposit(&self) -> Option<&DepositTransaction> {
+        match self {
+            SignedTransaction::Deposit(tx) => Some(tx),
+            _ => None,
+        }
+    }
+
+    /// Returns whether this is an OP-stack deposit transaction
+    pub fn is_deposit(&self) -> bool {
+        matches!(self, SignedTransaction::Deposit(_))
+    }
+
     /// Computes the hash of the transaction.
     pub fn hash(&self) -> &B256 {
         match self {
@@ -197,6 +229,7 @@ impl SignedTransaction {
             SignedTransaction::Eip2930(t) => t.hash(),
             SignedTransaction::Eip1559(t) => t.hash(),
             SignedTransaction::Eip4844(t) => t.hash(),
+            SignedTransaction::Deposit(t) => t.hash(),
         }
     }
 
@@ -208,6 +241,7 @@ impl SignedTransaction {
             SignedTransaction::Eip2930(tx) => tx.recover(),
             SignedTransaction::Eip1559(tx) => tx.recover(),
             SignedTransaction::Eip4844(tx) => tx.recover(),
+            SignedTransaction::Deposit(tx) => Ok(tx.from),
         }
     }
 
@@ -219,6 +253,7 @@ impl SignedTransaction {
             SignedTransaction::Eip2930(tx) => tx.kind,
             SignedTransaction::Eip1559(tx) => tx.kind,
             SignedTransaction::Eip4844(tx) => TransactionKind::Call(tx.to),
+            SignedTransaction::Deposit(tx) => tx.kind,
         }
     }
 
@@ -247,6 +282,12 @@ impl SignedTransaction {
                 s: tx.s,
                 v: u64::from(tx.odd_y_parity),
             },
+            // OP-stack nodes return a zero signature for deposit transactions
+            SignedTransaction::Deposit(_) => Signature {
+                r: U256::ZERO,
+                s: U256::ZERO,
+                v: 0,
+            },
         }
     }
 
@@ -256,6 +297,7 @@ impl SignedTransaction {
             SignedTransaction::Eip2930(_) => 1,
             SignedTransaction::Eip1559(_) => 2,
             SignedTransaction::Eip4844(_) => 3,
+            SignedTransaction::Deposit(_) => u64::from(DEPOSIT_TRANSACTION_TYPE),
         }
     }
 
@@ -294,6 +336,11 @@ impl Decodable for SignedTransaction {
                     Eip4844SignedTransaction::decode(buf)?,
                 ))
             }
+            DEPOSIT_TRANSACTION_TYPE => {
+                buf.advance(1);
+
+                Ok(SignedTransaction::Deposit(DepositTransaction::decode(buf)?))
+            }
             byte if is_list(byte) => {
                 let tx = LegacySignedTransaction::decode(buf)?;
                 if tx.signature.v >= 35 {
@@ -315,6 +362,7 @@ impl alloy_rlp::Encodable for SignedTransaction {
             SignedTransaction::Eip2930(tx) => enveloped(1, tx, out),
             SignedTransaction::Eip1559(tx) => enveloped(2, tx, out),
             SignedTransaction::Eip4844(tx) => enveloped(3, tx, out),
+            SignedTransaction::Deposit(tx) => enveloped(DEPOSIT_TRANSACTION_TYPE, tx, out),
         }
     }
 
@@ -325,6 +373,7 @@ impl alloy_rlp::Encodable for SignedTransaction {
             SignedTransaction::Eip2930(tx) => tx.length() + 1,
             SignedTransaction::Eip1559(tx) => tx.length() + 1,
             SignedTransaction::Eip4844(tx) => tx.length() + 1,
+            SignedTransaction::Deposit(tx) => tx.length() + 1,
         }
     }
 }
@@ -359,6 +408,12 @@ impl From<Eip4844SignedTransaction> for SignedTransaction {
     }
 }
 
+impl From<DepositTransaction> for SignedTransaction {
+    fn from(transaction: DepositTransaction) -> Self {
+        Self::Deposit(transaction)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::OnceLock;
@@ -499,6 +554,17 @@ mod tests {
                 s: U256::default(),
                 hash: OnceLock::new(),
             }),
+            deposit => SignedTransaction::Deposit(DepositTransaction {
+                source_hash: B256::random(),
+                from: Address::random(),
+                kind: TransactionKind::Create,
+                mint: U256::from(1),
+                value: U256::from(2),
+                gas_limit: 3,
+                is_system_tx: false,
+                input: Bytes::from(vec![1, 2]),
+                hash: OnceLock::new(),
+            }),
     }
 
     #[test]
@@ -666,6 +732,7 @@ mod tests {
                 SignedTransaction::Eip2930(transaction) => transaction.into(),
                 SignedTransaction::Eip1559(transaction) => transaction.into(),
                 SignedTransaction::Eip4844(transaction) => transaction.into(),
+                SignedTransaction::Deposit(transaction) => transaction.into(),
             }
         }
     }
@@ -0,0 +1,94 @@
+use std::sync::OnceLock;
+
+use alloy_primitives::keccak256;
+use alloy_rlp::{RlpDecodable, RlpEncodable};
+
+use crate::{transaction::kind::TransactionKind, utils::envelop_bytes, Address, Bytes, B256, U256};
+
+/// The type of an OP-stack deposit transaction.
+pub const DEPOSIT_TRANSACTION_TYPE: u8 = 0x7e;
+
+/// A deposit transaction of an OP-stack chain, derived from an L1 event.
+/// Deposit transactions aren't signed; their sender is part of the
+/// transaction.
+#[derive(Clone, Debug, Eq, RlpDecodable, RlpEncodable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DepositTransaction {
+    // The order of these fields determines de-/encoding order.
+    /// Uniquely identifies the source of the deposit
+    pub source_hash: B256,
+    /// The sender of the transaction
+    pub from: Address,
+    /// The recipient of the transaction, or a contract creation
+    pub kind: TransactionKind,
+    /// The ETH value to mint on L2
+    pub mint: U256,
+    /// The ETH value to transfer to the recipient
+    pub value: U256,
+    /// The L2 gas limit of the transaction
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde::u64"))]
+    pub gas_limit: u64,
+    /// Whether the transaction is exempt from the L2 gas limit
+    pub is_system_tx: bool,
+    /// The calldata or contract initcode
+    pub input: Bytes,
+    /// Cached transaction hash
+    #[rlp(default)]
+    #[rlp(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub hash: OnceLock<B256>,
+}
+
+impl DepositTransaction {
+    /// Computes the hash of the transaction, which is cached after the first
+    /// call.
+    pub fn hash(&self) -> &B256 {
+        self.hash.get_or_init(|| {
+            let encoded = alloy_rlp::encode(self);
+            let enveloped = envelop_bytes(DEPOSIT_TRANSACTION_TYPE, &encoded);
+
+            keccak256(enveloped)
+        })
+    }
+}
+
+impl PartialEq for DepositTransaction {
+    fn eq(&self, other: &Self) -> bool {
+        self.source_hash == other.source_hash
+            && self.from == other.from
+            && self.kind == other.kind
+            && self.mint == other.mint
+            && self.value == other.value
+            && self.gas_limit == other.gas_limit
+            && self.is_system_tx == other.is_system_tx
+            && self.input == other.input
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_rlp::Decodable;
+
+    use super::*;
+
+    #[test]
+    fn test_deposit_transaction_rlp() {
+        let transaction = DepositTransaction {
+            source_hash: B256::repeat_byte(0x01),
+            from: Address::repeat_byte(0x02),
+            kind: TransactionKind::Call(Address::repeat_byte(0x03)),
+            mint: U256::from(4),
+            value: U256::from(5),
+            gas_limit: 6,
+            is_system_tx: false,
+            input: Bytes::from_static(&[0x07]),
+            hash: OnceLock::new(),
+        };
+
+        let encoded = alloy_rlp::encode(&transaction);
+        assert_eq!(
+            transaction,
+            DepositTransaction::decode(&mut encoded.as_slice()).unwrap()
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use edr_eth::{
+    access_list::{AccessList, AccessListItem},
+    Address, Bytes, B256, U256,
+};
+use revm::{
+    interpreter::{opcode, CallInputs, CreateInputs, Gas, InstructionResult, Interpreter},
+    EVMData, Inspector,
+};
+
+/// Inspector that records the addresses and storage slots that are accessed
+/// by a transaction, like geth's `AccessListTracer`.
+///
+/// The caller, the recipient and precompiles are only included if one of
+/// their storage slots is accessed, as they are always warm.
+#[derive(Debug, Default)]
+pub struct AccessListTracer {
+    access_list: BTreeMap<Address, BTreeSet<B256>>,
+    excluded: HashSet<Address>,
+    depth: usize,
+}
+
+impl AccessListTracer {
+    /// Constructs a tracer for a transaction from `caller` to `to`, starting
+    /// from the transaction's access list.
+    pub fn new(access_list: &[AccessListItem], caller: Address, to: Option<Address>) -> Self {
+        let mut tracer = Self {
+            excluded: std::iter::once(caller).chain(to).collect(),
+            ..Self::default()
+        };
+
+        for item in access_list {
+            if !item.storage_keys.is_empty() || !tracer.excluded.contains(&item.address) {
+                tracer
+                    .access_list
+                    .entry(item.address)
+                    .or_default()
+                    .extend(item.storage_keys.iter().copied());
+            }
+        }
+
+        tracer
+    }
+
+    /// Converts the tracer into the recorded access list, ordered by address
+    /// and storage key.
+    pub fn into_access_list(self) -> AccessList {
+        self.access_list
+            .into_iter()
+            .map(|(address, storage_keys)| AccessListItem {
+                address,
+                storage_keys: storage_keys.into_iter().collect(),
+            })
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    fn add_address<DatabaseErrorT>(
+        &mut self,
+        data: &EVMData<'_, DatabaseErrorT>,
+        address: Address,
+    ) {
+        if !self.excluded.contains(&address) && !data.precompiles.contains(&address) {
+            self.access_list.entry(address).or_default();
+        }
+    }
+}
+
+impl<DatabaseErrorT> Inspector<DatabaseErrorT> for AccessListTracer {
+    fn step(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+    ) -> InstructionResult {
+        let stack = interp.stack.data();
+
+        match interp.current_opcode() {
+            opcode::SLOAD | opcode::SSTORE => {
+                if let Some(slot) = peek(stack, 0) {
+                    self.access_list
+                        .entry(interp.contract.address)
+                        .or_default()
+                        .insert(B256::from(slot));
+                }
+            }
+            opcode::BALANCE
+            | opcode::EXTCODECOPY
+            | opcode::EXTCODEHASH
+            | opcode::EXTCODESIZE
+            | opcode::SELFDESTRUCT => {
+                if let Some(address) = peek(stack, 0) {
+                    self.add_address(data, word_to_address(address));
+                }
+            }
+            opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL => {
+                if let Some(address) = peek(stack, 1) {
+                    self.add_address(data, word_to_address(address));
+                }
+            }
+            _ => (),
+        }
+
+        InstructionResult::Continue
+    }
+
+    fn call(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        _inputs: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.depth += 1;
+
+        (InstructionResult::Continue, Gas::new(0), Bytes::new())
+    }
+
+    fn call_end(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        _inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.depth -= 1;
+
+        (ret, remaining_gas, out)
+    }
+
+    fn create(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        _inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.depth += 1;
+
+        (
+            InstructionResult::Continue,
+            None,
+            Gas::new(0),
+            Bytes::default(),
+        )
+    }
+
+    fn create_end(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        _inputs: &CreateInputs,
+        ret: InstructionResult,
+        address: Option<Address>,
+        remaining_gas: Gas,
+        out: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.depth -= 1;
+
+        // The address of a created contract is only known after the creation,
+        // so it's excluded retroactively if it's the transaction's recipient.
+        if self.depth == 0 {
+            if let Some(address) = address {
+                self.excluded.insert(address);
+
+                if self
+                    .access_list
+                    .get(&address)
+                    .map_or(false, BTreeSet::is_empty)
+                {
+                    self.access_list.remove(&address);
+                }
+            }
+        }
+
+        (ret, address, remaining_gas, out)
+    }
+}
+
+fn peek(stack: &[U256], index: usize) -> Option<U256> {
+    stack
+        .len()
+        .checked_sub(index + 1)
+        .map(|position| stack[position])
+}
+
+fn word_to_address(word: U256) -> Address {
+    Address::from_slice(&B256::from(word)[12..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn excludes_caller_and_recipient_without_slots() {
+        let caller = Address::repeat_byte(1);
+        let recipient = Address::repeat_byte(2);
+        let other = Address::repeat_byte(3);
+        let slot = B256::repeat_byte(4);
+
+        let tracer = AccessListTracer::new(
+            &[
+                AccessListItem {
+                    address: caller,
+                    storage_keys: Vec::new(),
+                },
+                AccessListItem {
+                    address: recipient,
+                    storage_keys: vec![slot],
+                },
+                AccessListItem {
+                    address: other,
+                    storage_keys: Vec::new(),
+                },
+            ],
+            caller,
+            Some(recipient),
+        );
+
+        assert_eq!(
+            tracer.into_access_list(),
+            AccessList(vec![
+                AccessListItem {
+                    address: recipient,
+                    storage_keys: vec![slot],
+                },
+                AccessListItem {
+                    address: other,
+                    storage_keys: Vec::new(),
+                },
+            ])
+        );
+    }
+}
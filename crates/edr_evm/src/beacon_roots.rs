@@ -0,0 +1,105 @@
+use std::fmt::Debug;
+
+use edr_eth::{Address, Bytes, B256, U256};
+use revm::{
+    db::StateRef,
+    primitives::{
+        Account, AccountInfo, BlockEnv, Bytecode, CfgEnv, HashMap, TransactTo, TxEnv, KECCAK_EMPTY,
+    },
+};
+
+use crate::{
+    blockchain::SyncBlockchain,
+    evm::{build_evm, run_transaction},
+    state::SyncState,
+    BlockTransactionError,
+};
+
+/// The address of the EIP-4788 beacon roots contract.
+pub const BEACON_ROOTS_ADDRESS: Address = Address::new([
+    0x00, 0x0f, 0x3d, 0xf6, 0xd7, 0x32, 0x80, 0x7e, 0xf1, 0x31, 0x9f, 0xb7, 0xb8, 0xbb, 0x85, 0x22,
+    0xd0, 0xbe, 0xac, 0x02,
+]);
+
+/// The caller of system calls, as defined by EIP-4788.
+pub const SYSTEM_ADDRESS: Address = Address::new([
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xfe,
+]);
+
+/// The runtime bytecode of the beacon roots contract.
+const BEACON_ROOTS_CODE: [u8; 97] = [
+    0x33, 0x73, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xfe, 0x14, 0x60, 0x4d, 0x57, 0x60, 0x20, 0x36, 0x14, 0x60, 0x24,
+    0x57, 0x5f, 0x5f, 0xfd, 0x5b, 0x5f, 0x35, 0x80, 0x15, 0x60, 0x49, 0x57, 0x62, 0x00, 0x1f, 0xff,
+    0x81, 0x06, 0x90, 0x81, 0x54, 0x14, 0x60, 0x3c, 0x57, 0x5f, 0x5f, 0xfd, 0x5b, 0x62, 0x00, 0x1f,
+    0xff, 0x01, 0x54, 0x5f, 0x52, 0x60, 0x20, 0x5f, 0xf3, 0x5b, 0x5f, 0x5f, 0xfd, 0x5b, 0x62, 0x00,
+    0x1f, 0xff, 0x42, 0x06, 0x42, 0x81, 0x55, 0x5f, 0x35, 0x90, 0x62, 0x00, 0x1f, 0xff, 0x01, 0x55,
+    0x00,
+];
+
+/// The gas limit of the system call, which isn't subtracted from the block's
+/// gas.
+const SYSTEM_CALL_GAS_LIMIT: u64 = 30_000_000;
+
+/// Constructs the account of the beacon roots contract, as deployed on
+/// mainnet.
+pub fn beacon_roots_contract() -> AccountInfo {
+    let code = Bytecode::new_raw(Bytes::from_static(&BEACON_ROOTS_CODE));
+
+    AccountInfo {
+        balance: U256::ZERO,
+        nonce: 1,
+        code_hash: code.hash_slow(),
+        code: Some(code),
+    }
+}
+
+/// Runs the system call that stores the parent beacon block root in the beacon
+/// roots contract, returning the resulting changes of the contract's state.
+///
+/// Returns an empty set of changes if the contract isn't deployed.
+pub fn apply_beacon_root_contract_call<BlockchainErrorT, StateErrorT>(
+    blockchain: &dyn SyncBlockchain<BlockchainErrorT, StateErrorT>,
+    state: &dyn SyncState<StateErrorT>,
+    cfg: &CfgEnv,
+    block: BlockEnv,
+    parent_beacon_block_root: B256,
+) -> Result<HashMap<Address, Account>, BlockTransactionError<BlockchainErrorT, StateErrorT>>
+where
+    BlockchainErrorT: Debug + Send,
+    StateErrorT: Debug + Send,
+{
+    let has_code = state
+        .basic(BEACON_ROOTS_ADDRESS)
+        .map_err(BlockTransactionError::State)?
+        .map_or(false, |account_info| account_info.code_hash != KECCAK_EMPTY);
+
+    if !has_code {
+        return Ok(HashMap::new());
+    }
+
+    let mut cfg = cfg.clone();
+    // The system call neither pays for gas nor counts towards the block's gas
+    cfg.disable_base_fee = true;
+    cfg.disable_block_gas_limit = true;
+
+    let transaction = TxEnv {
+        caller: SYSTEM_ADDRESS,
+        transact_to: TransactTo::Call(BEACON_ROOTS_ADDRESS),
+        data: Bytes::copy_from_slice(parent_beacon_block_root.as_slice()),
+        gas_limit: SYSTEM_CALL_GAS_LIMIT,
+        ..TxEnv::default()
+    };
+
+    let evm = build_evm(blockchain, &state, cfg, transaction, block);
+    let mut result = run_transaction(evm, None).map_err(BlockTransactionError::from)?;
+
+    // Only the contract's storage is modified; the system address and the
+    // coinbase are merely touched by the call.
+    result
+        .state
+        .retain(|address, _account| *address == BEACON_ROOTS_ADDRESS);
+
+    Ok(result.state)
+}
@@ -7,28 +7,36 @@ use edr_eth::{
     block::{BlobGas, BlockOptions, Header, PartialHeader},
     log::{add_log_to_bloom, Log},
     receipt::{TransactionReceipt, TypedReceipt, TypedReceiptData},
+    spec::OptimismHardfork,
     transaction::SignedTransaction,
     trie::{ordered_trie_root, KECCAK_NULL_RLP},
+    withdrawal::Withdrawal,
     Address, Bloom, U256,
 };
 use revm::{
     db::DatabaseComponentError,
     primitives::{
         AccountInfo, BlobExcessGasAndPrice, BlockEnv, CfgEnv, EVMError, ExecutionResult,
-        InvalidHeader, InvalidTransaction, Output, ResultAndState, SpecId,
+        InvalidHeader, InvalidTransaction, Output, ResultAndState, SpecId, GAS_PER_BLOB,
+        MAX_BLOB_GAS_PER_BLOCK,
     },
 };
 
 use super::local::LocalBlock;
 use crate::{
+    beacon_roots,
     blockchain::SyncBlockchain,
     evm::{build_evm, run_transaction, SyncInspector},
+    optimism::{self, OptimismError},
     state::{AccountModifierFn, StateDiff, SyncState},
     ExecutableTransaction,
 };
 
 const DAO_EXTRA_DATA: &[u8] = b"dao-hard-fork";
 
+/// Withdrawal amounts are denominated in Gwei.
+const WEI_PER_GWEI: u64 = 1_000_000_000;
+
 /// An error caused during construction of a block builder.
 #[derive(Debug, thiserror::Error)]
 pub enum BlockBuilderCreationError {
@@ -49,6 +57,9 @@ pub enum BlockTransactionError<BE, SE> {
     /// Transaction has higher gas limit than is remaining in block
     #[error("Transaction has a higher gas limit than the remaining gas in the block")]
     ExceedsBlockGasLimit,
+    /// Transaction uses more blob gas than is remaining in block
+    #[error("Transaction uses more blob gas than the remaining blob gas in the block")]
+    ExceedsBlockBlobGasLimit,
     /// Sender does not have enough funds to send transaction.
     #[error("Sender doesn't have enough funds to send tx. The max upfront cost is: {max_upfront_cost} and the sender's balance is: {sender_balance}.")]
     InsufficientFunds {
@@ -65,6 +76,21 @@ pub enum BlockTransactionError<BE, SE> {
     State(SE),
 }
 
+impl<BE, SE> From<OptimismError<SE>> for BlockTransactionError<BE, SE> {
+    fn from(error: OptimismError<SE>) -> Self {
+        match error {
+            OptimismError::InsufficientFundsForL1Fee {
+                l1_fee,
+                sender_balance,
+            } => Self::InsufficientFunds {
+                max_upfront_cost: l1_fee,
+                sender_balance,
+            },
+            OptimismError::State(e) => Self::State(e),
+        }
+    }
+}
+
 impl<BE, SE> From<EVMError<DatabaseComponentError<SE, BE>>> for BlockTransactionError<BE, SE>
 where
     BE: Debug + Send,
@@ -106,6 +132,8 @@ pub struct BlockBuilder {
     state_diff: StateDiff,
     receipts: Vec<TransactionReceipt<Log>>,
     parent_gas_limit: Option<u64>,
+    optimism_hardfork: Option<OptimismHardfork>,
+    withdrawals: Option<Vec<Withdrawal>>,
 }
 
 impl BlockBuilder {
@@ -114,8 +142,9 @@ impl BlockBuilder {
     pub fn new(
         cfg: CfgEnv,
         parent: &Header,
-        options: BlockOptions,
+        mut options: BlockOptions,
         dao_hardfork_activation_block: Option<u64>,
+        optimism_hardfork: Option<OptimismHardfork>,
     ) -> Result<Self, BlockBuilderCreationError> {
         if cfg.spec_id < SpecId::BYZANTIUM {
             return Err(BlockBuilderCreationError::UnsupportedHardfork(cfg.spec_id));
@@ -127,6 +156,19 @@ impl BlockBuilder {
             None
         };
 
+        // An explicit withdrawals root is retained if no withdrawals are provided
+        let withdrawals = if cfg.spec_id >= SpecId::SHANGHAI {
+            options.withdrawals.take().or_else(|| {
+                if options.withdrawals_root.is_none() {
+                    Some(Vec::new())
+                } else {
+                    None
+                }
+            })
+        } else {
+            None
+        };
+
         let header = PartialHeader::new(cfg.spec_id, options, Some(parent));
 
         if let Some(dao_hardfork_activation_block) = dao_hardfork_activation_block {
@@ -148,6 +190,8 @@ impl BlockBuilder {
             state_diff: StateDiff::default(),
             receipts: Vec::new(),
             parent_gas_limit,
+            optimism_hardfork,
+            withdrawals,
         })
     }
 
@@ -166,31 +210,61 @@ impl BlockBuilder {
         self.header.gas_limit - self.gas_used()
     }
 
+    /// Retrieves the amount of blob gas left in the block, if blob gas is
+    /// supported.
+    pub fn blob_gas_remaining(&self) -> Option<u64> {
+        self.header
+            .blob_gas
+            .as_ref()
+            .map(|BlobGas { gas_used, .. }| MAX_BLOB_GAS_PER_BLOCK - gas_used)
+    }
+
     /// Retrieves the header of the block builder.
     pub fn header(&self) -> &PartialHeader {
         &self.header
     }
 
-    /// Adds a pending transaction to
+    /// Stores the parent beacon block root in the EIP-4788 beacon roots
+    /// contract, by running the system call that precedes the transactions of
+    /// post-Cancun blocks.
+    ///
+    /// Has to be called before adding transactions. Does nothing if the
+    /// contract isn't deployed.
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
-    pub fn add_transaction<BlockchainErrorT, StateErrorT>(
+    pub fn apply_beacon_root_contract_call<BlockchainErrorT, StateErrorT>(
         &mut self,
         blockchain: &dyn SyncBlockchain<BlockchainErrorT, StateErrorT>,
         state: &mut dyn SyncState<StateErrorT>,
-        transaction: ExecutableTransaction,
-        inspector: Option<&mut dyn SyncInspector<BlockchainErrorT, StateErrorT>>,
-    ) -> Result<ExecutionResult, BlockTransactionError<BlockchainErrorT, StateErrorT>>
+    ) -> Result<(), BlockTransactionError<BlockchainErrorT, StateErrorT>>
     where
         BlockchainErrorT: Debug + Send,
         StateErrorT: Debug + Send,
     {
-        //  transaction's gas limit cannot be greater than the remaining gas in the
-        // block
-        if transaction.gas_limit() > self.gas_remaining() {
-            return Err(BlockTransactionError::ExceedsBlockGasLimit);
-        }
+        let Some(parent_beacon_block_root) = self
+            .header
+            .parent_beacon_block_root
+            .filter(|_| self.cfg.spec_id >= SpecId::CANCUN)
+        else {
+            return Ok(());
+        };
+
+        let changes = beacon_roots::apply_beacon_root_contract_call(
+            blockchain,
+            state,
+            &self.cfg,
+            self.block_env(),
+            parent_beacon_block_root,
+        )?;
+
+        self.state_diff.apply_diff(changes.clone());
+        state.commit(changes);
+
+        Ok(())
+    }
 
-        let block = BlockEnv {
+    /// Constructs the block environment of the transactions in the block.
+    fn block_env(&self) -> BlockEnv {
+        BlockEnv {
             number: U256::from(self.header.number),
             coinbase: self.header.beneficiary,
             timestamp: U256::from(self.header.timestamp),
@@ -207,12 +281,70 @@ impl BlockBuilder {
                 .blob_gas
                 .as_ref()
                 .map(|BlobGas { excess_gas, .. }| BlobExcessGasAndPrice::new(*excess_gas)),
-        };
+        }
+    }
+
+    /// Adds a pending transaction to
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn add_transaction<BlockchainErrorT, StateErrorT>(
+        &mut self,
+        blockchain: &dyn SyncBlockchain<BlockchainErrorT, StateErrorT>,
+        state: &mut dyn SyncState<StateErrorT>,
+        transaction: ExecutableTransaction,
+        inspector: Option<&mut dyn SyncInspector<BlockchainErrorT, StateErrorT>>,
+    ) -> Result<ExecutionResult, BlockTransactionError<BlockchainErrorT, StateErrorT>>
+    where
+        BlockchainErrorT: Debug + Send,
+        StateErrorT: Debug + Send,
+    {
+        //  transaction's gas limit cannot be greater than the remaining gas in the
+        // block
+        if transaction.gas_limit() > self.gas_remaining() {
+            return Err(BlockTransactionError::ExceedsBlockGasLimit);
+        }
+
+        let blob_gas_used = transaction
+            .blob_hashes()
+            .map_or(0, |blob_hashes| GAS_PER_BLOB * blob_hashes.len() as u64);
+
+        // the transaction's blobs cannot exceed the remaining blob gas in the block
+        if let Some(blob_gas_remaining) = self.blob_gas_remaining() {
+            if blob_gas_used > blob_gas_remaining {
+                return Err(BlockTransactionError::ExceedsBlockBlobGasLimit);
+            }
+        }
+
+        let block = self.block_env();
+
+        let mut cfg = self.cfg.clone();
+        let mut pre_execution_changes = None;
+        let mut deposit_nonce = None;
+        if let Some(hardfork) = self.optimism_hardfork {
+            if transaction.is_deposit() {
+                // Deposits don't pay for L2 gas, so they're exempt from the base fee
+                cfg.disable_base_fee = true;
+
+                if hardfork >= OptimismHardfork::Regolith {
+                    deposit_nonce = Some(
+                        state
+                            .basic(*transaction.caller())
+                            .map_err(BlockTransactionError::State)?
+                            .map_or(0, |info| info.nonce),
+                    );
+                }
+            }
+
+            pre_execution_changes = Some(optimism::apply_pre_execution_changes(
+                state,
+                hardfork,
+                &transaction,
+            )?);
+        }
 
         let evm = build_evm(
             blockchain,
             &state,
-            self.cfg.clone(),
+            cfg,
             transaction.clone().into(),
             block.clone(),
         );
@@ -220,13 +352,51 @@ impl BlockBuilder {
         let ResultAndState {
             result,
             state: state_diff,
-        } = run_transaction(evm, inspector)?;
+        } = match run_transaction(evm, inspector) {
+            Ok(result) => result,
+            Err(error) => match (self.optimism_hardfork, transaction.as_deposit()) {
+                // Failed deposits are still included, retaining their mint
+                (Some(hardfork), Some(deposit)) => {
+                    optimism::failed_deposit_result(state, hardfork, deposit)
+                        .map_err(BlockTransactionError::State)?
+                }
+                _ => {
+                    // The transaction won't be included, so undo the OP-stack changes
+                    if let Some(changes) = &pre_execution_changes {
+                        optimism::revert_pre_execution_changes(state, &transaction, changes)
+                            .map_err(BlockTransactionError::State)?;
+                    }
+
+                    return Err(error.into());
+                }
+            },
+        };
+
+        let mut l1_fee = None;
+        if let Some(changes) = pre_execution_changes {
+            for (address, account_info) in changes.changed_accounts {
+                self.state_diff.apply_account_change(address, account_info);
+            }
+            l1_fee = changes.l1_fee;
+        }
 
         self.state_diff.apply_diff(state_diff.clone());
 
         state.commit(state_diff);
 
+        if self.optimism_hardfork.is_some() {
+            if let Some((address, account_info)) =
+                optimism::collect_base_fee(state, &transaction, block.basefee, result.gas_used())
+                    .map_err(BlockTransactionError::State)?
+            {
+                self.state_diff.apply_account_change(address, account_info);
+            }
+        }
+
         self.header.gas_used += result.gas_used();
+        if let Some(blob_gas) = self.header.blob_gas.as_mut() {
+            blob_gas.gas_used += blob_gas_used;
+        }
 
         let logs: Vec<Log> = result.logs().into_iter().map(Log::from).collect();
         let logs_bloom = {
@@ -250,9 +420,8 @@ impl BlockBuilder {
 
         let gas_price = transaction.gas_price();
         let effective_gas_price = if blockchain.spec_id() >= SpecId::LONDON {
-            if let SignedTransaction::Eip1559(transaction) = &*transaction {
-                block.basefee
-                    + (gas_price - block.basefee).min(transaction.max_priority_fee_per_gas)
+            if let Some(max_priority_fee_per_gas) = transaction.max_priority_fee_per_gas() {
+                block.basefee + (gas_price - block.basefee).min(max_priority_fee_per_gas)
             } else {
                 gas_price
             }
@@ -281,6 +450,14 @@ impl BlockBuilder {
                     SignedTransaction::Eip2930(_) => TypedReceiptData::Eip2930 { status },
                     SignedTransaction::Eip1559(_) => TypedReceiptData::Eip1559 { status },
                     SignedTransaction::Eip4844(_) => TypedReceiptData::Eip4844 { status },
+                    SignedTransaction::Deposit(_) => TypedReceiptData::Deposit {
+                        status,
+                        deposit_nonce,
+                        deposit_receipt_version: self
+                            .optimism_hardfork
+                            .filter(|hardfork| *hardfork >= OptimismHardfork::Canyon)
+                            .map(|_| 1),
+                    },
                 },
                 spec_id: self.cfg.spec_id,
             },
@@ -291,6 +468,7 @@ impl BlockBuilder {
             contract_address,
             gas_used: result.gas_used(),
             effective_gas_price: Some(effective_gas_price),
+            l1_fee,
         };
         self.receipts.push(receipt);
 
@@ -299,8 +477,8 @@ impl BlockBuilder {
         Ok(result)
     }
 
-    /// Finalizes the block, returning the block and the callers of the
-    /// transactions.
+    /// Finalizes the block, crediting the rewards and withdrawals, and returns
+    /// the block and the callers of the transactions.
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn finalize<StateT, StateErrorT>(
         mut self,
@@ -311,12 +489,19 @@ impl BlockBuilder {
         StateT: SyncState<StateErrorT> + ?Sized,
         StateErrorT: Debug + Send,
     {
-        for (address, reward) in rewards {
-            if reward > U256::ZERO {
+        let withdrawal_credits = self.withdrawals.iter().flatten().map(|withdrawal| {
+            (
+                withdrawal.address,
+                withdrawal.amount * U256::from(WEI_PER_GWEI),
+            )
+        });
+
+        for (address, amount) in rewards.into_iter().chain(withdrawal_credits) {
+            if amount > U256::ZERO {
                 let account_info = state.modify_account(
                     address,
                     AccountModifierFn::new(Box::new(move |balance, _nonce, _code| {
-                        *balance += reward;
+                        *balance += amount;
                     })),
                     &|| {
                         Ok(AccountInfo {
@@ -369,7 +554,7 @@ impl BlockBuilder {
             self.transactions,
             self.receipts,
             Vec::new(),
-            None,
+            self.withdrawals,
         );
 
         Ok(BuildBlockResult {
@@ -411,6 +596,7 @@ mod tests {
             &header,
             block_options,
             Some(DUMMY_DAO_HARDFORK_BLOCK_NUMBER),
+            None,
         );
         assert!(block_builder.is_ok());
     }
@@ -442,10 +628,225 @@ mod tests {
             &header,
             block_options,
             Some(DUMMY_DAO_HARDFORK_BLOCK_NUMBER),
+            None,
         );
         assert!(matches!(
             block_builder,
             Err(BlockBuilderCreationError::DaoHardforkInvalidData)
         ));
     }
+
+    #[test]
+    fn blob_gas_remaining() -> anyhow::Result<()> {
+        use edr_eth::block::BlockOptions;
+
+        use super::*;
+
+        let header = Header {
+            base_fee_per_gas: Some(U256::from(7)),
+            blob_gas: Some(BlobGas {
+                gas_used: MAX_BLOB_GAS_PER_BLOCK,
+                excess_gas: 0,
+            }),
+            ..Header::default()
+        };
+
+        let mut cfg = CfgEnv::default();
+        cfg.spec_id = SpecId::CANCUN;
+
+        let block_builder = BlockBuilder::new(cfg, &header, BlockOptions::default(), None, None)?;
+        assert_eq!(
+            block_builder.blob_gas_remaining(),
+            Some(MAX_BLOB_GAS_PER_BLOCK)
+        );
+
+        let mut cfg = CfgEnv::default();
+        cfg.spec_id = SpecId::SHANGHAI;
+
+        let block_builder = BlockBuilder::new(cfg, &header, BlockOptions::default(), None, None)?;
+        assert_eq!(block_builder.blob_gas_remaining(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn finalize_credits_withdrawals() -> anyhow::Result<()> {
+        use edr_eth::block::BlockOptions;
+        use revm::db::StateRef;
+
+        use super::*;
+        use crate::{state::TrieState, Block};
+
+        let recipient = Address::repeat_byte(1);
+        let withdrawals = vec![
+            Withdrawal {
+                index: 0,
+                validator_index: 1,
+                address: recipient,
+                amount: U256::from(2),
+            },
+            Withdrawal {
+                index: 1,
+                validator_index: 2,
+                address: recipient,
+                amount: U256::from(3),
+            },
+        ];
+
+        let parent = Header {
+            base_fee_per_gas: Some(U256::from(7)),
+            ..Header::default()
+        };
+
+        let mut cfg = CfgEnv::default();
+        cfg.spec_id = SpecId::SHANGHAI;
+
+        let block_options = BlockOptions {
+            withdrawals: Some(withdrawals.clone()),
+            ..BlockOptions::default()
+        };
+
+        let block_builder = BlockBuilder::new(cfg, &parent, block_options, None, None)?;
+
+        let mut state = TrieState::default();
+        let BuildBlockResult { block, .. } = block_builder.finalize(&mut state, Vec::new())?;
+
+        assert_eq!(block.withdrawals(), Some(withdrawals.as_slice()));
+        assert_eq!(
+            block.header().withdrawals_root,
+            Some(ordered_trie_root(withdrawals.iter().map(alloy_rlp::encode)))
+        );
+
+        let balance = state
+            .basic(recipient)?
+            .map(|account_info| account_info.balance);
+        assert_eq!(balance, Some(U256::from(5_000_000_000u64)));
+
+        // Without withdrawals, post-Shanghai blocks contain an empty list
+        let mut cfg = CfgEnv::default();
+        cfg.spec_id = SpecId::SHANGHAI;
+
+        let block_builder = BlockBuilder::new(cfg, &parent, BlockOptions::default(), None, None)?;
+        let BuildBlockResult { block, .. } = block_builder.finalize(&mut state, Vec::new())?;
+
+        assert_eq!(block.withdrawals(), Some(&[][..]));
+        assert_eq!(block.header().withdrawals_root, Some(KECCAK_NULL_RLP));
+
+        Ok(())
+    }
+
+    #[test]
+    fn optimism_failed_deposits_retain_mint_and_nonce() -> anyhow::Result<()> {
+        use std::sync::OnceLock;
+
+        use edr_eth::{
+            block::BlockOptions,
+            transaction::{DepositTransaction, TransactionKind},
+            B256,
+        };
+        use revm::primitives::{Account, AccountStatus, Bytecode, HashMap};
+
+        use super::*;
+        use crate::{
+            blockchain::{GenesisBlockOptions, LocalBlockchain},
+            state::IrregularState,
+            Block,
+        };
+
+        const GAS_LIMIT: u64 = 100_000;
+
+        let sender = Address::random();
+        let reverting_contract = Address::random();
+
+        // PUSH1 0 PUSH1 0 REVERT
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xfd]));
+        let genesis_diff = [(
+            reverting_contract,
+            Account {
+                info: AccountInfo {
+                    code_hash: code.hash_slow(),
+                    code: Some(code),
+                    ..AccountInfo::default()
+                },
+                storage: HashMap::new(),
+                status: AccountStatus::Created | AccountStatus::Touched,
+            },
+        )]
+        .into_iter()
+        .collect::<HashMap<_, _>>()
+        .into();
+
+        let blockchain = LocalBlockchain::new(
+            genesis_diff,
+            10,
+            SpecId::SHANGHAI,
+            6_000_000,
+            None,
+            Some(B256::random()),
+            None,
+            None,
+            None,
+            GenesisBlockOptions::default(),
+        )?;
+
+        let irregular_state = IrregularState::default();
+        let mut state = blockchain.state_at_block_number(0, irregular_state.state_overrides())?;
+
+        let mut cfg = CfgEnv::default();
+        cfg.spec_id = SpecId::SHANGHAI;
+
+        let mut block_builder = BlockBuilder::new(
+            cfg,
+            blockchain.last_block()?.header(),
+            BlockOptions::default(),
+            None,
+            Some(OptimismHardfork::Regolith),
+        )?;
+
+        let deposit = |kind, value| {
+            let transaction = SignedTransaction::Deposit(DepositTransaction {
+                source_hash: B256::random(),
+                from: sender,
+                kind,
+                mint: U256::from(1_000),
+                value,
+                gas_limit: GAS_LIMIT,
+                is_system_tx: false,
+                input: Bytes::new(),
+                hash: OnceLock::new(),
+            });
+
+            ExecutableTransaction::with_caller(SpecId::SHANGHAI, transaction, sender)
+        };
+
+        // A deposit that reverts
+        let result = block_builder.add_transaction(
+            &blockchain,
+            &mut *state,
+            deposit(TransactionKind::Call(reverting_contract), U256::ZERO)?,
+            None,
+        )?;
+        assert!(!result.is_success());
+
+        // A deposit that is invalid, as its value exceeds the sender's balance
+        let result = block_builder.add_transaction(
+            &blockchain,
+            &mut *state,
+            deposit(
+                TransactionKind::Call(Address::random()),
+                U256::from(1_000_000),
+            )?,
+            None,
+        )?;
+        assert!(!result.is_success());
+        assert_eq!(result.gas_used(), GAS_LIMIT);
+
+        assert_eq!(block_builder.transactions.len(), 2);
+
+        let sender_info = state.basic(sender)?.expect("sender should exist");
+        assert_eq!(sender_info.balance, U256::from(2_000));
+        assert_eq!(sender_info.nonce, 2);
+
+        Ok(())
+    }
 }
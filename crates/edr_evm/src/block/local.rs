@@ -188,6 +188,7 @@ fn transaction_to_block_receipts(
                     contract_address: receipt.contract_address,
                     gas_used: receipt.gas_used,
                     effective_gas_price: receipt.effective_gas_price,
+                    l1_fee: receipt.l1_fee,
                 },
                 block_hash: *block_hash,
                 block_number,
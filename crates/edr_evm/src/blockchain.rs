@@ -18,7 +18,11 @@ use revm::{
 use self::storage::ReservableSparseBlockchainStorage;
 pub use self::{
     forked::{CreationError as ForkedCreationError, ForkedBlockchain, ForkedBlockchainError},
-    local::{CreationError as LocalCreationError, LocalBlockchain},
+    local::{
+        export_genesis, CreationError as LocalCreationError, GenesisBlockOptions,
+        GenesisExportError, InsertBlockError as LocalInsertBlockError, LocalBlockchain,
+    },
+    storage::HistoryEntry,
 };
 use crate::{
     state::{StateDiff, StateOverride, SyncState},
@@ -75,6 +79,9 @@ pub enum BlockchainError {
         /// Hardfork activation history
         hardfork_activations: HardforkActivations,
     },
+    /// The blockchain doesn't support retrieving its local history
+    #[error("Retrieving the history of a blockchain with a pending block is not supported.")]
+    UnsupportedHistory,
 }
 
 /// Trait for implementations of an Ethereum blockchain.
@@ -118,6 +125,15 @@ pub trait Blockchain {
     /// Retrieves the last block number in the blockchain.
     fn last_block_number(&self) -> u64;
 
+    /// Retrieves the locally mined and reserved blocks, in ascending order.
+    #[allow(clippy::type_complexity)]
+    fn local_history(
+        &self,
+    ) -> Result<
+        Vec<HistoryEntry<Arc<dyn SyncBlock<Error = Self::BlockchainError>>>>,
+        Self::BlockchainError,
+    >;
+
     /// Retrieves the logs that match the provided filter.
     fn logs(
         &self,
@@ -266,7 +282,12 @@ fn validate_next_block(
         });
     }
 
-    if spec_id >= SpecId::SHANGHAI && next_header.withdrawals_root.is_none() {
+    // New blocks can precede Shanghai if it's activated by timestamp, but
+    // withdrawals can't be omitted once they have been included
+    if spec_id >= SpecId::SHANGHAI
+        && last_header.withdrawals_root.is_some()
+        && next_header.withdrawals_root.is_none()
+    {
         return Err(BlockchainError::MissingWithdrawals);
     }
 
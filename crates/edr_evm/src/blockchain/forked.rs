@@ -4,7 +4,7 @@ use edr_eth::{
     block::{largest_safe_block_number, safe_block_depth, LargestSafeBlockNumberArgs},
     log::FilterLog,
     receipt::BlockReceipt,
-    remote::{client::ForkMetadata, BlockSpec, RpcClient, RpcClientError},
+    remote::{client::ForkMetadata, BlockSpec, PreEip1898BlockSpec, RpcClient, RpcClientError},
     spec::{chain_hardfork_activations, chain_name, HardforkActivations},
     Address, B256, U256,
 };
@@ -16,9 +16,11 @@ use revm::{
 use tokio::runtime;
 
 use super::{
-    compute_state_at_block, remote::RemoteBlockchain, storage,
-    storage::ReservableSparseBlockchainStorage, validate_next_block, Blockchain, BlockchainError,
-    BlockchainMut,
+    compute_state_at_block,
+    remote::RemoteBlockchain,
+    storage,
+    storage::{HistoryEntry, ReservableSparseBlockchainStorage},
+    validate_next_block, Blockchain, BlockchainError, BlockchainMut,
 };
 use crate::{
     state::{ForkState, StateDiff, StateError, StateOverride, SyncState},
@@ -40,6 +42,12 @@ pub enum CreationError {
         /// Latest block number
         latest_block_number: u64,
     },
+    /// The fork block could not be retrieved from the remote blockchain
+    #[error("Trying to initialize a provider with block {fork_block_number} but the remote blockchain doesn't return it")]
+    MissingForkBlock {
+        /// Requested fork block number
+        fork_block_number: u64,
+    },
     /// The detected hardfork is not supported
     #[error("Cannot fork {chain_name} from block {fork_block_number}. The hardfork must be at least Spurious Dragon, but {hardfork:?} was detected.")]
     InvalidHardfork {
@@ -148,19 +156,24 @@ impl ForkedBlockchain {
                 }
             });
 
-        if let Some(hardfork) = hardfork_activations
-            .as_ref()
-            .and_then(|hardfork_activations| {
-                hardfork_activations.hardfork_at_block_number(fork_block_number)
-            })
-        {
-            if hardfork < SpecId::SPURIOUS_DRAGON {
-                return Err(CreationError::InvalidHardfork {
-                    chain_name: chain_name(remote_chain_id)
-                        .map_or_else(|| "unknown".to_string(), ToString::to_string),
-                    fork_block_number,
-                    hardfork,
-                });
+        if let Some(hardfork_activations) = &hardfork_activations {
+            let fork_block_timestamp = rpc_client
+                .get_block_by_number(PreEip1898BlockSpec::Number(fork_block_number))
+                .await?
+                .ok_or(CreationError::MissingForkBlock { fork_block_number })?
+                .timestamp;
+
+            if let Some(hardfork) =
+                hardfork_activations.hardfork_at_block(fork_block_number, fork_block_timestamp)
+            {
+                if hardfork < SpecId::SPURIOUS_DRAGON {
+                    return Err(CreationError::InvalidHardfork {
+                        chain_name: chain_name(remote_chain_id)
+                            .map_or_else(|| "unknown".to_string(), ToString::to_string),
+                        fork_block_number,
+                        hardfork,
+                    });
+                }
             }
         }
 
@@ -178,6 +191,11 @@ impl ForkedBlockchain {
         })
     }
 
+    /// Retrieves the hardfork activations of the remote blockchain, if known.
+    pub fn hardfork_activations(&self) -> Option<&HardforkActivations> {
+        self.hardfork_activations.as_ref()
+    }
+
     fn runtime(&self) -> &runtime::Handle {
         self.remote.runtime()
     }
@@ -289,6 +307,16 @@ impl Blockchain for ForkedBlockchain {
         self.local_storage.last_block_number()
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    fn local_history(
+        &self,
+    ) -> Result<
+        Vec<HistoryEntry<Arc<dyn SyncBlock<Error = Self::BlockchainError>>>>,
+        Self::BlockchainError,
+    > {
+        Ok(self.local_storage.history(self.fork_block_number + 1))
+    }
+
     fn logs(
         &self,
         from_block: u64,
@@ -364,7 +392,7 @@ impl Blockchain for ForkedBlockchain {
             .and_then(|block| {
                 if let Some(hardfork_activations) = &self.hardfork_activations {
                     hardfork_activations
-                        .hardfork_at_block_number(block.header().number)
+                        .hardfork_at_block(block.header().number, block.header().timestamp)
                         .ok_or(BlockchainError::UnknownBlockSpec {
                             block_number,
                             hardfork_activations: hardfork_activations.clone(),
@@ -8,19 +8,21 @@ use std::{
 
 use edr_eth::{
     block::{BlobGas, PartialHeader},
+    genesis::{Genesis, GenesisAccount, GenesisConfig},
     log::FilterLog,
     trie::KECCAK_NULL_RLP,
     Address, Bytes, B256, B64, U256,
 };
 use revm::{
-    db::BlockHashRef,
-    primitives::{HashSet, SpecId},
+    db::{BlockHashRef, StateRef},
+    primitives::{HashSet, SpecId, KECCAK_EMPTY},
     DatabaseCommit,
 };
 
 use super::{
-    compute_state_at_block, storage::ReservableSparseBlockchainStorage, validate_next_block,
-    Blockchain, BlockchainError, BlockchainMut,
+    compute_state_at_block,
+    storage::{HistoryEntry, ReservableSparseBlockchainStorage},
+    validate_next_block, Blockchain, BlockchainError, BlockchainMut,
 };
 use crate::{
     state::{StateDebug, StateDiff, StateError, StateOverride, SyncState, TrieState},
@@ -41,15 +43,55 @@ pub enum CreationError {
     MissingPrevrandao,
 }
 
+/// An error that occurs upon creation of a [`LocalBlockchain`] with a
+/// genesis block.
 #[derive(Debug, thiserror::Error)]
 pub enum InsertBlockError {
+    /// Invalid block number
     #[error("Invalid block number: {actual}. Expected: {expected}")]
-    InvalidBlockNumber { actual: u64, expected: u64 },
+    InvalidBlockNumber {
+        /// The block number of the genesis block
+        actual: u64,
+        /// The expected block number
+        expected: u64,
+    },
     /// Missing withdrawals for post-Shanghai blockchain
     #[error("Missing withdrawals for post-Shanghai blockchain")]
     MissingWithdrawals,
 }
 
+/// An error that occurs upon exporting a [`LocalBlockchain`] as a genesis
+/// file.
+#[derive(Debug, thiserror::Error)]
+pub enum GenesisExportError {
+    /// Blockchain error
+    #[error(transparent)]
+    Blockchain(#[from] BlockchainError),
+    /// State error
+    #[error(transparent)]
+    State(#[from] StateError),
+}
+
+/// Options for the genesis block's header fields. Fields that are not
+/// provided use the defaults for the blockchain's hardfork.
+#[derive(Clone, Debug, Default)]
+pub struct GenesisBlockOptions {
+    /// The beneficiary of the genesis block. Defaults to the zero address.
+    pub beneficiary: Option<Address>,
+    /// The difficulty of the genesis block
+    pub difficulty: Option<U256>,
+    /// The extra data of the genesis block. Defaults to `0x1234`.
+    pub extra_data: Option<Bytes>,
+    /// The mix hash of the genesis block. Defaults to the prevrandao for
+    /// post-merge blockchains.
+    pub mix_hash: Option<B256>,
+    /// The nonce of the genesis block
+    pub nonce: Option<B64>,
+    /// The hardfork of the genesis block, if later hardforks are activated
+    /// after it. Defaults to the blockchain's hardfork.
+    pub spec_id: Option<SpecId>,
+}
+
 /// A blockchain consisting of locally created blocks.
 #[derive(Debug)]
 pub struct LocalBlockchain {
@@ -73,22 +115,30 @@ impl LocalBlockchain {
         base_fee: Option<U256>,
         blob_gas: Option<BlobGas>,
         parent_beacon_block_root: Option<B256>,
+        genesis_options: GenesisBlockOptions,
     ) -> Result<Self, CreationError> {
         const EXTRA_DATA: &[u8] = b"\x12\x34";
 
+        let genesis_spec_id = genesis_options
+            .spec_id
+            .map_or(spec_id, |genesis_spec_id| genesis_spec_id.min(spec_id));
+
         let mut genesis_state = TrieState::default();
         genesis_state.commit(genesis_diff.clone().into());
 
         let partial_header = PartialHeader {
+            beneficiary: genesis_options.beneficiary.unwrap_or_default(),
             state_root: genesis_state
                 .state_root()
                 .expect("TrieState is guaranteed to successfully compute the state root"),
             receipts_root: KECCAK_NULL_RLP,
-            difficulty: if spec_id >= SpecId::MERGE {
-                U256::ZERO
-            } else {
-                U256::from(1)
-            },
+            difficulty: genesis_options.difficulty.unwrap_or_else(|| {
+                if genesis_spec_id >= SpecId::MERGE {
+                    U256::ZERO
+                } else {
+                    U256::from(1)
+                }
+            }),
             number: 0,
             gas_limit,
             gas_used: 0,
@@ -98,34 +148,40 @@ impl LocalBlockchain {
                     .expect("Current time must be after unix epoch")
                     .as_secs()
             }),
-            extra_data: Bytes::from(EXTRA_DATA),
-            mix_hash: if spec_id >= SpecId::MERGE {
+            extra_data: genesis_options
+                .extra_data
+                .unwrap_or_else(|| Bytes::from(EXTRA_DATA)),
+            mix_hash: if let Some(mix_hash) = genesis_options.mix_hash {
+                mix_hash
+            } else if genesis_spec_id >= SpecId::MERGE {
                 prevrandao.ok_or(CreationError::MissingPrevrandao)?
             } else {
                 B256::ZERO
             },
-            nonce: if spec_id >= SpecId::MERGE {
-                B64::ZERO
-            } else {
-                B64::from(66u64)
-            },
-            base_fee: if spec_id >= SpecId::LONDON {
+            nonce: genesis_options.nonce.unwrap_or_else(|| {
+                if genesis_spec_id >= SpecId::MERGE {
+                    B64::ZERO
+                } else {
+                    B64::from(66u64)
+                }
+            }),
+            base_fee: if genesis_spec_id >= SpecId::LONDON {
                 // Initial base fee from https://eips.ethereum.org/EIPS/eip-1559
                 Some(base_fee.unwrap_or(U256::from(1_000_000_000)))
             } else {
                 None
             },
-            withdrawals_root: if spec_id >= SpecId::SHANGHAI {
+            withdrawals_root: if genesis_spec_id >= SpecId::SHANGHAI {
                 Some(KECCAK_NULL_RLP)
             } else {
                 None
             },
-            blob_gas: if spec_id >= SpecId::CANCUN {
+            blob_gas: if genesis_spec_id >= SpecId::CANCUN {
                 Some(blob_gas.ok_or(CreationError::MissingBlobGas)?)
             } else {
                 None
             },
-            parent_beacon_block_root: if spec_id >= SpecId::CANCUN {
+            parent_beacon_block_root: if genesis_spec_id >= SpecId::CANCUN {
                 Some(parent_beacon_block_root.ok_or(CreationError::MissingParentBeaconBlockRoot)?)
             } else {
                 None
@@ -135,7 +191,7 @@ impl LocalBlockchain {
 
         Ok(unsafe {
             Self::with_genesis_block_unchecked(
-                LocalBlock::empty(spec_id, partial_header),
+                LocalBlock::empty(genesis_spec_id, partial_header),
                 genesis_diff,
                 chain_id,
                 spec_id,
@@ -198,6 +254,114 @@ impl LocalBlockchain {
             spec_id,
         }
     }
+
+    /// Exports the state of the last block as a genesis file, using the last
+    /// block's header for the genesis block's fields.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn genesis(
+        &self,
+        state_overrides: &BTreeMap<u64, StateOverride>,
+    ) -> Result<Genesis, GenesisExportError> {
+        export_genesis(self, state_overrides)
+    }
+}
+
+/// Exports the state of the provided blockchain's last block as a genesis
+/// file, using the last block's header for the genesis block's fields.
+///
+/// Only accounts that were modified in the blockchain's local history are
+/// exported.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn export_genesis<BlockchainT>(
+    blockchain: &BlockchainT,
+    state_overrides: &BTreeMap<u64, StateOverride>,
+) -> Result<Genesis, GenesisExportError>
+where
+    BlockchainT: Blockchain<BlockchainError = BlockchainError, StateError = StateError> + ?Sized,
+{
+    let last_block_number = blockchain.last_block_number();
+    let state = blockchain.state_at_block_number(last_block_number, state_overrides)?;
+
+    // The trie state can't enumerate its storage, so the modified addresses and
+    // storage slots are collected from the state diffs.
+    let mut storage_slots: BTreeMap<Address, HashSet<U256>> = BTreeMap::new();
+    let history = blockchain.local_history()?;
+    let diffs = history
+        .iter()
+        .filter_map(|entry| match entry {
+            HistoryEntry::Block { state_diff, .. } => Some(state_diff),
+            HistoryEntry::Reservation { .. } => None,
+        })
+        .chain(
+            state_overrides
+                .range(..=last_block_number)
+                .map(|(_, state_override)| &state_override.diff),
+        );
+
+    for diff in diffs {
+        for (address, account) in diff.as_inner() {
+            storage_slots
+                .entry(*address)
+                .or_default()
+                .extend(account.storage.keys().copied());
+        }
+    }
+
+    let mut alloc = BTreeMap::new();
+    for (address, indices) in storage_slots {
+        let Some(info) = state.basic(address)? else {
+            continue;
+        };
+
+        let mut storage = BTreeMap::new();
+        for index in indices {
+            let value = state.storage(address, index)?;
+            if value != U256::ZERO {
+                storage.insert(
+                    B256::from(index.to_be_bytes::<32>()),
+                    B256::from(value.to_be_bytes::<32>()),
+                );
+            }
+        }
+
+        let code = if info.code_hash == KECCAK_EMPTY {
+            None
+        } else {
+            Some(state.code_by_hash(info.code_hash)?.original_bytes())
+        };
+
+        if info.is_empty() && code.is_none() && storage.is_empty() {
+            continue;
+        }
+
+        alloc.insert(
+            address,
+            GenesisAccount {
+                code,
+                storage,
+                balance: info.balance,
+                nonce: info.nonce,
+            },
+        );
+    }
+
+    let last_block = blockchain.last_block()?;
+    let header = last_block.header();
+
+    Ok(Genesis {
+        config: GenesisConfig::with_spec_id(blockchain.chain_id(), blockchain.spec_id()),
+        nonce: u64::from_be_bytes(header.nonce.0),
+        timestamp: header.timestamp,
+        extra_data: header.extra_data.clone(),
+        gas_limit: header.gas_limit,
+        difficulty: Some(header.difficulty),
+        mix_hash: header.mix_hash,
+        coinbase: header.beneficiary,
+        alloc,
+        base_fee_per_gas: header.base_fee_per_gas,
+        excess_blob_gas: header.blob_gas.as_ref().map(|blob_gas| blob_gas.excess_gas),
+        blob_gas_used: header.blob_gas.as_ref().map(|blob_gas| blob_gas.gas_used),
+    })
 }
 
 impl Blockchain for LocalBlockchain {
@@ -253,6 +417,16 @@ impl Blockchain for LocalBlockchain {
         self.storage.last_block_number()
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    fn local_history(
+        &self,
+    ) -> Result<
+        Vec<HistoryEntry<Arc<dyn SyncBlock<Error = Self::BlockchainError>>>>,
+        Self::BlockchainError,
+    > {
+        Ok(self.storage.history(0))
+    }
+
     fn logs(
         &self,
         from_block: u64,
@@ -396,7 +570,7 @@ impl BlockHashRef for LocalBlockchain {
 #[cfg(test)]
 mod tests {
     use edr_eth::{AccountInfo, HashMap};
-    use revm::primitives::{Account, AccountStatus};
+    use revm::primitives::{Account, AccountStatus, Bytecode, StorageSlot};
 
     use super::*;
     use crate::state::IrregularState;
@@ -437,6 +611,7 @@ mod tests {
             None,
             Some(BlobGas::default()),
             Some(B256::random()),
+            GenesisBlockOptions::default(),
         )
         .unwrap();
 
@@ -457,4 +632,76 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn genesis_export() -> anyhow::Result<()> {
+        let address = Address::random();
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x01]));
+
+        let genesis_diff = [(
+            address,
+            Account {
+                info: AccountInfo {
+                    balance: U256::from(1_000u64),
+                    nonce: 1,
+                    code_hash: code.hash_slow(),
+                    code: Some(code),
+                },
+                storage: [
+                    (U256::from(1), StorageSlot::new(U256::from(2))),
+                    (U256::from(3), StorageSlot::new(U256::ZERO)),
+                ]
+                .into_iter()
+                .collect(),
+                status: AccountStatus::Created | AccountStatus::Touched,
+            },
+        )]
+        .into_iter()
+        .collect::<HashMap<_, _>>()
+        .into();
+
+        let blockchain = LocalBlockchain::new(
+            genesis_diff,
+            123,
+            SpecId::SHANGHAI,
+            6_000_000,
+            Some(1_000),
+            Some(B256::random()),
+            None,
+            None,
+            None,
+            GenesisBlockOptions {
+                extra_data: Some(Bytes::from_static(b"genesis")),
+                ..GenesisBlockOptions::default()
+            },
+        )?;
+
+        let irregular_state = IrregularState::default();
+        let genesis = blockchain.genesis(irregular_state.state_overrides())?;
+
+        assert_eq!(genesis.config.chain_id, 123);
+        assert_eq!(
+            genesis
+                .config
+                .hardfork_activations()
+                .hardfork_at_block(0, 0),
+            Some(SpecId::SHANGHAI)
+        );
+        assert_eq!(genesis.timestamp, 1_000);
+        assert_eq!(genesis.gas_limit, 6_000_000);
+        assert_eq!(genesis.extra_data, Bytes::from_static(b"genesis"));
+        assert_eq!(genesis.alloc.len(), 1);
+
+        let account = &genesis.alloc[&address];
+        assert_eq!(account.balance, U256::from(1_000u64));
+        assert_eq!(account.nonce, 1);
+        assert_eq!(account.code, Some(Bytes::from_static(&[0x60, 0x01])));
+        assert_eq!(account.storage.len(), 1);
+        assert_eq!(
+            account.storage[&B256::from(U256::from(1).to_be_bytes::<32>())],
+            B256::from(U256::from(2).to_be_bytes::<32>())
+        );
+
+        Ok(())
+    }
 }
@@ -3,7 +3,10 @@ mod sparse;
 
 use edr_eth::B256;
 
-pub use self::{reservable::ReservableSparseBlockchainStorage, sparse::SparseBlockchainStorage};
+pub use self::{
+    reservable::{HistoryEntry, ReservableSparseBlockchainStorage},
+    sparse::SparseBlockchainStorage,
+};
 
 /// An error that occurs when trying to insert a block into storage.
 #[derive(Debug, thiserror::Error)]
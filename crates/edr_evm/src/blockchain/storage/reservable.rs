@@ -23,6 +23,26 @@ struct Reservation {
     spec_id: SpecId,
 }
 
+/// A stored block or a sequence of reserved blocks, as returned by
+/// [`ReservableSparseBlockchainStorage::history`].
+#[derive(Clone, Debug)]
+pub enum HistoryEntry<BlockT> {
+    /// A block and the diff that it applied to the state
+    Block {
+        /// The block
+        block: BlockT,
+        /// The diff of the block's state
+        state_diff: StateDiff,
+    },
+    /// A sequence of reserved blocks
+    Reservation {
+        /// The number of reserved blocks
+        additional: u64,
+        /// The interval between the timestamps of the reserved blocks
+        interval: u64,
+    },
+}
+
 /// A storage solution for storing a subset of a Blockchain's blocks in-memory,
 /// while lazily loading blocks that have been reserved.
 #[derive(Debug)]
@@ -78,6 +98,78 @@ impl<BlockT: Block + Clone> ReservableSparseBlockchainStorage<BlockT> {
             .cloned()
     }
 
+    /// Retrieves the stored blocks, with the diffs of their states, and the
+    /// reserved blocks in ascending order, starting at the provided block
+    /// number.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn history(&self, first_block_number: u64) -> Vec<HistoryEntry<BlockT>> {
+        let reservations = self.reservations.read();
+        let storage = self.storage.read();
+
+        let mut history = Vec::new();
+        // The timestamp of the previous block, if it is stored; otherwise the
+        // reservation that contains it.
+        let mut previous_timestamp = None;
+        let mut previous_reservation = None;
+
+        let mut block_number = first_block_number;
+        while block_number <= self.last_block_number {
+            if let Some(reservation) = find_reservation(&reservations, block_number) {
+                history.push(HistoryEntry::Reservation {
+                    additional: reservation.last_number - block_number + 1,
+                    interval: reservation.interval,
+                });
+
+                previous_timestamp = None;
+                previous_reservation = Some(reservation);
+
+                block_number = reservation.last_number + 1;
+                continue;
+            }
+
+            let block = storage
+                .block_by_number(block_number)
+                .expect("Blocks that are not reserved must be stored")
+                .clone();
+
+            let timestamp = block.header().timestamp;
+
+            let entry = if let Some(diff_index) = self.number_to_diff_index.get(&block_number) {
+                HistoryEntry::Block {
+                    block,
+                    state_diff: self.state_diffs[*diff_index].1.clone(),
+                }
+            } else {
+                // Reserved blocks that have been retrieved are no longer part of a reservation
+                let previous_timestamp = previous_timestamp.unwrap_or_else(|| {
+                    let reservation = previous_reservation
+                        .expect("A reserved block must be preceded by another block");
+
+                    calculate_timestamp_for_reserved_block(
+                        &storage,
+                        &reservations,
+                        reservation,
+                        reservation.last_number,
+                    )
+                });
+
+                HistoryEntry::Reservation {
+                    additional: 1,
+                    interval: timestamp - previous_timestamp,
+                }
+            };
+
+            history.push(entry);
+
+            previous_timestamp = Some(timestamp);
+            previous_reservation = None;
+
+            block_number += 1;
+        }
+
+        history
+    }
+
     /// Retrieves whether a block with the provided number exists.
     #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn contains_block_number(&self, number: u64) -> bool {
@@ -0,0 +1,884 @@
+use std::collections::VecDeque;
+
+use alloy_sol_types::{sol, Revert, SolError, SolInterface};
+use edr_eth::{Address, Bytes, B256, U256};
+use revm::{
+    interpreter::{
+        opcode, return_ok, CallInputs, CreateInputs, Gas, InstructionResult, Interpreter,
+    },
+    primitives::{hex, BlockEnv, Bytecode, HashMap, State},
+    EVMData, Inspector,
+};
+
+/// The address of Foundry's `Vm` contract, on which cheatcodes are called.
+pub const CHEATCODE_ADDRESS: Address = Address::new([
+    0x71, 0x09, 0x70, 0x9e, 0xcf, 0xa9, 0x1a, 0x80, 0x62, 0x6f, 0xf3, 0x98, 0x9d, 0x68, 0xf6, 0x7f,
+    0x5b, 0x1d, 0xd1, 0x2d,
+]);
+
+/// Output of a call that reverted as expected. Its zeroes allow the caller to
+/// ABI-decode the return values it expects.
+const EXPECTED_REVERT_CALL_OUTPUT: [u8; 320] = [0u8; 320];
+
+/// Address returned by a create that reverted as expected, as Solidity treats
+/// the zero address as a failed deployment.
+const EXPECTED_REVERT_CREATE_ADDRESS: Address = Address::with_last_byte(1);
+
+sol! {
+    interface Vm {
+        function warp(uint256 newTimestamp) external;
+        function roll(uint256 newHeight) external;
+        function deal(address account, uint256 newBalance) external;
+        function prank(address msgSender) external;
+        function prank(address msgSender, address txOrigin) external;
+        function startPrank(address msgSender) external;
+        function startPrank(address msgSender, address txOrigin) external;
+        function stopPrank() external;
+        function store(address target, bytes32 slot, bytes32 value) external;
+        function load(address target, bytes32 slot) external view returns (bytes32 data);
+        function etch(address target, bytes calldata newRuntimeBytecode) external;
+        function expectRevert() external;
+        function expectRevert(bytes4 revertData) external;
+        function expectRevert(bytes calldata revertData) external;
+        function expectEmit() external;
+        function expectEmit(address emitter) external;
+        function expectEmit(bool checkTopic1, bool checkTopic2, bool checkTopic3, bool checkData) external;
+        function expectEmit(bool checkTopic1, bool checkTopic2, bool checkTopic3, bool checkData, address emitter) external;
+        function record() external;
+        function accesses(address target) external returns (bytes32[] memory readSlots, bytes32[] memory writeSlots);
+        function snapshot() external returns (uint256 snapshotId);
+        function revertTo(uint256 snapshotId) external returns (bool success);
+    }
+}
+
+/// Inspector that implements the core of Foundry's `Vm` interface for calls to
+/// [`CHEATCODE_ADDRESS`], allowing Solidity code to manipulate its environment
+/// within the same transaction.
+///
+/// Cheatcodes only affect the transaction that invokes them; all expectations,
+/// pranks, recordings, and snapshots are cleared at the start of each
+/// transaction.
+#[derive(Debug, Default)]
+pub struct CheatcodeInspector {
+    prank: Option<Prank>,
+    expected_revert: Option<ExpectedRevert>,
+    expected_emits: VecDeque<ExpectedEmit>,
+    recorded_accesses: Option<RecordedAccesses>,
+    snapshots: Vec<Snapshot>,
+}
+
+#[derive(Debug)]
+struct Prank {
+    /// The address that called `prank` or `startPrank`
+    prank_caller: Address,
+    /// The `tx.origin` at the time of the prank
+    prank_origin: Address,
+    new_caller: Address,
+    new_origin: Option<Address>,
+    depth: u64,
+    single_call: bool,
+}
+
+#[derive(Debug)]
+struct ExpectedRevert {
+    /// The expected revert data. Any revert matches if absent.
+    reason: Option<Bytes>,
+    depth: u64,
+}
+
+#[derive(Debug)]
+struct ExpectedEmit {
+    depth: u64,
+    /// The log to match against, which is the first log emitted after the
+    /// expectation.
+    log: Option<ExpectedLog>,
+    /// Whether to check topics 1-3 and the data, respectively.
+    checks: [bool; 4],
+    emitter: Option<Address>,
+    found: bool,
+}
+
+#[derive(Debug)]
+struct ExpectedLog {
+    topics: Vec<B256>,
+    data: Bytes,
+}
+
+#[derive(Debug, Default)]
+struct RecordedAccesses {
+    reads: HashMap<Address, Vec<U256>>,
+    writes: HashMap<Address, Vec<U256>>,
+}
+
+#[derive(Debug)]
+struct Snapshot {
+    state: State,
+    block: BlockEnv,
+    num_logs: usize,
+}
+
+enum CheatcodeError<DatabaseErrorT> {
+    Database(DatabaseErrorT),
+    Revert(String),
+}
+
+impl CheatcodeInspector {
+    fn apply_cheatcode<DatabaseErrorT>(
+        &mut self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &CallInputs,
+    ) -> Result<Bytes, CheatcodeError<DatabaseErrorT>> {
+        let call = Vm::VmCalls::abi_decode(&inputs.input, false).map_err(|_error| {
+            CheatcodeError::Revert(format!(
+                "Unsupported cheatcode: 0x{}",
+                hex::encode(inputs.input.get(..4).unwrap_or(&inputs.input))
+            ))
+        })?;
+
+        let depth = data.journaled_state.depth();
+
+        match call {
+            Vm::VmCalls::warp(Vm::warpCall { newTimestamp }) => {
+                data.env.block.timestamp = newTimestamp;
+            }
+            Vm::VmCalls::roll(Vm::rollCall { newHeight }) => {
+                data.env.block.number = newHeight;
+            }
+            Vm::VmCalls::deal(Vm::dealCall {
+                account,
+                newBalance,
+            }) => {
+                let address = Address::from_slice(account.as_slice());
+                load_touched_account(data, address)?;
+
+                data.journaled_state
+                    .state
+                    .get_mut(&address)
+                    .expect("Account was loaded")
+                    .info
+                    .balance = newBalance;
+            }
+            Vm::VmCalls::prank_0(Vm::prank_0Call { msgSender }) => {
+                self.start_prank(data, inputs, msgSender.as_slice(), None, true);
+            }
+            Vm::VmCalls::prank_1(Vm::prank_1Call {
+                msgSender,
+                txOrigin,
+            }) => {
+                self.start_prank(
+                    data,
+                    inputs,
+                    msgSender.as_slice(),
+                    Some(txOrigin.as_slice()),
+                    true,
+                );
+            }
+            Vm::VmCalls::startPrank_0(Vm::startPrank_0Call { msgSender }) => {
+                self.start_prank(data, inputs, msgSender.as_slice(), None, false);
+            }
+            Vm::VmCalls::startPrank_1(Vm::startPrank_1Call {
+                msgSender,
+                txOrigin,
+            }) => {
+                self.start_prank(
+                    data,
+                    inputs,
+                    msgSender.as_slice(),
+                    Some(txOrigin.as_slice()),
+                    false,
+                );
+            }
+            Vm::VmCalls::stopPrank(Vm::stopPrankCall {}) => {
+                if let Some(prank) = self.prank.take() {
+                    data.env.tx.caller = prank.prank_origin;
+                }
+            }
+            Vm::VmCalls::store(Vm::storeCall {
+                target,
+                slot,
+                value,
+            }) => {
+                let address = Address::from_slice(target.as_slice());
+                load_touched_account(data, address)?;
+
+                data.journaled_state
+                    .sstore(
+                        address,
+                        U256::from_be_bytes(slot.0),
+                        U256::from_be_bytes(value.0),
+                        data.db,
+                    )
+                    .map_err(CheatcodeError::Database)?;
+            }
+            Vm::VmCalls::load(Vm::loadCall { target, slot }) => {
+                let address = Address::from_slice(target.as_slice());
+                data.journaled_state
+                    .load_account(address, data.db)
+                    .map_err(CheatcodeError::Database)?;
+
+                let (value, _is_cold) = data
+                    .journaled_state
+                    .sload(address, U256::from_be_bytes(slot.0), data.db)
+                    .map_err(CheatcodeError::Database)?;
+
+                return Ok(Bytes::copy_from_slice(&value.to_be_bytes::<32>()));
+            }
+            Vm::VmCalls::etch(Vm::etchCall {
+                target,
+                newRuntimeBytecode,
+            }) => {
+                let address = Address::from_slice(target.as_slice());
+                load_touched_account(data, address)?;
+
+                let code = Bytecode::new_raw(Bytes::copy_from_slice(&newRuntimeBytecode));
+                data.journaled_state.set_code(address, code);
+            }
+            Vm::VmCalls::expectRevert_0(Vm::expectRevert_0Call {}) => {
+                self.expect_revert(None, depth)?;
+            }
+            Vm::VmCalls::expectRevert_1(Vm::expectRevert_1Call { revertData }) => {
+                self.expect_revert(Some(Bytes::copy_from_slice(revertData.as_slice())), depth)?;
+            }
+            Vm::VmCalls::expectRevert_2(Vm::expectRevert_2Call { revertData }) => {
+                self.expect_revert(Some(Bytes::copy_from_slice(&revertData)), depth)?;
+            }
+            Vm::VmCalls::expectEmit_0(Vm::expectEmit_0Call {}) => {
+                self.expect_emit([true; 4], None, depth);
+            }
+            Vm::VmCalls::expectEmit_1(Vm::expectEmit_1Call { emitter }) => {
+                self.expect_emit([true; 4], Some(emitter.as_slice()), depth);
+            }
+            Vm::VmCalls::expectEmit_2(Vm::expectEmit_2Call {
+                checkTopic1,
+                checkTopic2,
+                checkTopic3,
+                checkData,
+            }) => {
+                self.expect_emit(
+                    [checkTopic1, checkTopic2, checkTopic3, checkData],
+                    None,
+                    depth,
+                );
+            }
+            Vm::VmCalls::expectEmit_3(Vm::expectEmit_3Call {
+                checkTopic1,
+                checkTopic2,
+                checkTopic3,
+                checkData,
+                emitter,
+            }) => {
+                self.expect_emit(
+                    [checkTopic1, checkTopic2, checkTopic3, checkData],
+                    Some(emitter.as_slice()),
+                    depth,
+                );
+            }
+            Vm::VmCalls::record(Vm::recordCall {}) => {
+                self.recorded_accesses = Some(RecordedAccesses::default());
+            }
+            Vm::VmCalls::accesses(Vm::accessesCall { target }) => {
+                let address = Address::from_slice(target.as_slice());
+                let empty = Vec::new();
+
+                let (reads, writes) =
+                    self.recorded_accesses
+                        .as_ref()
+                        .map_or((&empty, &empty), |accesses| {
+                            (
+                                accesses.reads.get(&address).unwrap_or(&empty),
+                                accesses.writes.get(&address).unwrap_or(&empty),
+                            )
+                        });
+
+                return Ok(encode_slot_arrays(reads, writes));
+            }
+            Vm::VmCalls::snapshot(Vm::snapshotCall {}) => {
+                let snapshot_id = self.snapshots.len();
+                self.snapshots.push(Snapshot {
+                    state: data.journaled_state.state.clone(),
+                    block: data.env.block.clone(),
+                    num_logs: data.journaled_state.logs.len(),
+                });
+
+                return Ok(encode_word(U256::from(snapshot_id)));
+            }
+            Vm::VmCalls::revertTo(Vm::revertToCall { snapshotId }) => {
+                let snapshot_id = usize::try_from(snapshotId).unwrap_or(usize::MAX);
+
+                // Like `evm_revert`, reverting removes the snapshot and all subsequent ones
+                let success = if snapshot_id < self.snapshots.len() {
+                    let snapshot = self
+                        .snapshots
+                        .split_off(snapshot_id)
+                        .into_iter()
+                        .next()
+                        .expect("Snapshot exists");
+
+                    revert_to_snapshot(data, snapshot).map_err(CheatcodeError::Database)?;
+                    true
+                } else {
+                    false
+                };
+
+                return Ok(encode_word(U256::from(u8::from(success))));
+            }
+        }
+
+        Ok(Bytes::new())
+    }
+
+    fn start_prank<DatabaseErrorT>(
+        &mut self,
+        data: &EVMData<'_, DatabaseErrorT>,
+        inputs: &CallInputs,
+        new_caller: &[u8],
+        new_origin: Option<&[u8]>,
+        single_call: bool,
+    ) {
+        let prank_origin = self
+            .prank
+            .as_ref()
+            .map_or(data.env.tx.caller, |prank| prank.prank_origin);
+
+        self.prank = Some(Prank {
+            prank_caller: inputs.context.caller,
+            prank_origin,
+            new_caller: Address::from_slice(new_caller),
+            new_origin: new_origin.map(Address::from_slice),
+            depth: data.journaled_state.depth(),
+            single_call,
+        });
+    }
+
+    fn expect_revert<DatabaseErrorT>(
+        &mut self,
+        reason: Option<Bytes>,
+        depth: u64,
+    ) -> Result<(), CheatcodeError<DatabaseErrorT>> {
+        if self.expected_revert.is_some() {
+            return Err(CheatcodeError::Revert(String::from(
+                "A revert is already expected. Call another function before expecting a second revert.",
+            )));
+        }
+
+        self.expected_revert = Some(ExpectedRevert { reason, depth });
+
+        Ok(())
+    }
+
+    fn expect_emit(&mut self, checks: [bool; 4], emitter: Option<&[u8]>, depth: u64) {
+        self.expected_emits.push_back(ExpectedEmit {
+            depth,
+            log: None,
+            checks,
+            emitter: emitter.map(Address::from_slice),
+            found: false,
+        });
+    }
+
+    /// Applies an active prank to a call or create of the pranking contract.
+    /// Returns the new caller, if it should be replaced.
+    fn apply_prank<DatabaseErrorT>(
+        &self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        caller: Address,
+    ) -> Option<Address> {
+        let prank = self.prank.as_ref()?;
+
+        let depth = data.journaled_state.depth();
+        if depth < prank.depth || caller != prank.prank_caller {
+            return None;
+        }
+
+        // The origin is changed for all nested calls, but `msg.sender` only for calls
+        // made by the pranking contract.
+        if let Some(new_origin) = prank.new_origin {
+            data.env.tx.caller = new_origin;
+        }
+
+        (depth == prank.depth).then_some(prank.new_caller)
+    }
+
+    /// Ends single-call pranks and restores the `tx.origin`, after returning to
+    /// the depth of the prank.
+    fn end_prank<DatabaseErrorT>(&mut self, data: &mut EVMData<'_, DatabaseErrorT>) {
+        if let Some(prank) = &self.prank {
+            if data.journaled_state.depth() == prank.depth {
+                data.env.tx.caller = prank.prank_origin;
+
+                if prank.single_call {
+                    self.prank = None;
+                }
+            }
+        }
+    }
+
+    /// Checks the result of a call or create against the expected revert, if
+    /// it applies to the current depth. Returns the corrected result, if
+    /// applicable.
+    fn check_expected_revert(
+        &mut self,
+        depth: u64,
+        ret: InstructionResult,
+        output: &Bytes,
+    ) -> Option<Result<(), Bytes>> {
+        if self
+            .expected_revert
+            .as_ref()
+            .map_or(true, |expected| depth > expected.depth)
+        {
+            return None;
+        }
+
+        let expected = self.expected_revert.take()?;
+
+        if matches!(ret, return_ok!()) {
+            return Some(Err(revert_output("Call did not revert as expected")));
+        }
+
+        let Some(expected_reason) = expected.reason else {
+            return Some(Ok(()));
+        };
+
+        // Revert strings also match the raw bytes of the expected reason
+        let is_match = *output == expected_reason
+            || Revert::abi_decode(output, false).map_or(false, |revert| {
+                revert.reason.as_bytes() == expected_reason.as_ref()
+            });
+
+        Some(if is_match {
+            Ok(())
+        } else {
+            Err(revert_output(&format!(
+                "Error != expected error: {} != {}",
+                decode_revert_reason(output),
+                decode_revert_reason(&expected_reason)
+            )))
+        })
+    }
+
+    /// Checks whether all expected emits of the current depth were found.
+    /// Returns the revert output, if not.
+    fn check_expected_emits(&mut self, depth: u64, is_static: bool) -> Option<Bytes> {
+        if is_static
+            || !self
+                .expected_emits
+                .iter()
+                .any(|expected| expected.depth == depth)
+        {
+            return None;
+        }
+
+        if self.expected_emits.iter().all(|expected| expected.found) {
+            // Subsequent calls need to declare new expected emits
+            self.expected_emits.clear();
+            None
+        } else {
+            Some(revert_output("Log != expected log"))
+        }
+    }
+}
+
+impl<DatabaseErrorT> Inspector<DatabaseErrorT> for CheatcodeInspector {
+    fn step(
+        &mut self,
+        interp: &mut Interpreter,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+    ) -> InstructionResult {
+        if let Some(accesses) = &mut self.recorded_accesses {
+            let opcode = interp.current_opcode();
+            if matches!(opcode, opcode::SLOAD | opcode::SSTORE) {
+                if let Ok(index) = interp.stack.peek(0) {
+                    let address = interp.contract.address;
+
+                    accesses.reads.entry(address).or_default().push(index);
+                    if opcode == opcode::SSTORE {
+                        accesses.writes.entry(address).or_default().push(index);
+                    }
+                }
+            }
+        }
+
+        InstructionResult::Continue
+    }
+
+    fn log(
+        &mut self,
+        _evm_data: &mut EVMData<'_, DatabaseErrorT>,
+        address: &Address,
+        topics: &[B256],
+        data: &Bytes,
+    ) {
+        if self.expected_emits.iter().all(|expected| expected.found) {
+            return;
+        }
+
+        // Expected emits are filled in the order in which they were declared, after
+        // which they're matched like a queue.
+        let expected_emit = if self
+            .expected_emits
+            .iter()
+            .any(|expected| expected.log.is_none())
+        {
+            self.expected_emits.pop_back()
+        } else {
+            self.expected_emits.pop_front()
+        };
+
+        let Some(mut expected_emit) = expected_emit else {
+            return;
+        };
+
+        let Some(expected_log) = &expected_emit.log else {
+            expected_emit.log = Some(ExpectedLog {
+                topics: topics.to_vec(),
+                data: data.clone(),
+            });
+            self.expected_emits.push_back(expected_emit);
+            return;
+        };
+
+        let is_same_event = expected_log.topics.len() == topics.len()
+            && expected_log
+                .topics
+                .first()
+                .zip(topics.first())
+                .map_or(false, |(expected, actual)| expected == actual);
+
+        expected_emit.found = is_same_event
+            && topics
+                .iter()
+                .zip(expected_log.topics.iter())
+                .skip(1)
+                .zip(expected_emit.checks)
+                .all(|((actual, expected), check)| !check || actual == expected)
+            && (!expected_emit.checks[3] || *data == expected_log.data)
+            && expected_emit
+                .emitter
+                .map_or(true, |emitter| emitter == *address);
+
+        if expected_emit.found {
+            self.expected_emits.push_back(expected_emit);
+        } else {
+            self.expected_emits.push_front(expected_emit);
+        }
+    }
+
+    fn call(
+        &mut self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        if data.journaled_state.depth() == 0 {
+            *self = Self::default();
+        }
+
+        if inputs.contract == CHEATCODE_ADDRESS {
+            let gas = Gas::new(inputs.gas_limit);
+
+            return match self.apply_cheatcode(data, inputs) {
+                Ok(output) => (InstructionResult::Return, gas, output),
+                Err(CheatcodeError::Revert(reason)) => {
+                    (InstructionResult::Revert, gas, revert_output(&reason))
+                }
+                Err(CheatcodeError::Database(error)) => {
+                    data.error = Some(error);
+                    (InstructionResult::FatalExternalError, gas, Bytes::new())
+                }
+            };
+        }
+
+        if let Some(new_caller) = self.apply_prank(data, inputs.context.caller) {
+            inputs.context.caller = new_caller;
+            inputs.transfer.source = new_caller;
+        }
+
+        (
+            InstructionResult::Continue,
+            Gas::new(inputs.gas_limit),
+            Bytes::new(),
+        )
+    }
+
+    fn call_end(
+        &mut self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+    ) -> (InstructionResult, Gas, Bytes) {
+        if inputs.contract == CHEATCODE_ADDRESS {
+            return (ret, remaining_gas, out);
+        }
+
+        self.end_prank(data);
+
+        let depth = data.journaled_state.depth();
+        match self.check_expected_revert(depth, ret, &out) {
+            Some(Ok(())) => {
+                return (
+                    InstructionResult::Return,
+                    remaining_gas,
+                    Bytes::from_static(&EXPECTED_REVERT_CALL_OUTPUT),
+                )
+            }
+            Some(Err(output)) => return (InstructionResult::Revert, remaining_gas, output),
+            None => (),
+        }
+
+        if let Some(output) = self.check_expected_emits(depth, inputs.is_static) {
+            return (InstructionResult::Revert, remaining_gas, output);
+        }
+
+        (ret, remaining_gas, out)
+    }
+
+    fn create(
+        &mut self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        if data.journaled_state.depth() == 0 {
+            *self = Self::default();
+        }
+
+        if let Some(new_caller) = self.apply_prank(data, inputs.caller) {
+            inputs.caller = new_caller;
+        }
+
+        (
+            InstructionResult::Continue,
+            None,
+            Gas::new(inputs.gas_limit),
+            Bytes::new(),
+        )
+    }
+
+    fn create_end(
+        &mut self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        _inputs: &CreateInputs,
+        ret: InstructionResult,
+        address: Option<Address>,
+        remaining_gas: Gas,
+        out: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.end_prank(data);
+
+        let depth = data.journaled_state.depth();
+        match self.check_expected_revert(depth, ret, &out) {
+            Some(Ok(())) => (
+                InstructionResult::Return,
+                Some(EXPECTED_REVERT_CREATE_ADDRESS),
+                remaining_gas,
+                Bytes::new(),
+            ),
+            Some(Err(output)) => (InstructionResult::Revert, None, remaining_gas, output),
+            None => (ret, address, remaining_gas, out),
+        }
+    }
+}
+
+/// Loads the account into the journaled state and marks it as touched, such
+/// that direct modifications are committed.
+fn load_touched_account<DatabaseErrorT>(
+    data: &mut EVMData<'_, DatabaseErrorT>,
+    address: Address,
+) -> Result<(), CheatcodeError<DatabaseErrorT>> {
+    data.journaled_state
+        .load_account(address, data.db)
+        .map_err(CheatcodeError::Database)?;
+    data.journaled_state.touch(&address);
+
+    Ok(())
+}
+
+/// Restores the accounts and storage slots to their values at the time of the
+/// snapshot. Accounts and slots remain loaded, as the journal of the ongoing
+/// call frames might still refer to them.
+fn revert_to_snapshot<DatabaseErrorT>(
+    data: &mut EVMData<'_, DatabaseErrorT>,
+    snapshot: Snapshot,
+) -> Result<(), DatabaseErrorT> {
+    for (address, account) in &mut data.journaled_state.state {
+        if let Some(snapshot_account) = snapshot.state.get(address) {
+            account.info = snapshot_account.info.clone();
+
+            for (index, slot) in &mut account.storage {
+                slot.present_value = snapshot_account
+                    .storage
+                    .get(index)
+                    .map_or(slot.original_value, |snapshot_slot| {
+                        snapshot_slot.present_value
+                    });
+            }
+        } else {
+            // Accounts that were loaded after the snapshot still matched the database
+            account.info = data.db.basic(*address)?.unwrap_or_default();
+
+            for slot in account.storage.values_mut() {
+                slot.present_value = slot.original_value;
+            }
+        }
+    }
+
+    data.journaled_state.logs.truncate(snapshot.num_logs);
+    data.env.block = snapshot.block;
+
+    Ok(())
+}
+
+fn revert_output(reason: &str) -> Bytes {
+    Revert {
+        reason: reason.to_string(),
+    }
+    .abi_encode()
+    .into()
+}
+
+fn decode_revert_reason(output: &Bytes) -> String {
+    Revert::abi_decode(output, false).map_or_else(
+        |_error| {
+            String::from_utf8(output.to_vec())
+                .unwrap_or_else(|_error| format!("0x{}", hex::encode(output)))
+        },
+        |revert| revert.reason,
+    )
+}
+
+fn encode_word(value: U256) -> Bytes {
+    Bytes::copy_from_slice(&value.to_be_bytes::<32>())
+}
+
+/// ABI-encodes the return value `(bytes32[], bytes32[])`.
+fn encode_slot_arrays(first: &[U256], second: &[U256]) -> Bytes {
+    const HEAD_SIZE: usize = 2 * 32;
+
+    let first_offset = HEAD_SIZE;
+    let second_offset = first_offset + 32 * (1 + first.len());
+
+    let words = [U256::from(first_offset), U256::from(second_offset)]
+        .into_iter()
+        .chain(std::iter::once(U256::from(first.len())))
+        .chain(first.iter().copied())
+        .chain(std::iter::once(U256::from(second.len())))
+        .chain(second.iter().copied());
+
+    words
+        .flat_map(|word| word.to_be_bytes::<32>())
+        .collect::<Vec<u8>>()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cheatcode_address() {
+        assert_eq!(
+            CHEATCODE_ADDRESS,
+            "0x7109709ECfa91a80626fF3989D68f67F5b1DD12D"
+                .parse::<Address>()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn decodes_overloaded_cheatcodes() {
+        let call = Vm::VmCalls::abi_decode(&Vm::stopPrankCall {}.abi_encode(), true).unwrap();
+        assert!(matches!(call, Vm::VmCalls::stopPrank(_)));
+
+        let call = Vm::VmCalls::abi_decode(&Vm::expectEmit_0Call {}.abi_encode(), true).unwrap();
+        assert!(matches!(call, Vm::VmCalls::expectEmit_0(_)));
+
+        let call = Vm::VmCalls::abi_decode(
+            &Vm::expectEmit_2Call {
+                checkTopic1: true,
+                checkTopic2: false,
+                checkTopic3: false,
+                checkData: true,
+            }
+            .abi_encode(),
+            true,
+        )
+        .unwrap();
+        assert!(matches!(
+            call,
+            Vm::VmCalls::expectEmit_2(Vm::expectEmit_2Call {
+                checkTopic1: true,
+                checkTopic2: false,
+                checkTopic3: false,
+                checkData: true,
+            })
+        ));
+    }
+
+    #[test]
+    fn encodes_slot_arrays() {
+        let reads = [U256::from(1), U256::from(2)];
+        let writes = [U256::from(2)];
+
+        let encoded = encode_slot_arrays(&reads, &writes);
+        let (decoded_reads, decoded_writes) = Vm::accessesCall::abi_decode_returns(&encoded, true)
+            .map(|returns| (returns.readSlots, returns.writeSlots))
+            .unwrap();
+
+        assert_eq!(
+            decoded_reads
+                .iter()
+                .map(|slot| U256::from_be_bytes(slot.0))
+                .collect::<Vec<_>>(),
+            reads
+        );
+        assert_eq!(
+            decoded_writes
+                .iter()
+                .map(|slot| U256::from_be_bytes(slot.0))
+                .collect::<Vec<_>>(),
+            writes
+        );
+    }
+
+    #[test]
+    fn matches_revert_reason() {
+        let mut inspector = CheatcodeInspector {
+            expected_revert: Some(ExpectedRevert {
+                reason: Some(Bytes::from_static(b"Unauthorized")),
+                depth: 1,
+            }),
+            ..CheatcodeInspector::default()
+        };
+
+        // Deeper calls don't satisfy the expectation
+        assert!(inspector
+            .check_expected_revert(2, InstructionResult::Revert, &Bytes::new())
+            .is_none());
+
+        let result = inspector.check_expected_revert(
+            1,
+            InstructionResult::Revert,
+            &revert_output("Unauthorized"),
+        );
+        assert_eq!(result, Some(Ok(())));
+        assert!(inspector.expected_revert.is_none());
+    }
+
+    #[test]
+    fn rejects_successful_call() {
+        let mut inspector = CheatcodeInspector {
+            expected_revert: Some(ExpectedRevert {
+                reason: None,
+                depth: 1,
+            }),
+            ..CheatcodeInspector::default()
+        };
+
+        let result = inspector.check_expected_revert(1, InstructionResult::Return, &Bytes::new());
+        assert_eq!(
+            result,
+            Some(Err(revert_output("Call did not revert as expected")))
+        );
+    }
+}
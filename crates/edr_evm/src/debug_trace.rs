@@ -1,76 +1,144 @@
-use std::{collections::HashMap, fmt::Debug};
+mod call_tracer;
+mod four_byte_tracer;
+mod prestate_tracer;
 
-use edr_eth::{signature::SignatureError, utils::u256_to_padded_hex, B256};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+};
+
+use edr_eth::{signature::SignatureError, spec::OptimismHardfork, utils::u256_to_padded_hex, B256};
 use revm::{
+    db::{DatabaseComponentError, StateRef},
     inspectors::GasInspector,
     interpreter::{opcode, CallInputs, CreateInputs, Gas, InstructionResult, Interpreter, Stack},
     primitives::{
-        hex, Address, BlockEnv, Bytes, CfgEnv, ExecutionResult, ResultAndState, SpecId, U256,
+        hex, Address, BlockEnv, Bytes, CfgEnv, EVMError, ExecutionResult, ResultAndState, SpecId,
+        U256,
     },
     EVMData, Inspector, JournalEntry,
 };
 
+pub use self::{
+    call_tracer::{CallFrame, CallKind, CallLog, CallTracer, CallTracerConfig},
+    four_byte_tracer::FourByteTracer,
+    prestate_tracer::{prestate_trace, PrestateAccount, PrestateTrace, PrestateTracerConfig},
+};
 use crate::{
-    blockchain::SyncBlockchain, evm::build_evm, state::SyncState, ExecutableTransaction,
-    TransactionError,
+    beacon_roots,
+    blockchain::SyncBlockchain,
+    evm::{build_evm, run_transaction, SyncInspector},
+    optimism::{self, OptimismError},
+    state::SyncState,
+    BlockTransactionError, ExecutableTransaction, TransactionError,
 };
 
 /// Get trace output for `debug_traceTransaction`
+#[allow(clippy::too_many_arguments)]
 #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 pub fn debug_trace_transaction<BlockchainErrorT, StateErrorT>(
     blockchain: &dyn SyncBlockchain<BlockchainErrorT, StateErrorT>,
     // Take ownership of the state so that we can apply throw-away modifications on it
-    mut state: Box<dyn SyncState<StateErrorT>>,
+    state: Box<dyn SyncState<StateErrorT>>,
     evm_config: CfgEnv,
     trace_config: DebugTraceConfig,
     block_env: BlockEnv,
+    parent_beacon_block_root: Option<B256>,
     transactions: Vec<ExecutableTransaction>,
     transaction_hash: &B256,
-) -> Result<DebugTraceResult, DebugTraceError<BlockchainErrorT, StateErrorT>>
+    optimism_hardfork: Option<OptimismHardfork>,
+) -> Result<DebugTraceOutput, DebugTraceError<BlockchainErrorT, StateErrorT>>
 where
     BlockchainErrorT: Debug + Send,
     StateErrorT: Debug + Send,
 {
-    if evm_config.spec_id < SpecId::SPURIOUS_DRAGON {
-        // Matching Hardhat Network behaviour: https://github.com/NomicFoundation/hardhat/blob/af7e4ce6a18601ec9cd6d4aa335fa7e24450e638/packages/hardhat-core/src/internal/hardhat-network/provider/vm/ethereumjs.ts#L427
-        return Err(DebugTraceError::InvalidSpecId {
-            spec_id: evm_config.spec_id,
-        });
-    }
+    let mut inspector = DebugInspector::new(trace_config);
+    let (result, state) = replay_transaction(
+        blockchain,
+        state,
+        evm_config,
+        block_env,
+        parent_beacon_block_root,
+        transactions,
+        transaction_hash,
+        optimism_hardfork,
+        inspector.as_dyn_inspector(),
+    )?;
+
+    inspector
+        .into_output(result, &*state)
+        .map_err(|error| TransactionError::State(error).into())
+}
 
-    if evm_config.spec_id > SpecId::MERGE && block_env.prevrandao.is_none() {
-        return Err(TransactionError::MissingPrevrandao.into());
-    }
+/// Replays the transactions of a block up to the transaction with the provided
+/// hash, which is run with the inspector. Returns the transaction's result and
+/// the state before the transaction.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn replay_transaction<BlockchainErrorT, StateErrorT>(
+    blockchain: &dyn SyncBlockchain<BlockchainErrorT, StateErrorT>,
+    mut state: Box<dyn SyncState<StateErrorT>>,
+    evm_config: CfgEnv,
+    block_env: BlockEnv,
+    parent_beacon_block_root: Option<B256>,
+    transactions: Vec<ExecutableTransaction>,
+    transaction_hash: &B256,
+    optimism_hardfork: Option<OptimismHardfork>,
+    inspector: Option<&mut dyn SyncInspector<BlockchainErrorT, StateErrorT>>,
+) -> Result<
+    (ResultAndState, Box<dyn SyncState<StateErrorT>>),
+    DebugTraceError<BlockchainErrorT, StateErrorT>,
+>
+where
+    BlockchainErrorT: Debug + Send,
+    StateErrorT: Debug + Send,
+{
+    validate_trace_env(&evm_config, &block_env)?;
+    apply_beacon_root_contract_call(
+        blockchain,
+        &mut *state,
+        &evm_config,
+        &block_env,
+        parent_beacon_block_root,
+    )?;
 
     for transaction in transactions {
+        let transaction_config =
+            prepare_transaction(&mut *state, &evm_config, optimism_hardfork, &transaction)?;
+
         if transaction.hash() == transaction_hash {
             let evm = build_evm(
                 blockchain,
                 &state,
-                evm_config,
-                transaction.into(),
+                transaction_config,
+                transaction.clone().into(),
                 block_env,
             );
-            let mut tracer = TracerEip3155::new(trace_config);
-            let ResultAndState {
-                result: execution_result,
-                ..
-            } = evm
-                .inspect_ref(&mut tracer)
-                .map_err(TransactionError::from)?;
+            let result = run_transaction(evm, inspector).or_else(|error| {
+                failed_deposit_result(&*state, optimism_hardfork, &transaction, error)
+            })?;
 
-            return Ok(execution_result_to_debug_result(execution_result, tracer));
+            return Ok((result, state));
         } else {
+            let base_fee = block_env.basefee;
             let evm = build_evm(
                 blockchain,
                 &state,
-                evm_config.clone(),
-                transaction.into(),
+                transaction_config,
+                transaction.clone().into(),
                 block_env.clone(),
             );
-            let ResultAndState { state: changes, .. } =
-                evm.transact_ref().map_err(TransactionError::from)?;
+            let ResultAndState {
+                result,
+                state: changes,
+            } = evm.transact_ref().or_else(|error| {
+                failed_deposit_result(&*state, optimism_hardfork, &transaction, error)
+            })?;
             state.commit(changes);
+
+            if optimism_hardfork.is_some() {
+                optimism::collect_base_fee(&mut *state, &transaction, base_fee, result.gas_used())
+                    .map_err(TransactionError::State)?;
+            }
         }
     }
 
@@ -80,6 +148,168 @@ where
     })
 }
 
+/// Get trace output for `debug_traceBlockByNumber`, `debug_traceBlockByHash`
+/// and `debug_traceBlock`, containing one trace per transaction.
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn debug_trace_block<BlockchainErrorT, StateErrorT>(
+    blockchain: &dyn SyncBlockchain<BlockchainErrorT, StateErrorT>,
+    // Take ownership of the state so that we can apply throw-away modifications on it
+    mut state: Box<dyn SyncState<StateErrorT>>,
+    evm_config: CfgEnv,
+    trace_config: DebugTraceConfig,
+    block_env: BlockEnv,
+    parent_beacon_block_root: Option<B256>,
+    transactions: Vec<ExecutableTransaction>,
+    optimism_hardfork: Option<OptimismHardfork>,
+) -> Result<Vec<DebugTraceBlockResult>, DebugTraceError<BlockchainErrorT, StateErrorT>>
+where
+    BlockchainErrorT: Debug + Send,
+    StateErrorT: Debug + Send,
+{
+    validate_trace_env(&evm_config, &block_env)?;
+    apply_beacon_root_contract_call(
+        blockchain,
+        &mut *state,
+        &evm_config,
+        &block_env,
+        parent_beacon_block_root,
+    )?;
+
+    let mut results = Vec::with_capacity(transactions.len());
+    for transaction in transactions {
+        let tx_hash = *transaction.hash();
+
+        let transaction_config =
+            prepare_transaction(&mut *state, &evm_config, optimism_hardfork, &transaction)?;
+
+        let evm = build_evm(
+            blockchain,
+            &state,
+            transaction_config,
+            transaction.clone().into(),
+            block_env.clone(),
+        );
+        let mut inspector = DebugInspector::new(trace_config.clone());
+        let result = run_transaction(evm, inspector.as_dyn_inspector()).or_else(|error| {
+            failed_deposit_result(&*state, optimism_hardfork, &transaction, error)
+        })?;
+
+        let changes = result.state.clone();
+        let gas_used = result.result.gas_used();
+        let result = inspector
+            .into_output(result, &*state)
+            .map_err(TransactionError::State)?;
+
+        state.commit(changes);
+
+        if optimism_hardfork.is_some() {
+            optimism::collect_base_fee(&mut *state, &transaction, block_env.basefee, gas_used)
+                .map_err(TransactionError::State)?;
+        }
+
+        results.push(DebugTraceBlockResult { tx_hash, result });
+    }
+
+    Ok(results)
+}
+
+/// Constructs the result of a replayed deposit transaction that failed to
+/// execute, as failed deposits are included in blocks regardless. Returns the
+/// provided error for any other transaction.
+pub(crate) fn failed_deposit_result<BlockchainErrorT, StateErrorT>(
+    state: &dyn SyncState<StateErrorT>,
+    optimism_hardfork: Option<OptimismHardfork>,
+    transaction: &ExecutableTransaction,
+    error: EVMError<DatabaseComponentError<StateErrorT, BlockchainErrorT>>,
+) -> Result<ResultAndState, DebugTraceError<BlockchainErrorT, StateErrorT>>
+where
+    BlockchainErrorT: Debug + Send,
+    StateErrorT: Debug + Send,
+{
+    match (optimism_hardfork, transaction.as_deposit()) {
+        (Some(hardfork), Some(deposit)) => {
+            optimism::failed_deposit_result(state, hardfork, deposit)
+                .map_err(|error| TransactionError::State(error).into())
+        }
+        _ => Err(TransactionError::from(error).into()),
+    }
+}
+
+/// Runs the EIP-4788 system call that precedes the transactions of post-Cancun
+/// blocks, so replayed transactions observe the same beacon roots as when the
+/// block was mined.
+pub(crate) fn apply_beacon_root_contract_call<BlockchainErrorT, StateErrorT>(
+    blockchain: &dyn SyncBlockchain<BlockchainErrorT, StateErrorT>,
+    state: &mut dyn SyncState<StateErrorT>,
+    evm_config: &CfgEnv,
+    block_env: &BlockEnv,
+    parent_beacon_block_root: Option<B256>,
+) -> Result<(), DebugTraceError<BlockchainErrorT, StateErrorT>>
+where
+    BlockchainErrorT: Debug + Send,
+    StateErrorT: Debug + Send,
+{
+    let Some(parent_beacon_block_root) =
+        parent_beacon_block_root.filter(|_| evm_config.spec_id >= SpecId::CANCUN)
+    else {
+        return Ok(());
+    };
+
+    let changes = beacon_roots::apply_beacon_root_contract_call(
+        blockchain,
+        state,
+        evm_config,
+        block_env.clone(),
+        parent_beacon_block_root,
+    )?;
+    state.commit(changes);
+
+    Ok(())
+}
+
+/// Applies the OP-stack changes that precede the execution of a replayed
+/// transaction, returning the config to execute it with.
+pub(crate) fn prepare_transaction<BlockchainErrorT, StateErrorT>(
+    state: &mut dyn SyncState<StateErrorT>,
+    evm_config: &CfgEnv,
+    optimism_hardfork: Option<OptimismHardfork>,
+    transaction: &ExecutableTransaction,
+) -> Result<CfgEnv, DebugTraceError<BlockchainErrorT, StateErrorT>>
+where
+    StateErrorT: Debug + Send,
+{
+    let mut evm_config = evm_config.clone();
+
+    if let Some(hardfork) = optimism_hardfork {
+        optimism::apply_pre_execution_changes(state, hardfork, transaction)?;
+
+        if transaction.is_deposit() {
+            evm_config.disable_base_fee = true;
+        }
+    }
+
+    Ok(evm_config)
+}
+
+pub(crate) fn validate_trace_env<BlockchainErrorT, StateErrorT>(
+    evm_config: &CfgEnv,
+    block_env: &BlockEnv,
+) -> Result<(), DebugTraceError<BlockchainErrorT, StateErrorT>> {
+    if evm_config.spec_id < SpecId::SPURIOUS_DRAGON {
+        // Matching Hardhat Network behaviour: https://github.com/NomicFoundation/hardhat/blob/af7e4ce6a18601ec9cd6d4aa335fa7e24450e638/packages/hardhat-core/src/internal/hardhat-network/provider/vm/ethereumjs.ts#L427
+        return Err(DebugTraceError::InvalidSpecId {
+            spec_id: evm_config.spec_id,
+        });
+    }
+
+    if evm_config.spec_id > SpecId::MERGE && block_env.prevrandao.is_none() {
+        return Err(TransactionError::MissingPrevrandao.into());
+    }
+
+    Ok(())
+}
+
 /// Convert an `ExecutionResult` to a `DebugTraceResult`.
 pub fn execution_result_to_debug_result(
     execution_result: ExecutionResult,
@@ -112,6 +342,8 @@ pub fn execution_result_to_debug_result(
 /// Config options for `debug_traceTransaction`
 #[derive(Debug, Default, Clone)]
 pub struct DebugTraceConfig {
+    /// The tracer to use.
+    pub tracer: DebugTracer,
     /// Disable storage trace.
     pub disable_storage: bool,
     /// Disable memory trace.
@@ -120,6 +352,130 @@ pub struct DebugTraceConfig {
     pub disable_stack: bool,
 }
 
+/// The tracers supported by `debug_traceTransaction` and `debug_traceCall`.
+#[derive(Debug, Default, Clone)]
+pub enum DebugTracer {
+    /// The EIP-3155 tracer, which returns struct logs.
+    #[default]
+    Eip3155,
+    /// Geth's built-in `callTracer`
+    Call(CallTracerConfig),
+    /// Geth's built-in `prestateTracer`
+    Prestate(PrestateTracerConfig),
+    /// Geth's built-in `4byteTracer`
+    FourByte,
+    /// Geth's built-in `noopTracer`
+    Noop,
+}
+
+/// Inspector that collects the data of the tracer selected in a
+/// [`DebugTraceConfig`].
+#[derive(Debug)]
+pub enum DebugInspector {
+    /// The EIP-3155 tracer
+    Eip3155(TracerEip3155),
+    /// The `callTracer`
+    Call(CallTracer),
+    /// The `prestateTracer`, which only requires the state changes
+    Prestate(PrestateTracerConfig),
+    /// The `4byteTracer`
+    FourByte(FourByteTracer),
+    /// The `noopTracer`
+    Noop,
+}
+
+impl DebugInspector {
+    /// Creates the inspector for the provided config.
+    pub fn new(config: DebugTraceConfig) -> Self {
+        match config.tracer.clone() {
+            DebugTracer::Eip3155 => Self::Eip3155(TracerEip3155::new(config)),
+            DebugTracer::Call(config) => Self::Call(CallTracer::new(config)),
+            DebugTracer::Prestate(config) => Self::Prestate(config),
+            DebugTracer::FourByte => Self::FourByte(FourByteTracer::default()),
+            DebugTracer::Noop => Self::Noop,
+        }
+    }
+
+    /// Returns the inspector that needs to run during execution, if any.
+    pub fn as_dyn_inspector<BlockchainErrorT, StateErrorT>(
+        &mut self,
+    ) -> Option<&mut dyn SyncInspector<BlockchainErrorT, StateErrorT>>
+    where
+        BlockchainErrorT: Debug + Send,
+        StateErrorT: Debug + Send,
+    {
+        match self {
+            Self::Eip3155(tracer) => Some(tracer),
+            Self::Call(tracer) => Some(tracer),
+            Self::FourByte(tracer) => Some(tracer),
+            Self::Prestate(_) | Self::Noop => None,
+        }
+    }
+
+    /// Converts the collected data into the tracer's output, using the state
+    /// before the transaction and the result of the transaction.
+    pub fn into_output<StateT>(
+        self,
+        result: ResultAndState,
+        state: &StateT,
+    ) -> Result<DebugTraceOutput, StateT::Error>
+    where
+        StateT: StateRef + ?Sized,
+    {
+        let ResultAndState {
+            result,
+            state: changes,
+        } = result;
+
+        let output = match self {
+            Self::Eip3155(tracer) => {
+                DebugTraceOutput::StructLogs(execution_result_to_debug_result(result, tracer))
+            }
+            Self::Call(tracer) => tracer
+                .into_call_frame(&result)
+                .map_or(DebugTraceOutput::Noop(NoopFrame {}), DebugTraceOutput::Call),
+            Self::Prestate(config) => {
+                DebugTraceOutput::Prestate(prestate_trace(&config, state, &changes)?)
+            }
+            Self::FourByte(tracer) => DebugTraceOutput::FourByte(tracer.into_selectors()),
+            Self::Noop => DebugTraceOutput::Noop(NoopFrame {}),
+        };
+
+        Ok(output)
+    }
+}
+
+/// The output of `debug_traceTransaction` and `debug_traceCall`, depending on
+/// the selected tracer.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged)]
+pub enum DebugTraceOutput {
+    /// The output of the EIP-3155 tracer
+    StructLogs(DebugTraceResult),
+    /// The output of the `callTracer`
+    Call(CallFrame),
+    /// The output of the `prestateTracer`
+    Prestate(PrestateTrace),
+    /// The output of the `4byteTracer`
+    FourByte(BTreeMap<String, u64>),
+    /// The output of the `noopTracer`
+    Noop(NoopFrame),
+}
+
+/// The trace of a single transaction in a block.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugTraceBlockResult {
+    /// The hash of the traced transaction
+    pub tx_hash: B256,
+    /// The output of the tracer
+    pub result: DebugTraceOutput,
+}
+
+/// The empty output of the `noopTracer`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct NoopFrame {}
+
 /// Debug trace error.
 #[derive(Debug, thiserror::Error)]
 pub enum DebugTraceError<BlockchainErrorT, StateErrorT> {
@@ -143,6 +499,12 @@ pub enum DebugTraceError<BlockchainErrorT, StateErrorT> {
     /// Transaction error.
     #[error(transparent)]
     TransactionError(#[from] TransactionError<BlockchainErrorT, StateErrorT>),
+    /// OP-stack error.
+    #[error(transparent)]
+    Optimism(#[from] OptimismError<StateErrorT>),
+    /// An error that occurred while running the EIP-4788 system call.
+    #[error(transparent)]
+    BeaconRootContractCall(#[from] BlockTransactionError<BlockchainErrorT, StateErrorT>),
 }
 
 /// Result of a `debug_traceTransaction` call.
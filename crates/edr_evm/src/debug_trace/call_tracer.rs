@@ -0,0 +1,500 @@
+use edr_eth::{Address, Bytes, B256, U256};
+use revm::{
+    interpreter::{return_ok, CallInputs, CallScheme, CreateInputs, Gas, InstructionResult},
+    primitives::{CreateScheme, ExecutionResult},
+    EVMData, Inspector,
+};
+
+/// Selector of `Error(string)`
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Config options for the `callTracer`.
+#[derive(Clone, Debug, Default)]
+pub struct CallTracerConfig {
+    /// Only trace the top-level call.
+    pub only_top_call: bool,
+    /// Include the logs emitted by each call.
+    pub with_log: bool,
+}
+
+/// The type of a [`CallFrame`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CallKind {
+    /// `CALL`
+    Call,
+    /// `CALLCODE`
+    CallCode,
+    /// `DELEGATECALL`
+    DelegateCall,
+    /// `STATICCALL`
+    StaticCall,
+    /// `CREATE`
+    Create,
+    /// `CREATE2`
+    Create2,
+    /// `SELFDESTRUCT`
+    SelfDestruct,
+}
+
+/// A call frame, as returned by geth's `callTracer`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    /// The type of the call
+    #[serde(rename = "type")]
+    pub kind: CallKind,
+    /// The sender of the message
+    pub from: Address,
+    /// The gas provided to the call
+    #[serde(with = "edr_eth::serde::u64")]
+    pub gas: u64,
+    /// The gas used by the call
+    #[serde(with = "edr_eth::serde::u64")]
+    pub gas_used: u64,
+    /// The receiver of the message. `None` for failed creations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    /// The calldata or init code
+    pub input: Bytes,
+    /// The returned data or deployed code
+    #[serde(skip_serializing_if = "<[u8]>::is_empty")]
+    pub output: Bytes,
+    /// The error message, if the call failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The decoded revert reason, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    /// The nested calls
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<CallFrame>,
+    /// The logs emitted by the call
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<CallLog>,
+    /// The transferred value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<U256>,
+}
+
+impl CallFrame {
+    /// Removes the logs of failed calls, including those of their nested
+    /// calls.
+    fn clear_failed_logs(&mut self, parent_failed: bool) {
+        let failed = parent_failed || self.error.is_some();
+        if failed {
+            self.logs.clear();
+        }
+
+        for call in &mut self.calls {
+            call.clear_failed_logs(failed);
+        }
+    }
+
+    fn set_result(&mut self, ret: InstructionResult, output: Bytes) {
+        match ret {
+            return_ok!() => self.output = output,
+            InstructionResult::Revert => {
+                self.error = Some("execution reverted".to_string());
+                self.revert_reason = decode_revert_reason(&output);
+                self.output = output;
+            }
+            _ => self.error = Some(halt_message(ret)),
+        }
+
+        if self.error.is_some() && matches!(self.kind, CallKind::Create | CallKind::Create2) {
+            self.to = None;
+        }
+    }
+}
+
+/// A log emitted by a call, as returned by geth's `callTracer`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct CallLog {
+    /// The address of the contract that emitted the log
+    pub address: Address,
+    /// The topics of the log
+    pub topics: Vec<B256>,
+    /// The data of the log
+    pub data: Bytes,
+    /// The number of nested calls that preceded the log in its frame
+    #[serde(with = "edr_eth::serde::u64")]
+    pub position: u64,
+}
+
+/// Tracer that collects the tree of call frames of a transaction, like geth's
+/// `callTracer`.
+#[derive(Debug)]
+pub struct CallTracer {
+    config: CallTracerConfig,
+    /// Frames that have been entered but not exited yet
+    stack: Vec<CallFrame>,
+    /// The number of nested frames that are skipped due to `only_top_call`
+    skipped_depth: usize,
+    root: Option<CallFrame>,
+}
+
+impl CallTracer {
+    /// Creates a new tracer.
+    pub fn new(config: CallTracerConfig) -> Self {
+        Self {
+            config,
+            stack: Vec::new(),
+            skipped_depth: 0,
+            root: None,
+        }
+    }
+
+    /// Converts the tracer into the top-level call frame of the transaction
+    /// with the provided result.
+    pub fn into_call_frame(self, result: &ExecutionResult) -> Option<CallFrame> {
+        let mut root = self.root?;
+        root.gas_used = result.gas_used();
+        root.clear_failed_logs(false);
+
+        Some(root)
+    }
+
+    /// Whether the frame that is about to be entered should be skipped.
+    fn skip_frame(&mut self) -> bool {
+        if self.config.only_top_call && (!self.stack.is_empty() || self.root.is_some()) {
+            self.skipped_depth += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn enter_frame(&mut self, frame: CallFrame) {
+        self.stack.push(frame);
+    }
+
+    fn exit_frame(&mut self, gas: &Gas, ret: InstructionResult, output: Bytes) {
+        if self.skipped_depth > 0 {
+            self.skipped_depth -= 1;
+            return;
+        }
+
+        if let Some(mut frame) = self.stack.pop() {
+            frame.set_result(ret, output);
+
+            if let Some(parent) = self.stack.last_mut() {
+                frame.gas_used = gas.spend();
+                parent.calls.push(frame);
+            } else {
+                // The gas used by the top-level frame is determined by the transaction
+                self.root = Some(frame);
+            }
+        }
+    }
+}
+
+impl<DatabaseErrorT> Inspector<DatabaseErrorT> for CallTracer {
+    fn log(
+        &mut self,
+        _evm_data: &mut EVMData<'_, DatabaseErrorT>,
+        address: &Address,
+        topics: &[B256],
+        data: &Bytes,
+    ) {
+        if !self.config.with_log || self.skipped_depth > 0 {
+            return;
+        }
+
+        if let Some(frame) = self.stack.last_mut() {
+            frame.logs.push(CallLog {
+                address: *address,
+                topics: topics.to_vec(),
+                data: data.clone(),
+                position: frame.calls.len() as u64,
+            });
+        }
+    }
+
+    fn call(
+        &mut self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        if !self.skip_frame() {
+            let (kind, from, value) = match inputs.context.scheme {
+                CallScheme::Call => (
+                    CallKind::Call,
+                    inputs.context.caller,
+                    Some(inputs.transfer.value),
+                ),
+                CallScheme::CallCode => (
+                    CallKind::CallCode,
+                    inputs.context.caller,
+                    Some(inputs.transfer.value),
+                ),
+                CallScheme::DelegateCall => (CallKind::DelegateCall, inputs.context.address, None),
+                CallScheme::StaticCall => (CallKind::StaticCall, inputs.context.caller, None),
+            };
+
+            let gas = if self.stack.is_empty() {
+                data.env.tx.gas_limit
+            } else {
+                inputs.gas_limit
+            };
+
+            self.enter_frame(CallFrame {
+                kind,
+                from,
+                gas,
+                gas_used: 0,
+                to: Some(inputs.context.code_address),
+                input: inputs.input.clone(),
+                output: Bytes::new(),
+                error: None,
+                revert_reason: None,
+                calls: Vec::new(),
+                logs: Vec::new(),
+                value,
+            });
+        }
+
+        (InstructionResult::Continue, Gas::new(0), Bytes::new())
+    }
+
+    fn call_end(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        _inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.exit_frame(&remaining_gas, ret, out.clone());
+
+        (ret, remaining_gas, out)
+    }
+
+    fn create(
+        &mut self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        if !self.skip_frame() {
+            let kind = match inputs.scheme {
+                CreateScheme::Create => CallKind::Create,
+                CreateScheme::Create2 { .. } => CallKind::Create2,
+            };
+
+            let gas = if self.stack.is_empty() {
+                data.env.tx.gas_limit
+            } else {
+                inputs.gas_limit
+            };
+
+            self.enter_frame(CallFrame {
+                kind,
+                from: inputs.caller,
+                gas,
+                gas_used: 0,
+                to: None,
+                input: inputs.init_code.clone(),
+                output: Bytes::new(),
+                error: None,
+                revert_reason: None,
+                calls: Vec::new(),
+                logs: Vec::new(),
+                value: Some(inputs.value),
+            });
+        }
+
+        (
+            InstructionResult::Continue,
+            None,
+            Gas::new(0),
+            Bytes::default(),
+        )
+    }
+
+    fn create_end(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        _inputs: &CreateInputs,
+        ret: InstructionResult,
+        address: Option<Address>,
+        remaining_gas: Gas,
+        out: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        // The address needs to be known before the result is applied, as failed
+        // creations don't have a receiver.
+        if self.skipped_depth == 0 {
+            if let Some(frame) = self.stack.last_mut() {
+                frame.to = address;
+            }
+        }
+
+        self.exit_frame(&remaining_gas, ret, out.clone());
+
+        (ret, address, remaining_gas, out)
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        if self.config.only_top_call || self.skipped_depth > 0 {
+            return;
+        }
+
+        if let Some(frame) = self.stack.last_mut() {
+            frame.calls.push(CallFrame {
+                kind: CallKind::SelfDestruct,
+                from: contract,
+                gas: 0,
+                gas_used: 0,
+                to: Some(target),
+                input: Bytes::new(),
+                output: Bytes::new(),
+                error: None,
+                revert_reason: None,
+                calls: Vec::new(),
+                logs: Vec::new(),
+                value: Some(value),
+            });
+        }
+    }
+}
+
+/// Decodes the reason of an `Error(string)` revert.
+fn decode_revert_reason(output: &[u8]) -> Option<String> {
+    let data = output.strip_prefix(ERROR_SELECTOR.as_slice())?;
+
+    let offset = usize::try_from(U256::try_from_be_slice(data.get(..32)?)?).ok()?;
+    let length_end = offset.checked_add(32)?;
+    let length = usize::try_from(U256::try_from_be_slice(data.get(offset..length_end)?)?).ok()?;
+    let reason = data.get(length_end..length_end.checked_add(length)?)?;
+
+    String::from_utf8(reason.to_vec()).ok()
+}
+
+/// Formats the error message of a halted call, matching geth.
+fn halt_message(ret: InstructionResult) -> String {
+    match ret {
+        InstructionResult::OutOfGas
+        | InstructionResult::MemoryOOG
+        | InstructionResult::MemoryLimitOOG
+        | InstructionResult::PrecompileOOG
+        | InstructionResult::InvalidOperandOOG => "out of gas".to_string(),
+        InstructionResult::OpcodeNotFound | InstructionResult::InvalidFEOpcode => {
+            "invalid opcode".to_string()
+        }
+        InstructionResult::InvalidJump => "invalid jump destination".to_string(),
+        InstructionResult::StackUnderflow => "stack underflow".to_string(),
+        InstructionResult::StackOverflow => "stack limit reached".to_string(),
+        InstructionResult::CallTooDeep => "max call depth exceeded".to_string(),
+        InstructionResult::OutOfFund => "insufficient balance for transfer".to_string(),
+        InstructionResult::StateChangeDuringStaticCall => "write protection".to_string(),
+        InstructionResult::CreateCollision => "contract address collision".to_string(),
+        InstructionResult::CreateContractSizeLimit => "max code size exceeded".to_string(),
+        ret => format!("{ret:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use revm::primitives::hex;
+
+    use super::*;
+
+    fn frame(kind: CallKind) -> CallFrame {
+        CallFrame {
+            kind,
+            from: Address::repeat_byte(1),
+            gas: 100_000,
+            gas_used: 21_000,
+            to: Some(Address::repeat_byte(2)),
+            input: Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef]),
+            output: Bytes::new(),
+            error: None,
+            revert_reason: None,
+            calls: Vec::new(),
+            logs: Vec::new(),
+            value: None,
+        }
+    }
+
+    #[test]
+    fn serialize_call_frame() {
+        let mut call_frame = frame(CallKind::Call);
+        call_frame.value = Some(U256::from(10));
+        call_frame.calls.push(frame(CallKind::StaticCall));
+
+        assert_eq!(
+            serde_json::to_value(call_frame).unwrap(),
+            serde_json::json!({
+                "type": "CALL",
+                "from": "0x0101010101010101010101010101010101010101",
+                "gas": "0x186a0",
+                "gasUsed": "0x5208",
+                "to": "0x0202020202020202020202020202020202020202",
+                "input": "0xdeadbeef",
+                "calls": [{
+                    "type": "STATICCALL",
+                    "from": "0x0101010101010101010101010101010101010101",
+                    "gas": "0x186a0",
+                    "gasUsed": "0x5208",
+                    "to": "0x0202020202020202020202020202020202020202",
+                    "input": "0xdeadbeef",
+                }],
+                "value": "0xa",
+            })
+        );
+    }
+
+    #[test]
+    fn revert_sets_reason() {
+        // Error("Not enough Ether provided.")
+        let output = Bytes::from(
+            hex::decode(concat!(
+                "08c379a0",
+                "0000000000000000000000000000000000000000000000000000000000000020",
+                "000000000000000000000000000000000000000000000000000000000000001a",
+                "4e6f7420656e6f7567682045746865722070726f76696465642e000000000000"
+            ))
+            .unwrap(),
+        );
+
+        let mut call_frame = frame(CallKind::Create);
+        call_frame.set_result(InstructionResult::Revert, output.clone());
+
+        assert_eq!(call_frame.error.as_deref(), Some("execution reverted"));
+        assert_eq!(
+            call_frame.revert_reason.as_deref(),
+            Some("Not enough Ether provided.")
+        );
+        assert_eq!(call_frame.output, output);
+        assert_eq!(call_frame.to, None);
+    }
+
+    #[test]
+    fn failed_calls_have_no_logs() {
+        let log = CallLog {
+            address: Address::repeat_byte(2),
+            topics: Vec::new(),
+            data: Bytes::new(),
+            position: 0,
+        };
+
+        let mut child = frame(CallKind::Call);
+        child.logs.push(log.clone());
+
+        let mut reverted = frame(CallKind::Call);
+        reverted.error = Some("execution reverted".to_string());
+        reverted.logs.push(log.clone());
+        reverted.calls.push(child.clone());
+
+        let mut root = frame(CallKind::Call);
+        root.logs.push(log);
+        root.calls.push(child);
+        root.calls.push(reverted);
+
+        root.clear_failed_logs(false);
+
+        assert_eq!(root.logs.len(), 1);
+        assert_eq!(root.calls[0].logs.len(), 1);
+        assert!(root.calls[1].logs.is_empty());
+        assert!(root.calls[1].calls[0].logs.is_empty());
+    }
+}
@@ -0,0 +1,44 @@
+use std::collections::BTreeMap;
+
+use edr_eth::Bytes;
+use revm::{
+    interpreter::{CallInputs, Gas, InstructionResult},
+    primitives::hex,
+    EVMData, Inspector,
+};
+
+/// Tracer that counts the function selectors and calldata sizes of all calls,
+/// like geth's `4byteTracer`.
+#[derive(Debug, Default)]
+pub struct FourByteTracer {
+    selectors: BTreeMap<String, u64>,
+}
+
+impl FourByteTracer {
+    /// Converts the tracer into a map from `<selector>-<calldata size>` to the
+    /// number of calls.
+    pub fn into_selectors(self) -> BTreeMap<String, u64> {
+        self.selectors
+    }
+}
+
+impl<DatabaseErrorT> Inspector<DatabaseErrorT> for FourByteTracer {
+    fn call(
+        &mut self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        // Precompiles don't have function selectors
+        if inputs.input.len() >= 4 && !data.precompiles.contains(&inputs.contract) {
+            let key = format!(
+                "0x{}-{}",
+                hex::encode(&inputs.input[..4]),
+                inputs.input.len() - 4
+            );
+
+            *self.selectors.entry(key).or_default() += 1;
+        }
+
+        (InstructionResult::Continue, Gas::new(0), Bytes::new())
+    }
+}
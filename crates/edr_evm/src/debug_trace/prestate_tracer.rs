@@ -0,0 +1,303 @@
+use std::collections::BTreeMap;
+
+use edr_eth::{Address, Bytes, B256, U256};
+use revm::{
+    db::StateRef,
+    primitives::{Account, AccountInfo, Bytecode, HashMap, KECCAK_EMPTY},
+};
+
+/// Config options for the `prestateTracer`.
+#[derive(Clone, Debug, Default)]
+pub struct PrestateTracerConfig {
+    /// Return the differences between the state before and after the
+    /// transaction, instead of the state before the transaction.
+    pub diff_mode: bool,
+}
+
+/// The state of an account, as returned by geth's `prestateTracer`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+pub struct PrestateAccount {
+    /// The balance of the account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    /// The code of the account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// The nonce of the account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    /// The accessed storage slots of the account
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<B256, B256>,
+}
+
+/// The output of geth's `prestateTracer`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(untagged)]
+pub enum PrestateTrace {
+    /// The state of all accessed accounts before the transaction
+    Prestate(BTreeMap<Address, PrestateAccount>),
+    /// The modified accounts before and after the transaction
+    Diff {
+        /// The modified fields and storage slots before the transaction
+        pre: BTreeMap<Address, PrestateAccount>,
+        /// The modified fields and storage slots after the transaction
+        post: BTreeMap<Address, PrestateAccount>,
+    },
+}
+
+/// Computes the `prestateTracer` output of a transaction from the state before
+/// the transaction and the transaction's state changes.
+pub fn prestate_trace<StateT>(
+    config: &PrestateTracerConfig,
+    state: &StateT,
+    changes: &HashMap<Address, Account>,
+) -> Result<PrestateTrace, StateT::Error>
+where
+    StateT: StateRef + ?Sized,
+{
+    if config.diff_mode {
+        diff_trace(state, changes)
+    } else {
+        changes
+            .iter()
+            .map(|(address, account)| {
+                let info = state.basic(*address)?.unwrap_or_default();
+                Ok((*address, pre_account(state, info, account)?))
+            })
+            .collect::<Result<_, _>>()
+            .map(PrestateTrace::Prestate)
+    }
+}
+
+fn diff_trace<StateT>(
+    state: &StateT,
+    changes: &HashMap<Address, Account>,
+) -> Result<PrestateTrace, StateT::Error>
+where
+    StateT: StateRef + ?Sized,
+{
+    let mut pre = BTreeMap::new();
+    let mut post = BTreeMap::new();
+
+    for (address, account) in changes {
+        let info = state.basic(*address)?;
+        let existed = info.is_some();
+        let info = info.unwrap_or_default();
+
+        let mut pre_account = pre_account(state, info.clone(), account)?;
+
+        // Destructed accounts only have a pre-state
+        if account.is_selfdestructed() {
+            if existed {
+                pre.insert(*address, pre_account);
+            }
+            continue;
+        }
+
+        let mut post_account = PrestateAccount::default();
+        let mut modified = false;
+
+        if account.info.balance != info.balance {
+            modified = true;
+            post_account.balance = Some(account.info.balance);
+        }
+
+        if account.info.nonce != info.nonce {
+            modified = true;
+            post_account.nonce = Some(account.info.nonce);
+        }
+
+        if account.info.code_hash != info.code_hash {
+            modified = true;
+            post_account.code = account.info.code.as_ref().map(Bytecode::original_bytes);
+        }
+
+        for (index, slot) in &account.storage {
+            if slot.original_value == slot.present_value {
+                pre_account.storage.remove(&u256_to_b256(index));
+            } else {
+                modified = true;
+                if slot.present_value != U256::ZERO {
+                    post_account
+                        .storage
+                        .insert(u256_to_b256(index), u256_to_b256(&slot.present_value));
+                }
+            }
+        }
+
+        if modified {
+            // Created accounts didn't have a pre-state
+            if existed && !account.is_created() {
+                pre.insert(*address, pre_account);
+            }
+            post.insert(*address, post_account);
+        }
+    }
+
+    Ok(PrestateTrace::Diff { pre, post })
+}
+
+/// Constructs the state of an account before the transaction, including the
+/// storage slots that the transaction accessed.
+fn pre_account<StateT>(
+    state: &StateT,
+    info: AccountInfo,
+    account: &Account,
+) -> Result<PrestateAccount, StateT::Error>
+where
+    StateT: StateRef + ?Sized,
+{
+    let code = if info.code_hash == KECCAK_EMPTY {
+        None
+    } else {
+        let code = match info.code {
+            Some(code) => code,
+            None => state.code_by_hash(info.code_hash)?,
+        };
+
+        Some(code.original_bytes())
+    };
+
+    let storage = account
+        .storage
+        .iter()
+        .map(|(index, slot)| (u256_to_b256(index), u256_to_b256(&slot.original_value)))
+        .collect();
+
+    Ok(PrestateAccount {
+        balance: Some(info.balance),
+        code,
+        nonce: (info.nonce != 0).then_some(info.nonce),
+        storage,
+    })
+}
+
+fn u256_to_b256(value: &U256) -> B256 {
+    B256::from(value.to_be_bytes::<32>())
+}
+
+#[cfg(test)]
+mod tests {
+    use revm::primitives::{AccountStatus, StorageSlot};
+
+    use super::*;
+    use crate::state::{StateDebug, TrieState};
+
+    const SLOT: U256 = U256::from_limbs([1, 0, 0, 0]);
+
+    fn state_with_account(address: Address) -> TrieState {
+        let mut state = TrieState::default();
+        state
+            .insert_account(
+                address,
+                AccountInfo {
+                    balance: U256::from(100),
+                    nonce: 1,
+                    code: None,
+                    code_hash: KECCAK_EMPTY,
+                },
+            )
+            .unwrap();
+        state
+            .set_account_storage_slot(address, SLOT, U256::from(5))
+            .unwrap();
+
+        state
+    }
+
+    fn changed_account(balance: u64, slot: StorageSlot) -> Account {
+        Account {
+            info: AccountInfo {
+                balance: U256::from(balance),
+                nonce: 1,
+                code: None,
+                code_hash: KECCAK_EMPTY,
+            },
+            storage: [(SLOT, slot)].into_iter().collect(),
+            status: AccountStatus::Touched,
+        }
+    }
+
+    #[test]
+    fn prestate() -> anyhow::Result<()> {
+        let address = Address::repeat_byte(1);
+        let state = state_with_account(address);
+
+        let changes = [(
+            address,
+            changed_account(90, StorageSlot::new_changed(U256::from(5), U256::from(6))),
+        )]
+        .into_iter()
+        .collect();
+
+        let trace = prestate_trace(&PrestateTracerConfig::default(), &state, &changes)?;
+
+        assert_eq!(
+            serde_json::to_value(trace)?,
+            serde_json::json!({
+                "0x0101010101010101010101010101010101010101": {
+                    "balance": "0x64",
+                    "nonce": 1,
+                    "storage": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000001":
+                            "0x0000000000000000000000000000000000000000000000000000000000000005",
+                    },
+                },
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn diff_mode_omits_unchanged_fields() -> anyhow::Result<()> {
+        let modified = Address::repeat_byte(1);
+        let unmodified = Address::repeat_byte(2);
+
+        let mut state = state_with_account(modified);
+        state.insert_account(unmodified, AccountInfo::default())?;
+
+        let changes = [
+            (
+                modified,
+                changed_account(90, StorageSlot::new_changed(U256::from(5), U256::ZERO)),
+            ),
+            (
+                unmodified,
+                Account {
+                    info: AccountInfo::default(),
+                    storage: HashMap::new(),
+                    status: AccountStatus::Touched,
+                },
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        let trace = prestate_trace(&PrestateTracerConfig { diff_mode: true }, &state, &changes)?;
+
+        assert_eq!(
+            serde_json::to_value(trace)?,
+            serde_json::json!({
+                "pre": {
+                    "0x0101010101010101010101010101010101010101": {
+                        "balance": "0x64",
+                        "nonce": 1,
+                        "storage": {
+                            "0x0000000000000000000000000000000000000000000000000000000000000001":
+                                "0x0000000000000000000000000000000000000000000000000000000000000005",
+                        },
+                    },
+                },
+                "post": {
+                    "0x0101010101010101010101010101010101010101": {
+                        "balance": "0x5a",
+                    },
+                },
+            })
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,579 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+};
+
+use edr_eth::{spec::OptimismHardfork, B256};
+use revm::{
+    interpreter::{
+        instruction_result::SuccessOrHalt, opcode, CallInputs, CallScheme, CreateInputs, Gas,
+        InstructionResult, Interpreter,
+    },
+    primitives::{hex, Address, BlockEnv, Bytes, CfgEnv, CreateScheme},
+    EVMData, Inspector,
+};
+
+use crate::{
+    blockchain::SyncBlockchain, debug_trace::replay_transaction, state::SyncState, DebugTraceError,
+    ExecutableTransaction,
+};
+
+/// Replays the transaction with the provided hash and profiles its gas usage,
+/// for `hardhat_getGasProfile`.
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn gas_profile_transaction<BlockchainErrorT, StateErrorT>(
+    blockchain: &dyn SyncBlockchain<BlockchainErrorT, StateErrorT>,
+    // Take ownership of the state so that we can apply throw-away modifications on it
+    state: Box<dyn SyncState<StateErrorT>>,
+    evm_config: CfgEnv,
+    block_env: BlockEnv,
+    parent_beacon_block_root: Option<B256>,
+    transactions: Vec<ExecutableTransaction>,
+    transaction_hash: &B256,
+    optimism_hardfork: Option<OptimismHardfork>,
+) -> Result<GasProfile, DebugTraceError<BlockchainErrorT, StateErrorT>>
+where
+    BlockchainErrorT: Debug + Send,
+    StateErrorT: Debug + Send,
+{
+    let mut tracer = GasProfileTracer::default();
+    let (result, _state) = replay_transaction(
+        blockchain,
+        state,
+        evm_config,
+        block_env,
+        parent_beacon_block_root,
+        transactions,
+        transaction_hash,
+        optimism_hardfork,
+        Some(&mut tracer),
+    )?;
+
+    Ok(tracer.into_profile(result.result.gas_used()))
+}
+
+/// The class of an opcode, used to group the gas used by the instructions of
+/// a call frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OpcodeClass {
+    /// Arithmetic, comparison and bitwise operations
+    Arithmetic,
+    /// Calls and contract creations
+    Call,
+    /// Jumps and instructions that halt execution
+    Control,
+    /// Copies of calldata, code or return data into memory
+    Copy,
+    /// Access to the execution environment, block and accounts
+    Environment,
+    /// Keccak-256 hashing
+    Hash,
+    /// Emission of logs
+    Log,
+    /// Memory access
+    Memory,
+    /// Stack manipulation
+    Stack,
+    /// Access to persistent and transient storage
+    Storage,
+    /// Unknown opcodes
+    Other,
+}
+
+impl OpcodeClass {
+    /// Determines the class of an opcode.
+    pub fn from_opcode(opcode: u8) -> Self {
+        match opcode {
+            opcode::CALLDATACOPY
+            | opcode::CODECOPY
+            | opcode::EXTCODECOPY
+            | opcode::RETURNDATACOPY
+            | opcode::MCOPY => Self::Copy,
+            // ADD..=SIGNEXTEND, LT..=SAR
+            0x01..=0x0b | 0x10..=0x1d => Self::Arithmetic,
+            opcode::KECCAK256 => Self::Hash,
+            // ADDRESS..=BLOBBASEFEE
+            0x30..=0x4a => Self::Environment,
+            // POP, PUSH0..=PUSH32, DUP1..=DUP16, SWAP1..=SWAP16
+            opcode::POP | 0x5f..=0x9f => Self::Stack,
+            opcode::MLOAD | opcode::MSTORE | opcode::MSTORE8 | opcode::MSIZE => Self::Memory,
+            opcode::SLOAD | opcode::SSTORE | opcode::TLOAD | opcode::TSTORE => Self::Storage,
+            opcode::STOP
+            | opcode::JUMP
+            | opcode::JUMPI
+            | opcode::PC
+            | opcode::GAS
+            | opcode::JUMPDEST
+            | opcode::RETURN
+            | opcode::REVERT
+            | opcode::INVALID
+            | opcode::SELFDESTRUCT => Self::Control,
+            // LOG0..=LOG4
+            0xa0..=0xa4 => Self::Log,
+            opcode::CREATE
+            | opcode::CALL
+            | opcode::CALLCODE
+            | opcode::DELEGATECALL
+            | opcode::CREATE2
+            | opcode::STATICCALL => Self::Call,
+            _ => Self::Other,
+        }
+    }
+
+    /// The name of the class.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Arithmetic => "arithmetic",
+            Self::Call => "call",
+            Self::Control => "control",
+            Self::Copy => "copy",
+            Self::Environment => "environment",
+            Self::Hash => "hash",
+            Self::Log => "log",
+            Self::Memory => "memory",
+            Self::Stack => "stack",
+            Self::Storage => "storage",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// The gas usage of a transaction, as a tree of call frames.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasProfile {
+    /// The gas used by the transaction, after refunds
+    pub gas_used: u64,
+    /// The intrinsic gas of the transaction
+    pub intrinsic_gas: u64,
+    /// The gas that was refunded at the end of the transaction
+    pub gas_refunded: u64,
+    /// The outermost call frame, if the transaction was executed
+    pub root: Option<GasProfileFrame>,
+}
+
+impl GasProfile {
+    /// Formats the profile as folded stacks, as used by Brendan Gregg's
+    /// `flamegraph.pl`. Refunds aren't included.
+    pub fn to_folded_stacks(&self) -> String {
+        self.stacks()
+            .into_iter()
+            .map(|(stack, gas)| format!("{} {gas}\n", stack.join(";")))
+            .collect()
+    }
+
+    /// Formats the profile as a sampled profile in speedscope's file format,
+    /// with gas as the weight of samples. Refunds aren't included.
+    pub fn to_speedscope(&self, name: &str) -> serde_json::Value {
+        let mut frames = Vec::new();
+        let mut frame_indices = HashMap::new();
+
+        let mut samples = Vec::new();
+        let mut weights = Vec::new();
+        for (stack, gas) in self.stacks() {
+            let sample = stack
+                .into_iter()
+                .map(|frame| {
+                    *frame_indices.entry(frame).or_insert_with(|| {
+                        frames.push(serde_json::json!({ "name": frame }));
+                        frames.len() - 1
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            samples.push(sample);
+            weights.push(gas);
+        }
+
+        serde_json::json!({
+            "$schema": "https://www.speedscope.app/file-format-schema.json",
+            "shared": { "frames": frames },
+            "profiles": [{
+                "type": "sampled",
+                "name": name,
+                "unit": "none",
+                "startValue": 0,
+                "endValue": weights.iter().sum::<u64>(),
+                "samples": samples,
+                "weights": weights,
+            }],
+            "name": name,
+            "activeProfileIndex": 0,
+            "exporter": "edr",
+        })
+    }
+
+    /// The stacks of frame names with their gas, in depth-first order.
+    fn stacks(&self) -> Vec<(Vec<&str>, u64)> {
+        let mut stacks = Vec::new();
+        if self.intrinsic_gas > 0 {
+            stacks.push((vec!["intrinsic"], self.intrinsic_gas));
+        }
+
+        if let Some(root) = &self.root {
+            root.add_stacks(&mut Vec::new(), &mut stacks);
+        }
+
+        stacks
+    }
+}
+
+/// The gas usage of a call or create frame.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasProfileFrame {
+    /// The label of the frame, consisting of the call scheme, the code
+    /// address and the function selector
+    pub name: String,
+    /// The gas used by the frame, including nested frames
+    pub inclusive_gas: u64,
+    /// The gas used by the frame, excluding nested frames
+    pub exclusive_gas: u64,
+    /// The exclusive gas used per opcode class. Gas that isn't charged by an
+    /// instruction, like the cost of precompiles or code deposits, is only
+    /// part of the exclusive gas.
+    pub opcode_gas: BTreeMap<OpcodeClass, u64>,
+    /// The frames started by the frame
+    pub children: Vec<GasProfileFrame>,
+}
+
+impl GasProfileFrame {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            inclusive_gas: 0,
+            exclusive_gas: 0,
+            opcode_gas: BTreeMap::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn add_stacks<'frame>(
+        &'frame self,
+        path: &mut Vec<&'frame str>,
+        stacks: &mut Vec<(Vec<&'frame str>, u64)>,
+    ) {
+        path.push(&self.name);
+
+        let unattributed_gas = self
+            .exclusive_gas
+            .saturating_sub(self.opcode_gas.values().sum());
+
+        if unattributed_gas > 0 {
+            stacks.push((path.clone(), unattributed_gas));
+        }
+
+        for (class, gas) in &self.opcode_gas {
+            let mut stack = path.clone();
+            stack.push(class.as_str());
+            stacks.push((stack, *gas));
+        }
+
+        for child in &self.children {
+            child.add_stacks(path, stacks);
+        }
+
+        path.pop();
+    }
+}
+
+/// Inspector that attributes the gas used by a transaction to its call frames
+/// and opcode classes.
+#[derive(Debug, Default)]
+pub struct GasProfileTracer {
+    intrinsic_gas: u64,
+    /// Frames that have been entered but not exited yet
+    stack: Vec<PendingFrame>,
+    root: Option<GasProfileFrame>,
+}
+
+#[derive(Debug)]
+struct PendingFrame {
+    frame: GasProfileFrame,
+    /// The gas used by exited nested frames
+    children_gas: u64,
+    /// The instruction that is being executed
+    pending: Option<PendingInstruction>,
+}
+
+#[derive(Debug)]
+struct PendingInstruction {
+    opcode: u8,
+    gas_remaining: u64,
+    children_gas: u64,
+}
+
+impl GasProfileTracer {
+    /// Converts the tracer into the gas profile of the transaction, using
+    /// the gas used by the transaction.
+    pub fn into_profile(self, gas_used: u64) -> GasProfile {
+        let execution_gas = self.root.as_ref().map_or(0, |root| root.inclusive_gas);
+
+        GasProfile {
+            gas_used,
+            intrinsic_gas: self.intrinsic_gas,
+            gas_refunded: (self.intrinsic_gas + execution_gas).saturating_sub(gas_used),
+            root: self.root,
+        }
+    }
+
+    fn enter_frame(&mut self, name: String, transaction_gas_limit: u64, gas_limit: u64) {
+        if self.stack.is_empty() {
+            // The outermost frame receives the gas that remains after paying the
+            // intrinsic gas
+            self.intrinsic_gas = transaction_gas_limit.saturating_sub(gas_limit);
+        }
+
+        self.stack.push(PendingFrame {
+            frame: GasProfileFrame::new(name),
+            children_gas: 0,
+            pending: None,
+        });
+    }
+
+    fn exit_frame(&mut self, remaining_gas: &Gas, ret: InstructionResult) {
+        let Some(PendingFrame {
+            mut frame,
+            children_gas,
+            ..
+        }) = self.stack.pop()
+        else {
+            return;
+        };
+
+        // Exceptional halts consume all gas of the frame, unless the frame
+        // couldn't be started
+        frame.inclusive_gas = match SuccessOrHalt::from(ret) {
+            SuccessOrHalt::Halt(_)
+                if !matches!(
+                    ret,
+                    InstructionResult::CallTooDeep | InstructionResult::OutOfFund
+                ) =>
+            {
+                remaining_gas.limit()
+            }
+            _ => remaining_gas.spend(),
+        };
+        frame.exclusive_gas = frame.inclusive_gas.saturating_sub(children_gas);
+
+        if let Some(parent) = self.stack.last_mut() {
+            parent.children_gas += frame.inclusive_gas;
+            parent.frame.children.push(frame);
+        } else {
+            self.root = Some(frame);
+        }
+    }
+}
+
+impl<DatabaseErrorT> Inspector<DatabaseErrorT> for GasProfileTracer {
+    fn step(
+        &mut self,
+        interp: &mut Interpreter,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+    ) -> InstructionResult {
+        if let Some(frame) = self.stack.last_mut() {
+            frame.pending = Some(PendingInstruction {
+                opcode: interp.current_opcode(),
+                gas_remaining: interp.gas.remaining(),
+                children_gas: frame.children_gas,
+            });
+        }
+
+        InstructionResult::Continue
+    }
+
+    fn step_end(
+        &mut self,
+        interp: &mut Interpreter,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        _eval: InstructionResult,
+    ) -> InstructionResult {
+        let Some(frame) = self.stack.last_mut() else {
+            return InstructionResult::Continue;
+        };
+
+        let Some(pending) = frame.pending.take() else {
+            return InstructionResult::Continue;
+        };
+
+        // The gas used by a frame started by the instruction is attributed to
+        // that frame
+        let cost = pending
+            .gas_remaining
+            .saturating_sub(interp.gas.remaining())
+            .saturating_sub(frame.children_gas - pending.children_gas);
+
+        *frame
+            .frame
+            .opcode_gas
+            .entry(OpcodeClass::from_opcode(pending.opcode))
+            .or_default() += cost;
+
+        InstructionResult::Continue
+    }
+
+    fn call(
+        &mut self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        let scheme = match inputs.context.scheme {
+            CallScheme::Call => "CALL",
+            CallScheme::CallCode => "CALLCODE",
+            CallScheme::DelegateCall => "DELEGATECALL",
+            CallScheme::StaticCall => "STATICCALL",
+        };
+
+        let function = inputs.input.get(..4).map_or_else(
+            || "fallback".to_string(),
+            |selector| format!("0x{}", hex::encode(selector)),
+        );
+
+        self.enter_frame(
+            format!("{scheme} {}::{function}", inputs.contract),
+            data.env.tx.gas_limit,
+            inputs.gas_limit,
+        );
+
+        (InstructionResult::Continue, Gas::new(0), Bytes::new())
+    }
+
+    fn call_end(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        _inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.exit_frame(&remaining_gas, ret);
+
+        (ret, remaining_gas, out)
+    }
+
+    fn create(
+        &mut self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        let scheme = match inputs.scheme {
+            CreateScheme::Create => "CREATE",
+            CreateScheme::Create2 { .. } => "CREATE2",
+        };
+
+        self.enter_frame(scheme.to_string(), data.env.tx.gas_limit, inputs.gas_limit);
+
+        (
+            InstructionResult::Continue,
+            None,
+            Gas::new(0),
+            Bytes::default(),
+        )
+    }
+
+    fn create_end(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        _inputs: &CreateInputs,
+        ret: InstructionResult,
+        address: Option<Address>,
+        remaining_gas: Gas,
+        out: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        if let (Some(frame), Some(address)) = (self.stack.last_mut(), address) {
+            frame.frame.name = format!("{} {address}", frame.frame.name);
+        }
+
+        self.exit_frame(&remaining_gas, ret);
+
+        (ret, address, remaining_gas, out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> GasProfile {
+        let mut child = GasProfileFrame::new("STATICCALL 0x02::0x70a08231".to_string());
+        child.inclusive_gas = 2_600;
+        child.exclusive_gas = 2_600;
+        child.opcode_gas.insert(OpcodeClass::Storage, 2_100);
+        child.opcode_gas.insert(OpcodeClass::Stack, 500);
+
+        let mut root = GasProfileFrame::new("CALL 0x01::0xa9059cbb".to_string());
+        root.inclusive_gas = 3_000;
+        root.exclusive_gas = 400;
+        root.opcode_gas.insert(OpcodeClass::Call, 300);
+        root.children.push(child);
+
+        GasProfile {
+            gas_used: 24_000,
+            intrinsic_gas: 21_000,
+            gas_refunded: 0,
+            root: Some(root),
+        }
+    }
+
+    #[test]
+    fn opcode_classes() {
+        assert_eq!(
+            OpcodeClass::from_opcode(opcode::ADD),
+            OpcodeClass::Arithmetic
+        );
+        assert_eq!(
+            OpcodeClass::from_opcode(opcode::SSTORE),
+            OpcodeClass::Storage
+        );
+        assert_eq!(OpcodeClass::from_opcode(opcode::PUSH32), OpcodeClass::Stack);
+        assert_eq!(
+            OpcodeClass::from_opcode(opcode::CODECOPY),
+            OpcodeClass::Copy
+        );
+        assert_eq!(
+            OpcodeClass::from_opcode(opcode::CALLER),
+            OpcodeClass::Environment
+        );
+        assert_eq!(OpcodeClass::from_opcode(opcode::LOG2), OpcodeClass::Log);
+        assert_eq!(
+            OpcodeClass::from_opcode(opcode::DELEGATECALL),
+            OpcodeClass::Call
+        );
+        assert_eq!(OpcodeClass::from_opcode(0x0c), OpcodeClass::Other);
+    }
+
+    #[test]
+    fn folded_stacks() {
+        assert_eq!(
+            profile().to_folded_stacks(),
+            "intrinsic 21000
+CALL 0x01::0xa9059cbb 100
+CALL 0x01::0xa9059cbb;call 300
+CALL 0x01::0xa9059cbb;STATICCALL 0x02::0x70a08231;stack 500
+CALL 0x01::0xa9059cbb;STATICCALL 0x02::0x70a08231;storage 2100
+"
+        );
+    }
+
+    #[test]
+    fn speedscope() {
+        let speedscope = profile().to_speedscope("transfer");
+
+        let frames = speedscope["shared"]["frames"]
+            .as_array()
+            .expect("frames are an array");
+        assert_eq!(frames.len(), 6);
+        assert_eq!(frames[0]["name"], "intrinsic");
+
+        let profile = &speedscope["profiles"][0];
+        assert_eq!(profile["type"], "sampled");
+        assert_eq!(profile["endValue"], 24_000);
+        assert_eq!(profile["samples"][3], serde_json::json!([1, 3, 4]));
+        assert_eq!(
+            profile["weights"],
+            serde_json::json!([21_000, 100, 300, 500, 2_100])
+        );
+    }
+}
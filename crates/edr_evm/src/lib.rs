@@ -15,18 +15,34 @@ pub use revm::{
 };
 
 pub use crate::{
+    access_list::AccessListTracer,
     block::*,
+    cheatcodes::{CheatcodeInspector, CHEATCODE_ADDRESS},
     debug_trace::{
-        debug_trace_transaction, execution_result_to_debug_result, DebugTraceConfig,
-        DebugTraceError, DebugTraceLogItem, DebugTraceResult, TracerEip3155,
+        debug_trace_block, debug_trace_transaction, execution_result_to_debug_result,
+        prestate_trace, CallFrame, CallKind, CallLog, CallTracer, CallTracerConfig, DebugInspector,
+        DebugTraceBlockResult, DebugTraceConfig, DebugTraceError, DebugTraceLogItem,
+        DebugTraceOutput, DebugTraceResult, DebugTracer, FourByteTracer, NoopFrame,
+        PrestateAccount, PrestateTrace, PrestateTracerConfig, TracerEip3155,
     },
     evm::SyncInspector,
+    gas_profile::{
+        gas_profile_transaction, GasProfile, GasProfileFrame, GasProfileTracer, OpcodeClass,
+    },
     inspector::*,
     mempool::{MemPool, MemPoolAddTransactionError, OrderedTransaction},
     miner::*,
+    parity_trace::{
+        parity_trace_block, parity_trace_transaction, state_diff, AccountDiff, Action, CallAction,
+        CallOutput, CallType, ChangedValue, CreateAction, CreateOutput, Delta,
+        LocalizedTransactionTrace, MemoryDelta, ParityTraceConfig, ParityTracer,
+        SelfDestructAction, StorageDelta, TraceOutput, TraceResults, TransactionTrace,
+        TransactionTracer, VmExecutedOperation, VmInstruction, VmTrace, VmTracer,
+    },
     random::RandomHashGenerator,
     runtime::{dry_run, guaranteed_dry_run, run, SyncDatabase},
     transaction::*,
+    transfer_log::{TransferLogTracer, TRANSFER_EVENT_TOPIC, TRANSFER_LOG_ADDRESS},
 };
 
 /// Types for managing Ethereum blockchain
@@ -38,20 +54,29 @@ pub mod state;
 /// Types used for tracing EVM calls
 pub mod trace;
 
+mod access_list;
+/// The EIP-4788 beacon roots contract
+pub mod beacon_roots;
 mod block;
+mod cheatcodes;
 pub(crate) mod collections;
 mod debug_trace;
 pub(crate) mod evm;
+mod gas_profile;
 mod inspector;
 /// Types for managing Ethereum mem pool
 pub mod mempool;
 mod miner;
+/// Execution rules of OP-stack chains
+pub mod optimism;
+mod parity_trace;
 pub(crate) mod random;
 mod runtime;
 /// Utilities for testing
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
 mod transaction;
+mod transfer_log;
 
 /// Types for managing Ethereum precompiles
 pub mod precompile {
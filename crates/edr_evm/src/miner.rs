@@ -1,6 +1,6 @@
 use std::{cmp::Ordering, fmt::Debug, sync::Arc};
 
-use edr_eth::{block::BlockOptions, U256};
+use edr_eth::{block::BlockOptions, spec::OptimismHardfork, U256};
 use revm::primitives::{CfgEnv, ExecutionResult, InvalidTransaction};
 
 use crate::{
@@ -94,6 +94,7 @@ pub fn mine_block<BlockchainErrorT, StateErrorT>(
     mine_ordering: MineOrdering,
     reward: U256,
     dao_hardfork_activation_block: Option<u64>,
+    optimism_hardfork: Option<OptimismHardfork>,
     inspector: Option<&mut dyn SyncInspector<BlockchainErrorT, StateErrorT>>,
 ) -> Result<MineBlockResultAndState<StateErrorT>, MineBlockError<BlockchainErrorT, StateErrorT>>
 where
@@ -111,8 +112,11 @@ where
         parent_header,
         options,
         dao_hardfork_activation_block,
+        optimism_hardfork,
     )?;
 
+    block_builder.apply_beacon_root_contract_call(blockchain, &mut state)?;
+
     let mut pending_transactions = {
         type MineOrderComparator =
             dyn Fn(&OrderedTransaction, &OrderedTransaction) -> Ordering + Send;
@@ -147,8 +151,10 @@ where
         ) {
             Err(
                 BlockTransactionError::ExceedsBlockGasLimit
+                | BlockTransactionError::ExceedsBlockBlobGasLimit
                 | BlockTransactionError::InvalidTransaction(
-                    InvalidTransaction::GasPriceLessThanBasefee,
+                    InvalidTransaction::GasPriceLessThanBasefee
+                    | InvalidTransaction::BlobGasPriceGreaterThanMax,
                 ),
             ) => {
                 pending_transactions.remove_caller(&caller);
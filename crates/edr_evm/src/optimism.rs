@@ -0,0 +1,460 @@
+use std::fmt::Debug;
+
+use edr_eth::{
+    receipt::L1Fee,
+    spec::OptimismHardfork,
+    transaction::{DepositTransaction, SignedTransaction},
+    Address, Bytes, U256,
+};
+use revm::primitives::{
+    Account, AccountInfo, AccountStatus, ExecutionResult, HashMap, ResultAndState,
+};
+
+use crate::{
+    state::{AccountModifierFn, SyncState},
+    ExecutableTransaction,
+};
+
+/// The address of the `L1Block` predeploy, which stores the L1 fee parameters.
+pub const L1_BLOCK_ADDRESS: Address = Address::new([
+    0x42, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x15,
+]);
+
+/// The address of the vault that collects the base fees of L2 transactions.
+pub const BASE_FEE_VAULT_ADDRESS: Address = Address::new([
+    0x42, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x19,
+]);
+
+/// The address of the vault that collects the L1 data fees of L2 transactions.
+pub const L1_FEE_VAULT_ADDRESS: Address = Address::new([
+    0x42, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x1a,
+]);
+
+const L1_BASE_FEE_SLOT: u64 = 1;
+const ECOTONE_SCALARS_SLOT: u64 = 3;
+const L1_FEE_OVERHEAD_SLOT: u64 = 5;
+const L1_FEE_SCALAR_SLOT: u64 = 6;
+const L1_BLOB_BASE_FEE_SLOT: u64 = 7;
+
+/// Denominator of the fee scalars
+const FEE_SCALAR_DECIMALS: u64 = 1_000_000;
+
+/// Gas that was charged for the signature of a transaction before Regolith
+const PRE_REGOLITH_SIGNATURE_GAS: u64 = 68 * 16;
+
+/// An error that occurs while applying OP-stack specific state changes.
+#[derive(Debug, thiserror::Error)]
+pub enum OptimismError<StateErrorT> {
+    /// The sender can't pay the L1 data fee.
+    #[error("Sender doesn't have enough funds to pay the L1 data fee. The L1 fee is: {l1_fee} and the sender's balance is: {sender_balance}.")]
+    InsufficientFundsForL1Fee {
+        /// The L1 data fee of the transaction
+        l1_fee: U256,
+        /// The sender's balance
+        sender_balance: U256,
+    },
+    /// State error
+    #[error(transparent)]
+    State(StateErrorT),
+}
+
+/// The L1 fee parameters stored in the `L1Block` predeploy.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct L1BlockInfo {
+    /// The L1 base fee
+    pub base_fee: U256,
+    /// The fee overhead. Only used before Ecotone.
+    pub fee_overhead: U256,
+    /// The fee scalar. Only used before Ecotone.
+    pub fee_scalar: U256,
+    /// The base fee scalar. Only used since Ecotone.
+    pub base_fee_scalar: U256,
+    /// The L1 blob base fee. Only used since Ecotone.
+    pub blob_base_fee: U256,
+    /// The blob base fee scalar. Only used since Ecotone.
+    pub blob_base_fee_scalar: U256,
+}
+
+impl L1BlockInfo {
+    /// Reads the L1 fee parameters from the `L1Block` predeploy in the
+    /// provided state.
+    pub fn from_state<StateErrorT>(
+        state: &dyn SyncState<StateErrorT>,
+        hardfork: OptimismHardfork,
+    ) -> Result<Self, StateErrorT>
+    where
+        StateErrorT: Debug + Send,
+    {
+        let slot = |index: u64| state.storage(L1_BLOCK_ADDRESS, U256::from(index));
+
+        let mut info = Self {
+            base_fee: slot(L1_BASE_FEE_SLOT)?,
+            fee_overhead: slot(L1_FEE_OVERHEAD_SLOT)?,
+            fee_scalar: slot(L1_FEE_SCALAR_SLOT)?,
+            ..Self::default()
+        };
+
+        if hardfork >= OptimismHardfork::Ecotone {
+            let scalars = slot(ECOTONE_SCALARS_SLOT)?.to_be_bytes::<32>();
+
+            info.base_fee_scalar = U256::from_be_slice(&scalars[16..20]);
+            info.blob_base_fee_scalar = U256::from_be_slice(&scalars[20..24]);
+            info.blob_base_fee = slot(L1_BLOB_BASE_FEE_SLOT)?;
+        }
+
+        Ok(info)
+    }
+
+    /// Computes the L1 data fee of the provided transaction.
+    pub fn l1_fee(&self, hardfork: OptimismHardfork, transaction: &SignedTransaction) -> L1Fee {
+        let encoded = alloy_rlp::encode(transaction);
+
+        let mut data_gas = encoded
+            .iter()
+            .fold(0u64, |gas, byte| gas + if *byte == 0 { 4 } else { 16 });
+
+        if hardfork < OptimismHardfork::Regolith {
+            data_gas += PRE_REGOLITH_SIGNATURE_GAS;
+        }
+
+        let data_gas = U256::from(data_gas);
+
+        // The `L1Block` predeploy is only upgraded by the deposits of the Ecotone
+        // activation block, so the Bedrock formula applies until its scalars are set.
+        let is_ecotone = hardfork >= OptimismHardfork::Ecotone
+            && (self.base_fee_scalar != U256::ZERO || self.blob_base_fee_scalar != U256::ZERO);
+
+        if is_ecotone {
+            let calldata_cost_per_byte = self.base_fee * U256::from(16) * self.base_fee_scalar;
+            let blob_cost_per_byte = self.blob_base_fee * self.blob_base_fee_scalar;
+
+            L1Fee {
+                l1_gas_price: self.base_fee,
+                l1_gas_used: data_gas,
+                l1_fee: (calldata_cost_per_byte + blob_cost_per_byte) * data_gas
+                    / U256::from(16 * FEE_SCALAR_DECIMALS),
+                l1_fee_scalar: None,
+                l1_base_fee_scalar: Some(self.base_fee_scalar),
+                l1_blob_base_fee: Some(self.blob_base_fee),
+                l1_blob_base_fee_scalar: Some(self.blob_base_fee_scalar),
+            }
+        } else {
+            let l1_gas_used = data_gas + self.fee_overhead;
+
+            L1Fee {
+                l1_gas_price: self.base_fee,
+                l1_gas_used,
+                l1_fee: l1_gas_used * self.base_fee * self.fee_scalar
+                    / U256::from(FEE_SCALAR_DECIMALS),
+                l1_fee_scalar: Some(format_fee_scalar(self.fee_scalar)),
+                l1_base_fee_scalar: None,
+                l1_blob_base_fee: None,
+                l1_blob_base_fee_scalar: None,
+            }
+        }
+    }
+}
+
+/// The result of applying the state changes that precede the execution of a
+/// transaction on an OP-stack chain.
+#[derive(Debug)]
+pub struct PreExecutionChanges {
+    /// The accounts that were modified
+    pub changed_accounts: Vec<(Address, AccountInfo)>,
+    /// The L1 data fee charged to the sender. `None` for deposit transactions.
+    pub l1_fee: Option<L1Fee>,
+}
+
+/// Applies the state changes that precede the execution of a transaction on an
+/// OP-stack chain: the ETH minted by a deposit transaction is credited to its
+/// sender; the L1 data fee of any other transaction is deducted from the
+/// sender and credited to the L1 fee vault.
+pub fn apply_pre_execution_changes<StateErrorT>(
+    state: &mut dyn SyncState<StateErrorT>,
+    hardfork: OptimismHardfork,
+    transaction: &ExecutableTransaction,
+) -> Result<PreExecutionChanges, OptimismError<StateErrorT>>
+where
+    StateErrorT: Debug + Send,
+{
+    if let Some(deposit) = transaction.as_deposit() {
+        let changed_accounts = if deposit.mint > U256::ZERO {
+            let account_info = increase_balance(state, deposit.from, deposit.mint)
+                .map_err(OptimismError::State)?;
+
+            vec![(deposit.from, account_info)]
+        } else {
+            Vec::new()
+        };
+
+        return Ok(PreExecutionChanges {
+            changed_accounts,
+            l1_fee: None,
+        });
+    }
+
+    let l1_fee = L1BlockInfo::from_state(state, hardfork)
+        .map_err(OptimismError::State)?
+        .l1_fee(hardfork, transaction);
+
+    let caller = *transaction.caller();
+    let sender_balance = state
+        .basic(caller)
+        .map_err(OptimismError::State)?
+        .map_or(U256::ZERO, |info| info.balance);
+
+    if sender_balance < l1_fee.l1_fee {
+        return Err(OptimismError::InsufficientFundsForL1Fee {
+            l1_fee: l1_fee.l1_fee,
+            sender_balance,
+        });
+    }
+
+    let sender_info =
+        decrease_balance(state, caller, l1_fee.l1_fee).map_err(OptimismError::State)?;
+    let vault_info = increase_balance(state, L1_FEE_VAULT_ADDRESS, l1_fee.l1_fee)
+        .map_err(OptimismError::State)?;
+
+    Ok(PreExecutionChanges {
+        changed_accounts: vec![(caller, sender_info), (L1_FEE_VAULT_ADDRESS, vault_info)],
+        l1_fee: Some(l1_fee),
+    })
+}
+
+/// Undoes the changes of [`apply_pre_execution_changes`], for a transaction
+/// that turned out to be invalid. Invalid deposits are included regardless,
+/// so their changes are retained; see [`failed_deposit_result`].
+pub fn revert_pre_execution_changes<StateErrorT>(
+    state: &mut dyn SyncState<StateErrorT>,
+    transaction: &ExecutableTransaction,
+    changes: &PreExecutionChanges,
+) -> Result<(), StateErrorT>
+where
+    StateErrorT: Debug + Send,
+{
+    if let Some(l1_fee) = &changes.l1_fee {
+        increase_balance(state, *transaction.caller(), l1_fee.l1_fee)?;
+        decrease_balance(state, L1_FEE_VAULT_ADDRESS, l1_fee.l1_fee)?;
+    }
+
+    Ok(())
+}
+
+/// Constructs the result of a deposit transaction that failed to execute, e.g.
+/// because its value exceeds its sender's balance. Unlike other transactions,
+/// failed deposits are included in the block: the ETH they minted is retained
+/// and their sender's nonce is incremented, but all other changes are
+/// discarded.
+pub fn failed_deposit_result<StateErrorT>(
+    state: &dyn SyncState<StateErrorT>,
+    hardfork: OptimismHardfork,
+    deposit: &DepositTransaction,
+) -> Result<ResultAndState, StateErrorT>
+where
+    StateErrorT: Debug + Send,
+{
+    let mut sender_info = state.basic(deposit.from)?.unwrap_or_default();
+    sender_info.nonce += 1;
+
+    // Before Regolith, system transactions didn't use any gas
+    let gas_used = if hardfork >= OptimismHardfork::Regolith || !deposit.is_system_tx {
+        deposit.gas_limit
+    } else {
+        0
+    };
+
+    let sender = Account {
+        info: sender_info,
+        storage: HashMap::new(),
+        status: AccountStatus::Touched,
+    };
+
+    Ok(ResultAndState {
+        result: ExecutionResult::Revert {
+            gas_used,
+            output: Bytes::new(),
+        },
+        state: [(deposit.from, sender)].into_iter().collect(),
+    })
+}
+
+/// Credits the base fee of an executed transaction to the base fee vault, as
+/// OP-stack chains don't burn it. Returns the modified vault account, if any.
+pub fn collect_base_fee<StateErrorT>(
+    state: &mut dyn SyncState<StateErrorT>,
+    transaction: &ExecutableTransaction,
+    base_fee: U256,
+    gas_used: u64,
+) -> Result<Option<(Address, AccountInfo)>, StateErrorT>
+where
+    StateErrorT: Debug + Send,
+{
+    let fee = base_fee * U256::from(gas_used);
+    if transaction.is_deposit() || fee == U256::ZERO {
+        return Ok(None);
+    }
+
+    let account_info = increase_balance(state, BASE_FEE_VAULT_ADDRESS, fee)?;
+    Ok(Some((BASE_FEE_VAULT_ADDRESS, account_info)))
+}
+
+fn increase_balance<StateErrorT>(
+    state: &mut dyn SyncState<StateErrorT>,
+    address: Address,
+    amount: U256,
+) -> Result<AccountInfo, StateErrorT>
+where
+    StateErrorT: Debug + Send,
+{
+    modify_balance(state, address, move |balance| *balance += amount)
+}
+
+fn decrease_balance<StateErrorT>(
+    state: &mut dyn SyncState<StateErrorT>,
+    address: Address,
+    amount: U256,
+) -> Result<AccountInfo, StateErrorT>
+where
+    StateErrorT: Debug + Send,
+{
+    modify_balance(state, address, move |balance| *balance -= amount)
+}
+
+fn modify_balance<StateErrorT>(
+    state: &mut dyn SyncState<StateErrorT>,
+    address: Address,
+    modifier: impl Fn(&mut U256) + Send + 'static,
+) -> Result<AccountInfo, StateErrorT>
+where
+    StateErrorT: Debug + Send,
+{
+    state.modify_account(
+        address,
+        AccountModifierFn::new(Box::new(move |balance, _nonce, _code| modifier(balance))),
+        &|| {
+            Ok(AccountInfo {
+                code: None,
+                ..AccountInfo::default()
+            })
+        },
+    )
+}
+
+/// Formats a fee scalar as a decimal, the way OP-stack nodes report it.
+fn format_fee_scalar(scalar: U256) -> String {
+    let decimals = U256::from(FEE_SCALAR_DECIMALS);
+    let integer = scalar / decimals;
+    let fraction = scalar % decimals;
+
+    if fraction == U256::ZERO {
+        integer.to_string()
+    } else {
+        let fraction = format!("{:06}", fraction.to::<u64>());
+        format!("{integer}.{}", fraction.trim_end_matches('0'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use edr_eth::{
+        transaction::{Eip1559TransactionRequest, TransactionKind},
+        Bytes,
+    };
+
+    use super::*;
+
+    fn dummy_transaction() -> SignedTransaction {
+        let caller = Address::random();
+
+        Eip1559TransactionRequest {
+            chain_id: 10,
+            nonce: 0,
+            max_priority_fee_per_gas: U256::from(1),
+            max_fee_per_gas: U256::from(2),
+            gas_limit: 21_000,
+            kind: TransactionKind::Call(Address::random()),
+            value: U256::ZERO,
+            input: Bytes::from_static(&[0x00, 0x01]),
+            access_list: Vec::new(),
+        }
+        .fake_sign(&caller)
+        .into()
+    }
+
+    fn data_gas(transaction: &SignedTransaction) -> U256 {
+        let encoded = alloy_rlp::encode(transaction);
+        U256::from(
+            encoded
+                .iter()
+                .map(|byte| if *byte == 0 { 4u64 } else { 16 })
+                .sum::<u64>(),
+        )
+    }
+
+    #[test]
+    fn bedrock_l1_fee() {
+        let transaction = dummy_transaction();
+        let info = L1BlockInfo {
+            base_fee: U256::from(1_000),
+            fee_overhead: U256::from(188),
+            fee_scalar: U256::from(684_000),
+            ..L1BlockInfo::default()
+        };
+
+        let l1_fee = info.l1_fee(OptimismHardfork::Regolith, &transaction);
+
+        let l1_gas_used = data_gas(&transaction) + U256::from(188);
+        assert_eq!(l1_fee.l1_gas_used, l1_gas_used);
+        assert_eq!(
+            l1_fee.l1_fee,
+            l1_gas_used * U256::from(1_000) * U256::from(684_000) / U256::from(1_000_000)
+        );
+        assert_eq!(l1_fee.l1_fee_scalar.as_deref(), Some("0.684"));
+
+        let pre_regolith = info.l1_fee(OptimismHardfork::Bedrock, &transaction);
+        assert_eq!(
+            pre_regolith.l1_gas_used,
+            l1_gas_used + U256::from(PRE_REGOLITH_SIGNATURE_GAS)
+        );
+    }
+
+    #[test]
+    fn ecotone_l1_fee() {
+        let transaction = dummy_transaction();
+        let info = L1BlockInfo {
+            base_fee: U256::from(1_000),
+            base_fee_scalar: U256::from(1_368),
+            blob_base_fee: U256::from(1),
+            blob_base_fee_scalar: U256::from(810_949),
+            ..L1BlockInfo::default()
+        };
+
+        let l1_fee = info.l1_fee(OptimismHardfork::Ecotone, &transaction);
+
+        let data_gas = data_gas(&transaction);
+        assert_eq!(l1_fee.l1_gas_used, data_gas);
+        assert_eq!(
+            l1_fee.l1_fee,
+            (U256::from(16 * 1_000 * 1_368) + U256::from(810_949)) * data_gas
+                / U256::from(16_000_000)
+        );
+        assert_eq!(l1_fee.l1_fee_scalar, None);
+    }
+
+    #[test]
+    fn ecotone_before_l1_block_upgrade() {
+        let transaction = dummy_transaction();
+        let info = L1BlockInfo {
+            base_fee: U256::from(1_000),
+            fee_overhead: U256::from(188),
+            fee_scalar: U256::from(1_000_000),
+            ..L1BlockInfo::default()
+        };
+
+        let l1_fee = info.l1_fee(OptimismHardfork::Ecotone, &transaction);
+        assert_eq!(l1_fee.l1_fee_scalar.as_deref(), Some("1"));
+    }
+}
@@ -0,0 +1,322 @@
+mod state_diff;
+mod transaction_trace;
+mod vm_trace;
+
+use std::{collections::BTreeMap, fmt::Debug};
+
+use edr_eth::{spec::OptimismHardfork, Address, Bytes, B256, U256};
+use revm::{
+    db::StateRef,
+    interpreter::{CallInputs, CreateInputs, Gas, InstructionResult, Interpreter},
+    primitives::{BlockEnv, CfgEnv, ResultAndState},
+    EVMData, Inspector,
+};
+
+pub use self::{
+    state_diff::{state_diff, AccountDiff, ChangedValue, Delta},
+    transaction_trace::{
+        Action, CallAction, CallOutput, CallType, CreateAction, CreateOutput,
+        LocalizedTransactionTrace, SelfDestructAction, TraceOutput, TransactionTrace,
+        TransactionTracer,
+    },
+    vm_trace::{MemoryDelta, StorageDelta, VmExecutedOperation, VmInstruction, VmTrace, VmTracer},
+};
+use crate::{
+    blockchain::SyncBlockchain,
+    debug_trace::{
+        apply_beacon_root_contract_call, failed_deposit_result, prepare_transaction,
+        replay_transaction, validate_trace_env,
+    },
+    evm::{build_evm, run_transaction},
+    optimism,
+    state::SyncState,
+    DebugTraceError, ExecutableTransaction, TransactionError,
+};
+
+/// Get the traces of a transaction for `trace_replayTransaction` and
+/// `trace_transaction`.
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn parity_trace_transaction<BlockchainErrorT, StateErrorT>(
+    blockchain: &dyn SyncBlockchain<BlockchainErrorT, StateErrorT>,
+    // Take ownership of the state so that we can apply throw-away modifications on it
+    state: Box<dyn SyncState<StateErrorT>>,
+    evm_config: CfgEnv,
+    trace_config: ParityTraceConfig,
+    block_env: BlockEnv,
+    parent_beacon_block_root: Option<B256>,
+    transactions: Vec<ExecutableTransaction>,
+    transaction_hash: &B256,
+    optimism_hardfork: Option<OptimismHardfork>,
+) -> Result<TraceResults, DebugTraceError<BlockchainErrorT, StateErrorT>>
+where
+    BlockchainErrorT: Debug + Send,
+    StateErrorT: Debug + Send,
+{
+    let mut tracer = ParityTracer::new(trace_config);
+    let (result, state) = replay_transaction(
+        blockchain,
+        state,
+        evm_config,
+        block_env,
+        parent_beacon_block_root,
+        transactions,
+        transaction_hash,
+        optimism_hardfork,
+        Some(&mut tracer),
+    )?;
+
+    tracer
+        .into_results(&result, &*state)
+        .map_err(|error| TransactionError::State(error).into())
+}
+
+/// Get the traces of all transactions in a block for `trace_block` and
+/// `trace_filter`.
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn parity_trace_block<BlockchainErrorT, StateErrorT>(
+    blockchain: &dyn SyncBlockchain<BlockchainErrorT, StateErrorT>,
+    // Take ownership of the state so that we can apply throw-away modifications on it
+    mut state: Box<dyn SyncState<StateErrorT>>,
+    evm_config: CfgEnv,
+    trace_config: ParityTraceConfig,
+    block_env: BlockEnv,
+    parent_beacon_block_root: Option<B256>,
+    transactions: Vec<ExecutableTransaction>,
+    optimism_hardfork: Option<OptimismHardfork>,
+) -> Result<Vec<TraceResults>, DebugTraceError<BlockchainErrorT, StateErrorT>>
+where
+    BlockchainErrorT: Debug + Send,
+    StateErrorT: Debug + Send,
+{
+    validate_trace_env(&evm_config, &block_env)?;
+    apply_beacon_root_contract_call(
+        blockchain,
+        &mut *state,
+        &evm_config,
+        &block_env,
+        parent_beacon_block_root,
+    )?;
+
+    let mut results = Vec::with_capacity(transactions.len());
+    for transaction in transactions {
+        let transaction_config =
+            prepare_transaction(&mut *state, &evm_config, optimism_hardfork, &transaction)?;
+
+        let evm = build_evm(
+            blockchain,
+            &state,
+            transaction_config,
+            transaction.clone().into(),
+            block_env.clone(),
+        );
+
+        let mut tracer = ParityTracer::new(trace_config.clone());
+        let result = run_transaction(evm, Some(&mut tracer)).or_else(|error| {
+            failed_deposit_result(&*state, optimism_hardfork, &transaction, error)
+        })?;
+
+        results.push(
+            tracer
+                .into_results(&result, &*state)
+                .map_err(TransactionError::State)?,
+        );
+
+        let gas_used = result.result.gas_used();
+        state.commit(result.state);
+
+        if optimism_hardfork.is_some() {
+            optimism::collect_base_fee(&mut *state, &transaction, block_env.basefee, gas_used)
+                .map_err(TransactionError::State)?;
+        }
+    }
+
+    Ok(results)
+}
+
+/// The traces that are collected by a [`ParityTracer`].
+#[derive(Clone, Debug, Default)]
+pub struct ParityTraceConfig {
+    /// Collect the flat list of actions
+    pub trace: bool,
+    /// Collect the trace of the executed instructions
+    pub vm_trace: bool,
+    /// Collect the changes to the state
+    pub state_diff: bool,
+}
+
+/// The traces of a transaction, as returned by OpenEthereum's
+/// `trace_replayTransaction`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceResults {
+    /// The output of the transaction
+    pub output: Bytes,
+    /// The changes to the state, if requested
+    pub state_diff: Option<BTreeMap<Address, AccountDiff>>,
+    /// The flat list of actions, if requested
+    pub trace: Vec<TransactionTrace>,
+    /// The trace of the executed instructions, if requested
+    pub vm_trace: Option<VmTrace>,
+}
+
+/// Inspector that collects the traces selected in a [`ParityTraceConfig`].
+#[derive(Debug)]
+pub struct ParityTracer {
+    state_diff: bool,
+    transaction_tracer: Option<TransactionTracer>,
+    vm_tracer: Option<VmTracer>,
+}
+
+impl ParityTracer {
+    /// Creates a new tracer.
+    pub fn new(config: ParityTraceConfig) -> Self {
+        Self {
+            state_diff: config.state_diff,
+            transaction_tracer: config.trace.then(TransactionTracer::default),
+            vm_tracer: config.vm_trace.then(VmTracer::default),
+        }
+    }
+
+    /// Converts the collected data into the traces of the transaction, using
+    /// the state before the transaction and the result of the transaction.
+    pub fn into_results<StateT>(
+        self,
+        result: &ResultAndState,
+        state: &StateT,
+    ) -> Result<TraceResults, StateT::Error>
+    where
+        StateT: StateRef + ?Sized,
+    {
+        let state_diff = if self.state_diff {
+            Some(state_diff(state, &result.state)?)
+        } else {
+            None
+        };
+
+        Ok(TraceResults {
+            output: result.result.output().cloned().unwrap_or_default(),
+            state_diff,
+            trace: self
+                .transaction_tracer
+                .map(TransactionTracer::into_traces)
+                .unwrap_or_default(),
+            vm_trace: self.vm_tracer.and_then(VmTracer::into_vm_trace),
+        })
+    }
+}
+
+impl<DatabaseErrorT> Inspector<DatabaseErrorT> for ParityTracer {
+    fn step(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+    ) -> InstructionResult {
+        if let Some(vm_tracer) = &mut self.vm_tracer {
+            vm_tracer.step(interp, data);
+        }
+
+        InstructionResult::Continue
+    }
+
+    fn step_end(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        eval: InstructionResult,
+    ) -> InstructionResult {
+        if let Some(vm_tracer) = &mut self.vm_tracer {
+            vm_tracer.step_end(interp, data, eval);
+        }
+
+        InstructionResult::Continue
+    }
+
+    fn call(
+        &mut self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        if let Some(transaction_tracer) = &mut self.transaction_tracer {
+            transaction_tracer.call(data, inputs);
+        }
+
+        if let Some(vm_tracer) = &mut self.vm_tracer {
+            vm_tracer.call(data, inputs);
+        }
+
+        (InstructionResult::Continue, Gas::new(0), Bytes::new())
+    }
+
+    fn call_end(
+        &mut self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+    ) -> (InstructionResult, Gas, Bytes) {
+        if let Some(transaction_tracer) = &mut self.transaction_tracer {
+            transaction_tracer.call_end(data, inputs, remaining_gas, ret, out.clone());
+        }
+
+        if let Some(vm_tracer) = &mut self.vm_tracer {
+            vm_tracer.call_end(data, inputs, remaining_gas, ret, out.clone());
+        }
+
+        (ret, remaining_gas, out)
+    }
+
+    fn create(
+        &mut self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        if let Some(transaction_tracer) = &mut self.transaction_tracer {
+            transaction_tracer.create(data, inputs);
+        }
+
+        if let Some(vm_tracer) = &mut self.vm_tracer {
+            vm_tracer.create(data, inputs);
+        }
+
+        (
+            InstructionResult::Continue,
+            None,
+            Gas::new(0),
+            Bytes::default(),
+        )
+    }
+
+    fn create_end(
+        &mut self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &CreateInputs,
+        ret: InstructionResult,
+        address: Option<Address>,
+        remaining_gas: Gas,
+        out: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        if let Some(transaction_tracer) = &mut self.transaction_tracer {
+            transaction_tracer.create_end(data, inputs, ret, address, remaining_gas, out.clone());
+        }
+
+        if let Some(vm_tracer) = &mut self.vm_tracer {
+            vm_tracer.create_end(data, inputs, ret, address, remaining_gas, out.clone());
+        }
+
+        (ret, address, remaining_gas, out)
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        if let Some(transaction_tracer) = &mut self.transaction_tracer {
+            <TransactionTracer as Inspector<DatabaseErrorT>>::selfdestruct(
+                transaction_tracer,
+                contract,
+                target,
+                value,
+            );
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::BTreeMap;
+
+use edr_eth::{Address, Bytes, B256, U256, U64};
+use revm::{
+    db::StateRef,
+    primitives::{Account, AccountInfo, HashMap, KECCAK_EMPTY},
+};
+
+/// The change of a value, as returned by OpenEthereum's `stateDiff`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub enum Delta<T> {
+    /// The value didn't change
+    #[serde(rename = "=")]
+    Unchanged,
+    /// The value was created
+    #[serde(rename = "+")]
+    Added(T),
+    /// The value was removed
+    #[serde(rename = "-")]
+    Removed(T),
+    /// The value was modified
+    #[serde(rename = "*")]
+    Changed(ChangedValue<T>),
+}
+
+impl<T: PartialEq> Delta<T> {
+    fn new(from: T, to: T) -> Self {
+        if from == to {
+            Self::Unchanged
+        } else {
+            Self::Changed(ChangedValue { from, to })
+        }
+    }
+
+    fn is_unchanged(&self) -> bool {
+        matches!(self, Self::Unchanged)
+    }
+}
+
+/// The previous and new value of a modified value.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct ChangedValue<T> {
+    /// The previous value
+    pub from: T,
+    /// The new value
+    pub to: T,
+}
+
+/// The changes to an account.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct AccountDiff {
+    /// The change of the balance
+    pub balance: Delta<U256>,
+    /// The change of the code
+    pub code: Delta<Bytes>,
+    /// The change of the nonce
+    pub nonce: Delta<U64>,
+    /// The changes of the modified storage slots
+    pub storage: BTreeMap<B256, Delta<B256>>,
+}
+
+/// Computes the `stateDiff` of a transaction from the state before the
+/// transaction and the transaction's state changes.
+pub fn state_diff<StateT>(
+    state: &StateT,
+    changes: &HashMap<Address, Account>,
+) -> Result<BTreeMap<Address, AccountDiff>, StateT::Error>
+where
+    StateT: StateRef + ?Sized,
+{
+    let mut diff = BTreeMap::new();
+
+    for (address, account) in changes {
+        let pre = state.basic(*address)?.filter(|info| !info.is_empty());
+
+        let exists = !account.is_selfdestructed() && !account.info.is_empty();
+
+        let account_diff = match (pre, exists) {
+            (None, false) => continue,
+            (None, true) => AccountDiff {
+                balance: Delta::Added(account.info.balance),
+                code: Delta::Added(code_of(state, &account.info)?),
+                nonce: Delta::Added(U64::from(account.info.nonce)),
+                storage: account
+                    .storage
+                    .iter()
+                    .filter(|(_, slot)| slot.present_value != U256::ZERO)
+                    .map(|(index, slot)| {
+                        (
+                            B256::from(*index),
+                            Delta::Added(B256::from(slot.present_value)),
+                        )
+                    })
+                    .collect(),
+            },
+            (Some(pre), false) => AccountDiff {
+                balance: Delta::Removed(pre.balance),
+                code: Delta::Removed(code_of(state, &pre)?),
+                nonce: Delta::Removed(U64::from(pre.nonce)),
+                storage: account
+                    .storage
+                    .iter()
+                    .filter(|(_, slot)| slot.original_value != U256::ZERO)
+                    .map(|(index, slot)| {
+                        (
+                            B256::from(*index),
+                            Delta::Removed(B256::from(slot.original_value)),
+                        )
+                    })
+                    .collect(),
+            },
+            (Some(pre), true) => {
+                let code = if pre.code_hash == account.info.code_hash {
+                    Delta::Unchanged
+                } else {
+                    Delta::new(code_of(state, &pre)?, code_of(state, &account.info)?)
+                };
+
+                let account_diff = AccountDiff {
+                    balance: Delta::new(pre.balance, account.info.balance),
+                    code,
+                    nonce: Delta::new(U64::from(pre.nonce), U64::from(account.info.nonce)),
+                    storage: account
+                        .storage
+                        .iter()
+                        .filter(|(_, slot)| slot.original_value != slot.present_value)
+                        .map(|(index, slot)| {
+                            (
+                                B256::from(*index),
+                                Delta::new(
+                                    B256::from(slot.original_value),
+                                    B256::from(slot.present_value),
+                                ),
+                            )
+                        })
+                        .collect(),
+                };
+
+                if account_diff.balance.is_unchanged()
+                    && account_diff.code.is_unchanged()
+                    && account_diff.nonce.is_unchanged()
+                    && account_diff.storage.is_empty()
+                {
+                    continue;
+                }
+
+                account_diff
+            }
+        };
+
+        diff.insert(*address, account_diff);
+    }
+
+    Ok(diff)
+}
+
+fn code_of<StateT>(state: &StateT, info: &AccountInfo) -> Result<Bytes, StateT::Error>
+where
+    StateT: StateRef + ?Sized,
+{
+    if info.code_hash == KECCAK_EMPTY {
+        return Ok(Bytes::new());
+    }
+
+    let code = match &info.code {
+        Some(code) => code.clone(),
+        None => state.code_by_hash(info.code_hash)?,
+    };
+
+    Ok(code.original_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use revm::primitives::{AccountStatus, StorageSlot};
+
+    use super::*;
+    use crate::state::{StateDebug, TrieState};
+
+    #[test]
+    fn modified_and_created_accounts() -> anyhow::Result<()> {
+        let modified = Address::repeat_byte(1);
+        let created = Address::repeat_byte(2);
+        let slot = U256::from(1);
+
+        let mut state = TrieState::default();
+        state.insert_account(
+            modified,
+            AccountInfo {
+                balance: U256::from(100),
+                nonce: 1,
+                code: None,
+                code_hash: KECCAK_EMPTY,
+            },
+        )?;
+        state.set_account_storage_slot(modified, slot, U256::from(5))?;
+
+        let changes = [
+            (
+                modified,
+                Account {
+                    info: AccountInfo {
+                        balance: U256::from(90),
+                        nonce: 1,
+                        code: None,
+                        code_hash: KECCAK_EMPTY,
+                    },
+                    storage: [(slot, StorageSlot::new_changed(U256::from(5), U256::from(6)))]
+                        .into_iter()
+                        .collect(),
+                    status: AccountStatus::Touched,
+                },
+            ),
+            (
+                created,
+                Account {
+                    info: AccountInfo {
+                        balance: U256::from(10),
+                        nonce: 0,
+                        code: None,
+                        code_hash: KECCAK_EMPTY,
+                    },
+                    storage: HashMap::new(),
+                    status: AccountStatus::Touched,
+                },
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        let diff = state_diff(&state, &changes)?;
+
+        assert_eq!(
+            serde_json::to_value(diff)?,
+            serde_json::json!({
+                "0x0101010101010101010101010101010101010101": {
+                    "balance": { "*": { "from": "0x64", "to": "0x5a" } },
+                    "code": "=",
+                    "nonce": "=",
+                    "storage": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000001": {
+                            "*": {
+                                "from": "0x0000000000000000000000000000000000000000000000000000000000000005",
+                                "to": "0x0000000000000000000000000000000000000000000000000000000000000006",
+                            },
+                        },
+                    },
+                },
+                "0x0202020202020202020202020202020202020202": {
+                    "balance": { "+": "0xa" },
+                    "code": { "+": "0x" },
+                    "nonce": { "+": "0x0" },
+                    "storage": {},
+                },
+            })
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,429 @@
+use edr_eth::{Address, Bytes, B256, U256};
+use revm::{
+    interpreter::{return_ok, CallInputs, CallScheme, CreateInputs, Gas, InstructionResult},
+    EVMData, Inspector,
+};
+
+/// The type of a call [`Action`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CallType {
+    /// `CALL`
+    Call,
+    /// `CALLCODE`
+    CallCode,
+    /// `DELEGATECALL`
+    DelegateCall,
+    /// `STATICCALL`
+    StaticCall,
+}
+
+/// The action of a message call.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallAction {
+    /// The sender of the message
+    pub from: Address,
+    /// The type of the call
+    pub call_type: CallType,
+    /// The gas provided to the call
+    #[serde(with = "edr_eth::serde::u64")]
+    pub gas: u64,
+    /// The calldata
+    pub input: Bytes,
+    /// The address of the executed code
+    pub to: Address,
+    /// The transferred value
+    pub value: U256,
+}
+
+/// The action of a contract creation.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAction {
+    /// The creator of the contract
+    pub from: Address,
+    /// The gas provided to the creation
+    #[serde(with = "edr_eth::serde::u64")]
+    pub gas: u64,
+    /// The init code
+    pub init: Bytes,
+    /// The endowment of the contract
+    pub value: U256,
+}
+
+/// The action of a self-destruct.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfDestructAction {
+    /// The destroyed contract
+    pub address: Address,
+    /// The balance that was transferred to the refund address
+    pub balance: U256,
+    /// The recipient of the contract's balance
+    pub refund_address: Address,
+}
+
+/// The action that was traced.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "type", content = "action", rename_all = "lowercase")]
+pub enum Action {
+    /// A message call
+    Call(CallAction),
+    /// A contract creation
+    Create(CreateAction),
+    /// A self-destruct
+    #[serde(rename = "suicide")]
+    SelfDestruct(SelfDestructAction),
+}
+
+/// The result of a successful message call.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallOutput {
+    /// The gas used by the call
+    #[serde(with = "edr_eth::serde::u64")]
+    pub gas_used: u64,
+    /// The returned data
+    pub output: Bytes,
+}
+
+/// The result of a successful contract creation.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOutput {
+    /// The address of the created contract
+    pub address: Address,
+    /// The deployed code
+    pub code: Bytes,
+    /// The gas used by the creation
+    #[serde(with = "edr_eth::serde::u64")]
+    pub gas_used: u64,
+}
+
+/// The result of a successful [`Action`].
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(untagged)]
+pub enum TraceOutput {
+    /// The result of a message call
+    Call(CallOutput),
+    /// The result of a contract creation
+    Create(CreateOutput),
+}
+
+/// A flat trace of a single action in a transaction, as returned by
+/// OpenEthereum's `trace_*` methods.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionTrace {
+    /// The traced action
+    #[serde(flatten)]
+    pub action: Action,
+    /// The error message, if the action failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The result of the action. `None` for failed actions and self-destructs.
+    pub result: Option<TraceOutput>,
+    /// The number of direct child actions
+    pub subtraces: usize,
+    /// The position of the action in the tree of actions of the transaction
+    pub trace_address: Vec<usize>,
+}
+
+/// A [`TransactionTrace`] with the location of its transaction.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalizedTransactionTrace {
+    /// The trace
+    #[serde(flatten)]
+    pub trace: TransactionTrace,
+    /// The hash of the block that contains the transaction
+    pub block_hash: B256,
+    /// The number of the block that contains the transaction
+    pub block_number: u64,
+    /// The hash of the transaction
+    pub transaction_hash: B256,
+    /// The index of the transaction in its block
+    pub transaction_position: usize,
+}
+
+/// Tracer that collects the flat list of actions of a transaction.
+#[derive(Debug, Default)]
+pub struct TransactionTracer {
+    traces: Vec<TransactionTrace>,
+    /// Indices of the traces of frames that have been entered but not exited
+    /// yet
+    stack: Vec<usize>,
+}
+
+impl TransactionTracer {
+    /// Converts the tracer into the traces of the transaction, ordered
+    /// depth-first.
+    pub fn into_traces(self) -> Vec<TransactionTrace> {
+        self.traces
+    }
+
+    fn push_trace(&mut self, action: Action) -> usize {
+        let trace_address = if let Some(parent) = self.stack.last() {
+            let parent = &mut self.traces[*parent];
+
+            let mut trace_address = parent.trace_address.clone();
+            trace_address.push(parent.subtraces);
+
+            parent.subtraces += 1;
+            trace_address
+        } else {
+            Vec::new()
+        };
+
+        self.traces.push(TransactionTrace {
+            action,
+            error: None,
+            result: None,
+            subtraces: 0,
+            trace_address,
+        });
+
+        self.traces.len() - 1
+    }
+
+    fn enter_frame(&mut self, action: Action) {
+        let index = self.push_trace(action);
+        self.stack.push(index);
+    }
+
+    fn exit_frame(
+        &mut self,
+        ret: InstructionResult,
+        gas: &Gas,
+        output: Bytes,
+        address: Option<Address>,
+    ) {
+        let Some(index) = self.stack.pop() else {
+            return;
+        };
+
+        let trace = &mut self.traces[index];
+        match ret {
+            return_ok!() => {
+                let gas_used = gas.spend();
+
+                trace.result = Some(if let Action::Create(_) = trace.action {
+                    TraceOutput::Create(CreateOutput {
+                        address: address.unwrap_or_default(),
+                        code: output,
+                        gas_used,
+                    })
+                } else {
+                    TraceOutput::Call(CallOutput { gas_used, output })
+                });
+            }
+            ret => trace.error = Some(error_message(ret)),
+        }
+    }
+}
+
+impl<DatabaseErrorT> Inspector<DatabaseErrorT> for TransactionTracer {
+    fn call(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        let (call_type, from, value) = match inputs.context.scheme {
+            CallScheme::Call => (CallType::Call, inputs.context.caller, inputs.transfer.value),
+            CallScheme::CallCode => (
+                CallType::CallCode,
+                inputs.context.caller,
+                inputs.transfer.value,
+            ),
+            CallScheme::DelegateCall => (
+                CallType::DelegateCall,
+                inputs.context.address,
+                inputs.context.apparent_value,
+            ),
+            CallScheme::StaticCall => (CallType::StaticCall, inputs.context.caller, U256::ZERO),
+        };
+
+        self.enter_frame(Action::Call(CallAction {
+            from,
+            call_type,
+            gas: inputs.gas_limit,
+            input: inputs.input.clone(),
+            to: inputs.contract,
+            value,
+        }));
+
+        (InstructionResult::Continue, Gas::new(0), Bytes::new())
+    }
+
+    fn call_end(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        _inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.exit_frame(ret, &remaining_gas, out.clone(), None);
+
+        (ret, remaining_gas, out)
+    }
+
+    fn create(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.enter_frame(Action::Create(CreateAction {
+            from: inputs.caller,
+            gas: inputs.gas_limit,
+            init: inputs.init_code.clone(),
+            value: inputs.value,
+        }));
+
+        (
+            InstructionResult::Continue,
+            None,
+            Gas::new(0),
+            Bytes::default(),
+        )
+    }
+
+    fn create_end(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        _inputs: &CreateInputs,
+        ret: InstructionResult,
+        address: Option<Address>,
+        remaining_gas: Gas,
+        out: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.exit_frame(ret, &remaining_gas, out.clone(), address);
+
+        (ret, address, remaining_gas, out)
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        self.push_trace(Action::SelfDestruct(SelfDestructAction {
+            address: contract,
+            balance: value,
+            refund_address: target,
+        }));
+    }
+}
+
+/// Formats the error message of a failed action, matching OpenEthereum.
+fn error_message(ret: InstructionResult) -> String {
+    match ret {
+        InstructionResult::Revert => "Reverted".to_string(),
+        InstructionResult::OutOfGas
+        | InstructionResult::MemoryOOG
+        | InstructionResult::MemoryLimitOOG
+        | InstructionResult::PrecompileOOG
+        | InstructionResult::InvalidOperandOOG => "Out of gas".to_string(),
+        InstructionResult::OpcodeNotFound | InstructionResult::InvalidFEOpcode => {
+            "Bad instruction".to_string()
+        }
+        InstructionResult::InvalidJump => "Bad jump destination".to_string(),
+        InstructionResult::StackUnderflow => "Stack underflow".to_string(),
+        InstructionResult::StackOverflow | InstructionResult::CallTooDeep => {
+            "Out of stack".to_string()
+        }
+        InstructionResult::StateChangeDuringStaticCall => {
+            "Mutable Call In Static Context".to_string()
+        }
+        InstructionResult::OutOfOffset => "Out of bounds".to_string(),
+        InstructionResult::PrecompileError => "Built-in failed".to_string(),
+        ret => format!("{ret:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call_action(to: Address) -> Action {
+        Action::Call(CallAction {
+            from: Address::repeat_byte(1),
+            call_type: CallType::Call,
+            gas: 100_000,
+            input: Bytes::new(),
+            to,
+            value: U256::ZERO,
+        })
+    }
+
+    #[test]
+    fn trace_addresses() {
+        let mut tracer = TransactionTracer::default();
+
+        tracer.enter_frame(call_action(Address::repeat_byte(2)));
+        tracer.enter_frame(call_action(Address::repeat_byte(3)));
+        tracer.exit_frame(InstructionResult::Revert, &Gas::new(10), Bytes::new(), None);
+        tracer.enter_frame(call_action(Address::repeat_byte(4)));
+        tracer.enter_frame(call_action(Address::repeat_byte(5)));
+        tracer.exit_frame(InstructionResult::Stop, &Gas::new(10), Bytes::new(), None);
+        tracer.exit_frame(InstructionResult::Stop, &Gas::new(10), Bytes::new(), None);
+        tracer.exit_frame(InstructionResult::Stop, &Gas::new(10), Bytes::new(), None);
+
+        let traces = tracer.into_traces();
+        let trace_addresses: Vec<_> = traces
+            .iter()
+            .map(|trace| (trace.trace_address.clone(), trace.subtraces))
+            .collect();
+
+        assert_eq!(
+            trace_addresses,
+            vec![(vec![], 2), (vec![0], 0), (vec![1], 1), (vec![1, 0], 0)]
+        );
+        assert_eq!(traces[1].error, Some("Reverted".to_string()));
+        assert!(traces[1].result.is_none());
+    }
+
+    #[test]
+    fn serialize_localized_trace() -> anyhow::Result<()> {
+        let trace = LocalizedTransactionTrace {
+            trace: TransactionTrace {
+                action: call_action(Address::repeat_byte(2)),
+                error: None,
+                result: Some(TraceOutput::Call(CallOutput {
+                    gas_used: 21_000,
+                    output: Bytes::new(),
+                })),
+                subtraces: 0,
+                trace_address: Vec::new(),
+            },
+            block_hash: B256::repeat_byte(0xaa),
+            block_number: 1,
+            transaction_hash: B256::repeat_byte(0xbb),
+            transaction_position: 0,
+        };
+
+        assert_eq!(
+            serde_json::to_value(trace)?,
+            serde_json::json!({
+                "action": {
+                    "from": "0x0101010101010101010101010101010101010101",
+                    "callType": "call",
+                    "gas": "0x186a0",
+                    "input": "0x",
+                    "to": "0x0202020202020202020202020202020202020202",
+                    "value": "0x0",
+                },
+                "type": "call",
+                "result": {
+                    "gasUsed": "0x5208",
+                    "output": "0x",
+                },
+                "subtraces": 0,
+                "traceAddress": [],
+                "blockHash": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+                "blockNumber": 1,
+                "transactionHash": "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+                "transactionPosition": 0,
+            })
+        );
+
+        Ok(())
+    }
+}
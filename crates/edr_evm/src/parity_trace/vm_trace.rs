@@ -0,0 +1,359 @@
+use edr_eth::{Bytes, U256};
+use revm::{
+    interpreter::{
+        opcode, return_ok, return_revert, CallInputs, CreateInputs, Gas, InstructionResult,
+        Interpreter,
+    },
+    primitives::Address,
+    EVMData, Inspector,
+};
+
+/// The trace of the instructions executed by a call or creation, as returned
+/// by OpenEthereum's `vmTrace`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+pub struct VmTrace {
+    /// The executed code
+    pub code: Bytes,
+    /// The executed instructions
+    pub ops: Vec<VmInstruction>,
+}
+
+/// A single executed instruction.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct VmInstruction {
+    /// The program counter
+    pub pc: usize,
+    /// The gas cost of the instruction
+    pub cost: u64,
+    /// The effects of the instruction. `None` if the instruction failed.
+    pub ex: Option<VmExecutedOperation>,
+    /// The trace of the call or creation started by the instruction, if any
+    pub sub: Option<VmTrace>,
+}
+
+/// The effects of an executed instruction.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct VmExecutedOperation {
+    /// The remaining gas after the instruction
+    pub used: u64,
+    /// The stack items that were pushed or modified by the instruction
+    pub push: Vec<U256>,
+    /// The memory that was written by the instruction, if any
+    pub mem: Option<MemoryDelta>,
+    /// The storage slot that was written by the instruction, if any
+    pub store: Option<StorageDelta>,
+}
+
+/// A write to memory.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct MemoryDelta {
+    /// The offset of the written data
+    pub off: usize,
+    /// The written data
+    pub data: Bytes,
+}
+
+/// A write to storage.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct StorageDelta {
+    /// The index of the storage slot
+    pub key: U256,
+    /// The new value of the storage slot
+    pub val: U256,
+}
+
+/// Tracer that collects the [`VmTrace`] of a transaction.
+#[derive(Debug, Default)]
+pub struct VmTracer {
+    /// Frames that have been entered but not exited yet
+    stack: Vec<VmFrame>,
+    root: Option<VmTrace>,
+}
+
+#[derive(Debug)]
+struct VmFrame {
+    trace: VmTrace,
+    /// The instruction that is being executed
+    pending: Option<PendingInstruction>,
+    /// The trace of the nested call or creation of the pending instruction
+    sub: Option<VmTrace>,
+}
+
+#[derive(Debug)]
+struct PendingInstruction {
+    pc: usize,
+    opcode: u8,
+    gas_remaining: u64,
+    /// The offset and length of the memory that will be written
+    memory_write: Option<(usize, usize)>,
+    store: Option<StorageDelta>,
+}
+
+impl VmTracer {
+    /// Converts the tracer into the VM trace of the transaction.
+    pub fn into_vm_trace(self) -> Option<VmTrace> {
+        self.root
+    }
+
+    fn enter_frame(&mut self, code: Bytes) {
+        self.stack.push(VmFrame {
+            trace: VmTrace {
+                code,
+                ops: Vec::new(),
+            },
+            pending: None,
+            sub: None,
+        });
+    }
+
+    fn exit_frame(&mut self) {
+        if let Some(frame) = self.stack.pop() {
+            if let Some(parent) = self.stack.last_mut() {
+                parent.sub = Some(frame.trace);
+            } else {
+                self.root = Some(frame.trace);
+            }
+        }
+    }
+}
+
+impl<DatabaseErrorT> Inspector<DatabaseErrorT> for VmTracer {
+    fn step(
+        &mut self,
+        interp: &mut Interpreter,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+    ) -> InstructionResult {
+        if let Some(frame) = self.stack.last_mut() {
+            let opcode = interp.current_opcode();
+            let stack = interp.stack.data();
+
+            let store = if opcode == opcode::SSTORE {
+                peek(stack, 0)
+                    .zip(peek(stack, 1))
+                    .map(|(key, val)| StorageDelta { key, val })
+            } else {
+                None
+            };
+
+            frame.pending = Some(PendingInstruction {
+                pc: interp.program_counter(),
+                opcode,
+                gas_remaining: interp.gas.remaining(),
+                memory_write: memory_write(opcode, stack),
+                store,
+            });
+        }
+
+        InstructionResult::Continue
+    }
+
+    fn step_end(
+        &mut self,
+        interp: &mut Interpreter,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        eval: InstructionResult,
+    ) -> InstructionResult {
+        let Some(frame) = self.stack.last_mut() else {
+            return InstructionResult::Continue;
+        };
+
+        let Some(pending) = frame.pending.take() else {
+            return InstructionResult::Continue;
+        };
+
+        let gas_remaining = interp.gas.remaining();
+
+        let ex = if matches!(eval, return_ok!() | return_revert!()) {
+            let stack = interp.stack.data();
+            let pushed = stack_outputs(pending.opcode).min(stack.len());
+
+            let mem = pending.memory_write.and_then(|(offset, length)| {
+                let data = interp
+                    .memory
+                    .data()
+                    .get(offset..offset.checked_add(length)?)?;
+
+                Some(MemoryDelta {
+                    off: offset,
+                    data: Bytes::copy_from_slice(data),
+                })
+            });
+
+            Some(VmExecutedOperation {
+                used: gas_remaining,
+                push: stack[stack.len() - pushed..].to_vec(),
+                mem,
+                store: pending.store,
+            })
+        } else {
+            None
+        };
+
+        frame.trace.ops.push(VmInstruction {
+            pc: pending.pc,
+            cost: pending.gas_remaining.saturating_sub(gas_remaining),
+            ex,
+            sub: frame.sub.take(),
+        });
+
+        InstructionResult::Continue
+    }
+
+    fn call(
+        &mut self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        let code = code_of(data, inputs.contract);
+        self.enter_frame(code);
+
+        (InstructionResult::Continue, Gas::new(0), Bytes::new())
+    }
+
+    fn call_end(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        _inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.exit_frame();
+
+        (ret, remaining_gas, out)
+    }
+
+    fn create(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.enter_frame(inputs.init_code.clone());
+
+        (
+            InstructionResult::Continue,
+            None,
+            Gas::new(0),
+            Bytes::default(),
+        )
+    }
+
+    fn create_end(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        _inputs: &CreateInputs,
+        ret: InstructionResult,
+        address: Option<Address>,
+        remaining_gas: Gas,
+        out: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.exit_frame();
+
+        (ret, address, remaining_gas, out)
+    }
+}
+
+/// Retrieves the code of the account at the provided address, without loading
+/// it into the journaled state.
+fn code_of<DatabaseErrorT>(data: &mut EVMData<'_, DatabaseErrorT>, address: Address) -> Bytes {
+    let info = match data.journaled_state.state.get(&address) {
+        Some(account) => account.info.clone(),
+        None => match data.db.basic(address) {
+            Ok(Some(info)) => info,
+            _ => return Bytes::new(),
+        },
+    };
+
+    match info.code {
+        Some(code) => code.original_bytes(),
+        None => data
+            .db
+            .code_by_hash(info.code_hash)
+            .map(|code| code.original_bytes())
+            .unwrap_or_default(),
+    }
+}
+
+/// Returns the `index`-th item from the top of the stack.
+fn peek(stack: &[U256], index: usize) -> Option<U256> {
+    stack
+        .len()
+        .checked_sub(index + 1)
+        .map(|position| stack[position])
+}
+
+/// Returns the offset and length of the memory that will be written by the
+/// instruction, given the stack before its execution.
+fn memory_write(opcode: u8, stack: &[U256]) -> Option<(usize, usize)> {
+    let (offset, length) = match opcode {
+        opcode::MSTORE => (peek(stack, 0)?, U256::from(32)),
+        opcode::MSTORE8 => (peek(stack, 0)?, U256::from(1)),
+        opcode::CALLDATACOPY | opcode::CODECOPY | opcode::RETURNDATACOPY | opcode::MCOPY => {
+            (peek(stack, 0)?, peek(stack, 2)?)
+        }
+        opcode::EXTCODECOPY => (peek(stack, 1)?, peek(stack, 3)?),
+        opcode::CALL | opcode::CALLCODE => (peek(stack, 5)?, peek(stack, 6)?),
+        opcode::DELEGATECALL | opcode::STATICCALL => (peek(stack, 4)?, peek(stack, 5)?),
+        _ => return None,
+    };
+
+    if length == U256::ZERO {
+        return None;
+    }
+
+    Some((usize::try_from(offset).ok()?, usize::try_from(length).ok()?))
+}
+
+/// Returns the number of stack items that are pushed or modified by the
+/// instruction.
+fn stack_outputs(opcode: u8) -> usize {
+    match opcode {
+        opcode::DUP1..=opcode::DUP16 => usize::from(opcode - opcode::DUP1) + 2,
+        opcode::SWAP1..=opcode::SWAP16 => usize::from(opcode - opcode::SWAP1) + 2,
+        opcode::STOP
+        | opcode::CALLDATACOPY
+        | opcode::CODECOPY
+        | opcode::EXTCODECOPY
+        | opcode::RETURNDATACOPY
+        | opcode::POP
+        | opcode::MSTORE
+        | opcode::MSTORE8
+        | opcode::SSTORE
+        | opcode::JUMP
+        | opcode::JUMPI
+        | opcode::JUMPDEST
+        | opcode::TSTORE
+        | opcode::MCOPY
+        | opcode::LOG0..=opcode::LOG4
+        | opcode::RETURN
+        | opcode::REVERT
+        | opcode::INVALID
+        | opcode::SELFDESTRUCT => 0,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stack_outputs_of_stack_manipulation() {
+        assert_eq!(stack_outputs(opcode::PUSH1), 1);
+        assert_eq!(stack_outputs(opcode::DUP1), 2);
+        assert_eq!(stack_outputs(opcode::DUP16), 17);
+        assert_eq!(stack_outputs(opcode::SWAP1), 2);
+        assert_eq!(stack_outputs(opcode::SWAP16), 17);
+        assert_eq!(stack_outputs(opcode::POP), 0);
+    }
+
+    #[test]
+    fn memory_write_of_call() {
+        // Stack of `CALL(gas, to, value, in_offset, in_length, out_offset,
+        // out_length)`, with the top of the stack last
+        let stack = [32, 64, 0, 0, 0, 0, 1000].map(U256::from);
+
+        assert_eq!(memory_write(opcode::CALL, &stack), Some((64, 32)));
+        assert_eq!(memory_write(opcode::ADD, &stack), None);
+    }
+}
@@ -42,6 +42,10 @@ pub enum StateError {
         /// Whether the state root was intended for a fork
         is_fork: bool,
     },
+    /// The state doesn't support Merkle proofs. The state roots of a forked
+    /// state are generated, so its accounts can't be proven against them.
+    #[error("Merkle proofs are not supported for the local state of a forked blockchain.")]
+    ProofUnsupported,
     /// Error from the underlying RPC client
     #[error(transparent)]
     Remote(#[from] RpcClientError),
@@ -1,7 +1,7 @@
 use std::{fmt::Debug, ops::Deref};
 
 use auto_impl::auto_impl;
-use edr_eth::{Address, B256, U256};
+use edr_eth::{remote::eth::AccountProof, Address, B256, U256};
 use revm::primitives::{AccountInfo, Bytecode};
 
 type BoxedAccountModifierFn = Box<dyn Fn(&mut U256, &mut u64, &mut Option<Bytecode>) + Send>;
@@ -64,6 +64,10 @@ pub trait StateDebug {
         default_account_fn: &dyn Fn() -> Result<AccountInfo, Self::Error>,
     ) -> Result<AccountInfo, Self::Error>;
 
+    /// Constructs a Merkle proof of the account at the specified address and
+    /// the provided storage slots, as defined by EIP-1186.
+    fn proof(&self, address: Address, storage_keys: &[U256]) -> Result<AccountProof, Self::Error>;
+
     /// Removes and returns the account at the specified address, if it exists.
     fn remove_account(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error>;
 
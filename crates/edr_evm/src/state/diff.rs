@@ -3,7 +3,8 @@ use revm::primitives::{Account, AccountInfo, AccountStatus, HashMap, StorageSlot
 
 /// The difference between two states, which can be applied to a state to get
 /// the new state using [`revm::db::DatabaseCommit::commit`].
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(transparent)]
 pub struct StateDiff {
     inner: HashMap<Address, Account>,
 }
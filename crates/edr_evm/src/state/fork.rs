@@ -1,6 +1,10 @@
 use std::sync::Arc;
 
-use edr_eth::{remote::RpcClient, trie::KECCAK_NULL_RLP, Address, B256, U256};
+use edr_eth::{
+    remote::{eth::AccountProof, RpcClient},
+    trie::KECCAK_NULL_RLP,
+    Address, B256, U256,
+};
 use parking_lot::{Mutex, RwLock, RwLockUpgradableReadGuard};
 use revm::{
     db::components::{State, StateRef},
@@ -151,6 +155,14 @@ impl StateDebug for ForkState {
         })
     }
 
+    fn proof(
+        &self,
+        _address: Address,
+        _storage_keys: &[U256],
+    ) -> Result<AccountProof, Self::Error> {
+        Err(StateError::ProofUnsupported)
+    }
+
     fn remove_account(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
         if let Some(account_info) = self.local_state.remove_account(address)? {
             Ok(Some(account_info))
@@ -6,7 +6,8 @@ use std::{
 use super::StateOverride;
 
 /// Container for state that was modified outside of mining a block.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(transparent)]
 pub struct IrregularState {
     block_number_to_override: BTreeMap<u64, StateOverride>,
 }
@@ -20,6 +21,12 @@ impl IrregularState {
         self.block_number_to_override.entry(block_number)
     }
 
+    /// Removes the state overrides that were applied after the block with the
+    /// specified number.
+    pub fn remove_state_overrides_after(&mut self, block_number: u64) {
+        self.block_number_to_override.split_off(&(block_number + 1));
+    }
+
     /// Retrieves the irregular state overrides.
     pub fn state_overrides(&self) -> &BTreeMap<u64, StateOverride> {
         &self.block_number_to_override
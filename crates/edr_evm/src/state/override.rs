@@ -4,7 +4,8 @@ use super::StateDiff;
 
 /// Data for overriding a state with a diff and the state's resulting state
 /// root.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct StateOverride {
     /// The diff to be applied.
     pub diff: StateDiff,
@@ -5,7 +5,7 @@ use edr_eth::{
 };
 use revm::{
     db::StateRef,
-    primitives::{AccountInfo, Bytecode, HashMap},
+    primitives::{Account, AccountInfo, AccountStatus, Bytecode, HashMap, StorageSlot},
 };
 
 /// Type representing either a diff or full set of overrides for storage
@@ -215,6 +215,45 @@ impl StateOverrides {
         }
     }
 
+    /// Converts the overrides into changes that can be committed to the
+    /// provided state, to make the overrides persistent.
+    ///
+    /// A full storage override is committed as a newly created account, which
+    /// clears the account's existing storage in a local state. The storage of a
+    /// remote account is not cleared.
+    pub fn state_changes<StateError>(
+        &self,
+        state: &dyn StateRef<Error = StateError>,
+    ) -> Result<HashMap<Address, Account>, StateError> {
+        self.account_overrides
+            .iter()
+            .map(|(address, account_override)| {
+                let info = self.account_info(state, address)?.unwrap_or_default();
+
+                let (storage, status) = match &account_override.storage {
+                    Some(StorageOverride::Diff(storage)) => (Some(storage), AccountStatus::Touched),
+                    Some(StorageOverride::Full(storage)) => (
+                        Some(storage),
+                        AccountStatus::Touched | AccountStatus::Created,
+                    ),
+                    None => (None, AccountStatus::Touched),
+                };
+
+                let account = Account {
+                    info,
+                    storage: storage
+                        .into_iter()
+                        .flatten()
+                        .map(|(index, value)| (*index, StorageSlot::new(*value)))
+                        .collect(),
+                    status,
+                };
+
+                Ok((*address, account))
+            })
+            .collect()
+    }
+
     /// Retrieves the code for the provided hash, applying any overrides.
     pub fn code_by_hash<StateError>(
         &self,
@@ -1,6 +1,10 @@
 mod account;
 
-use edr_eth::{account::KECCAK_EMPTY, Address, B256, U256};
+use edr_eth::{
+    account::{BasicAccount, KECCAK_EMPTY},
+    remote::eth::{AccountProof, StorageProof},
+    Address, B256, U256,
+};
 use revm::{
     db::StateRef,
     primitives::{Account, AccountInfo, Bytecode, HashMap},
@@ -169,6 +173,40 @@ impl StateDebug for TrieState {
         Ok(account_info)
     }
 
+    fn proof(&self, address: Address, storage_keys: &[U256]) -> Result<AccountProof, Self::Error> {
+        // A proof of absence is returned for non-existent accounts and slots
+        let account = self.accounts.account(&address).unwrap_or_default();
+
+        let storage_proof = storage_keys
+            .iter()
+            .map(|index| StorageProof {
+                key: *index,
+                value: self
+                    .accounts
+                    .account_storage_slot(&address, index)
+                    .unwrap_or(U256::ZERO),
+                proof: self.accounts.account_storage_proof(&address, index),
+            })
+            .collect();
+
+        let BasicAccount {
+            nonce,
+            balance,
+            storage_root,
+            code_hash,
+        } = account;
+
+        Ok(AccountProof {
+            address,
+            account_proof: self.accounts.account_proof(&address),
+            balance,
+            code_hash,
+            nonce,
+            storage_hash: storage_root,
+            storage_proof,
+        })
+    }
+
     fn remove_account(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
         Ok(self.accounts.remove_account(&address).map(|account| {
             self.remove_code(&account.code_hash);
@@ -2,7 +2,7 @@ use std::{collections::BTreeMap, fmt::Debug, sync::Arc};
 
 use alloy_rlp::Decodable;
 use cita_trie::{MemoryDB, PatriciaTrie, Trie as CitaTrie};
-use edr_eth::{account::BasicAccount, Address, B256, U256};
+use edr_eth::{account::BasicAccount, Address, Bytes, B256, U256};
 use hasher::{Hasher, HasherKeccak};
 use revm::primitives::{Account, AccountInfo, HashMap};
 
@@ -166,6 +166,52 @@ impl AccountTrie {
             })
     }
 
+    /// Retrieves the Merkle proof of the account at the specified address.
+    /// If the account doesn't exist, the proof proves its absence.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn account_proof(&self, address: &Address) -> Vec<Bytes> {
+        let state_trie = Trie::from(
+            self.state_trie_db.clone(),
+            Arc::new(HasherKeccak::new()),
+            self.state_root.as_slice(),
+        )
+        .expect("Invalid state root");
+
+        let hashed_address = HasherKeccak::new().digest(address.as_slice());
+        state_trie
+            .get_proof(&hashed_address)
+            .unwrap()
+            .into_iter()
+            .map(Bytes::from)
+            .collect()
+    }
+
+    /// Retrieves the Merkle proof of the storage slot at the specified index of
+    /// the account at the specified address. If the account or storage slot
+    /// doesn't exist, the proof proves its absence.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn account_storage_proof(&self, address: &Address, index: &U256) -> Vec<Bytes> {
+        self.storage_trie_dbs.get(address).map_or_else(
+            Vec::new,
+            |(storage_trie_db, storage_root)| {
+                let storage_trie = Trie::from(
+                    storage_trie_db.clone(),
+                    Arc::new(HasherKeccak::new()),
+                    storage_root.as_slice(),
+                )
+                .expect("Invalid storage root");
+
+                let hashed_index = HasherKeccak::new().digest(&index.to_be_bytes::<32>());
+                storage_trie
+                    .get_proof(&hashed_index)
+                    .unwrap()
+                    .into_iter()
+                    .map(Bytes::from)
+                    .collect()
+            },
+        )
+    }
+
     /// Commits changes to the state.
     #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn commit(&mut self, changes: &HashMap<Address, Account>) {
@@ -753,4 +799,72 @@ mod tests {
         let storage_value = state.account_storage_slot(&expected_address, &expected_index);
         assert_eq!(storage_value, None);
     }
+
+    #[test]
+    fn account_and_storage_proofs() {
+        const DUMMY_ADDRESS: [u8; 20] = [1u8; 20];
+        const DUMMY_STORAGE_SLOT_INDEX: u64 = 100;
+        const DUMMY_STORAGE_SLOT_VALUE: u64 = 100;
+
+        let address = Address::from(DUMMY_ADDRESS);
+        let index = U256::from(DUMMY_STORAGE_SLOT_INDEX);
+        let value = U256::from(DUMMY_STORAGE_SLOT_VALUE);
+
+        let mut state = AccountTrie::with_accounts(&precompiled_contracts());
+        state.set_account(
+            &address,
+            &AccountInfo {
+                balance: U256::from(100u32),
+                nonce: 1,
+                code_hash: KECCAK_EMPTY,
+                code: None,
+            },
+        );
+        state.set_account_storage_slot(&address, &index, &value);
+
+        let verifier = Trie::new(Arc::new(MemoryDB::new(true)), Arc::new(HasherKeccak::new()));
+        let verify = |root: B256, key: Vec<u8>, proof: Vec<Bytes>| {
+            verifier
+                .verify_proof(
+                    root.as_slice(),
+                    &key,
+                    proof.into_iter().map(|node| node.to_vec()).collect(),
+                )
+                .expect("Proof should be valid")
+        };
+
+        let hashed_address = HasherKeccak::new().digest(address.as_slice());
+        let account = state.account(&address).expect("Account should exist");
+        assert_eq!(
+            verify(
+                state.state_root(),
+                hashed_address,
+                state.account_proof(&address)
+            ),
+            Some(alloy_rlp::encode(account))
+        );
+
+        let hashed_index = HasherKeccak::new().digest(&index.to_be_bytes::<32>());
+        let storage_root = state.storage_root(&address).expect("Account should exist");
+        assert_eq!(
+            verify(
+                storage_root,
+                hashed_index,
+                state.account_storage_proof(&address, &index)
+            ),
+            Some(alloy_rlp::encode(value))
+        );
+
+        // Proof of absence
+        let missing_address = Address::from([2u8; 20]);
+        let hashed_missing_address = HasherKeccak::new().digest(missing_address.as_slice());
+        assert_eq!(
+            verify(
+                state.state_root(),
+                hashed_missing_address,
+                state.account_proof(&missing_address)
+            ),
+            None
+        );
+    }
 }
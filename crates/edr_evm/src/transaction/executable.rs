@@ -5,8 +5,9 @@ use edr_eth::{
     remote::eth::Transaction,
     signature::Signature,
     transaction::{
-        Eip1559SignedTransaction, Eip155SignedTransaction, Eip2930SignedTransaction,
-        Eip4844SignedTransaction, LegacySignedTransaction, SignedTransaction, TransactionKind,
+        DepositTransaction, Eip1559SignedTransaction, Eip155SignedTransaction,
+        Eip2930SignedTransaction, Eip4844SignedTransaction, LegacySignedTransaction,
+        SignedTransaction, TransactionKind, DEPOSIT_TRANSACTION_TYPE,
     },
     Address, U256,
 };
@@ -223,6 +224,28 @@ impl From<ExecutableTransaction> for TxEnv {
                 blob_hashes,
                 max_fee_per_blob_gas: Some(max_fee_per_blob_gas),
             },
+            // Deposit transactions don't pay for L2 gas and skip the nonce check, as
+            // their validity was established on L1.
+            SignedTransaction::Deposit(DepositTransaction {
+                gas_limit,
+                kind,
+                value,
+                input,
+                ..
+            }) => Self {
+                caller: transaction.caller,
+                gas_limit,
+                gas_price: U256::ZERO,
+                gas_priority_fee: None,
+                transact_to: transact_to(kind),
+                value,
+                data: input,
+                chain_id: None,
+                nonce: None,
+                access_list: Vec::new(),
+                blob_hashes: Vec::new(),
+                max_fee_per_blob_gas: None,
+            },
         }
     }
 }
@@ -251,6 +274,9 @@ pub enum TransactionConversionError {
     /// EIP-4844 transaction is missing the receiver (to) address
     #[error("Missing receiver (to) address")]
     MissingReceiverAddress,
+    /// Deposit transaction is missing the source hash
+    #[error("Missing source hash")]
+    MissingSourceHash,
     /// The transaction type is not supported.
     #[error("Unsupported type {0}")]
     UnsupportedType(u64),
@@ -377,6 +403,22 @@ impl TryFrom<Transaction> for ExecutableTransaction {
                 s: value.s,
                 hash: OnceLock::from(value.hash),
             }),
+            Some(r#type) if r#type == u64::from(DEPOSIT_TRANSACTION_TYPE) => {
+                SignedTransaction::Deposit(DepositTransaction {
+                    source_hash: value
+                        .source_hash
+                        .ok_or(TransactionConversionError::MissingSourceHash)?,
+                    from: value.from,
+                    kind,
+                    // Pre-Regolith nodes omit the mint when it's zero
+                    mint: value.mint.unwrap_or_default(),
+                    value: value.value,
+                    gas_limit: value.gas.to(),
+                    is_system_tx: value.is_system_tx.unwrap_or(false),
+                    input: value.input,
+                    hash: OnceLock::from(value.hash),
+                })
+            }
             Some(r#type) => {
                 return Err(TransactionConversionError::UnsupportedType(r#type));
             }
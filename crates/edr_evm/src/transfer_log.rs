@@ -0,0 +1,173 @@
+use edr_eth::{Address, Bytes, B256, U256};
+use revm::{
+    interpreter::{return_ok, CallInputs, CreateInputs, Gas, InstructionResult},
+    primitives::Log,
+    EVMData, Inspector,
+};
+
+/// The pseudo-address that emits the logs of ether transfers, as defined by
+/// `eth_simulateV1`.
+pub const TRANSFER_LOG_ADDRESS: Address = Address::new([0xee; 20]);
+
+/// The topic of an ERC-20 `Transfer(address,address,uint256)` event.
+pub const TRANSFER_EVENT_TOPIC: B256 = B256::new([
+    0xdd, 0xf2, 0x52, 0xad, 0x1b, 0xe2, 0xc8, 0x9b, 0x69, 0xc2, 0xb0, 0x68, 0xfc, 0x37, 0x8d, 0xaa,
+    0x95, 0x2b, 0xa7, 0xf1, 0x63, 0xc4, 0xa1, 0x16, 0x28, 0xf5, 0x5a, 0x4d, 0xf5, 0x23, 0xb3, 0xef,
+]);
+
+/// Inspector that collects the logs of a transaction, including an ERC-20
+/// style `Transfer` log for every ether transfer. Logs of reverted calls are
+/// discarded.
+#[derive(Debug, Default)]
+pub struct TransferLogTracer {
+    /// The logs of the active call frames
+    frames: Vec<Vec<Log>>,
+    logs: Vec<Log>,
+}
+
+impl TransferLogTracer {
+    /// Converts the tracer into the collected logs, in execution order.
+    pub fn into_logs(self) -> Vec<Log> {
+        self.logs
+    }
+
+    fn exit_frame(&mut self, ret: InstructionResult) {
+        let Some(logs) = self.frames.pop() else {
+            return;
+        };
+
+        if matches!(ret, return_ok!()) {
+            self.frames
+                .last_mut()
+                .unwrap_or(&mut self.logs)
+                .extend(logs);
+        }
+    }
+
+    fn push_log(&mut self, log: Log) {
+        self.frames.last_mut().unwrap_or(&mut self.logs).push(log);
+    }
+}
+
+impl<DatabaseErrorT> Inspector<DatabaseErrorT> for TransferLogTracer {
+    fn log(
+        &mut self,
+        _evm_data: &mut EVMData<'_, DatabaseErrorT>,
+        address: &Address,
+        topics: &[B256],
+        data: &Bytes,
+    ) {
+        self.push_log(Log {
+            address: *address,
+            topics: topics.to_vec(),
+            data: data.clone(),
+        });
+    }
+
+    fn call(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.frames.push(Vec::new());
+
+        if inputs.transfer.value > U256::ZERO {
+            self.push_log(transfer_log(
+                inputs.transfer.source,
+                inputs.transfer.target,
+                inputs.transfer.value,
+            ));
+        }
+
+        (InstructionResult::Continue, Gas::new(0), Bytes::new())
+    }
+
+    fn call_end(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        _inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.exit_frame(ret);
+
+        (ret, remaining_gas, out)
+    }
+
+    fn create(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        _inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.frames.push(Vec::new());
+
+        (
+            InstructionResult::Continue,
+            None,
+            Gas::new(0),
+            Bytes::default(),
+        )
+    }
+
+    fn create_end(
+        &mut self,
+        _data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &CreateInputs,
+        ret: InstructionResult,
+        address: Option<Address>,
+        remaining_gas: Gas,
+        out: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        // The created address is only known at the end of the creation, but the
+        // transfer happened before any of the constructor's logs.
+        if let (Some(address), Some(logs)) = (address, self.frames.last_mut()) {
+            if inputs.value > U256::ZERO {
+                logs.insert(0, transfer_log(inputs.caller, address, inputs.value));
+            }
+        }
+
+        self.exit_frame(ret);
+
+        (ret, address, remaining_gas, out)
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        if value > U256::ZERO {
+            self.push_log(transfer_log(contract, target, value));
+        }
+    }
+}
+
+fn transfer_log(from: Address, to: Address, value: U256) -> Log {
+    Log {
+        address: TRANSFER_LOG_ADDRESS,
+        topics: vec![
+            TRANSFER_EVENT_TOPIC,
+            address_to_topic(from),
+            address_to_topic(to),
+        ],
+        data: Bytes::copy_from_slice(&value.to_be_bytes::<32>()),
+    }
+}
+
+fn address_to_topic(address: Address) -> B256 {
+    let mut topic = B256::ZERO;
+    topic[12..].copy_from_slice(address.as_slice());
+    topic
+}
+
+#[cfg(test)]
+mod tests {
+    use revm::primitives::keccak256;
+
+    use super::*;
+
+    #[test]
+    fn transfer_event_topic() {
+        assert_eq!(
+            TRANSFER_EVENT_TOPIC,
+            keccak256("Transfer(address,address,uint256)")
+        );
+    }
+}
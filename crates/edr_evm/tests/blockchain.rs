@@ -10,7 +10,7 @@ use edr_eth::{
     Address, Bloom, Bytes, B256, U256,
 };
 use edr_evm::{
-    blockchain::{BlockchainError, LocalBlockchain, SyncBlockchain},
+    blockchain::{BlockchainError, GenesisBlockOptions, LocalBlockchain, SyncBlockchain},
     state::{StateDiff, StateError},
     test_utils::dummy_eip155_transaction,
     HashSet, LocalBlock, Log, SpecId, SyncBlock,
@@ -80,6 +80,7 @@ async fn create_dummy_blockchains() -> Vec<Box<dyn SyncBlockchain<BlockchainErro
             excess_gas: 0,
         }),
         Some(KECCAK_NULL_RLP),
+        GenesisBlockOptions::default(),
     )
     .expect("Should construct without issues");
 
@@ -195,6 +196,7 @@ fn insert_dummy_block_with_transaction(
         contract_address: None,
         gas_used: GAS_USED,
         effective_gas_price: None,
+        l1_fee: None,
     };
 
     let block = LocalBlock::new(
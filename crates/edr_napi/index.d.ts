@@ -141,7 +141,8 @@ export interface ContractAndFunctionName {
 export interface LoggerConfig {
   /** Whether to enable the logger. */
   enable: boolean
-  decodeConsoleLogInputsCallback: (inputs: Buffer[]) => string[]
+  /** Overrides the native decoding of `console.log` calls. */
+  decodeConsoleLogInputsCallback?: (inputs: Buffer[]) => string[]
   getContractAndFunctionNameCallback: (code: Buffer, calldata?: Buffer) => ContractAndFunctionName
   printLineCallback: (message: string, replace: boolean) => void
 }
@@ -168,10 +169,15 @@ export interface HttpHeader {
   name: string
   value: string
 }
-/** Configuration for a hardfork activation */
+/**
+ * Configuration for a hardfork activation. Exactly one of `block_number` and
+ * `timestamp` must be provided.
+ */
 export interface HardforkActivation {
   /** The block number at which the hardfork is activated */
-  blockNumber: bigint
+  blockNumber?: bigint
+  /** The timestamp at which the hardfork is activated */
+  timestamp?: bigint
   /** The activated hardfork */
   specId: SpecId
 }
@@ -196,6 +202,44 @@ export interface MiningConfig {
   interval?: bigint | IntervalRange
   memPool: MemPoolConfig
 }
+/**
+ * Configuration for the safe and finalized blocks, which lag behind the
+ * latest block by a fixed number of blocks.
+ */
+export interface FinalityConfig {
+  /**
+   * The number of blocks by which the safe block lags behind the latest
+   * block. Must not exceed the finalized block lag.
+   */
+  safeBlockLag: bigint
+  /**
+   * The number of blocks by which the finalized block lags behind the
+   * latest block
+   */
+  finalizedBlockLag: bigint
+}
+/** A withdrawal to include in mined blocks */
+export interface WithdrawalRequest {
+  /** The index of the validator that generated the withdrawal */
+  validatorIndex: bigint
+  /** The recipient of the withdrawal */
+  address: Buffer
+  /** The amount of the withdrawal, in Gwei */
+  amount: bigint
+}
+/**
+ * Configuration for withdrawals that are included in blocks at a fixed
+ * interval.
+ */
+export interface WithdrawalScheduleConfig {
+  /**
+   * The number of blocks between blocks with the scheduled withdrawals. Must
+   * be greater than zero.
+   */
+  interval: bigint
+  /** The withdrawals to include in each scheduled block */
+  withdrawals: Array<WithdrawalRequest>
+}
 /** Configuration for a provider */
 export interface ProviderConfig {
   /** Whether to allow blocks with the same timestamp */
@@ -216,6 +260,21 @@ export interface ProviderConfig {
   chains: Array<ChainConfig>
   /** The address of the coinbase */
   coinbase: Buffer
+  /**
+   * Whether to support Foundry's `Vm` cheatcodes, such as `vm.warp` and
+   * `vm.prank`, in transactions and calls
+   */
+  enableCheatcodes?: boolean
+  /**
+   * Whether to collect code coverage of executed transactions and calls,
+   * which can be retrieved with `hardhat_getCoverage`
+   */
+  enableCoverage?: boolean
+  /**
+   * The lag of the safe and finalized blocks. If not provided, both
+   * resolve to the latest block.
+   */
+  finality?: FinalityConfig
   /**
    * The configuration for forking a blockchain. If not provided, a local
    * blockchain will be created
@@ -245,6 +304,21 @@ export interface ProviderConfig {
   mining: MiningConfig
   /** The network ID of the blockchain */
   networkId: bigint
+  /**
+   * Whether to run an OP-stack chain. Always enabled when forking a
+   * supported OP-stack chain.
+   */
+  optimism?: boolean
+  /**
+   * The path of a state dump, created with `hardhat_dumpState`, to
+   * restore the provider's state from
+   */
+  statePath?: string
+  /**
+   * Withdrawals to include in mined blocks at a fixed interval. Only
+   * applies to Shanghai and later hardforks.
+   */
+  withdrawalSchedule?: WithdrawalScheduleConfig
 }
 /** The possible reasons for successful termination of the EVM. */
 export const enum SuccessReason {
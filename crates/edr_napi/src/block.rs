@@ -77,6 +77,7 @@ impl TryFrom<BlockOptions> for edr_eth::block::BlockOptions {
                 .withdrawals_root
                 .map(TryCast::<B256>::try_cast)
                 .transpose()?,
+            withdrawals: None,
             parent_beacon_block_root: value
                 .parent_beacon_block_root
                 .map(TryCast::<B256>::try_cast)
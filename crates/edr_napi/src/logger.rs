@@ -54,8 +54,9 @@ struct ContractAndFunctionNameCall {
 pub struct LoggerConfig {
     /// Whether to enable the logger.
     pub enable: bool,
+    /// Overrides the native decoding of `console.log` calls.
     #[napi(ts_type = "(inputs: Buffer[]) => string[]")]
-    pub decode_console_log_inputs_callback: JsFunction,
+    pub decode_console_log_inputs_callback: Option<JsFunction>,
     #[napi(ts_type = "(code: Buffer, calldata?: Buffer) => ContractAndFunctionName")]
     pub get_contract_and_function_name_callback: JsFunction,
     #[napi(ts_type = "(message: string, replace: boolean) => void")]
@@ -247,7 +248,7 @@ pub struct CollapsedMethod {
 
 #[derive(Clone)]
 struct LogCollector {
-    decode_console_log_inputs_fn: ThreadsafeFunction<DecodeConsoleLogInputsCall>,
+    decode_console_log_inputs_fn: Option<ThreadsafeFunction<DecodeConsoleLogInputsCall>>,
     get_contract_and_function_name_fn: ThreadsafeFunction<ContractAndFunctionNameCall>,
     indentation: usize,
     is_enabled: bool,
@@ -259,45 +260,56 @@ struct LogCollector {
 
 impl LogCollector {
     pub fn new(env: &Env, config: LoggerConfig) -> napi::Result<Self> {
-        let decode_console_log_inputs_fn = ThreadsafeFunction::create(
-            env.raw(),
-            // SAFETY: The callback is guaranteed to be valid for the lifetime of the tracer.
-            unsafe { config.decode_console_log_inputs_callback.raw() },
-            0,
-            |ctx: ThreadSafeCallContext<DecodeConsoleLogInputsCall>| {
-                // Bytes[]
-                let inputs = ctx
-                    .env
-                    .create_array_with_length(ctx.value.inputs.len())
-                    .and_then(|mut inputs| {
-                        for (idx, input) in ctx.value.inputs.into_iter().enumerate() {
-                            // SAFETY: The input is guaranteed to be valid for the lifetime of the
-                            // JS buffer.
-                            unsafe {
-                                ctx.env.create_buffer_with_borrowed_data(
-                                    input.as_ptr(),
-                                    input.len(),
-                                    input,
-                                    |input: Bytes, _env| {
-                                        std::mem::drop(input);
-                                    },
-                                )
-                            }
-                            .and_then(|input| inputs.set_element(idx as u32, input.into_raw()))?;
-                        }
-
-                        Ok(inputs)
-                    })?;
-
-                let sender = ctx.value.sender.clone();
+        let decode_console_log_inputs_fn = config
+            .decode_console_log_inputs_callback
+            .as_ref()
+            .map(|callback| {
+                ThreadsafeFunction::create(
+                    env.raw(),
+                    // SAFETY: The callback is guaranteed to be valid for the lifetime of the tracer.
+                    unsafe { callback.raw() },
+                    0,
+                    |ctx: ThreadSafeCallContext<DecodeConsoleLogInputsCall>| {
+                        // Bytes[]
+                        let inputs = ctx
+                            .env
+                            .create_array_with_length(ctx.value.inputs.len())
+                            .and_then(|mut inputs| {
+                                for (idx, input) in ctx.value.inputs.into_iter().enumerate() {
+                                    // SAFETY: The input is guaranteed to be valid for the lifetime of the
+                                    // JS buffer.
+                                    unsafe {
+                                        ctx.env.create_buffer_with_borrowed_data(
+                                            input.as_ptr(),
+                                            input.len(),
+                                            input,
+                                            |input: Bytes, _env| {
+                                                std::mem::drop(input);
+                                            },
+                                        )
+                                    }
+                                    .and_then(|input| {
+                                        inputs.set_element(idx as u32, input.into_raw())
+                                    })?;
+                                }
+
+                                Ok(inputs)
+                            })?;
+
+                        let sender = ctx.value.sender.clone();
+
+                        let promise = ctx.callback.call(None, &[inputs])?;
+                        let result = await_promise::<Vec<String>, Vec<String>>(
+                            ctx.env,
+                            promise,
+                            ctx.value.sender,
+                        );
 
-                let promise = ctx.callback.call(None, &[inputs])?;
-                let result =
-                    await_promise::<Vec<String>, Vec<String>>(ctx.env, promise, ctx.value.sender);
-
-                handle_error(sender, result)
-            },
-        )?;
+                        handle_error(sender, result)
+                    },
+                )
+            })
+            .transpose()?;
 
         let get_contract_and_function_name_fn = ThreadsafeFunction::create(
             env.raw(),
@@ -391,6 +403,7 @@ impl LogCollector {
     ) {
         let edr_provider::CallResult {
             console_log_inputs,
+            console_log_messages,
             execution_result,
             trace,
         } = result;
@@ -408,7 +421,7 @@ impl LogCollector {
                 logger.log_with_title("Value", wei_to_human_readable(transaction.value()));
             }
 
-            logger.log_console_log_messages(console_log_inputs);
+            logger.log_console_log_messages(console_log_inputs, console_log_messages);
 
             if let Some(transaction_failure) = TransactionFailure::from_execution_result(
                 execution_result,
@@ -428,6 +441,7 @@ impl LogCollector {
     ) {
         let edr_provider::EstimateGasFailure {
             console_log_inputs,
+            console_log_messages,
             transaction_failure,
         } = result;
 
@@ -445,7 +459,7 @@ impl LogCollector {
             }
             logger.log_with_title("Value", wei_to_human_readable(transaction.value()));
 
-            logger.log_console_log_messages(console_log_inputs);
+            logger.log_console_log_messages(console_log_inputs, console_log_messages);
 
             logger.log_transaction_failure(&transaction_failure.failure);
         });
@@ -682,6 +696,7 @@ impl LogCollector {
             transaction_results,
             transaction_traces,
             console_log_inputs,
+            console_log_messages,
         } = result;
 
         let transactions = block.transactions();
@@ -712,6 +727,7 @@ impl LogCollector {
                         result,
                         trace,
                         console_log_inputs,
+                        console_log_messages,
                         should_highlight_hash,
                     );
 
@@ -750,6 +766,7 @@ impl LogCollector {
         result: &edr_evm::ExecutionResult,
         trace: &edr_evm::trace::Trace,
         console_log_inputs: &[Bytes],
+        console_log_messages: &[String],
         should_highlight_hash: bool,
     ) {
         let transaction_hash = transaction.hash();
@@ -778,7 +795,7 @@ impl LogCollector {
                 ),
             );
 
-            logger.log_console_log_messages(console_log_inputs);
+            logger.log_console_log_messages(console_log_inputs, console_log_messages);
 
             let transaction_failure = edr_provider::TransactionFailure::from_execution_result(
                 result,
@@ -792,22 +809,33 @@ impl LogCollector {
         });
     }
 
-    fn log_console_log_messages(&mut self, console_log_inputs: &[Bytes]) {
-        let (sender, receiver) = channel();
-
-        let status = self.decode_console_log_inputs_fn.call(
-            DecodeConsoleLogInputsCall {
-                inputs: console_log_inputs.to_vec(),
-                sender,
-            },
-            ThreadsafeFunctionCallMode::Blocking,
-        );
-        assert_eq!(status, Status::Ok);
+    /// Logs the `console.log` messages, decoded by the JS callback if one was
+    /// provided.
+    fn log_console_log_messages(
+        &mut self,
+        console_log_inputs: &[Bytes],
+        console_log_messages: &[String],
+    ) {
+        let console_log_inputs =
+            if let Some(decode_console_log_inputs_fn) = &self.decode_console_log_inputs_fn {
+                let (sender, receiver) = channel();
+
+                let status = decode_console_log_inputs_fn.call(
+                    DecodeConsoleLogInputsCall {
+                        inputs: console_log_inputs.to_vec(),
+                        sender,
+                    },
+                    ThreadsafeFunctionCallMode::Blocking,
+                );
+                assert_eq!(status, Status::Ok);
 
-        let console_log_inputs = receiver
-            .recv()
-            .unwrap()
-            .expect("Failed call to decode_console_log_inputs");
+                receiver
+                    .recv()
+                    .unwrap()
+                    .expect("Failed call to decode_console_log_inputs")
+            } else {
+                console_log_messages.to_vec()
+            };
         // This is a special case, as we always want to print the console.log messages.
         // The difference is how. If we have a logger, we should use that, so that logs
         // are printed in order. If we don't, we just print the messages here.
@@ -964,6 +992,7 @@ impl LogCollector {
             transaction_results,
             transaction_traces,
             console_log_inputs,
+            console_log_messages,
         } = result;
 
         let transactions = block.transactions();
@@ -992,6 +1021,7 @@ impl LogCollector {
                         result,
                         trace,
                         console_log_inputs,
+                        console_log_messages,
                         false,
                     );
 
@@ -1011,6 +1041,7 @@ impl LogCollector {
             transaction_results,
             transaction_traces,
             console_log_inputs,
+            console_log_messages,
         } = result;
 
         let transactions = block.transactions();
@@ -1043,6 +1074,7 @@ impl LogCollector {
                                 result,
                                 trace,
                                 console_log_inputs,
+                                console_log_messages,
                                 false,
                             );
 
@@ -1154,7 +1186,10 @@ impl LogCollector {
             let block_number = block_result.block.header().number;
             logger.log_with_title(format!("Block #{block_number}"), block_result.block.hash());
 
-            logger.log_console_log_messages(&block_result.console_log_inputs);
+            logger.log_console_log_messages(
+                &block_result.console_log_inputs,
+                &block_result.console_log_messages,
+            );
 
             let transaction_failure = edr_provider::TransactionFailure::from_execution_result(
                 transaction_result,
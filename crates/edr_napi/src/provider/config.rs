@@ -1,13 +1,14 @@
 use std::{
+    num::NonZeroU64,
     path::PathBuf,
     time::{Duration, SystemTime},
 };
 
-use edr_eth::HashMap;
+use edr_eth::{spec::ForkCondition, HashMap};
 use edr_provider::AccountConfig;
 use napi::{
     bindgen_prelude::{BigInt, Buffer},
-    Either,
+    Either, Status,
 };
 use napi_derive::napi;
 
@@ -40,11 +41,14 @@ pub struct HttpHeader {
     pub value: String,
 }
 
-/// Configuration for a hardfork activation
+/// Configuration for a hardfork activation. Exactly one of `block_number` and
+/// `timestamp` must be provided.
 #[napi(object)]
 pub struct HardforkActivation {
     /// The block number at which the hardfork is activated
-    pub block_number: BigInt,
+    pub block_number: Option<BigInt>,
+    /// The timestamp at which the hardfork is activated
+    pub timestamp: Option<BigInt>,
     /// The activated hardfork
     pub spec_id: SpecId,
 }
@@ -78,6 +82,40 @@ pub struct MiningConfig {
     pub mem_pool: MemPoolConfig,
 }
 
+/// Configuration for the safe and finalized blocks, which lag behind the
+/// latest block by a fixed number of blocks.
+#[napi(object)]
+pub struct FinalityConfig {
+    /// The number of blocks by which the safe block lags behind the latest
+    /// block. Must not exceed the finalized block lag.
+    pub safe_block_lag: BigInt,
+    /// The number of blocks by which the finalized block lags behind the
+    /// latest block
+    pub finalized_block_lag: BigInt,
+}
+
+/// A withdrawal to include in mined blocks
+#[napi(object)]
+pub struct WithdrawalRequest {
+    /// The index of the validator that generated the withdrawal
+    pub validator_index: BigInt,
+    /// The recipient of the withdrawal
+    pub address: Buffer,
+    /// The amount of the withdrawal, in Gwei
+    pub amount: BigInt,
+}
+
+/// Configuration for withdrawals that are included in blocks at a fixed
+/// interval.
+#[napi(object)]
+pub struct WithdrawalScheduleConfig {
+    /// The number of blocks between blocks with the scheduled withdrawals. Must
+    /// be greater than zero.
+    pub interval: BigInt,
+    /// The withdrawals to include in each scheduled block
+    pub withdrawals: Vec<WithdrawalRequest>,
+}
+
 /// Configuration for a provider
 #[napi(object)]
 pub struct ProviderConfig {
@@ -99,6 +137,15 @@ pub struct ProviderConfig {
     pub chains: Vec<ChainConfig>,
     /// The address of the coinbase
     pub coinbase: Buffer,
+    /// Whether to support Foundry's `Vm` cheatcodes, such as `vm.warp` and
+    /// `vm.prank`, in transactions and calls
+    pub enable_cheatcodes: Option<bool>,
+    /// Whether to collect code coverage of executed transactions and calls,
+    /// which can be retrieved with `hardhat_getCoverage`
+    pub enable_coverage: Option<bool>,
+    /// The lag of the safe and finalized blocks. If not provided, both
+    /// resolve to the latest block.
+    pub finality: Option<FinalityConfig>,
     /// The configuration for forking a blockchain. If not provided, a local
     /// blockchain will be created
     pub fork: Option<ForkConfig>,
@@ -122,6 +169,15 @@ pub struct ProviderConfig {
     pub mining: MiningConfig,
     /// The network ID of the blockchain
     pub network_id: BigInt,
+    /// Whether to run an OP-stack chain. Always enabled when forking a
+    /// supported OP-stack chain.
+    pub optimism: Option<bool>,
+    /// The path of a state dump, created with `hardhat_dumpState`, to
+    /// restore the provider's state from
+    pub state_path: Option<String>,
+    /// Withdrawals to include in mined blocks at a fixed interval. Only
+    /// applies to Shanghai and later hardforks.
+    pub withdrawal_schedule: Option<WithdrawalScheduleConfig>,
 }
 
 impl TryFrom<ForkConfig> for edr_provider::hardhat_rpc_types::ForkConfig {
@@ -192,6 +248,64 @@ impl TryFrom<MiningConfig> for edr_provider::MiningConfig {
     }
 }
 
+impl TryFrom<FinalityConfig> for edr_provider::FinalityConfig {
+    type Error = napi::Error;
+
+    fn try_from(value: FinalityConfig) -> Result<Self, Self::Error> {
+        let safe_block_lag: u64 = value.safe_block_lag.try_cast()?;
+        let finalized_block_lag: u64 = value.finalized_block_lag.try_cast()?;
+
+        if safe_block_lag > finalized_block_lag {
+            return Err(napi::Error::new(
+                Status::InvalidArg,
+                format!("The safe block lag ({safe_block_lag}) must not exceed the finalized block lag ({finalized_block_lag})."),
+            ));
+        }
+
+        Ok(Self {
+            safe_block_lag,
+            finalized_block_lag,
+        })
+    }
+}
+
+impl TryFrom<WithdrawalRequest> for edr_provider::hardhat_rpc_types::WithdrawalRequest {
+    type Error = napi::Error;
+
+    fn try_from(value: WithdrawalRequest) -> Result<Self, Self::Error> {
+        Ok(Self {
+            validator_index: value.validator_index.try_cast()?,
+            address: value.address.try_cast()?,
+            amount: value.amount.try_cast()?,
+        })
+    }
+}
+
+impl TryFrom<WithdrawalScheduleConfig> for edr_provider::WithdrawalScheduleConfig {
+    type Error = napi::Error;
+
+    fn try_from(value: WithdrawalScheduleConfig) -> Result<Self, Self::Error> {
+        let interval: u64 = value.interval.try_cast()?;
+        let interval = NonZeroU64::new(interval).ok_or_else(|| {
+            napi::Error::new(
+                Status::InvalidArg,
+                "The withdrawal schedule interval must be greater than zero.".to_string(),
+            )
+        })?;
+
+        let withdrawals = value
+            .withdrawals
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<napi::Result<_>>()?;
+
+        Ok(Self {
+            interval,
+            withdrawals,
+        })
+    }
+}
+
 impl TryFrom<ProviderConfig> for edr_provider::ProviderConfig {
     type Error = napi::Error;
 
@@ -209,12 +323,26 @@ impl TryFrom<ProviderConfig> for edr_provider::ProviderConfig {
                         .map(
                             |HardforkActivation {
                                  block_number,
+                                 timestamp,
                                  spec_id,
                              }| {
-                                let block_number = block_number.try_cast()?;
+                                let condition = match (block_number, timestamp) {
+                                    (Some(block_number), None) => {
+                                        ForkCondition::Block(block_number.try_cast()?)
+                                    }
+                                    (None, Some(timestamp)) => {
+                                        ForkCondition::Timestamp(timestamp.try_cast()?)
+                                    }
+                                    _ => {
+                                        return Err(napi::Error::new(
+                                            Status::InvalidArg,
+                                            "A hardfork activation must have either a block number or a timestamp.".to_string(),
+                                        ))
+                                    }
+                                };
                                 let spec_id = spec_id.try_into()?;
 
-                                Ok((block_number, spec_id))
+                                Ok((condition, spec_id))
                             },
                         )
                         .collect::<napi::Result<Vec<_>>>()?;
@@ -244,8 +372,13 @@ impl TryFrom<ProviderConfig> for edr_provider::ProviderConfig {
             chain_id: value.chain_id.try_cast()?,
             chains,
             coinbase: value.coinbase.try_cast()?,
+            enable_cheatcodes: value.enable_cheatcodes.unwrap_or(false),
+            enable_coverage: value.enable_coverage.unwrap_or(false),
+            finality: value.finality.map(TryInto::try_into).transpose()?,
             fork: value.fork.map(TryInto::try_into).transpose()?,
             genesis_accounts: HashMap::new(),
+            genesis_block: edr_evm::blockchain::GenesisBlockOptions::default(),
+            genesis_storage: HashMap::new(),
             hardfork: value.hardfork.try_into()?,
             initial_base_fee_per_gas: value
                 .initial_base_fee_per_gas
@@ -266,6 +399,12 @@ impl TryFrom<ProviderConfig> for edr_provider::ProviderConfig {
             mining: value.mining.try_into()?,
             min_gas_price: value.min_gas_price.try_cast()?,
             network_id: value.network_id.try_cast()?,
+            optimism: value.optimism.unwrap_or(false),
+            state_path: value.state_path.map(PathBuf::from),
+            withdrawal_schedule: value
+                .withdrawal_schedule
+                .map(TryInto::try_into)
+                .transpose()?,
         })
     }
 }
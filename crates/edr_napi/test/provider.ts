@@ -57,9 +57,6 @@ describe("Provider", () => {
 
   const loggerConfig = {
     enable: false,
-    decodeConsoleLogInputsCallback: (inputs: Buffer[]): string[] => {
-      return [];
-    },
     getContractAndFunctionNameCallback: (
       _code: Buffer,
       _calldata?: Buffer
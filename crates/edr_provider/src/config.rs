@@ -1,12 +1,23 @@
-use std::{path::PathBuf, time::SystemTime};
+use std::{
+    num::NonZeroU64,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use edr_eth::{
-    block::BlobGas, spec::HardforkActivations, AccountInfo, Address, HashMap, SpecId, B256, U256,
+    block::BlobGas, genesis::Genesis, spec::HardforkActivations, AccountInfo, Address, HashMap,
+    SpecId, B256, B64, U256,
+};
+use edr_evm::{
+    alloy_primitives::ChainId, blockchain::GenesisBlockOptions, Bytecode, MineOrdering,
+    KECCAK_EMPTY,
 };
-use edr_evm::{alloy_primitives::ChainId, MineOrdering};
 use rand::Rng;
 
-use crate::{requests::hardhat::rpc_types::ForkConfig, OneUsizeOrTwo};
+use crate::{
+    requests::hardhat::rpc_types::{ForkConfig, WithdrawalRequest},
+    OneUsizeOrTwo,
+};
 
 /// Configuration for interval mining.
 #[derive(Clone, Debug)]
@@ -54,6 +65,30 @@ pub struct MiningConfig {
     pub mem_pool: MemPoolConfig,
 }
 
+/// Configuration for the safe and finalized blocks, which lag behind the
+/// latest block by a fixed number of blocks.
+#[derive(Clone, Debug, Default)]
+pub struct FinalityConfig {
+    /// The number of blocks by which the safe block lags behind the latest
+    /// block. Must not exceed the finalized block lag.
+    pub safe_block_lag: u64,
+    /// The number of blocks by which the finalized block lags behind the
+    /// latest block. The finalized block is never newer than the safe block.
+    pub finalized_block_lag: u64,
+}
+
+/// Configuration for withdrawals that are included in blocks at a fixed
+/// interval, e.g. to simulate the skimming of validator rewards.
+#[derive(Clone, Debug)]
+pub struct WithdrawalScheduleConfig {
+    /// The number of blocks between blocks with the scheduled withdrawals.
+    /// Withdrawals are included in blocks whose number is a multiple of the
+    /// interval.
+    pub interval: NonZeroU64,
+    /// The withdrawals to include in each scheduled block
+    pub withdrawals: Vec<WithdrawalRequest>,
+}
+
 /// Configuration for the provider
 #[derive(Debug, Clone)]
 pub struct ProviderConfig {
@@ -69,9 +104,23 @@ pub struct ProviderConfig {
     pub chain_id: ChainId,
     pub chains: HashMap<ChainId, HardforkActivations>,
     pub coinbase: Address,
+    /// Whether to handle calls to Foundry's cheatcode address with a
+    /// [`edr_evm::CheatcodeInspector`]
+    pub enable_cheatcodes: bool,
+    /// Whether to collect code coverage of all executed transactions and
+    /// calls, which is reported by `hardhat_getCoverage`
+    pub enable_coverage: bool,
+    /// The lag of the safe and finalized blocks. If not provided, both resolve
+    /// to the latest block, unless they are set with `hardhat_setSafeBlock`
+    /// or `hardhat_setFinalizedBlock`.
+    pub finality: Option<FinalityConfig>,
     pub fork: Option<ForkConfig>,
     // Genesis accounts in addition to accounts. Useful for adding impersonated accounts for tests.
     pub genesis_accounts: HashMap<Address, AccountInfo>,
+    /// The header fields of the genesis block
+    pub genesis_block: GenesisBlockOptions,
+    /// The storage of the genesis accounts
+    pub genesis_storage: HashMap<Address, HashMap<U256, U256>>,
     pub hardfork: SpecId,
     pub initial_base_fee_per_gas: Option<U256>,
     pub initial_blob_gas: Option<BlobGas>,
@@ -80,6 +129,100 @@ pub struct ProviderConfig {
     pub min_gas_price: U256,
     pub mining: MiningConfig,
     pub network_id: u64,
+    /// Whether to run an OP-stack chain, with deposit transactions and L1 data
+    /// fees. Always enabled when forking a supported OP-stack chain.
+    pub optimism: bool,
+    /// The path of a state dump, created with `hardhat_dumpState`, to restore
+    /// the provider's state from
+    pub state_path: Option<PathBuf>,
+    /// Withdrawals to include in mined blocks at a fixed interval. Only
+    /// applies to Shanghai and later hardforks.
+    pub withdrawal_schedule: Option<WithdrawalScheduleConfig>,
+}
+
+impl ProviderConfig {
+    /// Applies a geth-style genesis file to the configuration, overriding the
+    /// chain ID, hardfork schedule, genesis block fields and genesis accounts.
+    ///
+    /// The hardfork is set to the last scheduled hardfork, so hardforks that
+    /// are activated after the genesis block apply to later blocks.
+    pub fn apply_genesis(&mut self, genesis: Genesis) {
+        // Defaults of geth's `params.GenesisDifficulty` and `params.GenesisGasLimit`
+        const GENESIS_DIFFICULTY: u64 = 131_072;
+        const GENESIS_GAS_LIMIT: u64 = 4_712_388;
+
+        let activations = genesis.config.hardfork_activations();
+        if let Some(hardfork) = activations.latest_hardfork() {
+            self.hardfork = hardfork;
+        }
+        let genesis_hardfork = activations.hardfork_at_block(0, genesis.timestamp);
+
+        self.chain_id = genesis.config.chain_id;
+        self.network_id = genesis.config.chain_id;
+        self.chains.insert(genesis.config.chain_id, activations);
+
+        self.block_gas_limit = if genesis.gas_limit == 0 {
+            GENESIS_GAS_LIMIT
+        } else {
+            genesis.gas_limit
+        };
+        self.initial_base_fee_per_gas = genesis.base_fee_per_gas;
+        self.initial_date = Some(UNIX_EPOCH + Duration::from_secs(genesis.timestamp));
+
+        // Like geth, a missing difficulty only defaults to a non-zero value if the
+        // mix hash isn't set either.
+        let difficulty = genesis.difficulty.unwrap_or_else(|| {
+            if genesis.mix_hash == B256::ZERO {
+                U256::from(GENESIS_DIFFICULTY)
+            } else {
+                U256::ZERO
+            }
+        });
+
+        self.genesis_block = GenesisBlockOptions {
+            beneficiary: Some(genesis.coinbase),
+            difficulty: Some(difficulty),
+            extra_data: Some(genesis.extra_data),
+            mix_hash: Some(genesis.mix_hash),
+            nonce: Some(B64::from(genesis.nonce)),
+            spec_id: genesis_hardfork,
+        };
+
+        if self.hardfork >= SpecId::CANCUN {
+            self.initial_blob_gas = Some(BlobGas {
+                gas_used: genesis.blob_gas_used.unwrap_or(0),
+                excess_gas: genesis.excess_blob_gas.unwrap_or(0),
+            });
+            self.initial_parent_beacon_block_root = Some(B256::ZERO);
+        }
+
+        for (address, account) in genesis.alloc {
+            let code = account.code.map(Bytecode::new_raw);
+
+            self.genesis_accounts.insert(
+                address,
+                AccountInfo {
+                    balance: account.balance,
+                    nonce: account.nonce,
+                    code_hash: code.as_ref().map_or(KECCAK_EMPTY, Bytecode::hash_slow),
+                    code,
+                },
+            );
+
+            if !account.storage.is_empty() {
+                self.genesis_storage.insert(
+                    address,
+                    account
+                        .storage
+                        .into_iter()
+                        .map(|(index, value)| {
+                            (U256::from_be_bytes(index.0), U256::from_be_bytes(value.0))
+                        })
+                        .collect(),
+                );
+            }
+        }
+    }
 }
 
 /// Configuration input for a single account
@@ -2,6 +2,7 @@ mod account;
 mod call;
 mod gas;
 mod inspector;
+mod simulate;
 
 use std::{
     cmp,
@@ -14,36 +15,55 @@ use std::{
 };
 
 use edr_eth::{
-    block::{calculate_next_base_fee, miner_reward, BlobGas, BlockOptions},
+    access_list::AccessList,
+    block::{calculate_next_base_fee, miner_reward, BlobGas, BlockOptions, Header, PartialHeader},
+    genesis::Genesis,
     log::FilterLog,
     receipt::BlockReceipt,
     remote::{
         client::{HeaderMap, HttpError},
-        eth::FeeHistoryResult,
+        eth::{AccountProof, FeeHistoryResult},
         filter::{FilteredEvents, LogOutput, SubscriptionType},
-        BlockSpec, BlockTag, Eip1898BlockSpec, RpcClient, RpcClientError,
+        BlockOverrides, BlockSpec, BlockTag, Eip1898BlockSpec, RpcClient, RpcClientError,
     },
     reward_percentile::RewardPercentile,
     signature::{RecoveryMessage, Signature},
-    transaction::{SignedTransaction, TransactionRequestAndSender},
+    spec::{optimism_hardfork_at_block, ForkCondition, HardforkActivations, OptimismHardfork},
+    transaction::{BlobSidecar, SignedTransaction, TransactionRequestAndSender},
+    withdrawal::Withdrawal,
     Address, Bytes, SpecId, B256, U256,
 };
 use edr_evm::{
+    beacon_roots::{beacon_roots_contract, BEACON_ROOTS_ADDRESS},
     blockchain::{
-        Blockchain, BlockchainError, ForkedBlockchain, ForkedCreationError, LocalBlockchain,
-        LocalCreationError, SyncBlockchain,
+        export_genesis, Blockchain, BlockchainError, ForkedBlockchain, ForkedCreationError,
+        HistoryEntry, LocalBlockchain, LocalCreationError, SyncBlockchain,
     },
+    calc_blob_gasprice, calc_excess_blob_gas,
     db::StateRef,
-    debug_trace_transaction, execution_result_to_debug_result, mempool, mine_block,
+    debug_trace_block, debug_trace_transaction, gas_profile_transaction, mempool, mine_block,
+    parity_trace_block, parity_trace_transaction,
     state::{
         AccountModifierFn, IrregularState, StateDiff, StateError, StateOverride, StateOverrides,
         SyncState,
     },
     trace::{Trace, TraceCollector},
-    Account, AccountInfo, BlobExcessGasAndPrice, Block, BlockEnv, Bytecode, CfgEnv,
-    DebugTraceConfig, DebugTraceResult, DualInspector, ExecutableTransaction, ExecutionResult,
-    HashMap, HashSet, MemPool, OrderedTransaction, RandomHashGenerator, StorageSlot, SyncBlock,
-    TracerEip3155, TxEnv, KECCAK_EMPTY,
+    AccessListTracer, Account, AccountInfo, AccountStatus, BlobExcessGasAndPrice, Block, BlockEnv,
+    Bytecode, CfgEnv, DebugInspector, DebugTraceBlockResult, DebugTraceConfig, DebugTraceOutput,
+    DualInspector, ExecutableTransaction, ExecutionResult, GasProfile, HashMap, HashSet,
+    LocalBlock, MemPool, MemPoolAddTransactionError, OrderedTransaction, ParityTraceConfig,
+    RandomHashGenerator, StorageSlot, SyncBlock, TraceResults, TxEnv, KECCAK_EMPTY,
+};
+use edr_solidity::{
+    artifacts::{CompilerInput, CompilerOutput},
+    build_model::{BuildModel, BuildModelError},
+    console_log::decode_console_log_inputs,
+    contracts_identifier::ContractsIdentifier,
+    coverage::{CoverageCollector, CoverageReport},
+    gas_report::{GasProfiler, GasReport},
+    message_trace::MessageTrace,
+    solidity_stack_trace::StackTraceEntry,
+    solidity_tracer::solidity_stack_trace,
 };
 use ethers_core::types::transaction::eip712::{Eip712, TypedData};
 use gas::gas_used_ratio;
@@ -51,16 +71,18 @@ use indexmap::IndexMap;
 use itertools::izip;
 use lazy_static::lazy_static;
 use lru::LruCache;
+use parking_lot::{Mutex, RwLock};
 use tokio::runtime;
 
 use self::{
     account::{create_accounts, InitialAccounts},
     gas::{BinarySearchEstimationResult, CheckGasResult},
-    inspector::EvmInspector,
+    simulate::{run_simulation, RunSimulationArgs},
 };
+pub use self::{inspector::EvmInspector, simulate::SimulatedBlock};
 use crate::{
     data::{
-        call::{run_call, RunCallArgs},
+        call::{run_call_with_state_changes, RunCallArgs},
         gas::{compute_rewards, BinarySearchEstimationArgs, CheckGasLimitArgs},
     },
     debug_mine::{DebugMineBlockResult, DebugMineBlockResultAndState},
@@ -68,8 +90,15 @@ use crate::{
     filter::{bloom_contains_log_filter, filter_logs, Filter, FilterData, LogFilter},
     logger::SyncLogger,
     pending::BlockchainWithPending,
-    requests::hardhat::rpc_types::{ForkConfig, ForkMetadata},
+    requests::{
+        eth::SimulatedBlockResult,
+        hardhat::rpc_types::{ForkConfig, ForkMetadata, WithdrawalRequest},
+    },
     snapshot::Snapshot,
+    state_dump::{
+        block_to_transaction_receipt, BlobSidecarDump, BlockDump, HistoryEntryDump, StateDump,
+        StateDumpError, TransactionDump, STATE_DUMP_VERSION,
+    },
     MiningConfig, ProviderConfig, ProviderError, SubscriptionEvent, SubscriptionEventData,
     SyncSubscriberCallback,
 };
@@ -81,6 +110,7 @@ const MAX_CACHED_STATES: usize = 64;
 #[derive(Clone, Debug)]
 pub struct CallResult {
     pub console_log_inputs: Vec<Bytes>,
+    pub console_log_messages: Vec<String>,
     pub execution_result: ExecutionResult,
     pub trace: Trace,
 }
@@ -117,6 +147,9 @@ pub enum CreationError {
     /// An error that occured while querying the remote state.
     #[error(transparent)]
     RpcClient(#[from] RpcClientError),
+    /// An error that occurred while restoring a state dump.
+    #[error(transparent)]
+    StateDump(#[from] StateDumpError),
 }
 
 pub struct ProviderData<LoggerErrorT: Debug> {
@@ -126,9 +159,13 @@ pub struct ProviderData<LoggerErrorT: Debug> {
     pub irregular_state: IrregularState,
     mem_pool: MemPool,
     beneficiary: Address,
-    dao_activation_block: Option<u64>,
+    dao_activation: Option<ForkCondition>,
+    // The hardfork activations of the local blockchain, or of the remote
+    // blockchain when forking
+    hardfork_activations: Option<HardforkActivations>,
     min_gas_price: U256,
     prev_randao_generator: RandomHashGenerator,
+    parent_beacon_block_root_generator: RandomHashGenerator,
     block_time_offset_seconds: i64,
     fork_metadata: Option<ForkMetadata>,
     // Must be set if the provider is created with a fork config.
@@ -146,6 +183,18 @@ pub struct ProviderData<LoggerErrorT: Debug> {
     local_accounts: IndexMap<Address, k256::SecretKey>,
     filters: HashMap<U256, Filter>,
     last_filter_id: U256,
+    // Blob sidecars of pending EIP-4844 transactions, by transaction hash
+    pending_blob_sidecars: HashMap<B256, BlobSidecar>,
+    // Blob sidecars of mined EIP-4844 transactions, by block number and
+    // transaction hash
+    blob_sidecars: BTreeMap<u64, HashMap<B256, BlobSidecar>>,
+    // Withdrawals to include in the next mined block
+    queued_withdrawals: Vec<WithdrawalRequest>,
+    next_withdrawal_index: u64,
+    // Safe and finalized blocks that were set explicitly, which take precedence
+    // over the configured lag
+    safe_block_number: Option<u64>,
+    finalized_block_number: Option<u64>,
     logger: Box<dyn SyncLogger<BlockchainError = BlockchainError, LoggerError = LoggerErrorT>>,
     impersonated_accounts: HashSet<Address>,
     subscriber_callback: Box<dyn SyncSubscriberCallback>,
@@ -154,6 +203,9 @@ pub struct ProviderData<LoggerErrorT: Debug> {
     block_state_cache: LruCache<StateId, Arc<Box<dyn SyncState<StateError>>>>,
     current_state_id: StateId,
     block_number_to_state_id: BTreeMap<u64, StateId>,
+    contracts_identifier: Arc<RwLock<ContractsIdentifier>>,
+    coverage: Option<CoverageCollector>,
+    gas_profiler: GasProfiler,
 }
 
 impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
@@ -162,6 +214,22 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
         logger: Box<dyn SyncLogger<BlockchainError = BlockchainError, LoggerError = LoggerErrorT>>,
         subscriber_callback: Box<dyn SyncSubscriberCallback>,
         config: ProviderConfig,
+    ) -> Result<Self, CreationError> {
+        if let Some(state_path) = &config.state_path {
+            let dump = StateDump::read_from_file(state_path)?;
+            Self::with_state_dump(runtime_handle, logger, subscriber_callback, config, dump)
+        } else {
+            Self::from_config(runtime_handle, logger, subscriber_callback, config)
+        }
+    }
+
+    fn from_config(
+        runtime_handle: runtime::Handle,
+        mut logger: Box<
+            dyn SyncLogger<BlockchainError = BlockchainError, LoggerError = LoggerErrorT>,
+        >,
+        subscriber_callback: Box<dyn SyncSubscriberCallback>,
+        config: ProviderConfig,
     ) -> Result<Self, CreationError> {
         let InitialAccounts {
             local_accounts,
@@ -177,6 +245,7 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
             prev_randao_generator,
             block_time_offset_seconds,
             next_block_base_fee_per_gas,
+            hardfork_activations,
         } = create_blockchain_and_state(runtime_handle.clone(), &config, genesis_accounts)?;
 
         let mut block_state_cache =
@@ -194,11 +263,19 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
         let is_auto_mining = config.mining.auto_mine;
         let min_gas_price = config.min_gas_price;
 
-        let dao_activation_block = config
+        let dao_activation = config
             .chains
             .get(&config.chain_id)
             .and_then(|config| config.hardfork_activation(SpecId::DAO_FORK));
 
+        let coverage = config.enable_coverage.then(CoverageCollector::default);
+
+        let next_withdrawal_index =
+            next_withdrawal_index(&*blockchain.last_block().map_err(CreationError::Blockchain)?);
+
+        let contracts_identifier = Arc::new(RwLock::new(ContractsIdentifier::default()));
+        logger.set_contracts_identifier(contracts_identifier.clone());
+
         Ok(Self {
             runtime_handle,
             initial_config: config,
@@ -206,9 +283,13 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
             irregular_state,
             mem_pool: MemPool::new(block_gas_limit),
             beneficiary,
-            dao_activation_block,
+            dao_activation,
+            hardfork_activations,
             min_gas_price,
             prev_randao_generator,
+            parent_beacon_block_root_generator: RandomHashGenerator::with_seed(
+                edr_defaults::PARENT_BEACON_BLOCK_ROOT_SEED,
+            ),
             block_time_offset_seconds,
             fork_metadata,
             rpc_client,
@@ -224,20 +305,84 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
             local_accounts,
             filters: HashMap::default(),
             last_filter_id: U256::ZERO,
+            pending_blob_sidecars: HashMap::default(),
+            blob_sidecars: BTreeMap::new(),
+            queued_withdrawals: Vec::new(),
+            next_withdrawal_index,
+            safe_block_number: None,
+            finalized_block_number: None,
             logger,
             impersonated_accounts: HashSet::new(),
             subscriber_callback,
             block_state_cache,
             current_state_id,
             block_number_to_state_id,
+            contracts_identifier,
+            coverage,
+            gas_profiler: GasProfiler::default(),
         })
     }
 
+    /// Restores the provider's state from a state dump, created with
+    /// [`ProviderData::dump_state`].
+    fn with_state_dump(
+        runtime_handle: runtime::Handle,
+        logger: Box<dyn SyncLogger<BlockchainError = BlockchainError, LoggerError = LoggerErrorT>>,
+        subscriber_callback: Box<dyn SyncSubscriberCallback>,
+        mut config: ProviderConfig,
+        dump: StateDump,
+    ) -> Result<Self, CreationError> {
+        if dump.version != STATE_DUMP_VERSION {
+            return Err(StateDumpError::UnsupportedVersion {
+                expected: STATE_DUMP_VERSION,
+                actual: dump.version,
+            }
+            .into());
+        }
+
+        if dump.chain_id != config.chain_id {
+            return Err(StateDumpError::ChainIdMismatch {
+                expected: config.chain_id,
+                actual: dump.chain_id,
+            }
+            .into());
+        }
+
+        if dump.hardfork != config.hardfork {
+            return Err(StateDumpError::HardforkMismatch {
+                expected: config.hardfork,
+                actual: dump.hardfork,
+            }
+            .into());
+        }
+
+        match (config.fork.as_mut(), dump.fork_block_number) {
+            (Some(fork_config), Some(fork_block_number)) => {
+                fork_config.block_number = Some(fork_block_number);
+            }
+            (None, None) => (),
+            (fork_config, _) => {
+                return Err(StateDumpError::ForkMismatch {
+                    dump_is_forked: dump.fork_block_number.is_some(),
+                    provider_is_forked: fork_config.is_some(),
+                }
+                .into())
+            }
+        }
+
+        let mut data = Self::from_config(runtime_handle, logger, subscriber_callback, config)?;
+        data.restore(dump)?;
+
+        Ok(data)
+    }
+
     pub fn reset(&mut self, fork_config: Option<ForkConfig>) -> Result<(), CreationError> {
         let mut config = self.initial_config.clone();
         config.fork = fork_config;
+        // The state dump is only loaded when the provider is created
+        config.state_path = None;
 
-        let mut reset_instance = Self::new(
+        let mut reset_instance = Self::from_config(
             self.runtime_handle.clone(),
             self.logger.clone(),
             self.subscriber_callback.clone(),
@@ -246,9 +391,67 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
 
         std::mem::swap(self, &mut reset_instance);
 
+        // Compilation results, coverage and gas usage are independent of the
+        // chain state
+        *self.contracts_identifier.write() =
+            std::mem::take(&mut *reset_instance.contracts_identifier.write());
+        self.coverage = reset_instance.coverage.take();
+        self.gas_profiler = std::mem::take(&mut reset_instance.gas_profiler);
+
+        Ok(())
+    }
+
+    /// Adds the bytecodes of a compilation result, so they can be used to
+    /// compute Solidity stack traces.
+    pub fn add_compilation_result(
+        &mut self,
+        solc_version: String,
+        compiler_input: &CompilerInput,
+        compiler_output: &CompilerOutput,
+    ) -> Result<(), BuildModelError> {
+        let build_model = BuildModel::new(solc_version, compiler_input, compiler_output)?;
+        let mut contracts_identifier = self.contracts_identifier.write();
+        for bytecode in build_model.bytecodes {
+            contracts_identifier.add_bytecode(bytecode);
+        }
+
         Ok(())
     }
 
+    /// Computes the Solidity stack trace of a failed transaction's trace.
+    /// Returns `None` if no stack trace could be computed.
+    pub fn solidity_stack_trace(&self, trace: &Trace) -> Option<Vec<StackTraceEntry>> {
+        let message_trace = MessageTrace::from_trace(trace)?;
+        let stack_trace = solidity_stack_trace(&self.contracts_identifier.read(), &message_trace);
+
+        if stack_trace.is_empty() {
+            None
+        } else {
+            Some(stack_trace)
+        }
+    }
+
+    /// Maps the instructions executed since the provider was created to the
+    /// sources of the added compilation results. Returns `None` if coverage
+    /// collection is disabled.
+    pub fn coverage_report(&self) -> Option<CoverageReport> {
+        self.coverage
+            .as_ref()
+            .map(|coverage| coverage.report(&self.contracts_identifier.read()))
+    }
+
+    /// Attributes the gas used by the frames executed since the provider was
+    /// created, or since the last [`ProviderData::reset_gas_report`], to the
+    /// contracts of the added compilation results.
+    pub fn gas_report(&self) -> GasReport {
+        self.gas_profiler.report(&self.contracts_identifier.read())
+    }
+
+    /// Discards the gas usage recorded so far.
+    pub fn reset_gas_report(&mut self) {
+        self.gas_profiler.clear();
+    }
+
     /// Retrieves the last pending nonce of the account corresponding to the
     /// provided address, if it exists.
     pub fn account_next_nonce(
@@ -318,6 +521,50 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
         self.blockchain.last_block_number()
     }
 
+    /// Retrieves the number of the finalized block: the block set with
+    /// `hardhat_setFinalizedBlock`, or the block that lags behind the last
+    /// block by the configured number of blocks.
+    pub fn finalized_block_number(&self) -> u64 {
+        let last_block_number = self.last_block_number();
+
+        self.finalized_block_number.map_or_else(
+            || {
+                let lag = self
+                    .initial_config
+                    .finality
+                    .as_ref()
+                    .map_or(0, |finality| finality.finalized_block_lag);
+
+                last_block_number.saturating_sub(lag)
+            },
+            // The block might have been removed by a reorg or snapshot revert
+            |block_number| block_number.min(last_block_number),
+        )
+    }
+
+    /// Retrieves the number of the safe block: the block set with
+    /// `hardhat_setSafeBlock`, or the block that lags behind the last block by
+    /// the configured number of blocks. The safe block is never older than
+    /// the finalized block.
+    pub fn safe_block_number(&self) -> u64 {
+        let last_block_number = self.last_block_number();
+
+        let safe_block_number = self.safe_block_number.map_or_else(
+            || {
+                let lag = self
+                    .initial_config
+                    .finality
+                    .as_ref()
+                    .map_or(0, |finality| finality.safe_block_lag);
+
+                last_block_number.saturating_sub(lag)
+            },
+            |block_number| block_number.min(last_block_number),
+        );
+
+        safe_block_number.max(self.finalized_block_number())
+    }
+
     /// Adds a filter for new blocks to the provider.
     pub fn add_block_filter<const IS_SUBSCRIPTION: bool>(
         &mut self,
@@ -405,17 +652,16 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
                     .block_by_number(0)?
                     .expect("genesis block should always exist"),
             ),
-            // Matching Hardhat behaviour by returning the last block for finalized and safe.
+            // Without a configured lag, this matches Hardhat behaviour by returning the last
+            // block for finalized and safe.
             // https://github.com/NomicFoundation/hardhat/blob/b84baf2d9f5d3ea897c06e0ecd5e7084780d8b6c/packages/hardhat-core/src/internal/hardhat-network/provider/modules/eth.ts#L1395
             BlockSpec::Tag(tag @ (BlockTag::Finalized | BlockTag::Safe)) => {
-                if self.spec_id() >= SpecId::MERGE {
-                    Some(self.blockchain.last_block()?)
-                } else {
-                    return Err(ProviderError::InvalidBlockTag {
-                        block_tag: *tag,
-                        spec: self.spec_id(),
-                    });
-                }
+                let block_number = self.post_merge_block_number(*tag)?;
+                Some(
+                    self.blockchain
+                        .block_by_number(block_number)?
+                        .expect("The safe and finalized blocks are at most the last block"),
+                )
             }
             BlockSpec::Tag(BlockTag::Latest) => Some(self.blockchain.last_block()?),
             BlockSpec::Tag(BlockTag::Pending) => None,
@@ -450,14 +696,7 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
             BlockSpec::Number(number) => Some(*number),
             BlockSpec::Tag(BlockTag::Earliest) => Some(0),
             BlockSpec::Tag(tag @ (BlockTag::Finalized | BlockTag::Safe)) => {
-                if self.spec_id() >= SpecId::MERGE {
-                    Some(self.blockchain.last_block_number())
-                } else {
-                    return Err(ProviderError::InvalidBlockTag {
-                        block_tag: *tag,
-                        spec: self.spec_id(),
-                    });
-                }
+                Some(self.post_merge_block_number(*tag)?)
             }
             BlockSpec::Tag(BlockTag::Latest) => Some(self.blockchain.last_block_number()),
             BlockSpec::Tag(BlockTag::Pending) => None,
@@ -478,6 +717,25 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
         Ok(block_number)
     }
 
+    /// Resolves the `safe` or `finalized` block tag to a block number.
+    ///
+    /// Returns `ProviderError::InvalidBlockTag` error if the hardfork is
+    /// pre-merge.
+    fn post_merge_block_number(&self, tag: BlockTag) -> Result<u64, ProviderError<LoggerErrorT>> {
+        if self.spec_id() < SpecId::MERGE {
+            return Err(ProviderError::InvalidBlockTag {
+                block_tag: tag,
+                spec: self.spec_id(),
+            });
+        }
+
+        if tag == BlockTag::Safe {
+            Ok(self.safe_block_number())
+        } else {
+            Ok(self.finalized_block_number())
+        }
+    }
+
     pub fn block_by_hash(
         &self,
         block_hash: &B256,
@@ -501,102 +759,235 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
         &mut self,
         transaction_hash: &B256,
         trace_config: DebugTraceConfig,
-    ) -> Result<DebugTraceResult, ProviderError<LoggerErrorT>> {
+    ) -> Result<DebugTraceOutput, ProviderError<LoggerErrorT>> {
         let block = self
             .blockchain
             .block_by_transaction_hash(transaction_hash)?
             .ok_or_else(|| ProviderError::InvalidTransactionHash(*transaction_hash))?;
 
         let header = block.header();
-        let block_spec = Some(BlockSpec::Number(header.number));
+        let optimism_hardfork = self.optimism_hardfork(header.number, header.timestamp);
+        let transactions = block.transactions().to_vec();
 
-        let cfg_env = self.create_evm_config(block_spec.as_ref())?;
+        self.execute_in_parent_block_context(header, |blockchain, state, cfg_env, block_env| {
+            debug_trace_transaction(
+                blockchain,
+                state.clone(),
+                cfg_env,
+                trace_config,
+                block_env,
+                header.parent_beacon_block_root,
+                transactions,
+                transaction_hash,
+                optimism_hardfork,
+            )
+            .map_err(ProviderError::DebugTrace)
+        })
+    }
 
-        let transactions = block.transactions().to_vec();
+    pub fn debug_trace_block(
+        &mut self,
+        header: &Header,
+        transactions: Vec<ExecutableTransaction>,
+        trace_config: DebugTraceConfig,
+    ) -> Result<Vec<DebugTraceBlockResult>, ProviderError<LoggerErrorT>> {
+        let optimism_hardfork = self.optimism_hardfork(header.number, header.timestamp);
 
-        let prev_block_number = block.header().number - 1;
-        let prev_block_spec = Some(BlockSpec::Number(prev_block_number));
+        self.execute_in_parent_block_context(header, |blockchain, state, cfg_env, block_env| {
+            debug_trace_block(
+                blockchain,
+                state.clone(),
+                cfg_env,
+                trace_config,
+                block_env,
+                header.parent_beacon_block_root,
+                transactions,
+                optimism_hardfork,
+            )
+            .map_err(ProviderError::DebugTrace)
+        })
+    }
 
-        self.execute_in_block_context(
-            prev_block_spec.as_ref(),
-            |blockchain, _prev_block, state| {
-                let block_env = BlockEnv {
-                    number: U256::from(header.number),
-                    coinbase: header.beneficiary,
-                    timestamp: U256::from(header.timestamp),
-                    gas_limit: U256::from(header.gas_limit),
-                    basefee: header.base_fee_per_gas.unwrap_or_default(),
-                    difficulty: U256::from(header.difficulty),
-                    prevrandao: if cfg_env.spec_id >= SpecId::MERGE {
-                        Some(header.mix_hash)
-                    } else {
-                        None
-                    },
-                    blob_excess_gas_and_price: header
-                        .blob_gas
-                        .as_ref()
-                        .map(|BlobGas { excess_gas, .. }| BlobExcessGasAndPrice::new(*excess_gas)),
-                };
+    /// Runs the transaction as a call and records the accessed addresses and
+    /// storage slots, starting from the transaction's access list.
+    pub fn create_access_list(
+        &mut self,
+        transaction: ExecutableTransaction,
+        block_spec: &BlockSpec,
+    ) -> Result<(ExecutionResult, AccessList), ProviderError<LoggerErrorT>> {
+        let cfg_env = self.create_evm_config(Some(block_spec))?;
 
-                debug_trace_transaction(
-                    blockchain,
-                    state.clone(),
-                    cfg_env,
-                    trace_config,
-                    block_env,
-                    transactions,
-                    transaction_hash,
-                )
-                .map_err(ProviderError::DebugTrace)
-            },
-        )?
+        let mut tracer = AccessListTracer::new(
+            transaction
+                .access_list()
+                .map_or(&[][..], |access_list| &access_list.0[..]),
+            *transaction.caller(),
+            transaction.to(),
+        );
+
+        let tx_env: TxEnv = transaction.into();
+
+        self.execute_in_block_context(Some(block_spec), |blockchain, block, state| {
+            let execution_result = call::run_call(RunCallArgs {
+                blockchain,
+                header: block.header(),
+                state,
+                state_overrides: &StateOverrides::default(),
+                block_overrides: &BlockOverrides::default(),
+                cfg_env,
+                tx_env,
+                inspector: Some(&mut tracer),
+            })?;
+
+            Ok((execution_result, tracer.into_access_list()))
+        })?
     }
 
     pub fn debug_trace_call(
         &mut self,
         transaction: ExecutableTransaction,
         block_spec: Option<&BlockSpec>,
+        block_overrides: &BlockOverrides,
         trace_config: DebugTraceConfig,
-    ) -> Result<DebugTraceResult, ProviderError<LoggerErrorT>> {
+    ) -> Result<DebugTraceOutput, ProviderError<LoggerErrorT>> {
         let cfg_env = self.create_evm_config(block_spec)?;
 
         let tx_env: TxEnv = transaction.into();
 
-        let mut tracer = TracerEip3155::new(trace_config);
+        let mut inspector = DebugInspector::new(trace_config);
 
         self.execute_in_block_context(block_spec, |blockchain, block, state| {
-            let result = run_call(RunCallArgs {
+            let result = run_call_with_state_changes(RunCallArgs {
                 blockchain,
                 header: block.header(),
                 state,
                 state_overrides: &StateOverrides::default(),
+                block_overrides,
                 cfg_env: cfg_env.clone(),
                 tx_env: tx_env.clone(),
-                inspector: Some(&mut tracer),
+                inspector: inspector.as_dyn_inspector(),
             })?;
 
-            Ok(execution_result_to_debug_result(result, tracer))
+            inspector
+                .into_output(result, &**state)
+                .map_err(ProviderError::State)
         })?
     }
 
+    pub fn trace_block(
+        &mut self,
+        header: &Header,
+        transactions: Vec<ExecutableTransaction>,
+        trace_config: ParityTraceConfig,
+    ) -> Result<Vec<TraceResults>, ProviderError<LoggerErrorT>> {
+        // Avoids tracing the genesis block, which doesn't have a parent
+        if transactions.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let optimism_hardfork = self.optimism_hardfork(header.number, header.timestamp);
+
+        self.execute_in_parent_block_context(header, |blockchain, state, cfg_env, block_env| {
+            parity_trace_block(
+                blockchain,
+                state.clone(),
+                cfg_env,
+                trace_config,
+                block_env,
+                header.parent_beacon_block_root,
+                transactions,
+                optimism_hardfork,
+            )
+            .map_err(ProviderError::DebugTrace)
+        })
+    }
+
+    pub fn trace_replay_transaction(
+        &mut self,
+        transaction_hash: &B256,
+        trace_config: ParityTraceConfig,
+    ) -> Result<TraceResults, ProviderError<LoggerErrorT>> {
+        let block = self
+            .blockchain
+            .block_by_transaction_hash(transaction_hash)?
+            .ok_or_else(|| ProviderError::InvalidTransactionHash(*transaction_hash))?;
+
+        let header = block.header();
+        let optimism_hardfork = self.optimism_hardfork(header.number, header.timestamp);
+        let transactions = block.transactions().to_vec();
+
+        self.execute_in_parent_block_context(header, |blockchain, state, cfg_env, block_env| {
+            parity_trace_transaction(
+                blockchain,
+                state.clone(),
+                cfg_env,
+                trace_config,
+                block_env,
+                header.parent_beacon_block_root,
+                transactions,
+                transaction_hash,
+                optimism_hardfork,
+            )
+            .map_err(ProviderError::DebugTrace)
+        })
+    }
+
+    /// Replays the transaction with the provided hash and attributes its gas
+    /// usage to its call frames.
+    pub fn gas_profile_transaction(
+        &mut self,
+        transaction_hash: &B256,
+    ) -> Result<GasProfile, ProviderError<LoggerErrorT>> {
+        let block = self
+            .blockchain
+            .block_by_transaction_hash(transaction_hash)?
+            .ok_or_else(|| ProviderError::InvalidTransactionHash(*transaction_hash))?;
+
+        let header = block.header();
+        let optimism_hardfork = self.optimism_hardfork(header.number, header.timestamp);
+        let transactions = block.transactions().to_vec();
+
+        self.execute_in_parent_block_context(header, |blockchain, state, cfg_env, block_env| {
+            gas_profile_transaction(
+                blockchain,
+                state.clone(),
+                cfg_env,
+                block_env,
+                header.parent_beacon_block_root,
+                transactions,
+                transaction_hash,
+                optimism_hardfork,
+            )
+            .map_err(ProviderError::DebugTrace)
+        })
+    }
+
     /// Estimate the gas cost of a transaction. Matches Hardhat behavior.
+    ///
+    /// On OP-stack chains, the estimate only covers L2 gas, like OP-stack
+    /// nodes do. The L1 data fee isn't paid in gas, so it can't be part of the
+    /// estimate; it's quoted by the `GasPriceOracle` predeploy instead.
     pub fn estimate_gas(
         &mut self,
         transaction: ExecutableTransaction,
         block_spec: &BlockSpec,
+        state_overrides: &StateOverrides,
+        block_overrides: &BlockOverrides,
     ) -> Result<EstimateGasResult, ProviderError<LoggerErrorT>> {
-        let cfg_env = self.create_evm_config(Some(block_spec))?;
+        let block_spec = Some(block_spec);
+        let cfg_env = self.create_evm_config(block_spec)?;
         // Minimum gas cost that is required for transaction to be included in
         // a block
         let minimum_cost = transaction.initial_cost(self.spec_id());
         let transaction_hash = *transaction.hash();
         let tx_env: TxEnv = transaction.into();
+        let enable_cheatcodes = self.initial_config.enable_cheatcodes;
 
-        let state_overrides = StateOverrides::default();
-
-        self.execute_in_block_context(Some(block_spec), |blockchain, block, state| {
-            let mut inspector =
-                DualInspector::new(EvmInspector::default(), TraceCollector::default());
+        let result = self.execute_in_block_context(block_spec, |blockchain, block, state| {
+            let mut inspector = DualInspector::new(
+                TraceCollector::default(),
+                EvmInspector::new(enable_cheatcodes),
+            );
 
             let header = block.header();
 
@@ -607,13 +998,14 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
                 blockchain,
                 header,
                 state,
-                state_overrides: &state_overrides,
+                state_overrides,
+                block_overrides,
                 cfg_env: cfg_env.clone(),
                 tx_env: tx_env.clone(),
                 inspector: Some(&mut inspector),
             })?;
 
-            let (debug_inspector, tracer) = inspector.into_parts();
+            let (tracer, debug_inspector) = inspector.into_parts();
             let trace = tracer.into_trace();
 
             let mut initial_estimation = match result {
@@ -629,12 +1021,17 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
                     trace.clone(),
                 )),
             }
-            .map_err(|failure| EstimateGasFailure {
-                console_log_inputs: debug_inspector.into_console_log_encoded_messages(),
-                transaction_failure: TransactionFailureWithTraces {
-                    traces: vec![failure.solidity_trace.clone()],
-                    failure,
-                },
+            .map_err(|failure| {
+                let console_log_inputs = debug_inspector.into_console_log_encoded_messages();
+
+                EstimateGasFailure {
+                    console_log_messages: decode_console_log_inputs(&console_log_inputs),
+                    console_log_inputs,
+                    transaction_failure: TransactionFailureWithTraces {
+                        traces: vec![failure.solidity_trace.clone()],
+                        failure,
+                    },
+                }
             })?;
 
             // Ensure that the initial estimation is at least the minimum cost + 1.
@@ -649,10 +1046,12 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
                 blockchain,
                 header,
                 state,
-                state_overrides: &state_overrides,
+                state_overrides,
+                block_overrides,
                 cfg_env: cfg_env.clone(),
                 tx_env: tx_env.clone(),
                 gas_limit: initial_estimation,
+                enable_cheatcodes,
             })?;
 
             traces.push(trace);
@@ -675,17 +1074,27 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
                 blockchain,
                 header,
                 state,
-                state_overrides: &state_overrides,
+                state_overrides,
+                block_overrides,
                 cfg_env: cfg_env.clone(),
                 tx_env: tx_env.clone(),
                 lower_bound: initial_estimation,
-                upper_bound: header.gas_limit,
+                upper_bound: block_overrides.gas_limit.unwrap_or(header.gas_limit),
+                enable_cheatcodes,
             })?;
 
             traces.append(&mut estimation_traces);
 
             Ok(EstimateGasResult { estimation, traces })
-        })?
+        })??;
+
+        // Only the initial run measures the gas used with an unconstrained gas
+        // limit. The remaining runs are part of the search.
+        if let Some(trace) = result.traces.first() {
+            self.gas_profiler.record(trace);
+        }
+
+        Ok(result)
     }
 
     // Matches Hardhat implementation
@@ -900,6 +1309,48 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
             .transpose()
     }
 
+    /// Returns the EIP-1186 proof of the account and storage slots. Proofs of
+    /// blocks up to and including the fork block are requested from the
+    /// remote node, as the local state roots of a fork can't be proven.
+    pub fn get_proof(
+        &mut self,
+        address: Address,
+        storage_keys: Vec<U256>,
+        block_spec: Option<&BlockSpec>,
+    ) -> Result<AccountProof, ProviderError<LoggerErrorT>> {
+        if let Some(ForkMetadata {
+            fork_block_number, ..
+        }) = self.fork_metadata.as_ref()
+        {
+            let block_number = match block_spec {
+                Some(block_spec) => self.block_number_by_block_spec(block_spec)?,
+                None => Some(self.blockchain.last_block_number()),
+            };
+
+            if let Some(block_number) = block_number {
+                if block_number <= *fork_block_number {
+                    let rpc_client = self
+                        .rpc_client
+                        .as_ref()
+                        .expect("the rpc client exists in fork mode");
+
+                    return Ok(tokio::task::block_in_place(|| {
+                        self.runtime_handle.block_on(rpc_client.get_proof(
+                            address,
+                            storage_keys,
+                            BlockSpec::Number(block_number),
+                        ))
+                    })?);
+                }
+            }
+        }
+
+        self.execute_in_block_context::<Result<AccountProof, ProviderError<LoggerErrorT>>>(
+            block_spec,
+            move |_blockchain, _block, state| Ok(state.proof(address, &storage_keys)?),
+        )?
+    }
+
     pub fn get_storage_at(
         &mut self,
         address: Address,
@@ -971,6 +1422,138 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
             .map_err(ProviderError::Blockchain)
     }
 
+    /// Exports the latest state as a geth-style genesis file. Only supported
+    /// for local blockchains, as the state of a forked blockchain can't be
+    /// enumerated.
+    pub fn dump_genesis(&self) -> Result<Genesis, ProviderError<LoggerErrorT>> {
+        if self.fork_metadata.is_some() {
+            return Err(ProviderError::GenesisExportForked);
+        }
+
+        let genesis = export_genesis(&*self.blockchain, self.irregular_state.state_overrides())?;
+        Ok(genesis)
+    }
+
+    /// Creates a dump of the provider's state, which can be restored with
+    /// [`ProviderData::load_state`].
+    pub fn dump_state(&self) -> Result<StateDump, ProviderError<LoggerErrorT>> {
+        let history = self
+            .blockchain
+            .local_history()?
+            .into_iter()
+            .map(|entry| match entry {
+                HistoryEntry::Block { block, state_diff } => {
+                    let hardfork = self.spec_at_block_number(block.header().number)?;
+
+                    Ok(HistoryEntryDump::Block(BlockDump::new(
+                        &*block, hardfork, state_diff,
+                    )?))
+                }
+                HistoryEntry::Reservation {
+                    additional,
+                    interval,
+                } => Ok(HistoryEntryDump::Reservation {
+                    additional,
+                    interval,
+                }),
+            })
+            .collect::<Result<Vec<_>, ProviderError<LoggerErrorT>>>()?;
+
+        // Re-adding the transactions in their original order results in the same mem
+        // pool
+        let mut transactions = self
+            .mem_pool
+            .pending_transactions()
+            .chain(self.mem_pool.future_transactions())
+            .collect::<Vec<_>>();
+        transactions.sort_by_key(|transaction| transaction.order_id());
+
+        let mut impersonated_accounts = self
+            .impersonated_accounts
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        impersonated_accounts.sort();
+
+        let mined_blob_sidecars = self
+            .blob_sidecars
+            .iter()
+            .flat_map(|(block_number, sidecars)| {
+                sidecars.iter().map(|(transaction_hash, sidecar)| {
+                    (Some(*block_number), transaction_hash, sidecar)
+                })
+            });
+        let pending_blob_sidecars = self
+            .pending_blob_sidecars
+            .iter()
+            .map(|(transaction_hash, sidecar)| (None, transaction_hash, sidecar));
+
+        // Sorted to make the dump deterministic
+        let mut blob_sidecars = mined_blob_sidecars
+            .chain(pending_blob_sidecars)
+            .map(
+                |(block_number, transaction_hash, sidecar)| BlobSidecarDump {
+                    block_number,
+                    transaction_hash: *transaction_hash,
+                    sidecar: sidecar.clone(),
+                },
+            )
+            .collect::<Vec<_>>();
+        blob_sidecars.sort_by_key(|dump| (dump.block_number, dump.transaction_hash));
+
+        Ok(StateDump {
+            version: STATE_DUMP_VERSION,
+            chain_id: self.initial_config.chain_id,
+            hardfork: self.initial_config.hardfork,
+            fork_block_number: self
+                .fork_metadata
+                .as_ref()
+                .map(|metadata| metadata.fork_block_number),
+            history,
+            irregular_state: self.irregular_state.clone(),
+            transactions: transactions
+                .into_iter()
+                .map(|transaction| TransactionDump::from(transaction.pending()))
+                .collect(),
+            block_gas_limit: self.mem_pool.block_gas_limit(),
+            block_time_offset_seconds: self.block_time_offset_seconds,
+            coinbase: self.beneficiary,
+            impersonated_accounts,
+            next_block_base_fee_per_gas: self.next_block_base_fee_per_gas,
+            next_block_timestamp: self.next_block_timestamp,
+            next_prev_randao: self.prev_randao_generator.next_value(),
+            next_parent_beacon_block_root: Some(
+                self.parent_beacon_block_root_generator.next_value(),
+            ),
+            safe_block_number: self.safe_block_number,
+            finalized_block_number: self.finalized_block_number,
+            queued_withdrawals: self.queued_withdrawals.clone(),
+            blob_sidecars,
+        })
+    }
+
+    /// Replaces the provider's state with the state of the provided dump.
+    /// Snapshots, filters and interval mining are reset, but compilation
+    /// results are retained.
+    pub fn load_state(&mut self, dump: StateDump) -> Result<(), CreationError> {
+        let mut loaded_instance = Self::with_state_dump(
+            self.runtime_handle.clone(),
+            self.logger.clone(),
+            self.subscriber_callback.clone(),
+            self.initial_config.clone(),
+            dump,
+        )?;
+
+        std::mem::swap(self, &mut loaded_instance);
+
+        *self.contracts_identifier.write() =
+            std::mem::take(&mut *loaded_instance.contracts_identifier.write());
+        self.coverage = loaded_instance.coverage.take();
+        self.gas_profiler = std::mem::take(&mut loaded_instance.gas_profiler);
+
+        Ok(())
+    }
+
     pub fn make_snapshot(&mut self) -> u64 {
         let id = self.next_snapshot_id;
         self.next_snapshot_id += 1;
@@ -980,11 +1563,17 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
             block_number_to_state_id: self.block_number_to_state_id.clone(),
             block_time_offset_seconds: self.block_time_offset_seconds,
             coinbase: self.beneficiary,
+            finalized_block_number: self.finalized_block_number,
             irregular_state: self.irregular_state.clone(),
             mem_pool: self.mem_pool.clone(),
             next_block_base_fee_per_gas: self.next_block_base_fee_per_gas,
             next_block_timestamp: self.next_block_timestamp,
+            next_withdrawal_index: self.next_withdrawal_index,
+            parent_beacon_block_root_generator: self.parent_beacon_block_root_generator.clone(),
+            pending_blob_sidecars: self.pending_blob_sidecars.clone(),
             prev_randao_generator: self.prev_randao_generator.clone(),
+            queued_withdrawals: self.queued_withdrawals.clone(),
+            safe_block_number: self.safe_block_number,
             time: Instant::now(),
         };
         self.snapshots.insert(id, snapshot);
@@ -999,10 +1588,15 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
         let (block_timestamp, new_offset) = self.next_block_timestamp(options.timestamp)?;
         options.timestamp = Some(block_timestamp);
 
-        if options.mix_hash.is_none() && self.blockchain.spec_id() >= SpecId::MERGE {
+        let spec_id =
+            self.hardfork_at_block(self.blockchain.last_block_number() + 1, block_timestamp);
+        if options.mix_hash.is_none() && spec_id >= SpecId::MERGE {
             options.mix_hash = Some(self.prev_randao_generator.next_value());
         }
 
+        let includes_queued_withdrawals =
+            options.withdrawals.is_none() && options.withdrawals_root.is_none();
+
         let result = self.mine_block(options)?;
 
         let block_and_total_difficulty = self
@@ -1025,10 +1619,31 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
         self.next_block_timestamp.take();
 
         self.prev_randao_generator.generate_next();
+        self.parent_beacon_block_root_generator.generate_next();
 
         let block = &block_and_total_difficulty.block;
-        for (filter_id, filter) in self.filters.iter_mut() {
-            match &mut filter.data {
+
+        if includes_queued_withdrawals {
+            self.queued_withdrawals.clear();
+        }
+
+        if let Some(withdrawal) = block.withdrawals().and_then(<[Withdrawal]>::last) {
+            self.next_withdrawal_index = withdrawal.index + 1;
+        }
+
+        let blob_sidecars: HashMap<B256, BlobSidecar> = block
+            .transactions()
+            .iter()
+            .filter_map(|transaction| self.pending_blob_sidecars.remove_entry(transaction.hash()))
+            .collect();
+
+        if !blob_sidecars.is_empty() {
+            self.blob_sidecars
+                .insert(block.header().number, blob_sidecars);
+        }
+
+        for (filter_id, filter) in self.filters.iter_mut() {
+            match &mut filter.data {
                 FilterData::Logs { criteria, logs } => {
                     let bloom = &block.header().logs_bloom;
                     if bloom_contains_log_filter(bloom, criteria) {
@@ -1067,11 +1682,20 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
 
         self.add_state_to_cache(result.state, block.header().number);
 
+        for trace in &result.transaction_traces {
+            if let Some(coverage) = &mut self.coverage {
+                coverage.record(trace);
+            }
+
+            self.gas_profiler.record(trace);
+        }
+
         Ok(DebugMineBlockResult {
             block: block_and_total_difficulty.block,
             transaction_results: result.transaction_results,
             transaction_traces: result.transaction_traces,
             console_log_inputs: result.console_log_inputs,
+            console_log_messages: result.console_log_messages,
         })
     }
 
@@ -1142,7 +1766,10 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
         let remaining_blocks = number_of_blocks
             - u64::try_from(mined_blocks.len()).expect("usize cannot be larger than u128");
 
-        if remaining_blocks < MINIMUM_RESERVABLE_BLOCKS {
+        // Reserved blocks are empty, so they can't contain scheduled withdrawals
+        if remaining_blocks < MINIMUM_RESERVABLE_BLOCKS
+            || self.initial_config.withdrawal_schedule.is_some()
+        {
             for _ in 0..remaining_blocks {
                 mine_block_with_interval(self, &mut mined_blocks)?;
             }
@@ -1195,6 +1822,27 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
             .map(Some)
     }
 
+    /// Calculates the next block's blob base fee, i.e. the price per unit of
+    /// blob gas.
+    pub fn next_block_blob_base_fee(&self) -> Result<U256, ProviderError<LoggerErrorT>> {
+        if self.spec_id() < SpecId::CANCUN {
+            return Err(ProviderError::UnmetHardfork {
+                actual: self.spec_id(),
+                minimum: SpecId::CANCUN,
+            });
+        }
+
+        let last_block = self.last_block()?;
+
+        // Pre-Cancun parents, e.g. of a forked chain, are evaluated as having
+        // neither used nor excess blob gas.
+        let excess_blob_gas = last_block.header().blob_gas.as_ref().map_or(0, |blob_gas| {
+            calc_excess_blob_gas(blob_gas.excess_gas, blob_gas.gas_used)
+        });
+
+        Ok(U256::from(calc_blob_gasprice(excess_blob_gas)))
+    }
+
     /// Calculates the gas price for the next block.
     pub fn next_gas_price(&self) -> Result<U256, BlockchainError> {
         if let Some(next_block_base_fee_per_gas) = self.next_block_base_fee_per_gas()? {
@@ -1256,6 +1904,308 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
         self.mem_pool.remove_transaction(transaction_hash)
     }
 
+    /// Replaces the last `depth` blocks with an alternative branch. If
+    /// `new_blocks` is provided, the branch contains a block per entry, with
+    /// the entry's transactions. Otherwise, `depth` blocks are mined from the
+    /// mem pool. Blocks at or below the finalized block cannot be replaced, if
+    /// finality is simulated.
+    ///
+    /// The transactions of the replaced blocks are returned to the mem pool,
+    /// ahead of the pending transactions, and their logs are sent to log
+    /// filters and subscriptions with `removed: true`. If mining the
+    /// alternative branch fails, the replaced blocks, the mem pool and the log
+    /// filters are restored, and subscriptions aren't notified.
+    pub fn reorg(
+        &mut self,
+        depth: u64,
+        new_blocks: Option<Vec<Vec<ExecutableTransaction>>>,
+    ) -> Result<Vec<DebugMineBlockResult<BlockchainError>>, ProviderError<LoggerErrorT>> {
+        // Neither the genesis block, remote blocks, nor finalized blocks can be
+        // replaced
+        let mut last_irreplaceable_block_number = self
+            .fork_metadata
+            .as_ref()
+            .map_or(0, |fork_metadata| fork_metadata.fork_block_number);
+
+        // Without a configured lag or an explicitly set finalized block, the
+        // finalized block is merely an alias of the last block
+        if self.finalized_block_number.is_some() || self.initial_config.finality.is_some() {
+            last_irreplaceable_block_number =
+                last_irreplaceable_block_number.max(self.finalized_block_number());
+        }
+
+        let last_block_number = self.last_block_number();
+        let max_depth = last_block_number - last_irreplaceable_block_number;
+
+        if depth == 0 || depth > max_depth {
+            return Err(ProviderError::InvalidReorgDepth { depth, max_depth });
+        }
+
+        let fork_point = last_block_number - depth;
+
+        let mut removed_transactions = Vec::new();
+        let mut removed_logs = Vec::new();
+        let mut first_removed_withdrawal_index = None;
+        for block_number in (fork_point + 1)..=last_block_number {
+            let block = self
+                .blockchain
+                .block_by_number(block_number)?
+                .expect("Block must exist, as its number is at most the last block number");
+
+            removed_transactions.extend(block.transactions().iter().cloned());
+
+            for receipt in block.transaction_receipts()? {
+                removed_logs.extend(receipt.logs().iter().map(|log| FilterLog {
+                    removed: true,
+                    ..log.clone()
+                }));
+            }
+
+            if first_removed_withdrawal_index.is_none() {
+                first_removed_withdrawal_index = block
+                    .withdrawals()
+                    .and_then(<[Withdrawal]>::first)
+                    .map(|withdrawal| withdrawal.index);
+            }
+        }
+
+        // The replaced blocks are retained, so they can be re-inserted if mining
+        // the alternative branch fails
+        let replaced_history = self.local_history_after(fork_point)?;
+
+        let snapshot_id = self.make_snapshot();
+
+        self.blockchain.revert_to_block(fork_point)?;
+
+        self.block_number_to_state_id.split_off(&(fork_point + 1));
+        self.irregular_state
+            .remove_state_overrides_after(fork_point);
+
+        // The blob sidecars of the replaced blocks' transactions are pending
+        // again, until their transactions are re-mined
+        let replaced_blob_sidecars = self.blob_sidecars.split_off(&(fork_point + 1));
+        self.pending_blob_sidecars.extend(
+            replaced_blob_sidecars
+                .values()
+                .flatten()
+                .map(|(transaction_hash, sidecar)| (*transaction_hash, sidecar.clone())),
+        );
+
+        if let Some(withdrawal_index) = first_removed_withdrawal_index {
+            self.next_withdrawal_index = withdrawal_index;
+        }
+
+        let pending_transactions: Vec<ExecutableTransaction> = std::mem::replace(
+            &mut self.mem_pool,
+            MemPool::new(self.mem_pool.block_gas_limit()),
+        )
+        .transactions()
+        .cloned()
+        .collect();
+
+        // Subscription events are held back until the alternative branch has
+        // been mined, as they can't be retracted if the reorg is rolled back.
+        let filters = self.filters.clone();
+        let subscription_events = Arc::new(Mutex::new(Vec::new()));
+        let subscriber_callback = std::mem::replace(&mut self.subscriber_callback, {
+            let subscription_events = subscription_events.clone();
+            Box::new(move |event: SubscriptionEvent| subscription_events.lock().push(event))
+        });
+
+        let result = self
+            .add_transactions_to_mem_pool(
+                removed_transactions.into_iter().chain(pending_transactions),
+            )
+            .and_then(|()| {
+                self.notify_removed_logs(&removed_logs);
+
+                if let Some(new_blocks) = new_blocks {
+                    new_blocks
+                        .into_iter()
+                        .map(|transactions| {
+                            self.mine_and_commit_block_with_transactions(transactions)
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                } else {
+                    (0..depth)
+                        .map(|_| self.mine_and_commit_block(BlockOptions::default()))
+                        .collect::<Result<Vec<_>, _>>()
+                }
+            });
+
+        self.subscriber_callback = subscriber_callback;
+
+        match result {
+            Ok(mined_blocks) => {
+                // Snapshots of the replaced blocks can no longer be reverted to
+                self.snapshots
+                    .retain(|_, snapshot| snapshot.block_number <= fork_point);
+
+                // Drop the blob sidecars of transactions that were neither
+                // re-mined nor returned to the mem pool
+                let mem_pool = &self.mem_pool;
+                self.pending_blob_sidecars.retain(|transaction_hash, _| {
+                    mem_pool.transaction_by_hash(transaction_hash).is_some()
+                });
+
+                for event in std::mem::take(&mut *subscription_events.lock()) {
+                    (self.subscriber_callback)(event);
+                }
+
+                Ok(mined_blocks)
+            }
+            Err(error) => {
+                self.blockchain
+                    .revert_to_block(fork_point)
+                    .expect("The fork point precedes the alternative branch");
+
+                for entry in replaced_history {
+                    match entry {
+                        HistoryEntry::Block { block, state_diff } => {
+                            self.blockchain
+                                .insert_block(block, state_diff)
+                                .expect("Replaced blocks were valid on top of the fork point");
+                        }
+                        HistoryEntry::Reservation {
+                            additional,
+                            interval,
+                        } => self
+                            .blockchain
+                            .reserve_blocks(additional, interval)
+                            .expect("Replaced reservations were valid on top of the fork point"),
+                    }
+                }
+
+                self.filters = filters;
+                self.revert_to_snapshot(snapshot_id);
+
+                self.blob_sidecars
+                    .retain(|block_number, _| *block_number <= fork_point);
+                self.blob_sidecars.extend(replaced_blob_sidecars);
+
+                Err(error)
+            }
+        }
+    }
+
+    /// Retrieves the local blocks and reservations after the block with the
+    /// provided number, in ascending order, such that they can be re-inserted
+    /// after reverting to that block.
+    fn local_history_after(
+        &self,
+        block_number: u64,
+    ) -> Result<Vec<HistoryEntry<LocalBlock>>, ProviderError<LoggerErrorT>> {
+        let mut next_block_number = self
+            .fork_metadata
+            .as_ref()
+            .map_or(0, |fork_metadata| fork_metadata.fork_block_number + 1);
+
+        let mut history = Vec::new();
+        for entry in self.blockchain.local_history()? {
+            match entry {
+                HistoryEntry::Block { block, state_diff } => {
+                    next_block_number = block.header().number + 1;
+
+                    if block.header().number > block_number {
+                        history.push(HistoryEntry::Block {
+                            block: to_local_block(&*block)?,
+                            state_diff,
+                        });
+                    }
+                }
+                HistoryEntry::Reservation {
+                    additional,
+                    interval,
+                } => {
+                    let first_retained_number = next_block_number.max(block_number + 1);
+                    next_block_number += additional;
+
+                    if next_block_number > first_retained_number {
+                        history.push(HistoryEntry::Reservation {
+                            additional: next_block_number - first_retained_number,
+                            interval,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(history)
+    }
+
+    /// Mines a block that contains the provided transactions, instead of the
+    /// pending transactions. Transactions that aren't included in the block are
+    /// added to the mem pool.
+    fn mine_and_commit_block_with_transactions(
+        &mut self,
+        transactions: Vec<ExecutableTransaction>,
+    ) -> Result<DebugMineBlockResult<BlockchainError>, ProviderError<LoggerErrorT>> {
+        let state = self.current_state()?;
+
+        let mut block_mem_pool = MemPool::new(self.mem_pool.block_gas_limit());
+        for transaction in transactions {
+            block_mem_pool.add_transaction(&*state, transaction)?;
+        }
+
+        let mem_pool = std::mem::replace(&mut self.mem_pool, block_mem_pool);
+        let result = self.mine_and_commit_block(BlockOptions::default());
+        let block_mem_pool = std::mem::replace(&mut self.mem_pool, mem_pool);
+        let result = result?;
+
+        let state = self.current_state()?;
+        self.mem_pool
+            .update(&*state)
+            .map_err(ProviderError::MemPoolUpdate)?;
+
+        self.add_transactions_to_mem_pool(block_mem_pool.transactions().cloned())?;
+
+        Ok(result)
+    }
+
+    /// Adds the transactions to the mem pool, skipping transactions that are
+    /// invalid in the current state, e.g. because their nonce is too low.
+    fn add_transactions_to_mem_pool(
+        &mut self,
+        transactions: impl IntoIterator<Item = ExecutableTransaction>,
+    ) -> Result<(), ProviderError<LoggerErrorT>> {
+        let state = self.current_state()?;
+
+        for transaction in transactions {
+            if let Err(MemPoolAddTransactionError::State(error)) =
+                self.mem_pool.add_transaction(&*state, transaction)
+            {
+                return Err(ProviderError::State(error));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sends the logs of replaced blocks to the log filters and subscriptions.
+    fn notify_removed_logs(&mut self, removed_logs: &[FilterLog]) {
+        if removed_logs.is_empty() {
+            return;
+        }
+
+        for (filter_id, filter) in self.filters.iter_mut() {
+            if let FilterData::Logs { criteria, logs } = &mut filter.data {
+                let mut filtered_logs = filter_logs(removed_logs.iter(), criteria);
+                if filtered_logs.is_empty() {
+                    continue;
+                }
+
+                if filter.is_subscription {
+                    (self.subscriber_callback)(SubscriptionEvent {
+                        filter_id: *filter_id,
+                        result: SubscriptionEventData::Logs(filtered_logs),
+                    });
+                } else {
+                    logs.append(&mut filtered_logs);
+                }
+            }
+        }
+    }
+
     pub fn revert_to_snapshot(&mut self, snapshot_id: u64) -> bool {
         // Ensure that, if the snapshot exists, we also remove all subsequent snapshots,
         // as they can only be used once in Ganache.
@@ -1267,11 +2217,17 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
                 block_number_to_state_id,
                 block_time_offset_seconds,
                 coinbase,
+                finalized_block_number,
                 irregular_state,
                 mem_pool,
                 next_block_base_fee_per_gas,
                 next_block_timestamp,
+                next_withdrawal_index,
+                parent_beacon_block_root_generator,
+                pending_blob_sidecars,
                 prev_randao_generator,
+                queued_withdrawals,
+                safe_block_number,
                 time,
             } = snapshot;
 
@@ -1292,7 +2248,17 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
             self.mem_pool = mem_pool;
             self.next_block_base_fee_per_gas = next_block_base_fee_per_gas;
             self.next_block_timestamp = next_block_timestamp;
+            self.next_withdrawal_index = next_withdrawal_index;
+            self.parent_beacon_block_root_generator = parent_beacon_block_root_generator;
+            self.pending_blob_sidecars = pending_blob_sidecars;
             self.prev_randao_generator = prev_randao_generator;
+            self.queued_withdrawals = queued_withdrawals;
+            self.safe_block_number = safe_block_number;
+            self.finalized_block_number = finalized_block_number;
+
+            // Sidecars of reverted blocks are dropped along with their blocks
+            self.blob_sidecars
+                .retain(|number, _| *number <= block_number);
 
             true
         } else {
@@ -1300,36 +2266,77 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
         }
     }
 
+    /// Runs a call without committing its changes. On OP-stack chains, the L1
+    /// data fee isn't charged, like OP-stack nodes do for `eth_call`, as
+    /// calls aren't posted to L1.
     pub fn run_call(
         &mut self,
         transaction: ExecutableTransaction,
         block_spec: Option<&BlockSpec>,
         state_overrides: &StateOverrides,
+        block_overrides: &BlockOverrides,
     ) -> Result<CallResult, ProviderError<LoggerErrorT>> {
         let cfg_env = self.create_evm_config(block_spec)?;
         let tx_env = transaction.into();
+        let enable_cheatcodes = self.initial_config.enable_cheatcodes;
 
-        self.execute_in_block_context(block_spec, |blockchain, block, state| {
-            let mut inspector =
-                DualInspector::new(EvmInspector::default(), TraceCollector::default());
+        let result = self.execute_in_block_context(block_spec, |blockchain, block, state| {
+            let mut inspector = DualInspector::new(
+                TraceCollector::default(),
+                EvmInspector::new(enable_cheatcodes),
+            );
 
             let execution_result = call::run_call(RunCallArgs {
                 blockchain,
                 header: block.header(),
                 state,
                 state_overrides,
+                block_overrides,
                 cfg_env,
                 tx_env,
                 inspector: Some(&mut inspector),
             })?;
 
-            let (debug_inspector, tracer) = inspector.into_parts();
+            let (tracer, debug_inspector) = inspector.into_parts();
+            let console_log_inputs = debug_inspector.into_console_log_encoded_messages();
 
             Ok(CallResult {
-                console_log_inputs: debug_inspector.into_console_log_encoded_messages(),
+                console_log_messages: decode_console_log_inputs(&console_log_inputs),
+                console_log_inputs,
                 execution_result,
                 trace: tracer.into_trace(),
             })
+        })??;
+
+        if let Some(coverage) = &mut self.coverage {
+            coverage.record(&result.trace);
+        }
+
+        self.gas_profiler.record(&result.trace);
+
+        Ok(result)
+    }
+
+    /// Simulates the blocks of calls on top of the specified block, as
+    /// requested by `eth_simulateV1`. The simulated blocks are discarded
+    /// afterwards.
+    pub fn simulate(
+        &mut self,
+        block_spec: &BlockSpec,
+        blocks: Vec<SimulatedBlock>,
+        trace_transfers: bool,
+    ) -> Result<Vec<SimulatedBlockResult>, ProviderError<LoggerErrorT>> {
+        let cfg_env = self.create_evm_config(Some(block_spec))?;
+
+        self.execute_in_block_context(Some(block_spec), |blockchain, block, state| {
+            run_simulation(RunSimulationArgs {
+                blockchain,
+                header: block.header(),
+                state: state.clone(),
+                cfg_env,
+                blocks,
+                trace_transfers,
+            })
         })?
     }
 
@@ -1538,6 +2545,61 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
         self.beneficiary = coinbase;
     }
 
+    /// Sets the finalized block, which no longer follows the configured lag.
+    /// If the safe block is older, it resolves to the finalized block.
+    pub fn set_finalized_block(
+        &mut self,
+        block_number: u64,
+    ) -> Result<(), ProviderError<LoggerErrorT>> {
+        self.validate_head_block_number(block_number)?;
+
+        self.finalized_block_number = Some(block_number);
+
+        Ok(())
+    }
+
+    /// Sets the safe block, which no longer follows the configured lag. The
+    /// safe block cannot be older than the finalized block.
+    pub fn set_safe_block(&mut self, block_number: u64) -> Result<(), ProviderError<LoggerErrorT>> {
+        self.validate_head_block_number(block_number)?;
+
+        let finalized_block_number = self.finalized_block_number();
+        if block_number < finalized_block_number {
+            return Err(ProviderError::InvalidArgument(format!(
+                "The safe block {block_number} cannot be older than the finalized block {finalized_block_number}"
+            )));
+        }
+
+        self.safe_block_number = Some(block_number);
+
+        Ok(())
+    }
+
+    /// Validates that the safe or finalized block can be set to the block with
+    /// the provided number.
+    fn validate_head_block_number(
+        &self,
+        block_number: u64,
+    ) -> Result<(), ProviderError<LoggerErrorT>> {
+        let spec_id = self.spec_id();
+        if spec_id < SpecId::MERGE {
+            return Err(ProviderError::UnmetHardfork {
+                actual: spec_id,
+                minimum: SpecId::MERGE,
+            });
+        }
+
+        let last_block_number = self.last_block_number();
+        if block_number > last_block_number {
+            return Err(ProviderError::InvalidBlockNumberOrHash {
+                block_spec: BlockSpec::Number(block_number),
+                latest_block_number: last_block_number,
+            });
+        }
+
+        Ok(())
+    }
+
     /// Sets the next block's base fee per gas.
     pub fn set_next_block_base_fee_per_gas(
         &mut self,
@@ -1593,6 +2655,26 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
         Ok(())
     }
 
+    /// Sets the next block's parent beacon block root, which is stored in the
+    /// EIP-4788 beacon roots contract when the block is mined.
+    pub fn set_next_parent_beacon_block_root(
+        &mut self,
+        parent_beacon_block_root: B256,
+    ) -> Result<(), ProviderError<LoggerErrorT>> {
+        let spec_id = self.spec_id();
+        if spec_id < SpecId::CANCUN {
+            return Err(ProviderError::UnmetHardfork {
+                actual: spec_id,
+                minimum: SpecId::CANCUN,
+            });
+        }
+
+        self.parent_beacon_block_root_generator
+            .set_next(parent_beacon_block_root);
+
+        Ok(())
+    }
+
     pub fn set_nonce(
         &mut self,
         address: Address,
@@ -1712,6 +2794,31 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
         self.blockchain.spec_id()
     }
 
+    /// Returns the hardfork of the block with the provided number. Blocks
+    /// after the last block, e.g. when provided as RLP, use the hardfork of
+    /// new blocks.
+    pub fn spec_at_block_number(
+        &self,
+        block_number: u64,
+    ) -> Result<SpecId, ProviderError<LoggerErrorT>> {
+        let is_local_block = self.fork_metadata.as_ref().map_or(true, |fork_metadata| {
+            block_number > fork_metadata.fork_block_number
+        });
+
+        if block_number > self.blockchain.last_block_number() {
+            Ok(self.blockchain.spec_id())
+        } else if is_local_block {
+            let block = self
+                .blockchain
+                .block_by_number(block_number)?
+                .expect("Block must exist, as its number is at most the last block number");
+
+            Ok(self.hardfork_at_block(block_number, block.header().timestamp))
+        } else {
+            Ok(self.blockchain.spec_at_block_number(block_number)?)
+        }
+    }
+
     pub fn stop_impersonating_account(&mut self, address: Address) -> bool {
         self.impersonated_accounts.remove(&address)
     }
@@ -1725,6 +2832,120 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
             .map_err(ProviderError::Blockchain)
     }
 
+    /// Stores the blob sidecar of a pending EIP-4844 transaction, so it can be
+    /// retrieved once the transaction has been mined. Returns whether no
+    /// sidecar was stored for the transaction yet.
+    pub fn add_blob_sidecar(&mut self, transaction_hash: B256, sidecar: BlobSidecar) -> bool {
+        self.pending_blob_sidecars
+            .insert(transaction_hash, sidecar)
+            .is_none()
+    }
+
+    /// Stores the blob sidecar of an EIP-4844 transaction that was mined in
+    /// the block with the provided number.
+    pub fn add_mined_blob_sidecar(
+        &mut self,
+        block_number: u64,
+        transaction_hash: B256,
+        sidecar: BlobSidecar,
+    ) {
+        self.blob_sidecars
+            .entry(block_number)
+            .or_default()
+            .insert(transaction_hash, sidecar);
+    }
+
+    /// Removes the blob sidecar of a pending EIP-4844 transaction, e.g. when
+    /// the transaction was rejected.
+    pub fn remove_blob_sidecar(&mut self, transaction_hash: &B256) {
+        self.pending_blob_sidecars.remove(transaction_hash);
+    }
+
+    /// Queues withdrawals to be included in the next mined block.
+    pub fn add_withdrawals(
+        &mut self,
+        withdrawals: Vec<WithdrawalRequest>,
+    ) -> Result<(), ProviderError<LoggerErrorT>> {
+        if self.spec_id() < SpecId::SHANGHAI {
+            return Err(ProviderError::UnmetHardfork {
+                actual: self.spec_id(),
+                minimum: SpecId::SHANGHAI,
+            });
+        }
+
+        self.queued_withdrawals.extend(withdrawals);
+
+        Ok(())
+    }
+
+    /// Constructs the withdrawals of the block with the provided number: the
+    /// queued withdrawals, followed by the scheduled withdrawals if the block
+    /// is due for them.
+    fn next_block_withdrawals(&self, block_number: u64) -> Vec<Withdrawal> {
+        let scheduled_withdrawals = self
+            .initial_config
+            .withdrawal_schedule
+            .iter()
+            .filter(|schedule| block_number % schedule.interval.get() == 0)
+            .flat_map(|schedule| schedule.withdrawals.iter());
+
+        self.queued_withdrawals
+            .iter()
+            .chain(scheduled_withdrawals)
+            .zip(self.next_withdrawal_index..)
+            .map(|(withdrawal, index)| Withdrawal {
+                index,
+                validator_index: withdrawal.validator_index,
+                address: withdrawal.address,
+                amount: withdrawal.amount,
+            })
+            .collect()
+    }
+
+    /// Retrieves the blob sidecars of the EIP-4844 transactions in the
+    /// specified block, in transaction order, along with the transactions'
+    /// hashes. For the pending block, the sidecars of the pending transactions
+    /// are returned.
+    pub fn blob_sidecars_by_block_spec(
+        &self,
+        block_spec: &BlockSpec,
+    ) -> Result<Vec<(B256, BlobSidecar)>, ProviderError<LoggerErrorT>> {
+        let (transaction_hashes, blob_sidecars): (Vec<B256>, _) =
+            if let Some(block) = self.block_by_block_spec(block_spec)? {
+                let transaction_hashes = block
+                    .transactions()
+                    .iter()
+                    .map(|transaction| *transaction.hash())
+                    .collect();
+
+                (
+                    transaction_hashes,
+                    self.blob_sidecars.get(&block.header().number),
+                )
+            } else {
+                let transaction_hashes = self
+                    .mem_pool
+                    .pending_transactions()
+                    .map(|transaction| *transaction.pending().hash())
+                    .collect();
+
+                (transaction_hashes, Some(&self.pending_blob_sidecars))
+            };
+
+        let Some(blob_sidecars) = blob_sidecars else {
+            return Ok(Vec::new());
+        };
+
+        Ok(transaction_hashes
+            .into_iter()
+            .filter_map(|transaction_hash| {
+                blob_sidecars
+                    .get(&transaction_hash)
+                    .map(|sidecar| (transaction_hash, sidecar.clone()))
+            })
+            .collect())
+    }
+
     /// Get a transaction by hash from the blockchain or from the mempool if
     /// it's not mined yet.
     pub fn transaction_by_hash(
@@ -1806,7 +3027,7 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
             .flatten();
 
         let spec_id = if let Some(block_number) = block_number {
-            self.blockchain.spec_at_block_number(block_number)?
+            self.spec_at_block_number(block_number)?
         } else {
             self.blockchain.spec_id()
         };
@@ -1824,19 +3045,125 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
         Ok(evm_config)
     }
 
-    fn execute_in_block_context<T>(
-        &mut self,
-        block_spec: Option<&BlockSpec>,
-        function: impl FnOnce(
-            &dyn SyncBlockchain<BlockchainError, StateError>,
-            &Arc<dyn SyncBlock<Error = BlockchainError>>,
-            &Box<dyn SyncState<StateError>>,
-        ) -> T,
-    ) -> Result<T, ProviderError<LoggerErrorT>> {
-        let block = if let Some(block_spec) = block_spec {
-            self.block_by_block_spec(block_spec)?
-        } else {
-            Some(self.blockchain.last_block()?)
+    /// Returns the hardfork of a new block with the provided number and
+    /// timestamp. If hardfork activations are known for the provider's chain,
+    /// they determine the hardfork, up to the configured hardfork.
+    fn hardfork_at_block(&self, block_number: u64, timestamp: u64) -> SpecId {
+        let hardfork = self.blockchain.spec_id();
+
+        self.hardfork_activations
+            .as_ref()
+            .and_then(|activations| activations.hardfork_at_block(block_number, timestamp))
+            .map_or(hardfork, |activated_hardfork| {
+                cmp::min(activated_hardfork, hardfork)
+            })
+    }
+
+    /// Returns the number of the block that activated the DAO fork, if it's
+    /// active for the block with the provided number and timestamp.
+    fn dao_activation_block(
+        &self,
+        block_number: u64,
+        timestamp: u64,
+    ) -> Result<Option<u64>, ProviderError<LoggerErrorT>> {
+        // The number of blocks after the activation block that must contain the
+        // DAO extra data, as enforced by the block builder
+        const DAO_FORCE_EXTRA_DATA_RANGE: u64 = 9;
+
+        match self.dao_activation {
+            Some(ForkCondition::Block(activation_block_number)) => {
+                Ok(Some(activation_block_number))
+            }
+            Some(ForkCondition::Timestamp(activation_timestamp))
+                if timestamp >= activation_timestamp =>
+            {
+                // Only the blocks in the range of the extra data requirement are relevant
+                let mut activation_block_number = block_number;
+                while activation_block_number > 0
+                    && block_number - activation_block_number <= DAO_FORCE_EXTRA_DATA_RANGE
+                {
+                    let previous_block = self
+                        .blockchain
+                        .block_by_number(activation_block_number - 1)?
+                        .expect("Block must exist, as its number is lower than the next block's");
+
+                    if previous_block.header().timestamp < activation_timestamp {
+                        break;
+                    }
+
+                    activation_block_number -= 1;
+                }
+
+                Ok(Some(activation_block_number))
+            }
+            Some(ForkCondition::Timestamp(_)) | None => Ok(None),
+        }
+    }
+
+    /// Returns the OP-stack hardfork of the block with the provided number and
+    /// timestamp, if the provider runs an OP-stack chain.
+    fn optimism_hardfork(&self, block_number: u64, timestamp: u64) -> Option<OptimismHardfork> {
+        let remote_hardfork = self.fork_metadata.as_ref().and_then(|fork_metadata| {
+            optimism_hardfork_at_block(fork_metadata.chain_id, block_number, timestamp)
+        });
+
+        remote_hardfork.or_else(|| {
+            self.initial_config
+                .optimism
+                .then(|| OptimismHardfork::from_spec_id(self.blockchain.spec_id()))
+        })
+    }
+
+    /// Executes the provided function on top of the state of the parent of the
+    /// block with the provided header, using the block's environment.
+    fn execute_in_parent_block_context<T>(
+        &mut self,
+        header: &Header,
+        function: impl FnOnce(
+            &dyn SyncBlockchain<BlockchainError, StateError>,
+            &Box<dyn SyncState<StateError>>,
+            CfgEnv,
+            BlockEnv,
+        ) -> Result<T, ProviderError<LoggerErrorT>>,
+    ) -> Result<T, ProviderError<LoggerErrorT>> {
+        if header.number == 0 {
+            return Err(ProviderError::InvalidInput(
+                "genesis is not traceable".to_string(),
+            ));
+        }
+
+        // The block might not be part of the blockchain, when provided as RLP
+        let spec_block_number = cmp::min(header.number, self.blockchain.last_block_number());
+        let cfg_env = self.create_evm_config(Some(&BlockSpec::Number(spec_block_number)))?;
+
+        let parent_block_spec = BlockSpec::Eip1898(Eip1898BlockSpec::Hash {
+            block_hash: header.parent_hash,
+            require_canonical: None,
+        });
+
+        self.execute_in_block_context(
+            Some(&parent_block_spec),
+            |blockchain, _parent_block, state| {
+                let block_env = block_env_from_header(header, cfg_env.spec_id);
+
+                function(blockchain, state, cfg_env, block_env)
+            },
+        )?
+    }
+
+    fn execute_in_block_context<T>(
+        &mut self,
+        block_spec: Option<&BlockSpec>,
+        function: impl FnOnce(
+            &dyn SyncBlockchain<BlockchainError, StateError>,
+            &Arc<dyn SyncBlock<Error = BlockchainError>>,
+            &Box<dyn SyncState<StateError>>,
+        ) -> T,
+    ) -> Result<T, ProviderError<LoggerErrorT>> {
+        let block = if let Some(block_spec) = block_spec {
+            self.block_by_block_spec(block_spec)?
+        } else {
+            Some(self.blockchain.last_block()?)
         };
 
         if let Some(block) = block {
@@ -1875,9 +3202,35 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
                 .unwrap_or_else(|| self.mem_pool.block_gas_limit()),
         );
 
-        let evm_config = self.create_evm_config(None)?;
+        let (block_number, block_timestamp) = {
+            let last_block = self.blockchain.last_block()?;
+            let last_header = last_block.header();
+
+            (
+                last_header.number + 1,
+                options.timestamp.unwrap_or(last_header.timestamp),
+            )
+        };
+
+        let mut evm_config = self.create_evm_config(None)?;
+        evm_config.spec_id = self.hardfork_at_block(block_number, block_timestamp);
+
+        if options.parent_beacon_block_root.is_none() && evm_config.spec_id >= SpecId::CANCUN {
+            options.parent_beacon_block_root =
+                Some(self.parent_beacon_block_root_generator.next_value());
+        }
+
+        if options.withdrawals.is_none()
+            && options.withdrawals_root.is_none()
+            && evm_config.spec_id >= SpecId::SHANGHAI
+        {
+            options.withdrawals = Some(self.next_block_withdrawals(block_number));
+        }
+
+        let dao_activation_block = self.dao_activation_block(block_number, block_timestamp)?;
+        let optimism_hardfork = self.optimism_hardfork(block_number, block_timestamp);
 
-        let mut inspector = EvmInspector::default();
+        let mut inspector = EvmInspector::new(self.initial_config.enable_cheatcodes);
 
         let state_to_be_modified = (*self.current_state()?).clone();
 
@@ -1890,7 +3243,8 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
             self.min_gas_price,
             self.initial_config.mining.mem_pool.order,
             miner_reward(evm_config.spec_id).unwrap_or(U256::ZERO),
-            self.dao_activation_block,
+            dao_activation_block,
+            optimism_hardfork,
             Some(&mut inspector),
         )?;
 
@@ -2095,6 +3449,110 @@ impl<LoggerErrorT: Debug> ProviderData<LoggerErrorT> {
             .clone())
     }
 
+    /// Restores the blockchain and the provider's state from the dump. The
+    /// blockchain must not contain any local blocks yet.
+    fn restore(&mut self, dump: StateDump) -> Result<(), StateDumpError> {
+        let StateDump {
+            chain_id,
+            hardfork,
+            history,
+            irregular_state,
+            transactions,
+            block_gas_limit,
+            block_time_offset_seconds,
+            coinbase,
+            impersonated_accounts,
+            next_block_base_fee_per_gas,
+            next_block_timestamp,
+            next_prev_randao,
+            next_parent_beacon_block_root,
+            safe_block_number,
+            finalized_block_number,
+            queued_withdrawals,
+            blob_sidecars,
+            ..
+        } = dump;
+
+        let mut history = history.into_iter();
+        if self.fork_metadata.is_none() {
+            let Some(HistoryEntryDump::Block(genesis_block)) = history.next() else {
+                return Err(StateDumpError::MissingGenesisBlock);
+            };
+
+            let (genesis_block, genesis_diff) = genesis_block.into_local_block()?;
+            self.blockchain = Box::new(LocalBlockchain::with_genesis_block(
+                genesis_block,
+                genesis_diff,
+                chain_id,
+                hardfork,
+            )?);
+        }
+
+        for entry in history {
+            match entry {
+                HistoryEntryDump::Block(block) => {
+                    let (block, state_diff) = block.into_local_block()?;
+                    if let Some(withdrawal) = block.withdrawals().and_then(<[Withdrawal]>::last) {
+                        self.next_withdrawal_index = withdrawal.index + 1;
+                    }
+
+                    self.blockchain.insert_block(block, state_diff)?;
+                }
+                HistoryEntryDump::Reservation {
+                    additional,
+                    interval,
+                } => self.blockchain.reserve_blocks(additional, interval)?,
+            }
+        }
+
+        self.irregular_state = irregular_state;
+        self.beneficiary = coinbase;
+        self.block_time_offset_seconds = block_time_offset_seconds;
+        self.next_block_base_fee_per_gas = next_block_base_fee_per_gas;
+        self.next_block_timestamp = next_block_timestamp;
+        self.prev_randao_generator.set_next(next_prev_randao);
+        if let Some(next_parent_beacon_block_root) = next_parent_beacon_block_root {
+            self.parent_beacon_block_root_generator
+                .set_next(next_parent_beacon_block_root);
+        }
+        self.safe_block_number = safe_block_number;
+        self.finalized_block_number = finalized_block_number;
+        self.impersonated_accounts = impersonated_accounts.into_iter().collect();
+        self.queued_withdrawals = queued_withdrawals;
+
+        for BlobSidecarDump {
+            block_number,
+            transaction_hash,
+            sidecar,
+        } in blob_sidecars
+        {
+            if let Some(block_number) = block_number {
+                self.add_mined_blob_sidecar(block_number, transaction_hash, sidecar);
+            } else {
+                self.add_blob_sidecar(transaction_hash, sidecar);
+            }
+        }
+
+        // The cached states belong to the blockchain before restoring
+        self.block_state_cache.clear();
+        self.block_number_to_state_id.clear();
+
+        let last_block_number = self.blockchain.last_block_number();
+        let state = self
+            .blockchain
+            .state_at_block_number(last_block_number, self.irregular_state.state_overrides())?;
+
+        self.mem_pool = MemPool::new(block_gas_limit);
+        for transaction in transactions {
+            let transaction = transaction.into_executable(hardfork)?;
+            self.mem_pool.add_transaction(&*state, transaction)?;
+        }
+
+        self.add_state_to_cache(state, last_block_number);
+
+        Ok(())
+    }
+
     fn add_state_to_cache(
         &mut self,
         state: Box<dyn SyncState<StateError>>,
@@ -2119,6 +3577,38 @@ impl StateId {
     }
 }
 
+/// The index of the withdrawal that follows the last withdrawal of the block,
+/// or zero if the block has no withdrawals.
+fn next_withdrawal_index(block: &dyn SyncBlock<Error = BlockchainError>) -> u64 {
+    block
+        .withdrawals()
+        .and_then(<[Withdrawal]>::last)
+        .map_or(0, |withdrawal| withdrawal.index + 1)
+}
+
+/// Converts a locally mined block into a [`LocalBlock`], retaining the
+/// hardfork of its receipts.
+fn to_local_block(
+    block: &dyn SyncBlock<Error = BlockchainError>,
+) -> Result<LocalBlock, BlockchainError> {
+    let receipts = block
+        .transaction_receipts()?
+        .iter()
+        .map(|receipt| block_to_transaction_receipt(receipt))
+        .collect();
+
+    let local_block = LocalBlock::new(
+        PartialHeader::from(block.header().clone()),
+        block.transactions().to_vec(),
+        receipts,
+        Vec::new(),
+        block.withdrawals().map(<[Withdrawal]>::to_vec),
+    );
+    debug_assert_eq!(local_block.hash(), block.hash());
+
+    Ok(local_block)
+}
+
 fn block_time_offset_seconds(config: &ProviderConfig) -> Result<i64, CreationError> {
     config.initial_date.map_or(Ok(0), |initial_date| {
         let initial_timestamp = i64::try_from(
@@ -2141,6 +3631,28 @@ fn block_time_offset_seconds(config: &ProviderConfig) -> Result<i64, CreationErr
     })
 }
 
+/// Constructs the block environment of a block with the provided header, when
+/// executed with the provided hardfork.
+pub fn block_env_from_header(header: &Header, spec_id: SpecId) -> BlockEnv {
+    BlockEnv {
+        number: U256::from(header.number),
+        coinbase: header.beneficiary,
+        timestamp: U256::from(header.timestamp),
+        gas_limit: U256::from(header.gas_limit),
+        basefee: header.base_fee_per_gas.unwrap_or_default(),
+        difficulty: U256::from(header.difficulty),
+        prevrandao: if spec_id >= SpecId::MERGE {
+            Some(header.mix_hash)
+        } else {
+            None
+        },
+        blob_excess_gas_and_price: header
+            .blob_gas
+            .as_ref()
+            .map(|BlobGas { excess_gas, .. }| BlobExcessGasAndPrice::new(*excess_gas)),
+    }
+}
+
 struct BlockchainAndState {
     blockchain: Box<dyn SyncBlockchain<BlockchainError, StateError>>,
     fork_metadata: Option<ForkMetadata>,
@@ -2150,6 +3662,34 @@ struct BlockchainAndState {
     prev_randao_generator: RandomHashGenerator,
     block_time_offset_seconds: i64,
     next_block_base_fee_per_gas: Option<U256>,
+    hardfork_activations: Option<HardforkActivations>,
+}
+
+/// The blockchain and state that a provider starts with.
+pub struct InitialBlockchainAndState {
+    /// The blockchain, which is forked if the config contains a fork
+    pub blockchain: Box<dyn SyncBlockchain<BlockchainError, StateError>>,
+    /// The state at the last block of the blockchain, including the configured
+    /// accounts
+    pub state: Box<dyn SyncState<StateError>>,
+}
+
+/// Creates the blockchain and state that a provider with the provided config
+/// starts with. This allows transactions to be run against the same chain as
+/// the provider, without going through JSON-RPC.
+pub fn create_initial_blockchain_and_state(
+    runtime: runtime::Handle,
+    config: &ProviderConfig,
+) -> Result<InitialBlockchainAndState, CreationError> {
+    let InitialAccounts {
+        genesis_accounts, ..
+    } = create_accounts(config);
+
+    let BlockchainAndState {
+        blockchain, state, ..
+    } = create_blockchain_and_state(runtime, config, genesis_accounts)?;
+
+    Ok(InitialBlockchainAndState { blockchain, state })
 }
 
 fn create_blockchain_and_state(
@@ -2277,6 +3817,8 @@ fn create_blockchain_and_state(
             None
         };
 
+        let hardfork_activations = blockchain.hardfork_activations().cloned();
+
         Ok(BlockchainAndState {
             fork_metadata: Some(ForkMetadata {
                 chain_id: blockchain.chain_id(),
@@ -2294,8 +3836,19 @@ fn create_blockchain_and_state(
             prev_randao_generator,
             block_time_offset_seconds,
             next_block_base_fee_per_gas,
+            hardfork_activations,
         })
     } else {
+        if config.hardfork >= SpecId::CANCUN {
+            genesis_accounts
+                .entry(BEACON_ROOTS_ADDRESS)
+                .or_insert_with(|| Account {
+                    info: beacon_roots_contract(),
+                    storage: HashMap::default(),
+                    status: AccountStatus::Created | AccountStatus::Touched,
+                });
+        }
+
         let blockchain = LocalBlockchain::new(
             StateDiff::from(genesis_accounts),
             config.chain_id,
@@ -2310,6 +3863,7 @@ fn create_blockchain_and_state(
             config.initial_base_fee_per_gas,
             config.initial_blob_gas.clone(),
             config.initial_parent_beacon_block_root,
+            config.genesis_block.clone(),
         )?;
 
         let irregular_state = IrregularState::default();
@@ -2330,6 +3884,7 @@ fn create_blockchain_and_state(
             // For local blockchain the initial base fee per gas config option is incorporated as
             // part of the genesis block.
             next_block_base_fee_per_gas: None,
+            hardfork_activations: config.chains.get(&config.chain_id).cloned(),
         })
     }
 }
@@ -2522,15 +4077,20 @@ pub(crate) mod test_utils {
 
 #[cfg(test)]
 mod tests {
-    use std::convert::Infallible;
+    use std::{convert::Infallible, num::NonZeroU64};
 
     use alloy_sol_types::{sol, SolCall};
     use anyhow::{anyhow, Context};
     use edr_eth::{
         remote::{eth::CallRequest, PreEip1898BlockSpec},
         spec::chain_hardfork_activations,
+        transaction::{Eip155TransactionRequest, TransactionKind, TransactionRequest},
+        FixedBytes, U64,
+    };
+    use edr_evm::{
+        hex, Action, CallKind, CallTracerConfig, ChangedValue, DebugTracer, Delta, MineOrdering,
+        OpcodeClass, PrestateTrace, PrestateTracerConfig, RemoteBlock, TransactionError,
     };
-    use edr_evm::{hex, MineOrdering, RemoteBlock, TransactionError};
     use edr_test_utils::env::get_alchemy_url;
     use serde_json::json;
 
@@ -2541,7 +4101,7 @@ mod tests {
         test_utils::{
             create_test_config, create_test_config_with_fork, one_ether, FORK_BLOCK_NUMBER,
         },
-        MemPoolConfig, MiningConfig, ProviderConfig,
+        FinalityConfig, MemPoolConfig, MiningConfig, ProviderConfig, WithdrawalScheduleConfig,
     };
 
     #[test]
@@ -2586,6 +4146,74 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn get_proof_local() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let address = Address::random();
+        let index = U256::from(1);
+        fixture.provider_data.set_balance(address, one_ether())?;
+        fixture
+            .provider_data
+            .set_account_storage_slot(address, index, U256::from(5))?;
+        fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?;
+
+        let proof = fixture
+            .provider_data
+            .get_proof(address, vec![index, U256::from(2)], None)?;
+
+        let state_root = fixture
+            .provider_data
+            .blockchain
+            .last_block()?
+            .header()
+            .state_root;
+        assert_eq!(
+            edr_evm::keccak256(&proof.account_proof[0]),
+            state_root,
+            "the proof starts at the state root"
+        );
+
+        assert_eq!(proof.balance, one_ether());
+        assert_eq!(proof.storage_proof.len(), 2);
+        assert_eq!(proof.storage_proof[0].value, U256::from(5));
+        assert_eq!(
+            edr_evm::keccak256(&proof.storage_proof[0].proof[0]),
+            proof.storage_hash
+        );
+        assert_eq!(proof.storage_proof[1].value, U256::ZERO);
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_proof_forked() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_forked(None)?;
+
+        let address = fixture.nth_local_account(0)?;
+        let proof = fixture.provider_data.get_proof(
+            address,
+            Vec::new(),
+            Some(&BlockSpec::Number(FORK_BLOCK_NUMBER)),
+        )?;
+        assert!(!proof.account_proof.is_empty());
+
+        // The local state of a fork can't be proven
+        fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?;
+
+        let result = fixture.provider_data.get_proof(address, Vec::new(), None);
+        assert!(matches!(
+            result,
+            Err(ProviderError::State(StateError::ProofUnsupported))
+        ));
+
+        Ok(())
+    }
+
     #[test]
     fn test_sign_transaction_request() -> anyhow::Result<()> {
         let fixture = ProviderTestFixture::new_local()?;
@@ -2660,6 +4288,24 @@ mod tests {
         test_add_pending_transaction(&mut fixture, transaction)
     }
 
+    #[test]
+    fn add_compilation_result() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let compiler_input: CompilerInput =
+            serde_json::from_str(include_str!("../tests/fixtures/compiler_input.json"))?;
+        let compiler_output: CompilerOutput =
+            serde_json::from_str(include_str!("../tests/fixtures/compiler_output.json"))?;
+
+        fixture.provider_data.add_compilation_result(
+            "0.8.0".to_string(),
+            &compiler_input,
+            &compiler_output,
+        )?;
+
+        Ok(())
+    }
+
     #[test]
     fn block_by_block_spec_earliest() -> anyhow::Result<()> {
         let fixture = ProviderTestFixture::new_local()?;
@@ -2704,109 +4350,1615 @@ mod tests {
     }
 
     #[test]
-    fn block_by_block_spec_pending() -> anyhow::Result<()> {
-        let fixture = ProviderTestFixture::new_local()?;
-
-        let block_spec = BlockSpec::Tag(BlockTag::Pending);
-
-        let block = fixture.provider_data.block_by_block_spec(&block_spec)?;
+    fn block_by_block_spec_finalized_safe_with_lag() -> anyhow::Result<()> {
+        let config = ProviderConfig {
+            finality: Some(FinalityConfig {
+                safe_block_lag: 2,
+                finalized_block_lag: 4,
+            }),
+            ..create_test_config()
+        };
 
-        assert!(block.is_none());
+        let runtime = runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .thread_name("provider-data-test")
+            .build()?;
 
-        Ok(())
-    }
+        let mut fixture = ProviderTestFixture::new(runtime, config)?;
 
-    // Make sure executing a transaction in a pending block context doesn't panic.
-    #[test]
-    fn execute_in_block_context_pending() -> anyhow::Result<()> {
-        let mut fixture = ProviderTestFixture::new_local()?;
+        // The heads can't lag behind the genesis block
+        assert_eq!(fixture.provider_data.safe_block_number(), 0);
+        assert_eq!(fixture.provider_data.finalized_block_number(), 0);
 
-        let block_spec = Some(BlockSpec::Tag(BlockTag::Pending));
+        fixture.provider_data.mine_and_commit_blocks(5, 1)?;
 
-        let mut value = 0;
-        let _ =
-            fixture
+        for (tag, expected) in [
+            (BlockTag::Finalized, 1),
+            (BlockTag::Safe, 3),
+            (BlockTag::Latest, 5),
+        ] {
+            let block = fixture
                 .provider_data
-                .execute_in_block_context(block_spec.as_ref(), |_, _, _| {
-                    value += 1;
-                    Ok::<(), ProviderError<Infallible>>(())
-                })?;
+                .block_by_block_spec(&BlockSpec::Tag(tag))?
+                .context("block should exist")?;
 
-        assert_eq!(value, 1);
+            assert_eq!(block.header().number, expected);
+        }
 
         Ok(())
     }
 
     #[test]
-    fn chain_id() -> anyhow::Result<()> {
-        let fixture = ProviderTestFixture::new_local()?;
+    fn mine_block_with_timestamp_activated_hardfork() -> anyhow::Result<()> {
+        let mut config = create_test_config();
+        config.hardfork = SpecId::CANCUN;
 
-        let chain_id = fixture.provider_data.chain_id();
-        assert_eq!(chain_id, fixture.config.chain_id);
+        // Cancun is activated a minute after the next block
+        let cancun_timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 60;
+        config.chains.insert(
+            config.chain_id,
+            HardforkActivations::from(
+                &[
+                    (ForkCondition::Block(0), SpecId::SHANGHAI),
+                    (ForkCondition::Timestamp(cancun_timestamp), SpecId::CANCUN),
+                ][..],
+            ),
+        );
 
-        Ok(())
-    }
+        let runtime = runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .thread_name("provider-data-test")
+            .build()?;
 
-    #[test]
-    fn chain_id_fork_mode() -> anyhow::Result<()> {
-        let fixture = ProviderTestFixture::new_forked(None)?;
+        let mut fixture = ProviderTestFixture::new(runtime, config)?;
 
-        let chain_id = fixture.provider_data.chain_id();
-        assert_eq!(chain_id, fixture.config.chain_id);
+        let shanghai_block = fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?
+            .block;
+        assert!(shanghai_block.header().parent_beacon_block_root.is_none());
+        assert_eq!(
+            fixture.provider_data.spec_at_block_number(1)?,
+            SpecId::SHANGHAI
+        );
+
+        let cancun_block = fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions {
+                timestamp: Some(cancun_timestamp),
+                ..BlockOptions::default()
+            })?
+            .block;
+        assert!(cancun_block.header().parent_beacon_block_root.is_some());
+        assert_eq!(
+            fixture.provider_data.spec_at_block_number(2)?,
+            SpecId::CANCUN
+        );
 
         Ok(())
     }
 
     #[test]
-    fn console_log_mine_block() -> anyhow::Result<()> {
+    fn set_safe_and_finalized_block() -> anyhow::Result<()> {
         let mut fixture = ProviderTestFixture::new_local()?;
-        let ConsoleLogTransaction {
-            transaction,
-            expected_call_data,
-        } = deploy_console_log_contract(&mut fixture.provider_data)?;
 
-        let signed_transaction = fixture
-            .provider_data
-            .sign_transaction_request(transaction)?;
-
-        fixture.provider_data.set_auto_mining(false);
-        fixture.provider_data.send_transaction(signed_transaction)?;
-        let (block_timestamp, _) = fixture.provider_data.next_block_timestamp(None)?;
-        let prevrandao = fixture.provider_data.prev_randao_generator.next_value();
-        let result = fixture.provider_data.mine_block(BlockOptions {
-            timestamp: Some(block_timestamp),
-            mix_hash: Some(prevrandao),
-            ..BlockOptions::default()
-        })?;
+        fixture.provider_data.mine_and_commit_blocks(5, 1)?;
 
-        let console_log_inputs = result.console_log_inputs;
-        assert_eq!(console_log_inputs.len(), 1);
-        assert_eq!(console_log_inputs[0], expected_call_data);
+        fixture.provider_data.set_finalized_block(2)?;
+        assert_eq!(fixture.provider_data.finalized_block_number(), 2);
+        // Without a configured lag, the safe block follows the latest block
+        assert_eq!(fixture.provider_data.safe_block_number(), 5);
+
+        assert!(matches!(
+            fixture.provider_data.set_safe_block(1),
+            Err(ProviderError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            fixture.provider_data.set_finalized_block(6),
+            Err(ProviderError::InvalidBlockNumberOrHash { .. })
+        ));
+
+        let snapshot_id = fixture.provider_data.make_snapshot();
+
+        fixture.provider_data.set_safe_block(3)?;
+        fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?;
+
+        let safe_block = fixture
+            .provider_data
+            .block_by_block_spec(&BlockSpec::Tag(BlockTag::Safe))?
+            .context("block should exist")?;
+        assert_eq!(safe_block.header().number, 3);
+
+        assert!(fixture.provider_data.revert_to_snapshot(snapshot_id));
+        assert_eq!(fixture.provider_data.safe_block_number(), 5);
+        assert_eq!(fixture.provider_data.finalized_block_number(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn block_by_block_spec_pending() -> anyhow::Result<()> {
+        let fixture = ProviderTestFixture::new_local()?;
+
+        let block_spec = BlockSpec::Tag(BlockTag::Pending);
+
+        let block = fixture.provider_data.block_by_block_spec(&block_spec)?;
+
+        assert!(block.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn blob_base_fee() -> anyhow::Result<()> {
+        let fixture = ProviderTestFixture::new_local()?;
+
+        // The minimum blob gas price, as there is no excess blob gas
+        let blob_base_fee = fixture.provider_data.next_block_blob_base_fee()?;
+        assert_eq!(blob_base_fee, U256::from(1));
+
+        let runtime = runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .thread_name("provider-data-test")
+            .build()?;
+
+        let config = ProviderConfig {
+            hardfork: SpecId::SHANGHAI,
+            initial_blob_gas: None,
+            initial_parent_beacon_block_root: None,
+            ..create_test_config()
+        };
+
+        let fixture = ProviderTestFixture::new(runtime, config)?;
+
+        let result = fixture.provider_data.next_block_blob_base_fee();
+        assert!(matches!(
+            result,
+            Err(ProviderError::UnmetHardfork {
+                actual: SpecId::SHANGHAI,
+                minimum: SpecId::CANCUN,
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn blob_sidecars_by_block_spec() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let sidecar = BlobSidecar {
+            blobs: vec![Bytes::from_static(&[1u8])],
+            commitments: vec![FixedBytes::repeat_byte(2)],
+            proofs: vec![FixedBytes::repeat_byte(3)],
+        };
+
+        let transaction = fixture.signed_dummy_transaction(0, None)?;
+        let transaction_hash = *transaction.hash();
+
+        fixture
+            .provider_data
+            .add_blob_sidecar(transaction_hash, sidecar.clone());
+        fixture.provider_data.add_pending_transaction(transaction)?;
+
+        let pending_sidecars = fixture
+            .provider_data
+            .blob_sidecars_by_block_spec(&BlockSpec::pending())?;
+        assert_eq!(pending_sidecars, vec![(transaction_hash, sidecar.clone())]);
+
+        fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?;
+
+        let mined_sidecars = fixture
+            .provider_data
+            .blob_sidecars_by_block_spec(&BlockSpec::latest())?;
+        assert_eq!(mined_sidecars, vec![(transaction_hash, sidecar)]);
+
+        let genesis_sidecars = fixture
+            .provider_data
+            .blob_sidecars_by_block_spec(&BlockSpec::Number(0))?;
+        assert!(genesis_sidecars.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn blob_sidecars_are_pruned_on_revert_and_reorg() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let sidecar = BlobSidecar {
+            blobs: vec![Bytes::from_static(&[1u8])],
+            commitments: vec![FixedBytes::repeat_byte(2)],
+            proofs: vec![FixedBytes::repeat_byte(3)],
+        };
+
+        let snapshot_id = fixture.provider_data.make_snapshot();
+
+        let transaction = fixture.signed_dummy_transaction(0, None)?;
+        let transaction_hash = *transaction.hash();
+
+        fixture
+            .provider_data
+            .add_blob_sidecar(transaction_hash, sidecar.clone());
+        fixture.provider_data.add_pending_transaction(transaction)?;
+        fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?;
+
+        assert!(fixture.provider_data.revert_to_snapshot(snapshot_id));
+
+        // Mine a block without the transaction at the reverted block's height
+        fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?;
+
+        let sidecars = fixture
+            .provider_data
+            .blob_sidecars_by_block_spec(&BlockSpec::Number(1))?;
+        assert!(sidecars.is_empty());
+
+        // A reorg that drops the transaction returns its sidecar to the pending
+        // sidecars
+        let transaction = fixture.signed_dummy_transaction(0, Some(0))?;
+        let transaction_hash = *transaction.hash();
+
+        fixture
+            .provider_data
+            .add_blob_sidecar(transaction_hash, sidecar.clone());
+        fixture.provider_data.add_pending_transaction(transaction)?;
+        fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?;
+
+        fixture.provider_data.reorg(1, Some(vec![Vec::new()]))?;
+
+        let mined_sidecars = fixture
+            .provider_data
+            .blob_sidecars_by_block_spec(&BlockSpec::latest())?;
+        assert!(mined_sidecars.is_empty());
+
+        let pending_sidecars = fixture
+            .provider_data
+            .blob_sidecars_by_block_spec(&BlockSpec::pending())?;
+        assert_eq!(pending_sidecars, vec![(transaction_hash, sidecar)]);
+
+        Ok(())
+    }
+
+    // Make sure executing a transaction in a pending block context doesn't panic.
+    #[test]
+    fn execute_in_block_context_pending() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let block_spec = Some(BlockSpec::Tag(BlockTag::Pending));
+
+        let mut value = 0;
+        let _ =
+            fixture
+                .provider_data
+                .execute_in_block_context(block_spec.as_ref(), |_, _, _| {
+                    value += 1;
+                    Ok::<(), ProviderError<Infallible>>(())
+                })?;
+
+        assert_eq!(value, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn chain_id() -> anyhow::Result<()> {
+        let fixture = ProviderTestFixture::new_local()?;
+
+        let chain_id = fixture.provider_data.chain_id();
+        assert_eq!(chain_id, fixture.config.chain_id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn chain_id_fork_mode() -> anyhow::Result<()> {
+        let fixture = ProviderTestFixture::new_forked(None)?;
+
+        let chain_id = fixture.provider_data.chain_id();
+        assert_eq!(chain_id, fixture.config.chain_id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn debug_trace_call_with_call_tracer() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+        let transaction = fixture.signed_dummy_transaction(0, None)?;
+        let sender = *transaction.caller();
+
+        let output = fixture.provider_data.debug_trace_call(
+            transaction,
+            None,
+            &BlockOverrides::default(),
+            DebugTraceConfig {
+                tracer: DebugTracer::Call(CallTracerConfig::default()),
+                ..DebugTraceConfig::default()
+            },
+        )?;
+
+        let DebugTraceOutput::Call(call_frame) = output else {
+            return Err(anyhow!("expected a call frame"));
+        };
+
+        assert_eq!(call_frame.kind, CallKind::Call);
+        assert_eq!(call_frame.from, sender);
+        assert_eq!(call_frame.to, Some(Address::ZERO));
+        assert_eq!(call_frame.gas, 30_000);
+        assert_eq!(call_frame.gas_used, 21_000);
+        assert_eq!(call_frame.value, Some(U256::from(1)));
+        assert!(call_frame.error.is_none());
+        assert!(call_frame.calls.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn debug_trace_block_with_call_tracer() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let transactions = [
+            fixture.signed_dummy_transaction(0, None)?,
+            fixture.signed_dummy_transaction(0, Some(1))?,
+        ];
+        let transaction_hashes: Vec<B256> = transactions
+            .iter()
+            .map(|transaction| *transaction.hash())
+            .collect();
+
+        for transaction in transactions {
+            fixture.provider_data.add_pending_transaction(transaction)?;
+        }
+
+        let result = fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?;
+
+        let traces = fixture.provider_data.debug_trace_block(
+            result.block.header(),
+            result.block.transactions().to_vec(),
+            DebugTraceConfig {
+                tracer: DebugTracer::Call(CallTracerConfig::default()),
+                ..DebugTraceConfig::default()
+            },
+        )?;
+
+        assert_eq!(traces.len(), 2);
+        for (trace, transaction_hash) in traces.iter().zip(transaction_hashes) {
+            assert_eq!(trace.tx_hash, transaction_hash);
+
+            let DebugTraceOutput::Call(call_frame) = &trace.result else {
+                return Err(anyhow!("expected a call frame"));
+            };
+            assert_eq!(call_frame.gas_used, 21_000);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn debug_trace_block_genesis() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let genesis = fixture
+            .provider_data
+            .block_by_block_spec(&BlockSpec::Number(0))?
+            .context("genesis block exists")?;
+
+        let result = fixture.provider_data.debug_trace_block(
+            genesis.header(),
+            Vec::new(),
+            DebugTraceConfig::default(),
+        );
+
+        assert!(matches!(result, Err(ProviderError::InvalidInput(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn trace_block_with_transfers() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        for nonce in 0..2 {
+            let transaction = fixture.signed_dummy_transaction(0, Some(nonce))?;
+            fixture.provider_data.add_pending_transaction(transaction)?;
+        }
+
+        let result = fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?;
+
+        let results = fixture.provider_data.trace_block(
+            result.block.header(),
+            result.block.transactions().to_vec(),
+            ParityTraceConfig {
+                trace: true,
+                ..ParityTraceConfig::default()
+            },
+        )?;
+
+        assert_eq!(results.len(), 2);
+        for result in results {
+            assert_eq!(result.trace.len(), 1);
+            assert!(result.trace[0].trace_address.is_empty());
+            assert!(matches!(
+                &result.trace[0].action,
+                Action::Call(action) if action.to == Address::ZERO && action.value == U256::from(1)
+            ));
+            assert!(result.state_diff.is_none());
+            assert!(result.vm_trace.is_none());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn trace_replay_transaction_with_state_diff() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let transaction = fixture.signed_dummy_transaction(0, None)?;
+        let sender = *transaction.caller();
+        let transaction_hash = fixture
+            .provider_data
+            .send_transaction(transaction)?
+            .transaction_hash;
+
+        let results = fixture.provider_data.trace_replay_transaction(
+            &transaction_hash,
+            ParityTraceConfig {
+                trace: false,
+                vm_trace: true,
+                state_diff: true,
+            },
+        )?;
+
+        assert!(results.trace.is_empty());
+        assert!(results.vm_trace.is_some());
+
+        let state_diff = results.state_diff.context("state diff was requested")?;
+        let sender_diff = state_diff.get(&sender).context("sender was modified")?;
+        assert_eq!(
+            sender_diff.nonce,
+            Delta::Changed(ChangedValue {
+                from: U64::from(0),
+                to: U64::from(1),
+            })
+        );
+
+        let receiver_diff = state_diff
+            .get(&Address::ZERO)
+            .context("receiver was modified")?;
+        assert!(matches!(
+            receiver_diff.balance,
+            Delta::Changed(_) | Delta::Added(_)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn gas_profile_transaction_with_nested_call() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+        let ConsoleLogTransaction { transaction, .. } =
+            deploy_console_log_contract(&mut fixture.provider_data)?;
+
+        let signed_transaction = fixture
+            .provider_data
+            .sign_transaction_request(transaction)?;
+        let transaction_hash = fixture
+            .provider_data
+            .send_transaction(signed_transaction)?
+            .transaction_hash;
+
+        let profile = fixture
+            .provider_data
+            .gas_profile_transaction(&transaction_hash)?;
+
+        // 21_000 + 4 non-zero calldata bytes
+        assert_eq!(profile.intrinsic_gas, 21_064);
+
+        let root = profile.root.as_ref().context("transaction was executed")?;
+        assert!(root.name.starts_with("CALL "));
+        assert!(root.name.ends_with("::0x26121ff0"));
+        assert!(root.opcode_gas.contains_key(&OpcodeClass::Call));
+
+        // console.log is a static call to an address without code
+        assert_eq!(root.children.len(), 1);
+        let console_log = &root.children[0];
+        assert!(console_log.name.starts_with("STATICCALL "));
+        assert_eq!(
+            root.inclusive_gas,
+            root.exclusive_gas + console_log.inclusive_gas
+        );
+
+        assert_eq!(
+            profile.gas_used,
+            profile.intrinsic_gas + root.inclusive_gas - profile.gas_refunded
+        );
+        assert!(profile
+            .to_folded_stacks()
+            .starts_with("intrinsic 21064\nCALL "));
+
+        Ok(())
+    }
+
+    #[test]
+    fn debug_trace_call_with_prestate_tracer_diff_mode() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+        let transaction = fixture.signed_dummy_transaction(0, None)?;
+        let sender = *transaction.caller();
+
+        let output = fixture.provider_data.debug_trace_call(
+            transaction,
+            None,
+            &BlockOverrides::default(),
+            DebugTraceConfig {
+                tracer: DebugTracer::Prestate(PrestateTracerConfig { diff_mode: true }),
+                ..DebugTraceConfig::default()
+            },
+        )?;
+
+        let DebugTraceOutput::Prestate(PrestateTrace::Diff { pre, post }) = output else {
+            return Err(anyhow!("expected a state diff"));
+        };
+
+        let pre_sender = pre.get(&sender).context("sender was modified")?;
+        assert_eq!(pre_sender.balance, Some(one_ether()));
+
+        let post_sender = post.get(&sender).context("sender was modified")?;
+        assert_eq!(post_sender.nonce, Some(1));
+
+        let post_receiver = post.get(&Address::ZERO).context("receiver was modified")?;
+        assert_eq!(post_receiver.balance, Some(U256::from(1)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn console_log_mine_block() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+        let ConsoleLogTransaction {
+            transaction,
+            expected_call_data,
+        } = deploy_console_log_contract(&mut fixture.provider_data)?;
+
+        let signed_transaction = fixture
+            .provider_data
+            .sign_transaction_request(transaction)?;
+
+        fixture.provider_data.set_auto_mining(false);
+        fixture.provider_data.send_transaction(signed_transaction)?;
+        let (block_timestamp, _) = fixture.provider_data.next_block_timestamp(None)?;
+        let prevrandao = fixture.provider_data.prev_randao_generator.next_value();
+        let result = fixture.provider_data.mine_block(BlockOptions {
+            timestamp: Some(block_timestamp),
+            mix_hash: Some(prevrandao),
+            ..BlockOptions::default()
+        })?;
+
+        let console_log_inputs = result.console_log_inputs;
+        assert_eq!(console_log_inputs.len(), 1);
+        assert_eq!(console_log_inputs[0], expected_call_data);
+        assert_eq!(result.console_log_messages, vec!["hello".to_string()]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn console_log_run_call() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+        let ConsoleLogTransaction {
+            transaction,
+            expected_call_data,
+        } = deploy_console_log_contract(&mut fixture.provider_data)?;
+
+        let pending_transaction = fixture
+            .provider_data
+            .sign_transaction_request(transaction)?;
+
+        let result = fixture.provider_data.run_call(
+            pending_transaction,
+            None,
+            &StateOverrides::default(),
+            &BlockOverrides::default(),
+        )?;
+
+        let console_log_inputs = result.console_log_inputs;
+        assert_eq!(console_log_inputs.len(), 1);
+        assert_eq!(console_log_inputs[0], expected_call_data);
+        assert_eq!(result.console_log_messages, vec!["hello".to_string()]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dump_and_load_state() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let sidecar = BlobSidecar {
+            blobs: vec![Bytes::from_static(&[1u8])],
+            commitments: vec![FixedBytes::repeat_byte(2)],
+            proofs: vec![FixedBytes::repeat_byte(3)],
+        };
+
+        let transaction = fixture.signed_dummy_transaction(0, Some(0))?;
+        fixture
+            .provider_data
+            .add_blob_sidecar(*transaction.hash(), sidecar.clone());
+        fixture.provider_data.add_pending_transaction(transaction)?;
+        fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?;
+
+        // Reserves blocks, one of which is retrieved before dumping the state
+        fixture.provider_data.mine_and_commit_blocks(100, 12)?;
+        let reserved_block = fixture
+            .provider_data
+            .block_by_block_spec(&BlockSpec::Number(50))?
+            .context("reserved block should exist")?;
+
+        let transaction = fixture.signed_dummy_transaction(0, Some(1))?;
+        fixture.provider_data.add_pending_transaction(transaction)?;
+        fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?;
+
+        let modified_account = Address::random();
+        fixture
+            .provider_data
+            .set_balance(modified_account, one_ether())?;
+
+        let pending_transaction = fixture.signed_dummy_transaction(1, Some(0))?;
+        fixture
+            .provider_data
+            .add_blob_sidecar(*pending_transaction.hash(), sidecar);
+        fixture
+            .provider_data
+            .add_pending_transaction(pending_transaction)?;
+        let future_transaction = fixture.signed_dummy_transaction(1, Some(2))?;
+        fixture
+            .provider_data
+            .add_pending_transaction(future_transaction)?;
+
+        fixture
+            .provider_data
+            .add_withdrawals(vec![WithdrawalRequest {
+                validator_index: 1,
+                address: Address::random(),
+                amount: U256::from(1_000),
+            }])?;
+
+        let state_file = tempfile::NamedTempFile::new()?;
+        fixture
+            .provider_data
+            .dump_state()?
+            .write_to_file(state_file.path())?;
+
+        let mut loaded = ProviderTestFixture::new_local()?;
+        loaded
+            .provider_data
+            .load_state(StateDump::read_from_file(state_file.path())?)?;
+
+        assert_eq!(
+            loaded.provider_data.last_block_number(),
+            fixture.provider_data.last_block_number()
+        );
+        assert_eq!(
+            loaded.provider_data.last_block()?.hash(),
+            fixture.provider_data.last_block()?.hash()
+        );
+
+        let loaded_reserved_block = loaded
+            .provider_data
+            .block_by_block_spec(&BlockSpec::Number(50))?
+            .context("reserved block should exist")?;
+        assert_eq!(loaded_reserved_block.hash(), reserved_block.hash());
+
+        let loaded_unfulfilled_block = loaded
+            .provider_data
+            .block_by_block_spec(&BlockSpec::Number(60))?
+            .context("reserved block should exist")?;
+        let unfulfilled_block = fixture
+            .provider_data
+            .block_by_block_spec(&BlockSpec::Number(60))?
+            .context("reserved block should exist")?;
+        assert_eq!(loaded_unfulfilled_block.hash(), unfulfilled_block.hash());
+
+        for address in [
+            modified_account,
+            fixture.impersonated_account,
+            fixture.nth_local_account(0)?,
+        ] {
+            assert_eq!(
+                loaded.provider_data.balance(address, None)?,
+                fixture.provider_data.balance(address, None)?
+            );
+        }
+
+        assert_eq!(
+            loaded
+                .provider_data
+                .pending_transactions()
+                .collect::<Vec<_>>(),
+            fixture
+                .provider_data
+                .pending_transactions()
+                .collect::<Vec<_>>()
+        );
+        assert!(loaded
+            .provider_data
+            .impersonated_accounts
+            .contains(&fixture.impersonated_account));
+
+        assert_eq!(
+            loaded.provider_data.queued_withdrawals,
+            fixture.provider_data.queued_withdrawals
+        );
+
+        for block_spec in [BlockSpec::Number(1), BlockSpec::pending()] {
+            let sidecars = fixture
+                .provider_data
+                .blob_sidecars_by_block_spec(&block_spec)?;
+            assert_eq!(sidecars.len(), 1);
+            assert_eq!(
+                loaded
+                    .provider_data
+                    .blob_sidecars_by_block_spec(&block_spec)?,
+                sidecars
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn dump_and_load_state_with_timestamp_activated_hardfork() -> anyhow::Result<()> {
+        let mut config = create_test_config();
+        config.hardfork = SpecId::CANCUN;
+
+        let cancun_timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 60;
+        config.chains.insert(
+            config.chain_id,
+            HardforkActivations::from(
+                &[
+                    (ForkCondition::Block(0), SpecId::SHANGHAI),
+                    (ForkCondition::Timestamp(cancun_timestamp), SpecId::CANCUN),
+                ][..],
+            ),
+        );
+
+        let create_fixture = |config: ProviderConfig| -> anyhow::Result<ProviderTestFixture> {
+            let runtime = runtime::Builder::new_multi_thread()
+                .worker_threads(1)
+                .enable_all()
+                .thread_name("provider-data-test")
+                .build()?;
+
+            ProviderTestFixture::new(runtime, config)
+        };
+
+        let mut fixture = create_fixture(config.clone())?;
+
+        let transaction = fixture.signed_dummy_transaction(0, Some(0))?;
+        let transaction_hash = fixture.provider_data.add_pending_transaction(transaction)?;
+        fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?;
+        fixture.provider_data.mine_and_commit_block(BlockOptions {
+            timestamp: Some(cancun_timestamp),
+            ..BlockOptions::default()
+        })?;
+
+        let dump = fixture.provider_data.dump_state()?;
+
+        let mut loaded = create_fixture(config)?;
+        loaded.provider_data.load_state(dump)?;
+
+        let receipt = loaded
+            .provider_data
+            .transaction_receipt(&transaction_hash)?
+            .context("receipt should exist")?;
+        assert_eq!(receipt.inner.inner.spec_id, SpecId::SHANGHAI);
+
+        Ok(())
+    }
+
+    #[test]
+    fn load_state_with_different_chain_id() -> anyhow::Result<()> {
+        let fixture = ProviderTestFixture::new_local()?;
+
+        let state_file = tempfile::NamedTempFile::new()?;
+        fixture
+            .provider_data
+            .dump_state()?
+            .write_to_file(state_file.path())?;
+
+        let mut config = create_test_config();
+        config.chain_id += 1;
+        config.state_path = Some(state_file.path().to_path_buf());
+
+        let runtime = runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .thread_name("provider-data-test")
+            .build()?;
+
+        let result = ProviderTestFixture::new(runtime, config);
+        assert!(matches!(
+            result
+                .err()
+                .and_then(|error| error.downcast::<CreationError>().ok()),
+            Some(CreationError::StateDump(
+                StateDumpError::ChainIdMismatch { .. }
+            ))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn create_from_genesis() -> anyhow::Result<()> {
+        let address = Address::repeat_byte(0x42);
+        let genesis: edr_eth::genesis::Genesis = serde_json::from_value(serde_json::json!({
+            "config": {
+                "chainId": 1337,
+                "homesteadBlock": 0,
+                "eip150Block": 0,
+                "eip155Block": 0,
+                "eip158Block": 0,
+                "byzantiumBlock": 0,
+                "constantinopleBlock": 0,
+                "petersburgBlock": 0,
+                "istanbulBlock": 0,
+                "berlinBlock": 0,
+                "londonBlock": 0,
+                "mergeNetsplitBlock": 0,
+                "shanghaiTime": 0,
+                "terminalTotalDifficulty": 0
+            },
+            "timestamp": "0x6553f100",
+            "extraData": "0xabcd",
+            "gasLimit": "0x1312d00",
+            "baseFeePerGas": "0x7",
+            "alloc": {
+                address.to_string(): {
+                    "code": "0x6001",
+                    "storage": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000001": "0x0000000000000000000000000000000000000000000000000000000000000002"
+                    },
+                    "balance": "0x0",
+                    "nonce": "0x1"
+                }
+            }
+        }))?;
+
+        let mut config = create_test_config();
+        config.apply_genesis(genesis);
+
+        let runtime = runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .thread_name("provider-data-test")
+            .build()?;
+
+        let mut fixture = ProviderTestFixture::new(runtime, config)?;
+
+        assert_eq!(fixture.provider_data.chain_id(), 1337);
+        assert_eq!(fixture.provider_data.spec_id(), SpecId::SHANGHAI);
+
+        let genesis_block = fixture.provider_data.last_block()?;
+        let header = genesis_block.header();
+        assert_eq!(header.timestamp, 0x6553f100);
+        assert_eq!(header.gas_limit, 20_000_000);
+        assert_eq!(header.extra_data, Bytes::from_static(&[0xab, 0xcd]));
+        assert_eq!(header.base_fee_per_gas, Some(U256::from(7)));
+
+        assert_eq!(
+            fixture.provider_data.get_code(address, None)?,
+            Bytes::from_static(&[0x60, 0x01])
+        );
+        assert_eq!(
+            fixture
+                .provider_data
+                .get_storage_at(address, U256::from(1), None)?,
+            U256::from(2)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn create_from_genesis_with_timestamp_activated_hardfork() -> anyhow::Result<()> {
+        const GENESIS_TIMESTAMP: u64 = 0x6553f100;
+        const CANCUN_TIMESTAMP: u64 = GENESIS_TIMESTAMP + 100;
+
+        let genesis: edr_eth::genesis::Genesis = serde_json::from_value(serde_json::json!({
+            "config": {
+                "chainId": 1337,
+                "homesteadBlock": 0,
+                "eip150Block": 0,
+                "eip155Block": 0,
+                "eip158Block": 0,
+                "byzantiumBlock": 0,
+                "constantinopleBlock": 0,
+                "petersburgBlock": 0,
+                "istanbulBlock": 0,
+                "berlinBlock": 0,
+                "londonBlock": 0,
+                "mergeNetsplitBlock": 0,
+                "shanghaiTime": 0,
+                "cancunTime": CANCUN_TIMESTAMP,
+                "terminalTotalDifficulty": 0
+            },
+            "timestamp": format!("{GENESIS_TIMESTAMP:#x}"),
+            "gasLimit": "0x1312d00",
+            "baseFeePerGas": "0x7",
+            "alloc": {}
+        }))?;
+
+        let mut config = create_test_config();
+        config.apply_genesis(genesis);
+
+        let runtime = runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .thread_name("provider-data-test")
+            .build()?;
+
+        let mut fixture = ProviderTestFixture::new(runtime, config)?;
+        assert_eq!(fixture.provider_data.spec_id(), SpecId::CANCUN);
+
+        let genesis_block = fixture.provider_data.last_block()?;
+        assert!(genesis_block.header().blob_gas.is_none());
+        assert!(genesis_block.header().parent_beacon_block_root.is_none());
+        assert_eq!(
+            fixture.provider_data.spec_at_block_number(0)?,
+            SpecId::SHANGHAI
+        );
+
+        let shanghai_block = fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions {
+                timestamp: Some(CANCUN_TIMESTAMP - 1),
+                ..BlockOptions::default()
+            })?
+            .block;
+        assert!(shanghai_block.header().parent_beacon_block_root.is_none());
+        assert_eq!(
+            fixture.provider_data.spec_at_block_number(1)?,
+            SpecId::SHANGHAI
+        );
+
+        let cancun_block = fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions {
+                timestamp: Some(CANCUN_TIMESTAMP),
+                ..BlockOptions::default()
+            })?
+            .block;
+        assert!(cancun_block.header().blob_gas.is_some());
+        assert!(cancun_block.header().parent_beacon_block_root.is_some());
+        assert_eq!(
+            fixture.provider_data.spec_at_block_number(2)?,
+            SpecId::CANCUN
+        );
 
         Ok(())
     }
 
     #[test]
-    fn console_log_run_call() -> anyhow::Result<()> {
+    fn create_from_geth_genesis_matches_hash() -> anyhow::Result<()> {
+        // The custom genesis of geth's `TestSetupGenesis`
+        let genesis: edr_eth::genesis::Genesis = serde_json::from_value(serde_json::json!({
+            "config": {
+                "chainId": 1,
+                "homesteadBlock": 3
+            },
+            "alloc": {
+                "0x0100000000000000000000000000000000000000": {
+                    "balance": "0x1",
+                    "storage": {
+                        "0x0100000000000000000000000000000000000000000000000000000000000000": "0x0100000000000000000000000000000000000000000000000000000000000000"
+                    }
+                }
+            }
+        }))?;
+
+        let mut config = create_test_config();
+        config.accounts = Vec::new();
+        config.apply_genesis(genesis);
+
+        let runtime = runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .thread_name("provider-data-test")
+            .build()?;
+
+        // The test fixture adds an impersonated account to the genesis state
+        let InitialAccounts {
+            genesis_accounts, ..
+        } = create_accounts(&config);
+        let BlockchainAndState { blockchain, .. } =
+            create_blockchain_and_state(runtime.handle().clone(), &config, genesis_accounts)?;
+
+        let genesis_block = blockchain.last_block()?;
+        assert_eq!(
+            *genesis_block.hash(),
+            "0x89c99d90b79719238d2645c7642f2c9295246e80775b38cfd162b696817fbd50".parse::<B256>()?
+        );
+
+        let header = genesis_block.header();
+        assert_eq!(header.difficulty, U256::from(131_072));
+        assert_eq!(header.gas_limit, 4_712_388);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dump_genesis_round_trip() -> anyhow::Result<()> {
+        let address = Address::repeat_byte(0x42);
+
         let mut fixture = ProviderTestFixture::new_local()?;
-        let ConsoleLogTransaction {
+        fixture
+            .provider_data
+            .set_balance(address, U256::from(1_000))?;
+        fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?;
+
+        let genesis = fixture.provider_data.dump_genesis()?;
+
+        let mut config = create_test_config();
+        config.accounts = Vec::new();
+        config.apply_genesis(genesis);
+
+        let runtime = runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .thread_name("provider-data-test")
+            .build()?;
+
+        let mut restored = ProviderTestFixture::new(runtime, config)?;
+        assert_eq!(
+            restored.provider_data.balance(address, None)?,
+            U256::from(1_000)
+        );
+
+        let original_account = fixture.impersonated_account;
+        assert_eq!(
+            restored.provider_data.balance(original_account, None)?,
+            fixture.provider_data.balance(original_account, None)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn optimism_charges_l1_fee_and_collects_base_fee() -> anyhow::Result<()> {
+        use edr_eth::rlp::Encodable;
+        use edr_evm::optimism::{BASE_FEE_VAULT_ADDRESS, L1_BLOCK_ADDRESS, L1_FEE_VAULT_ADDRESS};
+
+        const L1_BASE_FEE: u64 = 1_000;
+        const L1_FEE_OVERHEAD: u64 = 188;
+
+        let mut config = create_test_config();
+        config.optimism = true;
+        config.genesis_accounts.insert(
+            L1_BLOCK_ADDRESS,
+            AccountInfo {
+                code: None,
+                ..AccountInfo::default()
+            },
+        );
+        config.genesis_storage.insert(
+            L1_BLOCK_ADDRESS,
+            [
+                (U256::from(1), U256::from(L1_BASE_FEE)),
+                (U256::from(5), U256::from(L1_FEE_OVERHEAD)),
+                // A scalar of 1.0
+                (U256::from(6), U256::from(1_000_000)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        let runtime = runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .thread_name("provider-data-test")
+            .build()?;
+
+        let mut fixture = ProviderTestFixture::new(runtime, config)?;
+        fixture.provider_data.set_auto_mining(false);
+
+        let transaction = fixture.signed_dummy_transaction(0, None)?;
+        let mut encoded = Vec::new();
+        transaction.encode(&mut encoded);
+        let data_gas = encoded
+            .iter()
+            .map(|byte| if *byte == 0 { 4u64 } else { 16 })
+            .sum::<u64>();
+
+        let transaction_hash = fixture
+            .provider_data
+            .send_transaction(transaction)?
+            .transaction_hash;
+
+        let result = fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?;
+        let base_fee = result
+            .block
+            .header()
+            .base_fee_per_gas
+            .context("post-London blocks have a base fee")?;
+
+        let receipt = fixture
+            .provider_data
+            .transaction_receipt(&transaction_hash)?
+            .context("transaction was mined")?;
+        let l1_fee = receipt.l1_fee.clone().context("receipt has an L1 fee")?;
+
+        let expected_l1_fee = U256::from((data_gas + L1_FEE_OVERHEAD) * L1_BASE_FEE);
+        assert_eq!(l1_fee.l1_gas_used, U256::from(data_gas + L1_FEE_OVERHEAD));
+        assert_eq!(l1_fee.l1_fee, expected_l1_fee);
+        assert_eq!(l1_fee.l1_fee_scalar.as_deref(), Some("1"));
+
+        assert_eq!(
+            fixture.provider_data.balance(L1_FEE_VAULT_ADDRESS, None)?,
+            expected_l1_fee
+        );
+        assert_eq!(
+            fixture
+                .provider_data
+                .balance(BASE_FEE_VAULT_ADDRESS, None)?,
+            base_fee * U256::from(receipt.gas_used)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn optimism_trace_replay_transaction_with_deposits() -> anyhow::Result<()> {
+        use std::sync::OnceLock;
+
+        use edr_eth::transaction::{DepositTransaction, SignedTransaction};
+        use edr_evm::optimism::L1_BLOCK_ADDRESS;
+
+        const MINT: u64 = 1_000;
+
+        let depositor = Address::random();
+
+        let mut config = create_test_config();
+        config.optimism = true;
+        config.mining.mem_pool.order = MineOrdering::Fifo;
+        // The mem pool requires the depositor to cover the value without the mint
+        config.genesis_accounts.insert(
+            depositor,
+            AccountInfo {
+                balance: U256::from(MINT),
+                ..AccountInfo::default()
+            },
+        );
+        config.genesis_accounts.insert(
+            L1_BLOCK_ADDRESS,
+            AccountInfo {
+                code: None,
+                ..AccountInfo::default()
+            },
+        );
+        config.genesis_storage.insert(
+            L1_BLOCK_ADDRESS,
+            [
+                (U256::from(1), U256::from(1_000)),
+                (U256::from(5), U256::from(188)),
+                (U256::from(6), U256::from(1_000_000)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        let runtime = runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .thread_name("provider-data-test")
+            .build()?;
+
+        let mut fixture = ProviderTestFixture::new(runtime, config)?;
+
+        let spec_id = fixture.provider_data.spec_id();
+        let deposit = |from: Address, to: Address, value: U256, gas_limit: u64| {
+            let transaction = SignedTransaction::Deposit(DepositTransaction {
+                source_hash: B256::random(),
+                from,
+                kind: TransactionKind::Call(to),
+                mint: U256::from(MINT),
+                value,
+                gas_limit,
+                is_system_tx: false,
+                input: Bytes::new(),
+                hash: OnceLock::new(),
+            });
+
+            ExecutableTransaction::with_caller(spec_id, transaction, from)
+        };
+
+        // Transfers the minted value
+        let recipient = Address::random();
+        let successful_deposit = deposit(depositor, recipient, U256::from(MINT), 100_000)?;
+        // The gas limit doesn't cover the intrinsic gas, so the deposit fails
+        let failed_deposit = deposit(Address::random(), Address::random(), U256::ZERO, 1_000)?;
+        let transaction = fixture.signed_dummy_transaction(0, None)?;
+        let sender = *transaction.caller();
+
+        let successful_deposit_hash = *successful_deposit.hash();
+        let transaction_hash = *transaction.hash();
+
+        let result = fixture
+            .provider_data
+            .mine_and_commit_block_with_transactions(vec![
+                successful_deposit,
+                failed_deposit,
+                transaction,
+            ])?;
+        assert_eq!(result.block.transactions().len(), 3);
+
+        let trace_config = ParityTraceConfig {
+            trace: false,
+            vm_trace: false,
+            state_diff: true,
+        };
+
+        // The deposit's value is paid from its mint
+        let results = fixture
+            .provider_data
+            .trace_replay_transaction(&successful_deposit_hash, trace_config.clone())?;
+        let state_diff = results.state_diff.context("state diff was requested")?;
+        let depositor_diff = state_diff
+            .get(&depositor)
+            .context("depositor was modified")?;
+        let Delta::Changed(ChangedValue { to: balance, .. }) = depositor_diff.balance else {
+            return Err(anyhow!("depositor balance should have changed"));
+        };
+        assert_eq!(balance, fixture.provider_data.balance(depositor, None)?);
+        assert_eq!(balance, U256::from(MINT));
+
+        let recipient_diff = state_diff
+            .get(&recipient)
+            .context("recipient was modified")?;
+        assert_eq!(recipient_diff.balance, Delta::Added(U256::from(MINT)));
+
+        // The failed deposit doesn't abort the replay, and the L1 fee is charged
+        let results = fixture
+            .provider_data
+            .trace_replay_transaction(&transaction_hash, trace_config)?;
+        let state_diff = results.state_diff.context("state diff was requested")?;
+        let sender_diff = state_diff.get(&sender).context("sender was modified")?;
+        let Delta::Changed(ChangedValue { to: balance, .. }) = sender_diff.balance else {
+            return Err(anyhow!("sender balance should have changed"));
+        };
+        assert_eq!(balance, fixture.provider_data.balance(sender, None)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_call_with_cheatcodes() -> anyhow::Result<()> {
+        /// Runs init code that calls `vm.warp(12345)` and returns
+        /// `block.timestamp`.
+        fn timestamp_after_warp(enable_cheatcodes: bool) -> anyhow::Result<U256> {
+            let init_code = hex::decode(
+                "63e5d6bf0260e01b6000526130396004526000600060246000600073\
+                7109709ecfa91a80626ff3989d68f67f5b1dd12d5af1504260005260206000f3",
+            )?;
+
+            let runtime = runtime::Builder::new_multi_thread()
+                .worker_threads(1)
+                .enable_all()
+                .thread_name("provider-data-test")
+                .build()?;
+
+            let mut config = create_test_config();
+            config.enable_cheatcodes = enable_cheatcodes;
+
+            let mut fixture = ProviderTestFixture::new(runtime, config)?;
+
+            let request = CallRequest {
+                from: Some(fixture.nth_local_account(0)?),
+                data: Some(init_code.into()),
+                ..CallRequest::default()
+            };
+
+            let block_spec = BlockSpec::latest();
+            let state_overrides = StateOverrides::default();
+
+            let transaction = resolve_call_request(
+                &mut fixture.provider_data,
+                request,
+                Some(&block_spec),
+                &state_overrides,
+            )?;
+
+            let result = fixture.provider_data.run_call(
+                transaction,
+                Some(&block_spec),
+                &state_overrides,
+                &BlockOverrides::default(),
+            )?;
+
+            let output = result
+                .execution_result
+                .into_output()
+                .context("call has output")?;
+
+            U256::try_from_be_slice(&output).context("output is a word")
+        }
+
+        assert_eq!(timestamp_after_warp(true)?, U256::from(12_345));
+        assert_ne!(timestamp_after_warp(false)?, U256::from(12_345));
+
+        Ok(())
+    }
+
+    #[test]
+    fn coverage_report_of_run_call() -> anyhow::Result<()> {
+        let runtime = runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .thread_name("provider-data-test")
+            .build()?;
+
+        let mut config = create_test_config();
+        config.enable_coverage = true;
+
+        let mut fixture = ProviderTestFixture::new(runtime, config)?;
+
+        let compiler_input: CompilerInput =
+            serde_json::from_str(include_str!("../tests/fixtures/compiler_input.json"))?;
+        let compiler_output: CompilerOutput =
+            serde_json::from_str(include_str!("../tests/fixtures/compiler_output.json"))?;
+
+        fixture.provider_data.add_compilation_result(
+            "0.8.0".to_string(),
+            &compiler_input,
+            &compiler_output,
+        )?;
+
+        let contract_address = Address::repeat_byte(0x42);
+        let runtime_code = &compiler_output.contracts["literal.sol"]["Nine"]
+            .evm
+            .deployed_bytecode
+            .object;
+
+        fixture
+            .provider_data
+            .set_code(contract_address, hex::decode(runtime_code)?.into())?;
+
+        let request = CallRequest {
+            from: Some(fixture.nth_local_account(0)?),
+            to: Some(contract_address),
+            // returnNine()
+            data: Some(hex::decode("df78ca51")?.into()),
+            ..CallRequest::default()
+        };
+
+        let block_spec = BlockSpec::latest();
+        let state_overrides = StateOverrides::default();
+
+        let transaction = resolve_call_request(
+            &mut fixture.provider_data,
+            request,
+            Some(&block_spec),
+            &state_overrides,
+        )?;
+
+        fixture.provider_data.run_call(
             transaction,
-            expected_call_data,
-        } = deploy_console_log_contract(&mut fixture.provider_data)?;
+            Some(&block_spec),
+            &state_overrides,
+            &BlockOverrides::default(),
+        )?;
+
+        let report = fixture
+            .provider_data
+            .coverage_report()
+            .context("coverage is enabled")?;
+
+        let file = report
+            .files
+            .iter()
+            .find(|file| file.source_name == "literal.sol")
+            .context("source file is known")?;
+
+        assert!(file
+            .functions
+            .iter()
+            .any(|function| function.name == "Nine.returnNine" && function.hits > 0));
+        assert!(!file.lines.is_empty());
+        assert!(file.lines.iter().all(|line| line.hits > 0));
+        assert!(report.to_lcov().starts_with("TN:\nSF:literal.sol\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn gas_report_of_run_call() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let compiler_input: CompilerInput =
+            serde_json::from_str(include_str!("../tests/fixtures/compiler_input.json"))?;
+        let compiler_output: CompilerOutput =
+            serde_json::from_str(include_str!("../tests/fixtures/compiler_output.json"))?;
+
+        fixture.provider_data.add_compilation_result(
+            "0.8.0".to_string(),
+            &compiler_input,
+            &compiler_output,
+        )?;
+
+        let contract_address = Address::repeat_byte(0x42);
+        let runtime_code = &compiler_output.contracts["literal.sol"]["Nine"]
+            .evm
+            .deployed_bytecode
+            .object;
+
+        fixture
+            .provider_data
+            .set_code(contract_address, hex::decode(runtime_code)?.into())?;
+
+        let block_spec = BlockSpec::latest();
+        let state_overrides = StateOverrides::default();
+
+        for _ in 0..2 {
+            let request = CallRequest {
+                from: Some(fixture.nth_local_account(0)?),
+                to: Some(contract_address),
+                // returnNine()
+                data: Some(hex::decode("df78ca51")?.into()),
+                ..CallRequest::default()
+            };
+
+            let transaction = resolve_call_request(
+                &mut fixture.provider_data,
+                request,
+                Some(&block_spec),
+                &state_overrides,
+            )?;
+
+            fixture.provider_data.run_call(
+                transaction,
+                Some(&block_spec),
+                &state_overrides,
+                &BlockOverrides::default(),
+            )?;
+        }
+
+        let report = fixture.provider_data.gas_report();
+        let contract = report
+            .contracts
+            .iter()
+            .find(|contract| contract.contract_name == "Nine")
+            .context("contract is known")?;
+
+        assert_eq!(contract.source_name, "literal.sol");
+        assert_eq!(contract.deployment, None);
+        assert_eq!(contract.functions.len(), 1);
+
+        let function = &contract.functions[0];
+        assert_eq!(function.name, "returnNine");
+        assert_eq!(function.gas.count, 2);
+        assert!(function.gas.min > 0);
+        assert_eq!(function.gas.min, function.gas.max);
+
+        fixture.provider_data.reset_gas_report();
+        assert!(fixture.provider_data.gas_report().contracts.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn solidity_stack_trace_of_reverting_transaction() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let compiler_input: CompilerInput =
+            serde_json::from_str(include_str!("../tests/fixtures/compiler_input.json"))?;
+        let compiler_output: CompilerOutput =
+            serde_json::from_str(include_str!("../tests/fixtures/compiler_output.json"))?;
+
+        fixture.provider_data.add_compilation_result(
+            "0.8.0".to_string(),
+            &compiler_input,
+            &compiler_output,
+        )?;
+
+        let contract_address = Address::repeat_byte(0x42);
+        let runtime_code = &compiler_output.contracts["literal.sol"]["Nine"]
+            .evm
+            .deployed_bytecode
+            .object;
+
+        fixture
+            .provider_data
+            .set_code(contract_address, hex::decode(runtime_code)?.into())?;
+
+        let mut send_reverting_transaction =
+            |nonce: u64, value: U256, input: &str| -> anyhow::Result<Vec<StackTraceEntry>> {
+                let request = TransactionRequest::Eip155(Eip155TransactionRequest {
+                    kind: TransactionKind::Call(contract_address),
+                    gas_limit: 100_000,
+                    gas_price: U256::from(42_000_000_000_u64),
+                    value,
+                    input: hex::decode(input)?.into(),
+                    nonce,
+                    chain_id: fixture.config.chain_id,
+                });
+
+                let sender = fixture.nth_local_account(0)?;
+                let transaction = fixture
+                    .provider_data
+                    .sign_transaction_request(TransactionRequestAndSender { request, sender })?;
+
+                let SendTransactionResult {
+                    transaction_hash,
+                    transaction_result,
+                    ..
+                } = fixture.provider_data.send_transaction(transaction)?;
+
+                let (result, trace) = transaction_result.context("transaction was auto-mined")?;
+                let mut failure =
+                    TransactionFailure::from_execution_result(&result, &transaction_hash, &trace)
+                        .context("transaction reverted")?;
+
+                failure.stack_trace = fixture
+                    .provider_data
+                    .solidity_stack_trace(&failure.solidity_trace);
+
+                failure.stack_trace.context("contract is known")
+            };
+
+        // returnNine() is not payable
+        let stack_trace = send_reverting_transaction(0, U256::from(1), "df78ca51")?;
+        assert_eq!(stack_trace.len(), 1);
+
+        let StackTraceEntry::FunctionNotPayableError {
+            source_reference: Some(source_reference),
+            value,
+        } = &stack_trace[0]
+        else {
+            panic!("unexpected stack trace entry: {:?}", stack_trace[0]);
+        };
+
+        assert_eq!(*value, U256::from(1));
+        assert_eq!(source_reference.source_name, "literal.sol");
+        assert_eq!(source_reference.contract.as_deref(), Some("Nine"));
+        assert_eq!(source_reference.function.as_deref(), Some("returnNine"));
+        assert_eq!(source_reference.line, 3);
 
-        let pending_transaction = fixture
-            .provider_data
-            .sign_transaction_request(transaction)?;
+        // Nine has no fallback function
+        let stack_trace = send_reverting_transaction(1, U256::ZERO, "deadbeef")?;
+        assert_eq!(stack_trace.len(), 1);
 
-        let result = fixture.provider_data.run_call(
-            pending_transaction,
-            None,
-            &StateOverrides::default(),
-        )?;
+        let StackTraceEntry::UnrecognizedFunctionWithoutFallbackError { source_reference } =
+            &stack_trace[0]
+        else {
+            panic!("unexpected stack trace entry: {:?}", stack_trace[0]);
+        };
 
-        let console_log_inputs = result.console_log_inputs;
-        assert_eq!(console_log_inputs.len(), 1);
-        assert_eq!(console_log_inputs[0], expected_call_data);
+        assert_eq!(source_reference.contract.as_deref(), Some("Nine"));
+        assert_eq!(source_reference.function, None);
+        assert_eq!(source_reference.line, 2);
 
         Ok(())
     }
@@ -3175,6 +6327,268 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn mine_and_commit_block_stores_parent_beacon_block_root() -> anyhow::Result<()> {
+        const HISTORY_BUFFER_LENGTH: u64 = 8191;
+
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let code = fixture
+            .provider_data
+            .get_code(BEACON_ROOTS_ADDRESS, Some(&BlockSpec::latest()))?;
+        assert!(!code.is_empty());
+
+        let parent_beacon_block_root = B256::repeat_byte(0xbe);
+        fixture
+            .provider_data
+            .set_next_parent_beacon_block_root(parent_beacon_block_root)?;
+
+        let mined_block = fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?
+            .block;
+
+        let header = mined_block.header();
+        assert_eq!(
+            header.parent_beacon_block_root,
+            Some(parent_beacon_block_root)
+        );
+
+        let timestamp_index = U256::from(header.timestamp % HISTORY_BUFFER_LENGTH);
+        let stored_timestamp = fixture.provider_data.get_storage_at(
+            BEACON_ROOTS_ADDRESS,
+            timestamp_index,
+            Some(&BlockSpec::latest()),
+        )?;
+        assert_eq!(stored_timestamp, U256::from(header.timestamp));
+
+        let stored_root = fixture.provider_data.get_storage_at(
+            BEACON_ROOTS_ADDRESS,
+            timestamp_index + U256::from(HISTORY_BUFFER_LENGTH),
+            Some(&BlockSpec::latest()),
+        )?;
+        assert_eq!(B256::from(stored_root), parent_beacon_block_root);
+
+        // Subsequent blocks have generated roots
+        let next_block = fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?
+            .block;
+
+        let next_root = next_block
+            .header()
+            .parent_beacon_block_root
+            .expect("Cancun blocks have a parent beacon block root");
+        assert_ne!(next_root, parent_beacon_block_root);
+        assert_ne!(next_root, B256::ZERO);
+
+        Ok(())
+    }
+
+    #[test]
+    fn debug_trace_transaction_reads_parent_beacon_block_root() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let parent_beacon_block_root = B256::repeat_byte(0xbe);
+        fixture
+            .provider_data
+            .set_next_parent_beacon_block_root(parent_beacon_block_root)?;
+
+        // Reads the root that the system call of the same block stores
+        let (block_timestamp, _) = fixture.provider_data.next_block_timestamp(None)?;
+        let request = TransactionRequest::Eip155(Eip155TransactionRequest {
+            kind: TransactionKind::Call(BEACON_ROOTS_ADDRESS),
+            gas_limit: 100_000,
+            gas_price: U256::from(42_000_000_000_u64),
+            value: U256::ZERO,
+            input: U256::from(block_timestamp).to_be_bytes::<32>().into(),
+            nonce: 0,
+            chain_id: fixture.config.chain_id,
+        });
+        let sender = fixture.nth_local_account(0)?;
+        let transaction = fixture
+            .provider_data
+            .sign_transaction_request(TransactionRequestAndSender { request, sender })?;
+        let transaction_hash = *transaction.hash();
+
+        fixture.provider_data.add_pending_transaction(transaction)?;
+        fixture.provider_data.mine_and_commit_block(BlockOptions {
+            timestamp: Some(block_timestamp),
+            ..BlockOptions::default()
+        })?;
+
+        let DebugTraceOutput::StructLogs(trace) = fixture
+            .provider_data
+            .debug_trace_transaction(&transaction_hash, DebugTraceConfig::default())?
+        else {
+            return Err(anyhow!("expected struct logs"));
+        };
+
+        assert!(trace.pass);
+        assert_eq!(
+            trace.output,
+            Some(Bytes::copy_from_slice(parent_beacon_block_root.as_slice()))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn mine_and_commit_block_includes_queued_withdrawals() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let recipient = Address::repeat_byte(0x11);
+        fixture
+            .provider_data
+            .add_withdrawals(vec![WithdrawalRequest {
+                validator_index: 7,
+                address: recipient,
+                amount: U256::from(2),
+            }])?;
+
+        let expected_withdrawals = vec![Withdrawal {
+            index: 0,
+            validator_index: 7,
+            address: recipient,
+            amount: U256::from(2),
+        }];
+
+        // Mining a pending block doesn't consume the queued withdrawals
+        let pending_block = fixture.provider_data.mine_pending_block()?.block;
+        assert_eq!(
+            pending_block.withdrawals(),
+            Some(expected_withdrawals.as_slice())
+        );
+
+        let snapshot_id = fixture.provider_data.make_snapshot();
+
+        let mined_block = fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?
+            .block;
+        assert_eq!(
+            mined_block.withdrawals(),
+            Some(expected_withdrawals.as_slice())
+        );
+
+        let balance = fixture
+            .provider_data
+            .balance(recipient, Some(&BlockSpec::latest()))?;
+        assert_eq!(balance, U256::from(2_000_000_000u64));
+
+        let next_block = fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?
+            .block;
+        assert_eq!(next_block.withdrawals(), Some([].as_slice()));
+
+        // Reverting restores the queue
+        assert!(fixture.provider_data.revert_to_snapshot(snapshot_id));
+
+        let mined_block = fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?
+            .block;
+        assert_eq!(
+            mined_block.withdrawals(),
+            Some(expected_withdrawals.as_slice())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_withdrawals_unmet_hardfork() -> anyhow::Result<()> {
+        let config = ProviderConfig {
+            hardfork: SpecId::MERGE,
+            initial_blob_gas: None,
+            initial_parent_beacon_block_root: None,
+            ..create_test_config()
+        };
+
+        let runtime = runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .thread_name("provider-data-test")
+            .build()?;
+
+        let mut fixture = ProviderTestFixture::new(runtime, config)?;
+
+        let result = fixture.provider_data.add_withdrawals(Vec::new());
+        assert!(matches!(
+            result,
+            Err(ProviderError::UnmetHardfork {
+                actual: SpecId::MERGE,
+                minimum: SpecId::SHANGHAI,
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn mine_and_commit_blocks_scheduled_withdrawals() -> anyhow::Result<()> {
+        const NUM_MINED_BLOCKS: u64 = 10;
+
+        let recipient = Address::repeat_byte(0x22);
+        let config = ProviderConfig {
+            withdrawal_schedule: Some(WithdrawalScheduleConfig {
+                interval: NonZeroU64::new(2).expect("non-zero"),
+                withdrawals: vec![WithdrawalRequest {
+                    validator_index: 3,
+                    address: recipient,
+                    amount: U256::from(1),
+                }],
+            }),
+            ..create_test_config()
+        };
+
+        let runtime = runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .thread_name("provider-data-test")
+            .build()?;
+
+        let mut fixture = ProviderTestFixture::new(runtime, config)?;
+
+        let mined_blocks = fixture
+            .provider_data
+            .mine_and_commit_blocks(NUM_MINED_BLOCKS, 1)?;
+        assert_eq!(mined_blocks.len(), NUM_MINED_BLOCKS as usize);
+
+        let mut next_index = 0;
+        for mined_block in &mined_blocks {
+            let header = mined_block.block.header();
+            let withdrawals = mined_block
+                .block
+                .withdrawals()
+                .expect("Shanghai blocks have withdrawals");
+
+            if header.number % 2 == 0 {
+                assert_eq!(
+                    withdrawals,
+                    [Withdrawal {
+                        index: next_index,
+                        validator_index: 3,
+                        address: recipient,
+                        amount: U256::from(1),
+                    }]
+                );
+
+                next_index += 1;
+            } else {
+                assert!(withdrawals.is_empty());
+            }
+        }
+
+        let balance = fixture
+            .provider_data
+            .balance(recipient, Some(&BlockSpec::latest()))?;
+        assert_eq!(balance, U256::from(next_index * 1_000_000_000));
+
+        Ok(())
+    }
+
     #[test]
     fn mine_and_commit_blocks_increases_block_number() -> anyhow::Result<()> {
         const NUM_MINED_BLOCKS: u64 = 10;
@@ -3405,6 +6819,266 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn reorg_remines_removed_transactions() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        // PUSH1 0 PUSH1 0 LOG0 STOP, which emits an empty log upon deployment
+        let request = TransactionRequest::Eip155(Eip155TransactionRequest {
+            kind: TransactionKind::Create,
+            gas_limit: 100_000,
+            gas_price: U256::from(42_000_000_000_u64),
+            value: U256::ZERO,
+            input: Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xa0, 0x00]),
+            nonce: 0,
+            chain_id: fixture.config.chain_id,
+        });
+        let sender = fixture.nth_local_account(0)?;
+        let transaction = fixture
+            .provider_data
+            .sign_transaction_request(TransactionRequestAndSender { request, sender })?;
+        let transaction_hash = *transaction.hash();
+
+        fixture.provider_data.add_pending_transaction(transaction)?;
+        let replaced_block = fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?
+            .block;
+
+        let filter_id = fixture.provider_data.add_log_filter::<false>(LogFilter {
+            from_block: 0,
+            to_block: None,
+            addresses: HashSet::new(),
+            normalized_topics: Vec::new(),
+        })?;
+
+        // Discard the existing log
+        fixture.provider_data.get_filter_changes(&filter_id);
+
+        let results = fixture.provider_data.reorg(1, None)?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(fixture.provider_data.last_block_number(), 1);
+
+        let new_block = &results[0].block;
+        assert_ne!(new_block.hash(), replaced_block.hash());
+        assert_eq!(new_block.transactions().len(), 1);
+        assert_eq!(*new_block.transactions()[0].hash(), transaction_hash);
+
+        let Some(FilteredEvents::Logs(logs)) = fixture.provider_data.get_filter_changes(&filter_id)
+        else {
+            return Err(anyhow!("Expected log filter changes"));
+        };
+
+        assert_eq!(logs.len(), 2);
+        assert!(logs[0].removed);
+        assert_eq!(logs[0].block_hash, Some(*replaced_block.hash()));
+        assert!(!logs[1].removed);
+        assert_eq!(logs[1].block_hash, Some(*new_block.hash()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn reorg_returns_dropped_transactions_to_mem_pool() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let transaction = fixture.signed_dummy_transaction(0, None)?;
+        let transaction_hash = *transaction.hash();
+
+        fixture.provider_data.add_pending_transaction(transaction)?;
+        fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?;
+
+        assert_eq!(fixture.provider_data.pending_transactions().count(), 0);
+
+        let results = fixture.provider_data.reorg(1, Some(vec![Vec::new()]))?;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].block.transactions().is_empty());
+
+        let pending_transactions = fixture
+            .provider_data
+            .pending_transactions()
+            .map(|transaction| *transaction.hash())
+            .collect::<Vec<_>>();
+
+        assert_eq!(pending_transactions, vec![transaction_hash]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reorg_failure_restores_blocks_and_filters() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        // PUSH1 0 PUSH1 0 LOG0 STOP, which emits an empty log upon deployment
+        let request = TransactionRequest::Eip155(Eip155TransactionRequest {
+            kind: TransactionKind::Create,
+            gas_limit: 100_000,
+            gas_price: U256::from(42_000_000_000_u64),
+            value: U256::ZERO,
+            input: Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xa0, 0x00]),
+            nonce: 0,
+            chain_id: fixture.config.chain_id,
+        });
+        let sender = fixture.nth_local_account(0)?;
+        let transaction = fixture
+            .provider_data
+            .sign_transaction_request(TransactionRequestAndSender { request, sender })?;
+
+        fixture.provider_data.add_pending_transaction(transaction)?;
+        let replaced_block = fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?
+            .block;
+
+        let filter_id = fixture.provider_data.add_log_filter::<false>(LogFilter {
+            from_block: 0,
+            to_block: None,
+            addresses: HashSet::new(),
+            normalized_topics: Vec::new(),
+        })?;
+
+        // Discard the existing log
+        fixture.provider_data.get_filter_changes(&filter_id);
+
+        // Exceeds the block gas limit, so the alternative branch can't be mined
+        let request =
+            fixture.dummy_transaction_request(1, fixture.config.block_gas_limit + 1, None)?;
+        let invalid_transaction = fixture.provider_data.sign_transaction_request(request)?;
+
+        let pending_transaction = fixture.signed_dummy_transaction(1, None)?;
+        let pending_transaction_hash = *pending_transaction.hash();
+        fixture
+            .provider_data
+            .add_pending_transaction(pending_transaction)?;
+
+        // Fails after mining as many blocks as were replaced
+        for new_blocks in [
+            vec![vec![invalid_transaction.clone()]],
+            vec![Vec::new(), vec![invalid_transaction.clone()]],
+        ] {
+            let result = fixture.provider_data.reorg(1, Some(new_blocks));
+            assert!(result.is_err());
+
+            assert_eq!(fixture.provider_data.last_block_number(), 1);
+            assert_eq!(
+                fixture.provider_data.last_block()?.hash(),
+                replaced_block.hash()
+            );
+
+            let pending_transactions = fixture
+                .provider_data
+                .pending_transactions()
+                .map(|transaction| *transaction.hash())
+                .collect::<Vec<_>>();
+            assert_eq!(pending_transactions, vec![pending_transaction_hash]);
+
+            let Some(FilteredEvents::Logs(logs)) =
+                fixture.provider_data.get_filter_changes(&filter_id)
+            else {
+                return Err(anyhow!("Expected log filter changes"));
+            };
+            assert!(logs.is_empty());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn reorg_failure_restores_multiple_blocks() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let mut replaced_blocks = Vec::new();
+        for nonce in 0..2 {
+            let transaction = fixture.signed_dummy_transaction(0, Some(nonce))?;
+            fixture.provider_data.add_pending_transaction(transaction)?;
+
+            let block = fixture
+                .provider_data
+                .mine_and_commit_block(BlockOptions::default())?
+                .block;
+            replaced_blocks.push(block);
+        }
+
+        // Exceeds the block gas limit, so the alternative branch can't be mined
+        let request =
+            fixture.dummy_transaction_request(1, fixture.config.block_gas_limit + 1, None)?;
+        let invalid_transaction = fixture.provider_data.sign_transaction_request(request)?;
+
+        let result = fixture
+            .provider_data
+            .reorg(2, Some(vec![Vec::new(), vec![invalid_transaction]]));
+        assert!(result.is_err());
+
+        assert_eq!(fixture.provider_data.last_block_number(), 2);
+        for block in replaced_blocks {
+            let restored_block = fixture
+                .provider_data
+                .block_by_hash(block.hash())?
+                .context("Replaced block must be restored")?;
+            assert_eq!(restored_block.header().number, block.header().number);
+        }
+
+        assert_eq!(fixture.provider_data.pending_transactions().count(), 0);
+
+        // The restored blocks can still be replaced
+        let results = fixture.provider_data.reorg(2, None)?;
+        assert_eq!(results.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reorg_invalid_depth() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        fixture
+            .provider_data
+            .mine_and_commit_block(BlockOptions::default())?;
+
+        for depth in [0, 2] {
+            let result = fixture.provider_data.reorg(depth, None);
+            assert!(matches!(
+                result,
+                Err(ProviderError::InvalidReorgDepth { max_depth: 1, .. })
+            ));
+        }
+
+        assert_eq!(fixture.provider_data.last_block_number(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reorg_cannot_replace_finalized_blocks() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        for _ in 0..3 {
+            fixture
+                .provider_data
+                .mine_and_commit_block(BlockOptions::default())?;
+        }
+
+        fixture.provider_data.set_finalized_block(1)?;
+
+        let result = fixture.provider_data.reorg(3, None);
+        assert!(matches!(
+            result,
+            Err(ProviderError::InvalidReorgDepth {
+                depth: 3,
+                max_depth: 2
+            })
+        ));
+        assert_eq!(fixture.provider_data.last_block_number(), 3);
+
+        let results = fixture.provider_data.reorg(2, None)?;
+        assert_eq!(results.len(), 2);
+        assert_eq!(fixture.provider_data.finalized_block_number(), 1);
+
+        Ok(())
+    }
+
     #[test]
     fn reset_local_to_forking() -> anyhow::Result<()> {
         let mut fixture = ProviderTestFixture::new_local()?;
@@ -3537,7 +7211,12 @@ mod tests {
             let transaction =
                 resolve_call_request(data, request, Some(&block_spec), &state_overrides)?;
 
-            data.run_call(transaction, Some(&block_spec), &state_overrides)
+            data.run_call(
+                transaction,
+                Some(&block_spec),
+                &state_overrides,
+                &BlockOverrides::default(),
+            )
         }
 
         const EIP_1559_ACTIVATION_BLOCK: u64 = 12_965_000;
@@ -3741,11 +7420,12 @@ mod tests {
         let hardfork_activations =
             chain_hardfork_activations(chain_id).ok_or(anyhow!("Unsupported chain id"))?;
 
+        let replay_header = replay_block.header();
+
         let hardfork = hardfork_activations
-            .hardfork_at_block_number(block_number)
+            .hardfork_at_block(block_number, replay_header.timestamp)
             .ok_or(anyhow!("Unsupported block number"))?;
 
-        let replay_header = replay_block.header();
         let block_gas_limit = replay_header.gas_limit;
 
         let config = ProviderConfig {
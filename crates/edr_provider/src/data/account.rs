@@ -1,5 +1,5 @@
 use edr_eth::{signature::public_key_to_address, Address};
-use edr_evm::{Account, AccountInfo, AccountStatus, HashMap, KECCAK_EMPTY};
+use edr_evm::{Account, AccountInfo, AccountStatus, HashMap, StorageSlot, KECCAK_EMPTY};
 use indexmap::IndexMap;
 
 use crate::{AccountConfig, ProviderConfig};
@@ -35,9 +35,20 @@ pub(super) fn create_accounts(config: &ProviderConfig) -> InitialAccounts {
         )
         .chain(config.genesis_accounts.clone())
         .map(|(address, account_info)| {
+            let storage = config
+                .genesis_storage
+                .get(&address)
+                .map(|storage| {
+                    storage
+                        .iter()
+                        .map(|(index, value)| (*index, StorageSlot::new(*value)))
+                        .collect()
+                })
+                .unwrap_or_default();
+
             let account = Account {
                 info: account_info,
-                storage: HashMap::new(),
+                storage,
                 status: AccountStatus::Created | AccountStatus::Touched,
             };
 
@@ -2,13 +2,14 @@ use core::fmt::Debug;
 
 use edr_eth::{
     block::{BlobGas, Header},
+    remote::BlockOverrides,
     SpecId, U256,
 };
 use edr_evm::{
     blockchain::{BlockchainError, SyncBlockchain},
     guaranteed_dry_run,
     state::{StateError, StateOverrides, SyncState},
-    BlobExcessGasAndPrice, BlockEnv, CfgEnv, ExecutionResult, SyncInspector, TxEnv,
+    BlobExcessGasAndPrice, BlockEnv, CfgEnv, ExecutionResult, ResultAndState, SyncInspector, TxEnv,
 };
 
 use crate::ProviderError;
@@ -18,6 +19,7 @@ pub(super) struct RunCallArgs<'a> {
     pub header: &'a Header,
     pub state: &'a dyn SyncState<StateError>,
     pub state_overrides: &'a StateOverrides,
+    pub block_overrides: &'a BlockOverrides,
     pub cfg_env: CfgEnv,
     pub tx_env: TxEnv,
     pub inspector: Option<&'a mut dyn SyncInspector<BlockchainError, StateError>>,
@@ -27,33 +29,26 @@ pub(super) struct RunCallArgs<'a> {
 pub(super) fn run_call<LoggerErrorT: Debug>(
     args: RunCallArgs<'_>,
 ) -> Result<ExecutionResult, ProviderError<LoggerErrorT>> {
+    run_call_with_state_changes(args).map(|result| result.result)
+}
+
+/// Execute a transaction as a call. Returns the result and the state changes
+/// that weren't committed.
+pub(super) fn run_call_with_state_changes<LoggerErrorT: Debug>(
+    args: RunCallArgs<'_>,
+) -> Result<ResultAndState, ProviderError<LoggerErrorT>> {
     let RunCallArgs {
         blockchain,
         header,
         state,
         state_overrides,
+        block_overrides,
         cfg_env,
         tx_env,
         inspector,
     } = args;
 
-    let block = BlockEnv {
-        number: U256::from(header.number),
-        coinbase: header.beneficiary,
-        timestamp: U256::from(header.timestamp),
-        gas_limit: U256::from(header.gas_limit),
-        basefee: U256::ZERO,
-        difficulty: header.difficulty,
-        prevrandao: if cfg_env.spec_id >= SpecId::MERGE {
-            Some(header.mix_hash)
-        } else {
-            None
-        },
-        blob_excess_gas_and_price: header
-            .blob_gas
-            .as_ref()
-            .map(|BlobGas { excess_gas, .. }| BlobExcessGasAndPrice::new(*excess_gas)),
-    };
+    let block = block_env_for_call(header, cfg_env.spec_id, block_overrides);
 
     guaranteed_dry_run(
         blockchain,
@@ -64,8 +59,47 @@ pub(super) fn run_call<LoggerErrorT: Debug>(
         block,
         inspector,
     )
-    .map_or_else(
-        |error| Err(ProviderError::RunTransaction(error)),
-        |result| Ok(result.result),
-    )
+    .map_err(ProviderError::RunTransaction)
+}
+
+/// Constructs the block environment for running a call in the context of the
+/// provided block, applying the overrides. Calls don't pay a base fee unless
+/// it's overridden.
+pub(super) fn block_env_for_call(
+    header: &Header,
+    spec_id: SpecId,
+    overrides: &BlockOverrides,
+) -> BlockEnv {
+    BlockEnv {
+        number: U256::from(overrides.number.unwrap_or(header.number)),
+        coinbase: overrides.fee_recipient.unwrap_or(header.beneficiary),
+        timestamp: U256::from(overrides.time.unwrap_or(header.timestamp)),
+        gas_limit: U256::from(overrides.gas_limit.unwrap_or(header.gas_limit)),
+        basefee: overrides.base_fee_per_gas.unwrap_or(U256::ZERO),
+        difficulty: overrides.difficulty.unwrap_or(header.difficulty),
+        prevrandao: if spec_id >= SpecId::MERGE {
+            Some(overrides.prev_randao.unwrap_or(header.mix_hash))
+        } else {
+            None
+        },
+        blob_excess_gas_and_price: blob_excess_gas_and_price(header, overrides.blob_base_fee),
+    }
+}
+
+fn blob_excess_gas_and_price(
+    header: &Header,
+    blob_base_fee: Option<u64>,
+) -> Option<BlobExcessGasAndPrice> {
+    let excess_gas_and_price = header
+        .blob_gas
+        .as_ref()
+        .map(|BlobGas { excess_gas, .. }| BlobExcessGasAndPrice::new(*excess_gas));
+
+    match blob_base_fee {
+        Some(blob_gasprice) => Some(BlobExcessGasAndPrice {
+            blob_gasprice,
+            ..excess_gas_and_price.unwrap_or_else(|| BlobExcessGasAndPrice::new(0))
+        }),
+        None => excess_gas_and_price,
+    }
 }
@@ -1,17 +1,20 @@
 use core::fmt::Debug;
 use std::cmp;
 
-use edr_eth::{block::Header, reward_percentile::RewardPercentile, U256};
+use edr_eth::{block::Header, remote::BlockOverrides, reward_percentile::RewardPercentile, U256};
 use edr_evm::{
     blockchain::{BlockchainError, SyncBlockchain},
     state::{StateError, StateOverrides, SyncState},
     trace::{Trace, TraceCollector},
-    CfgEnv, ExecutionResult, SyncBlock, TxEnv,
+    CfgEnv, DualInspector, ExecutionResult, SyncBlock, TxEnv,
 };
 use itertools::Itertools;
 
 use crate::{
-    data::call::{self, RunCallArgs},
+    data::{
+        call::{self, RunCallArgs},
+        inspector::EvmInspector,
+    },
     ProviderError,
 };
 
@@ -20,9 +23,11 @@ pub(super) struct CheckGasLimitArgs<'a> {
     pub header: &'a Header,
     pub state: &'a dyn SyncState<StateError>,
     pub state_overrides: &'a StateOverrides,
+    pub block_overrides: &'a BlockOverrides,
     pub cfg_env: CfgEnv,
     pub tx_env: TxEnv,
     pub gas_limit: u64,
+    pub enable_cheatcodes: bool,
 }
 
 pub(super) struct CheckGasResult {
@@ -41,25 +46,33 @@ pub(super) fn check_gas_limit<LoggerErrorT: Debug>(
         header,
         state,
         state_overrides,
+        block_overrides,
         cfg_env,
         mut tx_env,
         gas_limit,
+        enable_cheatcodes,
     } = args;
 
     tx_env.gas_limit = gas_limit;
 
-    let mut tracer = TraceCollector::default();
+    let mut inspector = DualInspector::new(
+        TraceCollector::default(),
+        EvmInspector::new(enable_cheatcodes),
+    );
 
     let result = call::run_call(RunCallArgs {
         blockchain,
         header,
         state,
         state_overrides,
+        block_overrides,
         cfg_env,
         tx_env,
-        inspector: Some(&mut tracer),
+        inspector: Some(&mut inspector),
     })?;
 
+    let (tracer, _evm_inspector) = inspector.into_parts();
+
     let success = matches!(result, ExecutionResult::Success { .. });
     Ok(CheckGasResult {
         success,
@@ -72,10 +85,12 @@ pub(super) struct BinarySearchEstimationArgs<'a> {
     pub header: &'a Header,
     pub state: &'a dyn SyncState<StateError>,
     pub state_overrides: &'a StateOverrides,
+    pub block_overrides: &'a BlockOverrides,
     pub cfg_env: CfgEnv,
     pub tx_env: TxEnv,
     pub lower_bound: u64,
     pub upper_bound: u64,
+    pub enable_cheatcodes: bool,
 }
 
 pub(super) struct BinarySearchEstimationResult {
@@ -96,10 +111,12 @@ pub(super) fn binary_search_estimation<LoggerErrorT: Debug>(
         header,
         state,
         state_overrides,
+        block_overrides,
         cfg_env,
         tx_env,
         mut lower_bound,
         mut upper_bound,
+        enable_cheatcodes,
     } = args;
 
     let mut i = 0;
@@ -119,9 +136,11 @@ pub(super) fn binary_search_estimation<LoggerErrorT: Debug>(
             header,
             state,
             state_overrides,
+            block_overrides,
             cfg_env: cfg_env.clone(),
             tx_env: tx_env.clone(),
             gas_limit: mid,
+            enable_cheatcodes,
         })?;
         traces.push(trace);
 
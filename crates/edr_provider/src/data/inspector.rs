@@ -1,36 +1,127 @@
 use core::fmt::Debug;
 
-use edr_eth::Bytes;
-use edr_evm::{CallInputs, EVMData, Gas, Inspector, InstructionResult};
+use edr_eth::{Address, Bytes, B256};
+use edr_evm::{
+    CallInputs, CheatcodeInspector, CreateInputs, EVMData, Gas, Inspector, InstructionResult,
+    Interpreter,
+};
 
 use crate::data::CONSOLE_ADDRESS;
 
-#[derive(Debug, Default)]
-pub(super) struct EvmInspector {
+/// The inspector used by the provider to execute transactions. It collects
+/// `console.log` messages and optionally handles cheatcodes.
+#[derive(Debug)]
+pub struct EvmInspector {
+    cheatcodes: Option<CheatcodeInspector>,
     console_log_encoded_messages: Vec<Bytes>,
 }
 
 impl EvmInspector {
+    /// Constructs an inspector that optionally handles cheatcodes.
+    pub fn new(enable_cheatcodes: bool) -> Self {
+        Self {
+            cheatcodes: enable_cheatcodes.then(CheatcodeInspector::default),
+            console_log_encoded_messages: Vec::new(),
+        }
+    }
+
+    /// Returns the ABI-encoded `console.log` messages that were collected.
     pub fn into_console_log_encoded_messages(self) -> Vec<Bytes> {
         self.console_log_encoded_messages
     }
 }
 
 impl<DatabaseErrorT> Inspector<DatabaseErrorT> for EvmInspector {
+    fn step(
+        &mut self,
+        interp: &mut Interpreter,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+    ) -> InstructionResult {
+        if let Some(cheatcodes) = &mut self.cheatcodes {
+            cheatcodes.step(interp, data)
+        } else {
+            InstructionResult::Continue
+        }
+    }
+
+    fn log(
+        &mut self,
+        evm_data: &mut EVMData<'_, DatabaseErrorT>,
+        address: &Address,
+        topics: &[B256],
+        data: &Bytes,
+    ) {
+        if let Some(cheatcodes) = &mut self.cheatcodes {
+            cheatcodes.log(evm_data, address, topics, data);
+        }
+    }
+
     fn call(
         &mut self,
-        _data: &mut EVMData<'_, DatabaseErrorT>,
+        data: &mut EVMData<'_, DatabaseErrorT>,
         inputs: &mut CallInputs,
     ) -> (InstructionResult, Gas, Bytes) {
         if inputs.contract == *CONSOLE_ADDRESS {
             self.console_log_encoded_messages.push(inputs.input.clone());
         }
 
-        (
-            InstructionResult::Continue,
-            Gas::new(inputs.gas_limit),
-            Bytes::new(),
-        )
+        if let Some(cheatcodes) = &mut self.cheatcodes {
+            cheatcodes.call(data, inputs)
+        } else {
+            (
+                InstructionResult::Continue,
+                Gas::new(inputs.gas_limit),
+                Bytes::new(),
+            )
+        }
+    }
+
+    fn call_end(
+        &mut self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+    ) -> (InstructionResult, Gas, Bytes) {
+        if let Some(cheatcodes) = &mut self.cheatcodes {
+            cheatcodes.call_end(data, inputs, remaining_gas, ret, out)
+        } else {
+            (ret, remaining_gas, out)
+        }
+    }
+
+    fn create(
+        &mut self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        if let Some(cheatcodes) = &mut self.cheatcodes {
+            cheatcodes.create(data, inputs)
+        } else {
+            (
+                InstructionResult::Continue,
+                None,
+                Gas::new(inputs.gas_limit),
+                Bytes::new(),
+            )
+        }
+    }
+
+    fn create_end(
+        &mut self,
+        data: &mut EVMData<'_, DatabaseErrorT>,
+        inputs: &CreateInputs,
+        ret: InstructionResult,
+        address: Option<Address>,
+        remaining_gas: Gas,
+        out: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        if let Some(cheatcodes) = &mut self.cheatcodes {
+            cheatcodes.create_end(data, inputs, ret, address, remaining_gas, out)
+        } else {
+            (ret, address, remaining_gas, out)
+        }
     }
 }
 
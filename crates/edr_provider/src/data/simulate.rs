@@ -0,0 +1,218 @@
+use core::fmt::Debug;
+
+use edr_eth::{block::Header, remote::BlockOverrides, B256, U64};
+use edr_evm::{
+    blockchain::{BlockchainError, SyncBlockchain},
+    guaranteed_dry_run,
+    state::{StateError, StateOverrides, SyncState},
+    trace::Trace,
+    CfgEnv, ExecutableTransaction, ExecutionResult, ResultAndState, SyncInspector,
+    TransferLogTracer, TxEnv,
+};
+
+use super::call::block_env_for_call;
+use crate::{
+    requests::eth::{SimulatedBlockResult, SimulatedCallError, SimulatedCallResult, SimulatedLog},
+    ProviderError, TransactionFailure,
+};
+
+/// The number of seconds between simulated blocks that don't override their
+/// timestamp, matching geth.
+const DEFAULT_TIMESTAMP_INCREMENT: u64 = 12;
+
+/// The JSON-RPC error code of a reverted call in `eth_simulateV1`.
+const REVERTED_ERROR_CODE: i64 = 3;
+/// The JSON-RPC error code of a halted call in `eth_simulateV1`.
+const VM_ERROR_CODE: i64 = -32015;
+
+/// A block of calls to simulate, as resolved from an `eth_simulateV1` request.
+#[derive(Clone, Debug)]
+pub struct SimulatedBlock {
+    pub block_overrides: BlockOverrides,
+    pub state_overrides: StateOverrides,
+    pub transactions: Vec<ExecutableTransaction>,
+}
+
+pub(super) struct RunSimulationArgs<'a> {
+    pub blockchain: &'a dyn SyncBlockchain<BlockchainError, StateError>,
+    pub header: &'a Header,
+    pub state: Box<dyn SyncState<StateError>>,
+    pub cfg_env: CfgEnv,
+    pub blocks: Vec<SimulatedBlock>,
+    pub trace_transfers: bool,
+}
+
+/// Simulates the blocks on top of the provided block. The calls are executed
+/// sequentially, so each call observes the state changes of the previous
+/// calls and blocks.
+pub(super) fn run_simulation<LoggerErrorT: Debug>(
+    args: RunSimulationArgs<'_>,
+) -> Result<Vec<SimulatedBlockResult>, ProviderError<LoggerErrorT>> {
+    let RunSimulationArgs {
+        blockchain,
+        header,
+        mut state,
+        cfg_env,
+        blocks,
+        trace_transfers,
+    } = args;
+
+    let mut parent_number = header.number;
+    let mut parent_timestamp = header.timestamp;
+
+    let mut results = Vec::with_capacity(blocks.len());
+    for SimulatedBlock {
+        block_overrides,
+        state_overrides,
+        transactions,
+    } in blocks
+    {
+        let number = block_overrides.number.unwrap_or(parent_number + 1);
+        if number <= parent_number {
+            return Err(ProviderError::InvalidInput(format!(
+                "The simulated block number {number} must be greater than the previous block number {parent_number}"
+            )));
+        }
+
+        let timestamp = block_overrides
+            .time
+            .unwrap_or(parent_timestamp + DEFAULT_TIMESTAMP_INCREMENT);
+        if timestamp <= parent_timestamp {
+            return Err(ProviderError::InvalidInput(format!(
+                "The simulated block timestamp {timestamp} must be greater than the previous block timestamp {parent_timestamp}"
+            )));
+        }
+
+        parent_number = number;
+        parent_timestamp = timestamp;
+
+        let state_ref = state.as_ref();
+        let changes = state_overrides.state_changes(&state_ref)?;
+        state.commit(changes);
+
+        let block_env = block_env_for_call(
+            header,
+            cfg_env.spec_id,
+            &BlockOverrides {
+                number: Some(number),
+                time: Some(timestamp),
+                ..block_overrides
+            },
+        );
+
+        let mut block_gas_used = 0;
+        let mut log_index = 0;
+        let mut calls = Vec::with_capacity(transactions.len());
+        for (transaction_index, transaction) in transactions.into_iter().enumerate() {
+            let transaction_hash = *transaction.hash();
+
+            let mut tx_env: TxEnv = transaction.into();
+            // The nonces of the calls were resolved before the simulation, so they don't
+            // account for earlier calls from the same sender.
+            tx_env.nonce = None;
+
+            let mut tracer = TransferLogTracer::default();
+            let inspector: Option<&mut dyn SyncInspector<BlockchainError, StateError>> =
+                if trace_transfers {
+                    Some(&mut tracer)
+                } else {
+                    None
+                };
+
+            let ResultAndState {
+                result,
+                state: changes,
+            } = guaranteed_dry_run(
+                blockchain,
+                state.as_ref(),
+                &StateOverrides::default(),
+                cfg_env.clone(),
+                tx_env,
+                block_env.clone(),
+                inspector,
+            )
+            .map_err(ProviderError::RunTransaction)?;
+
+            state.commit(changes);
+
+            let logs = if trace_transfers {
+                tracer.into_logs()
+            } else if let ExecutionResult::Success { logs, .. } = &result {
+                logs.clone()
+            } else {
+                Vec::new()
+            };
+
+            let logs = logs
+                .into_iter()
+                .map(|log| {
+                    let log = SimulatedLog {
+                        address: log.address,
+                        topics: log.topics,
+                        data: log.data,
+                        block_number: U64::from(number),
+                        transaction_hash,
+                        transaction_index: U64::from(transaction_index),
+                        log_index: U64::from(log_index),
+                    };
+
+                    log_index += 1;
+                    log
+                })
+                .collect();
+
+            block_gas_used += result.gas_used();
+            calls.push(call_result(result, transaction_hash, logs));
+        }
+
+        results.push(SimulatedBlockResult {
+            number: U64::from(number),
+            timestamp: U64::from(timestamp),
+            gas_limit: U64::from(block_env.gas_limit.to::<u64>()),
+            gas_used: U64::from(block_gas_used),
+            fee_recipient: block_env.coinbase,
+            base_fee_per_gas: block_env.basefee,
+            prev_randao: block_env.prevrandao,
+            calls,
+        });
+    }
+
+    Ok(results)
+}
+
+fn call_result(
+    result: ExecutionResult,
+    transaction_hash: B256,
+    logs: Vec<SimulatedLog>,
+) -> SimulatedCallResult {
+    let error =
+        TransactionFailure::from_execution_result(&result, &transaction_hash, &Trace::default())
+            .map(|failure| {
+                let (code, data) = match &result {
+                    ExecutionResult::Revert { output, .. } => {
+                        (REVERTED_ERROR_CODE, Some(output.clone()))
+                    }
+                    _ => (VM_ERROR_CODE, None),
+                };
+
+                SimulatedCallError {
+                    code,
+                    message: failure.to_string(),
+                    data,
+                }
+            });
+
+    let status = if result.is_success() {
+        U64::from(1)
+    } else {
+        U64::ZERO
+    };
+
+    SimulatedCallResult {
+        gas_used: U64::from(result.gas_used()),
+        return_data: result.into_output().unwrap_or_default(),
+        logs,
+        status,
+        error,
+    }
+}
@@ -7,6 +7,7 @@ use edr_evm::{
     trace::Trace,
     ExecutionResult, LocalBlock, MineBlockResultAndState, SyncBlock,
 };
+use edr_solidity::console_log::decode_console_log_inputs;
 
 /// The result of mining a block, including the state, in debug mode. This
 /// result needs to be inserted into the blockchain to be persistent.
@@ -23,22 +24,27 @@ pub struct DebugMineBlockResultAndState<StateErrorT> {
     pub transaction_traces: Vec<Trace>,
     /// Encoded `console.log` call inputs
     pub console_log_inputs: Vec<Bytes>,
+    /// Decoded `console.log` messages
+    pub console_log_messages: Vec<String>,
 }
 
 impl<StateErrorT> DebugMineBlockResultAndState<StateErrorT> {
-    /// Constructs a new instance from a [`MineBlockResultAndState`] and decoded
-    /// console log messages.
+    /// Constructs a new instance from a [`MineBlockResultAndState`] and encoded
+    /// console log inputs.
     pub fn new(
         result: MineBlockResultAndState<StateErrorT>,
-        console_log_decoded_messages: Vec<Bytes>,
+        console_log_inputs: Vec<Bytes>,
     ) -> Self {
+        let console_log_messages = decode_console_log_inputs(&console_log_inputs);
+
         Self {
             block: result.block,
             state: result.state,
             state_diff: result.state_diff,
             transaction_results: result.transaction_results,
             transaction_traces: result.transaction_traces,
-            console_log_inputs: console_log_decoded_messages,
+            console_log_inputs,
+            console_log_messages,
         }
     }
 }
@@ -55,6 +61,8 @@ pub struct DebugMineBlockResult<BlockchainErrorT> {
     pub transaction_traces: Vec<Trace>,
     /// Encoded `console.log` call inputs
     pub console_log_inputs: Vec<Bytes>,
+    /// Decoded `console.log` messages
+    pub console_log_messages: Vec<String>,
 }
 
 impl<BlockchainErrorT> Clone for DebugMineBlockResult<BlockchainErrorT> {
@@ -64,6 +72,7 @@ impl<BlockchainErrorT> Clone for DebugMineBlockResult<BlockchainErrorT> {
             transaction_results: self.transaction_results.clone(),
             transaction_traces: self.transaction_traces.clone(),
             console_log_inputs: self.console_log_inputs.clone(),
+            console_log_messages: self.console_log_messages.clone(),
         }
     }
 }
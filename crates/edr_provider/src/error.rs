@@ -4,19 +4,21 @@ use std::{num::TryFromIntError, time::SystemTimeError};
 use alloy_sol_types::{ContractError, SolInterface};
 use edr_eth::{
     remote::{filter::SubscriptionType, jsonrpc, BlockSpec, BlockTag, RpcClientError},
+    transaction::BlobVerificationError,
     Address, Bytes, SpecId, B256, U256,
 };
 use edr_evm::{
-    blockchain::BlockchainError,
+    blockchain::{BlockchainError, GenesisExportError},
     hex,
     state::{AccountOverrideConversionError, StateError},
     trace::Trace,
     DebugTraceError, ExecutionResult, Halt, MemPoolAddTransactionError, MineBlockError,
     OutOfGasError, TransactionCreationError, TransactionError,
 };
+use edr_solidity::solidity_stack_trace::StackTraceEntry;
 use ethers_core::types::transaction::eip712::Eip712Error;
 
-use crate::data::CreationError;
+use crate::{data::CreationError, state_dump::StateDumpError};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ProviderError<LoggerErrorT> {
@@ -44,6 +46,9 @@ pub enum ProviderError<LoggerErrorT> {
     /// Blockchain error
     #[error(transparent)]
     Blockchain(#[from] BlockchainError),
+    /// Code coverage was requested, but the provider doesn't collect it.
+    #[error("Code coverage is not enabled. Enable it in the provider config to use hardhat_getCoverage.")]
+    CoverageDisabled,
     #[error(transparent)]
     Creation(#[from] CreationError),
     #[error(transparent)]
@@ -53,8 +58,17 @@ pub enum ProviderError<LoggerErrorT> {
     /// A transaction error occurred while estimating gas.
     #[error(transparent)]
     EstimateGasTransactionFailure(#[from] EstimateGasFailure),
+    /// An error occurred while exporting a genesis file.
+    #[error(transparent)]
+    GenesisExport(#[from] GenesisExportError),
+    /// A genesis file can't be exported from a forked blockchain.
+    #[error("Exporting a genesis file is not supported in fork mode")]
+    GenesisExportForked,
     #[error("{0}")]
     InvalidArgument(String),
+    /// The blob sidecar of an EIP-4844 transaction is invalid.
+    #[error("Invalid blob sidecar: {0}")]
+    InvalidBlobSidecar(#[from] BlobVerificationError),
     /// Block number or hash doesn't exist in blockchain
     #[error(
         "Received invalid block tag {block_spec}. Latest block number is {latest_block_number}"
@@ -85,6 +99,10 @@ pub enum ProviderError<LoggerErrorT> {
     },
     #[error("{0}")]
     InvalidInput(String),
+    /// The number of blocks to replace in a reorg is zero or exceeds the
+    /// number of replaceable blocks.
+    #[error("Cannot replace {depth} blocks. The reorg depth must be between 1 and {max_depth}.")]
+    InvalidReorgDepth { depth: u64, max_depth: u64 },
     /// Transaction hash doesn't exist on the blockchain.
     #[error("Transaction hash '{0}' doesn't exist on the blockchain.")]
     InvalidTransactionHash(B256),
@@ -143,6 +161,9 @@ pub enum ProviderError<LoggerErrorT> {
     /// State error
     #[error(transparent)]
     State(#[from] StateError),
+    /// An error occurred while writing or loading a state dump.
+    #[error(transparent)]
+    StateDump(#[from] StateDumpError),
     /// System time error
     #[error(transparent)]
     SystemTime(#[from] SystemTimeError),
@@ -200,11 +221,15 @@ impl<LoggerErrorT: Debug> From<ProviderError<LoggerErrorT>> for jsonrpc::Error {
             ProviderError::AutoMineNonceTooLow { .. } => INVALID_INPUT,
             ProviderError::AutoMinePriorityFeeTooLow { .. } => INVALID_INPUT,
             ProviderError::Blockchain(_) => INVALID_INPUT,
+            ProviderError::CoverageDisabled => INVALID_INPUT,
             ProviderError::Creation(_) => INVALID_INPUT,
             ProviderError::DebugTrace(_) => INTERNAL_ERROR,
             ProviderError::Eip712Error(_) => INVALID_INPUT,
             ProviderError::EstimateGasTransactionFailure(_) => INVALID_INPUT,
+            ProviderError::GenesisExport(_) => INTERNAL_ERROR,
+            ProviderError::GenesisExportForked => INVALID_INPUT,
             ProviderError::InvalidArgument(_) => INVALID_PARAMS,
+            ProviderError::InvalidBlobSidecar(_) => INVALID_PARAMS,
             ProviderError::InvalidBlockNumberOrHash { .. } => INVALID_INPUT,
             ProviderError::InvalidBlockTag { .. } => INVALID_PARAMS,
             ProviderError::InvalidChainId { .. } => INVALID_PARAMS,
@@ -212,6 +237,7 @@ impl<LoggerErrorT: Debug> From<ProviderError<LoggerErrorT>> for jsonrpc::Error {
             ProviderError::InvalidEip155TransactionChainId => INVALID_PARAMS,
             ProviderError::InvalidFilterSubscriptionType { .. } => INVALID_PARAMS,
             ProviderError::InvalidInput(_) => INVALID_INPUT,
+            ProviderError::InvalidReorgDepth { .. } => INVALID_PARAMS,
             ProviderError::InvalidTransactionHash { .. } => INVALID_PARAMS,
             ProviderError::InvalidTransactionIndex(_) => INVALID_PARAMS,
             ProviderError::InvalidTransactionInput(_) => INVALID_INPUT,
@@ -231,6 +257,7 @@ impl<LoggerErrorT: Debug> From<ProviderError<LoggerErrorT>> for jsonrpc::Error {
             ProviderError::SetNextPrevRandaoUnsupported { .. } => INVALID_INPUT,
             ProviderError::Signature(_) => INVALID_INPUT,
             ProviderError::State(_) => INVALID_INPUT,
+            ProviderError::StateDump(_) => INVALID_INPUT,
             ProviderError::SystemTime(_) => INVALID_INPUT,
             ProviderError::TimestampLowerThanPrevious { .. } => INVALID_INPUT,
             ProviderError::TimestampEqualsPrevious { .. } => INVALID_INPUT,
@@ -281,6 +308,7 @@ impl<LoggerErrorT: Debug> From<ProviderError<LoggerErrorT>> for jsonrpc::Error {
 #[derive(Debug, thiserror::Error)]
 pub struct EstimateGasFailure {
     pub console_log_inputs: Vec<Bytes>,
+    pub console_log_messages: Vec<String>,
     pub transaction_failure: TransactionFailureWithTraces,
 }
 
@@ -311,6 +339,9 @@ pub struct TransactionFailure {
     pub data: String,
     #[serde(skip)]
     pub solidity_trace: Trace,
+    /// The Solidity stack trace, if the failing contracts are known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack_trace: Option<Vec<StackTraceEntry>>,
     pub transaction_hash: B256,
 }
 
@@ -341,6 +372,7 @@ impl TransactionFailure {
             reason: TransactionFailureReason::Revert(output),
             data,
             solidity_trace,
+            stack_trace: None,
             transaction_hash,
         }
     }
@@ -358,6 +390,7 @@ impl TransactionFailure {
             reason,
             data: "0x".to_string(),
             solidity_trace,
+            stack_trace: None,
             transaction_hash: tx_hash,
         }
     }
@@ -425,7 +458,8 @@ fn revert_error(output: &Bytes) -> String {
     }
 }
 
-fn panic_code_to_error_reason(error_code: u64) -> &'static str {
+/// Returns the reason of a Solidity panic with the provided error code.
+pub fn panic_code_to_error_reason(error_code: u64) -> &'static str {
     match error_code {
         0x1 => "Assertion error",
         0x11 => "Arithmetic operation underflowed or overflowed outside of an unchecked block",
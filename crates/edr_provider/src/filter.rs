@@ -12,6 +12,7 @@ use edr_eth::{
 
 pub use self::criteria::*;
 
+#[derive(Clone)]
 pub struct Filter {
     pub data: FilterData,
     pub deadline: Instant,
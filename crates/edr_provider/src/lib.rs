@@ -8,9 +8,10 @@ mod logger;
 mod pending;
 mod requests;
 mod snapshot;
+mod state_dump;
 mod subscribe;
 /// Test utilities
-#[cfg(test)]
+#[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
 
 use core::fmt::Debug;
@@ -25,22 +26,31 @@ use tokio::{runtime, sync::Mutex as AsyncMutex, task};
 
 pub use self::{
     config::*,
-    data::CallResult,
+    data::{
+        block_env_from_header, create_initial_blockchain_and_state, CallResult, CreationError,
+        EvmInspector, InitialBlockchainAndState,
+    },
     debug_mine::DebugMineBlockResult,
-    error::{EstimateGasFailure, ProviderError, TransactionFailure, TransactionFailureReason},
-    logger::Logger,
+    error::{
+        panic_code_to_error_reason, EstimateGasFailure, ProviderError, TransactionFailure,
+        TransactionFailureReason,
+    },
+    logger::{JsonLogger, Logger},
     requests::{
-        hardhat::rpc_types as hardhat_rpc_types, InvalidRequestReason, MethodInvocation,
-        OneUsizeOrTwo, ProviderRequest, U64OrUsize,
+        eth::{BlockStateCalls, SimulatePayload},
+        hardhat::rpc_types as hardhat_rpc_types,
+        trace::{TraceFilterRequest, TraceType},
+        InvalidRequestReason, MethodInvocation, OneUsizeOrTwo, ProviderRequest, U64OrUsize,
     },
+    state_dump::{StateDump, StateDumpError},
     subscribe::*,
 };
 use self::{
-    data::{CreationError, ProviderData},
+    data::ProviderData,
     interval::IntervalMiner,
     requests::{eth, hardhat},
 };
-use crate::requests::debug;
+use crate::requests::{debug, trace};
 
 lazy_static! {
     pub static ref PRIVATE_RPC_METHODS: HashSet<&'static str> = {
@@ -187,19 +197,39 @@ impl<LoggerErrorT: Debug + Send + Sync + 'static> Provider<LoggerErrorT> {
         let result = match request {
             // eth_* method
             MethodInvocation::Accounts(()) => eth::handle_accounts_request(data).and_then(to_json),
+            MethodInvocation::BlobBaseFee(()) => eth::handle_blob_base_fee(data).and_then(to_json),
             MethodInvocation::BlockNumber(()) => {
                 eth::handle_block_number_request(data).and_then(to_json)
             }
-            MethodInvocation::Call(request, block_spec, state_overrides) => {
-                eth::handle_call_request(data, request, block_spec, state_overrides)
-                    .and_then(to_json_with_trace)
+            MethodInvocation::Call(request, block_spec, state_overrides, block_overrides) => {
+                eth::handle_call_request(
+                    data,
+                    request,
+                    block_spec,
+                    state_overrides,
+                    block_overrides,
+                )
+                .and_then(to_json_with_trace)
             }
             MethodInvocation::ChainId(()) => eth::handle_chain_id_request(data).and_then(to_json),
             MethodInvocation::Coinbase(()) => eth::handle_coinbase_request(data).and_then(to_json),
-            MethodInvocation::EstimateGas(call_request, block_spec) => {
-                eth::handle_estimate_gas(data, call_request, block_spec)
-                    .and_then(to_json_with_traces)
+            MethodInvocation::CreateAccessList(call_request, block_spec) => {
+                eth::handle_create_access_list_request(data, call_request, block_spec)
+                    .and_then(to_json)
             }
+            MethodInvocation::EstimateGas(
+                call_request,
+                block_spec,
+                state_overrides,
+                block_overrides,
+            ) => eth::handle_estimate_gas(
+                data,
+                call_request,
+                block_spec,
+                state_overrides,
+                block_overrides,
+            )
+            .and_then(to_json_with_traces),
             MethodInvocation::FeeHistory(block_count, newest_block, reward_percentiles) => {
                 eth::handle_fee_history(data, block_count, newest_block, reward_percentiles)
                     .and_then(to_json)
@@ -236,6 +266,10 @@ impl<LoggerErrorT: Debug + Send + Sync + 'static> Provider<LoggerErrorT> {
             MethodInvocation::GetLogs(filter_options) => {
                 eth::handle_get_logs_request(data, filter_options).and_then(to_json)
             }
+            MethodInvocation::GetProof(address, storage_keys, block_spec) => {
+                eth::handle_get_proof_request(data, address, storage_keys, block_spec)
+                    .and_then(to_json)
+            }
             MethodInvocation::GetStorageAt(address, index, block_spec) => {
                 eth::handle_get_storage_at_request(data, address, index, block_spec)
                     .and_then(to_json)
@@ -294,6 +328,9 @@ impl<LoggerErrorT: Debug + Send + Sync + 'static> Provider<LoggerErrorT> {
             MethodInvocation::SignTypedDataV4(address, message) => {
                 eth::handle_sign_typed_data_v4(data, address, message).and_then(to_json)
             }
+            MethodInvocation::SimulateV1(payload, block_spec) => {
+                eth::handle_simulate_v1_request(data, payload, block_spec).and_then(to_json)
+            }
             MethodInvocation::Subscribe(subscription_type, filter_options) => {
                 eth::handle_subscribe_request(data, subscription_type, filter_options)
                     .and_then(to_json)
@@ -345,25 +382,83 @@ impl<LoggerErrorT: Debug + Send + Sync + 'static> Provider<LoggerErrorT> {
             }
 
             // debug_* methods
+            MethodInvocation::DebugTraceBlock(rlp, config) => {
+                debug::handle_debug_trace_block(data, rlp, config).and_then(to_json)
+            }
+            MethodInvocation::DebugTraceBlockByHash(block_hash, config) => {
+                debug::handle_debug_trace_block_by_hash(data, block_hash, config).and_then(to_json)
+            }
+            MethodInvocation::DebugTraceBlockByNumber(block_spec, config) => {
+                debug::handle_debug_trace_block_by_number(data, block_spec, config)
+                    .and_then(to_json)
+            }
             MethodInvocation::DebugTraceTransaction(transaction_hash, config) => {
                 debug::handle_debug_trace_transaction(data, transaction_hash, config)
                     .and_then(to_json)
             }
-            MethodInvocation::DebugTraceCall(call_request, block_spec, config) => {
-                debug::handle_debug_trace_call(data, call_request, block_spec, config)
+            MethodInvocation::DebugTraceCall(call_request, block_spec, config, block_overrides) => {
+                debug::handle_debug_trace_call(
+                    data,
+                    call_request,
+                    block_spec,
+                    config,
+                    block_overrides,
+                )
+                .and_then(to_json)
+            }
+
+            // trace_* methods
+            MethodInvocation::TraceBlock(block_spec) => {
+                trace::handle_trace_block(data, block_spec).and_then(to_json)
+            }
+            MethodInvocation::TraceFilter(filter) => {
+                trace::handle_trace_filter(data, filter).and_then(to_json)
+            }
+            MethodInvocation::TraceReplayTransaction(transaction_hash, trace_types) => {
+                trace::handle_trace_replay_transaction(data, transaction_hash, trace_types)
                     .and_then(to_json)
             }
+            MethodInvocation::TraceTransaction(transaction_hash) => {
+                trace::handle_trace_transaction(data, transaction_hash).and_then(to_json)
+            }
 
             // hardhat_* methods
-            MethodInvocation::AddCompilationResult(_, _, _) => Err(ProviderError::Unimplemented(
-                "AddCompilationResult".to_string(),
-            )),
+            MethodInvocation::AddCompilationResult(
+                solc_version,
+                compiler_input,
+                compiler_output,
+            ) => hardhat::handle_add_compilation_result(
+                data,
+                solc_version,
+                compiler_input,
+                compiler_output,
+            )
+            .and_then(to_json),
+            MethodInvocation::AddWithdrawals(withdrawals) => {
+                hardhat::handle_add_withdrawals(data, withdrawals).and_then(to_json)
+            }
             MethodInvocation::DropTransaction(transaction_hash) => {
                 hardhat::handle_drop_transaction(data, transaction_hash).and_then(to_json)
             }
+            MethodInvocation::DumpGenesis(()) => {
+                hardhat::handle_dump_genesis(data).and_then(to_json)
+            }
+            MethodInvocation::DumpState(()) => hardhat::handle_dump_state(data).and_then(to_json),
             MethodInvocation::GetAutomine(()) => {
                 hardhat::handle_get_automine_request(data).and_then(to_json)
             }
+            MethodInvocation::GetBlobSidecars(block_spec) => {
+                hardhat::handle_get_blob_sidecars(data, block_spec).and_then(to_json)
+            }
+            MethodInvocation::GetCoverage(format) => {
+                hardhat::handle_get_coverage(data, format).and_then(to_json)
+            }
+            MethodInvocation::GetGasProfile(transaction_hash) => {
+                hardhat::handle_get_gas_profile(data, transaction_hash).and_then(to_json)
+            }
+            MethodInvocation::GetGasReport(()) => {
+                hardhat::handle_get_gas_report(data).and_then(to_json)
+            }
             MethodInvocation::GetStackTraceFailuresCount(()) => Err(ProviderError::Unimplemented(
                 "GetStackTraceFailuresCount".to_string(),
             )),
@@ -374,13 +469,22 @@ impl<LoggerErrorT: Debug + Send + Sync + 'static> Provider<LoggerErrorT> {
             MethodInvocation::IntervalMine(()) => {
                 hardhat::handle_interval_mine_request(data).and_then(to_json)
             }
+            MethodInvocation::LoadState(dump) => {
+                hardhat::handle_load_state(data, *dump).and_then(to_json)
+            }
             MethodInvocation::Metadata(()) => {
                 hardhat::handle_metadata_request(data).and_then(to_json)
             }
             MethodInvocation::Mine(number_of_blocks, interval) => {
                 hardhat::handle_mine(data, number_of_blocks, interval).and_then(to_json_with_traces)
             }
+            MethodInvocation::Reorg(depth, new_blocks) => {
+                hardhat::handle_reorg(data, depth, new_blocks).and_then(to_json_with_traces)
+            }
             MethodInvocation::Reset(config) => self.reset(data, config).and_then(to_json),
+            MethodInvocation::ResetGasReport(()) => {
+                hardhat::handle_reset_gas_report(data).and_then(to_json)
+            }
             MethodInvocation::SetBalance(address, balance) => {
                 hardhat::handle_set_balance(data, address, balance).and_then(to_json)
             }
@@ -390,6 +494,9 @@ impl<LoggerErrorT: Debug + Send + Sync + 'static> Provider<LoggerErrorT> {
             MethodInvocation::SetCoinbase(coinbase) => {
                 hardhat::handle_set_coinbase_request(data, coinbase).and_then(to_json)
             }
+            MethodInvocation::SetFinalizedBlock(block_number) => {
+                hardhat::handle_set_finalized_block(data, block_number).and_then(to_json)
+            }
             MethodInvocation::SetLoggingEnabled(is_enabled) => {
                 hardhat::handle_set_logging_enabled_request(data, is_enabled).and_then(to_json)
             }
@@ -403,9 +510,16 @@ impl<LoggerErrorT: Debug + Send + Sync + 'static> Provider<LoggerErrorT> {
             MethodInvocation::SetNonce(address, nonce) => {
                 hardhat::handle_set_nonce(data, address, nonce).and_then(to_json)
             }
+            MethodInvocation::SetParentBeaconBlockRoot(parent_beacon_block_root) => {
+                hardhat::handle_set_parent_beacon_block_root(data, parent_beacon_block_root)
+                    .and_then(to_json)
+            }
             MethodInvocation::SetPrevRandao(prev_randao) => {
                 hardhat::handle_set_prev_randao_request(data, prev_randao).and_then(to_json)
             }
+            MethodInvocation::SetSafeBlock(block_number) => {
+                hardhat::handle_set_safe_block(data, block_number).and_then(to_json)
+            }
             MethodInvocation::SetStorageAt(address, index, value) => {
                 hardhat::handle_set_storage_at(data, address, index, value).and_then(to_json)
             }
@@ -414,6 +528,23 @@ impl<LoggerErrorT: Debug + Send + Sync + 'static> Provider<LoggerErrorT> {
             }
         };
 
+        // Attach Solidity stack traces to failed transactions
+        let result = result.map_err(|mut error| {
+            match &mut error {
+                ProviderError::EstimateGasTransactionFailure(EstimateGasFailure {
+                    transaction_failure,
+                    ..
+                })
+                | ProviderError::TransactionFailed(transaction_failure) => {
+                    let failure = &mut transaction_failure.failure;
+                    failure.stack_trace = data.solidity_stack_trace(&failure.solidity_trace);
+                }
+                _ => (),
+            }
+
+            error
+        });
+
         if let Some(method_name) = method_name {
             // Skip printing for `hardhat_intervalMine` unless it is an error
             if method_name != "hardhat_intervalMine" || result.is_err() {
@@ -1,8 +1,14 @@
+mod json;
+
 use core::fmt::Debug;
+use std::sync::Arc;
 
 use dyn_clone::DynClone;
 use edr_evm::ExecutableTransaction;
+use edr_solidity::contracts_identifier::ContractsIdentifier;
+use parking_lot::RwLock;
 
+pub use self::json::JsonLogger;
 use crate::{
     data::CallResult, debug_mine::DebugMineBlockResult, error::EstimateGasFailure, ProviderError,
 };
@@ -18,6 +24,13 @@ pub trait Logger {
     /// Sets whether the logger is enabled.
     fn set_is_enabled(&mut self, is_enabled: bool);
 
+    /// Provides the contracts of the compilation results added to the
+    /// provider, which can be used to identify the contracts and functions
+    /// of traces.
+    fn set_contracts_identifier(&mut self, contracts_identifier: Arc<RwLock<ContractsIdentifier>>) {
+        let _contracts_identifier = contracts_identifier;
+    }
+
     fn log_call(
         &mut self,
         spec_id: edr_eth::SpecId,
@@ -0,0 +1,534 @@
+use std::{
+    convert::Infallible,
+    fs::OpenOptions,
+    io::{self, Write},
+    path::Path,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use edr_eth::{Address, B256, U256};
+use edr_evm::{
+    blockchain::BlockchainError,
+    trace::{Trace, TraceMessage},
+    ExecutableTransaction, ExecutionResult, SyncBlock,
+};
+use edr_solidity::contracts_identifier::{
+    CallMessageTrace, ContractsIdentifier, CreateMessageTrace, EvmMessageTrace,
+};
+use itertools::izip;
+use parking_lot::{Mutex, RwLock};
+
+use super::Logger;
+use crate::{
+    data::CallResult, debug_mine::DebugMineBlockResult, error::EstimateGasFailure, ProviderError,
+    TransactionFailure,
+};
+
+/// A logger that writes one JSON object per line for each event.
+///
+/// Events that occur while handling a method are written, tagged with the
+/// method's name, once the method has been handled, followed by a `method`
+/// event. Blocks mined by the interval miner are written immediately.
+///
+/// Failing to write doesn't fail the method; the error is logged instead.
+#[derive(Clone)]
+pub struct JsonLogger {
+    contracts_identifier: Arc<RwLock<ContractsIdentifier>>,
+    events: Vec<LogEvent>,
+    is_enabled: bool,
+    writer: Arc<Mutex<dyn Write + Send>>,
+}
+
+impl JsonLogger {
+    /// Constructs a new instance that writes to the provided writer.
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            contracts_identifier: Arc::default(),
+            events: Vec::new(),
+            is_enabled: true,
+            writer: Arc::new(Mutex::new(writer)),
+        }
+    }
+
+    /// Constructs a new instance that appends to the file at the provided
+    /// path, creating it if it doesn't exist.
+    pub fn with_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(Self::new(io::LineWriter::new(file)))
+    }
+
+    fn write_event(&self, method: Option<&str>, event: &LogEvent) {
+        if let Err(error) = self.try_write_event(method, event) {
+            log::error!("Failed to write JSON log: {error}");
+        }
+    }
+
+    fn try_write_event(&self, method: Option<&str>, event: &LogEvent) -> io::Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| {
+                u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+            });
+
+        let line = serde_json::to_string(&LogLine {
+            timestamp,
+            method,
+            event,
+        })?;
+
+        let mut writer = self.writer.lock();
+        writeln!(writer, "{line}")?;
+        writer.flush()
+    }
+
+    fn block_entry(&self, result: &DebugMineBlockResult<BlockchainError>) -> BlockEntry {
+        let header = result.block.header();
+
+        let transactions = izip!(
+            result.block.transactions(),
+            &result.transaction_results,
+            &result.transaction_traces
+        )
+        .map(|(transaction, result, trace)| {
+            self.transaction_entry(transaction, Some(result), trace)
+        })
+        .collect();
+
+        BlockEntry {
+            number: header.number,
+            hash: *result.block.hash(),
+            base_fee_per_gas: header.base_fee_per_gas,
+            transactions,
+            console_logs: result.console_log_messages.clone(),
+        }
+    }
+
+    fn transaction_entry(
+        &self,
+        transaction: &ExecutableTransaction,
+        result: Option<&ExecutionResult>,
+        trace: &Trace,
+    ) -> TransactionEntry {
+        let (contract, function) = self.contract_and_function_name(trace);
+
+        TransactionEntry {
+            hash: *transaction.hash(),
+            from: *transaction.caller(),
+            to: transaction.to(),
+            value: transaction.value(),
+            gas_limit: transaction.gas_limit(),
+            gas_used: result.map(ExecutionResult::gas_used),
+            contract,
+            function,
+            failure: result.and_then(|result| {
+                TransactionFailure::from_execution_result(result, transaction.hash(), trace)
+                    .map(|failure| failure.to_string())
+            }),
+        }
+    }
+
+    /// Identifies the contract and function of the trace's outermost message,
+    /// using the compilation results added to the provider.
+    fn contract_and_function_name(&self, trace: &Trace) -> (Option<String>, Option<String>) {
+        let Some(TraceMessage::Before(message)) = trace.messages.first() else {
+            return (None, None);
+        };
+
+        let message_trace = if message.to.is_some() {
+            let code = message
+                .code
+                .as_ref()
+                .map(edr_evm::Bytecode::original_bytes)
+                .unwrap_or_default();
+
+            EvmMessageTrace::Call(CallMessageTrace::new(code))
+        } else {
+            EvmMessageTrace::Create(CreateMessageTrace::new(message.data.clone()))
+        };
+
+        let Some(bytecode) = self
+            .contracts_identifier
+            .read()
+            .bytecode_by_message_trace(message_trace)
+        else {
+            return (None, None);
+        };
+
+        let contract = bytecode.contract();
+        let function = if message.to.is_none() {
+            Some("constructor".to_string())
+        } else if let Some(function) = message
+            .data
+            .get(..4)
+            .and_then(|selector| contract.function_from_selector(selector))
+        {
+            Some(function.name.clone())
+        } else if message.data.is_empty() && contract.receive.is_some() {
+            Some("receive".to_string())
+        } else {
+            contract.fallback.as_ref().map(|_| "fallback".to_string())
+        };
+
+        (Some(contract.name.clone()), function)
+    }
+}
+
+impl Logger for JsonLogger {
+    type BlockchainError = BlockchainError;
+
+    type LoggerError = Infallible;
+
+    fn is_enabled(&self) -> bool {
+        self.is_enabled
+    }
+
+    fn set_is_enabled(&mut self, is_enabled: bool) {
+        self.is_enabled = is_enabled;
+    }
+
+    fn set_contracts_identifier(&mut self, contracts_identifier: Arc<RwLock<ContractsIdentifier>>) {
+        self.contracts_identifier = contracts_identifier;
+    }
+
+    fn log_call(
+        &mut self,
+        _spec_id: edr_eth::SpecId,
+        transaction: &ExecutableTransaction,
+        result: &CallResult,
+    ) -> Result<(), Self::LoggerError> {
+        if self.is_enabled {
+            let transaction =
+                self.transaction_entry(transaction, Some(&result.execution_result), &result.trace);
+
+            self.events.push(LogEvent::Call(CallEntry {
+                transaction,
+                console_logs: result.console_log_messages.clone(),
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn log_estimate_gas_failure(
+        &mut self,
+        _spec_id: edr_eth::SpecId,
+        transaction: &ExecutableTransaction,
+        result: &EstimateGasFailure,
+    ) -> Result<(), Self::LoggerError> {
+        if self.is_enabled {
+            let failure = &result.transaction_failure.failure;
+
+            let mut transaction =
+                self.transaction_entry(transaction, None, &failure.solidity_trace);
+            transaction.failure = Some(failure.to_string());
+
+            self.events.push(LogEvent::EstimateGasFailure(CallEntry {
+                transaction,
+                console_logs: result.console_log_messages.clone(),
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn log_interval_mined(
+        &mut self,
+        _spec_id: edr_eth::SpecId,
+        result: &DebugMineBlockResult<Self::BlockchainError>,
+    ) -> Result<(), Self::LoggerError> {
+        if self.is_enabled {
+            self.write_event(
+                None,
+                &LogEvent::IntervalMinedBlock(self.block_entry(result)),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn log_mined_block(
+        &mut self,
+        _spec_id: edr_eth::SpecId,
+        results: &[DebugMineBlockResult<Self::BlockchainError>],
+    ) -> Result<(), Self::LoggerError> {
+        if self.is_enabled {
+            for result in results {
+                let block = self.block_entry(result);
+                self.events.push(LogEvent::MinedBlock(block));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn log_send_transaction(
+        &mut self,
+        _spec_id: edr_eth::SpecId,
+        transaction: &ExecutableTransaction,
+        mining_results: &[DebugMineBlockResult<Self::BlockchainError>],
+    ) -> Result<(), Self::LoggerError> {
+        if !self.is_enabled {
+            return Ok(());
+        }
+
+        let mut blocks = Vec::with_capacity(mining_results.len());
+        let mut sent_transaction = None;
+
+        for result in mining_results {
+            let block = self.block_entry(result);
+
+            if let Some(entry) = block
+                .transactions
+                .iter()
+                .find(|entry| entry.hash == *transaction.hash())
+            {
+                sent_transaction = Some((entry.clone(), block.number, block.hash));
+            }
+
+            blocks.push(block);
+        }
+
+        let event = match sent_transaction {
+            Some((transaction, block_number, block_hash)) => SentTransactionEntry {
+                transaction,
+                block_number: Some(block_number),
+                block_hash: Some(block_hash),
+            },
+            // The transaction was added to the mem pool
+            None => SentTransactionEntry {
+                transaction: self.transaction_entry(transaction, None, &Trace::default()),
+                block_number: None,
+                block_hash: None,
+            },
+        };
+
+        self.events
+            .extend(blocks.into_iter().map(LogEvent::MinedBlock));
+        self.events.push(LogEvent::Transaction(event));
+
+        Ok(())
+    }
+
+    fn print_method_logs(
+        &mut self,
+        method: &str,
+        error: Option<&ProviderError<Self::LoggerError>>,
+    ) -> Result<(), Self::LoggerError> {
+        let events = std::mem::take(&mut self.events);
+        if !self.is_enabled {
+            return Ok(());
+        }
+
+        for event in &events {
+            self.write_event(Some(method), event);
+        }
+
+        self.write_event(
+            Some(method),
+            &LogEvent::Method(MethodEntry {
+                error: error.map(ToString::to_string),
+            }),
+        );
+
+        Ok(())
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LogLine<'a> {
+    /// Milliseconds since the UNIX epoch
+    timestamp: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    method: Option<&'a str>,
+    #[serde(flatten)]
+    event: &'a LogEvent,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(tag = "event", rename_all = "camelCase")]
+enum LogEvent {
+    Call(CallEntry),
+    EstimateGasFailure(CallEntry),
+    IntervalMinedBlock(BlockEntry),
+    Method(MethodEntry),
+    MinedBlock(BlockEntry),
+    Transaction(SentTransactionEntry),
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockEntry {
+    number: u64,
+    hash: B256,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_fee_per_gas: Option<U256>,
+    transactions: Vec<TransactionEntry>,
+    console_logs: Vec<String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CallEntry {
+    transaction: TransactionEntry,
+    console_logs: Vec<String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MethodEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SentTransactionEntry {
+    transaction: TransactionEntry,
+    /// The number of the block that includes the transaction, if it was
+    /// mined
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_number: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_hash: Option<B256>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionEntry {
+    hash: B256,
+    from: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<Address>,
+    value: U256,
+    gas_limit: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gas_used: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contract: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    function: Option<String>,
+    /// The decoded reason of a failed transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failure: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use edr_eth::{
+        transaction::{Eip155TransactionRequest, TransactionKind},
+        Bytes, SpecId,
+    };
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl SharedBuffer {
+        fn lines(&self) -> Vec<serde_json::Value> {
+            let buffer = self.0.lock();
+            String::from_utf8_lossy(&buffer)
+                .lines()
+                .map(|line| serde_json::from_str(line).expect("line should be valid JSON"))
+                .collect()
+        }
+    }
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn call_transaction() -> anyhow::Result<ExecutableTransaction> {
+        let caller = Address::repeat_byte(0x01);
+        let request = Eip155TransactionRequest {
+            nonce: 0,
+            gas_price: U256::ZERO,
+            gas_limit: 30_000,
+            kind: TransactionKind::Call(Address::repeat_byte(0x02)),
+            value: U256::from(1),
+            input: Bytes::new(),
+            chain_id: 123,
+        };
+
+        let transaction = ExecutableTransaction::with_caller(
+            SpecId::LATEST,
+            request.fake_sign(&caller).into(),
+            caller,
+        )?;
+
+        Ok(transaction)
+    }
+
+    #[test]
+    fn call_and_method_events() -> anyhow::Result<()> {
+        let buffer = SharedBuffer::default();
+        let mut logger = JsonLogger::new(buffer.clone());
+
+        let transaction = call_transaction()?;
+        let result = CallResult {
+            console_log_inputs: Vec::new(),
+            console_log_messages: vec!["hello".to_string()],
+            execution_result: ExecutionResult::Revert {
+                gas_used: 21_000,
+                output: Bytes::new(),
+            },
+            trace: Trace::default(),
+        };
+
+        logger.log_call(SpecId::LATEST, &transaction, &result)?;
+        assert!(buffer.lines().is_empty());
+
+        logger.print_method_logs("eth_call", None)?;
+        logger.print_method_logs(
+            "eth_foo",
+            Some(&ProviderError::UnsupportedMethod {
+                method_name: "eth_foo".to_string(),
+            }),
+        )?;
+
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), 3);
+
+        let call = &lines[0];
+        assert_eq!(call["event"], "call");
+        assert_eq!(call["method"], "eth_call");
+        assert!(call["timestamp"].is_u64());
+
+        let entry = &call["transaction"];
+        assert_eq!(entry["hash"], serde_json::to_value(transaction.hash())?);
+        assert_eq!(entry["from"], serde_json::to_value(transaction.caller())?);
+        assert_eq!(entry["gasUsed"], 21_000);
+        assert_eq!(entry["failure"], "Transaction reverted without a reason");
+        assert_eq!(call["consoleLogs"], serde_json::json!(["hello"]));
+
+        assert_eq!(lines[1]["event"], "method");
+        assert_eq!(lines[1]["method"], "eth_call");
+        assert!(lines[1].get("error").is_none());
+
+        assert_eq!(lines[2]["method"], "eth_foo");
+        assert_eq!(lines[2]["error"], "eth_foo - Method not supported");
+
+        Ok(())
+    }
+
+    #[test]
+    fn disabled() -> anyhow::Result<()> {
+        let buffer = SharedBuffer::default();
+        let mut logger = JsonLogger::new(buffer.clone());
+        logger.set_is_enabled(false);
+
+        logger.print_method_logs("eth_blockNumber", None)?;
+        assert!(buffer.lines().is_empty());
+
+        Ok(())
+    }
+}
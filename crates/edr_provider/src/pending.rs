@@ -2,7 +2,7 @@ use std::{collections::BTreeMap, sync::Arc};
 
 use edr_eth::{receipt::BlockReceipt, SpecId, B256, U256};
 use edr_evm::{
-    blockchain::{Blockchain, BlockchainError, BlockchainMut, SyncBlockchain},
+    blockchain::{Blockchain, BlockchainError, BlockchainMut, HistoryEntry, SyncBlockchain},
     db::BlockHashRef,
     state::{StateDiff, StateError, StateOverride, SyncState},
     BlockAndTotalDifficulty, LocalBlock, SyncBlock,
@@ -102,6 +102,15 @@ impl<'blockchain> Blockchain for BlockchainWithPending<'blockchain> {
         self.pending_block.header().number
     }
 
+    fn local_history(
+        &self,
+    ) -> Result<
+        Vec<HistoryEntry<Arc<dyn SyncBlock<Error = Self::BlockchainError>>>>,
+        Self::BlockchainError,
+    > {
+        Err(BlockchainError::UnsupportedHistory)
+    }
+
     fn logs(
         &self,
         _from_block: u64,
@@ -5,6 +5,8 @@ pub mod eth;
 pub mod hardhat;
 mod methods;
 mod serde;
+/// Parity-style trace RPC request types
+pub mod trace;
 mod validation;
 
 use std::fmt;
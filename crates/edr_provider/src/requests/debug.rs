@@ -1,10 +1,15 @@
 use core::fmt::Debug;
 
 use edr_eth::{
-    remote::{eth::CallRequest, BlockSpec},
-    B256,
+    block::Block,
+    remote::{eth::CallRequest, BlockOverrides, BlockSpec, PreEip1898BlockSpec},
+    rlp::Decodable,
+    Bytes, B256,
+};
+use edr_evm::{
+    state::StateOverrides, CallTracerConfig, DebugTraceBlockResult, DebugTraceOutput, DebugTracer,
+    ExecutableTransaction, PrestateTracerConfig,
 };
-use edr_evm::{state::StateOverrides, DebugTraceResult};
 use serde::{Deserialize, Deserializer};
 
 use crate::{
@@ -17,7 +22,7 @@ pub fn handle_debug_trace_transaction<LoggerErrorT: Debug>(
     data: &mut ProviderData<LoggerErrorT>,
     transaction_hash: B256,
     config: Option<DebugTraceConfig>,
-) -> Result<DebugTraceResult, ProviderError<LoggerErrorT>> {
+) -> Result<DebugTraceOutput, ProviderError<LoggerErrorT>> {
     data.debug_trace_transaction(
         &transaction_hash,
         config.map(Into::into).unwrap_or_default(),
@@ -35,7 +40,8 @@ pub fn handle_debug_trace_call<LoggerErrorT: Debug>(
     call_request: CallRequest,
     block_spec: Option<BlockSpec>,
     config: Option<DebugTraceConfig>,
-) -> Result<DebugTraceResult, ProviderError<LoggerErrorT>> {
+    block_overrides: Option<BlockOverrides>,
+) -> Result<DebugTraceOutput, ProviderError<LoggerErrorT>> {
     validate_call_request(data.spec_id(), &call_request, &block_spec)?;
 
     let transaction = resolve_call_request(
@@ -47,6 +53,64 @@ pub fn handle_debug_trace_call<LoggerErrorT: Debug>(
     data.debug_trace_call(
         transaction,
         block_spec.as_ref(),
+        &block_overrides.unwrap_or_default(),
+        config.map(Into::into).unwrap_or_default(),
+    )
+}
+
+pub fn handle_debug_trace_block_by_number<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    block_spec: PreEip1898BlockSpec,
+    config: Option<DebugTraceConfig>,
+) -> Result<Vec<DebugTraceBlockResult>, ProviderError<LoggerErrorT>> {
+    let block = data
+        .block_by_block_spec(&block_spec.into())?
+        .ok_or_else(|| {
+            ProviderError::InvalidInput("Tracing the pending block is not supported".to_string())
+        })?;
+
+    data.debug_trace_block(
+        block.header(),
+        block.transactions().to_vec(),
+        config.map(Into::into).unwrap_or_default(),
+    )
+}
+
+pub fn handle_debug_trace_block_by_hash<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    block_hash: B256,
+    config: Option<DebugTraceConfig>,
+) -> Result<Vec<DebugTraceBlockResult>, ProviderError<LoggerErrorT>> {
+    let block = data
+        .block_by_hash(&block_hash)?
+        .ok_or_else(|| ProviderError::InvalidInput(format!("Block {block_hash} not found")))?;
+
+    data.debug_trace_block(
+        block.header(),
+        block.transactions().to_vec(),
+        config.map(Into::into).unwrap_or_default(),
+    )
+}
+
+pub fn handle_debug_trace_block<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    rlp: Bytes,
+    config: Option<DebugTraceConfig>,
+) -> Result<Vec<DebugTraceBlockResult>, ProviderError<LoggerErrorT>> {
+    let block = Block::decode(&mut rlp.as_ref()).map_err(|error| {
+        ProviderError::InvalidArgument(format!("Could not decode block: {error}"))
+    })?;
+
+    let spec_id = data.spec_at_block_number(block.header.number)?;
+    let transactions = block
+        .transactions
+        .into_iter()
+        .map(|transaction| ExecutableTransaction::new(spec_id, transaction))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    data.debug_trace_block(
+        &block.header,
+        transactions,
         config.map(Into::into).unwrap_or_default(),
     )
 }
@@ -55,11 +119,15 @@ pub fn handle_debug_trace_call<LoggerErrorT: Debug>(
 #[derive(Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DebugTraceConfig {
-    /// Which tracer to use. This argument is currently unsupported.
+    /// Which tracer to use. Defaults to the EIP-3155 tracer.
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(deserialize_with = "deserialize_tracer")]
     #[serde(default)]
     pub tracer: Option<Tracer>,
+    /// Config options for the built-in tracers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub tracer_config: Option<TracerConfig>,
     /// Disable storage trace.
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
@@ -74,11 +142,36 @@ pub struct DebugTraceConfig {
     pub disable_stack: Option<bool>,
 }
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+/// The geth built-in tracers
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub enum Tracer {
-    #[default]
-    #[serde(rename = "default")]
-    Default,
+    #[serde(rename = "callTracer")]
+    CallTracer,
+    #[serde(rename = "prestateTracer")]
+    PrestateTracer,
+    #[serde(rename = "4byteTracer")]
+    FourByteTracer,
+    #[serde(rename = "noopTracer")]
+    NoopTracer,
+}
+
+/// Config options for the built-in tracers. Options that don't apply to the
+/// selected tracer are ignored.
+#[derive(Clone, Debug, Default, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TracerConfig {
+    /// Only trace the top-level call. Used by the `callTracer`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub only_top_call: Option<bool>,
+    /// Include the logs of calls. Used by the `callTracer`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub with_log: Option<bool>,
+    /// Return the state diff of the transaction. Used by the `prestateTracer`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub diff_mode: Option<bool>,
 }
 
 fn deserialize_tracer<'de, DeserializerT>(
@@ -87,31 +180,83 @@ fn deserialize_tracer<'de, DeserializerT>(
 where
     DeserializerT: Deserializer<'de>,
 {
-    const HARDHAT_ERROR: &str = "Hardhat currently only supports the default tracer, so no tracer parameter should be passed.";
-
-    let tracer = Option::<Tracer>::deserialize(deserializer)
-        .map_err(|_error| serde::de::Error::custom(HARDHAT_ERROR))?;
+    const HARDHAT_ERROR: &str = "Hardhat currently only supports the default tracer and the callTracer, prestateTracer, 4byteTracer and noopTracer built-in tracers.";
 
-    if tracer.is_some() {
-        Err(serde::de::Error::custom(HARDHAT_ERROR))
-    } else {
-        Ok(tracer)
-    }
+    Option::<Tracer>::deserialize(deserializer)
+        .map_err(|_error| serde::de::Error::custom(HARDHAT_ERROR))
 }
 
 impl From<DebugTraceConfig> for edr_evm::DebugTraceConfig {
     fn from(value: DebugTraceConfig) -> Self {
         let DebugTraceConfig {
+            tracer,
+            tracer_config,
             disable_storage,
             disable_memory,
             disable_stack,
-            // Tracer argument is not supported by Hardhat
-            tracer: _,
         } = value;
+
+        let TracerConfig {
+            only_top_call,
+            with_log,
+            diff_mode,
+        } = tracer_config.unwrap_or_default();
+
+        let tracer = match tracer {
+            None => DebugTracer::Eip3155,
+            Some(Tracer::CallTracer) => DebugTracer::Call(CallTracerConfig {
+                only_top_call: only_top_call.unwrap_or_default(),
+                with_log: with_log.unwrap_or_default(),
+            }),
+            Some(Tracer::PrestateTracer) => DebugTracer::Prestate(PrestateTracerConfig {
+                diff_mode: diff_mode.unwrap_or_default(),
+            }),
+            Some(Tracer::FourByteTracer) => DebugTracer::FourByte,
+            Some(Tracer::NoopTracer) => DebugTracer::Noop,
+        };
+
         Self {
+            tracer,
             disable_storage: disable_storage.unwrap_or_default(),
             disable_memory: disable_memory.unwrap_or_default(),
             disable_stack: disable_stack.unwrap_or_default(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_call_tracer_config() {
+        let config: DebugTraceConfig = serde_json::from_value(serde_json::json!({
+            "tracer": "callTracer",
+            "tracerConfig": {
+                "onlyTopCall": true,
+            },
+        }))
+        .unwrap();
+
+        let config = edr_evm::DebugTraceConfig::from(config);
+        assert!(matches!(
+            config.tracer,
+            DebugTracer::Call(CallTracerConfig {
+                only_top_call: true,
+                with_log: false,
+            })
+        ));
+    }
+
+    #[test]
+    fn deserialize_unsupported_tracer() {
+        let error = serde_json::from_value::<DebugTraceConfig>(serde_json::json!({
+            "tracer": "unsupportedTracer",
+        }))
+        .unwrap_err();
+
+        assert!(error
+            .to_string()
+            .starts_with("Hardhat currently only supports the default tracer"));
+    }
+}
@@ -8,11 +8,12 @@ mod filter;
 mod gas;
 mod mine;
 mod sign;
+mod simulate;
 mod state;
 mod transactions;
 mod web3;
 
 pub use self::{
     accounts::*, blockchain::*, blocks::*, call::*, config::*, evm::*, filter::*, gas::*, mine::*,
-    sign::*, state::*, transactions::*, web3::*,
+    sign::*, simulate::*, state::*, transactions::*, web3::*,
 };
@@ -1,12 +1,13 @@
 use core::fmt::Debug;
 
 use edr_eth::{
-    remote::{eth::CallRequest, BlockSpec, StateOverrideOptions},
+    access_list::AccessListItem,
+    remote::{eth::CallRequest, BlockOverrides, BlockSpec, StateOverrideOptions},
     transaction::{
         Eip1559TransactionRequest, Eip155TransactionRequest, Eip2930TransactionRequest,
         TransactionRequest,
     },
-    Bytes, SpecId, U256,
+    Bytes, SpecId, U256, U64,
 };
 use edr_evm::{state::StateOverrides, trace::Trace, ExecutableTransaction};
 
@@ -20,6 +21,7 @@ pub fn handle_call_request<LoggerErrorT: Debug>(
     request: CallRequest,
     block_spec: Option<BlockSpec>,
     state_overrides: Option<StateOverrideOptions>,
+    block_overrides: Option<BlockOverrides>,
 ) -> Result<(Bytes, Trace), ProviderError<LoggerErrorT>> {
     validate_call_request(data.spec_id(), &request, &block_spec)?;
 
@@ -27,7 +29,12 @@ pub fn handle_call_request<LoggerErrorT: Debug>(
         state_overrides.map_or(Ok(StateOverrides::default()), StateOverrides::try_from)?;
 
     let transaction = resolve_call_request(data, request, block_spec.as_ref(), &state_overrides)?;
-    let result = data.run_call(transaction.clone(), block_spec.as_ref(), &state_overrides)?;
+    let result = data.run_call(
+        transaction.clone(),
+        block_spec.as_ref(),
+        &state_overrides,
+        &block_overrides.unwrap_or_default(),
+    )?;
 
     let spec_id = data.spec_id();
     data.logger_mut()
@@ -53,6 +60,73 @@ pub fn handle_call_request<LoggerErrorT: Debug>(
     Ok((output, result.trace))
 }
 
+pub fn handle_create_access_list_request<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    request: CallRequest,
+    block_spec: Option<BlockSpec>,
+) -> Result<AccessListResult, ProviderError<LoggerErrorT>> {
+    if data.spec_id() < SpecId::BERLIN {
+        return Err(ProviderError::InvalidInput(
+            "eth_createAccessList is disabled. It only works with the Berlin hardfork or a later one."
+                .into(),
+        ));
+    }
+
+    validate_call_request(data.spec_id(), &request, &block_spec)?;
+
+    // Matching geth behavior in defaulting to "pending" instead of "latest"
+    let block_spec = block_spec.unwrap_or_else(BlockSpec::pending);
+
+    // Accessing an address or slot can change the execution, so the access list is
+    // recomputed until it no longer changes.
+    let mut access_list = request.access_list.clone().unwrap_or_default();
+    loop {
+        let transaction = resolve_call_request(
+            data,
+            CallRequest {
+                access_list: Some(access_list.clone()),
+                ..request.clone()
+            },
+            Some(&block_spec),
+            &StateOverrides::default(),
+        )?;
+        let transaction_hash = *transaction.hash();
+
+        let (execution_result, new_access_list) =
+            data.create_access_list(transaction, &block_spec)?;
+
+        if new_access_list.0 == access_list {
+            let error = TransactionFailure::from_execution_result(
+                &execution_result,
+                &transaction_hash,
+                &Trace::default(),
+            )
+            .map(|failure| failure.to_string());
+
+            return Ok(AccessListResult {
+                access_list,
+                gas_used: U64::from(execution_result.gas_used()),
+                error,
+            });
+        }
+
+        access_list = new_access_list.into();
+    }
+}
+
+/// The result of `eth_createAccessList`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListResult {
+    /// The addresses and storage slots accessed by the call
+    pub access_list: Vec<AccessListItem>,
+    /// The gas used by the call when the access list is applied
+    pub gas_used: U64,
+    /// The reason the call failed, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 pub(crate) fn resolve_call_request<LoggerErrorT: Debug>(
     data: &mut ProviderData<LoggerErrorT>,
     request: CallRequest,
@@ -162,9 +236,92 @@ pub(crate) fn resolve_call_request_inner<LoggerErrorT: Debug>(
 
 #[cfg(test)]
 mod tests {
+    use edr_eth::{Address, B256};
+
     use super::*;
     use crate::{data::test_utils::ProviderTestFixture, test_utils::pending_base_fee};
 
+    #[test]
+    fn call_with_block_overrides() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let contract = Address::repeat_byte(0x42);
+
+        // MSTORE(0, TIMESTAMP); MSTORE(32, NUMBER); RETURN(0, 64)
+        let code = vec![
+            0x42, 0x60, 0x00, 0x52, 0x43, 0x60, 0x20, 0x52, 0x60, 0x40, 0x60, 0x00, 0xf3,
+        ];
+        fixture.provider_data.set_code(contract, code.into())?;
+
+        let request = CallRequest {
+            from: Some(fixture.nth_local_account(0)?),
+            to: Some(contract),
+            ..CallRequest::default()
+        };
+
+        let block_overrides = BlockOverrides {
+            number: Some(1_000),
+            time: Some(2_000_000_000),
+            ..BlockOverrides::default()
+        };
+
+        let (output, _trace) = handle_call_request(
+            &mut fixture.provider_data,
+            request,
+            None,
+            None,
+            Some(block_overrides),
+        )?;
+
+        assert_eq!(
+            U256::from_be_slice(&output[..32]),
+            U256::from(2_000_000_000)
+        );
+        assert_eq!(U256::from_be_slice(&output[32..]), U256::from(1_000));
+
+        Ok(())
+    }
+
+    #[test]
+    fn create_access_list() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let contract = Address::repeat_byte(0x42);
+        let other = Address::repeat_byte(0x11);
+
+        // SLOAD(1); BALANCE(other); BALANCE(ecrecover); STOP
+        let mut code = vec![0x60, 0x01, 0x54, 0x50, 0x73];
+        code.extend_from_slice(other.as_slice());
+        code.extend_from_slice(&[0x31, 0x50, 0x60, 0x01, 0x31, 0x50, 0x00]);
+        fixture.provider_data.set_code(contract, code.into())?;
+
+        let request = CallRequest {
+            from: Some(fixture.nth_local_account(0)?),
+            to: Some(contract),
+            ..CallRequest::default()
+        };
+
+        let result = handle_create_access_list_request(&mut fixture.provider_data, request, None)?;
+
+        assert_eq!(
+            result.access_list,
+            vec![
+                AccessListItem {
+                    address: other,
+                    storage_keys: Vec::new(),
+                },
+                AccessListItem {
+                    address: contract,
+                    storage_keys: vec![B256::from(U256::from(1))],
+                },
+            ]
+        );
+        assert!(result.gas_used > U64::from(21_000));
+        assert_eq!(result.error, None);
+
+        Ok(())
+    }
+
     #[test]
     fn resolve_call_request_inner_with_gas_price() -> anyhow::Result<()> {
         let mut fixture = ProviderTestFixture::new_local()?;
@@ -4,6 +4,12 @@ use edr_eth::{Address, U256, U64};
 
 use crate::{data::ProviderData, ProviderError};
 
+pub fn handle_blob_base_fee<LoggerErrorT: Debug>(
+    data: &ProviderData<LoggerErrorT>,
+) -> Result<U256, ProviderError<LoggerErrorT>> {
+    data.next_block_blob_base_fee()
+}
+
 pub fn handle_gas_price<LoggerErrorT: Debug>(
     data: &ProviderData<LoggerErrorT>,
 ) -> Result<U256, ProviderError<LoggerErrorT>> {
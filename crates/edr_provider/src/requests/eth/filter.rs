@@ -126,10 +126,9 @@ fn validate_filter_criteria<const SHOULD_RESOLVE_LATEST: bool, LoggerErrorT: Deb
                 | BlockSpec::Eip1898(Eip1898BlockSpec::Number { block_number }),
             ) => Some(block_number),
             Some(BlockSpec::Tag(BlockTag::Earliest)) => Some(0),
-            Some(BlockSpec::Tag(
-                BlockTag::Latest | BlockTag::Pending | BlockTag::Safe | BlockTag::Finalized,
-            ))
-            | None => None,
+            Some(BlockSpec::Tag(BlockTag::Safe)) => Some(data.safe_block_number()),
+            Some(BlockSpec::Tag(BlockTag::Finalized)) => Some(data.finalized_block_number()),
+            Some(BlockSpec::Tag(BlockTag::Latest | BlockTag::Pending)) | None => None,
             Some(BlockSpec::Eip1898(Eip1898BlockSpec::Hash { block_hash, .. })) => {
                 let block =
                     data.block_by_hash(&block_hash)?
@@ -3,7 +3,7 @@ use core::fmt::Debug;
 use edr_eth::{
     remote::{
         eth::{CallRequest, FeeHistoryResult},
-        BlockSpec,
+        BlockOverrides, BlockSpec, StateOverrideOptions,
     },
     reward_percentile::RewardPercentile,
     SpecId, U256, U64,
@@ -21,17 +21,28 @@ pub fn handle_estimate_gas<LoggerErrorT: Debug>(
     data: &mut ProviderData<LoggerErrorT>,
     call_request: CallRequest,
     block_spec: Option<BlockSpec>,
+    state_overrides: Option<StateOverrideOptions>,
+    block_overrides: Option<BlockOverrides>,
 ) -> Result<(U64, Vec<Trace>), ProviderError<LoggerErrorT>> {
     validate_call_request(data.spec_id(), &call_request, &block_spec)?;
 
+    let state_overrides =
+        state_overrides.map_or(Ok(StateOverrides::default()), StateOverrides::try_from)?;
+    let block_overrides = block_overrides.unwrap_or_default();
+
     // Matching Hardhat behavior in defaulting to "pending" instead of "latest" for
     // estimate gas.
     let block_spec = block_spec.unwrap_or_else(BlockSpec::pending);
 
     let transaction =
-        resolve_estimate_gas_request(data, call_request, &block_spec, &StateOverrides::default())?;
-
-    let result = data.estimate_gas(transaction.clone(), &block_spec);
+        resolve_estimate_gas_request(data, call_request, &block_spec, &state_overrides)?;
+
+    let result = data.estimate_gas(
+        transaction.clone(),
+        &block_spec,
+        &state_overrides,
+        &block_overrides,
+    );
     if let Err(ProviderError::EstimateGasTransactionFailure(failure)) = result {
         let spec_id = data.spec_id();
         data.logger_mut()
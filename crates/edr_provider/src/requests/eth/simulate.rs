@@ -0,0 +1,271 @@
+use core::fmt::Debug;
+
+use edr_eth::{
+    remote::{eth::CallRequest, BlockOverrides, BlockSpec, StateOverrideOptions},
+    Address, Bytes, B256, U256, U64,
+};
+use edr_evm::state::StateOverrides;
+
+use super::resolve_call_request;
+use crate::{
+    data::{ProviderData, SimulatedBlock},
+    requests::validation::validate_call_request,
+    ProviderError,
+};
+
+pub fn handle_simulate_v1_request<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    payload: SimulatePayload,
+    block_spec: Option<BlockSpec>,
+) -> Result<Vec<SimulatedBlockResult>, ProviderError<LoggerErrorT>> {
+    let SimulatePayload {
+        block_state_calls,
+        trace_transfers,
+        validation,
+    } = payload;
+
+    if validation {
+        return Err(ProviderError::InvalidArgument(
+            "eth_simulateV1 doesn't support validation mode".into(),
+        ));
+    }
+
+    let block_spec = block_spec.unwrap_or_else(BlockSpec::latest);
+
+    let blocks = block_state_calls
+        .into_iter()
+        .map(|block| {
+            let BlockStateCalls {
+                block_overrides,
+                state_overrides,
+                calls,
+            } = block;
+
+            let state_overrides =
+                state_overrides.map_or(Ok(StateOverrides::default()), StateOverrides::try_from)?;
+
+            let transactions = calls
+                .into_iter()
+                .map(|request| {
+                    validate_call_request(data.spec_id(), &request, &Some(block_spec.clone()))?;
+
+                    resolve_call_request(data, request, Some(&block_spec), &state_overrides)
+                })
+                .collect::<Result<_, _>>()?;
+
+            Ok(SimulatedBlock {
+                block_overrides: block_overrides.unwrap_or_default(),
+                state_overrides,
+                transactions,
+            })
+        })
+        .collect::<Result<_, ProviderError<LoggerErrorT>>>()?;
+
+    data.simulate(&block_spec, blocks, trace_transfers)
+}
+
+/// The input of `eth_simulateV1`.
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatePayload {
+    /// The blocks to simulate, in order
+    pub block_state_calls: Vec<BlockStateCalls>,
+    /// Whether to add an ERC-20 style `Transfer` log for every ether transfer
+    #[serde(default)]
+    pub trace_transfers: bool,
+    /// Whether to validate the calls like transactions. Not supported.
+    #[serde(default)]
+    pub validation: bool,
+}
+
+/// A block of calls to simulate with `eth_simulateV1`.
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockStateCalls {
+    /// The overrides of the simulated block's environment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_overrides: Option<BlockOverrides>,
+    /// The state overrides that are applied before the block's calls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_overrides: Option<StateOverrideOptions>,
+    /// The calls to run sequentially
+    #[serde(default)]
+    pub calls: Vec<CallRequest>,
+}
+
+/// A block that was simulated by `eth_simulateV1`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlockResult {
+    /// The number of the block
+    pub number: U64,
+    /// The timestamp of the block
+    pub timestamp: U64,
+    /// The gas limit of the block
+    pub gas_limit: U64,
+    /// The total gas used by the block's calls
+    pub gas_used: U64,
+    /// The beneficiary of the block
+    pub fee_recipient: Address,
+    /// The base fee of the block
+    pub base_fee_per_gas: U256,
+    /// The prevrandao of the block, if the merge is active
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_randao: Option<B256>,
+    /// The results of the block's calls
+    pub calls: Vec<SimulatedCallResult>,
+}
+
+/// The result of a call in a simulated block.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedCallResult {
+    /// The output of the call
+    pub return_data: Bytes,
+    /// The logs emitted by the call
+    pub logs: Vec<SimulatedLog>,
+    /// The gas used by the call
+    pub gas_used: U64,
+    /// `1` if the call succeeded, `0` otherwise
+    pub status: U64,
+    /// The reason the call failed, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<SimulatedCallError>,
+}
+
+/// The error of a failed call in a simulated block.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct SimulatedCallError {
+    /// The JSON-RPC error code
+    pub code: i64,
+    /// The error message
+    pub message: String,
+    /// The revert data, if the call reverted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Bytes>,
+}
+
+/// A log emitted by a call in a simulated block.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedLog {
+    /// The address that emitted the log
+    pub address: Address,
+    /// The topics of the log
+    pub topics: Vec<B256>,
+    /// The data of the log
+    pub data: Bytes,
+    /// The number of the simulated block
+    pub block_number: U64,
+    /// The hash of the call's transaction
+    pub transaction_hash: B256,
+    /// The index of the call in the block
+    pub transaction_index: U64,
+    /// The index of the log in the block
+    pub log_index: U64,
+}
+
+#[cfg(test)]
+mod tests {
+    use edr_eth::remote::AccountOverrideOptions;
+    use edr_evm::{TRANSFER_EVENT_TOPIC, TRANSFER_LOG_ADDRESS};
+
+    use super::*;
+    use crate::data::test_utils::ProviderTestFixture;
+
+    #[test]
+    fn simulate_shares_state_between_blocks() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let sender = fixture.nth_local_account(0)?;
+        let recipient = Address::repeat_byte(0x42);
+        let contract = Address::repeat_byte(0x43);
+
+        // BALANCE(recipient); MSTORE(0); RETURN(0, 32)
+        let mut code = vec![0x73];
+        code.extend_from_slice(recipient.as_slice());
+        code.extend_from_slice(&[0x31, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3]);
+
+        let payload = SimulatePayload {
+            block_state_calls: vec![
+                BlockStateCalls {
+                    calls: vec![CallRequest {
+                        from: Some(sender),
+                        to: Some(recipient),
+                        value: Some(U256::from(1_000)),
+                        ..CallRequest::default()
+                    }],
+                    ..BlockStateCalls::default()
+                },
+                BlockStateCalls {
+                    state_overrides: Some(
+                        [(
+                            contract,
+                            AccountOverrideOptions {
+                                balance: None,
+                                nonce: None,
+                                code: Some(code.into()),
+                                storage: None,
+                                storage_diff: None,
+                            },
+                        )]
+                        .into_iter()
+                        .collect(),
+                    ),
+                    calls: vec![CallRequest {
+                        from: Some(sender),
+                        to: Some(contract),
+                        ..CallRequest::default()
+                    }],
+                    ..BlockStateCalls::default()
+                },
+            ],
+            trace_transfers: true,
+            validation: false,
+        };
+
+        let latest_block_number = fixture.provider_data.last_block_number();
+        let blocks = handle_simulate_v1_request(&mut fixture.provider_data, payload, None)?;
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].number, U64::from(latest_block_number + 1));
+        assert_eq!(blocks[1].number, U64::from(latest_block_number + 2));
+        assert_eq!(
+            blocks[1].timestamp.to::<u64>() - blocks[0].timestamp.to::<u64>(),
+            12
+        );
+
+        let transfer = &blocks[0].calls[0];
+        assert_eq!(transfer.status, U64::from(1));
+        assert_eq!(transfer.logs.len(), 1);
+        assert_eq!(transfer.logs[0].address, TRANSFER_LOG_ADDRESS);
+        assert_eq!(transfer.logs[0].topics[0], TRANSFER_EVENT_TOPIC);
+
+        let balance = &blocks[1].calls[0];
+        assert_eq!(balance.status, U64::from(1));
+        assert_eq!(U256::from_be_slice(&balance.return_data), U256::from(1_000));
+
+        // The simulation doesn't modify the blockchain.
+        assert_eq!(
+            fixture.provider_data.last_block_number(),
+            latest_block_number
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn simulate_rejects_validation() -> anyhow::Result<()> {
+        let mut fixture = ProviderTestFixture::new_local()?;
+
+        let payload = SimulatePayload {
+            validation: true,
+            ..SimulatePayload::default()
+        };
+
+        let result = handle_simulate_v1_request(&mut fixture.provider_data, payload, None);
+        assert!(matches!(result, Err(ProviderError::InvalidArgument(_))));
+
+        Ok(())
+    }
+}
@@ -1,6 +1,10 @@
 use core::fmt::Debug;
 
-use edr_eth::{remote::BlockSpec, utils::u256_to_padded_hex, Address, Bytes, U256};
+use edr_eth::{
+    remote::{eth::AccountProof, BlockSpec},
+    utils::u256_to_padded_hex,
+    Address, Bytes, U256,
+};
 
 use crate::{
     data::ProviderData, requests::validation::validate_post_merge_block_tags, ProviderError,
@@ -30,6 +34,19 @@ pub fn handle_get_code_request<LoggerErrorT: Debug>(
     data.get_code(address, block_spec.as_ref())
 }
 
+pub fn handle_get_proof_request<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    address: Address,
+    storage_keys: Vec<U256>,
+    block_spec: Option<BlockSpec>,
+) -> Result<AccountProof, ProviderError<LoggerErrorT>> {
+    if let Some(block_spec) = block_spec.as_ref() {
+        validate_post_merge_block_tags(data.spec_id(), block_spec)?;
+    }
+
+    data.get_proof(address, storage_keys, block_spec.as_ref())
+}
+
 pub fn handle_get_storage_at_request<LoggerErrorT: Debug>(
     data: &mut ProviderData<LoggerErrorT>,
     address: Address,
@@ -6,9 +6,9 @@ use edr_eth::{
     remote::{self, PreEip1898BlockSpec},
     rlp::Decodable,
     transaction::{
-        Eip1559TransactionRequest, Eip155TransactionRequest, Eip2930TransactionRequest,
-        EthTransactionRequest, SignedTransaction, TransactionKind, TransactionRequest,
-        TransactionRequestAndSender,
+        BlobSidecar, Eip1559TransactionRequest, Eip155TransactionRequest,
+        Eip2930TransactionRequest, EthTransactionRequest, PooledTransaction, SignedTransaction,
+        TransactionKind, TransactionRequest, TransactionRequestAndSender, DEPOSIT_TRANSACTION_TYPE,
     },
     Bytes, SpecId, B256, U256,
 };
@@ -184,6 +184,7 @@ pub fn transaction_to_rpc_result<LoggerErrorT: Debug>(
         SignedTransaction::Eip1559(_) | SignedTransaction::Eip4844(_) => {
             gas_price_for_post_eip1559(&signed_transaction, block)
         }
+        SignedTransaction::Deposit(_) => U256::ZERO,
     };
 
     let chain_id = match &signed_transaction {
@@ -193,6 +194,7 @@ pub fn transaction_to_rpc_result<LoggerErrorT: Debug>(
         SignedTransaction::Eip2930(tx) => Some(tx.chain_id),
         SignedTransaction::Eip1559(tx) => Some(tx.chain_id),
         SignedTransaction::Eip4844(tx) => Some(tx.chain_id),
+        SignedTransaction::Deposit(_) => None,
     };
 
     let show_transaction_type = spec_id >= FIRST_HARDFORK_WITH_TRANSACTION_TYPE;
@@ -244,6 +246,13 @@ pub fn transaction_to_rpc_result<LoggerErrorT: Debug>(
         max_priority_fee_per_gas: signed_transaction.max_priority_fee_per_gas(),
         max_fee_per_blob_gas: signed_transaction.max_fee_per_blob_gas(),
         blob_versioned_hashes: signed_transaction.blob_hashes(),
+        source_hash: signed_transaction
+            .as_deposit()
+            .map(|deposit| deposit.source_hash),
+        mint: signed_transaction.as_deposit().map(|deposit| deposit.mint),
+        is_system_tx: signed_transaction
+            .as_deposit()
+            .map(|deposit| deposit.is_system_tx),
     })
 }
 
@@ -263,9 +272,32 @@ pub fn handle_send_raw_transaction_request<LoggerErrorT: Debug>(
     data: &mut ProviderData<LoggerErrorT>,
     raw_transaction: Bytes,
 ) -> Result<(B256, Vec<Trace>), ProviderError<LoggerErrorT>> {
+    let (pending_transaction, blob_sidecar) = decode_raw_transaction(data, raw_transaction)?;
+
+    let transaction_hash = *pending_transaction.hash();
+    let is_new_blob_sidecar = blob_sidecar.map_or(false, |blob_sidecar| {
+        data.add_blob_sidecar(transaction_hash, blob_sidecar)
+    });
+
+    let result = send_raw_transaction_and_log(data, pending_transaction);
+
+    // Don't retain the sidecar of a rejected transaction
+    if result.is_err() && is_new_blob_sidecar {
+        data.remove_blob_sidecar(&transaction_hash);
+    }
+
+    result
+}
+
+/// Decodes and validates a signed raw transaction. The blob sidecar of an
+/// EIP-4844 transaction is verified and returned, but not stored.
+pub(crate) fn decode_raw_transaction<LoggerErrorT: Debug>(
+    data: &ProviderData<LoggerErrorT>,
+    raw_transaction: Bytes,
+) -> Result<(ExecutableTransaction, Option<BlobSidecar>), ProviderError<LoggerErrorT>> {
     let mut raw_transaction: &[u8] = raw_transaction.as_ref();
-    let signed_transaction =
-        SignedTransaction::decode(&mut raw_transaction).map_err(|err| match err {
+    let pooled_transaction =
+        PooledTransaction::decode(&mut raw_transaction).map_err(|err| match err {
             edr_eth::rlp::Error::Custom(message) if SignedTransaction::is_invalid_transaction_type_error(message) => {
                 let type_id = *raw_transaction.first().expect("We already validated that the transaction is not empty if it's an invalid transaction type error.");
                 ProviderError::InvalidTransactionType(type_id)
@@ -273,11 +305,36 @@ pub fn handle_send_raw_transaction_request<LoggerErrorT: Debug>(
             err => ProviderError::InvalidArgument(err.to_string()),
         })?;
 
+    let (signed_transaction, blob_sidecar) = pooled_transaction.into_parts();
+
+    // Deposit transactions can only be derived from L1
+    if signed_transaction.is_deposit() {
+        return Err(ProviderError::InvalidTransactionType(
+            DEPOSIT_TRANSACTION_TYPE,
+        ));
+    }
+
     validate_send_raw_transaction_request(data, &signed_transaction)?;
 
+    // The blobs are required to verify the transaction's blob hashes, so
+    // blob transactions have to be sent in their network form.
+    let blob_sidecar = if let Some(blob_hashes) = signed_transaction.blob_hashes() {
+        let blob_sidecar = blob_sidecar.ok_or_else(|| {
+            ProviderError::InvalidArgument(
+                "EIP-4844 transactions must be sent in their network form, including the blobs, commitments and proofs.".to_string(),
+            )
+        })?;
+
+        blob_sidecar.verify(&blob_hashes)?;
+
+        Some(blob_sidecar)
+    } else {
+        None
+    };
+
     let pending_transaction = ExecutableTransaction::new(data.spec_id(), signed_transaction)?;
 
-    send_raw_transaction_and_log(data, pending_transaction)
+    Ok((pending_transaction, blob_sidecar))
 }
 
 fn resolve_transaction_request<LoggerErrorT: Debug>(
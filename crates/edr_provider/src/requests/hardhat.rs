@@ -1,9 +1,18 @@
 mod accounts;
+mod blobs;
+mod compiler;
 mod config;
+mod coverage;
+mod gas_profile;
+mod gas_report;
 mod log;
 mod miner;
 pub mod rpc_types;
 mod state;
 mod transactions;
+mod withdrawals;
 
-pub use self::{accounts::*, config::*, log::*, miner::*, state::*, transactions::*};
+pub use self::{
+    accounts::*, blobs::*, compiler::*, config::*, coverage::*, gas_profile::*, gas_report::*,
+    log::*, miner::*, state::*, transactions::*, withdrawals::*,
+};
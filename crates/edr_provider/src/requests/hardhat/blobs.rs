@@ -0,0 +1,39 @@
+use core::fmt::Debug;
+
+use edr_eth::{remote::BlockSpec, transaction::BlobSidecar};
+
+use crate::{data::ProviderData, requests::hardhat::rpc_types::BlobSidecarEntry, ProviderError};
+
+pub fn handle_get_blob_sidecars<LoggerErrorT: Debug>(
+    data: &ProviderData<LoggerErrorT>,
+    block_spec: BlockSpec,
+) -> Result<Vec<BlobSidecarEntry>, ProviderError<LoggerErrorT>> {
+    let sidecars = data.blob_sidecars_by_block_spec(&block_spec)?;
+
+    Ok(sidecars
+        .into_iter()
+        .flat_map(|(transaction_hash, sidecar)| {
+            let BlobSidecar {
+                blobs,
+                commitments,
+                proofs,
+            } = sidecar;
+
+            blobs
+                .into_iter()
+                .zip(commitments)
+                .zip(proofs)
+                .enumerate()
+                .map(
+                    move |(index, ((blob, kzg_commitment), kzg_proof))| BlobSidecarEntry {
+                        index: index as u64,
+                        blob,
+                        versioned_hash: BlobSidecar::versioned_hash(&kzg_commitment),
+                        kzg_commitment,
+                        kzg_proof,
+                        transaction_hash,
+                    },
+                )
+        })
+        .collect())
+}
@@ -0,0 +1,25 @@
+use core::fmt::Debug;
+
+use crate::{
+    data::ProviderData,
+    requests::hardhat::rpc_types::{CompilerInput, CompilerOutput},
+    ProviderError,
+};
+
+pub fn handle_add_compilation_result<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    solc_version: String,
+    compiler_input: CompilerInput,
+    compiler_output: CompilerOutput,
+) -> Result<bool, ProviderError<LoggerErrorT>> {
+    if let Err(error) = data.add_compilation_result(solc_version, &compiler_input, &compiler_output)
+    {
+        log::warn!(
+            "The Hardhat Network tracing engine could not be initialized. Run Hardhat with --verbose to learn more. Error: {error}"
+        );
+
+        return Ok(false);
+    }
+
+    Ok(true)
+}
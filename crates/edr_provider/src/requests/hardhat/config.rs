@@ -1,6 +1,6 @@
 use core::fmt::Debug;
 
-use edr_eth::{Address, B256, U256};
+use edr_eth::{Address, B256, U256, U64};
 
 use crate::{
     data::ProviderData,
@@ -36,6 +36,15 @@ pub fn handle_set_coinbase_request<LoggerErrorT: Debug>(
     Ok(true)
 }
 
+pub fn handle_set_finalized_block<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    block_number: U64,
+) -> Result<bool, ProviderError<LoggerErrorT>> {
+    data.set_finalized_block(block_number.as_limbs()[0])?;
+
+    Ok(true)
+}
+
 pub fn handle_set_min_gas_price<LoggerErrorT: Debug>(
     data: &mut ProviderData<LoggerErrorT>,
     min_gas_price: U256,
@@ -54,6 +63,15 @@ pub fn handle_set_next_block_base_fee_per_gas_request<LoggerErrorT: Debug>(
     Ok(true)
 }
 
+pub fn handle_set_parent_beacon_block_root<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    parent_beacon_block_root: B256,
+) -> Result<bool, ProviderError<LoggerErrorT>> {
+    data.set_next_parent_beacon_block_root(parent_beacon_block_root)?;
+
+    Ok(true)
+}
+
 pub fn handle_set_prev_randao_request<LoggerErrorT: Debug>(
     data: &mut ProviderData<LoggerErrorT>,
     prev_randao: B256,
@@ -62,3 +80,12 @@ pub fn handle_set_prev_randao_request<LoggerErrorT: Debug>(
 
     Ok(true)
 }
+
+pub fn handle_set_safe_block<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    block_number: U64,
+) -> Result<bool, ProviderError<LoggerErrorT>> {
+    data.set_safe_block(block_number.as_limbs()[0])?;
+
+    Ok(true)
+}
@@ -0,0 +1,21 @@
+use core::fmt::Debug;
+
+use crate::{
+    data::ProviderData,
+    requests::hardhat::rpc_types::{CoverageFormat, CoverageOutput},
+    ProviderError,
+};
+
+pub fn handle_get_coverage<LoggerErrorT: Debug>(
+    data: &ProviderData<LoggerErrorT>,
+    format: Option<CoverageFormat>,
+) -> Result<CoverageOutput, ProviderError<LoggerErrorT>> {
+    let report = data
+        .coverage_report()
+        .ok_or(ProviderError::CoverageDisabled)?;
+
+    Ok(match format.unwrap_or_default() {
+        CoverageFormat::Json => CoverageOutput::Json(report),
+        CoverageFormat::Lcov => CoverageOutput::Lcov(report.to_lcov()),
+    })
+}
@@ -0,0 +1,18 @@
+use core::fmt::Debug;
+
+use edr_eth::B256;
+
+use crate::{data::ProviderData, requests::hardhat::rpc_types::GasProfileResult, ProviderError};
+
+pub fn handle_get_gas_profile<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    transaction_hash: B256,
+) -> Result<GasProfileResult, ProviderError<LoggerErrorT>> {
+    let profile = data.gas_profile_transaction(&transaction_hash)?;
+
+    Ok(GasProfileResult {
+        folded_stacks: profile.to_folded_stacks(),
+        speedscope: profile.to_speedscope(&format!("Transaction {transaction_hash}")),
+        profile,
+    })
+}
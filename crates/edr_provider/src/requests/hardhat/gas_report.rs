@@ -0,0 +1,19 @@
+use core::fmt::Debug;
+
+use edr_solidity::gas_report::GasReport;
+
+use crate::{data::ProviderData, ProviderError};
+
+pub fn handle_get_gas_report<LoggerErrorT: Debug>(
+    data: &ProviderData<LoggerErrorT>,
+) -> Result<GasReport, ProviderError<LoggerErrorT>> {
+    Ok(data.gas_report())
+}
+
+pub fn handle_reset_gas_report<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+) -> Result<bool, ProviderError<LoggerErrorT>> {
+    data.reset_gas_report();
+
+    Ok(true)
+}
@@ -1,8 +1,9 @@
 use core::fmt::Debug;
 
-use edr_evm::trace::Trace;
+use edr_eth::Bytes;
+use edr_evm::{trace::Trace, ExecutableTransaction};
 
-use crate::{data::ProviderData, ProviderError};
+use crate::{data::ProviderData, requests::eth::decode_raw_transaction, ProviderError};
 
 pub fn handle_interval_mine_request<LoggerErrorT: Debug>(
     data: &mut ProviderData<LoggerErrorT>,
@@ -32,3 +33,65 @@ pub fn handle_mine<LoggerErrorT: Debug>(
 
     Ok((true, traces))
 }
+
+pub fn handle_reorg<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    depth: u64,
+    new_blocks: Option<Vec<Vec<Bytes>>>,
+) -> Result<(bool, Vec<Trace>), ProviderError<LoggerErrorT>> {
+    // Blob sidecars are only stored once the reorg has succeeded
+    let mut blob_sidecars = Vec::new();
+    let new_blocks = new_blocks
+        .map(|new_blocks| {
+            new_blocks
+                .into_iter()
+                .map(|raw_transactions| {
+                    raw_transactions
+                        .into_iter()
+                        .map(|raw_transaction| {
+                            let (transaction, blob_sidecar) =
+                                decode_raw_transaction(data, raw_transaction)?;
+
+                            if let Some(blob_sidecar) = blob_sidecar {
+                                blob_sidecars.push((*transaction.hash(), blob_sidecar));
+                            }
+
+                            Ok::<_, ProviderError<LoggerErrorT>>(transaction)
+                        })
+                        .collect::<Result<Vec<ExecutableTransaction>, _>>()
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?;
+
+    let mined_block_results = data.reorg(depth, new_blocks)?;
+
+    for (transaction_hash, blob_sidecar) in blob_sidecars {
+        let block_number = mined_block_results.iter().find_map(|result| {
+            result
+                .block
+                .transactions()
+                .iter()
+                .any(|transaction| *transaction.hash() == transaction_hash)
+                .then(|| result.block.header().number)
+        });
+
+        if let Some(block_number) = block_number {
+            data.add_mined_blob_sidecar(block_number, transaction_hash, blob_sidecar);
+        } else {
+            data.add_blob_sidecar(transaction_hash, blob_sidecar);
+        }
+    }
+
+    let spec_id = data.spec_id();
+    data.logger_mut()
+        .log_mined_block(spec_id, &mined_block_results)
+        .map_err(ProviderError::Logger)?;
+
+    let traces = mined_block_results
+        .into_iter()
+        .flat_map(|result| result.transaction_traces)
+        .collect();
+
+    Ok((true, traces))
+}
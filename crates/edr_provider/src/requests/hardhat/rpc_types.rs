@@ -1,7 +1,15 @@
+mod blob_sidecar;
 mod compiler;
 mod config;
+mod coverage;
+mod gas_profile;
 mod metadata;
+mod withdrawal;
 
+pub use blob_sidecar::BlobSidecarEntry;
 pub use compiler::{CompilerInput, CompilerInputSource, CompilerOutput, CompilerOutputContract};
 pub use config::{ForkConfig, ResetProviderConfig};
+pub use coverage::{CoverageFormat, CoverageOutput};
+pub use gas_profile::GasProfileResult;
 pub use metadata::{ForkMetadata, Metadata};
+pub use withdrawal::WithdrawalRequest;
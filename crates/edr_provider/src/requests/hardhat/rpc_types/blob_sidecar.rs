@@ -0,0 +1,21 @@
+use edr_eth::{Bytes, FixedBytes, B256};
+
+/// A blob of an EIP-4844 transaction, as returned by
+/// `hardhat_getBlobSidecars`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobSidecarEntry {
+    /// The index of the blob in the transaction
+    #[serde(with = "edr_eth::serde::u64")]
+    pub index: u64,
+    /// The blob
+    pub blob: Bytes,
+    /// The KZG commitment of the blob
+    pub kzg_commitment: FixedBytes<48>,
+    /// The KZG proof of the blob
+    pub kzg_proof: FixedBytes<48>,
+    /// The versioned hash of the KZG commitment
+    pub versioned_hash: B256,
+    /// The hash of the transaction that contains the blob
+    pub transaction_hash: B256,
+}
@@ -0,0 +1,22 @@
+use edr_solidity::coverage::CoverageReport;
+
+/// The format of the report returned by `hardhat_getCoverage`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CoverageFormat {
+    /// A JSON object with the coverage of each source file
+    #[default]
+    Json,
+    /// An LCOV tracefile
+    Lcov,
+}
+
+/// A coverage report in the requested [`CoverageFormat`].
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(untagged)]
+pub enum CoverageOutput {
+    /// A JSON report
+    Json(CoverageReport),
+    /// An LCOV tracefile
+    Lcov(String),
+}
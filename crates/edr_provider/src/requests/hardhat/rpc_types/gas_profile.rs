@@ -0,0 +1,14 @@
+use edr_evm::GasProfile;
+
+/// The result of `hardhat_getGasProfile`.
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasProfileResult {
+    /// The tree of call frames
+    #[serde(flatten)]
+    pub profile: GasProfile,
+    /// The profile as folded stacks, for `flamegraph.pl`
+    pub folded_stacks: String,
+    /// The profile in speedscope's file format
+    pub speedscope: serde_json::Value,
+}
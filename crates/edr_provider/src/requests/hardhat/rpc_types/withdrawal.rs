@@ -0,0 +1,15 @@
+use edr_eth::{Address, U256};
+
+/// A withdrawal to be included in a mined block, as accepted by
+/// `hardhat_addWithdrawals`. Its index is assigned when the block is mined.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalRequest {
+    /// The index of the validator that generated the withdrawal
+    #[serde(with = "edr_eth::serde::u64")]
+    pub validator_index: u64,
+    /// The recipient of the withdrawal
+    pub address: Address,
+    /// The amount of the withdrawal, in Gwei
+    pub amount: U256,
+}
@@ -1,8 +1,8 @@
 use core::fmt::Debug;
 
-use edr_eth::{Address, Bytes, U256};
+use edr_eth::{genesis::Genesis, Address, Bytes, U256};
 
-use crate::{data::ProviderData, ProviderError};
+use crate::{data::ProviderData, state_dump::StateDump, ProviderError};
 
 pub fn handle_set_balance<LoggerErrorT: Debug>(
     data: &mut ProviderData<LoggerErrorT>,
@@ -44,3 +44,24 @@ pub fn handle_set_storage_at<LoggerErrorT: Debug>(
 
     Ok(true)
 }
+
+pub fn handle_dump_genesis<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+) -> Result<Genesis, ProviderError<LoggerErrorT>> {
+    data.dump_genesis()
+}
+
+pub fn handle_dump_state<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+) -> Result<StateDump, ProviderError<LoggerErrorT>> {
+    data.dump_state()
+}
+
+pub fn handle_load_state<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    dump: StateDump,
+) -> Result<bool, ProviderError<LoggerErrorT>> {
+    data.load_state(dump)?;
+
+    Ok(true)
+}
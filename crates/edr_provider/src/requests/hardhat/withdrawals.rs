@@ -0,0 +1,12 @@
+use core::fmt::Debug;
+
+use crate::{data::ProviderData, requests::hardhat::rpc_types::WithdrawalRequest, ProviderError};
+
+pub fn handle_add_withdrawals<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    withdrawals: Vec<WithdrawalRequest>,
+) -> Result<bool, ProviderError<LoggerErrorT>> {
+    data.add_withdrawals(withdrawals)?;
+
+    Ok(true)
+}
@@ -2,7 +2,7 @@ use edr_eth::{
     remote::{
         eth::CallRequest,
         filter::{LogFilterOptions, SubscriptionType},
-        BlockSpec, PreEip1898BlockSpec, StateOverrideOptions,
+        BlockOverrides, BlockSpec, PreEip1898BlockSpec, StateOverrideOptions,
     },
     serde::{optional_single_to_sequence, sequence_to_optional_single},
     transaction::EthTransactionRequest,
@@ -11,9 +11,16 @@ use edr_eth::{
 use ethers_core::types::transaction::eip712::TypedData;
 
 use super::serde::RpcAddress;
-use crate::requests::{
-    debug::DebugTraceConfig,
-    hardhat::rpc_types::{CompilerInput, CompilerOutput, ResetProviderConfig},
+use crate::{
+    requests::{
+        debug::DebugTraceConfig,
+        eth::SimulatePayload,
+        hardhat::rpc_types::{
+            CompilerInput, CompilerOutput, CoverageFormat, ResetProviderConfig, WithdrawalRequest,
+        },
+        trace::{TraceFilterRequest, TraceType},
+    },
+    state_dump::StateDump,
 };
 
 mod optional_block_spec {
@@ -35,6 +42,9 @@ pub enum MethodInvocation {
     /// eth_accounts
     #[serde(rename = "eth_accounts", with = "edr_eth::serde::empty_params")]
     Accounts(()),
+    /// eth_blobBaseFee
+    #[serde(rename = "eth_blobBaseFee", with = "edr_eth::serde::empty_params")]
+    BlobBaseFee(()),
     /// eth_blockNumber
     #[serde(rename = "eth_blockNumber", with = "edr_eth::serde::empty_params")]
     BlockNumber(()),
@@ -48,6 +58,7 @@ pub enum MethodInvocation {
         )]
         Option<BlockSpec>,
         #[serde(default, skip_serializing_if = "Option::is_none")] Option<StateOverrideOptions>,
+        #[serde(default, skip_serializing_if = "Option::is_none")] Option<BlockOverrides>,
     ),
     /// eth_chainId
     #[serde(rename = "eth_chainId", with = "edr_eth::serde::empty_params")]
@@ -55,6 +66,16 @@ pub enum MethodInvocation {
     /// eth_coinbase
     #[serde(rename = "eth_coinbase", with = "edr_eth::serde::empty_params")]
     Coinbase(()),
+    /// eth_createAccessList
+    #[serde(rename = "eth_createAccessList")]
+    CreateAccessList(
+        CallRequest,
+        #[serde(
+            skip_serializing_if = "Option::is_none",
+            default = "optional_block_spec::pending"
+        )]
+        Option<BlockSpec>,
+    ),
     /// eth_estimateGas
     #[serde(rename = "eth_estimateGas")]
     EstimateGas(
@@ -64,6 +85,8 @@ pub enum MethodInvocation {
             default = "optional_block_spec::pending"
         )]
         Option<BlockSpec>,
+        #[serde(default, skip_serializing_if = "Option::is_none")] Option<StateOverrideOptions>,
+        #[serde(default, skip_serializing_if = "Option::is_none")] Option<BlockOverrides>,
     ),
     /// eth_feeHistory
     #[serde(rename = "eth_feeHistory")]
@@ -135,6 +158,17 @@ pub enum MethodInvocation {
     /// eth_getLogs
     #[serde(rename = "eth_getLogs", with = "edr_eth::serde::sequence")]
     GetLogs(LogFilterOptions),
+    /// eth_getProof
+    #[serde(rename = "eth_getProof")]
+    GetProof(
+        #[serde(deserialize_with = "crate::requests::serde::deserialize_address")] Address,
+        Vec<U256>,
+        #[serde(
+            skip_serializing_if = "Option::is_none",
+            default = "optional_block_spec::latest"
+        )]
+        Option<BlockSpec>,
+    ),
     /// eth_getStorageAt
     #[serde(rename = "eth_getStorageAt")]
     GetStorageAt(
@@ -221,6 +255,16 @@ pub enum MethodInvocation {
         #[serde(deserialize_with = "crate::requests::serde::deserialize_address")] Address,
         #[serde(deserialize_with = "crate::requests::serde::deserialize_typed_data")] TypedData,
     ),
+    /// eth_simulateV1
+    #[serde(rename = "eth_simulateV1")]
+    SimulateV1(
+        SimulatePayload,
+        #[serde(
+            skip_serializing_if = "Option::is_none",
+            default = "optional_block_spec::latest"
+        )]
+        Option<BlockSpec>,
+    ),
     /// eth_subscribe
     #[serde(rename = "eth_subscribe")]
     Subscribe(
@@ -274,17 +318,43 @@ pub enum MethodInvocation {
     #[serde(rename = "evm_snapshot", with = "edr_eth::serde::empty_params")]
     EvmSnapshot(()),
 
+    /// debug_traceBlock
+    #[serde(rename = "debug_traceBlock")]
+    DebugTraceBlock(Bytes, #[serde(default)] Option<DebugTraceConfig>),
+    /// debug_traceBlockByHash
+    #[serde(rename = "debug_traceBlockByHash")]
+    DebugTraceBlockByHash(B256, #[serde(default)] Option<DebugTraceConfig>),
+    /// debug_traceBlockByNumber
+    #[serde(rename = "debug_traceBlockByNumber")]
+    DebugTraceBlockByNumber(
+        PreEip1898BlockSpec,
+        #[serde(default)] Option<DebugTraceConfig>,
+    ),
     // debug_traceTransaction
     #[serde(rename = "debug_traceCall")]
     DebugTraceCall(
         CallRequest,
         #[serde(default)] Option<BlockSpec>,
         #[serde(default)] Option<DebugTraceConfig>,
+        #[serde(default, skip_serializing_if = "Option::is_none")] Option<BlockOverrides>,
     ),
     // debug_traceTransaction
     #[serde(rename = "debug_traceTransaction")]
     DebugTraceTransaction(B256, #[serde(default)] Option<DebugTraceConfig>),
 
+    /// trace_block
+    #[serde(rename = "trace_block", with = "edr_eth::serde::sequence")]
+    TraceBlock(PreEip1898BlockSpec),
+    /// trace_filter
+    #[serde(rename = "trace_filter", with = "edr_eth::serde::sequence")]
+    TraceFilter(TraceFilterRequest),
+    /// trace_replayTransaction
+    #[serde(rename = "trace_replayTransaction")]
+    TraceReplayTransaction(B256, Vec<TraceType>),
+    /// trace_transaction
+    #[serde(rename = "trace_transaction", with = "edr_eth::serde::sequence")]
+    TraceTransaction(B256),
+
     /// hardhat_addCompilationResult
     #[serde(rename = "hardhat_addCompilationResult")]
     AddCompilationResult(
@@ -293,12 +363,37 @@ pub enum MethodInvocation {
         CompilerInput,
         CompilerOutput,
     ),
+    /// hardhat_addWithdrawals
+    #[serde(rename = "hardhat_addWithdrawals", with = "edr_eth::serde::sequence")]
+    AddWithdrawals(Vec<WithdrawalRequest>),
     /// hardhat_dropTransaction
     #[serde(rename = "hardhat_dropTransaction", with = "edr_eth::serde::sequence")]
     DropTransaction(B256),
+    /// hardhat_dumpGenesis
+    #[serde(rename = "hardhat_dumpGenesis", with = "edr_eth::serde::empty_params")]
+    DumpGenesis(()),
+    /// hardhat_dumpState
+    #[serde(rename = "hardhat_dumpState", with = "edr_eth::serde::empty_params")]
+    DumpState(()),
     /// hardhat_getAutomine
     #[serde(rename = "hardhat_getAutomine", with = "edr_eth::serde::empty_params")]
     GetAutomine(()),
+    /// hardhat_getBlobSidecars
+    #[serde(rename = "hardhat_getBlobSidecars", with = "edr_eth::serde::sequence")]
+    GetBlobSidecars(BlockSpec),
+    /// hardhat_getCoverage
+    #[serde(
+        rename = "hardhat_getCoverage",
+        serialize_with = "optional_single_to_sequence",
+        deserialize_with = "sequence_to_optional_single"
+    )]
+    GetCoverage(Option<CoverageFormat>),
+    /// hardhat_getGasProfile
+    #[serde(rename = "hardhat_getGasProfile", with = "edr_eth::serde::sequence")]
+    GetGasProfile(B256),
+    /// hardhat_getGasReport
+    #[serde(rename = "hardhat_getGasReport", with = "edr_eth::serde::empty_params")]
+    GetGasReport(()),
     /// hardhat_getStackTraceFailuresCount
     #[serde(
         rename = "hardhat_getStackTraceFailuresCount",
@@ -314,6 +409,9 @@ pub enum MethodInvocation {
     /// hardhat_intervalMine
     #[serde(rename = "hardhat_intervalMine", with = "edr_eth::serde::empty_params")]
     IntervalMine(()),
+    /// hardhat_loadState
+    #[serde(rename = "hardhat_loadState", with = "edr_eth::serde::sequence")]
+    LoadState(Box<StateDump>),
     /// hardhat_metadata
     #[serde(rename = "hardhat_metadata", with = "edr_eth::serde::empty_params")]
     Metadata(()),
@@ -331,6 +429,16 @@ pub enum MethodInvocation {
         )]
         Option<u64>,
     ),
+    /// hardhat_reorg
+    #[serde(rename = "hardhat_reorg")]
+    Reorg(
+        /// depth:
+        #[serde(with = "edr_eth::serde::u64")]
+        u64,
+        /// raw transactions of the new blocks:
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        Option<Vec<Vec<Bytes>>>,
+    ),
     /// hardhat_reset
     #[serde(
         rename = "hardhat_reset",
@@ -338,6 +446,12 @@ pub enum MethodInvocation {
         deserialize_with = "sequence_to_optional_single"
     )]
     Reset(Option<ResetProviderConfig>),
+    /// hardhat_resetGasReport
+    #[serde(
+        rename = "hardhat_resetGasReport",
+        with = "edr_eth::serde::empty_params"
+    )]
+    ResetGasReport(()),
     /// hardhat_setBalance
     #[serde(rename = "hardhat_setBalance")]
     SetBalance(
@@ -353,6 +467,12 @@ pub enum MethodInvocation {
     /// hardhat_setCoinbase
     #[serde(rename = "hardhat_setCoinbase", with = "edr_eth::serde::sequence")]
     SetCoinbase(#[serde(deserialize_with = "crate::requests::serde::deserialize_address")] Address),
+    /// hardhat_setFinalizedBlock
+    #[serde(
+        rename = "hardhat_setFinalizedBlock",
+        with = "edr_eth::serde::sequence"
+    )]
+    SetFinalizedBlock(U64),
     /// hardhat_setLoggingEnabled
     #[serde(
         rename = "hardhat_setLoggingEnabled",
@@ -378,9 +498,18 @@ pub enum MethodInvocation {
         )]
         u64,
     ),
+    /// hardhat_setParentBeaconBlockRoot
+    #[serde(
+        rename = "hardhat_setParentBeaconBlockRoot",
+        with = "edr_eth::serde::sequence"
+    )]
+    SetParentBeaconBlockRoot(B256),
     /// hardhat_setPrevRandao
     #[serde(rename = "hardhat_setPrevRandao", with = "edr_eth::serde::sequence")]
     SetPrevRandao(B256),
+    /// hardhat_setSafeBlock
+    #[serde(rename = "hardhat_setSafeBlock", with = "edr_eth::serde::sequence")]
+    SetSafeBlock(U64),
     /// hardhat_setStorageAt
     #[serde(rename = "hardhat_setStorageAt")]
     SetStorageAt(
@@ -401,11 +530,13 @@ impl MethodInvocation {
     pub fn method_name(&self) -> &'static str {
         match self {
             MethodInvocation::Accounts(_) => "eth_accounts",
+            MethodInvocation::BlobBaseFee(_) => "eth_blobBaseFee",
             MethodInvocation::BlockNumber(_) => "eth_blockNumber",
-            MethodInvocation::Call(_, _, _) => "eth_call",
+            MethodInvocation::Call(_, _, _, _) => "eth_call",
             MethodInvocation::ChainId(_) => "eth_chainId",
             MethodInvocation::Coinbase(_) => "eth_coinbase",
-            MethodInvocation::EstimateGas(_, _) => "eth_estimateGas",
+            MethodInvocation::CreateAccessList(_, _) => "eth_createAccessList",
+            MethodInvocation::EstimateGas(_, _, _, _) => "eth_estimateGas",
             MethodInvocation::FeeHistory(_, _, _) => "eth_feeHistory",
             MethodInvocation::GasPrice(_) => "eth_gasPrice",
             MethodInvocation::GetBalance(_, _) => "eth_getBalance",
@@ -421,6 +552,7 @@ impl MethodInvocation {
             MethodInvocation::GetFilterChanges(_) => "eth_getFilterChanges",
             MethodInvocation::GetFilterLogs(_) => "eth_getFilterLogs",
             MethodInvocation::GetLogs(_) => "eth_getLogs",
+            MethodInvocation::GetProof(_, _, _) => "eth_getProof",
             MethodInvocation::GetStorageAt(_, _, _) => "eth_getStorageAt",
             MethodInvocation::GetTransactionByBlockHashAndIndex(_, _) => {
                 "eth_getTransactionByBlockHashAndIndex"
@@ -443,6 +575,7 @@ impl MethodInvocation {
             MethodInvocation::SendTransaction(_) => "eth_sendTransaction",
             MethodInvocation::Sign(_, _) => "eth_sign",
             MethodInvocation::SignTypedDataV4(_, _) => "eth_signTypedData_v4",
+            MethodInvocation::SimulateV1(_, _) => "eth_simulateV1",
             MethodInvocation::Subscribe(_, _) => "eth_subscribe",
             MethodInvocation::Syncing(_) => "eth_syncing",
             MethodInvocation::UninstallFilter(_) => "eth_uninstallFilter",
@@ -457,25 +590,45 @@ impl MethodInvocation {
             MethodInvocation::EvmSetIntervalMining(_) => "evm_setIntervalMining",
             MethodInvocation::EvmSetNextBlockTimestamp(_) => "evm_setNextBlockTimestamp",
             MethodInvocation::EvmSnapshot(_) => "evm_snapshot",
-            MethodInvocation::DebugTraceCall(_, _, _) => "debug_traceCall",
+            MethodInvocation::DebugTraceBlock(_, _) => "debug_traceBlock",
+            MethodInvocation::DebugTraceBlockByHash(_, _) => "debug_traceBlockByHash",
+            MethodInvocation::DebugTraceBlockByNumber(_, _) => "debug_traceBlockByNumber",
+            MethodInvocation::DebugTraceCall(_, _, _, _) => "debug_traceCall",
             MethodInvocation::DebugTraceTransaction(_, _) => "debug_traceTransaction",
+            MethodInvocation::TraceBlock(_) => "trace_block",
+            MethodInvocation::TraceFilter(_) => "trace_filter",
+            MethodInvocation::TraceReplayTransaction(_, _) => "trace_replayTransaction",
+            MethodInvocation::TraceTransaction(_) => "trace_transaction",
             MethodInvocation::AddCompilationResult(_, _, _) => "hardhat_addCompilationResult",
+            MethodInvocation::AddWithdrawals(_) => "hardhat_addWithdrawals",
             MethodInvocation::DropTransaction(_) => "hardhat_dropTransaction",
+            MethodInvocation::DumpGenesis(_) => "hardhat_dumpGenesis",
+            MethodInvocation::DumpState(_) => "hardhat_dumpState",
             MethodInvocation::GetAutomine(_) => "hardhat_getAutomine",
+            MethodInvocation::GetBlobSidecars(_) => "hardhat_getBlobSidecars",
+            MethodInvocation::GetCoverage(_) => "hardhat_getCoverage",
+            MethodInvocation::GetGasProfile(_) => "hardhat_getGasProfile",
+            MethodInvocation::GetGasReport(_) => "hardhat_getGasReport",
             MethodInvocation::GetStackTraceFailuresCount(_) => "hardhat_getStackTraceFailuresCount",
             MethodInvocation::ImpersonateAccount(_) => "hardhat_impersonateAccount",
             MethodInvocation::IntervalMine(_) => "hardhat_intervalMine",
+            MethodInvocation::LoadState(_) => "hardhat_loadState",
             MethodInvocation::Metadata(_) => "hardhat_metadata",
             MethodInvocation::Mine(_, _) => "hardhat_mine",
+            MethodInvocation::Reorg(_, _) => "hardhat_reorg",
             MethodInvocation::Reset(_) => "hardhat_reset",
+            MethodInvocation::ResetGasReport(_) => "hardhat_resetGasReport",
             MethodInvocation::SetBalance(_, _) => "hardhat_setBalance",
             MethodInvocation::SetCode(_, _) => "hardhat_setCode",
             MethodInvocation::SetCoinbase(_) => "hardhat_setCoinbase",
+            MethodInvocation::SetFinalizedBlock(_) => "hardhat_setFinalizedBlock",
             MethodInvocation::SetLoggingEnabled(_) => "hardhat_setLoggingEnabled",
             MethodInvocation::SetMinGasPrice(_) => "hardhat_setMinGasPrice",
             MethodInvocation::SetNextBlockBaseFeePerGas(_) => "hardhat_setNextBlockBaseFeePerGas",
             MethodInvocation::SetNonce(_, _) => "hardhat_setNonce",
+            MethodInvocation::SetParentBeaconBlockRoot(_) => "hardhat_setParentBeaconBlockRoot",
             MethodInvocation::SetPrevRandao(_) => "hardhat_setPrevRandao",
+            MethodInvocation::SetSafeBlock(_) => "hardhat_setSafeBlock",
             MethodInvocation::SetStorageAt(_, _, _) => "hardhat_setStorageAt",
             MethodInvocation::StopImpersonatingAccount(_) => "hardhat_stopImpersonatingAccount",
         }
@@ -0,0 +1,307 @@
+use core::fmt::Debug;
+use std::sync::Arc;
+
+use edr_eth::{remote::PreEip1898BlockSpec, Address, B256};
+use edr_evm::{
+    blockchain::BlockchainError, Action, LocalizedTransactionTrace, ParityTraceConfig, SyncBlock,
+    TraceOutput, TraceResults, TransactionTrace,
+};
+
+use crate::{
+    data::{BlockDataForTransaction, ProviderData, TransactionAndBlock},
+    ProviderError,
+};
+
+pub fn handle_trace_block<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    block_spec: PreEip1898BlockSpec,
+) -> Result<Option<Vec<LocalizedTransactionTrace>>, ProviderError<LoggerErrorT>> {
+    let block = match data.block_by_block_spec(&block_spec.into()) {
+        Ok(Some(block)) => block,
+        // Pending and unknown blocks don't have traces
+        Ok(None) | Err(ProviderError::InvalidBlockNumberOrHash { .. }) => return Ok(None),
+        Err(error) => return Err(error),
+    };
+
+    trace_block(data, &block).map(Some)
+}
+
+pub fn handle_trace_filter<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    filter: TraceFilterRequest,
+) -> Result<Vec<LocalizedTransactionTrace>, ProviderError<LoggerErrorT>> {
+    let from_block = resolve_block_number(data, filter.from_block.clone())?;
+    let to_block = resolve_block_number(data, filter.to_block.clone())?;
+
+    if from_block > to_block {
+        return Err(ProviderError::InvalidArgument(format!(
+            "fromBlock ({from_block}) must not be greater than toBlock ({to_block})"
+        )));
+    }
+
+    let mut traces = Vec::new();
+    for block_number in from_block..=to_block {
+        let block = data
+            .block_by_block_spec(&block_number.into())?
+            .expect("Block numbers up to the last block exist");
+
+        traces.extend(
+            trace_block(data, &block)?
+                .into_iter()
+                .filter(|trace| filter.matches(&trace.trace)),
+        );
+    }
+
+    let traces = traces.into_iter().skip(filter.after.unwrap_or(0));
+    Ok(if let Some(count) = filter.count {
+        traces.take(count).collect()
+    } else {
+        traces.collect()
+    })
+}
+
+pub fn handle_trace_replay_transaction<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    transaction_hash: B256,
+    trace_types: Vec<TraceType>,
+) -> Result<TraceResults, ProviderError<LoggerErrorT>> {
+    let trace_config = ParityTraceConfig {
+        trace: trace_types.contains(&TraceType::Trace),
+        vm_trace: trace_types.contains(&TraceType::VmTrace),
+        state_diff: trace_types.contains(&TraceType::StateDiff),
+    };
+
+    data.trace_replay_transaction(&transaction_hash, trace_config)
+}
+
+pub fn handle_trace_transaction<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    transaction_hash: B256,
+) -> Result<Option<Vec<LocalizedTransactionTrace>>, ProviderError<LoggerErrorT>> {
+    let Some(TransactionAndBlock {
+        block_data:
+            Some(BlockDataForTransaction {
+                block,
+                transaction_index,
+            }),
+        ..
+    }) = data.transaction_by_hash(&transaction_hash)?
+    else {
+        return Ok(None);
+    };
+
+    let results = data.trace_replay_transaction(
+        &transaction_hash,
+        ParityTraceConfig {
+            trace: true,
+            ..ParityTraceConfig::default()
+        },
+    )?;
+
+    let transaction_position =
+        usize::try_from(transaction_index).expect("Indices cannot be larger than usize::MAX");
+
+    Ok(Some(localize_traces(
+        &*block,
+        transaction_hash,
+        transaction_position,
+        results.trace,
+    )))
+}
+
+/// The traces to return for `trace_replayTransaction`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceType {
+    /// The flat list of actions
+    Trace,
+    /// The trace of the executed instructions
+    VmTrace,
+    /// The changes to the state
+    StateDiff,
+}
+
+/// Filter options for `trace_filter`
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceFilterRequest {
+    /// The first block to trace. Defaults to the latest block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_block: Option<PreEip1898BlockSpec>,
+    /// The last block to trace. Defaults to the latest block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_block: Option<PreEip1898BlockSpec>,
+    /// Only return actions sent by these addresses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_address: Option<Vec<Address>>,
+    /// Only return actions received by these addresses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_address: Option<Vec<Address>>,
+    /// The number of matching traces to skip
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<usize>,
+    /// The maximum number of traces to return
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+}
+
+impl TraceFilterRequest {
+    /// Whether the trace matches the address filters.
+    fn matches(&self, trace: &TransactionTrace) -> bool {
+        let (from, to) = match &trace.action {
+            Action::Call(action) => (action.from, Some(action.to)),
+            Action::Create(action) => {
+                let address = match &trace.result {
+                    Some(TraceOutput::Create(output)) => Some(output.address),
+                    _ => None,
+                };
+
+                (action.from, address)
+            }
+            Action::SelfDestruct(action) => (action.address, Some(action.refund_address)),
+        };
+
+        let from_matches = self.from_address.as_ref().map_or(true, |addresses| {
+            addresses.is_empty() || addresses.contains(&from)
+        });
+
+        let to_matches = self.to_address.as_ref().map_or(true, |addresses| {
+            addresses.is_empty() || to.map_or(false, |to| addresses.contains(&to))
+        });
+
+        from_matches && to_matches
+    }
+}
+
+fn resolve_block_number<LoggerErrorT: Debug>(
+    data: &ProviderData<LoggerErrorT>,
+    block_spec: Option<PreEip1898BlockSpec>,
+) -> Result<u64, ProviderError<LoggerErrorT>> {
+    let block_spec = block_spec.unwrap_or_else(PreEip1898BlockSpec::latest);
+
+    // The pending block doesn't have traces, so the latest block is used instead
+    Ok(data
+        .block_by_block_spec(&block_spec.into())?
+        .map_or_else(|| data.last_block_number(), |block| block.header().number))
+}
+
+fn trace_block<LoggerErrorT: Debug>(
+    data: &mut ProviderData<LoggerErrorT>,
+    block: &Arc<dyn SyncBlock<Error = BlockchainError>>,
+) -> Result<Vec<LocalizedTransactionTrace>, ProviderError<LoggerErrorT>> {
+    let results = data.trace_block(
+        block.header(),
+        block.transactions().to_vec(),
+        ParityTraceConfig {
+            trace: true,
+            ..ParityTraceConfig::default()
+        },
+    )?;
+
+    Ok(results
+        .into_iter()
+        .zip(block.transactions())
+        .enumerate()
+        .flat_map(|(transaction_position, (result, transaction))| {
+            localize_traces(
+                &**block,
+                *transaction.hash(),
+                transaction_position,
+                result.trace,
+            )
+        })
+        .collect())
+}
+
+fn localize_traces(
+    block: &dyn SyncBlock<Error = BlockchainError>,
+    transaction_hash: B256,
+    transaction_position: usize,
+    traces: Vec<TransactionTrace>,
+) -> Vec<LocalizedTransactionTrace> {
+    traces
+        .into_iter()
+        .map(|trace| LocalizedTransactionTrace {
+            trace,
+            block_hash: *block.hash(),
+            block_number: block.header().number,
+            transaction_hash,
+            transaction_position,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use edr_eth::{Bytes, U256};
+    use edr_evm::{CallAction, CallType, CreateAction, CreateOutput};
+
+    use super::*;
+
+    fn call_trace(from: Address, to: Address) -> TransactionTrace {
+        TransactionTrace {
+            action: Action::Call(CallAction {
+                from,
+                call_type: CallType::Call,
+                gas: 0,
+                input: Bytes::new(),
+                to,
+                value: U256::ZERO,
+            }),
+            error: None,
+            result: None,
+            subtraces: 0,
+            trace_address: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn filter_matches_addresses() {
+        let sender = Address::repeat_byte(1);
+        let receiver = Address::repeat_byte(2);
+
+        let filter = TraceFilterRequest {
+            from_address: Some(vec![sender]),
+            to_address: Some(vec![receiver]),
+            ..TraceFilterRequest::default()
+        };
+
+        assert!(filter.matches(&call_trace(sender, receiver)));
+        assert!(!filter.matches(&call_trace(receiver, receiver)));
+        assert!(!filter.matches(&call_trace(sender, sender)));
+        assert!(TraceFilterRequest::default().matches(&call_trace(receiver, sender)));
+    }
+
+    #[test]
+    fn filter_matches_created_contract() {
+        let creator = Address::repeat_byte(1);
+        let contract = Address::repeat_byte(2);
+
+        let filter = TraceFilterRequest {
+            to_address: Some(vec![contract]),
+            ..TraceFilterRequest::default()
+        };
+
+        let mut trace = TransactionTrace {
+            action: Action::Create(CreateAction {
+                from: creator,
+                gas: 0,
+                init: Bytes::new(),
+                value: U256::ZERO,
+            }),
+            error: Some("Reverted".to_string()),
+            result: None,
+            subtraces: 0,
+            trace_address: Vec::new(),
+        };
+        assert!(!filter.matches(&trace));
+
+        trace.error = None;
+        trace.result = Some(TraceOutput::Create(CreateOutput {
+            address: contract,
+            code: Bytes::new(),
+            gas_used: 0,
+        }));
+        assert!(filter.matches(&trace));
+    }
+}
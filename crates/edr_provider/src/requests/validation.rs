@@ -73,6 +73,12 @@ impl<'data> From<&'data SignedTransaction> for SpecValidationData<'data> {
                 max_priority_fee_per_gas: Some(&tx.max_priority_fee_per_gas),
                 access_list: Some(tx.access_list.0.as_ref()),
             },
+            SignedTransaction::Deposit(_) => Self {
+                gas_price: None,
+                max_fee_per_gas: None,
+                max_priority_fee_per_gas: None,
+                access_list: None,
+            },
         }
     }
 }
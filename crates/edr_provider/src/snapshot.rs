@@ -1,19 +1,25 @@
 use std::{collections::BTreeMap, time::Instant};
 
-use edr_eth::{Address, U256};
-use edr_evm::{state::IrregularState, MemPool, RandomHashGenerator};
+use edr_eth::{transaction::BlobSidecar, Address, B256, U256};
+use edr_evm::{state::IrregularState, HashMap, MemPool, RandomHashGenerator};
 
-use crate::data::StateId;
+use crate::{data::StateId, requests::hardhat::rpc_types::WithdrawalRequest};
 
 pub(crate) struct Snapshot {
     pub block_number: u64,
     pub block_number_to_state_id: BTreeMap<u64, StateId>,
     pub block_time_offset_seconds: i64,
     pub coinbase: Address,
+    pub finalized_block_number: Option<u64>,
     pub irregular_state: IrregularState,
     pub mem_pool: MemPool,
     pub next_block_base_fee_per_gas: Option<U256>,
     pub next_block_timestamp: Option<u64>,
+    pub next_withdrawal_index: u64,
+    pub parent_beacon_block_root_generator: RandomHashGenerator,
+    pub pending_blob_sidecars: HashMap<B256, BlobSidecar>,
     pub prev_randao_generator: RandomHashGenerator,
+    pub queued_withdrawals: Vec<WithdrawalRequest>,
+    pub safe_block_number: Option<u64>,
     pub time: Instant,
 }
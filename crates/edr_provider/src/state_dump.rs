@@ -0,0 +1,350 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+use edr_eth::{
+    block::{Header, PartialHeader},
+    log::{FilterLog, Log},
+    receipt::{BlockReceipt, TransactionReceipt, TypedReceipt},
+    transaction::{BlobSidecar, SignedTransaction},
+    withdrawal::Withdrawal,
+    Address, SpecId, B256, U256,
+};
+use edr_evm::{
+    blockchain::{BlockchainError, LocalInsertBlockError},
+    state::{IrregularState, StateDiff, StateError},
+    Block, ExecutableTransaction, LocalBlock, MemPoolAddTransactionError, SyncBlock,
+    TransactionCreationError,
+};
+
+use crate::requests::hardhat::rpc_types::WithdrawalRequest;
+
+/// The version of the state dump format. Increment when making breaking
+/// changes to the format.
+pub(crate) const STATE_DUMP_VERSION: u32 = 1;
+
+/// An error that occurred while writing or restoring a state dump.
+#[derive(Debug, thiserror::Error)]
+pub enum StateDumpError {
+    /// A blockchain error
+    #[error(transparent)]
+    Blockchain(#[from] BlockchainError),
+    /// The state dump was created for a different chain ID.
+    #[error("The state dump was created for chain ID {actual}, but the provider uses chain ID {expected}")]
+    ChainIdMismatch {
+        /// The chain ID of the provider
+        expected: u64,
+        /// The chain ID of the state dump
+        actual: u64,
+    },
+    /// The state dump was created for a forked blockchain, but the provider
+    /// doesn't have a fork configured, or vice versa.
+    #[error("The state dump was created {}, but the provider is {}", fork_description(*.dump_is_forked), fork_description(*.provider_is_forked))]
+    ForkMismatch {
+        /// Whether the state dump was created for a forked blockchain
+        dump_is_forked: bool,
+        /// Whether the provider has a fork configured
+        provider_is_forked: bool,
+    },
+    /// The state dump was created for a different hardfork.
+    #[error("The state dump was created for hardfork {actual:?}, but the provider uses hardfork {expected:?}")]
+    HardforkMismatch {
+        /// The hardfork of the provider
+        expected: SpecId,
+        /// The hardfork of the state dump
+        actual: SpecId,
+    },
+    /// The hash of a restored block doesn't match its header.
+    #[error("The restored block {block_number} doesn't match the hash of the dumped block")]
+    InvalidBlockHash {
+        /// The number of the restored block
+        block_number: u64,
+    },
+    /// An I/O error occurred while reading or writing the state dump.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// The state dump could not be (de)serialized.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// An error occurred while restoring the genesis block.
+    #[error(transparent)]
+    LocalBlockchain(#[from] LocalInsertBlockError),
+    /// An error occurred while restoring a pending transaction.
+    #[error(transparent)]
+    MemPool(#[from] MemPoolAddTransactionError<StateError>),
+    /// The state dump of a local blockchain doesn't start with a genesis
+    /// block.
+    #[error("The state dump doesn't contain a genesis block")]
+    MissingGenesisBlock,
+    /// A state error
+    #[error(transparent)]
+    State(#[from] StateError),
+    /// An error occurred while restoring a transaction.
+    #[error(transparent)]
+    TransactionCreation(#[from] TransactionCreationError),
+    /// The state dump was created with an unsupported version of the format.
+    #[error("Unsupported state dump version {actual}. Expected version {expected}")]
+    UnsupportedVersion {
+        /// The version of the format supported by the provider
+        expected: u32,
+        /// The version of the format of the state dump
+        actual: u32,
+    },
+}
+
+fn fork_description(is_forked: bool) -> &'static str {
+    if is_forked {
+        "for a forked blockchain"
+    } else {
+        "for a local blockchain"
+    }
+}
+
+/// The complete state of a provider, as returned by `hardhat_dumpState`.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateDump {
+    /// The version of the format
+    pub version: u32,
+    /// The chain ID of the provider
+    pub chain_id: u64,
+    /// The hardfork of new blocks
+    pub hardfork: SpecId,
+    /// The block number of the forked blockchain, if any. Only blocks after
+    /// the fork block are included in the history.
+    pub fork_block_number: Option<u64>,
+    /// The local blocks and reserved blocks, in ascending order
+    pub history: Vec<HistoryEntryDump>,
+    /// The state overrides that were applied on top of blocks, e.g. by
+    /// `hardhat_setBalance`
+    pub irregular_state: IrregularState,
+    /// The transactions in the mem pool, in insertion order
+    pub transactions: Vec<TransactionDump>,
+    /// The gas limit of new blocks
+    pub block_gas_limit: u64,
+    /// The offset of new blocks' timestamps from the current time, in seconds
+    pub block_time_offset_seconds: i64,
+    /// The beneficiary of new blocks
+    pub coinbase: Address,
+    /// The accounts that are impersonated, in ascending order
+    pub impersonated_accounts: Vec<Address>,
+    /// The base fee of the next block, if it was set with
+    /// `hardhat_setNextBlockBaseFeePerGas`
+    pub next_block_base_fee_per_gas: Option<U256>,
+    /// The timestamp of the next block, if it was set with
+    /// `evm_setNextBlockTimestamp`
+    pub next_block_timestamp: Option<u64>,
+    /// The prevrandao of the next post-merge block
+    pub next_prev_randao: B256,
+    /// The parent beacon block root of the next post-Cancun block
+    pub next_parent_beacon_block_root: Option<B256>,
+    /// The safe block, if it was set with `hardhat_setSafeBlock`
+    pub safe_block_number: Option<u64>,
+    /// The finalized block, if it was set with `hardhat_setFinalizedBlock`
+    pub finalized_block_number: Option<u64>,
+    /// The withdrawals that were queued with `hardhat_addWithdrawals`, in
+    /// insertion order
+    pub queued_withdrawals: Vec<WithdrawalRequest>,
+    /// The stored blob sidecars of mined and pending EIP-4844 transactions
+    pub blob_sidecars: Vec<BlobSidecarDump>,
+}
+
+impl StateDump {
+    /// Reads a state dump from the file at the provided path.
+    pub fn read_from_file(path: &Path) -> Result<Self, StateDumpError> {
+        let reader = BufReader::new(File::open(path)?);
+        let dump = serde_json::from_reader(reader)?;
+
+        Ok(dump)
+    }
+
+    /// Writes the state dump to the file at the provided path, overwriting
+    /// any existing file.
+    pub fn write_to_file(&self, path: &Path) -> Result<(), StateDumpError> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(writer, self)?;
+
+        Ok(())
+    }
+}
+
+/// An entry in the history of a [`StateDump`].
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum HistoryEntryDump {
+    /// A mined block
+    Block(BlockDump),
+    /// A sequence of reserved blocks, as created by `hardhat_mine`
+    Reservation {
+        /// The number of reserved blocks
+        additional: u64,
+        /// The interval between the timestamps of the reserved blocks
+        interval: u64,
+    },
+}
+
+/// A mined block and the diff that it applied to the state.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockDump {
+    /// The block's header
+    pub header: Header,
+    /// The block's transactions
+    pub transactions: Vec<TransactionDump>,
+    /// The receipts of the block's transactions
+    pub receipts: Vec<TransactionReceipt<Log>>,
+    /// The block's withdrawals, if Shanghai was active
+    pub withdrawals: Option<Vec<Withdrawal>>,
+    /// The diff that the block applied to the state
+    pub state_diff: StateDiff,
+    /// The hardfork that the block was mined with
+    pub hardfork: SpecId,
+}
+
+impl BlockDump {
+    /// Constructs a dump of the provided block, which was mined with the
+    /// provided hardfork, and its state diff.
+    pub fn new(
+        block: &dyn SyncBlock<Error = BlockchainError>,
+        hardfork: SpecId,
+        state_diff: StateDiff,
+    ) -> Result<Self, BlockchainError> {
+        let receipts = block
+            .transaction_receipts()?
+            .iter()
+            .map(|receipt| block_to_transaction_receipt(receipt))
+            .collect();
+
+        Ok(Self {
+            header: block.header().clone(),
+            transactions: block
+                .transactions()
+                .iter()
+                .map(TransactionDump::from)
+                .collect(),
+            receipts,
+            withdrawals: block.withdrawals().map(<[Withdrawal]>::to_vec),
+            state_diff,
+            hardfork,
+        })
+    }
+
+    /// Converts the dump into a block and its state diff, validating that the
+    /// restored block has the same hash as the dumped block.
+    pub fn into_local_block(self) -> Result<(LocalBlock, StateDiff), StateDumpError> {
+        let spec_id = self.hardfork;
+        let block_number = self.header.number;
+        let expected_hash = self.header.hash();
+
+        let transactions = self
+            .transactions
+            .into_iter()
+            .map(|transaction| transaction.into_executable(spec_id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let receipts = self
+            .receipts
+            .into_iter()
+            .map(|mut receipt| {
+                // The hardfork of a receipt isn't serialized
+                receipt.inner.spec_id = spec_id;
+                receipt
+            })
+            .collect();
+
+        let block = LocalBlock::new(
+            PartialHeader::from(self.header),
+            transactions,
+            receipts,
+            Vec::new(),
+            self.withdrawals,
+        );
+
+        if *block.hash() != expected_hash {
+            return Err(StateDumpError::InvalidBlockHash { block_number });
+        }
+
+        Ok((block, self.state_diff))
+    }
+}
+
+/// The blob sidecar of an EIP-4844 transaction.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobSidecarDump {
+    /// The number of the block that contains the transaction, or `None` if
+    /// the transaction is pending
+    pub block_number: Option<u64>,
+    /// The hash of the transaction
+    pub transaction_hash: B256,
+    /// The blob sidecar
+    pub sidecar: BlobSidecar,
+}
+
+/// A signed transaction and its caller.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionDump {
+    /// The signed transaction
+    pub transaction: SignedTransaction,
+    /// The transaction's caller, which can differ from the signer for
+    /// impersonated accounts
+    pub caller: Address,
+}
+
+impl TransactionDump {
+    /// Converts the dump into an executable transaction.
+    pub fn into_executable(
+        self,
+        spec_id: SpecId,
+    ) -> Result<ExecutableTransaction, TransactionCreationError> {
+        ExecutableTransaction::with_caller(spec_id, self.transaction, self.caller)
+    }
+}
+
+impl From<&ExecutableTransaction> for TransactionDump {
+    fn from(transaction: &ExecutableTransaction) -> Self {
+        Self {
+            transaction: transaction.as_inner().clone(),
+            caller: *transaction.caller(),
+        }
+    }
+}
+
+pub(crate) fn block_to_transaction_receipt(receipt: &BlockReceipt) -> TransactionReceipt<Log> {
+    let TransactionReceipt {
+        inner,
+        transaction_hash,
+        transaction_index,
+        from,
+        to,
+        contract_address,
+        gas_used,
+        effective_gas_price,
+        l1_fee,
+    } = receipt.inner.clone();
+
+    TransactionReceipt {
+        inner: TypedReceipt {
+            cumulative_gas_used: inner.cumulative_gas_used,
+            logs_bloom: inner.logs_bloom,
+            logs: inner
+                .logs
+                .into_iter()
+                .map(|log: FilterLog| log.inner.inner.inner)
+                .collect(),
+            data: inner.data,
+            spec_id: inner.spec_id,
+        },
+        transaction_hash,
+        transaction_index,
+        from,
+        to,
+        contract_address,
+        gas_used,
+        effective_gas_price,
+        l1_fee,
+    }
+}
@@ -4,7 +4,7 @@ use edr_eth::{
     block::BlobGas, signature::secret_key_from_str, trie::KECCAK_NULL_RLP, Address, HashMap,
     SpecId, U256,
 };
-use edr_evm::{alloy_primitives::U160, Block};
+use edr_evm::{alloy_primitives::U160, blockchain::GenesisBlockOptions, Block};
 
 use super::*;
 use crate::{config::MiningConfig, requests::hardhat::rpc_types::ForkConfig};
@@ -49,8 +49,13 @@ pub fn create_test_config_with_fork(fork: Option<ForkConfig>) -> ProviderConfig
         chain_id: 123,
         chains: HashMap::new(),
         coinbase: Address::from(U160::from(1)),
+        enable_cheatcodes: false,
+        enable_coverage: false,
+        finality: None,
         fork,
         genesis_accounts: HashMap::new(),
+        genesis_block: GenesisBlockOptions::default(),
+        genesis_storage: HashMap::new(),
         hardfork: SpecId::LATEST,
         initial_base_fee_per_gas: Some(U256::from(1000000000)),
         initial_blob_gas: Some(BlobGas {
@@ -62,7 +67,10 @@ pub fn create_test_config_with_fork(fork: Option<ForkConfig>) -> ProviderConfig
         min_gas_price: U256::ZERO,
         mining: MiningConfig::default(),
         network_id: 123,
+        optimism: false,
         cache_dir: edr_defaults::CACHE_DIR.into(),
+        state_path: None,
+        withdrawal_schedule: None,
     }
 }
 
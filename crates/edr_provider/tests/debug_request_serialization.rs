@@ -0,0 +1,67 @@
+mod common;
+
+use edr_eth::{
+    remote::{eth::CallRequest, BlockOverrides, BlockSpec, PreEip1898BlockSpec},
+    Address, Bytes, B256, U256,
+};
+use edr_provider::MethodInvocation;
+
+use crate::common::help_test_method_invocation_serde;
+
+#[test]
+fn serde_debug_trace_block() {
+    help_test_method_invocation_serde(MethodInvocation::DebugTraceBlock(
+        Bytes::from(&b"whatever"[..]),
+        None,
+    ));
+}
+
+#[test]
+fn serde_debug_trace_block_by_hash() {
+    help_test_method_invocation_serde(MethodInvocation::DebugTraceBlockByHash(
+        B256::from(U256::from(1)),
+        None,
+    ));
+}
+
+#[test]
+fn serde_debug_trace_block_by_number() {
+    help_test_method_invocation_serde(MethodInvocation::DebugTraceBlockByNumber(
+        PreEip1898BlockSpec::Number(100),
+        None,
+    ));
+}
+
+#[test]
+fn serde_debug_trace_block_by_tag() {
+    help_test_method_invocation_serde(MethodInvocation::DebugTraceBlockByNumber(
+        PreEip1898BlockSpec::latest(),
+        None,
+    ));
+}
+
+#[test]
+fn serde_debug_trace_call() {
+    let call_request = CallRequest {
+        from: Some(Address::repeat_byte(1)),
+        to: Some(Address::repeat_byte(2)),
+        ..CallRequest::default()
+    };
+
+    help_test_method_invocation_serde(MethodInvocation::DebugTraceCall(
+        call_request.clone(),
+        Some(BlockSpec::latest()),
+        None,
+        None,
+    ));
+    help_test_method_invocation_serde(MethodInvocation::DebugTraceCall(
+        call_request,
+        Some(BlockSpec::latest()),
+        None,
+        Some(BlockOverrides {
+            time: Some(1_700_000_000),
+            prev_randao: Some(B256::from(U256::from(3))),
+            ..BlockOverrides::default()
+        }),
+    ));
+}
@@ -4,13 +4,13 @@ use edr_eth::{
     remote::{
         eth::CallRequest,
         filter::{LogFilterOptions, LogOutput, OneOrMore},
-        BlockSpec, BlockTag, PreEip1898BlockSpec,
+        BlockOverrides, BlockSpec, BlockTag, PreEip1898BlockSpec, StateOverrideOptions,
     },
     transaction::EthTransactionRequest,
     Address, Bytes, B256, U256, U64,
 };
 use edr_evm::alloy_primitives::U160;
-use edr_provider::{MethodInvocation, OneUsizeOrTwo, U64OrUsize};
+use edr_provider::{BlockStateCalls, MethodInvocation, OneUsizeOrTwo, SimulatePayload, U64OrUsize};
 
 use crate::common::{
     help_test_method_invocation_serde, help_test_method_invocation_serde_with_expected,
@@ -21,6 +21,11 @@ fn test_serde_eth_accounts() {
     help_test_method_invocation_serde(MethodInvocation::Accounts(()));
 }
 
+#[test]
+fn test_serde_eth_blob_base_fee() {
+    help_test_method_invocation_serde(MethodInvocation::BlobBaseFee(()));
+}
+
 #[test]
 fn test_serde_eth_block_number() {
     help_test_method_invocation_serde(MethodInvocation::BlockNumber(()));
@@ -44,10 +49,23 @@ fn test_serde_eth_call() {
         tx.clone(),
         Some(BlockSpec::latest()),
         None,
+        None,
+    ));
+    help_test_method_invocation_serde(MethodInvocation::Call(
+        tx.clone(),
+        Some(BlockSpec::latest()),
+        Some(StateOverrideOptions::default()),
+        Some(BlockOverrides {
+            number: Some(100),
+            time: Some(1_700_000_000),
+            base_fee_per_gas: Some(U256::from(7)),
+            blob_base_fee: Some(1),
+            ..BlockOverrides::default()
+        }),
     ));
     help_test_method_invocation_serde_with_expected(
-        MethodInvocation::Call(tx.clone(), None, None),
-        MethodInvocation::Call(tx, Some(BlockSpec::latest()), None),
+        MethodInvocation::Call(tx.clone(), None, None, None),
+        MethodInvocation::Call(tx, Some(BlockSpec::latest()), None, None),
     );
 }
 
@@ -61,6 +79,30 @@ fn test_serde_eth_coinbase() {
     help_test_method_invocation_serde(MethodInvocation::Coinbase(()));
 }
 
+#[test]
+fn test_serde_eth_create_access_list() {
+    let tx = CallRequest {
+        from: Some(Address::from(U160::from(1))),
+        to: Some(Address::from(U160::from(2))),
+        gas: Some(3),
+        gas_price: Some(U256::from(4)),
+        max_fee_per_gas: None,
+        max_priority_fee_per_gas: None,
+        value: Some(U256::from(123568919)),
+        data: Some(Bytes::from(&b"whatever"[..])),
+        access_list: None,
+        transaction_type: None,
+    };
+    help_test_method_invocation_serde(MethodInvocation::CreateAccessList(
+        tx.clone(),
+        Some(BlockSpec::latest()),
+    ));
+    help_test_method_invocation_serde_with_expected(
+        MethodInvocation::CreateAccessList(tx.clone(), None),
+        MethodInvocation::CreateAccessList(tx, Some(BlockSpec::pending())),
+    );
+}
+
 #[test]
 fn test_serde_eth_estimate_gas() {
     let tx = CallRequest {
@@ -78,10 +120,22 @@ fn test_serde_eth_estimate_gas() {
     help_test_method_invocation_serde(MethodInvocation::EstimateGas(
         tx.clone(),
         Some(BlockSpec::latest()),
+        None,
+        None,
+    ));
+    help_test_method_invocation_serde(MethodInvocation::EstimateGas(
+        tx.clone(),
+        Some(BlockSpec::latest()),
+        Some(StateOverrideOptions::default()),
+        Some(BlockOverrides {
+            gas_limit: Some(30_000_000),
+            fee_recipient: Some(Address::from(U160::from(3))),
+            ..BlockOverrides::default()
+        }),
     ));
     help_test_method_invocation_serde_with_expected(
-        MethodInvocation::EstimateGas(tx.clone(), None),
-        MethodInvocation::EstimateGas(tx, Some(BlockSpec::pending())),
+        MethodInvocation::EstimateGas(tx.clone(), None, None, None),
+        MethodInvocation::EstimateGas(tx, Some(BlockSpec::pending()), None, None),
     );
 }
 
@@ -219,6 +273,23 @@ fn test_serde_eth_get_logs_by_block_hash() {
     }));
 }
 
+#[test]
+fn test_serde_eth_get_proof() {
+    help_test_method_invocation_serde(MethodInvocation::GetProof(
+        Address::from(U160::from(1)),
+        vec![U256::ZERO, U256::from(1)],
+        Some(BlockSpec::latest()),
+    ));
+    help_test_method_invocation_serde_with_expected(
+        MethodInvocation::GetProof(Address::from(U160::from(1)), Vec::new(), None),
+        MethodInvocation::GetProof(
+            Address::from(U160::from(1)),
+            Vec::new(),
+            Some(BlockSpec::latest()),
+        ),
+    );
+}
+
 #[test]
 fn test_serde_eth_get_storage_at() {
     help_test_method_invocation_serde(MethodInvocation::GetStorageAt(
@@ -346,6 +417,38 @@ fn test_serde_eth_sign() {
     ));
 }
 
+#[test]
+fn test_serde_eth_simulate_v1() {
+    let payload = SimulatePayload {
+        block_state_calls: vec![BlockStateCalls {
+            block_overrides: Some(BlockOverrides {
+                number: Some(100),
+                time: Some(1_700_000_000),
+                fee_recipient: Some(Address::from(U160::from(3))),
+                ..BlockOverrides::default()
+            }),
+            state_overrides: None,
+            calls: vec![CallRequest {
+                from: Some(Address::from(U160::from(1))),
+                to: Some(Address::from(U160::from(2))),
+                value: Some(U256::from(123)),
+                ..CallRequest::default()
+            }],
+        }],
+        trace_transfers: true,
+        validation: false,
+    };
+
+    help_test_method_invocation_serde(MethodInvocation::SimulateV1(
+        payload.clone(),
+        Some(BlockSpec::pending()),
+    ));
+    help_test_method_invocation_serde_with_expected(
+        MethodInvocation::SimulateV1(payload.clone(), None),
+        MethodInvocation::SimulateV1(payload, Some(BlockSpec::latest())),
+    );
+}
+
 macro_rules! impl_serde_eth_subscribe_tests {
     ($(
         $name:ident => $variant:expr,
@@ -1,10 +1,13 @@
 mod common;
 
-use edr_eth::{Address, Bytes, B256, U256};
-use edr_evm::alloy_primitives::U160;
+use edr_eth::{remote::BlockSpec, Address, Bytes, SpecId, B256, U256, U64};
+use edr_evm::{alloy_primitives::U160, state::IrregularState};
 use edr_provider::{
-    hardhat_rpc_types::{CompilerInput, CompilerOutput, ForkConfig, ResetProviderConfig},
-    MethodInvocation,
+    hardhat_rpc_types::{
+        CompilerInput, CompilerOutput, CoverageFormat, ForkConfig, ResetProviderConfig,
+        WithdrawalRequest,
+    },
+    MethodInvocation, StateDump,
 };
 
 use crate::common::help_test_method_invocation_serde;
@@ -44,16 +47,66 @@ fn serde_hardhat_compiler() {
     }
 }
 
+#[test]
+fn serde_hardhat_add_withdrawals() {
+    help_test_method_invocation_serde(MethodInvocation::AddWithdrawals(vec![WithdrawalRequest {
+        validator_index: 1,
+        address: Address::from(U160::from(1)),
+        amount: U256::from(32_000_000_000u64),
+    }]));
+}
+
 #[test]
 fn serde_hardhat_drop_transaction() {
     help_test_method_invocation_serde(MethodInvocation::DropTransaction(B256::from(U256::from(1))));
 }
 
+#[test]
+fn serde_hardhat_dump_genesis() {
+    help_test_method_invocation_serde(MethodInvocation::DumpGenesis(()));
+}
+
+#[test]
+fn serde_hardhat_dump_state() {
+    help_test_method_invocation_serde(MethodInvocation::DumpState(()));
+}
+
 #[test]
 fn serde_hardhat_get_automine() {
     help_test_method_invocation_serde(MethodInvocation::GetAutomine(()));
 }
 
+#[test]
+fn serde_hardhat_get_blob_sidecars() {
+    help_test_method_invocation_serde(MethodInvocation::GetBlobSidecars(BlockSpec::Number(1)));
+    help_test_method_invocation_serde(MethodInvocation::GetBlobSidecars(BlockSpec::latest()));
+}
+
+#[test]
+fn serde_hardhat_get_coverage() {
+    help_test_method_invocation_serde(MethodInvocation::GetCoverage(None));
+    help_test_method_invocation_serde(MethodInvocation::GetCoverage(Some(CoverageFormat::Json)));
+    help_test_method_invocation_serde(MethodInvocation::GetCoverage(Some(CoverageFormat::Lcov)));
+
+    let json = r#"{"jsonrpc":"2.0","method":"hardhat_getCoverage","params":["lcov"],"id":2}"#;
+    let deserialized: MethodInvocation = serde_json::from_str(json)
+        .unwrap_or_else(|_| panic!("should have successfully deserialized json {json}"));
+    assert_eq!(
+        MethodInvocation::GetCoverage(Some(CoverageFormat::Lcov)),
+        deserialized
+    );
+}
+
+#[test]
+fn serde_hardhat_get_gas_profile() {
+    help_test_method_invocation_serde(MethodInvocation::GetGasProfile(B256::from(U256::from(1))));
+}
+
+#[test]
+fn serde_hardhat_get_gas_report() {
+    help_test_method_invocation_serde(MethodInvocation::GetGasReport(()));
+}
+
 #[test]
 fn serde_hardhat_get_stack_trace_failures_count() {
     help_test_method_invocation_serde(MethodInvocation::GetStackTraceFailuresCount(()));
@@ -71,6 +124,29 @@ fn serde_hardhat_interval_mine() {
     help_test_method_invocation_serde(MethodInvocation::IntervalMine(()));
 }
 
+#[test]
+fn serde_hardhat_load_state() {
+    help_test_method_invocation_serde(MethodInvocation::LoadState(Box::new(StateDump {
+        version: 1,
+        chain_id: 31337,
+        hardfork: SpecId::SHANGHAI,
+        fork_block_number: None,
+        history: Vec::new(),
+        irregular_state: IrregularState::default(),
+        transactions: Vec::new(),
+        block_gas_limit: 30_000_000,
+        block_time_offset_seconds: 0,
+        coinbase: Address::random(),
+        impersonated_accounts: vec![Address::random()],
+        next_block_base_fee_per_gas: Some(U256::from(1_000_000_000u64)),
+        next_block_timestamp: None,
+        next_prev_randao: B256::random(),
+        next_parent_beacon_block_root: Some(B256::random()),
+        safe_block_number: None,
+        finalized_block_number: None,
+    })));
+}
+
 #[test]
 fn serde_hardhat_metadata() {
     help_test_method_invocation_serde(MethodInvocation::Metadata(()));
@@ -89,6 +165,15 @@ fn serde_hardhat_mine() {
     assert_eq!(MethodInvocation::Mine(None, None), deserialized);
 }
 
+#[test]
+fn serde_hardhat_reorg() {
+    help_test_method_invocation_serde(MethodInvocation::Reorg(2, None));
+    help_test_method_invocation_serde(MethodInvocation::Reorg(
+        1,
+        Some(vec![vec![Bytes::from_static(&[0x01, 0x02])], Vec::new()]),
+    ));
+}
+
 #[test]
 fn serde_hardhat_reset() {
     help_test_method_invocation_serde(MethodInvocation::Reset(Some(ResetProviderConfig {
@@ -100,6 +185,11 @@ fn serde_hardhat_reset() {
     })));
 }
 
+#[test]
+fn serde_hardhat_reset_gas_report() {
+    help_test_method_invocation_serde(MethodInvocation::ResetGasReport(()));
+}
+
 #[test]
 fn serde_hardhat_set_balance() {
     help_test_method_invocation_serde(MethodInvocation::SetBalance(
@@ -121,6 +211,11 @@ fn serde_hardhat_set_coinbase() {
     help_test_method_invocation_serde(MethodInvocation::SetCoinbase(Address::random()));
 }
 
+#[test]
+fn serde_hardhat_set_finalized_block() {
+    help_test_method_invocation_serde(MethodInvocation::SetFinalizedBlock(U64::from(10)));
+}
+
 #[test]
 fn serde_hardhat_set_logging_enabled() {
     help_test_method_invocation_serde(MethodInvocation::SetLoggingEnabled(true));
@@ -141,11 +236,21 @@ fn serde_hardhat_set_nonce() {
     help_test_method_invocation_serde(MethodInvocation::SetNonce(Address::random(), 1u64));
 }
 
+#[test]
+fn serde_hardhat_set_parent_beacon_block_root() {
+    help_test_method_invocation_serde(MethodInvocation::SetParentBeaconBlockRoot(B256::random()));
+}
+
 #[test]
 fn serde_hardhat_set_prev_randao() {
     help_test_method_invocation_serde(MethodInvocation::SetPrevRandao(B256::random()));
 }
 
+#[test]
+fn serde_hardhat_set_safe_block() {
+    help_test_method_invocation_serde(MethodInvocation::SetSafeBlock(U64::from(10)));
+}
+
 #[test]
 fn serde_hardhat_set_storage_at() {
     help_test_method_invocation_serde(MethodInvocation::SetStorageAt(
@@ -0,0 +1,45 @@
+mod common;
+
+use edr_eth::{remote::PreEip1898BlockSpec, Address, B256, U256};
+use edr_provider::{MethodInvocation, TraceFilterRequest, TraceType};
+
+use crate::common::help_test_method_invocation_serde;
+
+#[test]
+fn serde_trace_block() {
+    help_test_method_invocation_serde(MethodInvocation::TraceBlock(PreEip1898BlockSpec::Number(
+        100,
+    )));
+}
+
+#[test]
+fn serde_trace_filter() {
+    help_test_method_invocation_serde(MethodInvocation::TraceFilter(TraceFilterRequest {
+        from_block: Some(PreEip1898BlockSpec::Number(1)),
+        to_block: Some(PreEip1898BlockSpec::latest()),
+        from_address: Some(vec![Address::repeat_byte(1)]),
+        to_address: None,
+        after: Some(2),
+        count: Some(10),
+    }));
+}
+
+#[test]
+fn serde_trace_filter_empty() {
+    help_test_method_invocation_serde(MethodInvocation::TraceFilter(TraceFilterRequest::default()));
+}
+
+#[test]
+fn serde_trace_replay_transaction() {
+    help_test_method_invocation_serde(MethodInvocation::TraceReplayTransaction(
+        B256::from(U256::from(1)),
+        vec![TraceType::Trace, TraceType::VmTrace, TraceType::StateDiff],
+    ));
+}
+
+#[test]
+fn serde_trace_transaction() {
+    help_test_method_invocation_serde(MethodInvocation::TraceTransaction(B256::from(U256::from(
+        1,
+    ))));
+}
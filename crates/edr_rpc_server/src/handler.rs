@@ -0,0 +1,241 @@
+use std::{convert::Infallible, sync::Arc};
+
+use edr_eth::{remote::jsonrpc, U256};
+use edr_provider::{InvalidRequestReason, MethodInvocation, Provider, ProviderRequest};
+use serde::Serialize;
+
+use crate::subscriptions::Session;
+
+/// Invalid JSON was received by the server.
+const PARSE_ERROR: i16 = -32700;
+/// The JSON sent is not a valid request object.
+const INVALID_REQUEST: i16 = -32600;
+/// The method is not available over the used transport.
+const METHOD_NOT_FOUND: i16 = -32601;
+/// Internal JSON-RPC error.
+const INTERNAL_ERROR: i16 = -32603;
+
+/// A JSON-RPC 2.0 response whose id is echoed verbatim, including `null`.
+#[derive(Debug, Serialize)]
+struct Response {
+    jsonrpc: jsonrpc::Version,
+    id: serde_json::Value,
+    #[serde(flatten)]
+    data: jsonrpc::ResponseData<serde_json::Value>,
+}
+
+impl Response {
+    fn error(code: i16, message: &str) -> Self {
+        Self {
+            jsonrpc: jsonrpc::Version::V2_0,
+            id: serde_json::Value::Null,
+            data: jsonrpc::ResponseData::new_error(code, message, None),
+        }
+    }
+}
+
+/// Handles JSON-RPC envelopes and forwards their method invocations to a
+/// [`Provider`].
+#[derive(Clone)]
+pub struct RequestHandler {
+    provider: Arc<Provider<Infallible>>,
+}
+
+impl RequestHandler {
+    /// Constructs a new instance.
+    pub fn new(provider: Arc<Provider<Infallible>>) -> Self {
+        Self { provider }
+    }
+
+    /// Handles a single or batch JSON-RPC request received over a transport
+    /// without support for subscriptions, such as HTTP.
+    ///
+    /// Returns `None` if no response should be sent, i.e. when the request
+    /// only consisted of notifications.
+    pub async fn handle(&self, json_request: &str) -> Option<String> {
+        self.handle_with_session(json_request, None).await
+    }
+
+    /// Handles a single or batch JSON-RPC request. Subscriptions are only
+    /// supported if a session is provided.
+    pub(crate) async fn handle_with_session(
+        &self,
+        json_request: &str,
+        session: Option<&Session>,
+    ) -> Option<String> {
+        let request: serde_json::Value = match serde_json::from_str(json_request) {
+            Ok(request) => request,
+            Err(error) => {
+                return Some(serialize(&Response::error(
+                    PARSE_ERROR,
+                    &format!("Parse error: {error}"),
+                )))
+            }
+        };
+
+        if let serde_json::Value::Array(requests) = request {
+            if requests.is_empty() {
+                return Some(serialize(&Response::error(
+                    INVALID_REQUEST,
+                    "Invalid request: empty batch",
+                )));
+            }
+
+            let mut responses = Vec::with_capacity(requests.len());
+            for request in requests {
+                if let Some(response) = self.handle_single(request, session).await {
+                    responses.push(response);
+                }
+            }
+
+            if responses.is_empty() {
+                None
+            } else {
+                Some(serialize(&responses))
+            }
+        } else {
+            self.handle_single(request, session)
+                .await
+                .map(|response| serialize(&response))
+        }
+    }
+
+    /// Unsubscribes from the subscriptions with the provided IDs, ignoring
+    /// failures.
+    pub(crate) async fn unsubscribe(&self, filter_ids: Vec<U256>) {
+        for filter_id in filter_ids {
+            let provider = self.provider.clone();
+            let _result = tokio::task::spawn_blocking(move || {
+                provider.handle_request(ProviderRequest::Single(MethodInvocation::Unsubscribe(
+                    filter_id,
+                )))
+            })
+            .await;
+        }
+    }
+
+    async fn handle_single(
+        &self,
+        request: serde_json::Value,
+        session: Option<&Session>,
+    ) -> Option<Response> {
+        if !request.is_object() {
+            return Some(Response::error(
+                INVALID_REQUEST,
+                "Invalid request: expected an object",
+            ));
+        }
+
+        // Requests without an id are notifications, which are executed but never
+        // answered.
+        let id = request.get("id").cloned();
+        let data = self.execute(&request, session).await;
+
+        id.map(|id| Response {
+            jsonrpc: jsonrpc::Version::V2_0,
+            id,
+            data,
+        })
+    }
+
+    async fn execute(
+        &self,
+        request: &serde_json::Value,
+        session: Option<&Session>,
+    ) -> jsonrpc::ResponseData<serde_json::Value> {
+        if request.get("jsonrpc").and_then(serde_json::Value::as_str) != Some("2.0") {
+            return jsonrpc::ResponseData::new_error(
+                INVALID_REQUEST,
+                "Invalid request: jsonrpc version must be 2.0",
+                None,
+            );
+        }
+
+        let json_request = request.to_string();
+        let method_invocation = match serde_json::from_str::<MethodInvocation>(&json_request) {
+            Ok(method_invocation) => method_invocation,
+            Err(error) => return self.invalid_request(&json_request, &error.to_string()).await,
+        };
+
+        let is_subscribe = matches!(method_invocation, MethodInvocation::Subscribe(..));
+        if is_subscribe && session.is_none() {
+            return jsonrpc::ResponseData::new_error(
+                METHOD_NOT_FOUND,
+                "eth_subscribe is only supported over WebSocket",
+                None,
+            );
+        }
+
+        let unsubscribed_id = if let MethodInvocation::Unsubscribe(filter_id) = &method_invocation
+        {
+            // Sessions can only cancel their own subscriptions
+            if !session.map_or(false, |session| session.has_subscription(filter_id)) {
+                return jsonrpc::ResponseData::Success {
+                    result: serde_json::Value::Bool(false),
+                };
+            }
+
+            Some(*filter_id)
+        } else {
+            None
+        };
+
+        // Register the subscription before it is installed, so no events are missed
+        let pending_subscription = match session {
+            Some(session) if is_subscribe => Some(session.begin_subscription().await),
+            _ => None,
+        };
+
+        let provider = self.provider.clone();
+        let result = match tokio::task::spawn_blocking(move || {
+            provider.handle_request(ProviderRequest::Single(method_invocation))
+        })
+        .await
+        {
+            Ok(result) => result.map(|response| response.result),
+            Err(error) => {
+                return jsonrpc::ResponseData::new_error(INTERNAL_ERROR, &error.to_string(), None)
+            }
+        };
+
+        if let (Some(session), Ok(result)) = (session, &result) {
+            if let Some(pending_subscription) = pending_subscription {
+                match serde_json::from_value::<U256>(result.clone()) {
+                    Ok(filter_id) => pending_subscription.complete(filter_id),
+                    Err(error) => tracing::error!(%error, "Invalid subscription id"),
+                }
+            } else if let Some(filter_id) = unsubscribed_id {
+                session.remove_subscription(&filter_id);
+            }
+        }
+
+        jsonrpc::ResponseData::from(result)
+    }
+
+    async fn invalid_request(
+        &self,
+        json_request: &str,
+        error_message: &str,
+    ) -> jsonrpc::ResponseData<serde_json::Value> {
+        let reason = InvalidRequestReason::new(json_request, error_message);
+
+        // Failed deserialization attempts need to be logged when they concern input
+        // validation.
+        if let Some((method_name, provider_error)) = reason.provider_error() {
+            let provider = self.provider.clone();
+            // Ignore potential failure of logging, as returning the original error is more
+            // important
+            let _result = tokio::task::spawn_blocking(move || {
+                provider.log_failed_deserialization(&method_name, &provider_error)
+            })
+            .await;
+        }
+
+        let data = serde_json::from_str(json_request).ok();
+        jsonrpc::ResponseData::new_error(reason.error_code(), &reason.error_message(), data)
+    }
+}
+
+fn serialize<T: Serialize>(response: &T) -> String {
+    serde_json::to_string(response).expect("JSON-RPC responses are always serializable")
+}
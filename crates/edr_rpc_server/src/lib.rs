@@ -0,0 +1,78 @@
+#![warn(missing_docs)]
+
+//! A JSON-RPC server that exposes an [`edr_provider::Provider`] over HTTP and
+//! WebSocket.
+
+mod handler;
+mod logger;
+mod server;
+mod subscriptions;
+
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+use edr_evm::blockchain::BlockchainError;
+use edr_provider::{CreationError, Logger, Provider, ProviderConfig};
+use tokio::runtime;
+
+pub use self::{handler::RequestHandler, logger::TracingLogger, subscriptions::SubscriptionRouter};
+
+/// A JSON-RPC server for Ethereum.
+pub struct Server {
+    handler: RequestHandler,
+    subscriptions: SubscriptionRouter,
+}
+
+impl Server {
+    /// Constructs a new instance, creating a provider with the provided
+    /// configuration.
+    pub fn new(runtime: runtime::Handle, config: ProviderConfig) -> Result<Self, CreationError> {
+        Self::with_logger(runtime, TracingLogger::default(), config)
+    }
+
+    /// Constructs a new instance, creating a provider with the provided
+    /// logger and configuration.
+    pub fn with_logger<LoggerT>(
+        runtime: runtime::Handle,
+        logger: LoggerT,
+        config: ProviderConfig,
+    ) -> Result<Self, CreationError>
+    where
+        LoggerT: Logger<BlockchainError = BlockchainError, LoggerError = Infallible>
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+    {
+        let subscriptions = SubscriptionRouter::default();
+        let provider = Provider::new(runtime, Box::new(logger), subscriptions.callback(), config)?;
+
+        Ok(Self::with_provider(Arc::new(provider), subscriptions))
+    }
+
+    /// Constructs a new instance for an existing provider. The provider's
+    /// subscriber callback must have been created by `subscriptions`.
+    pub fn with_provider(
+        provider: Arc<Provider<Infallible>>,
+        subscriptions: SubscriptionRouter,
+    ) -> Self {
+        Self {
+            handler: RequestHandler::new(provider),
+            subscriptions,
+        }
+    }
+
+    /// Returns the router that serves JSON-RPC requests.
+    pub fn router(&self) -> axum::Router {
+        server::router(self.handler.clone(), self.subscriptions.clone())
+    }
+
+    /// Serves JSON-RPC requests on the provided address until an error
+    /// occurs.
+    pub async fn serve(self, address: SocketAddr) -> Result<(), axum::Error> {
+        axum::Server::try_bind(&address)
+            .map_err(axum::Error::new)?
+            .serve(self.router().into_make_service())
+            .await
+            .map_err(axum::Error::new)
+    }
+}
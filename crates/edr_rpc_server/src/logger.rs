@@ -0,0 +1,44 @@
+use std::convert::Infallible;
+
+use edr_evm::blockchain::BlockchainError;
+use edr_provider::{Logger, ProviderError};
+
+/// A logger that reports handled methods using [`tracing`].
+#[derive(Clone, Debug)]
+pub struct TracingLogger {
+    is_enabled: bool,
+}
+
+impl Default for TracingLogger {
+    fn default() -> Self {
+        Self { is_enabled: true }
+    }
+}
+
+impl Logger for TracingLogger {
+    type BlockchainError = BlockchainError;
+
+    type LoggerError = Infallible;
+
+    fn is_enabled(&self) -> bool {
+        self.is_enabled
+    }
+
+    fn set_is_enabled(&mut self, is_enabled: bool) {
+        self.is_enabled = is_enabled;
+    }
+
+    fn print_method_logs(
+        &mut self,
+        method: &str,
+        error: Option<&ProviderError<Infallible>>,
+    ) -> Result<(), Infallible> {
+        if let Some(error) = error {
+            tracing::warn!(method, %error, "Request failed");
+        } else {
+            tracing::info!(method);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,397 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    net::SocketAddr,
+    path::PathBuf,
+    str::FromStr,
+    time::SystemTime,
+};
+
+use clap::{ArgEnum, Parser};
+use edr_eth::{
+    block::BlobGas, signature::secret_key_from_str, Address, HashMap, SpecId, B256, U256,
+};
+use edr_evm::blockchain::GenesisBlockOptions;
+use edr_provider::{
+    hardhat_rpc_types::ForkConfig, AccountConfig, FinalityConfig, JsonLogger, MethodInvocation,
+    MiningConfig, Provider, ProviderConfig, ProviderRequest,
+};
+use edr_rpc_server::{Server, TracingLogger};
+use tokio::runtime;
+
+const DEFAULT_COINBASE: &str = "0xc014ba5ec014ba5ec014ba5ec014ba5ec014ba5e";
+
+#[derive(Parser)]
+#[clap(name = "edr", version, about = "Serves an EDR provider over JSON-RPC")]
+struct Args {
+    /// The address to listen on
+    #[clap(long, default_value = "127.0.0.1:8545")]
+    address: SocketAddr,
+    /// The chain ID of the local blockchain
+    #[clap(long, default_value = "31337")]
+    chain_id: u64,
+    /// The hardfork of the local blockchain
+    #[clap(long, arg_enum, default_value = "shanghai")]
+    hardfork: Hardfork,
+    /// The block gas limit
+    #[clap(long, default_value = "30000000")]
+    block_gas_limit: u64,
+    /// The URL of a JSON-RPC endpoint to fork from
+    #[clap(long)]
+    fork_url: Option<String>,
+    /// The block number to fork from. Defaults to the latest safe block.
+    /// Ignored if `--fork-url` is not provided.
+    #[clap(long)]
+    fork_block_number: Option<u64>,
+    /// Disables mining a block for each transaction
+    #[clap(long)]
+    no_automine: bool,
+    /// The directory used for caching remote data
+    #[clap(long, default_value = edr_defaults::CACHE_DIR)]
+    cache_dir: PathBuf,
+    /// The path of a state dump, created with `hardhat_dumpState`, to start
+    /// from
+    #[clap(long)]
+    state_path: Option<PathBuf>,
+    /// The path of a geth-style genesis.json to build the local blockchain's
+    /// genesis block and state from. Overrides `--chain-id`, `--hardfork` and
+    /// `--block-gas-limit`.
+    #[clap(long)]
+    genesis: Option<PathBuf>,
+    /// Writes the latest state, e.g. restored with `--state-path`, to the
+    /// provided path as a geth-style genesis.json and exits, instead of
+    /// serving requests
+    #[clap(long)]
+    dump_genesis: Option<PathBuf>,
+    /// Runs an OP-stack chain, with deposit transactions and L1 data fees.
+    /// Implied when forking a supported OP-stack chain.
+    #[clap(long)]
+    optimism: bool,
+    /// Handles calls to Foundry's cheatcode address, allowing contracts to
+    /// use cheatcodes like `vm.warp` and `vm.prank`
+    #[clap(long)]
+    enable_cheatcodes: bool,
+    /// Collects code coverage of executed transactions and calls, which can be
+    /// retrieved with `hardhat_getCoverage`
+    #[clap(long)]
+    enable_coverage: bool,
+    /// The number of blocks by which the `safe` block tag lags behind the
+    /// latest block. If neither lag is provided, finality isn't simulated:
+    /// the `safe` and `finalized` tags alias the latest block and
+    /// `hardhat_reorg` can replace any local block.
+    #[clap(long)]
+    safe_block_lag: Option<u64>,
+    /// The number of blocks by which the `finalized` block tag lags behind the
+    /// latest block. Blocks at or below the finalized block can't be replaced
+    /// by `hardhat_reorg`. Defaults to the safe block lag and must not be
+    /// smaller than it.
+    #[clap(long)]
+    finalized_block_lag: Option<u64>,
+    /// The format of the provider's logs. `json` writes one JSON object per
+    /// line for each handled method, mined block and transaction.
+    #[clap(long, arg_enum, default_value = "text")]
+    log_format: LogFormat,
+    /// The file to append JSON logs to, instead of writing them to stdout.
+    /// Requires `--log-format json`.
+    #[clap(long)]
+    log_file: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ArgEnum)]
+enum LogFormat {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, ArgEnum)]
+enum Hardfork {
+    London,
+    ArrowGlacier,
+    GrayGlacier,
+    Merge,
+    Shanghai,
+    Cancun,
+}
+
+impl From<Hardfork> for SpecId {
+    fn from(value: Hardfork) -> Self {
+        match value {
+            Hardfork::London => SpecId::LONDON,
+            Hardfork::ArrowGlacier => SpecId::ARROW_GLACIER,
+            Hardfork::GrayGlacier => SpecId::GRAY_GLACIER,
+            Hardfork::Merge => SpecId::MERGE,
+            Hardfork::Shanghai => SpecId::SHANGHAI,
+            Hardfork::Cancun => SpecId::CANCUN,
+        }
+    }
+}
+
+impl Args {
+    fn provider_config(&self) -> anyhow::Result<ProviderConfig> {
+        // 10,000 ETH
+        let balance = U256::from(10_000u64) * U256::from(10).pow(U256::from(18));
+
+        let accounts = edr_defaults::SECRET_KEYS
+            .iter()
+            .map(|secret_key| {
+                Ok(AccountConfig {
+                    secret_key: secret_key_from_str(secret_key)?,
+                    balance,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let finality = if self.safe_block_lag.is_some() || self.finalized_block_lag.is_some() {
+            let safe_block_lag = self.safe_block_lag.unwrap_or(0);
+            // The finalized block is never newer than the safe block
+            let finalized_block_lag = self.finalized_block_lag.unwrap_or(safe_block_lag);
+
+            anyhow::ensure!(
+                safe_block_lag <= finalized_block_lag,
+                "`--safe-block-lag` ({safe_block_lag}) must not exceed `--finalized-block-lag` ({finalized_block_lag})"
+            );
+
+            Some(FinalityConfig {
+                safe_block_lag,
+                finalized_block_lag,
+            })
+        } else {
+            None
+        };
+
+        let mut config = ProviderConfig {
+            allow_blocks_with_same_timestamp: false,
+            allow_unlimited_contract_size: false,
+            accounts,
+            bail_on_call_failure: false,
+            bail_on_transaction_failure: false,
+            block_gas_limit: self.block_gas_limit,
+            cache_dir: self.cache_dir.clone(),
+            chain_id: self.chain_id,
+            chains: HashMap::new(),
+            coinbase: Address::from_str(DEFAULT_COINBASE)?,
+            enable_cheatcodes: self.enable_cheatcodes,
+            enable_coverage: self.enable_coverage,
+            finality,
+            fork: self.fork_url.clone().map(|json_rpc_url| ForkConfig {
+                json_rpc_url,
+                block_number: self.fork_block_number,
+                http_headers: None,
+            }),
+            genesis_accounts: HashMap::new(),
+            genesis_block: GenesisBlockOptions::default(),
+            genesis_storage: HashMap::new(),
+            hardfork: self.hardfork.into(),
+            initial_base_fee_per_gas: None,
+            initial_blob_gas: Some(BlobGas::default()),
+            initial_date: Some(SystemTime::now()),
+            initial_parent_beacon_block_root: Some(B256::ZERO),
+            min_gas_price: U256::ZERO,
+            mining: MiningConfig {
+                auto_mine: !self.no_automine,
+                ..MiningConfig::default()
+            },
+            network_id: self.chain_id,
+            optimism: self.optimism,
+            state_path: self.state_path.clone(),
+            withdrawal_schedule: None,
+        };
+
+        if let Some(genesis_path) = &self.genesis {
+            let genesis = serde_json::from_reader(BufReader::new(File::open(genesis_path)?))?;
+            config.apply_genesis(genesis);
+        }
+
+        Ok(config)
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    if args.log_file.is_some() && args.log_format != LogFormat::Json {
+        anyhow::bail!("`--log-file` requires `--log-format json`");
+    }
+
+    let subscriber = tracing_subscriber::fmt().with_env_filter(
+        tracing_subscriber::EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+    );
+
+    // Keep stdout free for JSON logs
+    if args.log_format == LogFormat::Json && args.log_file.is_none() {
+        subscriber.with_writer(std::io::stderr).init();
+    } else {
+        subscriber.init();
+    }
+
+    let config = args.provider_config()?;
+    if let Some(genesis_path) = &args.dump_genesis {
+        let genesis = tokio::task::spawn_blocking(move || {
+            let provider = Provider::new(
+                runtime::Handle::current(),
+                Box::new(TracingLogger::default()),
+                Box::new(|_| ()),
+                config,
+            )?;
+
+            let response = provider
+                .handle_request(ProviderRequest::Single(MethodInvocation::DumpGenesis(())))
+                .map_err(|error| anyhow::anyhow!("Failed to export genesis: {error}"))?;
+
+            anyhow::Ok(response.result)
+        })
+        .await??;
+
+        serde_json::to_writer_pretty(BufWriter::new(File::create(genesis_path)?), &genesis)?;
+        tracing::info!("Wrote genesis file to {}", genesis_path.display());
+
+        return Ok(());
+    }
+
+    let json_logger = match (args.log_format, &args.log_file) {
+        (LogFormat::Text, _) => None,
+        (LogFormat::Json, Some(log_file)) => Some(JsonLogger::with_file(log_file)?),
+        (LogFormat::Json, None) => Some(JsonLogger::new(std::io::stdout())),
+    };
+
+    // Creating the provider blocks on remote requests when forking.
+    let server = tokio::task::spawn_blocking(move || {
+        let runtime = runtime::Handle::current();
+        match json_logger {
+            Some(logger) => Server::with_logger(runtime, logger, config),
+            None => Server::with_logger(runtime, TracingLogger::default(), config),
+        }
+    })
+    .await??;
+
+    tracing::info!(
+        "Listening for JSON-RPC requests on http://{address} and ws://{address}",
+        address = args.address
+    );
+    server.serve(args.address).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use edr_rpc_server::{RequestHandler, SubscriptionRouter};
+    use serde_json::json;
+
+    use super::*;
+
+    fn create_handler(args: &[&str]) -> anyhow::Result<RequestHandler> {
+        let config = Args::try_parse_from(args)?.provider_config()?;
+
+        let provider = Provider::new(
+            runtime::Handle::current(),
+            Box::<TracingLogger>::default(),
+            SubscriptionRouter::default().callback(),
+            config,
+        )?;
+
+        Ok(RequestHandler::new(Arc::new(provider)))
+    }
+
+    async fn handle(
+        handler: &RequestHandler,
+        request: serde_json::Value,
+    ) -> anyhow::Result<serde_json::Value> {
+        let response = handler
+            .handle(&request.to_string())
+            .await
+            .ok_or_else(|| anyhow::anyhow!("a response should be returned"))?;
+
+        Ok(serde_json::from_str(&response)?)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn reorg_with_default_args() -> anyhow::Result<()> {
+        let handler = create_handler(&["edr"])?;
+
+        handle(
+            &handler,
+            json!({ "jsonrpc": "2.0", "method": "hardhat_mine", "params": ["0x2"], "id": 1 }),
+        )
+        .await?;
+
+        let response = handle(
+            &handler,
+            json!({ "jsonrpc": "2.0", "method": "hardhat_reorg", "params": ["0x2"], "id": 2 }),
+        )
+        .await?;
+
+        assert_eq!(response["result"], json!(true), "{response}");
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn reorg_respects_finalized_block_lag() -> anyhow::Result<()> {
+        let handler = create_handler(&["edr", "--finalized-block-lag", "1"])?;
+
+        handle(
+            &handler,
+            json!({ "jsonrpc": "2.0", "method": "hardhat_mine", "params": ["0x2"], "id": 1 }),
+        )
+        .await?;
+
+        let response = handle(
+            &handler,
+            json!({ "jsonrpc": "2.0", "method": "hardhat_reorg", "params": ["0x2"], "id": 2 }),
+        )
+        .await?;
+
+        assert!(response.get("error").is_some(), "{response}");
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn reorg_with_only_safe_block_lag() -> anyhow::Result<()> {
+        // The finalized block lags behind by the same number of blocks
+        let handler = create_handler(&["edr", "--safe-block-lag", "1"])?;
+
+        handle(
+            &handler,
+            json!({ "jsonrpc": "2.0", "method": "hardhat_mine", "params": ["0x3"], "id": 1 }),
+        )
+        .await?;
+
+        let response = handle(
+            &handler,
+            json!({ "jsonrpc": "2.0", "method": "eth_getBlockByNumber", "params": ["safe", false], "id": 2 }),
+        )
+        .await?;
+        assert_eq!(response["result"]["number"], json!("0x2"), "{response}");
+
+        let response = handle(
+            &handler,
+            json!({ "jsonrpc": "2.0", "method": "hardhat_reorg", "params": ["0x1"], "id": 3 }),
+        )
+        .await?;
+        assert_eq!(response["result"], json!(true), "{response}");
+
+        let response = handle(
+            &handler,
+            json!({ "jsonrpc": "2.0", "method": "hardhat_reorg", "params": ["0x2"], "id": 4 }),
+        )
+        .await?;
+        assert!(response.get("error").is_some(), "{response}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn safe_block_lag_must_not_exceed_finalized_block_lag() -> anyhow::Result<()> {
+        let args =
+            Args::try_parse_from(["edr", "--safe-block-lag", "2", "--finalized-block-lag", "1"])?;
+
+        assert!(args.provider_config().is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+
+use crate::{
+    handler::RequestHandler,
+    subscriptions::{Session, SubscriptionRouter},
+};
+
+#[derive(Clone)]
+struct ServerState {
+    handler: RequestHandler,
+    subscriptions: SubscriptionRouter,
+}
+
+/// Constructs a router that serves JSON-RPC over HTTP `POST` requests and
+/// WebSocket connections on the root path.
+pub(crate) fn router(handler: RequestHandler, subscriptions: SubscriptionRouter) -> Router {
+    Router::new()
+        .route("/", get(handle_websocket_upgrade).post(handle_http))
+        .with_state(ServerState {
+            handler,
+            subscriptions,
+        })
+}
+
+async fn handle_http(State(state): State<ServerState>, body: String) -> Response {
+    match state.handler.handle(&body).await {
+        Some(json_response) => (
+            [(header::CONTENT_TYPE, "application/json")],
+            json_response,
+        )
+            .into_response(),
+        None => StatusCode::NO_CONTENT.into_response(),
+    }
+}
+
+async fn handle_websocket_upgrade(
+    State(state): State<ServerState>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    upgrade.on_upgrade(move |socket| handle_websocket(socket, state))
+}
+
+async fn handle_websocket(mut socket: WebSocket, state: ServerState) {
+    let (session, mut notifications) = Session::new(state.subscriptions.clone());
+
+    loop {
+        tokio::select! {
+            message = socket.recv() => {
+                let json_request = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Binary(bytes))) => String::from_utf8_lossy(&bytes).into_owned(),
+                    // Pings are answered automatically
+                    Some(Ok(Message::Ping(_) | Message::Pong(_))) => continue,
+                    Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                };
+
+                if let Some(json_response) = state
+                    .handler
+                    .handle_with_session(&json_request, Some(&session))
+                    .await
+                {
+                    if socket.send(Message::Text(json_response)).await.is_err() {
+                        break;
+                    }
+                }
+            }
+            // The session owns a sender, so the channel is never closed.
+            Some(notification) = notifications.recv() => {
+                if socket.send(Message::Text(notification)).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    state
+        .handler
+        .unsubscribe(session.take_subscriptions())
+        .await;
+}
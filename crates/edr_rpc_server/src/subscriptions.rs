@@ -0,0 +1,269 @@
+use std::{collections::HashMap, sync::Arc};
+
+use edr_eth::{remote::eth, B256, U256};
+use edr_provider::{SubscriptionEvent, SubscriptionEventData, SyncSubscriberCallback};
+use parking_lot::Mutex;
+use tokio::sync::mpsc;
+
+/// Routes subscription events emitted by the provider to the WebSocket
+/// connection that created the subscription.
+#[derive(Clone, Debug, Default)]
+pub struct SubscriptionRouter {
+    routes: Arc<Mutex<Routes>>,
+    /// Serializes the creation of subscriptions, so events emitted before a
+    /// subscription is routed can be attributed to it.
+    subscribe_lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+#[derive(Debug, Default)]
+struct Routes {
+    senders: HashMap<U256, mpsc::UnboundedSender<String>>,
+    /// Notifications of unrouted subscriptions, buffered while a subscription
+    /// is being created.
+    pending: Option<Vec<(U256, String)>>,
+}
+
+impl SubscriptionRouter {
+    /// Constructs a subscriber callback for a provider, which forwards events
+    /// to the subscribed connections.
+    pub fn callback(&self) -> Box<dyn SyncSubscriberCallback> {
+        let router = self.clone();
+        Box::new(move |event: SubscriptionEvent| router.dispatch(event))
+    }
+
+    fn dispatch(&self, event: SubscriptionEvent) {
+        let mut routes = self.routes.lock();
+        let filter_id = event.filter_id;
+
+        if let Some(sender) = routes.senders.get(&filter_id) {
+            for notification in notifications(event) {
+                // The connection might have closed in the meantime, in which case its
+                // subscriptions are about to be removed.
+                let _result = sender.send(notification);
+            }
+        } else if let Some(pending) = routes.pending.as_mut() {
+            pending.extend(
+                notifications(event)
+                    .into_iter()
+                    .map(|notification| (filter_id, notification)),
+            );
+        }
+    }
+}
+
+/// The subscriptions of a single WebSocket connection.
+#[derive(Debug)]
+pub(crate) struct Session {
+    router: SubscriptionRouter,
+    sender: mpsc::UnboundedSender<String>,
+    filter_ids: Mutex<Vec<U256>>,
+}
+
+impl Session {
+    /// Constructs a new session, returning the receiver of its notifications.
+    pub fn new(router: SubscriptionRouter) -> (Self, mpsc::UnboundedReceiver<String>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        let session = Self {
+            router,
+            sender,
+            filter_ids: Mutex::new(Vec::new()),
+        };
+
+        (session, receiver)
+    }
+
+    /// Prepares the creation of a subscription. Events emitted before the
+    /// subscription is completed are buffered and routed to this session once
+    /// its ID is known.
+    pub async fn begin_subscription(&self) -> PendingSubscription<'_> {
+        let guard = self.router.subscribe_lock.clone().lock_owned().await;
+        self.router.routes.lock().pending = Some(Vec::new());
+
+        PendingSubscription {
+            session: self,
+            _guard: guard,
+        }
+    }
+
+    /// Returns whether the subscription with the provided ID belongs to this
+    /// session.
+    pub fn has_subscription(&self, filter_id: &U256) -> bool {
+        self.filter_ids.lock().contains(filter_id)
+    }
+
+    /// Stops routing events of the subscription with the provided ID, if it
+    /// belongs to this session.
+    pub fn remove_subscription(&self, filter_id: &U256) {
+        let mut filter_ids = self.filter_ids.lock();
+        if let Some(index) = filter_ids.iter().position(|id| id == filter_id) {
+            filter_ids.swap_remove(index);
+            self.router.routes.lock().senders.remove(filter_id);
+        }
+    }
+
+    /// Removes all subscriptions of this session, returning their IDs.
+    pub fn take_subscriptions(&self) -> Vec<U256> {
+        let filter_ids = std::mem::take(&mut *self.filter_ids.lock());
+
+        let mut routes = self.router.routes.lock();
+        for filter_id in &filter_ids {
+            routes.senders.remove(filter_id);
+        }
+
+        filter_ids
+    }
+}
+
+/// A subscription of a [`Session`] that is being created.
+pub(crate) struct PendingSubscription<'session> {
+    session: &'session Session,
+    _guard: tokio::sync::OwnedMutexGuard<()>,
+}
+
+impl PendingSubscription<'_> {
+    /// Routes events of the subscription with the provided ID to the session,
+    /// including the events that were emitted while it was being created.
+    pub fn complete(self, filter_id: U256) {
+        let mut routes = self.session.router.routes.lock();
+
+        let buffered = routes.pending.take().unwrap_or_default();
+        for (_, notification) in buffered.into_iter().filter(|(id, _)| *id == filter_id) {
+            let _result = self.session.sender.send(notification);
+        }
+
+        routes
+            .senders
+            .insert(filter_id, self.session.sender.clone());
+        drop(routes);
+
+        self.session.filter_ids.lock().push(filter_id);
+    }
+}
+
+impl Drop for PendingSubscription<'_> {
+    fn drop(&mut self) {
+        self.session.router.routes.lock().pending = None;
+    }
+}
+
+/// Converts a subscription event into `eth_subscription` notifications. Logs
+/// are sent as one notification per log.
+fn notifications(event: SubscriptionEvent) -> Vec<String> {
+    let SubscriptionEvent { filter_id, result } = event;
+
+    let results = match result {
+        SubscriptionEventData::Logs(logs) => logs.iter().map(serde_json::to_value).collect(),
+        SubscriptionEventData::NewHeads(block) => {
+            serde_json::to_value(eth::Block::<B256>::from(block)).map(|block| vec![block])
+        }
+        SubscriptionEventData::NewPendingTransactions(transaction_hash) => {
+            serde_json::to_value(transaction_hash).map(|hash| vec![hash])
+        }
+    };
+
+    match results {
+        Ok(results) => results
+            .into_iter()
+            .map(|result| {
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "method": "eth_subscription",
+                    "params": {
+                        "subscription": filter_id,
+                        "result": result,
+                    },
+                })
+                .to_string()
+            })
+            .collect(),
+        Err(error) => {
+            tracing::error!(%filter_id, %error, "Failed to serialize subscription event");
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn routes_only_subscribed_events() {
+        let router = SubscriptionRouter::default();
+        let callback = router.callback();
+
+        let (session, mut notifications) = Session::new(router);
+        session.begin_subscription().await.complete(U256::from(1));
+
+        let transaction_hash = B256::repeat_byte(0x11);
+        for filter_id in [U256::from(1), U256::from(2)] {
+            callback(SubscriptionEvent {
+                filter_id,
+                result: SubscriptionEventData::NewPendingTransactions(transaction_hash),
+            });
+        }
+
+        let notification: serde_json::Value =
+            serde_json::from_str(&notifications.try_recv().expect("notification was routed"))
+                .expect("notification is valid JSON");
+
+        assert_eq!(notification["method"], "eth_subscription");
+        assert_eq!(notification["params"]["subscription"], "0x1");
+        assert_eq!(
+            notification["params"]["result"],
+            serde_json::to_value(transaction_hash).unwrap()
+        );
+        assert!(notifications.try_recv().is_err());
+
+        assert_eq!(session.take_subscriptions(), vec![U256::from(1)]);
+        callback(SubscriptionEvent {
+            filter_id: U256::from(1),
+            result: SubscriptionEventData::NewPendingTransactions(transaction_hash),
+        });
+        assert!(notifications.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn routes_events_emitted_during_subscription() {
+        let router = SubscriptionRouter::default();
+        let callback = router.callback();
+
+        let (session, mut notifications) = Session::new(router);
+        let pending = session.begin_subscription().await;
+
+        let transaction_hash = B256::repeat_byte(0x22);
+        for filter_id in [U256::from(1), U256::from(2)] {
+            callback(SubscriptionEvent {
+                filter_id,
+                result: SubscriptionEventData::NewPendingTransactions(transaction_hash),
+            });
+        }
+        assert!(notifications.try_recv().is_err());
+
+        pending.complete(U256::from(2));
+
+        let notification: serde_json::Value =
+            serde_json::from_str(&notifications.try_recv().expect("notification was buffered"))
+                .expect("notification is valid JSON");
+
+        assert_eq!(notification["params"]["subscription"], "0x2");
+        assert!(notifications.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn sessions_only_remove_own_subscriptions() {
+        let router = SubscriptionRouter::default();
+
+        let (first, _first_notifications) = Session::new(router.clone());
+        let (second, _second_notifications) = Session::new(router.clone());
+        first.begin_subscription().await.complete(U256::from(1));
+
+        assert!(first.has_subscription(&U256::from(1)));
+        assert!(!second.has_subscription(&U256::from(1)));
+
+        second.remove_subscription(&U256::from(1));
+        assert!(router.routes.lock().senders.contains_key(&U256::from(1)));
+        assert_eq!(first.take_subscriptions(), vec![U256::from(1)]);
+    }
+}
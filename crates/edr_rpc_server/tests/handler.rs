@@ -0,0 +1,162 @@
+use std::{sync::Arc, time::SystemTime};
+
+use edr_eth::{block::BlobGas, Address, HashMap, SpecId, B256, U256};
+use edr_evm::blockchain::GenesisBlockOptions;
+use edr_provider::{MiningConfig, Provider, ProviderConfig};
+use edr_rpc_server::{RequestHandler, SubscriptionRouter, TracingLogger};
+use serde_json::json;
+use tokio::runtime;
+
+fn create_handler() -> RequestHandler {
+    let config = ProviderConfig {
+        allow_blocks_with_same_timestamp: false,
+        allow_unlimited_contract_size: false,
+        accounts: Vec::new(),
+        bail_on_call_failure: false,
+        bail_on_transaction_failure: false,
+        block_gas_limit: 30_000_000,
+        cache_dir: edr_defaults::CACHE_DIR.into(),
+        chain_id: 123,
+        chains: HashMap::new(),
+        coinbase: Address::ZERO,
+        enable_cheatcodes: false,
+        enable_coverage: false,
+        finality: None,
+        fork: None,
+        genesis_accounts: HashMap::new(),
+        genesis_block: GenesisBlockOptions::default(),
+        genesis_storage: HashMap::new(),
+        hardfork: SpecId::SHANGHAI,
+        initial_base_fee_per_gas: None,
+        initial_blob_gas: Some(BlobGas::default()),
+        initial_date: Some(SystemTime::now()),
+        initial_parent_beacon_block_root: Some(B256::ZERO),
+        min_gas_price: U256::ZERO,
+        mining: MiningConfig::default(),
+        network_id: 123,
+        optimism: false,
+        state_path: None,
+        withdrawal_schedule: None,
+    };
+
+    let provider = Provider::new(
+        runtime::Handle::current(),
+        Box::<TracingLogger>::default(),
+        SubscriptionRouter::default().callback(),
+        config,
+    )
+    .expect("provider should be created");
+
+    RequestHandler::new(Arc::new(provider))
+}
+
+async fn handle(handler: &RequestHandler, request: serde_json::Value) -> serde_json::Value {
+    let response = handler
+        .handle(&request.to_string())
+        .await
+        .expect("a response should be returned");
+
+    serde_json::from_str(&response).expect("response should be valid JSON")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn single_request() {
+    let handler = create_handler();
+
+    let response = handle(
+        &handler,
+        json!({ "jsonrpc": "2.0", "method": "eth_chainId", "params": [], "id": "foo" }),
+    )
+    .await;
+
+    assert_eq!(
+        response,
+        json!({ "jsonrpc": "2.0", "id": "foo", "result": "0x7b" })
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn batch_request_with_notification() {
+    let handler = create_handler();
+
+    let response = handle(
+        &handler,
+        json!([
+            { "jsonrpc": "2.0", "method": "eth_chainId", "params": [], "id": 1 },
+            { "jsonrpc": "2.0", "method": "eth_blockNumber", "params": [] },
+            { "jsonrpc": "2.0", "method": "eth_blockNumber", "params": [], "id": 2 },
+        ]),
+    )
+    .await;
+
+    assert_eq!(
+        response,
+        json!([
+            { "jsonrpc": "2.0", "id": 1, "result": "0x7b" },
+            { "jsonrpc": "2.0", "id": 2, "result": "0x0" },
+        ])
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn notification_without_response() {
+    let handler = create_handler();
+
+    let response = handler
+        .handle(&json!({ "jsonrpc": "2.0", "method": "eth_chainId", "params": [] }).to_string())
+        .await;
+
+    assert!(response.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn error_codes() {
+    let handler = create_handler();
+
+    let response: serde_json::Value =
+        serde_json::from_str(&handler.handle("{").await.expect("parse error response"))
+            .expect("response should be valid JSON");
+    assert_eq!(response["id"], serde_json::Value::Null);
+    assert_eq!(response["error"]["code"], -32700);
+
+    let response = handle(&handler, json!([])).await;
+    assert_eq!(response["error"]["code"], -32600);
+
+    let response = handle(
+        &handler,
+        json!({ "jsonrpc": "1.0", "method": "eth_chainId", "params": [], "id": 1 }),
+    )
+    .await;
+    assert_eq!(response["error"]["code"], -32600);
+
+    let response = handle(
+        &handler,
+        json!({ "jsonrpc": "2.0", "method": "eth_foo", "params": [], "id": 1 }),
+    )
+    .await;
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["error"]["code"], -32004);
+
+    let response = handle(
+        &handler,
+        json!({ "jsonrpc": "2.0", "method": "eth_subscribe", "params": ["newHeads"], "id": 1 }),
+    )
+    .await;
+    assert_eq!(response["error"]["code"], -32601);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unsubscribe_without_session() {
+    let handler = create_handler();
+
+    let response = handle(
+        &handler,
+        json!({ "jsonrpc": "2.0", "method": "eth_unsubscribe", "params": ["0x1"], "id": 1 }),
+    )
+    .await;
+
+    assert_eq!(
+        response,
+        json!({ "jsonrpc": "2.0", "id": 1, "result": false })
+    );
+}
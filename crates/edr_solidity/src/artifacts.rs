@@ -0,0 +1,189 @@
+use edr_eth::HashMap;
+use serde::{Deserialize, Serialize};
+
+/// Standard JSON input of the Solidity compiler.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompilerInput {
+    /// The source code language
+    pub language: String,
+    /// mapping: source name -> source
+    pub sources: HashMap<String, CompilerInputSource>,
+    /// The compiler settings
+    pub settings: CompilerSettings,
+}
+
+/// A source file that was provided to the compiler.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompilerInputSource {
+    /// The source code
+    pub content: String,
+}
+
+/// Settings of the Solidity compiler.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompilerSettings {
+    /// Whether the IR-based code generator was used
+    #[serde(rename = "viaIR")]
+    pub via_ir: Option<bool>,
+    /// The optimizer settings
+    pub optimizer: OptimizerSettings,
+    /// The metadata settings
+    pub metadata: Option<MetadataSettings>,
+    /// mapping: source name -> (mapping: contract name -> compiler output
+    /// selections)
+    pub output_selection: HashMap<String, HashMap<String, Vec<String>>>,
+    /// The targeted EVM version
+    pub evm_version: Option<String>,
+    /// mapping: library file name -> (mapping: library name -> library
+    /// content)
+    pub libraries: Option<HashMap<String, HashMap<String, String>>>,
+    /// Import remappings
+    pub remappings: Option<Vec<String>>,
+}
+
+/// Settings of the Solidity optimizer.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimizerSettings {
+    /// The number of runs to optimize for
+    pub runs: Option<usize>,
+    /// Whether the optimizer is enabled
+    pub enabled: Option<bool>,
+    /// Detailed optimizer settings
+    pub details: Option<OptimizerDetails>,
+}
+
+/// Detailed settings of the Solidity optimizer.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimizerDetails {
+    /// Settings of the Yul optimizer
+    pub yul_details: YulDetails,
+}
+
+/// Settings of the Yul optimizer.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YulDetails {
+    /// The sequence of optimization steps
+    pub optimizer_steps: String,
+}
+
+/// Settings of the contract metadata.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataSettings {
+    /// Whether the metadata contains the source content
+    pub use_literal_content: bool,
+}
+
+/// Standard JSON output of the Solidity compiler.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompilerOutput {
+    /// mapping: source name -> CompilerOutputSource
+    pub sources: HashMap<String, CompilerOutputSource>,
+    /// mapping: source name -> (mapping: contract name ->
+    /// CompilerOutputContract)
+    pub contracts: HashMap<String, HashMap<String, CompilerOutputContract>>,
+}
+
+/// Compiler output for a single source file.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompilerOutputSource {
+    /// The source file's ID, as used in source maps
+    pub id: usize,
+    /// The source file's AST
+    pub ast: serde_json::Value,
+}
+
+/// Compiler output for a single contract.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompilerOutputContract {
+    /// The contract's ABI
+    pub abi: serde_json::Value,
+    /// EVM-related outputs
+    pub evm: CompilerOutputContractEvm,
+}
+
+/// EVM-related compiler output for a single contract.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompilerOutputContractEvm {
+    /// The deployment bytecode
+    pub bytecode: CompilerOutputBytecode,
+    /// The runtime bytecode
+    pub deployed_bytecode: CompilerOutputBytecode,
+    /// mapping: method signature -> method identifier
+    pub method_identifiers: HashMap<String, String>,
+}
+
+/// Compiler output of a bytecode.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompilerOutputBytecode {
+    /// The hex-encoded bytecode, possibly containing library placeholders
+    pub object: String,
+    /// The opcodes as a string
+    pub opcodes: String,
+    /// The compressed source map
+    pub source_map: String,
+    /// mapping: source name -> (mapping: library name -> LinkReferences)
+    pub link_references: HashMap<String, HashMap<String, Vec<LinkReference>>>,
+    /// mapping: AST id -> references to the immutable in the runtime bytecode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub immutable_references: Option<HashMap<String, Vec<ImmutableReferenceLocation>>>,
+}
+
+mod u64_that_must_be_20 {
+    pub fn serialize<S>(val: &u64, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if *val == 20 {
+            s.serialize_u64(*val)
+        } else {
+            use serde::ser::Error;
+            Err(S::Error::custom("value must be 20"))
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u64, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s: u64 = serde::de::Deserialize::deserialize(deserializer)?;
+        if s == 20 {
+            Ok(s)
+        } else {
+            use serde::de::Error;
+            Err(D::Error::custom("value must be 20"))
+        }
+    }
+}
+
+/// The location of a library address placeholder in a bytecode.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkReference {
+    /// Byte offset of the placeholder
+    pub start: usize,
+    /// Length of the placeholder in bytes, which is always 20
+    #[serde(with = "u64_that_must_be_20")]
+    pub length: u64,
+}
+
+/// The location of an immutable value in a runtime bytecode.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImmutableReferenceLocation {
+    /// Byte offset of the value
+    pub start: usize,
+    /// Length of the value in bytes
+    pub length: usize,
+}
@@ -1,11 +1,489 @@
-/// Temporary stub
+use std::sync::Arc;
+
+use edr_eth::{Bytes, HashMap};
+
+use crate::{
+    artifacts::{CompilerInput, CompilerOutput},
+    compiler,
+};
+
+/// Source files of a compilation job, indexed by the ID that the compiler
+/// assigned to them.
+pub type SourceFiles = HashMap<u32, SourceFile>;
+
+/// The model of a single compilation job.
+#[derive(Debug)]
 pub struct BuildModel {
-    /// Temporary stub
-    pub source_files: Vec<SourceFile>,
+    /// The version of the compiler
+    pub solc_version: String,
+    /// The compiled source files
+    pub source_files: Arc<SourceFiles>,
+    /// The deployment and runtime bytecodes of all non-abstract contracts
+    pub bytecodes: Vec<Arc<Bytecode>>,
+}
+
+impl BuildModel {
+    /// Constructs the model of a compilation job from the compiler's standard
+    /// JSON input and output.
+    pub fn new(
+        solc_version: String,
+        compiler_input: &CompilerInput,
+        compiler_output: &CompilerOutput,
+    ) -> Result<Self, BuildModelError> {
+        compiler::create_model(solc_version, compiler_input, compiler_output)
+    }
+}
+
+/// An error that occurred while building a [`BuildModel`].
+#[derive(Debug, thiserror::Error)]
+pub enum BuildModelError {
+    /// An AST node is missing a required field.
+    #[error("Invalid AST node: missing or invalid field `{0}`")]
+    InvalidAstNode(&'static str),
+    /// A source location in the AST could not be parsed.
+    #[error("Invalid source location: {0}")]
+    InvalidSourceLocation(String),
+    /// The compiler output contains a source that is not in the compiler
+    /// input.
+    #[error("Source `{0}` is missing from the compiler input")]
+    MissingSource(String),
+    /// A bytecode object is not valid hex.
+    #[error("Invalid bytecode of contract `{contract}`: {error}")]
+    InvalidBytecode {
+        /// The name of the contract
+        contract: String,
+        /// The decoding error
+        error: hex::FromHexError,
+    },
 }
 
-/// Temporary stub
+/// A Solidity source file.
+#[derive(Debug, PartialEq)]
 pub struct SourceFile {
-    /// Temporary stub
+    /// The source name, as used by the compiler
     pub source_name: String,
+    /// The source code
+    pub content: String,
+    functions: Vec<Arc<ContractFunction>>,
+    statements: Vec<SourceLocation>,
+    branches: Vec<Branch>,
+}
+
+impl SourceFile {
+    pub(crate) fn new(source_name: String, content: String) -> Self {
+        Self {
+            source_name,
+            content,
+            functions: Vec::new(),
+            statements: Vec::new(),
+            branches: Vec::new(),
+        }
+    }
+
+    pub(crate) fn add_function(&mut self, function: Arc<ContractFunction>) {
+        self.functions.push(function);
+    }
+
+    pub(crate) fn add_statement(&mut self, location: SourceLocation) {
+        self.statements.push(location);
+    }
+
+    pub(crate) fn add_branch(&mut self, branch: Branch) {
+        self.branches.push(branch);
+    }
+
+    /// The functions, modifiers and getters defined in the file.
+    pub fn functions(&self) -> &[Arc<ContractFunction>] {
+        &self.functions
+    }
+
+    /// The locations of all statements in the file, including nested ones.
+    pub fn statements(&self) -> &[SourceLocation] {
+        &self.statements
+    }
+
+    /// The `if` statements and conditional expressions in the file.
+    pub fn branches(&self) -> &[Branch] {
+        &self.branches
+    }
+
+    /// Returns the innermost function that contains the provided location.
+    pub fn function_containing(&self, location: &SourceLocation) -> Option<&Arc<ContractFunction>> {
+        self.functions
+            .iter()
+            .filter(|function| function.location.contains(location))
+            .min_by_key(|function| function.location.length)
+    }
+
+    /// Returns the 1-based line number of the provided byte offset.
+    pub fn line_number(&self, offset: u32) -> u32 {
+        let end = (offset as usize).min(self.content.len());
+        let newlines = self.content.as_bytes()[..end]
+            .iter()
+            .filter(|byte| **byte == b'\n')
+            .count();
+
+        1 + newlines as u32
+    }
+}
+
+/// A range of bytes in a source file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SourceLocation {
+    /// The ID of the source file
+    pub file_id: u32,
+    /// Byte offset of the start of the range
+    pub offset: u32,
+    /// Length of the range in bytes
+    pub length: u32,
+}
+
+impl SourceLocation {
+    /// Whether this location fully contains the other location.
+    pub fn contains(&self, other: &SourceLocation) -> bool {
+        self.file_id == other.file_id
+            && other.offset >= self.offset
+            && other.offset + other.length <= self.offset + self.length
+    }
+}
+
+/// A point where execution branches, i.e. an `if` statement or a conditional
+/// expression.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Branch {
+    /// The location of the whole statement or expression
+    pub location: SourceLocation,
+    /// The location of the condition
+    pub condition: SourceLocation,
+    /// The location of the code that is executed if the condition holds
+    pub true_body: SourceLocation,
+    /// The location of the code that is executed otherwise, if any
+    pub false_body: Option<SourceLocation>,
+}
+
+/// The kind of a contract.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContractKind {
+    /// A regular contract
+    Contract,
+    /// A library
+    Library,
+}
+
+/// A contract or library definition.
+#[derive(Debug, PartialEq)]
+pub struct Contract {
+    /// The name of the contract
+    pub name: String,
+    /// The kind of the contract
+    pub kind: ContractKind,
+    /// The location of the contract's definition
+    pub location: SourceLocation,
+    /// The functions, modifiers and getters defined by the contract itself
+    pub local_functions: Vec<Arc<ContractFunction>>,
+    /// The custom errors that the contract can revert with
+    pub custom_errors: Vec<CustomError>,
+    /// The constructor, if defined
+    pub constructor: Option<Arc<ContractFunction>>,
+    /// The fallback function, if defined or inherited
+    pub fallback: Option<Arc<ContractFunction>>,
+    /// The receive function, if defined or inherited
+    pub receive: Option<Arc<ContractFunction>>,
+    selector_to_function: HashMap<[u8; 4], Arc<ContractFunction>>,
+}
+
+impl Contract {
+    pub(crate) fn new(name: String, kind: ContractKind, location: SourceLocation) -> Self {
+        Self {
+            name,
+            kind,
+            location,
+            local_functions: Vec::new(),
+            custom_errors: Vec::new(),
+            constructor: None,
+            fallback: None,
+            receive: None,
+            selector_to_function: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn add_local_function(&mut self, function: Arc<ContractFunction>) {
+        if function.is_externally_callable() {
+            if let Some(selector) = function.selector {
+                self.selector_to_function.insert(selector, function.clone());
+            }
+        }
+
+        match function.function_type {
+            ContractFunctionType::Constructor => self.constructor = Some(function.clone()),
+            ContractFunctionType::Fallback => self.fallback = Some(function.clone()),
+            ContractFunctionType::Receive => self.receive = Some(function.clone()),
+            _ => (),
+        }
+
+        self.local_functions.push(function);
+    }
+
+    /// Inherits the fallback, receive and external functions of a base
+    /// contract. Base contracts need to be added in the order of
+    /// linearization, as earlier definitions take precedence.
+    pub(crate) fn add_next_linearized_base_contract(&mut self, base_contract: &Contract) {
+        if self.fallback.is_none() {
+            self.fallback = base_contract.fallback.clone();
+        }
+
+        if self.receive.is_none() {
+            self.receive = base_contract.receive.clone();
+        }
+
+        for function in &base_contract.local_functions {
+            if !matches!(
+                function.function_type,
+                ContractFunctionType::Function | ContractFunctionType::Getter
+            ) || !function.is_externally_callable()
+            {
+                continue;
+            }
+
+            if let Some(selector) = function.selector {
+                self.selector_to_function
+                    .entry(selector)
+                    .or_insert_with(|| function.clone());
+            }
+        }
+    }
+
+    /// Returns the external function with the provided selector.
+    pub fn function_from_selector(&self, selector: &[u8]) -> Option<&Arc<ContractFunction>> {
+        let selector: [u8; 4] = selector.try_into().ok()?;
+        self.selector_to_function.get(&selector)
+    }
+}
+
+/// The type of a [`ContractFunction`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ContractFunctionType {
+    /// A constructor
+    Constructor,
+    /// A regular function
+    Function,
+    /// A fallback function
+    Fallback,
+    /// A receive function
+    Receive,
+    /// The getter of a public state variable
+    Getter,
+    /// A modifier
+    Modifier,
+    /// A function defined outside of a contract
+    FreeFunction,
+}
+
+/// The visibility of a [`ContractFunction`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContractFunctionVisibility {
+    /// Only visible within the contract
+    Private,
+    /// Visible within the contract and derived contracts
+    Internal,
+    /// Visible internally and externally
+    Public,
+    /// Only visible externally
+    External,
+}
+
+/// A function-like definition in a source file.
+#[derive(Debug, PartialEq)]
+pub struct ContractFunction {
+    /// The name of the function
+    pub name: String,
+    /// The type of the function
+    pub function_type: ContractFunctionType,
+    /// The location of the function's definition
+    pub location: SourceLocation,
+    /// The name of the contract that defines the function, if any
+    pub contract_name: Option<String>,
+    /// The visibility of the function, if any
+    pub visibility: Option<ContractFunctionVisibility>,
+    /// Whether the function accepts value
+    pub is_payable: bool,
+    /// The function's selector, if it can be called externally
+    pub selector: Option<[u8; 4]>,
+}
+
+impl ContractFunction {
+    fn is_externally_callable(&self) -> bool {
+        matches!(
+            self.visibility,
+            Some(ContractFunctionVisibility::Public | ContractFunctionVisibility::External)
+        )
+    }
+}
+
+/// A custom error that a contract can revert with.
+#[derive(Debug, PartialEq)]
+pub struct CustomError {
+    /// The name of the error
+    pub name: String,
+    /// The error's selector
+    pub selector: [u8; 4],
+    /// The canonical ABI types of the error's parameters
+    pub parameter_types: Vec<String>,
+}
+
+/// The type of jump that an instruction performs, according to the source map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JumpType {
+    /// Not a jump
+    NotJump,
+    /// A jump into a function
+    IntoFunction,
+    /// A jump out of a function
+    OutofFunction,
+    /// A jump within a function
+    InternalJump,
+}
+
+/// A decoded instruction of a bytecode.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instruction {
+    /// The program counter
+    pub pc: u64,
+    /// The opcode
+    pub opcode: u8,
+    /// The type of jump, if any
+    pub jump_type: JumpType,
+    /// The data pushed by a `PUSHn` instruction
+    pub push_data: Option<Bytes>,
+    /// The source code that generated the instruction, if known
+    pub location: Option<SourceLocation>,
+}
+
+/// The type of a [`Bytecode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BytecodeType {
+    /// Runtime bytecode
+    Runtime,
+    /// Deployment bytecode
+    Deployment,
+}
+
+/// The location of an immutable value in a runtime bytecode.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImmutableReference {
+    /// Byte offset of the value
+    pub offset: usize,
+    /// Length of the value in bytes
+    pub length: usize,
+}
+
+/// A compiled bytecode of a contract.
+#[derive(Debug, PartialEq)]
+pub struct Bytecode {
+    pub(crate) contract: Arc<Contract>,
+    pub(crate) source_files: Arc<SourceFiles>,
+    pub(crate) normalized_code: Bytes,
+    pub(crate) bytecode_type: BytecodeType,
+    pub(crate) library_offsets: Vec<usize>,
+    pub(crate) immutable_references: Vec<ImmutableReference>,
+    pub(crate) compiler_version: String,
+    pc_to_instruction: HashMap<u64, Instruction>,
+}
+
+impl Bytecode {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        contract: Arc<Contract>,
+        source_files: Arc<SourceFiles>,
+        normalized_code: Bytes,
+        bytecode_type: BytecodeType,
+        instructions: Vec<Instruction>,
+        library_offsets: Vec<usize>,
+        immutable_references: Vec<ImmutableReference>,
+        compiler_version: String,
+    ) -> Self {
+        let pc_to_instruction = instructions
+            .into_iter()
+            .map(|instruction| (instruction.pc, instruction))
+            .collect();
+
+        Self {
+            contract,
+            source_files,
+            normalized_code,
+            bytecode_type,
+            library_offsets,
+            immutable_references,
+            compiler_version,
+            pc_to_instruction,
+        }
+    }
+
+    /// The contract that the bytecode belongs to.
+    pub fn contract(&self) -> &Arc<Contract> {
+        &self.contract
+    }
+
+    /// The version of the compiler that generated the bytecode.
+    pub fn compiler_version(&self) -> &str {
+        &self.compiler_version
+    }
+
+    /// Whether this is a deployment bytecode.
+    pub fn is_deployment(&self) -> bool {
+        self.bytecode_type == BytecodeType::Deployment
+    }
+
+    /// Returns the instruction at the provided program counter.
+    pub fn instruction(&self, pc: u64) -> Option<&Instruction> {
+        self.pc_to_instruction.get(&pc)
+    }
+
+    /// Returns the source file that contains the provided location.
+    pub fn source_file(&self, location: &SourceLocation) -> Option<&SourceFile> {
+        self.source_files.get(&location.file_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_location_contains() {
+        let outer = SourceLocation {
+            file_id: 0,
+            offset: 10,
+            length: 20,
+        };
+
+        assert!(outer.contains(&outer));
+        assert!(outer.contains(&SourceLocation {
+            file_id: 0,
+            offset: 15,
+            length: 5,
+        }));
+        assert!(!outer.contains(&SourceLocation {
+            file_id: 0,
+            offset: 25,
+            length: 10,
+        }));
+        assert!(!outer.contains(&SourceLocation {
+            file_id: 1,
+            offset: 15,
+            length: 5,
+        }));
+    }
+
+    #[test]
+    fn source_file_line_number() {
+        let file = SourceFile::new("test.sol".to_string(), "a\nbc\n\nd".to_string());
+
+        assert_eq!(file.line_number(0), 1);
+        assert_eq!(file.line_number(2), 2);
+        assert_eq!(file.line_number(5), 3);
+        assert_eq!(file.line_number(6), 4);
+        assert_eq!(file.line_number(100), 4);
+    }
 }
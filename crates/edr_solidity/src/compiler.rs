@@ -0,0 +1,645 @@
+use std::sync::Arc;
+
+use edr_eth::{Bytes, HashMap};
+use edr_evm::keccak256;
+use serde_json::Value;
+
+use crate::{
+    artifacts::{CompilerInput, CompilerOutput, CompilerOutputBytecode},
+    build_model::{
+        Branch, BuildModel, BuildModelError, Bytecode, BytecodeType, Contract, ContractFunction,
+        ContractFunctionType, ContractFunctionVisibility, ContractKind, CustomError,
+        ImmutableReference, SourceFile, SourceFiles, SourceLocation,
+    },
+    source_map::decode_instructions,
+};
+
+/// A contract whose inheritance has not been applied yet.
+struct ParsedContract {
+    contract: Contract,
+    linearized_base_contracts: Vec<i64>,
+}
+
+/// Constructs a [`BuildModel`] by walking the ASTs of the compiler output and
+/// decoding the bytecodes of all contracts.
+pub(crate) fn create_model(
+    solc_version: String,
+    compiler_input: &CompilerInput,
+    compiler_output: &CompilerOutput,
+) -> Result<BuildModel, BuildModelError> {
+    let mut source_files = SourceFiles::new();
+    let mut contracts = HashMap::<i64, ParsedContract>::new();
+    let mut contract_ids = HashMap::<(String, String), i64>::new();
+
+    for (source_name, source) in &compiler_output.sources {
+        let content = compiler_input
+            .sources
+            .get(source_name)
+            .ok_or_else(|| BuildModelError::MissingSource(source_name.clone()))?
+            .content
+            .clone();
+
+        let mut file = SourceFile::new(source_name.clone(), content);
+        let contract_outputs = compiler_output.contracts.get(source_name);
+
+        for node in child_nodes(&source.ast) {
+            match node_type(node) {
+                Some("ContractDefinition") => {
+                    let method_identifiers = node["name"]
+                        .as_str()
+                        .and_then(|name| contract_outputs?.get(name))
+                        .map(|contract_output| &contract_output.evm.method_identifiers);
+
+                    if let Some((id, parsed)) =
+                        parse_contract_node(node, &mut file, method_identifiers)?
+                    {
+                        contract_ids
+                            .insert((source_name.clone(), parsed.contract.name.clone()), id);
+                        contracts.insert(id, parsed);
+                    }
+                }
+                Some("FunctionDefinition") => {
+                    if let Some(function) = parse_function_definition(node, None, None)? {
+                        file.add_function(Arc::new(function));
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        collect_statements(&source.ast, &mut file)?;
+
+        source_files.insert(source.id as u32, file);
+    }
+
+    apply_inheritance(&mut contracts);
+
+    for (source_name, contract_outputs) in &compiler_output.contracts {
+        for (contract_name, contract_output) in contract_outputs {
+            let Some(parsed) = contract_ids
+                .get(&(source_name.clone(), contract_name.clone()))
+                .and_then(|id| contracts.get_mut(id))
+            else {
+                continue;
+            };
+
+            parsed.contract.custom_errors = custom_errors_from_abi(&contract_output.abi);
+        }
+    }
+
+    let contracts: HashMap<i64, Arc<Contract>> = contracts
+        .into_iter()
+        .map(|(id, parsed)| (id, Arc::new(parsed.contract)))
+        .collect();
+
+    let source_files = Arc::new(source_files);
+
+    let mut bytecodes = Vec::new();
+    for (source_name, contract_outputs) in &compiler_output.contracts {
+        for (contract_name, contract_output) in contract_outputs {
+            // Abstract contracts don't have a bytecode
+            if contract_output.evm.bytecode.object.is_empty() {
+                continue;
+            }
+
+            let Some(contract) = contract_ids
+                .get(&(source_name.clone(), contract_name.clone()))
+                .and_then(|id| contracts.get(id))
+            else {
+                continue;
+            };
+
+            for (bytecode_type, compiler_bytecode) in [
+                (BytecodeType::Deployment, &contract_output.evm.bytecode),
+                (
+                    BytecodeType::Runtime,
+                    &contract_output.evm.deployed_bytecode,
+                ),
+            ] {
+                let bytecode = decode_bytecode(
+                    contract.clone(),
+                    source_files.clone(),
+                    &solc_version,
+                    bytecode_type,
+                    compiler_bytecode,
+                )?;
+
+                bytecodes.push(Arc::new(bytecode));
+            }
+        }
+    }
+
+    Ok(BuildModel {
+        solc_version,
+        source_files,
+        bytecodes,
+    })
+}
+
+fn child_nodes(node: &Value) -> impl Iterator<Item = &Value> {
+    node["nodes"].as_array().into_iter().flatten()
+}
+
+fn node_type(node: &Value) -> Option<&str> {
+    node["nodeType"].as_str()
+}
+
+/// Whether nodes of the provided type are statements that are covered by code
+/// coverage. Blocks are excluded, as they merely group other statements.
+fn is_statement(node_type: &str) -> bool {
+    matches!(
+        node_type,
+        "Break"
+            | "Continue"
+            | "DoWhileStatement"
+            | "EmitStatement"
+            | "ExpressionStatement"
+            | "ForStatement"
+            | "IfStatement"
+            | "InlineAssembly"
+            | "Return"
+            | "RevertStatement"
+            | "TryStatement"
+            | "VariableDeclarationStatement"
+            | "WhileStatement"
+    )
+}
+
+/// Recursively collects the statements and branches of an AST node.
+fn collect_statements(node: &Value, file: &mut SourceFile) -> Result<(), BuildModelError> {
+    match node {
+        Value::Object(fields) => {
+            let branch = match node_type(node) {
+                Some("IfStatement") => Some(("trueBody", "falseBody")),
+                Some("Conditional") => Some(("trueExpression", "falseExpression")),
+                _ => None,
+            };
+
+            if let Some((true_body, false_body)) = branch {
+                file.add_branch(Branch {
+                    location: parse_source_location(node)?,
+                    condition: parse_source_location(&node["condition"])?,
+                    true_body: parse_source_location(&node[true_body])?,
+                    false_body: node
+                        .get(false_body)
+                        .filter(|body| !body.is_null())
+                        .map(parse_source_location)
+                        .transpose()?,
+                });
+            }
+
+            if node_type(node).is_some_and(is_statement) {
+                file.add_statement(parse_source_location(node)?);
+            }
+
+            for value in fields.values() {
+                collect_statements(value, file)?;
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                collect_statements(value, file)?;
+            }
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+fn parse_source_location(node: &Value) -> Result<SourceLocation, BuildModelError> {
+    let src = node["src"]
+        .as_str()
+        .ok_or(BuildModelError::InvalidAstNode("src"))?;
+
+    let mut parts = src.split(':').map(str::parse::<u32>);
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(offset)), Some(Ok(length)), Some(Ok(file_id))) => Ok(SourceLocation {
+            file_id,
+            offset,
+            length,
+        }),
+        _ => Err(BuildModelError::InvalidSourceLocation(src.to_string())),
+    }
+}
+
+fn parse_visibility(node: &Value) -> Option<ContractFunctionVisibility> {
+    match node["visibility"].as_str()? {
+        "private" => Some(ContractFunctionVisibility::Private),
+        "internal" => Some(ContractFunctionVisibility::Internal),
+        "public" => Some(ContractFunctionVisibility::Public),
+        "external" => Some(ContractFunctionVisibility::External),
+        _ => None,
+    }
+}
+
+fn decode_selector(selector: &str) -> Option<[u8; 4]> {
+    hex::decode(selector).ok()?.try_into().ok()
+}
+
+/// Older compilers don't include function selectors in the AST, so we fall
+/// back to the method identifiers of the compiler output. This only works for
+/// functions that aren't overloaded.
+fn selector_from_method_identifiers(
+    name: &str,
+    method_identifiers: Option<&HashMap<String, String>>,
+) -> Option<[u8; 4]> {
+    let mut matching = method_identifiers?
+        .iter()
+        .filter(|(signature, _)| signature.split('(').next() == Some(name));
+
+    let (_, selector) = matching.next()?;
+    if matching.next().is_some() {
+        return None;
+    }
+
+    decode_selector(selector)
+}
+
+fn parse_contract_node(
+    node: &Value,
+    file: &mut SourceFile,
+    method_identifiers: Option<&HashMap<String, String>>,
+) -> Result<Option<(i64, ParsedContract)>, BuildModelError> {
+    let kind = match node["contractKind"].as_str() {
+        Some("contract") => ContractKind::Contract,
+        Some("library") => ContractKind::Library,
+        // Interfaces don't have any code
+        _ => return Ok(None),
+    };
+
+    let id = node["id"]
+        .as_i64()
+        .ok_or(BuildModelError::InvalidAstNode("id"))?;
+
+    let name = node["name"]
+        .as_str()
+        .ok_or(BuildModelError::InvalidAstNode("name"))?;
+
+    let linearized_base_contracts = node["linearizedBaseContracts"]
+        .as_array()
+        .ok_or(BuildModelError::InvalidAstNode("linearizedBaseContracts"))?
+        .iter()
+        .map(|id| {
+            id.as_i64()
+                .ok_or(BuildModelError::InvalidAstNode("linearizedBaseContracts"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut contract = Contract::new(name.to_string(), kind, parse_source_location(node)?);
+
+    for child in child_nodes(node) {
+        let function = match node_type(child) {
+            Some("FunctionDefinition") => {
+                parse_function_definition(child, Some(name), method_identifiers)?
+            }
+            Some("ModifierDefinition") => Some(ContractFunction {
+                name: child["name"]
+                    .as_str()
+                    .ok_or(BuildModelError::InvalidAstNode("name"))?
+                    .to_string(),
+                function_type: ContractFunctionType::Modifier,
+                location: parse_source_location(child)?,
+                contract_name: Some(name.to_string()),
+                visibility: parse_visibility(child),
+                is_payable: false,
+                selector: None,
+            }),
+            Some("VariableDeclaration") => {
+                parse_variable_declaration(child, name, method_identifiers)?
+            }
+            _ => None,
+        };
+
+        if let Some(function) = function {
+            let function = Arc::new(function);
+            file.add_function(function.clone());
+            contract.add_local_function(function);
+        }
+    }
+
+    Ok(Some((
+        id,
+        ParsedContract {
+            contract,
+            linearized_base_contracts,
+        },
+    )))
+}
+
+fn parse_function_definition(
+    node: &Value,
+    contract_name: Option<&str>,
+    method_identifiers: Option<&HashMap<String, String>>,
+) -> Result<Option<ContractFunction>, BuildModelError> {
+    if node["implemented"] == Value::Bool(false) {
+        return Ok(None);
+    }
+
+    let name = node["name"]
+        .as_str()
+        .ok_or(BuildModelError::InvalidAstNode("name"))?;
+
+    let function_type = match node["kind"].as_str() {
+        Some("constructor") => ContractFunctionType::Constructor,
+        Some("fallback") => ContractFunctionType::Fallback,
+        Some("receive") => ContractFunctionType::Receive,
+        Some("freeFunction") => ContractFunctionType::FreeFunction,
+        _ => ContractFunctionType::Function,
+    };
+
+    let visibility = parse_visibility(node);
+
+    let selector = if function_type == ContractFunctionType::Function
+        && matches!(
+            visibility,
+            Some(ContractFunctionVisibility::Public | ContractFunctionVisibility::External)
+        ) {
+        node["functionSelector"]
+            .as_str()
+            .and_then(decode_selector)
+            .or_else(|| selector_from_method_identifiers(name, method_identifiers))
+    } else {
+        None
+    };
+
+    Ok(Some(ContractFunction {
+        name: name.to_string(),
+        function_type,
+        location: parse_source_location(node)?,
+        contract_name: contract_name.map(str::to_string),
+        visibility,
+        is_payable: node["stateMutability"].as_str() == Some("payable"),
+        selector,
+    }))
+}
+
+fn parse_variable_declaration(
+    node: &Value,
+    contract_name: &str,
+    method_identifiers: Option<&HashMap<String, String>>,
+) -> Result<Option<ContractFunction>, BuildModelError> {
+    let visibility = parse_visibility(node);
+
+    // Only public state variables have a getter
+    if visibility != Some(ContractFunctionVisibility::Public) {
+        return Ok(None);
+    }
+
+    let name = node["name"]
+        .as_str()
+        .ok_or(BuildModelError::InvalidAstNode("name"))?;
+
+    let selector = node["functionSelector"]
+        .as_str()
+        .and_then(decode_selector)
+        .or_else(|| selector_from_method_identifiers(name, method_identifiers));
+
+    Ok(Some(ContractFunction {
+        name: name.to_string(),
+        function_type: ContractFunctionType::Getter,
+        location: parse_source_location(node)?,
+        contract_name: Some(contract_name.to_string()),
+        visibility,
+        is_payable: false,
+        selector,
+    }))
+}
+
+fn apply_inheritance(contracts: &mut HashMap<i64, ParsedContract>) {
+    let ids: Vec<i64> = contracts.keys().copied().collect();
+
+    for id in ids {
+        let base_contract_ids = contracts[&id].linearized_base_contracts.clone();
+
+        // The first linearized base contract is the contract itself
+        for base_contract_id in base_contract_ids.into_iter().skip(1) {
+            // Interfaces are not part of the model
+            let Some(base_contract) = contracts.remove(&base_contract_id) else {
+                continue;
+            };
+
+            if let Some(parsed) = contracts.get_mut(&id) {
+                parsed
+                    .contract
+                    .add_next_linearized_base_contract(&base_contract.contract);
+            }
+
+            contracts.insert(base_contract_id, base_contract);
+        }
+    }
+}
+
+fn canonical_abi_type(parameter: &Value) -> Option<String> {
+    let abi_type = parameter["type"].as_str()?;
+
+    match abi_type.strip_prefix("tuple") {
+        Some(array_suffix) => {
+            let components = parameter["components"]
+                .as_array()?
+                .iter()
+                .map(canonical_abi_type)
+                .collect::<Option<Vec<_>>>()?;
+
+            Some(format!("({}){array_suffix}", components.join(",")))
+        }
+        None => Some(abi_type.to_string()),
+    }
+}
+
+fn custom_errors_from_abi(abi: &Value) -> Vec<CustomError> {
+    abi.as_array()
+        .into_iter()
+        .flatten()
+        .filter(|item| item["type"].as_str() == Some("error"))
+        .filter_map(|item| {
+            let name = item["name"].as_str()?;
+            let parameter_types = item["inputs"]
+                .as_array()?
+                .iter()
+                .map(canonical_abi_type)
+                .collect::<Option<Vec<_>>>()?;
+
+            let signature = format!("{name}({})", parameter_types.join(","));
+            let mut selector = [0u8; 4];
+            selector.copy_from_slice(&keccak256(signature.as_bytes())[..4]);
+
+            Some(CustomError {
+                name: name.to_string(),
+                selector,
+                parameter_types,
+            })
+        })
+        .collect()
+}
+
+/// Decodes the hex-encoded bytecode, replacing library address placeholders by
+/// zeroes.
+fn normalize_compiler_output_bytecode(
+    object: &str,
+    library_offsets: &[usize],
+) -> Result<Bytes, hex::FromHexError> {
+    const ZERO_ADDRESS: &str = "0000000000000000000000000000000000000000";
+
+    let mut object = object.strip_prefix("0x").unwrap_or(object).to_string();
+    for offset in library_offsets {
+        let start = offset * 2;
+        let end = start + ZERO_ADDRESS.len();
+
+        if end <= object.len() && object.is_char_boundary(start) && object.is_char_boundary(end) {
+            object.replace_range(start..end, ZERO_ADDRESS);
+        }
+    }
+
+    hex::decode(object).map(Bytes::from)
+}
+
+fn decode_bytecode(
+    contract: Arc<Contract>,
+    source_files: Arc<SourceFiles>,
+    solc_version: &str,
+    bytecode_type: BytecodeType,
+    compiler_bytecode: &CompilerOutputBytecode,
+) -> Result<Bytecode, BuildModelError> {
+    let library_offsets: Vec<usize> = compiler_bytecode
+        .link_references
+        .values()
+        .flat_map(HashMap::values)
+        .flatten()
+        .map(|reference| reference.start)
+        .collect();
+
+    let immutable_references = compiler_bytecode
+        .immutable_references
+        .iter()
+        .flat_map(HashMap::values)
+        .flatten()
+        .map(|reference| ImmutableReference {
+            offset: reference.start,
+            length: reference.length,
+        })
+        .collect();
+
+    let normalized_code =
+        normalize_compiler_output_bytecode(&compiler_bytecode.object, &library_offsets).map_err(
+            |error| BuildModelError::InvalidBytecode {
+                contract: contract.name.clone(),
+                error,
+            },
+        )?;
+
+    let instructions = decode_instructions(
+        &normalized_code,
+        &compiler_bytecode.source_map,
+        &source_files,
+        bytecode_type == BytecodeType::Deployment,
+    );
+
+    Ok(Bytecode::new(
+        contract,
+        source_files,
+        normalized_code,
+        bytecode_type,
+        instructions,
+        library_offsets,
+        immutable_references,
+        solc_version.to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_bytecode_with_library_placeholder() {
+        let object = format!("0x60{}00", "__$a7f1a4b5e1a4b5e1a4b5e1a4b5e1a4b5e1$__");
+        let normalized = normalize_compiler_output_bytecode(&object, &[1]).unwrap();
+
+        let mut expected = vec![0x60];
+        expected.extend([0u8; 20]);
+        expected.push(0x00);
+        assert_eq!(normalized, Bytes::from(expected));
+    }
+
+    #[test]
+    fn collect_statements_and_branches() {
+        let ast = serde_json::json!({
+            "nodeType": "Block",
+            "src": "0:100:0",
+            "statements": [
+                {
+                    "nodeType": "IfStatement",
+                    "src": "10:50:0",
+                    "condition": { "nodeType": "Identifier", "src": "14:1:0" },
+                    "trueBody": {
+                        "nodeType": "Return",
+                        "src": "20:7:0",
+                        "expression": {
+                            "nodeType": "Conditional",
+                            "src": "21:5:0",
+                            "condition": { "nodeType": "Identifier", "src": "21:1:0" },
+                            "trueExpression": { "nodeType": "Literal", "src": "23:1:0" },
+                            "falseExpression": { "nodeType": "Literal", "src": "25:1:0" }
+                        }
+                    },
+                    "falseBody": null
+                },
+                { "nodeType": "ExpressionStatement", "src": "70:10:0" }
+            ]
+        });
+
+        let mut file = SourceFile::new("test.sol".to_string(), String::new());
+        collect_statements(&ast, &mut file).unwrap();
+
+        let location = |offset, length| SourceLocation {
+            file_id: 0,
+            offset,
+            length,
+        };
+
+        assert_eq!(
+            file.statements(),
+            &[location(10, 50), location(20, 7), location(70, 10)]
+        );
+        assert_eq!(
+            file.branches(),
+            &[
+                Branch {
+                    location: location(10, 50),
+                    condition: location(14, 1),
+                    true_body: location(20, 7),
+                    false_body: None,
+                },
+                Branch {
+                    location: location(21, 5),
+                    condition: location(21, 1),
+                    true_body: location(23, 1),
+                    false_body: Some(location(25, 1)),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_error_selector() {
+        let abi = serde_json::json!([
+            { "type": "function", "name": "foo", "inputs": [] },
+            {
+                "type": "error",
+                "name": "InsufficientBalance",
+                "inputs": [
+                    { "name": "available", "type": "uint256" },
+                    { "name": "required", "type": "uint256" }
+                ]
+            }
+        ]);
+
+        let errors = custom_errors_from_abi(&abi);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].name, "InsufficientBalance");
+        assert_eq!(errors[0].parameter_types, vec!["uint256", "uint256"]);
+        // cast sig "InsufficientBalance(uint256,uint256)"
+        assert_eq!(errors[0].selector, [0xcf, 0x47, 0x91, 0x81]);
+    }
+}
@@ -0,0 +1,515 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use edr_eth::{Bytes, U256};
+use edr_evm::keccak256;
+
+/// The type of a parameter of a `console.sol` function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ParamType {
+    Address,
+    Bool,
+    Bytes,
+    /// `bytesN`, with the number of bytes
+    FixedBytes(usize),
+    Int,
+    String,
+    Uint,
+}
+
+impl ParamType {
+    fn name(self) -> String {
+        match self {
+            ParamType::Address => "address".to_string(),
+            ParamType::Bool => "bool".to_string(),
+            ParamType::Bytes => "bytes".to_string(),
+            ParamType::FixedBytes(size) => format!("bytes{size}"),
+            ParamType::Int => "int256".to_string(),
+            ParamType::String => "string".to_string(),
+            ParamType::Uint => "uint256".to_string(),
+        }
+    }
+
+    /// Older versions of `console.sol` computed selectors with the `int` and
+    /// `uint` aliases.
+    fn legacy_name(self) -> String {
+        match self {
+            ParamType::Int => "int".to_string(),
+            ParamType::Uint => "uint".to_string(),
+            _ => self.name(),
+        }
+    }
+}
+
+/// The parameter types of the functions of Hardhat's `console.sol`, by
+/// selector.
+fn console_log_signatures() -> &'static HashMap<[u8; 4], Vec<ParamType>> {
+    static SIGNATURES: OnceLock<HashMap<[u8; 4], Vec<ParamType>>> = OnceLock::new();
+
+    SIGNATURES.get_or_init(|| {
+        let mut functions: Vec<(String, Vec<ParamType>)> = vec![
+            ("log".to_string(), Vec::new()),
+            ("log".to_string(), vec![ParamType::Int]),
+            ("logInt".to_string(), vec![ParamType::Int]),
+            ("logUint".to_string(), vec![ParamType::Uint]),
+            ("logString".to_string(), vec![ParamType::String]),
+            ("logBool".to_string(), vec![ParamType::Bool]),
+            ("logAddress".to_string(), vec![ParamType::Address]),
+            ("logBytes".to_string(), vec![ParamType::Bytes]),
+        ];
+
+        for size in 1..=32 {
+            functions.push((format!("logBytes{size}"), vec![ParamType::FixedBytes(size)]));
+        }
+
+        // `log` is overloaded for all combinations of up to four parameters
+        let log_types = [
+            ParamType::Uint,
+            ParamType::String,
+            ParamType::Bool,
+            ParamType::Address,
+        ];
+
+        let mut combinations = vec![Vec::new()];
+        for _ in 0..4 {
+            combinations = combinations
+                .into_iter()
+                .flat_map(|combination: Vec<ParamType>| {
+                    log_types.iter().map(move |ty| {
+                        let mut combination = combination.clone();
+                        combination.push(*ty);
+                        combination
+                    })
+                })
+                .collect();
+
+            functions.extend(
+                combinations
+                    .iter()
+                    .map(|combination| ("log".to_string(), combination.clone())),
+            );
+        }
+
+        let mut signatures = HashMap::new();
+        for (name, types) in functions {
+            for type_name in [
+                ParamType::name as fn(ParamType) -> String,
+                ParamType::legacy_name,
+            ] {
+                let signature = format!(
+                    "{name}({})",
+                    types
+                        .iter()
+                        .map(|ty| type_name(*ty))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                );
+
+                let mut selector = [0u8; 4];
+                selector.copy_from_slice(&keccak256(signature.as_bytes())[..4]);
+
+                signatures.insert(selector, types.clone());
+            }
+        }
+
+        signatures
+    })
+}
+
+/// Decodes a `console.log` argument. Like in Hardhat, all values are formatted
+/// as strings.
+fn decode_value(data: &[u8], ty: ParamType, position: usize) -> Option<String> {
+    let word = data.get(position..position + 32)?;
+
+    let value = match ty {
+        ParamType::Address => format!("0x{}", hex::encode(&word[12..])),
+        ParamType::Bool => (word[31] != 0).to_string(),
+        ParamType::Bytes | ParamType::String => {
+            let start = usize::try_from(U256::from_be_slice(word)).ok()?;
+            let length = data.get(start..start.checked_add(32)?)?;
+            let length = usize::try_from(U256::from_be_slice(length)).ok()?;
+
+            let content_start = start + 32;
+            let content = data.get(content_start..content_start.checked_add(length)?)?;
+
+            if ty == ParamType::String {
+                String::from_utf8_lossy(content).into_owned()
+            } else {
+                format!("0x{}", hex::encode(content))
+            }
+        }
+        ParamType::FixedBytes(size) => format!("0x{}", hex::encode(&word[..size])),
+        ParamType::Int => {
+            let value = U256::from_be_slice(word);
+            if value.bit(255) {
+                format!("-{}", value.wrapping_neg())
+            } else {
+                value.to_string()
+            }
+        }
+        ParamType::Uint => U256::from_be_slice(word).to_string(),
+    };
+
+    Some(value)
+}
+
+/// Decodes the input of a call to the `console.log` address into the message
+/// that Hardhat prints for it. Returns `None` if the function is unknown or the
+/// input is malformed.
+pub fn decode_console_log(input: &[u8]) -> Option<String> {
+    let selector: [u8; 4] = input.get(..4)?.try_into().ok()?;
+    let types = console_log_signatures().get(&selector)?;
+
+    let data = &input[4..];
+    let values = types
+        .iter()
+        .enumerate()
+        .map(|(index, ty)| decode_value(data, *ty, index * 32))
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(format_values(&values))
+}
+
+/// Decodes the inputs of calls to the `console.log` address, skipping inputs
+/// that can't be decoded.
+pub fn decode_console_log_inputs(inputs: &[Bytes]) -> Vec<String> {
+    inputs
+        .iter()
+        .filter_map(|input| decode_console_log(input))
+        .collect()
+}
+
+/// Replaces the `%d` and `%i` specifiers with `%s`, like Hardhat does, as
+/// numbers could be too large to be formatted as a `Number`. A specifier is
+/// only replaced if it's preceded by an odd number of `%`, as an even number
+/// means that the `%` is escaped.
+fn replace_number_format_specifiers(format: &str) -> String {
+    let mut replaced = String::with_capacity(format.len());
+    let mut percent_signs = 0usize;
+
+    for c in format.chars() {
+        if matches!(c, 'd' | 'i') && percent_signs % 2 == 1 {
+            replaced.push('s');
+        } else {
+            replaced.push(c);
+        }
+
+        if c == '%' {
+            percent_signs += 1;
+        } else {
+            percent_signs = 0;
+        }
+    }
+
+    replaced
+}
+
+/// Formats the values like Hardhat formats `console.log` messages, which
+/// replaces the number specifiers and then uses Node.js's `util.format`.
+fn format_values(values: &[String]) -> String {
+    let Some((format, mut remaining)) = values.split_first() else {
+        return String::new();
+    };
+
+    let format = replace_number_format_specifiers(format);
+
+    // `util.format` returns a lone argument unchanged
+    if remaining.is_empty() {
+        return format;
+    }
+
+    let mut message = String::new();
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            message.push(c);
+            continue;
+        }
+
+        let Some(&specifier) = chars.peek() else {
+            message.push(c);
+            continue;
+        };
+
+        if specifier == '%' {
+            chars.next();
+            message.push('%');
+            continue;
+        }
+
+        let Some((value, rest)) = remaining.split_first() else {
+            message.push(c);
+            continue;
+        };
+
+        let formatted = match specifier {
+            's' => value.clone(),
+            'f' => format_number(parse_float(value)),
+            'j' => serde_json::Value::from(value.as_str()).to_string(),
+            'o' | 'O' => format!("'{value}'"),
+            'c' => String::new(),
+            _ => {
+                message.push(c);
+                continue;
+            }
+        };
+
+        chars.next();
+        message.push_str(&formatted);
+        remaining = rest;
+    }
+
+    for value in remaining {
+        message.push(' ');
+        message.push_str(value);
+    }
+
+    message
+}
+
+/// Parses the longest prefix of the value that is a decimal number, like
+/// JavaScript's `parseFloat`.
+fn parse_float(value: &str) -> f64 {
+    let value = value.trim_start();
+
+    let (sign, unsigned) = match value.as_bytes().first() {
+        Some(b'-') => (-1.0, &value[1..]),
+        Some(b'+') => (1.0, &value[1..]),
+        _ => (1.0, value),
+    };
+
+    if unsigned.starts_with("Infinity") {
+        return sign * f64::INFINITY;
+    }
+
+    let bytes = unsigned.as_bytes();
+    let count_digits = |start: usize| {
+        bytes[start..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit())
+            .count()
+    };
+
+    let integer_digits = count_digits(0);
+    let mut end = integer_digits;
+    let mut fraction_digits = 0;
+    if bytes.get(end) == Some(&b'.') {
+        fraction_digits = count_digits(end + 1);
+        if integer_digits > 0 || fraction_digits > 0 {
+            end += 1 + fraction_digits;
+        }
+    }
+
+    if integer_digits == 0 && fraction_digits == 0 {
+        return f64::NAN;
+    }
+
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let sign_length = usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+        let exponent_digits = count_digits(end + 1 + sign_length);
+        if exponent_digits > 0 {
+            end += 1 + sign_length + exponent_digits;
+        }
+    }
+
+    unsigned[..end]
+        .parse::<f64>()
+        .map_or(f64::NAN, |number| sign * number)
+}
+
+/// Formats a number like `util.format` does.
+fn format_number(number: f64) -> String {
+    if number.is_nan() {
+        "NaN".to_string()
+    } else if number.is_infinite() {
+        if number.is_sign_negative() {
+            "-Infinity".to_string()
+        } else {
+            "Infinity".to_string()
+        }
+    } else if number == 0.0 {
+        if number.is_sign_negative() {
+            "-0".to_string()
+        } else {
+            "0".to_string()
+        }
+    } else if (1e-6..1e21).contains(&number.abs()) {
+        number.to_string()
+    } else {
+        let formatted = format!("{number:e}");
+        if formatted.contains("e-") {
+            formatted
+        } else {
+            formatted.replace('e', "e+")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(value: u64) -> [u8; 32] {
+        U256::from(value).to_be_bytes()
+    }
+
+    fn encode_string(offset: u64, value: &str) -> Vec<u8> {
+        let mut encoded = word(offset).to_vec();
+        encoded.extend_from_slice(&word(value.len() as u64));
+
+        let mut content = value.as_bytes().to_vec();
+        content.resize(value.len().div_ceil(32) * 32, 0);
+        encoded.extend(content);
+
+        encoded
+    }
+
+    fn format_strings(values: &[&str]) -> String {
+        format_values(&values.iter().map(ToString::to_string).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn signatures() {
+        let signatures = console_log_signatures();
+
+        // log(), log(int256), six logType functions, 32 logBytesN functions and
+        // 4 + 16 + 64 + 256 log overloads, plus the legacy aliases of the
+        // functions with integer parameters
+        assert!(signatures.len() > 380);
+
+        assert_eq!(signatures.get(&[0x51, 0x97, 0x3e, 0xc9]), Some(&Vec::new()));
+        assert_eq!(
+            signatures.get(&[0xf8, 0x2c, 0x50, 0xf1]),
+            Some(&vec![ParamType::Uint])
+        );
+        // log(uint)
+        assert_eq!(
+            signatures.get(&[0xf5, 0xb1, 0xbb, 0xa9]),
+            Some(&vec![ParamType::Uint])
+        );
+        assert_eq!(
+            signatures.get(&[0x2d, 0x5b, 0x6c, 0xb9]),
+            Some(&vec![ParamType::Int])
+        );
+        assert_eq!(
+            signatures.get(&[0xfb, 0xa3, 0xad, 0x39]),
+            Some(&vec![ParamType::FixedBytes(4)])
+        );
+        assert_eq!(
+            signatures.get(&[0x85, 0x3c, 0x48, 0x49]),
+            Some(&vec![ParamType::Bool, ParamType::Address])
+        );
+    }
+
+    #[test]
+    fn decode_string() {
+        // log(string)
+        let mut input = vec![0x41, 0x30, 0x4f, 0xac];
+        input.extend(encode_string(32, "hello"));
+
+        assert_eq!(decode_console_log(&input), Some("hello".to_string()));
+    }
+
+    #[test]
+    fn decode_format_string() {
+        // log(string,uint256)
+        let mut input = vec![0xb6, 0x0e, 0x72, 0xcc];
+        input.extend_from_slice(&word(64));
+        input.extend_from_slice(&word(42));
+        input.extend(encode_string(32, "value: %d%%")[32..].iter());
+
+        assert_eq!(decode_console_log(&input), Some("value: 42%".to_string()));
+    }
+
+    #[test]
+    fn decode_static_values() {
+        // log(bool,address)
+        let mut input = vec![0x85, 0x3c, 0x48, 0x49];
+        input.extend_from_slice(&word(1));
+        input.extend_from_slice(&word(0xdead));
+
+        assert_eq!(
+            decode_console_log(&input),
+            Some("true 0x000000000000000000000000000000000000dead".to_string())
+        );
+
+        // log(int256)
+        let mut input = vec![0x2d, 0x5b, 0x6c, 0xb9];
+        input.extend_from_slice(&U256::from(5).wrapping_neg().to_be_bytes::<32>());
+
+        assert_eq!(decode_console_log(&input), Some("-5".to_string()));
+    }
+
+    #[test]
+    fn decode_invalid_input() {
+        assert_eq!(decode_console_log(&[]), None);
+        assert_eq!(decode_console_log(&[0xde, 0xad, 0xbe, 0xef]), None);
+        // log(uint256) without argument
+        assert_eq!(decode_console_log(&[0xf8, 0x2c, 0x50, 0xf1]), None);
+    }
+
+    #[test]
+    fn decode_number_specifier_with_string() {
+        // log(string,string)
+        let mut input = vec![0x4b, 0x5c, 0x42, 0x77];
+        input.extend_from_slice(&word(64));
+        input.extend_from_slice(&word(128));
+        input.extend(encode_string(64, "%d")[32..].iter());
+        input.extend(encode_string(128, "abc")[32..].iter());
+
+        assert_eq!(decode_console_log(&input), Some("abc".to_string()));
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(
+            format_strings(&["%s has %d tokens", "alice", "10"]),
+            "alice has 10 tokens"
+        );
+        assert_eq!(format_strings(&["%s %s", "only one"]), "only one %s");
+        assert_eq!(format_strings(&["%o", "quoted", "false"]), "'quoted' false");
+        assert_eq!(format_strings(&["true", "%s"]), "true %s");
+        assert_eq!(format_strings(&["%j%c", "a\"b", "css"]), "\"a\\\"b\"");
+        assert_eq!(format_strings(&["%x %%", "value"]), "%x % value");
+        assert_eq!(format_strings(&["100%%"]), "100%%");
+    }
+
+    #[test]
+    fn format_number_specifiers() {
+        // Numbers are formatted as strings, so that they don't lose precision
+        assert_eq!(
+            format_strings(&[
+                "%d",
+                "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+            ]),
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+        assert_eq!(format_strings(&["%d", "abc"]), "abc");
+        assert_eq!(format_strings(&["%i", "true"]), "true");
+        assert_eq!(format_strings(&["%d"]), "%s");
+        // An even number of `%` escapes the specifier
+        assert_eq!(format_strings(&["%%d", "1"]), "%d 1");
+        assert_eq!(format_strings(&["%%%d", "1"]), "%1");
+        assert_eq!(format_strings(&["%%%%i %i", "1"]), "%%i 1");
+    }
+
+    #[test]
+    fn format_float_specifier() {
+        let format_float = |value: &str| format_strings(&["%f", value]);
+
+        assert_eq!(format_float("true"), "NaN");
+        assert_eq!(format_float("1.5"), "1.5");
+        assert_eq!(format_float(" 12.5abc"), "12.5");
+        assert_eq!(format_float(".5"), "0.5");
+        assert_eq!(format_float("5.e3"), "5000");
+        assert_eq!(format_float("1e"), "1");
+        assert_eq!(format_float("-0"), "-0");
+        assert_eq!(format_float("0x10"), "0");
+        assert_eq!(format_float("-Infinityx"), "-Infinity");
+        assert_eq!(format_float("0.0000001"), "1e-7");
+        assert_eq!(
+            format_float("123456789012345678901234"),
+            "1.2345678901234569e+23"
+        );
+    }
+}
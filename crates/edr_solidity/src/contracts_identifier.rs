@@ -1,61 +1,47 @@
 mod radix_tree;
 
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use edr_eth::Bytes;
+use edr_evm::opcode;
 use radix_tree::RadixTree;
-use revm::interpreter::opcode;
 
 use self::radix_tree::RadixNode;
-use crate::opcodes::opcode_length;
-
-/// Temporary stub
-#[derive(Debug, PartialEq)]
-pub enum BytecodeType {
-    /// Temporary stub
-    Runtime,
-    /// Temporary stub
-    Deployment,
-}
-
-/// Temporary stub
-#[derive(Debug, PartialEq)]
-pub struct ImmutableReference {
-    offset: usize,
-    length: usize,
-}
+use crate::{
+    build_model::{Bytecode, ImmutableReference},
+    message_trace::MessageTrace,
+    opcodes::opcode_length,
+};
 
-/// Temporary stub
-#[derive(Debug, PartialEq)]
-pub struct Bytecode {
-    normalized_code: Bytes,
-    bytecode_type: BytecodeType,
-    library_offsets: Vec<usize>,
-    immutable_references: Vec<ImmutableReference>,
+/// The code of a create message
+pub struct CreateMessageTrace {
+    code: Bytes,
 }
 
-impl Bytecode {
-    /// Temporary stub
-    pub fn is_deployment(&self) -> bool {
-        self.bytecode_type == BytecodeType::Deployment
+impl CreateMessageTrace {
+    /// Constructs a new instance from the init code.
+    pub fn new(code: Bytes) -> Self {
+        Self { code }
     }
 }
 
-/// Temporary stub
-pub struct CreateMessageTrace {
+/// The code of a call message
+pub struct CallMessageTrace {
     code: Bytes,
 }
 
-/// Temporary stub
-pub struct CallMessageTrace {
-    code: Bytes,
+impl CallMessageTrace {
+    /// Constructs a new instance from the code of the callee.
+    pub fn new(code: Bytes) -> Self {
+        Self { code }
+    }
 }
 
-/// Temporary stub
+/// The code of a message that needs to be identified
 pub enum EvmMessageTrace {
-    /// Temporary stub
+    /// A create message
     Create(CreateMessageTrace),
-    /// Temporary stub
+    /// A call message
     Call(CallMessageTrace),
 }
 
@@ -68,26 +54,43 @@ impl EvmMessageTrace {
     }
 }
 
+impl From<&MessageTrace> for EvmMessageTrace {
+    fn from(trace: &MessageTrace) -> Self {
+        if trace.is_create() {
+            EvmMessageTrace::Create(CreateMessageTrace::new(trace.code.clone()))
+        } else {
+            EvmMessageTrace::Call(CallMessageTrace::new(trace.code.clone()))
+        }
+    }
+}
+
 /// Map of bytecodes to known contracts
 // TODO add a caching layer: https://github.com/NomicFoundation/edr/issues/257
-#[derive(Default)]
-pub struct ContractsIdentifier<'a> {
+#[derive(Debug, Default)]
+pub struct ContractsIdentifier {
     tree: RadixTree,
-    bytecodes: HashMap<&'a Bytes, &'a Bytecode>,
+    bytecodes: HashMap<Bytes, Arc<Bytecode>>,
 }
 
-impl<'a> ContractsIdentifier<'a> {
+impl ContractsIdentifier {
     /// Add a known bytecode
-    pub fn add_bytecode(&mut self, bytecode: &'a Bytecode) {
+    pub fn add_bytecode(&mut self, bytecode: Arc<Bytecode>) {
         self.tree.add_word(bytecode.normalized_code.clone());
-        self.bytecodes.insert(&bytecode.normalized_code, bytecode);
+        self.bytecodes
+            .insert(bytecode.normalized_code.clone(), bytecode);
+    }
+
+    /// Returns all known bytecodes, in no particular order.
+    pub fn bytecodes(&self) -> impl Iterator<Item = &Arc<Bytecode>> {
+        self.bytecodes.values()
     }
 
     /// Get a bytecode by its normalized code
-    pub fn bytecode_by_message_trace(&self, trace: EvmMessageTrace) -> Option<&Bytecode> {
+    pub fn bytecode_by_message_trace(&self, trace: EvmMessageTrace) -> Option<Arc<Bytecode>> {
         let normalized_code = normalize_library_runtime_bytecode_if_necessary(trace.code().clone());
 
         self.search_bytecode_in_radix_tree(&trace, &normalized_code, true, None)
+            .cloned()
     }
 
     fn search_bytecode_in_radix_tree(
@@ -96,13 +99,13 @@ impl<'a> ContractsIdentifier<'a> {
         code: &Bytes,
         normalize_libraries: bool,
         radix_node: Option<&RadixNode>,
-    ) -> Option<&Bytecode> {
+    ) -> Option<&Arc<Bytecode>> {
         let radix_node = radix_node.unwrap_or(self.tree.root());
 
         let (found, matched_bytes, node) = radix_node.longest_match(code);
 
         if found {
-            return self.bytecodes.get(&code).copied();
+            return self.bytecodes.get(code);
         }
 
         // The entire vector is present as a prefix, but not exactly
@@ -139,7 +142,7 @@ impl<'a> ContractsIdentifier<'a> {
                 .concat()
                 .into();
 
-            let bytecode = self.bytecodes.get(&matched_bytecode).copied();
+            let bytecode = self.bytecodes.get(&matched_bytecode);
 
             if let Some(bytecode) = bytecode {
                 if bytecode.is_deployment() {
@@ -203,7 +206,7 @@ impl<'a> ContractsIdentifier<'a> {
                     .concat()
                     .into();
 
-                return self.bytecodes.get(&descendant).copied();
+                return self.bytecodes.get(&descendant);
             }
         }
 
@@ -283,6 +286,7 @@ mod tests {
     use std::vec;
 
     use super::*;
+    use crate::build_model::{BytecodeType, Contract, ContractKind, SourceLocation};
 
     fn create_test_call_trace(code: Bytes) -> EvmMessageTrace {
         EvmMessageTrace::Call(CallMessageTrace { code })
@@ -292,35 +296,50 @@ mod tests {
         EvmMessageTrace::Create(CreateMessageTrace { code })
     }
 
-    fn create_test_bytecode(normalized_code: Bytes) -> Bytecode {
-        Bytecode {
+    fn create_test_bytecode_of_type(
+        normalized_code: Bytes,
+        bytecode_type: BytecodeType,
+        library_offsets: Vec<usize>,
+        immutable_references: Vec<ImmutableReference>,
+    ) -> Arc<Bytecode> {
+        let location = SourceLocation {
+            file_id: 0,
+            offset: 0,
+            length: 0,
+        };
+        let contract = Contract::new("Test".to_string(), ContractKind::Contract, location);
+
+        Arc::new(Bytecode::new(
+            Arc::new(contract),
+            Arc::default(),
             normalized_code,
-            bytecode_type: BytecodeType::Runtime,
-            library_offsets: vec![],
-            immutable_references: vec![],
-        }
+            bytecode_type,
+            Vec::new(),
+            library_offsets,
+            immutable_references,
+            "0.8.0".to_string(),
+        ))
+    }
+
+    fn create_test_bytecode(normalized_code: Bytes) -> Arc<Bytecode> {
+        create_test_bytecode_of_type(normalized_code, BytecodeType::Runtime, vec![], vec![])
     }
 
     fn create_test_bytecode_with_libraries_and_immutable_references(
         normalized_code: Bytes,
         library_offsets: Vec<usize>,
         immutable_references: Vec<ImmutableReference>,
-    ) -> Bytecode {
-        Bytecode {
+    ) -> Arc<Bytecode> {
+        create_test_bytecode_of_type(
             normalized_code,
-            bytecode_type: BytecodeType::Runtime,
+            BytecodeType::Runtime,
             library_offsets,
             immutable_references,
-        }
+        )
     }
 
-    fn create_test_deployment_bytecode(normalized_code: Bytes) -> Bytecode {
-        Bytecode {
-            normalized_code,
-            bytecode_type: BytecodeType::Deployment,
-            library_offsets: vec![],
-            immutable_references: vec![],
-        }
+    fn create_test_deployment_bytecode(normalized_code: Bytes) -> Arc<Bytecode> {
+        create_test_bytecode_of_type(normalized_code, BytecodeType::Deployment, vec![], vec![])
     }
 
     #[test]
@@ -343,12 +362,12 @@ mod tests {
         let mut contracts_identifier = ContractsIdentifier::default();
 
         let bytecode = create_test_bytecode(vec![1, 2, 3, 4, 5].into());
-        contracts_identifier.add_bytecode(&bytecode);
+        contracts_identifier.add_bytecode(bytecode.clone());
 
         // should find a bytecode that matches exactly
         let call_trace = create_test_call_trace(vec![1, 2, 3, 4, 5].into());
         let contract = contracts_identifier.bytecode_by_message_trace(call_trace);
-        assert_eq!(contract, Some(&bytecode));
+        assert_eq!(contract, Some(bytecode.clone()));
 
         // should not find a bytecode that doesn't match
         let call_trace = create_test_call_trace(vec![1, 2, 3, 4, 6].into());
@@ -362,18 +381,18 @@ mod tests {
 
         let bytecode1 = create_test_bytecode(vec![1, 2, 3, 4, 5].into());
         let bytecode2 = create_test_bytecode(vec![1, 2, 3, 4, 5, 6, 7, 8].into());
-        contracts_identifier.add_bytecode(&bytecode1);
-        contracts_identifier.add_bytecode(&bytecode2);
+        contracts_identifier.add_bytecode(bytecode1.clone());
+        contracts_identifier.add_bytecode(bytecode2.clone());
 
         // should find the exact match
         let call_trace = create_test_call_trace(vec![1, 2, 3, 4, 5].into());
         let contract = contracts_identifier.bytecode_by_message_trace(call_trace);
-        assert_eq!(contract, Some(&bytecode1));
+        assert_eq!(contract, Some(bytecode1.clone()));
 
         // should find the exact match
         let call_trace = create_test_call_trace(vec![1, 2, 3, 4, 5, 6, 7, 8].into());
         let contract = contracts_identifier.bytecode_by_message_trace(call_trace);
-        assert_eq!(contract, Some(&bytecode2));
+        assert_eq!(contract, Some(bytecode2.clone()));
 
         // should not find a bytecode that doesn't match
         let call_trace = create_test_call_trace(vec![0, 1, 2, 3, 4, 5, 6, 7, 8].into());
@@ -388,8 +407,8 @@ mod tests {
         // add two bytecodes that share a prefix
         let bytecode1 = create_test_bytecode(vec![1, 2, 3, 4, 5].into());
         let bytecode2 = create_test_bytecode(vec![1, 2, 3, 6, 7].into());
-        contracts_identifier.add_bytecode(&bytecode1);
-        contracts_identifier.add_bytecode(&bytecode2);
+        contracts_identifier.add_bytecode(bytecode1.clone());
+        contracts_identifier.add_bytecode(bytecode2.clone());
 
         // search a trace that matches the common prefix
         let call_trace = create_test_call_trace(vec![1, 2, 3].into());
@@ -402,13 +421,13 @@ mod tests {
         let mut contracts_identifier = ContractsIdentifier::default();
 
         let bytecode = create_test_deployment_bytecode(vec![1, 2, 3, 4, 5].into());
-        contracts_identifier.add_bytecode(&bytecode);
+        contracts_identifier.add_bytecode(bytecode.clone());
 
         // a create trace that matches the a deployment bytecode plus some extra stuff
         // (constructor args)
         let create_trace = create_test_create_trace(vec![1, 2, 3, 4, 5, 10, 11].into());
         let contract = contracts_identifier.bytecode_by_message_trace(create_trace);
-        assert_eq!(contract, Some(&bytecode));
+        assert_eq!(contract, Some(bytecode.clone()));
 
         // the same bytecode, but for a call trace, should not match
         let call_trace = create_test_call_trace(vec![1, 2, 3, 4, 5, 10, 11].into());
@@ -418,7 +437,7 @@ mod tests {
         // the same scenario but with a runtime bytecode shouldn't result in matches
         let mut contracts_identifier = ContractsIdentifier::default();
         let bytecode = create_test_bytecode(vec![1, 2, 3, 4, 5].into());
-        contracts_identifier.add_bytecode(&bytecode);
+        contracts_identifier.add_bytecode(bytecode.clone());
 
         let create_trace = create_test_create_trace(vec![1, 2, 3, 4, 5, 10, 11].into());
         let contract = contracts_identifier.bytecode_by_message_trace(create_trace);
@@ -447,7 +466,7 @@ mod tests {
             vec![20],
             vec![],
         );
-        contracts_identifier.add_bytecode(&bytecode);
+        contracts_identifier.add_bytecode(bytecode.clone());
 
         // the same bytecode, but for a call trace, should not match
         let call_trace = create_test_call_trace(
@@ -464,7 +483,7 @@ mod tests {
             .into(),
         );
         let contract = contracts_identifier.bytecode_by_message_trace(call_trace);
-        assert_eq!(contract, Some(&bytecode));
+        assert_eq!(contract, Some(bytecode.clone()));
     }
 
     #[test]
@@ -488,7 +507,7 @@ mod tests {
                 length: 10,
             }],
         );
-        contracts_identifier.add_bytecode(&bytecode);
+        contracts_identifier.add_bytecode(bytecode.clone());
 
         // the same bytecode, but for a call trace, should not match
         let call_trace = create_test_call_trace(
@@ -504,7 +523,7 @@ mod tests {
             .into(),
         );
         let contract = contracts_identifier.bytecode_by_message_trace(call_trace);
-        assert_eq!(contract, Some(&bytecode));
+        assert_eq!(contract, Some(bytecode.clone()));
     }
 
     #[test]
@@ -533,7 +552,7 @@ mod tests {
                 length: 10,
             }],
         );
-        contracts_identifier.add_bytecode(&bytecode);
+        contracts_identifier.add_bytecode(bytecode.clone());
 
         // the same bytecode, but for a call trace, should not match
         let call_trace = create_test_call_trace(
@@ -555,7 +574,7 @@ mod tests {
             .into(),
         );
         let contract = contracts_identifier.bytecode_by_message_trace(call_trace);
-        assert_eq!(contract, Some(&bytecode));
+        assert_eq!(contract, Some(bytecode.clone()));
     }
 
     #[test]
@@ -601,7 +620,7 @@ mod tests {
                 },
             ],
         );
-        contracts_identifier.add_bytecode(&bytecode);
+        contracts_identifier.add_bytecode(bytecode.clone());
 
         // the same bytecode, but for a call trace, should not match
         let call_trace = create_test_call_trace(
@@ -635,7 +654,7 @@ mod tests {
             .into(),
         );
         let contract = contracts_identifier.bytecode_by_message_trace(call_trace);
-        assert_eq!(contract, Some(&bytecode));
+        assert_eq!(contract, Some(bytecode.clone()));
     }
 
     #[test]
@@ -650,7 +669,7 @@ mod tests {
             ]
             .into(),
         );
-        contracts_identifier.add_bytecode(&bytecode);
+        contracts_identifier.add_bytecode(bytecode.clone());
 
         let call_trace = create_test_call_trace(
             vec![
@@ -662,7 +681,7 @@ mod tests {
         );
 
         let contract = contracts_identifier.bytecode_by_message_trace(call_trace);
-        assert_eq!(contract, Some(&bytecode));
+        assert_eq!(contract, Some(bytecode.clone()));
     }
 
     #[test]
@@ -682,7 +701,7 @@ mod tests {
             ]
             .into(),
         );
-        contracts_identifier.add_bytecode(&bytecode);
+        contracts_identifier.add_bytecode(bytecode.clone());
 
         let call_trace = create_test_call_trace(
             vec![
@@ -699,6 +718,6 @@ mod tests {
         );
 
         let contract = contracts_identifier.bytecode_by_message_trace(call_trace);
-        assert_eq!(contract, Some(&bytecode));
+        assert_eq!(contract, Some(bytecode.clone()));
     }
 }
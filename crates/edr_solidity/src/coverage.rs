@@ -0,0 +1,514 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+};
+
+use edr_eth::{Bytes, HashMap};
+use edr_evm::trace::{BeforeMessage, Trace, TraceMessage};
+
+use crate::{
+    build_model::{ContractFunctionType, SourceFile, SourceLocation},
+    contracts_identifier::{
+        CallMessageTrace, ContractsIdentifier, CreateMessageTrace, EvmMessageTrace,
+    },
+};
+
+/// The code executed by a message.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct ExecutedCode {
+    pub is_create: bool,
+    /// The init code for create messages, otherwise the callee's code
+    pub code: Bytes,
+}
+
+impl ExecutedCode {
+    pub fn new(message: &BeforeMessage) -> Self {
+        let code = match message.to {
+            Some(_) => message
+                .code
+                .as_ref()
+                .map(edr_evm::Bytecode::original_bytes)
+                .unwrap_or_default(),
+            None => message.data.clone(),
+        };
+
+        Self {
+            is_create: message.to.is_none(),
+            code,
+        }
+    }
+}
+
+/// Accumulates how often each program counter of each executed bytecode was
+/// executed.
+#[derive(Clone, Debug, Default)]
+pub struct CoverageCollector {
+    hits: HashMap<ExecutedCode, HashMap<u64, u64>>,
+}
+
+impl CoverageCollector {
+    /// Records the instructions executed in the trace.
+    pub fn record(&mut self, trace: &Trace) {
+        // Hits are counted per message, to avoid hashing the code for every step
+        let mut stack: Vec<(ExecutedCode, HashMap<u64, u64>)> = Vec::new();
+
+        for message in &trace.messages {
+            match message {
+                TraceMessage::Before(before) => {
+                    stack.push((ExecutedCode::new(before), HashMap::new()));
+                }
+                TraceMessage::Step(step) => {
+                    if let Some((_, hits)) = stack.last_mut() {
+                        *hits.entry(step.pc).or_default() += 1;
+                    }
+                }
+                TraceMessage::After(_) => {
+                    if let Some((code, hits)) = stack.pop() {
+                        self.add_hits(code, hits);
+                    }
+                }
+            }
+        }
+
+        // Incomplete traces
+        while let Some((code, hits)) = stack.pop() {
+            self.add_hits(code, hits);
+        }
+    }
+
+    /// Whether no instructions have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.hits.is_empty()
+    }
+
+    fn add_hits(&mut self, code: ExecutedCode, hits: HashMap<u64, u64>) {
+        if hits.is_empty() {
+            return;
+        }
+
+        let total_hits = self.hits.entry(code).or_default();
+        for (pc, count) in hits {
+            *total_hits.entry(pc).or_default() += count;
+        }
+    }
+
+    /// Maps the recorded instructions to the Solidity sources of the known
+    /// bytecodes. Instructions of unknown bytecodes are ignored.
+    pub fn report(&self, contracts_identifier: &ContractsIdentifier) -> CoverageReport {
+        // The hits of each executed source range, per source name. If multiple
+        // instructions map to the same range, the most executed one is used.
+        let mut executed_ranges = HashMap::<String, HashMap<(u32, u32), u64>>::new();
+
+        for (code, hits) in &self.hits {
+            let message_trace = if code.is_create {
+                EvmMessageTrace::Create(CreateMessageTrace::new(code.code.clone()))
+            } else {
+                EvmMessageTrace::Call(CallMessageTrace::new(code.code.clone()))
+            };
+
+            let Some(bytecode) = contracts_identifier.bytecode_by_message_trace(message_trace)
+            else {
+                continue;
+            };
+
+            for (pc, count) in hits {
+                let Some(location) = bytecode
+                    .instruction(*pc)
+                    .and_then(|instruction| instruction.location)
+                else {
+                    continue;
+                };
+
+                let Some(source_file) = bytecode.source_file(&location) else {
+                    continue;
+                };
+
+                let range_hits = executed_ranges
+                    .entry(source_file.source_name.clone())
+                    .or_default()
+                    .entry((location.offset, location.length))
+                    .or_default();
+
+                *range_hits = (*range_hits).max(*count);
+            }
+        }
+
+        // Source files can be part of multiple compilation jobs, so they are
+        // deduplicated by name.
+        let mut source_files = BTreeMap::<&str, &SourceFile>::new();
+        for bytecode in contracts_identifier.bytecodes() {
+            for source_file in bytecode.source_files.values() {
+                source_files
+                    .entry(&source_file.source_name)
+                    .or_insert(source_file);
+            }
+        }
+
+        let empty_ranges = HashMap::new();
+        let files = source_files
+            .into_values()
+            .filter(|source_file| {
+                !source_file.functions().is_empty() || !source_file.statements().is_empty()
+            })
+            .map(|source_file| {
+                let executed_ranges = executed_ranges
+                    .get(&source_file.source_name)
+                    .unwrap_or(&empty_ranges);
+
+                FileCoverage::new(source_file, executed_ranges)
+            })
+            .collect();
+
+        CoverageReport { files }
+    }
+}
+
+/// Code coverage of all known Solidity source files.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageReport {
+    /// The coverage per source file, sorted by source name
+    pub files: Vec<FileCoverage>,
+}
+
+impl CoverageReport {
+    /// Formats the report as an LCOV tracefile.
+    pub fn to_lcov(&self) -> String {
+        let mut lcov = String::new();
+        self.write_lcov(&mut lcov)
+            .expect("Writing to a string cannot fail");
+
+        lcov
+    }
+
+    fn write_lcov(&self, writer: &mut impl Write) -> fmt::Result {
+        for file in &self.files {
+            writeln!(writer, "TN:")?;
+            writeln!(writer, "SF:{}", file.source_name)?;
+
+            for function in &file.functions {
+                writeln!(writer, "FN:{},{}", function.line, function.name)?;
+            }
+            for function in &file.functions {
+                writeln!(writer, "FNDA:{},{}", function.hits, function.name)?;
+            }
+            writeln!(writer, "FNF:{}", file.functions.len())?;
+            writeln!(
+                writer,
+                "FNH:{}",
+                file.functions
+                    .iter()
+                    .filter(|function| function.hits > 0)
+                    .count()
+            )?;
+
+            for (block, branch) in file.branches.iter().enumerate() {
+                for (index, hits) in [branch.true_hits, branch.false_hits].iter().enumerate() {
+                    if branch.hits == 0 {
+                        writeln!(writer, "BRDA:{},{block},{index},-", branch.line)?;
+                    } else {
+                        writeln!(writer, "BRDA:{},{block},{index},{hits}", branch.line)?;
+                    }
+                }
+            }
+            writeln!(writer, "BRF:{}", 2 * file.branches.len())?;
+            writeln!(
+                writer,
+                "BRH:{}",
+                file.branches
+                    .iter()
+                    .map(|branch| {
+                        usize::from(branch.true_hits > 0) + usize::from(branch.false_hits > 0)
+                    })
+                    .sum::<usize>()
+            )?;
+
+            for line in &file.lines {
+                writeln!(writer, "DA:{},{}", line.line, line.hits)?;
+            }
+            writeln!(writer, "LF:{}", file.lines.len())?;
+            writeln!(
+                writer,
+                "LH:{}",
+                file.lines.iter().filter(|line| line.hits > 0).count()
+            )?;
+
+            writeln!(writer, "end_of_record")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Code coverage of a single source file.
+///
+/// Hit counts of statements, functions and branches are the number of times
+/// their most executed instruction was executed.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileCoverage {
+    /// The source name, as used by the compiler
+    pub source_name: String,
+    /// The functions and modifiers of the file
+    pub functions: Vec<FunctionCoverage>,
+    /// The statements of the file
+    pub statements: Vec<StatementCoverage>,
+    /// The branches of the file
+    pub branches: Vec<BranchCoverage>,
+    /// The lines that contain statements, sorted by line number
+    pub lines: Vec<LineCoverage>,
+}
+
+impl FileCoverage {
+    fn new(source_file: &SourceFile, executed_ranges: &HashMap<(u32, u32), u64>) -> Self {
+        let hits = |location: &SourceLocation| {
+            executed_ranges
+                .iter()
+                .filter(|((offset, length), _)| {
+                    *offset >= location.offset
+                        && offset + length <= location.offset + location.length
+                })
+                .map(|(_, hits)| *hits)
+                .max()
+                .unwrap_or(0)
+        };
+
+        let functions = source_file
+            .functions()
+            .iter()
+            .filter(|function| function.function_type != ContractFunctionType::Getter)
+            .map(|function| {
+                let name = match function.function_type {
+                    ContractFunctionType::Constructor => "constructor",
+                    ContractFunctionType::Fallback => "fallback",
+                    ContractFunctionType::Receive => "receive",
+                    _ => function.name.as_str(),
+                };
+
+                FunctionCoverage {
+                    name: function
+                        .contract_name
+                        .as_ref()
+                        .map_or_else(|| name.to_string(), |contract| format!("{contract}.{name}")),
+                    line: source_file.line_number(function.location.offset),
+                    hits: hits(&function.location),
+                }
+            })
+            .collect();
+
+        let statements = source_file
+            .statements()
+            .iter()
+            .map(|location| StatementCoverage {
+                line: source_file.line_number(location.offset),
+                offset: location.offset,
+                length: location.length,
+                hits: hits(location),
+            })
+            .collect::<Vec<_>>();
+
+        let branches = source_file
+            .branches()
+            .iter()
+            .map(|branch| {
+                let branch_hits = hits(&branch.condition);
+                let true_hits = hits(&branch.true_body);
+                // Without an else body, the false branch is taken whenever the
+                // true body isn't.
+                let false_hits = branch.false_body.as_ref().map_or_else(
+                    || branch_hits.saturating_sub(true_hits),
+                    |false_body| hits(false_body),
+                );
+
+                BranchCoverage {
+                    line: source_file.line_number(branch.location.offset),
+                    hits: branch_hits,
+                    true_hits,
+                    false_hits,
+                }
+            })
+            .collect();
+
+        let mut lines = BTreeMap::<u32, u64>::new();
+        for statement in &statements {
+            let line_hits = lines.entry(statement.line).or_default();
+            *line_hits = (*line_hits).max(statement.hits);
+        }
+
+        Self {
+            source_name: source_file.source_name.clone(),
+            functions,
+            statements,
+            branches,
+            lines: lines
+                .into_iter()
+                .map(|(line, hits)| LineCoverage { line, hits })
+                .collect(),
+        }
+    }
+}
+
+/// Code coverage of a function or modifier.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionCoverage {
+    /// The name of the function, prefixed by its contract's name
+    pub name: String,
+    /// The 1-based line of the function's definition
+    pub line: u32,
+    /// The number of hits
+    pub hits: u64,
+}
+
+/// Code coverage of a statement.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementCoverage {
+    /// The 1-based line of the start of the statement
+    pub line: u32,
+    /// Byte offset of the statement
+    pub offset: u32,
+    /// Length of the statement in bytes
+    pub length: u32,
+    /// The number of hits
+    pub hits: u64,
+}
+
+/// Code coverage of an `if` statement or conditional expression.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchCoverage {
+    /// The 1-based line of the branch point
+    pub line: u32,
+    /// The number of times the condition was evaluated
+    pub hits: u64,
+    /// The number of times the true branch was taken
+    pub true_hits: u64,
+    /// The number of times the false branch was taken
+    pub false_hits: u64,
+}
+
+/// Code coverage of a line.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineCoverage {
+    /// The 1-based line number
+    pub line: u32,
+    /// The number of hits of the line's most executed statement
+    pub hits: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use edr_eth::{Address, U256};
+    use edr_evm::{trace::Step, Bytecode, Eval, ExecutionResult, Output};
+
+    use super::*;
+
+    fn before_call(code: &'static [u8]) -> TraceMessage {
+        TraceMessage::Before(BeforeMessage {
+            depth: 0,
+            caller: Address::ZERO,
+            to: Some(Address::repeat_byte(1)),
+            gas_limit: 100_000,
+            data: Bytes::new(),
+            value: U256::ZERO,
+            code_address: Some(Address::repeat_byte(1)),
+            code: Some(Bytecode::new_raw(Bytes::from_static(code))),
+        })
+    }
+
+    fn step(pc: u64) -> TraceMessage {
+        TraceMessage::Step(Step {
+            pc,
+            depth: 0,
+            opcode: 0,
+            stack_top: None,
+        })
+    }
+
+    fn after() -> TraceMessage {
+        TraceMessage::After(ExecutionResult::Success {
+            reason: Eval::Stop,
+            gas_used: 0,
+            gas_refunded: 0,
+            logs: Vec::new(),
+            output: Output::Call(Bytes::new()),
+        })
+    }
+
+    #[test]
+    fn record_accumulates_hits() {
+        let trace = Trace {
+            messages: vec![before_call(&[0x01]), step(0), step(1), step(0), after()],
+            return_value: Bytes::new(),
+        };
+
+        let mut collector = CoverageCollector::default();
+        assert!(collector.is_empty());
+
+        collector.record(&trace);
+        collector.record(&trace);
+
+        let hits = collector
+            .hits
+            .get(&ExecutedCode {
+                is_create: false,
+                code: Bytes::from_static(&[0x01]),
+            })
+            .expect("code was executed");
+
+        assert_eq!(hits.get(&0), Some(&4));
+        assert_eq!(hits.get(&1), Some(&2));
+    }
+
+    #[test]
+    fn report_to_lcov() {
+        let report = CoverageReport {
+            files: vec![FileCoverage {
+                source_name: "contracts/Counter.sol".to_string(),
+                functions: vec![FunctionCoverage {
+                    name: "Counter.increment".to_string(),
+                    line: 4,
+                    hits: 2,
+                }],
+                statements: vec![StatementCoverage {
+                    line: 5,
+                    offset: 80,
+                    length: 8,
+                    hits: 2,
+                }],
+                branches: vec![BranchCoverage {
+                    line: 5,
+                    hits: 2,
+                    true_hits: 2,
+                    false_hits: 0,
+                }],
+                lines: vec![
+                    LineCoverage { line: 5, hits: 2 },
+                    LineCoverage { line: 6, hits: 0 },
+                ],
+            }],
+        };
+
+        assert_eq!(
+            report.to_lcov(),
+            "TN:
+SF:contracts/Counter.sol
+FN:4,Counter.increment
+FNDA:2,Counter.increment
+FNF:1
+FNH:1
+BRDA:5,0,0,2
+BRDA:5,0,1,0
+BRF:2
+BRH:1
+DA:5,2
+DA:6,0
+LF:2
+LH:1
+end_of_record
+"
+        );
+    }
+}
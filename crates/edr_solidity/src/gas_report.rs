@@ -0,0 +1,340 @@
+use std::collections::BTreeMap;
+
+use edr_eth::{Bytes, HashMap};
+use edr_evm::{
+    trace::{Trace, TraceMessage},
+    ExecutionResult,
+};
+
+use crate::{
+    contracts_identifier::{
+        CallMessageTrace, ContractsIdentifier, CreateMessageTrace, EvmMessageTrace,
+    },
+    coverage::ExecutedCode,
+};
+
+/// The gas used by the frames of a single bytecode, keyed by the first (at
+/// most) four bytes of their calldata. Deployments use an empty key.
+type FrameGas = HashMap<Bytes, GasUsage>;
+
+/// Accumulates the gas used by call and create frames, per executed bytecode
+/// and function selector.
+///
+/// The gas of a frame is the gas spent by its execution, including nested
+/// frames. It excludes the intrinsic gas and refunds of the transaction.
+#[derive(Clone, Debug, Default)]
+pub struct GasProfiler {
+    frames: HashMap<ExecutedCode, FrameGas>,
+}
+
+impl GasProfiler {
+    /// Records the gas used by the frames of the trace.
+    pub fn record(&mut self, trace: &Trace) {
+        let mut stack = Vec::new();
+
+        for message in &trace.messages {
+            match message {
+                TraceMessage::Before(before) => {
+                    let code = ExecutedCode::new(before);
+                    let key = if code.is_create {
+                        Bytes::new()
+                    } else {
+                        Bytes::copy_from_slice(&before.data[..before.data.len().min(4)])
+                    };
+
+                    stack.push((code, key));
+                }
+                TraceMessage::Step(_) => (),
+                TraceMessage::After(result) => {
+                    if let Some((code, key)) = stack.pop() {
+                        let gas_used = match result {
+                            ExecutionResult::Success { gas_used, .. }
+                            | ExecutionResult::Revert { gas_used, .. }
+                            | ExecutionResult::Halt { gas_used, .. } => *gas_used,
+                        };
+
+                        self.frames
+                            .entry(code)
+                            .or_default()
+                            .entry(key)
+                            .or_default()
+                            .add(gas_used);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether no frames have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Removes all recorded frames.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// Attributes the recorded frames to the functions of the known contracts.
+    /// Frames of unknown bytecodes are ignored.
+    pub fn report(&self, contracts_identifier: &ContractsIdentifier) -> GasReport {
+        // Multiple bytecodes can belong to the same contract, e.g. if they have
+        // different immutable values, so contracts are merged by name.
+        let mut contracts = BTreeMap::<(String, String), ContractGasBuilder>::new();
+
+        for (code, frame_gas) in &self.frames {
+            let message_trace = if code.is_create {
+                EvmMessageTrace::Create(CreateMessageTrace::new(code.code.clone()))
+            } else {
+                EvmMessageTrace::Call(CallMessageTrace::new(code.code.clone()))
+            };
+
+            let Some(bytecode) = contracts_identifier.bytecode_by_message_trace(message_trace)
+            else {
+                continue;
+            };
+
+            let contract = bytecode.contract();
+            let source_name = bytecode
+                .source_file(&contract.location)
+                .map(|source_file| source_file.source_name.clone())
+                .unwrap_or_default();
+
+            let builder = contracts
+                .entry((source_name, contract.name.clone()))
+                .or_default();
+
+            for (key, stats) in frame_gas {
+                if code.is_create {
+                    builder.deployment.merge(stats);
+                    continue;
+                }
+
+                let function = <[u8; 4]>::try_from(key.as_ref()).ok().and_then(|selector| {
+                    contract
+                        .function_from_selector(&selector)
+                        .map(|function| (function.name.clone(), Some(selector)))
+                });
+
+                let function = function.unwrap_or_else(|| {
+                    let name = if key.is_empty() && contract.receive.is_some() {
+                        "receive"
+                    } else {
+                        "fallback"
+                    };
+
+                    (name.to_string(), None)
+                });
+
+                builder.functions.entry(function).or_default().merge(stats);
+            }
+        }
+
+        GasReport {
+            contracts: contracts
+                .into_iter()
+                .map(
+                    |((source_name, contract_name), builder)| ContractGasReport {
+                        source_name,
+                        contract_name,
+                        deployment: (builder.deployment.count > 0)
+                            .then(|| builder.deployment.stats()),
+                        functions: builder
+                            .functions
+                            .into_iter()
+                            .map(|((name, selector), gas)| FunctionGasReport {
+                                name,
+                                selector: selector
+                                    .map(|selector| Bytes::copy_from_slice(&selector)),
+                                gas: gas.stats(),
+                            })
+                            .collect(),
+                    },
+                )
+                .collect(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct ContractGasBuilder {
+    deployment: GasUsage,
+    functions: BTreeMap<(String, Option<[u8; 4]>), GasUsage>,
+}
+
+/// Gas usage of all known contracts that were executed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasReport {
+    /// The gas usage per contract, sorted by source and contract name
+    pub contracts: Vec<ContractGasReport>,
+}
+
+/// Gas usage of a contract.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractGasReport {
+    /// The source name of the contract, as used by the compiler
+    pub source_name: String,
+    /// The name of the contract
+    pub contract_name: String,
+    /// The gas used by deployments of the contract, if it was deployed
+    pub deployment: Option<GasStats>,
+    /// The gas used per function, sorted by name
+    pub functions: Vec<FunctionGasReport>,
+}
+
+/// Gas usage of calls to a function.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionGasReport {
+    /// The name of the function
+    pub name: String,
+    /// The selector of the function. `None` for the fallback and receive
+    /// functions.
+    pub selector: Option<Bytes>,
+    /// The gas used by calls to the function
+    #[serde(flatten)]
+    pub gas: GasStats,
+}
+
+/// Statistics of the gas used by a number of frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasStats {
+    /// The number of frames
+    pub count: u64,
+    /// The minimum gas used
+    pub min: u64,
+    /// The average gas used, rounded down
+    pub avg: u64,
+    /// The maximum gas used
+    pub max: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct GasUsage {
+    count: u64,
+    min: u64,
+    max: u64,
+    total: u128,
+}
+
+impl GasUsage {
+    fn add(&mut self, gas_used: u64) {
+        self.merge(&GasUsage {
+            count: 1,
+            min: gas_used,
+            max: gas_used,
+            total: gas_used.into(),
+        });
+    }
+
+    fn merge(&mut self, other: &GasUsage) {
+        if other.count == 0 {
+            return;
+        }
+
+        if self.count == 0 {
+            *self = *other;
+        } else {
+            self.count += other.count;
+            self.min = self.min.min(other.min);
+            self.max = self.max.max(other.max);
+            self.total += other.total;
+        }
+    }
+
+    fn stats(&self) -> GasStats {
+        let avg = self.total.checked_div(self.count.into()).map_or(0, |avg| {
+            u64::try_from(avg).expect("average is at most the maximum")
+        });
+
+        GasStats {
+            count: self.count,
+            min: self.min,
+            avg,
+            max: self.max,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use edr_eth::{Address, U256};
+    use edr_evm::{trace::BeforeMessage, Bytecode, Eval, Output};
+
+    use super::*;
+
+    fn before_call(code: &'static [u8], data: &'static [u8]) -> TraceMessage {
+        TraceMessage::Before(BeforeMessage {
+            depth: 0,
+            caller: Address::ZERO,
+            to: Some(Address::repeat_byte(1)),
+            gas_limit: 100_000,
+            data: Bytes::from_static(data),
+            value: U256::ZERO,
+            code_address: Some(Address::repeat_byte(1)),
+            code: Some(Bytecode::new_raw(Bytes::from_static(code))),
+        })
+    }
+
+    fn after(gas_used: u64) -> TraceMessage {
+        TraceMessage::After(ExecutionResult::Success {
+            reason: Eval::Stop,
+            gas_used,
+            gas_refunded: 0,
+            logs: Vec::new(),
+            output: Output::Call(Bytes::new()),
+        })
+    }
+
+    #[test]
+    fn record_nested_frames() {
+        let selector = &[0xde, 0xad, 0xbe, 0xef];
+        let trace = Trace {
+            messages: vec![
+                before_call(&[0x01], &[0xde, 0xad, 0xbe, 0xef, 0x00]),
+                before_call(&[0x02], &[]),
+                after(100),
+                after(1_000),
+            ],
+            return_value: Bytes::new(),
+        };
+
+        let mut profiler = GasProfiler::default();
+        assert!(profiler.is_empty());
+
+        profiler.record(&trace);
+        profiler.record(&Trace {
+            messages: vec![before_call(&[0x01], selector), after(2_000)],
+            return_value: Bytes::new(),
+        });
+
+        let outer = profiler.frames[&ExecutedCode {
+            is_create: false,
+            code: Bytes::from_static(&[0x01]),
+        }][&Bytes::from_static(selector)];
+
+        assert_eq!(
+            outer.stats(),
+            GasStats {
+                count: 2,
+                min: 1_000,
+                avg: 1_500,
+                max: 2_000,
+            }
+        );
+
+        let inner = profiler.frames[&ExecutedCode {
+            is_create: false,
+            code: Bytes::from_static(&[0x02]),
+        }][&Bytes::new()];
+
+        assert_eq!(inner.count, 1);
+        assert_eq!(inner.total, 100);
+
+        profiler.clear();
+        assert!(profiler.is_empty());
+    }
+}
@@ -2,10 +2,34 @@
 
 //! Repository of information about contracts written in Solidity.
 
+/// Standard JSON input and output of the Solidity compiler
+pub mod artifacts;
+
 /// Model of the project's codebase
 pub mod build_model;
 
+/// Decoding of Hardhat's `console.log` calls
+pub mod console_log;
+
 /// Map of bytecodes to known contracts
 pub mod contracts_identifier;
 
+/// Code coverage of executed bytecodes
+pub mod coverage;
+
+/// Gas usage per contract and function
+pub mod gas_report;
+
+/// Tree of the messages executed by the EVM
+pub mod message_trace;
+
+/// Solidity-level stack traces of failed messages
+pub mod solidity_stack_trace;
+
+/// Computation of Solidity stack traces from message traces
+pub mod solidity_tracer;
+
+mod compiler;
 mod opcodes;
+mod return_data;
+mod source_map;
@@ -0,0 +1,218 @@
+use edr_eth::{Address, Bytes, U256};
+use edr_evm::{
+    trace::{BeforeMessage, Step, Trace, TraceMessage},
+    ExecutionResult, Output,
+};
+
+/// A call or create message that was executed by the EVM, including its
+/// nested messages.
+#[derive(Clone, Debug)]
+pub struct MessageTrace {
+    /// The call depth
+    pub depth: usize,
+    /// The caller
+    pub caller: Address,
+    /// The callee, or `None` for create messages
+    pub to: Option<Address>,
+    /// The address of the executed code, or `None` for create messages
+    pub code_address: Option<Address>,
+    /// The executed code. For create messages, this is the init code.
+    pub code: Bytes,
+    /// The calldata. Empty for create messages.
+    pub calldata: Bytes,
+    /// The transferred value
+    pub value: U256,
+    /// The EVM steps and nested messages, in order of execution
+    pub steps: Vec<MessageTraceStep>,
+    /// The result of the message, or `None` if the trace is incomplete
+    pub result: Option<ExecutionResult>,
+}
+
+/// A step of a [`MessageTrace`].
+#[derive(Clone, Debug)]
+pub enum MessageTraceStep {
+    /// An executed instruction
+    Evm(Step),
+    /// A nested message
+    Message(MessageTrace),
+}
+
+impl MessageTrace {
+    fn new(message: &BeforeMessage) -> Self {
+        let (code, calldata) = match message.to {
+            Some(_) => (
+                message
+                    .code
+                    .as_ref()
+                    .map(edr_evm::Bytecode::original_bytes)
+                    .unwrap_or_default(),
+                message.data.clone(),
+            ),
+            None => (message.data.clone(), Bytes::new()),
+        };
+
+        Self {
+            depth: message.depth,
+            caller: message.caller,
+            to: message.to,
+            code_address: message.code_address,
+            code,
+            calldata,
+            value: message.value,
+            steps: Vec::new(),
+            result: None,
+        }
+    }
+
+    /// Constructs the tree of messages from the flat list of a [`Trace`].
+    /// Returns `None` if the trace doesn't contain any messages.
+    pub fn from_trace(trace: &Trace) -> Option<Self> {
+        let mut stack: Vec<MessageTrace> = Vec::new();
+        let mut root = None;
+
+        let mut finish = |stack: &mut Vec<MessageTrace>, message: MessageTrace| {
+            if let Some(parent) = stack.last_mut() {
+                parent.steps.push(MessageTraceStep::Message(message));
+            } else {
+                root = Some(message);
+            }
+        };
+
+        for message in &trace.messages {
+            match message {
+                TraceMessage::Before(before) => stack.push(MessageTrace::new(before)),
+                TraceMessage::Step(step) => {
+                    if let Some(current) = stack.last_mut() {
+                        current.steps.push(MessageTraceStep::Evm(step.clone()));
+                    }
+                }
+                TraceMessage::After(result) => {
+                    if let Some(mut message) = stack.pop() {
+                        message.result = Some(result.clone());
+                        finish(&mut stack, message);
+                    }
+                }
+            }
+        }
+
+        // Close messages of incomplete traces
+        while let Some(message) = stack.pop() {
+            finish(&mut stack, message);
+        }
+
+        root
+    }
+
+    /// Whether the message created a contract.
+    pub fn is_create(&self) -> bool {
+        self.to.is_none()
+    }
+
+    /// Whether the message reverted or halted.
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self.result,
+            Some(ExecutionResult::Revert { .. } | ExecutionResult::Halt { .. })
+        )
+    }
+
+    /// The data returned by the message.
+    pub fn return_data(&self) -> Bytes {
+        match &self.result {
+            Some(ExecutionResult::Success { output, .. }) => match output {
+                Output::Call(data) | Output::Create(data, _) => data.clone(),
+            },
+            Some(ExecutionResult::Revert { output, .. }) => output.clone(),
+            Some(ExecutionResult::Halt { .. }) | None => Bytes::new(),
+        }
+    }
+
+    /// The number of nested messages.
+    pub fn number_of_subtraces(&self) -> usize {
+        self.steps
+            .iter()
+            .filter(|step| matches!(step, MessageTraceStep::Message(_)))
+            .count()
+    }
+
+    /// The last nested message, if any.
+    pub fn last_subtrace(&self) -> Option<&MessageTrace> {
+        self.steps.iter().rev().find_map(|step| match step {
+            MessageTraceStep::Message(message) => Some(message),
+            MessageTraceStep::Evm(_) => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use edr_evm::{Bytecode, Eval, Halt};
+
+    use super::*;
+
+    fn before_call(depth: usize, code: Bytes) -> TraceMessage {
+        TraceMessage::Before(BeforeMessage {
+            depth,
+            caller: Address::ZERO,
+            to: Some(Address::repeat_byte(depth as u8 + 1)),
+            gas_limit: 100_000,
+            data: Bytes::from_static(&[0xde, 0xad]),
+            value: U256::ZERO,
+            code_address: Some(Address::repeat_byte(depth as u8 + 1)),
+            code: Some(Bytecode::new_raw(code)),
+        })
+    }
+
+    fn step(depth: u64, pc: u64) -> TraceMessage {
+        TraceMessage::Step(Step {
+            pc,
+            depth,
+            opcode: 0,
+            stack_top: None,
+        })
+    }
+
+    #[test]
+    fn from_trace_nests_messages() {
+        let trace = Trace {
+            messages: vec![
+                before_call(0, Bytes::from_static(&[0x01])),
+                step(0, 0),
+                before_call(1, Bytes::from_static(&[0x02])),
+                step(1, 0),
+                TraceMessage::After(ExecutionResult::Halt {
+                    reason: Halt::InvalidFEOpcode,
+                    gas_used: 10,
+                }),
+                step(0, 1),
+                TraceMessage::After(ExecutionResult::Success {
+                    reason: Eval::Stop,
+                    gas_used: 20,
+                    gas_refunded: 0,
+                    logs: Vec::new(),
+                    output: Output::Call(Bytes::from_static(&[0x03])),
+                }),
+            ],
+            return_value: Bytes::new(),
+        };
+
+        let message = MessageTrace::from_trace(&trace).expect("trace has messages");
+        assert!(!message.is_create());
+        assert!(!message.is_error());
+        assert_eq!(message.code, Bytes::from_static(&[0x01]));
+        assert_eq!(message.calldata, Bytes::from_static(&[0xde, 0xad]));
+        assert_eq!(message.return_data(), Bytes::from_static(&[0x03]));
+        assert_eq!(message.steps.len(), 3);
+        assert_eq!(message.number_of_subtraces(), 1);
+
+        let subtrace = message.last_subtrace().expect("has a subtrace");
+        assert!(subtrace.is_error());
+        assert_eq!(subtrace.depth, 1);
+        assert_eq!(subtrace.steps.len(), 1);
+    }
+
+    #[test]
+    fn from_trace_empty() {
+        assert!(MessageTrace::from_trace(&Trace::default()).is_none());
+    }
+}
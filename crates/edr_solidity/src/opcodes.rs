@@ -1,10 +1,14 @@
-use revm::interpreter::opcode;
+use edr_evm::opcode;
 
-fn is_push(opcode: u8) -> bool {
+pub fn is_push(opcode: u8) -> bool {
     (opcode::PUSH1..=opcode::PUSH32).contains(&opcode)
 }
 
-fn push_length(opcode: u8) -> usize {
+pub fn is_jump(opcode: u8) -> bool {
+    opcode == opcode::JUMP || opcode == opcode::JUMPI
+}
+
+pub fn push_length(opcode: u8) -> usize {
     (opcode - opcode::PUSH1 + 1) as usize
 }
 
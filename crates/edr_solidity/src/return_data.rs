@@ -0,0 +1,171 @@
+use edr_eth::{Address, U256};
+
+use crate::build_model::CustomError;
+
+/// Selector of `Error(string)`
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of `Panic(uint256)`
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+fn matches_selector(return_data: &[u8], selector: &[u8; 4]) -> bool {
+    return_data.get(..4) == Some(selector.as_slice())
+}
+
+/// Whether the return data is an `Error(string)` revert reason.
+pub(crate) fn is_error_return_data(return_data: &[u8]) -> bool {
+    matches_selector(return_data, &ERROR_SELECTOR)
+}
+
+/// Decodes the error code of a `Panic(uint256)` error.
+pub(crate) fn decode_panic(return_data: &[u8]) -> Option<U256> {
+    if return_data.len() != 36 || !matches_selector(return_data, &PANIC_SELECTOR) {
+        return None;
+    }
+
+    Some(U256::from_be_slice(&return_data[4..]))
+}
+
+/// Formats a message for the custom error that the return data encodes.
+pub(crate) fn custom_error_message(custom_errors: &[CustomError], return_data: &[u8]) -> String {
+    let custom_error = custom_errors
+        .iter()
+        .find(|custom_error| matches_selector(return_data, &custom_error.selector));
+
+    match custom_error {
+        Some(custom_error) => {
+            match decode_parameters(&custom_error.parameter_types, &return_data[4..]) {
+                Some(values) => format!(
+                    "reverted with custom error '{}({})'",
+                    custom_error.name,
+                    values.join(", ")
+                ),
+                None => format!(
+                    "reverted with custom error '{}' (return data: 0x{})",
+                    custom_error.name,
+                    hex::encode(return_data)
+                ),
+            }
+        }
+        None => format!(
+            "reverted with an unrecognized custom error (return data: 0x{})",
+            hex::encode(return_data)
+        ),
+    }
+}
+
+fn word(data: &[u8], offset: usize) -> Option<&[u8]> {
+    data.get(offset..offset.checked_add(32)?)
+}
+
+fn word_to_usize(word: &[u8]) -> Option<usize> {
+    usize::try_from(U256::from_be_slice(word)).ok()
+}
+
+/// Decodes ABI-encoded values of elementary types. Returns `None` if any of
+/// the types is not supported or the data is malformed.
+fn decode_parameters(parameter_types: &[String], data: &[u8]) -> Option<Vec<String>> {
+    parameter_types
+        .iter()
+        .enumerate()
+        .map(|(index, parameter_type)| {
+            let head = word(data, index * 32)?;
+            decode_value(parameter_type, head, data)
+        })
+        .collect()
+}
+
+fn decode_value(parameter_type: &str, head: &[u8], data: &[u8]) -> Option<String> {
+    // Arrays and tuples are not supported
+    if parameter_type.ends_with(']') || parameter_type.starts_with('(') {
+        return None;
+    }
+
+    match parameter_type {
+        "address" => Some(Address::from_slice(&head[12..]).to_checksum(None)),
+        "bool" => Some((head[31] != 0).to_string()),
+        "string" | "bytes" => {
+            let offset = word_to_usize(head)?;
+            let length = word_to_usize(word(data, offset)?)?;
+            let start = offset.checked_add(32)?;
+            let value = data.get(start..start.checked_add(length)?)?;
+
+            if parameter_type == "string" {
+                serde_json::to_string(&String::from_utf8_lossy(value)).ok()
+            } else {
+                Some(format!("0x{}", hex::encode(value)))
+            }
+        }
+        _ => {
+            if let Some(size) = parameter_type.strip_prefix("bytes") {
+                let size: usize = size.parse().ok()?;
+                Some(format!("0x{}", hex::encode(head.get(..size)?)))
+            } else if parameter_type.starts_with("uint") {
+                Some(U256::from_be_slice(head).to_string())
+            } else if parameter_type.starts_with("int") {
+                let value = U256::from_be_slice(head);
+                if head[0] & 0x80 == 0 {
+                    Some(value.to_string())
+                } else {
+                    // Two's complement
+                    Some(format!("-{}", (!value).wrapping_add(U256::from(1))))
+                }
+            } else {
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_word(value: u64) -> Vec<u8> {
+        U256::from(value).to_be_bytes::<32>().to_vec()
+    }
+
+    #[test]
+    fn panic_return_data() {
+        let mut return_data = PANIC_SELECTOR.to_vec();
+        return_data.extend(encode_word(0x11));
+
+        assert_eq!(decode_panic(&return_data), Some(U256::from(0x11)));
+        assert!(!is_error_return_data(&return_data));
+        assert_eq!(decode_panic(&return_data[..4]), None);
+    }
+
+    #[test]
+    fn custom_error_with_parameters() {
+        let custom_errors = vec![CustomError {
+            name: "Failure".to_string(),
+            selector: [1, 2, 3, 4],
+            parameter_types: vec![
+                "uint256".to_string(),
+                "int8".to_string(),
+                "string".to_string(),
+            ],
+        }];
+
+        let mut return_data = vec![1, 2, 3, 4];
+        return_data.extend(encode_word(42));
+        return_data.extend([0xff; 32]);
+        return_data.extend(encode_word(96));
+        return_data.extend(encode_word(2));
+        let mut string = b"hi".to_vec();
+        string.resize(32, 0);
+        return_data.extend(string);
+
+        assert_eq!(
+            custom_error_message(&custom_errors, &return_data),
+            "reverted with custom error 'Failure(42, -1, \"hi\")'"
+        );
+    }
+
+    #[test]
+    fn unrecognized_custom_error() {
+        assert_eq!(
+            custom_error_message(&[], &[0xaa, 0xbb, 0xcc, 0xdd]),
+            "reverted with an unrecognized custom error (return data: 0xaabbccdd)"
+        );
+    }
+}
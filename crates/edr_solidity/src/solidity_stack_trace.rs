@@ -0,0 +1,209 @@
+use edr_eth::{Address, Bytes, U256};
+use serde::Serialize;
+
+use crate::build_model::ContractFunctionType;
+
+/// A reference to a range of Solidity source code.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceReference {
+    /// The source name of the file, as used by the compiler
+    pub source_name: String,
+    /// The name of the contract, if any
+    pub contract: Option<String>,
+    /// The name of the function, if any
+    pub function: Option<String>,
+    /// The 1-based line number of the start of the range
+    pub line: u32,
+    /// The byte offsets of the start and end of the range
+    pub range: (u32, u32),
+}
+
+/// An entry of a Solidity stack trace.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "SCREAMING_SNAKE_CASE",
+    rename_all_fields = "camelCase"
+)]
+pub enum StackTraceEntry {
+    /// A call to a Solidity function
+    CallstackEntry {
+        /// The location of the call
+        source_reference: SourceReference,
+        /// The type of the called function
+        function_type: ContractFunctionType,
+    },
+    /// A call to an unrecognized contract
+    UnrecognizedContractCallstackEntry {
+        /// The address of the contract
+        address: Address,
+    },
+    /// The creation of an unrecognized contract
+    UnrecognizedCreateCallstackEntry,
+    /// A jump into a function generated by the compiler
+    InternalFunctionCallstackEntry {
+        /// The program counter of the jump
+        pc: u64,
+        /// The contract that contains the function, if known
+        source_reference: Option<SourceReference>,
+    },
+    /// A failed call to a precompile
+    PrecompileError {
+        /// The number of the precompile
+        precompile: u32,
+    },
+    /// A revert, with or without reason, or an invalid opcode
+    RevertError {
+        /// The location of the revert, if known
+        source_reference: Option<SourceReference>,
+        /// The returned data
+        return_data: Bytes,
+        /// Whether the error was caused by an invalid opcode
+        is_invalid_opcode_error: bool,
+    },
+    /// A revert with a `Panic(uint256)` error
+    PanicError {
+        /// The location of the panic, if known
+        source_reference: Option<SourceReference>,
+        /// The panic code
+        error_code: U256,
+    },
+    /// A revert with a custom error
+    CustomError {
+        /// The location of the revert, if known
+        source_reference: Option<SourceReference>,
+        /// A description of the error
+        message: String,
+    },
+    /// A non-payable function was called with value
+    FunctionNotPayableError {
+        /// The called function
+        source_reference: Option<SourceReference>,
+        /// The transferred value
+        value: U256,
+    },
+    /// A non-payable fallback function was called with value
+    FallbackNotPayableError {
+        /// The fallback function
+        source_reference: Option<SourceReference>,
+        /// The transferred value
+        value: U256,
+    },
+    /// A contract without fallback function was called with an unknown
+    /// selector
+    UnrecognizedFunctionWithoutFallbackError {
+        /// The called contract
+        source_reference: SourceReference,
+    },
+    /// A contract without fallback and receive functions was called without
+    /// calldata
+    MissingFallbackOrReceiveError {
+        /// The called contract
+        source_reference: SourceReference,
+    },
+    /// The deployed code exceeds the maximum contract size
+    ContractTooLargeError {
+        /// The constructor of the contract, if known
+        source_reference: Option<SourceReference>,
+    },
+    /// Any other kind of execution error, e.g. running out of gas
+    OtherExecutionError {
+        /// The last known location, if any
+        source_reference: Option<SourceReference>,
+    },
+    /// A failure in an unrecognized contract
+    UnrecognizedContractError {
+        /// The address of the contract
+        address: Address,
+        /// The returned data
+        return_data: Bytes,
+        /// Whether the error was caused by an invalid opcode
+        is_invalid_opcode_error: bool,
+    },
+    /// A failure while creating an unrecognized contract
+    UnrecognizedCreateError {
+        /// The returned data
+        return_data: Bytes,
+        /// Whether the error was caused by an invalid opcode
+        is_invalid_opcode_error: bool,
+    },
+}
+
+impl StackTraceEntry {
+    /// The source code location of the entry, if known.
+    pub fn source_reference(&self) -> Option<&SourceReference> {
+        match self {
+            StackTraceEntry::CallstackEntry {
+                source_reference, ..
+            }
+            | StackTraceEntry::UnrecognizedFunctionWithoutFallbackError { source_reference }
+            | StackTraceEntry::MissingFallbackOrReceiveError { source_reference } => {
+                Some(source_reference)
+            }
+            StackTraceEntry::InternalFunctionCallstackEntry {
+                source_reference, ..
+            }
+            | StackTraceEntry::RevertError {
+                source_reference, ..
+            }
+            | StackTraceEntry::PanicError {
+                source_reference, ..
+            }
+            | StackTraceEntry::CustomError {
+                source_reference, ..
+            }
+            | StackTraceEntry::FunctionNotPayableError {
+                source_reference, ..
+            }
+            | StackTraceEntry::FallbackNotPayableError {
+                source_reference, ..
+            }
+            | StackTraceEntry::ContractTooLargeError { source_reference }
+            | StackTraceEntry::OtherExecutionError { source_reference } => {
+                source_reference.as_ref()
+            }
+            StackTraceEntry::UnrecognizedContractCallstackEntry { .. }
+            | StackTraceEntry::UnrecognizedCreateCallstackEntry
+            | StackTraceEntry::PrecompileError { .. }
+            | StackTraceEntry::UnrecognizedContractError { .. }
+            | StackTraceEntry::UnrecognizedCreateError { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_entry() {
+        let entry = StackTraceEntry::RevertError {
+            source_reference: Some(SourceReference {
+                source_name: "contracts/Foo.sol".to_string(),
+                contract: Some("Foo".to_string()),
+                function: Some("bar".to_string()),
+                line: 12,
+                range: (100, 120),
+            }),
+            return_data: Bytes::new(),
+            is_invalid_opcode_error: false,
+        };
+
+        assert_eq!(
+            serde_json::to_value(entry).unwrap(),
+            serde_json::json!({
+                "type": "REVERT_ERROR",
+                "sourceReference": {
+                    "sourceName": "contracts/Foo.sol",
+                    "contract": "Foo",
+                    "function": "bar",
+                    "line": 12,
+                    "range": [100, 120],
+                },
+                "returnData": "0x",
+                "isInvalidOpcodeError": false,
+            })
+        );
+    }
+}
@@ -0,0 +1,910 @@
+use std::{mem, sync::Arc};
+
+use edr_eth::U256;
+use edr_evm::{opcode, ExecutionResult, Halt};
+
+use crate::{
+    build_model::{
+        Bytecode, ContractFunction, ContractFunctionType, ContractKind, Instruction, JumpType,
+        SourceLocation,
+    },
+    contracts_identifier::ContractsIdentifier,
+    message_trace::{MessageTrace, MessageTraceStep},
+    return_data,
+    solidity_stack_trace::{SourceReference, StackTraceEntry},
+};
+
+/// The highest address of a precompile
+const MAX_PRECOMPILE_NUMBER: u8 = 0x0a;
+
+/// Computes the Solidity stack trace of a message. Returns an empty stack
+/// trace if the message didn't fail.
+pub fn solidity_stack_trace(
+    contracts_identifier: &ContractsIdentifier,
+    trace: &MessageTrace,
+) -> Vec<StackTraceEntry> {
+    SolidityTracer {
+        contracts_identifier,
+    }
+    .stack_trace(trace)
+}
+
+struct SolidityTracer<'a> {
+    contracts_identifier: &'a ContractsIdentifier,
+}
+
+/// The stack trace of the last nested message of a message.
+struct LastSubmessage<'t> {
+    step_index: usize,
+    trace: &'t MessageTrace,
+    stack_trace: Vec<StackTraceEntry>,
+}
+
+impl<'a> SolidityTracer<'a> {
+    fn stack_trace(&self, trace: &MessageTrace) -> Vec<StackTraceEntry> {
+        if !trace.is_error() {
+            return Vec::new();
+        }
+
+        if let Some(precompile) = precompile_number(trace) {
+            return vec![StackTraceEntry::PrecompileError { precompile }];
+        }
+
+        match self
+            .contracts_identifier
+            .bytecode_by_message_trace(trace.into())
+        {
+            Some(bytecode) => {
+                let message = DecodedMessage {
+                    trace,
+                    bytecode: &bytecode,
+                };
+
+                message
+                    .infer_before_tracing()
+                    .unwrap_or_else(|| self.trace_evm_execution(&message))
+            }
+            None => self.unrecognized_message_stack_trace(trace),
+        }
+    }
+
+    fn unrecognized_message_stack_trace(&self, trace: &MessageTrace) -> Vec<StackTraceEntry> {
+        if let Some(subtrace) = trace.last_subtrace() {
+            // This is not a very exact heuristic, but most of the time it will be right, as
+            // Solidity reverts if a call fails, and most contracts are in Solidity.
+            if subtrace.is_error() && trace.return_data() == subtrace.return_data() {
+                let entry = match trace.to {
+                    Some(address) => {
+                        StackTraceEntry::UnrecognizedContractCallstackEntry { address }
+                    }
+                    None => StackTraceEntry::UnrecognizedCreateCallstackEntry,
+                };
+
+                let mut stack_trace = vec![entry];
+                stack_trace.extend(self.stack_trace(subtrace));
+                return stack_trace;
+            }
+        }
+
+        if is_contract_too_large_error(trace) {
+            return vec![StackTraceEntry::ContractTooLargeError {
+                source_reference: None,
+            }];
+        }
+
+        let return_data = trace.return_data();
+        let is_invalid_opcode_error = is_invalid_opcode_error(trace);
+
+        match trace.to {
+            Some(address) => vec![StackTraceEntry::UnrecognizedContractError {
+                address,
+                return_data,
+                is_invalid_opcode_error,
+            }],
+            None => vec![StackTraceEntry::UnrecognizedCreateError {
+                return_data,
+                is_invalid_opcode_error,
+            }],
+        }
+    }
+
+    fn trace_evm_execution(&self, message: &DecodedMessage<'_>) -> Vec<StackTraceEntry> {
+        let trace = message.trace;
+        let number_of_subtraces = trace.number_of_subtraces();
+
+        let mut stack_trace = Vec::new();
+        let mut subtraces_seen = 0;
+        let mut jumped_into_function = false;
+        let mut last_submessage = None;
+
+        for (step_index, step) in trace.steps.iter().enumerate() {
+            match step {
+                MessageTraceStep::Evm(step) => {
+                    let Some(instruction) = message.bytecode.instruction(step.pc) else {
+                        continue;
+                    };
+
+                    match instruction.jump_type {
+                        JumpType::IntoFunction => {
+                            let next_instruction = match trace.steps.get(step_index + 1) {
+                                Some(MessageTraceStep::Evm(next_step)) => {
+                                    message.bytecode.instruction(next_step.pc)
+                                }
+                                _ => None,
+                            };
+
+                            if let Some(next_instruction) = next_instruction {
+                                if next_instruction.opcode == opcode::JUMPDEST {
+                                    stack_trace.push(message.callstack_entry(instruction));
+                                    if next_instruction.location.is_some() {
+                                        jumped_into_function = true;
+                                    }
+                                }
+                            }
+                        }
+                        JumpType::OutofFunction => {
+                            stack_trace.pop();
+                        }
+                        JumpType::NotJump | JumpType::InternalJump => (),
+                    }
+                }
+                MessageTraceStep::Message(subtrace) => {
+                    subtraces_seen += 1;
+
+                    // If there are more subtraces, this one didn't terminate the execution
+                    if subtraces_seen < number_of_subtraces {
+                        continue;
+                    }
+
+                    last_submessage = Some(LastSubmessage {
+                        step_index,
+                        trace: subtrace,
+                        stack_trace: self.stack_trace(subtrace),
+                    });
+                }
+            }
+        }
+
+        let stack_trace = message
+            .check_last_submessage(&stack_trace, last_submessage)
+            .or_else(|| message.check_last_instruction(&stack_trace, jumped_into_function))
+            .or_else(|| message.check_contract_too_large())
+            .unwrap_or_else(|| {
+                stack_trace.push(StackTraceEntry::OtherExecutionError {
+                    source_reference: message.last_source_reference(),
+                });
+                stack_trace
+            });
+
+        filter_redundant_frames(stack_trace)
+    }
+}
+
+/// A message whose bytecode has been identified.
+struct DecodedMessage<'t> {
+    trace: &'t MessageTrace,
+    bytecode: &'t Bytecode,
+}
+
+impl<'t> DecodedMessage<'t> {
+    fn called_function(&self) -> Option<&ContractFunction> {
+        self.trace
+            .calldata
+            .get(..4)
+            .and_then(|selector| self.bytecode.contract().function_from_selector(selector))
+            .map(Arc::as_ref)
+    }
+
+    fn function_containing(&self, location: &SourceLocation) -> Option<&ContractFunction> {
+        self.bytecode
+            .source_file(location)?
+            .function_containing(location)
+            .map(Arc::as_ref)
+    }
+
+    fn source_reference(
+        &self,
+        location: &SourceLocation,
+        function: Option<String>,
+    ) -> Option<SourceReference> {
+        let file = self.bytecode.source_file(location)?;
+
+        Some(SourceReference {
+            source_name: file.source_name.clone(),
+            contract: Some(self.bytecode.contract().name.clone()),
+            function,
+            line: file.line_number(location.offset),
+            range: (location.offset, location.offset + location.length),
+        })
+    }
+
+    /// Maps a location within a function to a source reference.
+    fn function_source_reference(&self, location: &SourceLocation) -> Option<SourceReference> {
+        let function = self.function_containing(location)?;
+
+        let mut source_reference =
+            self.source_reference(location, Some(function_display_name(function)))?;
+
+        if function.function_type == ContractFunctionType::FreeFunction {
+            source_reference.contract = None;
+        }
+
+        Some(source_reference)
+    }
+
+    fn function_start_source_reference(
+        &self,
+        function: &ContractFunction,
+    ) -> Option<SourceReference> {
+        self.source_reference(&function.location, Some(function_display_name(function)))
+    }
+
+    fn contract_start_source_reference(&self) -> Option<SourceReference> {
+        self.source_reference(&self.bytecode.contract().location, None)
+    }
+
+    fn constructor_start_source_reference(&self) -> Option<SourceReference> {
+        let contract = self.bytecode.contract();
+        let location = contract
+            .constructor
+            .as_ref()
+            .map_or(&contract.location, |constructor| &constructor.location);
+
+        self.source_reference(location, Some("constructor".to_string()))
+    }
+
+    /// The source reference of the last executed instruction that belongs to a
+    /// function.
+    fn last_source_reference(&self) -> Option<SourceReference> {
+        self.trace.steps.iter().rev().find_map(|step| match step {
+            MessageTraceStep::Evm(step) => self
+                .bytecode
+                .instruction(step.pc)?
+                .location
+                .and_then(|location| self.function_source_reference(&location)),
+            MessageTraceStep::Message(_) => None,
+        })
+    }
+
+    fn callstack_entry(&self, instruction: &Instruction) -> StackTraceEntry {
+        let callstack_entry =
+            instruction
+                .location
+                .and_then(|location| match self.function_containing(&location) {
+                    Some(function) => Some((
+                        self.function_source_reference(&location)?,
+                        function.function_type,
+                    )),
+                    None => Some((
+                        self.source_reference(&location, None)?,
+                        ContractFunctionType::Function,
+                    )),
+                });
+
+        match callstack_entry {
+            Some((source_reference, function_type)) => StackTraceEntry::CallstackEntry {
+                source_reference,
+                function_type,
+            },
+            // Jumps made from code without a location, e.g. Yul utility functions generated
+            // by the compiler, don't map to a Solidity function
+            None => StackTraceEntry::InternalFunctionCallstackEntry {
+                pc: instruction.pc,
+                source_reference: self.contract_start_source_reference(),
+            },
+        }
+    }
+
+    fn last_instruction(&self) -> Option<&Instruction> {
+        match self.trace.steps.last()? {
+            MessageTraceStep::Evm(step) => self.bytecode.instruction(step.pc),
+            MessageTraceStep::Message(_) => None,
+        }
+    }
+
+    /// Detects errors that happen before any function code is executed.
+    fn infer_before_tracing(&self) -> Option<Vec<StackTraceEntry>> {
+        let trace = self.trace;
+        let contract = self.bytecode.contract();
+
+        // These errors don't return data
+        if !trace.return_data().is_empty() {
+            return None;
+        }
+
+        if trace.is_create() {
+            let constructor = contract.constructor.as_ref()?;
+            if trace.value > U256::ZERO && !constructor.is_payable {
+                return Some(vec![StackTraceEntry::FunctionNotPayableError {
+                    source_reference: self.constructor_start_source_reference(),
+                    value: trace.value,
+                }]);
+            }
+
+            return None;
+        }
+
+        if let Some(function) = self.called_function() {
+            // Libraries don't have a non-payable check
+            if trace.value > U256::ZERO
+                && contract.kind != ContractKind::Library
+                && !function.is_payable
+            {
+                return Some(vec![StackTraceEntry::FunctionNotPayableError {
+                    source_reference: self.function_start_source_reference(function),
+                    value: trace.value,
+                }]);
+            }
+
+            return None;
+        }
+
+        let has_receive = trace.calldata.is_empty() && contract.receive.is_some();
+        match &contract.fallback {
+            None if !has_receive => {
+                let source_reference = self.contract_start_source_reference()?;
+
+                let entry = if trace.calldata.is_empty() {
+                    StackTraceEntry::MissingFallbackOrReceiveError { source_reference }
+                } else {
+                    StackTraceEntry::UnrecognizedFunctionWithoutFallbackError { source_reference }
+                };
+
+                Some(vec![entry])
+            }
+            Some(fallback) if !has_receive && trace.value > U256::ZERO && !fallback.is_payable => {
+                Some(vec![StackTraceEntry::FallbackNotPayableError {
+                    source_reference: self.function_start_source_reference(fallback),
+                    value: trace.value,
+                }])
+            }
+            _ => None,
+        }
+    }
+
+    /// Detects errors that were propagated from the last nested message.
+    fn check_last_submessage(
+        &self,
+        stack_trace: &[StackTraceEntry],
+        last_submessage: Option<LastSubmessage<'_>>,
+    ) -> Option<Vec<StackTraceEntry>> {
+        let last_submessage = last_submessage?;
+        if !last_submessage.trace.is_error()
+            || !self.is_subtrace_error_propagated(last_submessage.step_index)
+        {
+            return None;
+        }
+
+        let mut stack_trace = stack_trace.to_vec();
+
+        // Add the call or create that generated the message
+        if let Some(instruction) = self.instruction_before_step(last_submessage.step_index) {
+            stack_trace.push(self.callstack_entry(instruction));
+        }
+
+        stack_trace.extend(last_submessage.stack_trace);
+        Some(stack_trace)
+    }
+
+    fn instruction_before_step(&self, step_index: usize) -> Option<&Instruction> {
+        match self.trace.steps.get(step_index.checked_sub(1)?)? {
+            MessageTraceStep::Evm(step) => self.bytecode.instruction(step.pc),
+            MessageTraceStep::Message(_) => None,
+        }
+    }
+
+    fn is_subtrace_error_propagated(&self, subtrace_step_index: usize) -> bool {
+        let Some(MessageTraceStep::Message(subtrace)) = self.trace.steps.get(subtrace_step_index)
+        else {
+            return false;
+        };
+
+        let return_data = self.trace.return_data();
+        if return_data != subtrace.return_data() {
+            return false;
+        }
+
+        if is_out_of_gas_error(self.trace) && is_out_of_gas_error(subtrace) {
+            return true;
+        }
+
+        // If the return data is not empty, and it's still the same, we assume it is
+        // being propagated
+        if !return_data.is_empty() {
+            return true;
+        }
+
+        self.fails_right_after_call(subtrace_step_index)
+    }
+
+    fn fails_right_after_call(&self, subtrace_step_index: usize) -> bool {
+        if self
+            .last_instruction()
+            .map(|instruction| instruction.opcode)
+            != Some(opcode::REVERT)
+        {
+            return false;
+        }
+
+        // Calls are always made from within functions
+        let Some(call_location) = self
+            .instruction_before_step(subtrace_step_index)
+            .and_then(|instruction| instruction.location)
+        else {
+            return false;
+        };
+
+        self.trace.steps[subtrace_step_index + 1..]
+            .iter()
+            .all(|step| match step {
+                MessageTraceStep::Evm(step) => self
+                    .bytecode
+                    .instruction(step.pc)
+                    .and_then(|instruction| instruction.location)
+                    .map_or(true, |location| location == call_location),
+                MessageTraceStep::Message(_) => false,
+            })
+    }
+
+    /// Detects errors caused by the last executed instruction.
+    fn check_last_instruction(
+        &self,
+        stack_trace: &[StackTraceEntry],
+        jumped_into_function: bool,
+    ) -> Option<Vec<StackTraceEntry>> {
+        let last_instruction = self.last_instruction()?;
+
+        if let Some(stack_trace) =
+            self.check_revert_or_invalid_opcode(stack_trace, last_instruction, jumped_into_function)
+        {
+            return Some(stack_trace);
+        }
+
+        if self.trace.is_create() || jumped_into_function {
+            return None;
+        }
+
+        let contract = self.bytecode.contract();
+        let failed_inside_fallback_or_receive = last_instruction.opcode == opcode::REVERT
+            && last_instruction.location.map_or(false, |location| {
+                [&contract.fallback, &contract.receive]
+                    .into_iter()
+                    .flatten()
+                    .any(|function| function.location.contains(&location))
+            });
+
+        let return_data = self.trace.return_data();
+        let is_invalid_opcode_error = last_instruction.opcode == opcode::INVALID;
+
+        if failed_inside_fallback_or_receive {
+            return Some(vec![StackTraceEntry::RevertError {
+                source_reference: last_instruction
+                    .location
+                    .and_then(|location| self.function_source_reference(&location)),
+                return_data,
+                is_invalid_opcode_error,
+            }]);
+        }
+
+        // Sometimes we do fail inside of a function but there's no jump into
+        if let Some(function) = last_instruction
+            .location
+            .and_then(|location| self.function_containing(&location))
+        {
+            return Some(vec![StackTraceEntry::RevertError {
+                source_reference: self.function_start_source_reference(function),
+                return_data,
+                is_invalid_opcode_error,
+            }]);
+        }
+
+        Some(vec![StackTraceEntry::OtherExecutionError {
+            source_reference: self.contract_start_source_reference(),
+        }])
+    }
+
+    fn check_revert_or_invalid_opcode(
+        &self,
+        stack_trace: &[StackTraceEntry],
+        last_instruction: &Instruction,
+        jumped_into_function: bool,
+    ) -> Option<Vec<StackTraceEntry>> {
+        if last_instruction.opcode != opcode::REVERT && last_instruction.opcode != opcode::INVALID {
+            return None;
+        }
+
+        let mut stack_trace = stack_trace.to_vec();
+        let return_data = self.trace.return_data();
+        let is_invalid_opcode_error = last_instruction.opcode == opcode::INVALID;
+        let source_reference = last_instruction
+            .location
+            .and_then(|location| self.function_source_reference(&location));
+
+        if let Some(error_code) = return_data::decode_panic(&return_data) {
+            // If the last frame is an internal function, it means that the trace jumped
+            // there to return the panic. If that's the case, we remove that frame.
+            if let Some(StackTraceEntry::InternalFunctionCallstackEntry { .. }) = stack_trace.last()
+            {
+                stack_trace.pop();
+            }
+
+            // If the error comes from a call to a zero-initialized function, we remove the
+            // last frame, which represents the call, to avoid having duplicated frames
+            if error_code == U256::from(0x51) {
+                stack_trace.pop();
+            }
+
+            stack_trace.push(StackTraceEntry::PanicError {
+                source_reference,
+                error_code,
+            });
+            return Some(stack_trace);
+        }
+
+        if !return_data.is_empty() && !return_data::is_error_return_data(&return_data) {
+            stack_trace.push(StackTraceEntry::CustomError {
+                source_reference,
+                message: return_data::custom_error_message(
+                    &self.bytecode.contract().custom_errors,
+                    &return_data,
+                ),
+            });
+            return Some(stack_trace);
+        }
+
+        if let Some(location) = last_instruction.location {
+            if !self.trace.is_create() && !jumped_into_function {
+                return None;
+            }
+
+            let source_reference = if self.function_containing(&location).is_some() {
+                source_reference
+            } else if self.trace.is_create() {
+                self.constructor_start_source_reference()
+            } else {
+                // This is here because of the optimizations
+                let function = self.called_function()?;
+                self.function_start_source_reference(function)
+            };
+
+            stack_trace.push(StackTraceEntry::RevertError {
+                source_reference,
+                return_data,
+                is_invalid_opcode_error,
+            });
+            return Some(stack_trace);
+        }
+
+        // If the revert instruction is not mapped but there is return data, we add the
+        // frame anyway, with the best source reference we can get
+        if !return_data.is_empty() {
+            stack_trace.push(StackTraceEntry::RevertError {
+                source_reference: self
+                    .last_source_reference()
+                    .or_else(|| self.contract_start_source_reference()),
+                return_data,
+                is_invalid_opcode_error,
+            });
+            return Some(stack_trace);
+        }
+
+        None
+    }
+
+    fn check_contract_too_large(&self) -> Option<Vec<StackTraceEntry>> {
+        if self.trace.is_create() && is_contract_too_large_error(self.trace) {
+            Some(vec![StackTraceEntry::ContractTooLargeError {
+                source_reference: self.constructor_start_source_reference(),
+            }])
+        } else {
+            None
+        }
+    }
+}
+
+fn function_display_name(function: &ContractFunction) -> String {
+    match function.function_type {
+        ContractFunctionType::Constructor => "constructor".to_string(),
+        ContractFunctionType::Fallback => "<fallback>".to_string(),
+        ContractFunctionType::Receive => "<receive>".to_string(),
+        _ => function.name.clone(),
+    }
+}
+
+fn precompile_number(trace: &MessageTrace) -> Option<u32> {
+    let address = trace.to?;
+    let bytes = address.as_slice();
+    let (prefix, number) = bytes.split_at(bytes.len() - 1);
+
+    if prefix.iter().all(|byte| *byte == 0) && (1..=MAX_PRECOMPILE_NUMBER).contains(&number[0]) {
+        Some(u32::from(number[0]))
+    } else {
+        None
+    }
+}
+
+fn is_contract_too_large_error(trace: &MessageTrace) -> bool {
+    matches!(
+        trace.result,
+        Some(ExecutionResult::Halt {
+            reason: Halt::CreateContractSizeLimit,
+            ..
+        })
+    )
+}
+
+fn is_invalid_opcode_error(trace: &MessageTrace) -> bool {
+    matches!(
+        trace.result,
+        Some(ExecutionResult::Halt {
+            reason: Halt::OpcodeNotFound | Halt::InvalidFEOpcode,
+            ..
+        })
+    )
+}
+
+fn is_out_of_gas_error(trace: &MessageTrace) -> bool {
+    matches!(
+        trace.result,
+        Some(ExecutionResult::Halt {
+            reason: Halt::OutOfGas(_),
+            ..
+        })
+    )
+}
+
+/// Removes call stack entries whose location contains the location of the next
+/// entry, as they don't provide any additional information.
+fn filter_redundant_frames(stack_trace: Vec<StackTraceEntry>) -> Vec<StackTraceEntry> {
+    let is_redundant: Vec<bool> = stack_trace
+        .windows(2)
+        .enumerate()
+        .map(|(index, frames)| {
+            let (frame, next_frame) = (&frames[0], &frames[1]);
+
+            let (Some(source_reference), Some(next_source_reference)) =
+                (frame.source_reference(), next_frame.source_reference())
+            else {
+                return false;
+            };
+
+            if source_reference.function.as_deref() == Some("constructor")
+                && next_source_reference.function.as_deref() != Some("constructor")
+            {
+                return false;
+            }
+
+            // This is probably a recursive call
+            if index > 0
+                && mem::discriminant(frame) == mem::discriminant(next_frame)
+                && source_reference.range == next_source_reference.range
+                && source_reference.line == next_source_reference.line
+            {
+                return false;
+            }
+
+            source_reference.range.0 <= next_source_reference.range.0
+                && source_reference.range.1 >= next_source_reference.range.1
+        })
+        .collect();
+
+    stack_trace
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| !is_redundant.get(*index).copied().unwrap_or(false))
+        .map(|(_, frame)| frame)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use edr_eth::{Address, Bytes};
+    use edr_evm::trace::Step;
+
+    use super::*;
+    use crate::build_model::{
+        BytecodeType, Contract, ContractFunctionVisibility, CustomError, SourceFile,
+    };
+
+    const SOURCE: &str =
+        "contract Test {\n    function run() public {\n        revert();\n    }\n}\n";
+    const FUNCTION: &str = "function run() public {\n        revert();\n    }";
+    const REVERT: &str = "revert();";
+
+    /// PUSH1 0x04, JUMP into `run`, JUMPDEST, PUSH1 0x00, PUSH1 0x00, REVERT
+    const CODE: [u8; 9] = [0x60, 0x04, 0x56, 0x5b, 0x60, 0x00, 0x60, 0x00, 0xfd];
+    const PCS: [u64; 6] = [0, 2, 3, 4, 6, 8];
+
+    const RUN_SELECTOR: [u8; 4] = [0xc0, 0x40, 0x62, 0x26];
+
+    fn location(fragment: &str) -> SourceLocation {
+        let offset = SOURCE
+            .find(fragment)
+            .expect("fragment is part of the source");
+
+        SourceLocation {
+            file_id: 0,
+            offset: offset as u32,
+            length: fragment.len() as u32,
+        }
+    }
+
+    fn contracts_identifier() -> ContractsIdentifier {
+        let function = Arc::new(ContractFunction {
+            name: "run".to_string(),
+            function_type: ContractFunctionType::Function,
+            location: location(FUNCTION),
+            contract_name: Some("Test".to_string()),
+            visibility: Some(ContractFunctionVisibility::Public),
+            is_payable: false,
+            selector: Some(RUN_SELECTOR),
+        });
+
+        let mut source_file = SourceFile::new("Test.sol".to_string(), SOURCE.to_string());
+        source_file.add_function(function.clone());
+
+        let mut contract = Contract::new(
+            "Test".to_string(),
+            ContractKind::Contract,
+            location(SOURCE.trim_end()),
+        );
+        contract.add_local_function(function);
+        contract.custom_errors.push(CustomError {
+            name: "Failure".to_string(),
+            selector: [1, 2, 3, 4],
+            parameter_types: vec!["uint256".to_string()],
+        });
+
+        let instructions = PCS
+            .into_iter()
+            .map(|pc| {
+                let opcode = CODE[pc as usize];
+                let (jump_type, location) = match opcode {
+                    opcode::JUMP => (JumpType::IntoFunction, Some(location(FUNCTION))),
+                    opcode::JUMPDEST => (JumpType::NotJump, Some(location(FUNCTION))),
+                    opcode::REVERT => (JumpType::NotJump, Some(location(REVERT))),
+                    _ => (JumpType::NotJump, None),
+                };
+
+                Instruction {
+                    pc,
+                    opcode,
+                    jump_type,
+                    push_data: None,
+                    location,
+                }
+            })
+            .collect();
+
+        let bytecode = Bytecode::new(
+            Arc::new(contract),
+            Arc::new([(0, source_file)].into_iter().collect()),
+            Bytes::from_static(&CODE),
+            BytecodeType::Runtime,
+            instructions,
+            Vec::new(),
+            Vec::new(),
+            "0.8.0".to_string(),
+        );
+
+        let mut contracts_identifier = ContractsIdentifier::default();
+        contracts_identifier.add_bytecode(Arc::new(bytecode));
+        contracts_identifier
+    }
+
+    /// Computes the stack trace of a call to `run` that reverts with the
+    /// provided return data.
+    fn stack_trace_of_revert(return_data: Vec<u8>) -> Vec<StackTraceEntry> {
+        let address = Address::repeat_byte(0x42);
+        let trace = MessageTrace {
+            depth: 0,
+            caller: Address::ZERO,
+            to: Some(address),
+            code_address: Some(address),
+            code: Bytes::from_static(&CODE),
+            calldata: Bytes::from_static(&RUN_SELECTOR),
+            value: U256::ZERO,
+            steps: PCS
+                .into_iter()
+                .map(|pc| {
+                    MessageTraceStep::Evm(Step {
+                        pc,
+                        depth: 0,
+                        opcode: CODE[pc as usize],
+                        stack_top: None,
+                    })
+                })
+                .collect(),
+            result: Some(ExecutionResult::Revert {
+                gas_used: 100,
+                output: return_data.into(),
+            }),
+        };
+
+        solidity_stack_trace(&contracts_identifier(), &trace)
+    }
+
+    fn assert_revert_location(source_reference: &Option<SourceReference>) {
+        let source_reference = source_reference
+            .as_ref()
+            .expect("revert has a source reference");
+
+        assert_eq!(source_reference.source_name, "Test.sol");
+        assert_eq!(source_reference.contract.as_deref(), Some("Test"));
+        assert_eq!(source_reference.function.as_deref(), Some("run"));
+        assert_eq!(source_reference.line, 3);
+    }
+
+    fn encode_word(value: u64) -> Vec<u8> {
+        U256::from(value).to_be_bytes::<32>().to_vec()
+    }
+
+    #[test]
+    fn revert_with_reason() {
+        // Error("no")
+        let mut return_data = vec![0x08, 0xc3, 0x79, 0xa0];
+        return_data.extend(encode_word(32));
+        return_data.extend(encode_word(2));
+        let mut reason = b"no".to_vec();
+        reason.resize(32, 0);
+        return_data.extend(reason);
+
+        let stack_trace = stack_trace_of_revert(return_data.clone());
+        assert_eq!(stack_trace.len(), 1);
+
+        let StackTraceEntry::RevertError {
+            source_reference,
+            return_data: error_return_data,
+            is_invalid_opcode_error,
+        } = &stack_trace[0]
+        else {
+            panic!("unexpected stack trace entry: {:?}", stack_trace[0]);
+        };
+
+        assert_revert_location(source_reference);
+        assert_eq!(*error_return_data, Bytes::from(return_data));
+        assert!(!is_invalid_opcode_error);
+    }
+
+    #[test]
+    fn revert_with_panic() {
+        // Panic(0x11)
+        let mut return_data = vec![0x4e, 0x48, 0x7b, 0x71];
+        return_data.extend(encode_word(0x11));
+
+        let stack_trace = stack_trace_of_revert(return_data);
+        assert_eq!(stack_trace.len(), 1);
+
+        let StackTraceEntry::PanicError {
+            source_reference,
+            error_code,
+        } = &stack_trace[0]
+        else {
+            panic!("unexpected stack trace entry: {:?}", stack_trace[0]);
+        };
+
+        assert_revert_location(source_reference);
+        assert_eq!(*error_code, U256::from(0x11));
+    }
+
+    #[test]
+    fn revert_with_custom_error() {
+        // Failure(42)
+        let mut return_data = vec![1, 2, 3, 4];
+        return_data.extend(encode_word(42));
+
+        let stack_trace = stack_trace_of_revert(return_data);
+        assert_eq!(stack_trace.len(), 1);
+
+        let StackTraceEntry::CustomError {
+            source_reference,
+            message,
+        } = &stack_trace[0]
+        else {
+            panic!("unexpected stack trace entry: {:?}", stack_trace[0]);
+        };
+
+        assert_revert_location(source_reference);
+        assert_eq!(message, "reverted with custom error 'Failure(42)'");
+    }
+}
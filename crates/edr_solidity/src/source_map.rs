@@ -0,0 +1,245 @@
+use crate::{
+    build_model::{Instruction, JumpType, SourceFiles, SourceLocation},
+    opcodes::{is_jump, is_push, opcode_length, push_length},
+};
+
+/// A single, uncompressed entry of a source map.
+#[derive(Clone, Debug, PartialEq)]
+struct SourceMapEntry {
+    offset: u32,
+    length: u32,
+    /// The source file's ID, or -1 if the instruction doesn't map to a source
+    /// file.
+    file: i64,
+    jump_type: JumpType,
+}
+
+fn jump_letter_to_jump_type(letter: &str) -> JumpType {
+    match letter {
+        "i" => JumpType::IntoFunction,
+        "o" => JumpType::OutofFunction,
+        _ => JumpType::NotJump,
+    }
+}
+
+/// Uncompresses a source map of the format `s:l:f:j;...`, where empty fields
+/// inherit the value of the previous entry.
+fn uncompress_source_map(compressed: &str) -> Vec<SourceMapEntry> {
+    let mut entries: Vec<SourceMapEntry> = Vec::new();
+
+    for mapping in compressed.split(';') {
+        let mut parts = mapping.split(':');
+        let mut next_part = || parts.next().filter(|part| !part.is_empty());
+
+        let offset = next_part().and_then(|offset| offset.parse().ok());
+        let length = next_part().and_then(|length| length.parse().ok());
+        let file = next_part().and_then(|file| file.parse().ok());
+        let jump_type = next_part().map(jump_letter_to_jump_type);
+
+        let entry = match entries.last() {
+            Some(previous) => SourceMapEntry {
+                offset: offset.unwrap_or(previous.offset),
+                length: length.unwrap_or(previous.length),
+                file: file.unwrap_or(previous.file),
+                jump_type: jump_type.unwrap_or(previous.jump_type),
+            },
+            // See: https://github.com/nomiclabs/hardhat/issues/593
+            None => SourceMapEntry {
+                offset: offset.unwrap_or(0),
+                length: length.unwrap_or(0),
+                file: file.unwrap_or(-1),
+                jump_type: jump_type.unwrap_or(JumpType::NotJump),
+            },
+        };
+
+        entries.push(entry);
+    }
+
+    entries
+}
+
+fn decode_instruction(
+    bytecode: &[u8],
+    pc: usize,
+    jump_type: JumpType,
+    location: Option<SourceLocation>,
+) -> Instruction {
+    let opcode = bytecode[pc];
+
+    let push_data = if is_push(opcode) {
+        let start = (pc + 1).min(bytecode.len());
+        let end = (start + push_length(opcode)).min(bytecode.len());
+        Some(bytecode[start..end].to_vec().into())
+    } else {
+        None
+    };
+
+    let jump_type = if is_jump(opcode) && jump_type == JumpType::NotJump {
+        JumpType::InternalJump
+    } else {
+        jump_type
+    };
+
+    Instruction {
+        pc: pc as u64,
+        opcode,
+        jump_type,
+        push_data,
+        location,
+    }
+}
+
+/// Decodes the instructions of a bytecode, using its compressed source map to
+/// attach source locations.
+pub(crate) fn decode_instructions(
+    bytecode: &[u8],
+    compressed_source_map: &str,
+    source_files: &SourceFiles,
+    is_deployment: bool,
+) -> Vec<Instruction> {
+    let source_map = uncompress_source_map(compressed_source_map);
+
+    let mut instructions = Vec::with_capacity(source_map.len());
+    let mut pc = 0;
+
+    // Solidity inlines some data after the contract, so we stop decoding as soon
+    // as we have as many instructions as source map entries.
+    for entry in source_map {
+        if pc >= bytecode.len() {
+            break;
+        }
+
+        let location = u32::try_from(entry.file)
+            .ok()
+            .filter(|file_id| source_files.contains_key(file_id))
+            .map(|file_id| SourceLocation {
+                file_id,
+                offset: entry.offset,
+                length: entry.length,
+            });
+
+        let instruction = decode_instruction(bytecode, pc, entry.jump_type, location);
+        pc += opcode_length(instruction.opcode);
+
+        instructions.push(instruction);
+    }
+
+    // The source maps of deployment bytecodes don't cover the code that's
+    // appended to them. See: https://github.com/ethereum/solidity/issues/9133
+    if is_deployment {
+        while pc < bytecode.len() {
+            let instruction = decode_instruction(bytecode, pc, JumpType::NotJump, None);
+            pc += opcode_length(instruction.opcode);
+
+            instructions.push(instruction);
+        }
+    }
+
+    instructions
+}
+
+#[cfg(test)]
+mod tests {
+    use edr_eth::HashMap;
+    use edr_evm::opcode;
+
+    use super::*;
+    use crate::build_model::SourceFile;
+
+    #[test]
+    fn uncompress_inherits_previous_values() {
+        let entries = uncompress_source_map("1:2:0:i;:3;;4::-1:o;:::-");
+
+        assert_eq!(
+            entries,
+            vec![
+                SourceMapEntry {
+                    offset: 1,
+                    length: 2,
+                    file: 0,
+                    jump_type: JumpType::IntoFunction,
+                },
+                SourceMapEntry {
+                    offset: 1,
+                    length: 3,
+                    file: 0,
+                    jump_type: JumpType::IntoFunction,
+                },
+                SourceMapEntry {
+                    offset: 1,
+                    length: 3,
+                    file: 0,
+                    jump_type: JumpType::IntoFunction,
+                },
+                SourceMapEntry {
+                    offset: 4,
+                    length: 3,
+                    file: -1,
+                    jump_type: JumpType::OutofFunction,
+                },
+                SourceMapEntry {
+                    offset: 4,
+                    length: 3,
+                    file: -1,
+                    jump_type: JumpType::NotJump,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn uncompress_first_entry_without_values() {
+        let entries = uncompress_source_map(";1:1:0");
+
+        assert_eq!(entries[0].file, -1);
+        assert_eq!(entries[0].jump_type, JumpType::NotJump);
+        assert_eq!(entries[1].file, 0);
+    }
+
+    #[test]
+    fn decode_instructions_with_push_data_and_jumps() {
+        let mut source_files = HashMap::new();
+        source_files.insert(0, SourceFile::new("a.sol".to_string(), String::new()));
+
+        // PUSH1 0x04 JUMP JUMPDEST STOP
+        let bytecode = [
+            opcode::PUSH1,
+            0x04,
+            opcode::JUMP,
+            opcode::JUMPDEST,
+            opcode::STOP,
+        ];
+        let instructions =
+            decode_instructions(&bytecode, "0:10:0;:::i;:::-;5:1:1", &source_files, false);
+
+        assert_eq!(instructions.len(), 4);
+        assert_eq!(instructions[0].push_data, Some(vec![0x04].into()));
+        assert_eq!(instructions[1].pc, 2);
+        assert_eq!(instructions[1].jump_type, JumpType::IntoFunction);
+        assert_eq!(instructions[2].jump_type, JumpType::NotJump);
+        assert_eq!(
+            instructions[2].location,
+            Some(SourceLocation {
+                file_id: 0,
+                offset: 0,
+                length: 10
+            })
+        );
+        // File 1 is not part of the compilation job
+        assert_eq!(instructions[3].location, None);
+    }
+
+    #[test]
+    fn decode_instructions_of_deployment_bytecode_appends_unmapped_code() {
+        let source_files = HashMap::new();
+
+        // STOP JUMP PUSH2 0x0102
+        let bytecode = [opcode::STOP, opcode::JUMP, opcode::PUSH2, 0x01, 0x02];
+        let instructions = decode_instructions(&bytecode, "0:1:-1", &source_files, true);
+
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(instructions[1].jump_type, JumpType::InternalJump);
+        assert_eq!(instructions[2].pc, 2);
+        assert_eq!(instructions[2].push_data, Some(vec![0x01, 0x02].into()));
+    }
+}
@@ -0,0 +1,184 @@
+use edr_eth::{Bytes, B256, U256};
+use edr_evm::RandomHashGenerator;
+
+/// A Solidity parameter type for which fuzzed inputs can be generated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum FuzzType {
+    Address,
+    Bool,
+    /// `bytesN`, with the number of bytes
+    FixedBytes(usize),
+    /// `intN`, with the number of bits
+    Int(usize),
+    /// `uintN`, with the number of bits
+    Uint(usize),
+}
+
+impl FuzzType {
+    fn parse(ty: &str) -> Option<Self> {
+        match ty {
+            "address" => Some(Self::Address),
+            "bool" => Some(Self::Bool),
+            _ => {
+                if let Some(bits) = ty.strip_prefix("uint") {
+                    parse_bits(bits).map(Self::Uint)
+                } else if let Some(bits) = ty.strip_prefix("int") {
+                    parse_bits(bits).map(Self::Int)
+                } else if let Some(size) = ty.strip_prefix("bytes") {
+                    size.parse()
+                        .ok()
+                        .filter(|size| (1..=32).contains(size))
+                        .map(Self::FixedBytes)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Converts a random word into an ABI-encoded value of the type.
+    fn encode_random(self, word: B256) -> B256 {
+        let value = U256::from_be_bytes(word.0);
+
+        let encoded = match self {
+            FuzzType::Address => value & (U256::MAX >> 96),
+            FuzzType::Bool => value & U256::from(1),
+            FuzzType::FixedBytes(size) => value & (U256::MAX << (256 - 8 * size)),
+            FuzzType::Int(bits) => {
+                let value = value & (U256::MAX >> (256 - bits));
+                if bits < 256 && value.bit(bits - 1) {
+                    value | (U256::MAX << bits)
+                } else {
+                    value
+                }
+            }
+            FuzzType::Uint(bits) => value & (U256::MAX >> (256 - bits)),
+        };
+
+        B256::new(encoded.to_be_bytes())
+    }
+}
+
+fn parse_bits(bits: &str) -> Option<usize> {
+    if bits.is_empty() {
+        return Some(256);
+    }
+
+    bits.parse()
+        .ok()
+        .filter(|bits| bits % 8 == 0 && (8..=256).contains(bits))
+}
+
+/// Parses the parameter types of a function signature, e.g.
+/// `testFuzz_transfer(address,uint256)`.
+pub(super) fn parse_parameters(signature: &str) -> Result<Vec<FuzzType>, String> {
+    let parameters = signature
+        .split_once('(')
+        .and_then(|(_, rest)| rest.strip_suffix(')'))
+        .ok_or_else(|| format!("Invalid function signature: {signature}"))?;
+
+    if parameters.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    parameters
+        .split(',')
+        .map(|ty| {
+            FuzzType::parse(ty).ok_or_else(|| format!("Unsupported fuzz parameter type: {ty}"))
+        })
+        .collect()
+}
+
+/// Generates the calldata of fuzz runs from a seeded generator, so failures
+/// can be reproduced.
+pub(super) struct FuzzInputGenerator {
+    generator: RandomHashGenerator,
+    selector: [u8; 4],
+    parameters: Vec<FuzzType>,
+}
+
+impl FuzzInputGenerator {
+    pub fn new(seed: &str, selector: [u8; 4], parameters: Vec<FuzzType>) -> Self {
+        Self {
+            generator: RandomHashGenerator::with_seed(seed),
+            selector,
+            parameters,
+        }
+    }
+
+    pub fn next_calldata(&mut self) -> Bytes {
+        let mut calldata = self.selector.to_vec();
+        for parameter in &self.parameters {
+            let word = parameter.encode_random(self.generator.generate_next());
+            calldata.extend_from_slice(word.as_slice());
+        }
+
+        calldata.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_parameters_success() {
+        assert_eq!(parse_parameters("testFuzz_nothing()"), Ok(Vec::new()));
+        assert_eq!(
+            parse_parameters("testFuzz_all(address,bool,bytes4,int8,uint)"),
+            Ok(vec![
+                FuzzType::Address,
+                FuzzType::Bool,
+                FuzzType::FixedBytes(4),
+                FuzzType::Int(8),
+                FuzzType::Uint(256),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_parameters_unsupported() {
+        assert_eq!(
+            parse_parameters("testFuzz_bytes(bytes)"),
+            Err("Unsupported fuzz parameter type: bytes".to_string())
+        );
+        assert_eq!(
+            parse_parameters("testFuzz_uint(uint7)"),
+            Err("Unsupported fuzz parameter type: uint7".to_string())
+        );
+    }
+
+    #[test]
+    fn encode_random_stays_in_range() {
+        let word = B256::repeat_byte(0xff);
+
+        assert_eq!(
+            FuzzType::Uint(8).encode_random(word),
+            B256::with_last_byte(0xff)
+        );
+        assert_eq!(FuzzType::Int(8).encode_random(word), word);
+        assert_eq!(
+            FuzzType::Int(8).encode_random(B256::with_last_byte(0x7f)),
+            B256::with_last_byte(0x7f)
+        );
+        assert_eq!(FuzzType::Bool.encode_random(word), B256::with_last_byte(1));
+        assert_eq!(
+            &FuzzType::FixedBytes(2).encode_random(word)[..3],
+            &[0xff, 0xff, 0x00]
+        );
+    }
+
+    #[test]
+    fn generator_is_deterministic() {
+        let parameters = vec![FuzzType::Address, FuzzType::Uint(256)];
+
+        let mut first = FuzzInputGenerator::new("seed", [1, 2, 3, 4], parameters.clone());
+        let mut second = FuzzInputGenerator::new("seed", [1, 2, 3, 4], parameters);
+
+        let calldata = first.next_calldata();
+        assert_eq!(calldata.len(), 4 + 2 * 32);
+        assert_eq!(&calldata[..4], &[1, 2, 3, 4]);
+        assert_eq!(calldata, second.next_calldata());
+        assert_ne!(first.next_calldata(), calldata);
+    }
+}
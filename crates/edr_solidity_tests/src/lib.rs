@@ -0,0 +1,846 @@
+#![warn(missing_docs)]
+
+//! Runner for Solidity tests, i.e. test contracts with `test`-prefixed
+//! functions, executed against the chain of a provider config.
+
+mod fuzz;
+
+use std::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use alloy_sol_types::{ContractError, GenericContractError, SolInterface};
+use edr_eth::{Address, Bytes, U256};
+use edr_evm::{
+    blockchain::{BlockchainError, SyncBlockchain},
+    hex, keccak256,
+    state::{AccountModifierFn, StateError, SyncState},
+    AccountInfo, BlockEnv, CfgEnv, CreateScheme, ExecutionResult, Log, Output, TransactTo,
+    TransactionError, TxEnv, KECCAK_EMPTY,
+};
+use edr_provider::{
+    block_env_from_header, create_initial_blockchain_and_state, panic_code_to_error_reason,
+    CreationError, EvmInspector, InitialBlockchainAndState, ProviderConfig,
+};
+use edr_solidity::artifacts::{CompilerOutput, CompilerOutputContract};
+use tokio::runtime;
+
+use self::fuzz::{parse_parameters, FuzzInputGenerator};
+
+/// The default sender of Forge, used to deploy and call test contracts.
+pub const DEFAULT_TEST_SENDER: Address = Address::new([
+    0x18, 0x04, 0xc8, 0xab, 0x1f, 0x12, 0xe6, 0xbb, 0xf3, 0x89, 0x4d, 0x40, 0x83, 0xf3, 0x3e, 0x07,
+    0x30, 0x9d, 0x1f, 0x38,
+]);
+
+/// The balance of deployed test contracts.
+const TEST_CONTRACT_BALANCE: U256 = U256::from_limbs([u64::MAX, u32::MAX as u64, 0, 0]);
+
+/// Configuration for running Solidity tests.
+#[derive(Clone, Debug)]
+pub struct SolidityTestConfig {
+    /// The chain that tests are run against, which can be a fork.
+    pub provider_config: ProviderConfig,
+    /// The account that deploys test contracts and calls test functions.
+    pub sender: Address,
+    /// The gas limit of each test call
+    pub gas_limit: u64,
+    /// Configuration of fuzzed tests
+    pub fuzz: FuzzConfig,
+}
+
+impl SolidityTestConfig {
+    /// Constructs a configuration with the default sender and fuzz settings.
+    pub fn new(provider_config: ProviderConfig) -> Self {
+        Self {
+            gas_limit: provider_config.block_gas_limit,
+            provider_config,
+            sender: DEFAULT_TEST_SENDER,
+            fuzz: FuzzConfig::default(),
+        }
+    }
+}
+
+/// Configuration of fuzzed tests, i.e. test functions with parameters.
+#[derive(Clone, Debug)]
+pub struct FuzzConfig {
+    /// The number of runs per fuzzed test
+    pub runs: u64,
+    /// The seed from which inputs are generated. Test inputs only depend on
+    /// the seed, contract name, and test signature.
+    pub seed: String,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        Self {
+            runs: 256,
+            seed: String::from("0x0"),
+        }
+    }
+}
+
+/// An error that occurred while running Solidity tests. Test failures are
+/// reported as part of [`TestResult`]s instead.
+#[derive(Debug, thiserror::Error)]
+pub enum SolidityTestError {
+    /// Blockchain error
+    #[error(transparent)]
+    Blockchain(#[from] BlockchainError),
+    /// Error while creating the blockchain and state
+    #[error(transparent)]
+    Creation(#[from] CreationError),
+    /// State error
+    #[error(transparent)]
+    State(#[from] StateError),
+    /// Error while running a transaction
+    #[error(transparent)]
+    Transaction(#[from] TransactionError<BlockchainError, StateError>),
+}
+
+/// The results of all tests in a test contract.
+#[derive(Clone, Debug)]
+pub struct SuiteResult {
+    /// The name of the source file that contains the contract
+    pub source_name: String,
+    /// The name of the test contract
+    pub contract_name: String,
+    /// The results of the contract's test functions, sorted by signature.
+    pub test_results: Vec<TestResult>,
+}
+
+/// The status of a test.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TestStatus {
+    /// The test passed
+    Success,
+    /// The test failed
+    Failure,
+}
+
+/// The result of a single test function.
+#[derive(Clone, Debug)]
+pub struct TestResult {
+    /// The signature of the test function, e.g. `testTransfer()`
+    pub signature: String,
+    /// Whether the test passed
+    pub status: TestStatus,
+    /// The decoded revert reason of a failed test, if any.
+    pub reason: Option<String>,
+    /// Whether the test was fuzzed, including its gas usage.
+    pub kind: TestKind,
+    /// The logs emitted by the last test call
+    pub logs: Vec<Log>,
+    /// The encoded `console.log` calls of the last test call
+    pub console_log_inputs: Vec<Bytes>,
+}
+
+/// The kind of a test.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TestKind {
+    /// A test without parameters
+    Standard {
+        /// The gas used by the test call
+        gas_used: u64,
+    },
+    /// A test with parameters, which is called with generated inputs.
+    Fuzz {
+        /// The number of runs that were executed
+        runs: u64,
+        /// The mean gas used over all runs
+        mean_gas: u64,
+        /// The median gas used over all runs
+        median_gas: u64,
+        /// The calldata of the run that failed, if any.
+        counterexample: Option<Bytes>,
+    },
+}
+
+/// Runs all tests of the test contracts in the compiler output.
+///
+/// A test contract is a contract with functions prefixed by `test`. Each
+/// contract is deployed from the configured sender and its `setUp()` function
+/// is called, if present. Afterwards, each test function is run in parallel
+/// against a clone of the resulting state. Test functions prefixed with
+/// `testFail` are expected to revert. Test functions with parameters are
+/// fuzzed.
+///
+/// Cheatcodes are always enabled.
+pub fn run_solidity_tests(
+    runtime: runtime::Handle,
+    config: &SolidityTestConfig,
+    compiler_output: &CompilerOutput,
+) -> Result<Vec<SuiteResult>, SolidityTestError> {
+    let InitialBlockchainAndState {
+        blockchain,
+        mut state,
+    } = create_initial_blockchain_and_state(runtime, &config.provider_config)?;
+
+    state.modify_account(
+        config.sender,
+        AccountModifierFn::new(Box::new(|balance, _, _| *balance = U256::MAX)),
+        &|| {
+            Ok(AccountInfo {
+                balance: U256::MAX,
+                nonce: 0,
+                code: None,
+                code_hash: KECCAK_EMPTY,
+            })
+        },
+    )?;
+
+    let executor = TestExecutor::new(&*blockchain, config)?;
+    let test_contracts = find_test_contracts(compiler_output);
+
+    let deployments = parallel_map(&test_contracts, |contract| {
+        executor.deploy(state.clone(), contract)
+    })
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()?;
+
+    let tests = test_contracts
+        .iter()
+        .zip(deployments.iter())
+        .flat_map(|(contract, deployment)| {
+            contract
+                .test_functions
+                .iter()
+                .map(move |function| (contract, deployment, function))
+        })
+        .collect::<Vec<_>>();
+
+    let mut test_results = parallel_map(&tests, |(contract, deployment, function)| {
+        executor.run_test(contract, deployment, function)
+    })
+    .into_iter();
+
+    test_contracts
+        .iter()
+        .map(|contract| -> Result<SuiteResult, SolidityTestError> {
+            Ok(SuiteResult {
+                source_name: contract.source_name.to_string(),
+                contract_name: contract.contract_name.to_string(),
+                test_results: test_results
+                    .by_ref()
+                    .take(contract.test_functions.len())
+                    .collect::<Result<_, _>>()?,
+            })
+        })
+        .collect()
+}
+
+struct TestContract<'a> {
+    source_name: &'a str,
+    contract_name: &'a str,
+    contract: &'a CompilerOutputContract,
+    has_set_up: bool,
+    has_failed: bool,
+    test_functions: Vec<TestFunction<'a>>,
+}
+
+struct TestFunction<'a> {
+    signature: &'a str,
+    selector: [u8; 4],
+}
+
+impl<'a> TestFunction<'a> {
+    fn new(signature: &'a str) -> Self {
+        let mut selector = [0u8; 4];
+        selector.copy_from_slice(&keccak256(signature.as_bytes())[..4]);
+
+        Self {
+            signature,
+            selector,
+        }
+    }
+}
+
+/// Finds all deployable contracts with test functions, sorted by source and
+/// contract name.
+fn find_test_contracts(compiler_output: &CompilerOutput) -> Vec<TestContract<'_>> {
+    let mut test_contracts = compiler_output
+        .contracts
+        .iter()
+        .flat_map(|(source_name, contracts)| {
+            contracts
+                .iter()
+                .map(move |(contract_name, contract)| (source_name, contract_name, contract))
+        })
+        .filter(|(_, _, contract)| !contract.evm.bytecode.object.is_empty())
+        .filter_map(|(source_name, contract_name, contract)| {
+            let method_identifiers = &contract.evm.method_identifiers;
+
+            let mut test_functions = method_identifiers
+                .keys()
+                .filter(|signature| signature.starts_with("test"))
+                .map(|signature| TestFunction::new(signature.as_str()))
+                .collect::<Vec<_>>();
+
+            if test_functions.is_empty() {
+                return None;
+            }
+
+            test_functions.sort_unstable_by_key(|function| function.signature);
+
+            Some(TestContract {
+                source_name,
+                contract_name,
+                contract,
+                has_set_up: method_identifiers.contains_key("setUp()"),
+                has_failed: method_identifiers.contains_key("failed()"),
+                test_functions,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    test_contracts.sort_unstable_by_key(|contract| (contract.source_name, contract.contract_name));
+    test_contracts
+}
+
+/// A deployed test contract, after running its `setUp()` function.
+struct Deployment {
+    address: Address,
+    state: Box<dyn SyncState<StateError>>,
+}
+
+struct TransactionOutcome {
+    result: ExecutionResult,
+    console_log_inputs: Vec<Bytes>,
+}
+
+struct TestCaseOutcome {
+    success: bool,
+    reason: Option<String>,
+    gas_used: u64,
+    logs: Vec<Log>,
+    console_log_inputs: Vec<Bytes>,
+}
+
+struct TestExecutor<'a> {
+    blockchain: &'a dyn SyncBlockchain<BlockchainError, StateError>,
+    config: &'a SolidityTestConfig,
+    cfg: CfgEnv,
+    block: BlockEnv,
+}
+
+impl<'a> TestExecutor<'a> {
+    fn new(
+        blockchain: &'a dyn SyncBlockchain<BlockchainError, StateError>,
+        config: &'a SolidityTestConfig,
+    ) -> Result<Self, SolidityTestError> {
+        let spec_id = blockchain.spec_id();
+
+        let mut cfg = CfgEnv::default();
+        cfg.chain_id = blockchain.chain_id();
+        cfg.spec_id = spec_id;
+        cfg.limit_contract_code_size = if config.provider_config.allow_unlimited_contract_size {
+            Some(usize::MAX)
+        } else {
+            None
+        };
+        cfg.disable_base_fee = true;
+        cfg.disable_block_gas_limit = true;
+        cfg.disable_eip3607 = true;
+
+        let last_block = blockchain.last_block()?;
+        let block = block_env_from_header(last_block.header(), spec_id);
+
+        Ok(Self {
+            blockchain,
+            config,
+            cfg,
+            block,
+        })
+    }
+
+    fn transact(
+        &self,
+        state: &mut dyn SyncState<StateError>,
+        transact_to: TransactTo,
+        data: Bytes,
+    ) -> Result<TransactionOutcome, SolidityTestError> {
+        let transaction = TxEnv {
+            caller: self.config.sender,
+            gas_limit: self.config.gas_limit,
+            gas_price: U256::ZERO,
+            transact_to,
+            data,
+            ..TxEnv::default()
+        };
+
+        let mut inspector = EvmInspector::new(true);
+        let result = edr_evm::run(
+            self.blockchain,
+            state,
+            self.cfg.clone(),
+            transaction,
+            self.block.clone(),
+            Some(&mut inspector),
+        )?;
+
+        Ok(TransactionOutcome {
+            result,
+            console_log_inputs: inspector.into_console_log_encoded_messages(),
+        })
+    }
+
+    /// Deploys the test contract and runs its `setUp()` function. Returns the
+    /// failure reason if either reverts.
+    fn deploy(
+        &self,
+        mut state: Box<dyn SyncState<StateError>>,
+        contract: &TestContract<'_>,
+    ) -> Result<Result<Deployment, String>, SolidityTestError> {
+        let bytecode = &contract.contract.evm.bytecode;
+        if !bytecode.link_references.is_empty() {
+            let libraries = bytecode
+                .link_references
+                .values()
+                .flat_map(|libraries| libraries.keys())
+                .cloned()
+                .collect::<Vec<_>>();
+
+            return Ok(Err(format!(
+                "Deployment failed: unlinked libraries {}",
+                libraries.join(", ")
+            )));
+        }
+
+        let code = match hex::decode(&bytecode.object) {
+            Ok(code) => Bytes::from(code),
+            Err(error) => {
+                return Ok(Err(format!(
+                    "Deployment failed: invalid bytecode ({error})"
+                )))
+            }
+        };
+
+        let outcome = self.transact(&mut *state, TransactTo::Create(CreateScheme::Create), code)?;
+        let address = match outcome.result {
+            ExecutionResult::Success {
+                output: Output::Create(_, Some(address)),
+                ..
+            } => address,
+            result => {
+                return Ok(Err(format!(
+                    "Deployment failed: {}",
+                    failure_reason(&result).unwrap_or_default()
+                )))
+            }
+        };
+
+        state.modify_account(
+            address,
+            AccountModifierFn::new(Box::new(|balance, _, _| *balance = TEST_CONTRACT_BALANCE)),
+            &|| unreachable!("Deployed contract must exist"),
+        )?;
+
+        if contract.has_set_up {
+            let outcome = self.transact(
+                &mut *state,
+                TransactTo::Call(address),
+                TestFunction::new("setUp()").selector.to_vec().into(),
+            )?;
+
+            if let Some(reason) = failure_reason(&outcome.result) {
+                return Ok(Err(format!("setUp() failed: {reason}")));
+            }
+        }
+
+        Ok(Ok(Deployment { address, state }))
+    }
+
+    fn run_test(
+        &self,
+        contract: &TestContract<'_>,
+        deployment: &Result<Deployment, String>,
+        function: &TestFunction<'_>,
+    ) -> Result<TestResult, SolidityTestError> {
+        let failure = |reason: String| TestResult {
+            signature: function.signature.to_string(),
+            status: TestStatus::Failure,
+            reason: Some(reason),
+            kind: TestKind::Standard { gas_used: 0 },
+            logs: Vec::new(),
+            console_log_inputs: Vec::new(),
+        };
+
+        let deployment = match deployment {
+            Ok(deployment) => deployment,
+            Err(reason) => return Ok(failure(reason.clone())),
+        };
+
+        let parameters = match parse_parameters(function.signature) {
+            Ok(parameters) => parameters,
+            Err(reason) => return Ok(failure(reason)),
+        };
+
+        let expect_failure = function.signature.starts_with("testFail");
+
+        if parameters.is_empty() {
+            let outcome = self.run_test_case(
+                contract,
+                deployment,
+                function.selector.to_vec().into(),
+                expect_failure,
+            )?;
+
+            return Ok(TestResult {
+                signature: function.signature.to_string(),
+                status: status(outcome.success),
+                reason: outcome.reason,
+                kind: TestKind::Standard {
+                    gas_used: outcome.gas_used,
+                },
+                logs: outcome.logs,
+                console_log_inputs: outcome.console_log_inputs,
+            });
+        }
+
+        let mut generator = FuzzInputGenerator::new(
+            &format!(
+                "{}:{}:{}",
+                self.config.fuzz.seed, contract.contract_name, function.signature
+            ),
+            function.selector,
+            parameters,
+        );
+
+        let mut gas_used = Vec::new();
+        let mut last_outcome = None;
+        let mut counterexample = None;
+        for _ in 0..self.config.fuzz.runs {
+            let calldata = generator.next_calldata();
+            let outcome =
+                self.run_test_case(contract, deployment, calldata.clone(), expect_failure)?;
+
+            gas_used.push(outcome.gas_used);
+
+            let success = outcome.success;
+            last_outcome = Some(outcome);
+
+            if !success {
+                counterexample = Some(calldata);
+                break;
+            }
+        }
+
+        let runs = gas_used.len() as u64;
+        let mean_gas = gas_used.iter().sum::<u64>().checked_div(runs).unwrap_or(0);
+        gas_used.sort_unstable();
+        let median_gas = gas_used.get(gas_used.len() / 2).copied().unwrap_or(0);
+
+        let (reason, logs, console_log_inputs) = last_outcome.map_or_else(
+            || (None, Vec::new(), Vec::new()),
+            |outcome| (outcome.reason, outcome.logs, outcome.console_log_inputs),
+        );
+
+        Ok(TestResult {
+            signature: function.signature.to_string(),
+            status: status(counterexample.is_none()),
+            reason,
+            kind: TestKind::Fuzz {
+                runs,
+                mean_gas,
+                median_gas,
+                counterexample,
+            },
+            logs,
+            console_log_inputs,
+        })
+    }
+
+    fn run_test_case(
+        &self,
+        contract: &TestContract<'_>,
+        deployment: &Deployment,
+        calldata: Bytes,
+        expect_failure: bool,
+    ) -> Result<TestCaseOutcome, SolidityTestError> {
+        let mut state = deployment.state.clone();
+        let TransactionOutcome {
+            result,
+            console_log_inputs,
+        } = self.transact(&mut *state, TransactTo::Call(deployment.address), calldata)?;
+
+        let gas_used = result.gas_used();
+        let revert_reason = failure_reason(&result);
+        let logs = match result {
+            ExecutionResult::Success { logs, .. } => logs,
+            ExecutionResult::Revert { .. } | ExecutionResult::Halt { .. } => Vec::new(),
+        };
+
+        // DSTest-style assertions don't revert, but instead mark the test as failed.
+        let failed = revert_reason.is_some()
+            || (contract.has_failed && self.has_failed(&mut *state, deployment.address)?);
+
+        let (success, reason) = if expect_failure {
+            (
+                failed,
+                (!failed).then(|| String::from("Expected the test to fail")),
+            )
+        } else {
+            (!failed, revert_reason)
+        };
+
+        Ok(TestCaseOutcome {
+            success,
+            reason,
+            gas_used,
+            logs,
+            console_log_inputs,
+        })
+    }
+
+    fn has_failed(
+        &self,
+        state: &mut dyn SyncState<StateError>,
+        address: Address,
+    ) -> Result<bool, SolidityTestError> {
+        let outcome = self.transact(
+            state,
+            TransactTo::Call(address),
+            TestFunction::new("failed()").selector.to_vec().into(),
+        )?;
+
+        Ok(match outcome.result {
+            ExecutionResult::Success {
+                output: Output::Call(output),
+                ..
+            } => output.iter().any(|byte| *byte != 0),
+            _ => false,
+        })
+    }
+}
+
+fn status(success: bool) -> TestStatus {
+    if success {
+        TestStatus::Success
+    } else {
+        TestStatus::Failure
+    }
+}
+
+/// Returns the reason why the transaction failed, if it did.
+fn failure_reason(result: &ExecutionResult) -> Option<String> {
+    match result {
+        ExecutionResult::Success { .. } => None,
+        ExecutionResult::Revert { output, .. } => Some(decode_revert_reason(output)),
+        ExecutionResult::Halt { reason, .. } => Some(format!("EVM error: {reason:?}")),
+    }
+}
+
+fn decode_revert_reason(output: &Bytes) -> String {
+    if output.is_empty() {
+        return String::from("Reverted without a reason");
+    }
+
+    match GenericContractError::abi_decode(output.as_ref(), /* validate */ false) {
+        Ok(ContractError::Revert(revert)) => revert.reason().to_string(),
+        Ok(ContractError::Panic(panic)) => {
+            let reason =
+                u64::try_from(panic.code).map_or("Unknown panic code", panic_code_to_error_reason);
+
+            format!("Panic: {reason} (0x{:x})", panic.code)
+        }
+        Ok(ContractError::CustomError(_)) | Err(_) => {
+            format!("Custom error: 0x{}", hex::encode(output))
+        }
+    }
+}
+
+/// Applies the function to all items, using a thread per available core.
+/// Results are returned in the order of the items.
+fn parallel_map<ItemT, ResultT, F>(items: &[ItemT], f: F) -> Vec<ResultT>
+where
+    ItemT: Sync,
+    ResultT: Send,
+    F: Fn(&ItemT) -> ResultT + Sync,
+{
+    let num_workers = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(items.len())
+        .max(1);
+
+    let next_index = AtomicUsize::new(0);
+    let mut results = thread::scope(|scope| {
+        let workers = (0..num_workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let index = next_index.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            break;
+                        };
+
+                        results.push((index, f(item)));
+                    }
+                    results
+                })
+            })
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("Test worker panicked"))
+            .collect::<Vec<_>>()
+    });
+
+    results.sort_unstable_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use edr_provider::test_utils::create_test_config;
+
+    use super::*;
+
+    /// Creation code that deploys the provided runtime code.
+    fn creation_code(runtime_code: &str) -> String {
+        let length = runtime_code.len() / 2;
+        format!("60{length:02x}600c60003960{length:02x}6000f3{runtime_code}")
+    }
+
+    fn compiler_output(contracts: &[(&str, &str, &[&str])]) -> anyhow::Result<CompilerOutput> {
+        let contracts = contracts
+            .iter()
+            .map(|(name, runtime_code, functions)| {
+                let method_identifiers = functions
+                    .iter()
+                    .map(|signature| {
+                        (
+                            signature.to_string(),
+                            serde_json::Value::from(hex::encode(
+                                TestFunction::new(signature).selector,
+                            )),
+                        )
+                    })
+                    .collect::<serde_json::Map<_, _>>();
+
+                let bytecode = |object: String| {
+                    serde_json::json!({
+                        "object": object,
+                        "opcodes": "",
+                        "sourceMap": "",
+                        "linkReferences": {},
+                    })
+                };
+
+                (
+                    name.to_string(),
+                    serde_json::json!({
+                        "abi": [],
+                        "evm": {
+                            "bytecode": bytecode(creation_code(runtime_code)),
+                            "deployedBytecode": bytecode(runtime_code.to_string()),
+                            "methodIdentifiers": method_identifiers,
+                        },
+                    }),
+                )
+            })
+            .collect::<serde_json::Map<_, _>>();
+
+        Ok(serde_json::from_value(serde_json::json!({
+            "sources": {},
+            "contracts": {
+                "Test.sol": contracts,
+            },
+        }))?)
+    }
+
+    #[test]
+    fn run_solidity_tests_success() -> anyhow::Result<()> {
+        const STOP: &str = "00";
+        // PUSH1 0 PUSH1 0 REVERT
+        const REVERT: &str = "60006000fd";
+
+        let compiler_output = compiler_output(&[
+            (
+                "PassingTest",
+                STOP,
+                &["testPass()", "testFuzz_pass(address,uint256)", "helper()"],
+            ),
+            (
+                "RevertingTest",
+                REVERT,
+                &["testRevert()", "testFailRevert()", "testFuzz_revert(int8)"],
+            ),
+            ("NotATest", STOP, &["helper()"]),
+        ])?;
+
+        let runtime = runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .thread_name("solidity-test-runner-test")
+            .build()?;
+
+        let mut config = SolidityTestConfig::new(create_test_config());
+        config.fuzz.runs = 10;
+
+        let results = run_solidity_tests(runtime.handle().clone(), &config, &compiler_output)?;
+        assert_eq!(results.len(), 2);
+
+        let passing = &results[0];
+        assert_eq!(passing.contract_name, "PassingTest");
+        assert_eq!(passing.test_results.len(), 2);
+        assert!(passing
+            .test_results
+            .iter()
+            .all(|result| result.status == TestStatus::Success));
+        assert!(matches!(
+            passing.test_results[0].kind,
+            TestKind::Fuzz {
+                runs: 10,
+                counterexample: None,
+                ..
+            }
+        ));
+
+        let reverting = &results[1];
+        assert_eq!(reverting.contract_name, "RevertingTest");
+
+        let statuses = reverting
+            .test_results
+            .iter()
+            .map(|result| (result.signature.as_str(), result.status))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            [
+                ("testFailRevert()", TestStatus::Success),
+                ("testFuzz_revert(int8)", TestStatus::Failure),
+                ("testRevert()", TestStatus::Failure),
+            ]
+        );
+
+        assert_eq!(
+            reverting.test_results[2].reason.as_deref(),
+            Some("Reverted without a reason")
+        );
+        assert!(matches!(
+            &reverting.test_results[1].kind,
+            TestKind::Fuzz {
+                runs: 1,
+                counterexample: Some(calldata),
+                ..
+            } if calldata.len() == 4 + 32
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn decode_revert_reason_panic() {
+        let mut output = hex::decode("4e487b71").unwrap();
+        output.extend_from_slice(&U256::from(0x11).to_be_bytes::<32>());
+
+        assert_eq!(
+            decode_revert_reason(&output.into()),
+            "Panic: Arithmetic operation underflowed or overflowed outside of an unchecked block (0x11)"
+        );
+    }
+}
@@ -44,7 +44,6 @@ import {
 import { isErrorResponse } from "../../core/providers/http";
 import { getHardforkName } from "../../util/hardforks";
 import { createModelsAndDecodeBytecodes } from "../stack-traces/compiler-to-model";
-import { ConsoleLogger } from "../stack-traces/consoleLogger";
 import { ContractsIdentifier } from "../stack-traces/contracts-identifier";
 import {
   VmTraceDecoder,
@@ -66,7 +65,7 @@ import {
   TracingConfig,
 } from "./node-types";
 import {
-  edrRpcDebugTraceToHardhat,
+  edrDebugTraceOutputToHardhat,
   edrTracingMessageResultToMinimalEVMResult,
   edrTracingMessageToMinimalMessage,
   edrTracingStepToMinimalInterpreterStep,
@@ -273,10 +272,6 @@ export class EdrProviderWrapper
       },
       {
         enable: loggerConfig.enabled,
-        decodeConsoleLogInputsCallback: (inputs: Buffer[]) => {
-          const consoleLogger = new ConsoleLogger();
-          return consoleLogger.getDecodedLogs(inputs);
-        },
         getContractAndFunctionNameCallback: (
           code: Buffer,
           calldata?: Buffer
@@ -432,7 +427,16 @@ export class EdrProviderWrapper
       args.method === "debug_traceTransaction" ||
       args.method === "debug_traceCall"
     ) {
-      return edrRpcDebugTraceToHardhat(response.result);
+      return edrDebugTraceOutputToHardhat(response.result);
+    } else if (
+      args.method === "debug_traceBlock" ||
+      args.method === "debug_traceBlockByHash" ||
+      args.method === "debug_traceBlockByNumber"
+    ) {
+      return response.result.map((trace: any) => ({
+        txHash: trace.txHash,
+        result: edrDebugTraceOutputToHardhat(trace.result),
+      }));
     } else {
       return response.result;
     }
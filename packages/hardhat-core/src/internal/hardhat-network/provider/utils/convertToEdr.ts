@@ -192,6 +192,15 @@ export function edrRpcDebugTraceToHardhat(
   };
 }
 
+export function edrDebugTraceOutputToHardhat(output: any): any {
+  // Only the output of the default tracer differs from Hardhat's format
+  if (output?.structLogs === undefined) {
+    return output;
+  }
+
+  return edrRpcDebugTraceToHardhat(output);
+}
+
 export function edrTracingStepToMinimalInterpreterStep(
   step: TracingStep
 ): MinimalInterpreterStep {
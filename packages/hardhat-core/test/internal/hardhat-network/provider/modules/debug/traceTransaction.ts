@@ -81,7 +81,7 @@ describe("Debug module", function () {
                 tracer: "unsupportedTracer",
               },
             ],
-            "Hardhat currently only supports the default tracer, so no tracer parameter should be passed."
+            "Hardhat currently only supports the default tracer and the callTracer, prestateTracer, 4byteTracer and noopTracer built-in tracers."
           );
         });
 